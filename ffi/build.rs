@@ -0,0 +1,13 @@
+//! Regenerate `include/mathatura.h` from the exported functions.
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let config = cbindgen::Config::from_file("cbindgen.toml").expect("cbindgen.toml");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src("src/lib.rs")
+        .generate()
+        .expect("generate C header")
+        .write_to_file("include/mathatura.h");
+}
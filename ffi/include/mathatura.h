@@ -0,0 +1,122 @@
+/* mathatura — C API for the mathematics-of-nature models. */
+
+#ifndef MATHATURA_H
+#define MATHATURA_H
+
+/* Generated by cbindgen from ffi/src/lib.rs; do not edit. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define MATHATURA_TURING_SPOTS 0
+
+#define MATHATURA_TURING_STRIPES 1
+
+#define MATHATURA_TURING_CORAL 2
+
+#define MATHATURA_TURING_MITOSIS 3
+
+#define MATHATURA_TURING_WORMS 4
+
+// Opaque handle to Vogel-model parameters.
+typedef struct MathaturaPhyllotaxis MathaturaPhyllotaxis;
+
+// Opaque handle to a Gray–Scott simulation.
+typedef struct MathaturaTuring MathaturaTuring;
+
+// A heap array of doubles owned by the library.
+typedef struct MathaturaBuffer {
+  double *data;
+  size_t len;
+} MathaturaBuffer;
+
+// Message of the last failed call on this thread, or NULL. Valid until the
+// next failing call on the same thread.
+const char *mathatura_last_error(void);
+
+// Library version as a static string.
+const char *mathatura_version(void);
+
+// Release a buffer returned by the library.
+//
+// # Safety
+// `buffer` must come from this library and not have been freed already.
+void mathatura_buffer_free(struct MathaturaBuffer buffer);
+
+// Release a string returned by the library.
+//
+// # Safety
+// `s` must come from this library and not have been freed already.
+void mathatura_string_free(char *s);
+
+// New phyllotaxis parameters with the defaults (500 florets, golden angle).
+struct MathaturaPhyllotaxis *mathatura_phyllotaxis_new(void);
+
+// Set count, divergence angle in degrees, and scale. Returns false (and
+// leaves the parameters unchanged) if they are invalid.
+//
+// # Safety
+// `params` must be a live handle from [`mathatura_phyllotaxis_new`].
+bool mathatura_phyllotaxis_set(struct MathaturaPhyllotaxis *params,
+                               size_t count,
+                               double angle_deg,
+                               double scale);
+
+// Floret centers as interleaved x, y pairs.
+//
+// # Safety
+// `params` must be a live handle from [`mathatura_phyllotaxis_new`].
+struct MathaturaBuffer mathatura_phyllotaxis_points(const struct MathaturaPhyllotaxis *params);
+
+// The sunflower as an SVG document.
+//
+// # Safety
+// `params` must be a live handle from [`mathatura_phyllotaxis_new`].
+char *mathatura_phyllotaxis_svg(const struct MathaturaPhyllotaxis *params);
+
+// Release phyllotaxis parameters.
+//
+// # Safety
+// `params` must come from [`mathatura_phyllotaxis_new`] and not have been freed.
+void mathatura_phyllotaxis_free(struct MathaturaPhyllotaxis *params);
+
+// Barnsley fern points as interleaved x, y pairs (y up).
+struct MathaturaBuffer mathatura_fern_points(size_t count, uint64_t seed);
+
+// Lorenz trajectory from (1, 1, 1) as interleaved x, y, z triples.
+struct MathaturaBuffer mathatura_lorenz_points(size_t steps, double sigma, double rho, double beta);
+
+// New simulation with random seed blobs. `preset` is one of the
+// `MATHATURA_TURING_*` constants; returns NULL for a bad size or preset.
+struct MathaturaTuring *mathatura_turing_new(size_t width,
+                                             size_t height,
+                                             uint32_t preset,
+                                             uint64_t seed);
+
+// Advance the simulation by `steps` time steps.
+//
+// # Safety
+// `sim` must be a live handle from [`mathatura_turing_new`].
+void mathatura_turing_step(struct MathaturaTuring *sim, size_t steps);
+
+// Copy the B concentrations (row-major, width × height values) into `out`,
+// which holds `capacity` doubles. Returns the number of values written.
+//
+// # Safety
+// `sim` must be a live handle and `out` must point to `capacity` writable doubles.
+size_t mathatura_turing_read(const struct MathaturaTuring *sim, double *out, size_t capacity);
+
+// The current pattern as an SVG document.
+//
+// # Safety
+// `sim` must be a live handle from [`mathatura_turing_new`].
+char *mathatura_turing_svg(const struct MathaturaTuring *sim);
+
+// Release a simulation.
+//
+// # Safety
+// `sim` must come from [`mathatura_turing_new`] and not have been freed.
+void mathatura_turing_free(struct MathaturaTuring *sim);
+
+#endif  /* MATHATURA_H */
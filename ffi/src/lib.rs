@@ -0,0 +1,383 @@
+//! # mathatura-ffi
+//!
+//! A C API over the mathatura models for C, C++, game engines, and any
+//! language with a C FFI. The build regenerates the matching header,
+//! `include/mathatura.h`, with cbindgen.
+//!
+//! Conventions:
+//! - Parameters and simulations are opaque handles created by `*_new` and
+//!   released with the matching `*_free`.
+//! - Point data comes back as a [`MathaturaBuffer`] of doubles, released with
+//!   [`mathatura_buffer_free`].
+//! - SVG documents come back as NUL-terminated strings, released with
+//!   [`mathatura_string_free`].
+//! - Failing calls return NULL (or an empty buffer); the reason is available
+//!   from [`mathatura_last_error`] on the same thread.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use mathatura::categories::{chaos, fractals, phyllotaxis, turing};
+use mathatura::error::check_count;
+use mathatura::MathaturaError;
+
+/// Largest grid side accepted for simulations.
+const MAX_GRID_SIZE: usize = 4096;
+
+/// Most points a single call will produce.
+const MAX_POINTS: usize = 50_000_000;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(e: MathaturaError) {
+    let message = CString::new(e.to_string()).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Message of the last failed call on this thread, or NULL. Valid until the
+/// next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn mathatura_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// Library version as a static string.
+#[no_mangle]
+pub extern "C" fn mathatura_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// A heap array of doubles owned by the library.
+#[repr(C)]
+#[derive(Debug)]
+pub struct MathaturaBuffer {
+    pub data: *mut f64,
+    pub len: usize,
+}
+
+impl MathaturaBuffer {
+    fn from_vec(values: Vec<f64>) -> Self {
+        let len = values.len();
+        let data = Box::into_raw(values.into_boxed_slice()).cast();
+        MathaturaBuffer { data, len }
+    }
+
+    fn empty() -> Self {
+        MathaturaBuffer { data: ptr::null_mut(), len: 0 }
+    }
+}
+
+/// Release a buffer returned by the library.
+///
+/// # Safety
+/// `buffer` must come from this library and not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_buffer_free(buffer: MathaturaBuffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)));
+    }
+}
+
+fn into_c_string(svg: String) -> *mut c_char {
+    // SVG output never contains NUL bytes
+    CString::new(svg).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Release a string returned by the library.
+///
+/// # Safety
+/// `s` must come from this library and not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+// ---------------------------------------------------------------- phyllotaxis
+
+/// Opaque handle to Vogel-model parameters.
+pub struct MathaturaPhyllotaxis(phyllotaxis::Params);
+
+/// New phyllotaxis parameters with the defaults (500 florets, golden angle).
+#[no_mangle]
+pub extern "C" fn mathatura_phyllotaxis_new() -> *mut MathaturaPhyllotaxis {
+    Box::into_raw(Box::new(MathaturaPhyllotaxis(phyllotaxis::Params::default())))
+}
+
+/// Set count, divergence angle in degrees, and scale. Returns false (and
+/// leaves the parameters unchanged) if they are invalid.
+///
+/// # Safety
+/// `params` must be a live handle from [`mathatura_phyllotaxis_new`].
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_phyllotaxis_set(
+    params: *mut MathaturaPhyllotaxis,
+    count: usize,
+    angle_deg: f64,
+    scale: f64,
+) -> bool {
+    let Some(handle) = params.as_mut() else { return false };
+    let candidate = phyllotaxis::Params { count, divergence_angle: angle_deg, scale };
+    match candidate.validate() {
+        Ok(()) => {
+            handle.0 = candidate;
+            true
+        }
+        Err(e) => {
+            set_error(e);
+            false
+        }
+    }
+}
+
+/// Floret centers as interleaved x, y pairs.
+///
+/// # Safety
+/// `params` must be a live handle from [`mathatura_phyllotaxis_new`].
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_phyllotaxis_points(params: *const MathaturaPhyllotaxis) -> MathaturaBuffer {
+    let Some(handle) = params.as_ref() else { return MathaturaBuffer::empty() };
+    MathaturaBuffer::from_vec(phyllotaxis::vogel_spiral_as::<f64>(&handle.0).into_iter().flatten().collect())
+}
+
+/// The sunflower as an SVG document.
+///
+/// # Safety
+/// `params` must be a live handle from [`mathatura_phyllotaxis_new`].
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_phyllotaxis_svg(params: *const MathaturaPhyllotaxis) -> *mut c_char {
+    let Some(handle) = params.as_ref() else { return ptr::null_mut() };
+    into_c_string(phyllotaxis::to_svg(&phyllotaxis::vogel_spiral(&handle.0), phyllotaxis::Pattern::Sunflower))
+}
+
+/// Release phyllotaxis parameters.
+///
+/// # Safety
+/// `params` must come from [`mathatura_phyllotaxis_new`] and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_phyllotaxis_free(params: *mut MathaturaPhyllotaxis) {
+    if !params.is_null() {
+        drop(Box::from_raw(params));
+    }
+}
+
+// ------------------------------------------------------------ point clouds
+
+/// Barnsley fern points as interleaved x, y pairs (y up).
+#[no_mangle]
+pub extern "C" fn mathatura_fern_points(count: usize, seed: u64) -> MathaturaBuffer {
+    if let Err(e) = check_count("count", count, MAX_POINTS) {
+        set_error(e);
+        return MathaturaBuffer::empty();
+    }
+    MathaturaBuffer::from_vec(fractals::barnsley_fern_iter_as::<f64>(seed).take(count).flatten().collect())
+}
+
+/// Lorenz trajectory from (1, 1, 1) as interleaved x, y, z triples.
+#[no_mangle]
+pub extern "C" fn mathatura_lorenz_points(steps: usize, sigma: f64, rho: f64, beta: f64) -> MathaturaBuffer {
+    let params = chaos::LorenzParams { sigma, rho, beta, ..Default::default() };
+    if let Err(e) = params.validate().and_then(|_| check_count("steps", steps, MAX_POINTS)) {
+        set_error(e);
+        return MathaturaBuffer::empty();
+    }
+    let start = chaos::Point3D { x: 1.0, y: 1.0, z: 1.0 };
+    MathaturaBuffer::from_vec(chaos::lorenz_iter_as::<f64>(&params, start).take(steps).flatten().collect())
+}
+
+// --------------------------------------------------------- Turing patterns
+
+/// Opaque handle to a Gray–Scott simulation.
+pub struct MathaturaTuring {
+    grid: turing::Grid,
+    params: turing::GrayScottParams,
+}
+
+pub const MATHATURA_TURING_SPOTS: u32 = 0;
+pub const MATHATURA_TURING_STRIPES: u32 = 1;
+pub const MATHATURA_TURING_CORAL: u32 = 2;
+pub const MATHATURA_TURING_MITOSIS: u32 = 3;
+pub const MATHATURA_TURING_WORMS: u32 = 4;
+
+/// Gray–Scott presets, indexed by the `MATHATURA_TURING_*` constants.
+const TURING_PRESETS: [turing::Preset; 5] = [
+    turing::Preset::Spots,
+    turing::Preset::Stripes,
+    turing::Preset::Coral,
+    turing::Preset::Mitosis,
+    turing::Preset::Worms,
+];
+
+/// New simulation with random seed blobs. `preset` is one of the
+/// `MATHATURA_TURING_*` constants; returns NULL for a bad size or preset.
+#[no_mangle]
+pub extern "C" fn mathatura_turing_new(width: usize, height: usize, preset: u32, seed: u64) -> *mut MathaturaTuring {
+    let checked = check_count("width", width, MAX_GRID_SIZE)
+        .and_then(|_| check_count("height", height, MAX_GRID_SIZE))
+        .and_then(|_| {
+            TURING_PRESETS.get(preset as usize).copied().ok_or(MathaturaError::InvalidParameter {
+                name: "preset",
+                value: preset as f64,
+                expected: "0 to 4",
+            })
+        });
+    match checked {
+        Ok(p) => Box::into_raw(Box::new(MathaturaTuring {
+            grid: turing::Grid::new_random(width, height, seed),
+            params: p.params(),
+        })),
+        Err(e) => {
+            set_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Advance the simulation by `steps` time steps.
+///
+/// # Safety
+/// `sim` must be a live handle from [`mathatura_turing_new`].
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_turing_step(sim: *mut MathaturaTuring, steps: usize) {
+    if let Some(sim) = sim.as_mut() {
+        sim.grid.simulate(&sim.params, steps);
+    }
+}
+
+/// Copy the B concentrations (row-major, width × height values) into `out`,
+/// which holds `capacity` doubles. Returns the number of values written.
+///
+/// # Safety
+/// `sim` must be a live handle and `out` must point to `capacity` writable doubles.
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_turing_read(sim: *const MathaturaTuring, out: *mut f64, capacity: usize) -> usize {
+    let Some(sim) = sim.as_ref() else { return 0 };
+    if out.is_null() {
+        return 0;
+    }
+    let out = std::slice::from_raw_parts_mut(out, capacity);
+    for (dst, cell) in out.iter_mut().zip(&sim.grid.cells) {
+        *dst = cell.b;
+    }
+    capacity.min(sim.grid.cells.len())
+}
+
+/// The current pattern as an SVG document.
+///
+/// # Safety
+/// `sim` must be a live handle from [`mathatura_turing_new`].
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_turing_svg(sim: *const MathaturaTuring) -> *mut c_char {
+    sim.as_ref().map_or(ptr::null_mut(), |sim| into_c_string(turing::grid_to_svg(&sim.grid)))
+}
+
+/// Release a simulation.
+///
+/// # Safety
+/// `sim` must come from [`mathatura_turing_new`] and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn mathatura_turing_free(sim: *mut MathaturaTuring) {
+    if !sim.is_null() {
+        drop(Box::from_raw(sim));
+    }
+}
+
+/// Read the last error as a Rust string (for tests and Rust callers).
+pub fn last_error() -> Option<String> {
+    let p = mathatura_last_error();
+    // SAFETY: non-null pointers come from the thread-local CString
+    (!p.is_null()).then(|| unsafe { CStr::from_ptr(p) }.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phyllotaxis_roundtrip() {
+        unsafe {
+            let params = mathatura_phyllotaxis_new();
+            assert!(mathatura_phyllotaxis_set(params, 100, 137.5, 4.0));
+            let buffer = mathatura_phyllotaxis_points(params);
+            assert_eq!(buffer.len, 200);
+            let xy = std::slice::from_raw_parts(buffer.data, buffer.len);
+            assert_eq!((xy[0], xy[1]), (0.0, 0.0));
+            mathatura_buffer_free(buffer);
+
+            let svg = mathatura_phyllotaxis_svg(params);
+            assert!(CStr::from_ptr(svg).to_str().unwrap().starts_with("<svg"));
+            mathatura_string_free(svg);
+            mathatura_phyllotaxis_free(params);
+        }
+    }
+
+    #[test]
+    fn test_invalid_params_set_error() {
+        unsafe {
+            let params = mathatura_phyllotaxis_new();
+            assert!(!mathatura_phyllotaxis_set(params, 100, 137.5, -1.0));
+            assert!(last_error().unwrap().contains("scale"));
+            mathatura_phyllotaxis_free(params);
+        }
+        assert!(mathatura_turing_new(10, 10, 9, 1).is_null());
+        assert!(last_error().unwrap().contains("preset"));
+        let empty = mathatura_fern_points(0, 1);
+        assert!(empty.data.is_null());
+        assert!(last_error().unwrap().contains("count"));
+    }
+
+    #[test]
+    fn test_point_buffers() {
+        let fern = mathatura_fern_points(1000, 3);
+        assert_eq!(fern.len, 2000);
+        unsafe { mathatura_buffer_free(fern) };
+        let lorenz = mathatura_lorenz_points(500, 10.0, 28.0, 8.0 / 3.0);
+        assert_eq!(lorenz.len, 1500);
+        unsafe {
+            let xyz = std::slice::from_raw_parts(lorenz.data, lorenz.len);
+            assert_eq!(&xyz[..3], &[1.0, 1.0, 1.0]);
+            mathatura_buffer_free(lorenz);
+        }
+    }
+
+    #[test]
+    fn test_turing_handle() {
+        let sim = mathatura_turing_new(16, 12, 2, 42);
+        assert!(!sim.is_null());
+        let mut values = vec![0.0; 16 * 12 + 5];
+        unsafe {
+            mathatura_turing_step(sim, 3);
+            assert_eq!(mathatura_turing_read(sim, values.as_mut_ptr(), values.len()), 16 * 12);
+            let svg = mathatura_turing_svg(sim);
+            assert!(!svg.is_null());
+            mathatura_string_free(svg);
+            mathatura_turing_free(sim);
+        }
+        assert!(values[..16 * 12].iter().any(|&b| b > 0.0));
+    }
+
+    #[test]
+    fn test_version() {
+        let v = unsafe { CStr::from_ptr(mathatura_version()) };
+        assert_eq!(v.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_header_declares_every_export() {
+        let header = include_str!("../include/mathatura.h");
+        let source = include_str!("lib.rs");
+        let exports: Vec<&str> = source
+            .lines()
+            .filter_map(|l| l.split("extern \"C\" fn ").nth(1))
+            .filter_map(|rest| rest.split('(').next())
+            .collect();
+        assert!(exports.len() >= 14);
+        for name in exports {
+            assert!(header.contains(&format!("{name}(")), "{name} missing from mathatura.h");
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Pattern analysis — measuring the structure hidden in generated geometry.
+//!
+//! Symmetry is detected by transforming a point set (rotating by 2π/n or
+//! reflecting across an axis through the centroid) and checking how well the
+//! transformed points land on the original ones. The match tolerance adapts
+//! to the typical nearest-neighbor spacing, so the same scores work for sparse
+//! motifs and dense point clouds alike.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::categories::fractals::Point;
+
+/// Maximum number of points used as queries when scoring a transform.
+const MAX_SAMPLES: usize = 2000;
+
+/// Score above which a rotation or reflection counts as a symmetry.
+pub const SYMMETRY_THRESHOLD: f64 = 0.85;
+
+/// Summary of the symmetries found in a point set.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SymmetryReport {
+    /// Highest n for which the set is n-fold rotationally symmetric (1 = none)
+    pub rotational_order: usize,
+    /// Match score of the detected rotation (1.0 for order 1)
+    pub rotational_score: f64,
+    /// Mirror axis angles in radians, in [0, π), through the centroid
+    pub mirror_axes: Vec<f64>,
+    /// Best mirror match score (0.0 if no axis was found)
+    pub mirror_score: f64,
+    /// Combined symmetry score in [0, 1]
+    pub score: f64,
+}
+
+/// Spatial hash over a point set for approximate nearest-neighbor queries.
+struct PointIndex<'a> {
+    points: &'a [Point],
+    cell: f64,
+    buckets: Map<(i64, i64), Vec<usize>>,
+}
+
+impl<'a> PointIndex<'a> {
+    fn new(points: &'a [Point], cell: f64) -> Self {
+        let mut buckets: Map<(i64, i64), Vec<usize>> = Map::new();
+        for (i, p) in points.iter().enumerate() {
+            buckets.entry(Self::key(p, cell)).or_default().push(i);
+        }
+        PointIndex { points, cell, buckets }
+    }
+
+    fn key(p: &Point, cell: f64) -> (i64, i64) {
+        ((p.x / cell).floor() as i64, (p.y / cell).floor() as i64)
+    }
+
+    /// Distance to the nearest point within one cell, or infinity.
+    fn nearest(&self, p: &Point, skip: Option<usize>) -> f64 {
+        let (kx, ky) = Self::key(p, self.cell);
+        let mut best = f64::INFINITY;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let Some(bucket) = self.buckets.get(&(kx + dx, ky + dy)) else { continue };
+                for &i in bucket {
+                    if Some(i) == skip {
+                        continue;
+                    }
+                    let q = &self.points[i];
+                    let d = ((p.x - q.x).powi(2) + (p.y - q.y).powi(2)).sqrt();
+                    best = best.min(d);
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Points shifted so their centroid is at the origin.
+fn centered(points: &[Point]) -> Vec<Point> {
+    let n = points.len().max(1) as f64;
+    let cx = points.iter().map(|p| p.x).sum::<f64>() / n;
+    let cy = points.iter().map(|p| p.y).sum::<f64>() / n;
+    points.iter().map(|p| Point { x: p.x - cx, y: p.y - cy }).collect()
+}
+
+/// Evenly strided subset used as transform queries.
+fn samples(points: &[Point]) -> Vec<Point> {
+    let stride = points.len().div_ceil(MAX_SAMPLES).max(1);
+    points.iter().step_by(stride).copied().collect()
+}
+
+/// Match tolerance: half the median nearest-neighbor spacing.
+fn tolerance(points: &[Point]) -> f64 {
+    let rms = (points.iter().map(|p| p.x * p.x + p.y * p.y).sum::<f64>() / points.len() as f64).sqrt();
+    let floor = (rms * 1e-3).max(1e-9);
+    // A coarse index is enough to find neighbors in all but very sparse sets
+    let coarse = PointIndex::new(points, rms.max(floor) / 4.0);
+    let stride = points.len().div_ceil(MAX_SAMPLES).max(1);
+    let mut spacings: Vec<f64> = (0..points.len())
+        .step_by(stride)
+        .map(|i| coarse.nearest(&points[i], Some(i)))
+        .filter(|d| d.is_finite())
+        .collect();
+    if spacings.is_empty() {
+        return (rms / 4.0).max(floor);
+    }
+    spacings.sort_by(|a, b| a.total_cmp(b));
+    (spacings[spacings.len() / 2] / 2.0).max(floor)
+}
+
+/// Scores how well a transformed copy of a centered point set overlaps the original.
+struct Matcher {
+    points: Vec<Point>,
+    queries: Vec<Point>,
+    sigma: f64,
+}
+
+impl Matcher {
+    fn new(points: &[Point]) -> Self {
+        let points = centered(points);
+        let sigma = tolerance(&points);
+        let queries = samples(&points);
+        Matcher { points, queries, sigma }
+    }
+
+    /// Mean Gaussian match of the transformed queries against the set.
+    fn score(&self, index: &PointIndex, transform: impl Fn(&Point) -> Point) -> f64 {
+        if self.queries.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self
+            .queries
+            .iter()
+            .map(|p| {
+                let d = index.nearest(&transform(p), None);
+                (-(d * d) / (2.0 * self.sigma * self.sigma)).exp()
+            })
+            .sum();
+        total / self.queries.len() as f64
+    }
+
+    fn index(&self) -> PointIndex<'_> {
+        PointIndex::new(&self.points, self.sigma * 3.0)
+    }
+}
+
+fn rotate(p: &Point, angle: f64) -> Point {
+    let (s, c) = angle.sin_cos();
+    Point { x: p.x * c - p.y * s, y: p.x * s + p.y * c }
+}
+
+fn reflect(p: &Point, axis: f64) -> Point {
+    let (s, c) = (2.0 * axis).sin_cos();
+    Point { x: p.x * c + p.y * s, y: p.x * s - p.y * c }
+}
+
+/// Score n-fold rotational symmetry about the centroid for n = 2..=max_order.
+pub fn rotational_scores(points: &[Point], max_order: usize) -> Vec<(usize, f64)> {
+    if points.len() < 2 {
+        return vec![];
+    }
+    let matcher = Matcher::new(points);
+    let index = matcher.index();
+    (2..=max_order)
+        .map(|n| {
+            let angle = 2.0 * PI / n as f64;
+            (n, matcher.score(&index, |p| rotate(p, angle)))
+        })
+        .collect()
+}
+
+/// Estimate the rotational order: the largest n whose score passes the threshold.
+///
+/// Returns `(1, 1.0)` for sets with no rotational symmetry.
+pub fn rotational_order(points: &[Point], max_order: usize) -> (usize, f64) {
+    order_from_scores(&rotational_scores(points, max_order))
+}
+
+fn order_from_scores(scores: &[(usize, f64)]) -> (usize, f64) {
+    scores
+        .iter()
+        .copied()
+        .rfind(|&(_, s)| s >= SYMMETRY_THRESHOLD)
+        .unwrap_or((1, 1.0))
+}
+
+/// Score reflection across axes through the centroid at `resolution` evenly spaced angles in [0, π).
+pub fn mirror_scores(points: &[Point], resolution: usize) -> Vec<(f64, f64)> {
+    if points.len() < 2 || resolution == 0 {
+        return vec![];
+    }
+    let matcher = Matcher::new(points);
+    let index = matcher.index();
+    (0..resolution)
+        .map(|i| {
+            let axis = PI * i as f64 / resolution as f64;
+            (axis, matcher.score(&index, |p| reflect(p, axis)))
+        })
+        .collect()
+}
+
+/// Find mirror axes: local maxima of the reflection score above the threshold,
+/// refined between neighboring samples. Returns `(angle, score)` pairs.
+pub fn mirror_axes(points: &[Point], resolution: usize) -> Vec<(f64, f64)> {
+    let coarse = mirror_scores(points, resolution);
+    if coarse.is_empty() {
+        return vec![];
+    }
+    let matcher = Matcher::new(points);
+    let index = matcher.index();
+    let step = PI / resolution as f64;
+    let n = coarse.len();
+
+    let mut axes = Vec::new();
+    for i in 0..n {
+        let prev = coarse[(i + n - 1) % n].1;
+        let next = coarse[(i + 1) % n].1;
+        let (axis, score) = coarse[i];
+        if score < prev || score <= next {
+            continue;
+        }
+        // Ternary search for the exact peak within half a step on either side
+        let (mut lo, mut hi) = (axis - step / 2.0, axis + step / 2.0);
+        for _ in 0..30 {
+            let m1 = lo + (hi - lo) / 3.0;
+            let m2 = hi - (hi - lo) / 3.0;
+            if matcher.score(&index, |p| reflect(p, m1)) < matcher.score(&index, |p| reflect(p, m2)) {
+                lo = m1;
+            } else {
+                hi = m2;
+            }
+        }
+        let best = (lo + hi) / 2.0;
+        let best_score = matcher.score(&index, |p| reflect(p, best));
+        if best_score >= SYMMETRY_THRESHOLD {
+            axes.push((best.rem_euclid(PI), best_score));
+        }
+    }
+    axes
+}
+
+/// Detect rotational and mirror symmetry in a point set.
+///
+/// The combined score averages the best rotational match (for order ≥ 2) and
+/// the best mirror match, so a fully asymmetric set scores near 0 and a
+/// regular polygon scores near 1.
+pub fn analyze_symmetry(points: &[Point]) -> SymmetryReport {
+    let scores = rotational_scores(points, 12);
+    let (rotational_order, rotational_score) = order_from_scores(&scores);
+    let best_rotation = scores.iter().map(|&(_, s)| s).fold(0.0, f64::max);
+
+    let axes = mirror_axes(points, 180);
+    let mirror_score = axes.iter().map(|&(_, s)| s).fold(0.0, f64::max);
+    let best_mirror = mirror_scores(points, 180)
+        .iter()
+        .map(|&(_, s)| s)
+        .fold(mirror_score, f64::max);
+
+    SymmetryReport {
+        rotational_order,
+        rotational_score,
+        mirror_axes: axes.into_iter().map(|(a, _)| a).collect(),
+        mirror_score,
+        score: ((best_rotation + best_mirror) / 2.0).clamp(0.0, 1.0),
+    }
+}
+
+/// Combined symmetry score in [0, 1] for a point set.
+pub fn symmetry_score(points: &[Point]) -> f64 {
+    analyze_symmetry(points).score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::fractals::SimpleRng;
+
+    fn polygon(n: usize, offset: Point) -> Vec<Point> {
+        (0..n)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / n as f64 + 0.3;
+                Point { x: offset.x + 10.0 * a.cos(), y: offset.y + 10.0 * a.sin() }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hexagon_order_six() {
+        let pts = polygon(6, Point { x: 0.0, y: 0.0 });
+        let (order, score) = rotational_order(&pts, 12);
+        assert_eq!(order, 6);
+        assert!(score > 0.99);
+    }
+
+    #[test]
+    fn test_order_independent_of_position() {
+        let pts = polygon(5, Point { x: 40.0, y: -17.0 });
+        assert_eq!(rotational_order(&pts, 12).0, 5);
+    }
+
+    #[test]
+    fn test_triangle_mirror_axes() {
+        let pts = polygon(3, Point { x: 0.0, y: 0.0 });
+        let axes = mirror_axes(&pts, 180);
+        assert_eq!(axes.len(), 3, "Axes: {:?}", axes);
+    }
+
+    #[test]
+    fn test_bilateral_shape() {
+        // A kite: symmetric about the y axis only
+        let pts = vec![
+            Point { x: 0.0, y: 10.0 },
+            Point { x: 4.0, y: 0.0 },
+            Point { x: -4.0, y: 0.0 },
+            Point { x: 0.0, y: -20.0 },
+        ];
+        assert_eq!(rotational_order(&pts, 12).0, 1);
+        let axes = mirror_axes(&pts, 180);
+        assert_eq!(axes.len(), 1);
+        assert!((axes[0].0 - PI / 2.0).abs() < 1e-3, "Axis: {}", axes[0].0);
+    }
+
+    #[test]
+    fn test_random_points_asymmetric() {
+        let mut rng = SimpleRng::new(7);
+        let pts: Vec<Point> = (0..50)
+            .map(|_| Point { x: rng.next_f64() * 100.0, y: rng.next_f64() * 60.0 })
+            .collect();
+        let report = analyze_symmetry(&pts);
+        assert_eq!(report.rotational_order, 1);
+        assert!(report.mirror_axes.is_empty());
+        assert!(report.score < 0.5, "Score: {}", report.score);
+    }
+
+    #[test]
+    fn test_symmetric_scores_higher() {
+        let mut rng = SimpleRng::new(3);
+        let asym: Vec<Point> = (0..36)
+            .map(|_| Point { x: rng.next_f64() * 100.0, y: rng.next_f64() * 100.0 })
+            .collect();
+        let sym = polygon(36, Point { x: 0.0, y: 0.0 });
+        assert!(symmetry_score(&sym) > symmetry_score(&asym));
+    }
+
+    #[test]
+    fn test_snowflake_is_sixfold() {
+        use crate::categories::snowflake::{Preset, Snowflake};
+        let params = Preset::Stellar.params();
+        let mut flake = Snowflake::new(25, &params);
+        flake.simulate(&params, 300);
+        let size = 2 * flake.radius + 1;
+        let pts: Vec<Point> = (0..size * size)
+            .filter(|&i| flake.frozen_at[i].is_some())
+            .map(|i| flake.cell_center(i % size, i / size))
+            .collect();
+        let report = analyze_symmetry(&pts);
+        assert_eq!(report.rotational_order, 6);
+        assert_eq!(report.mirror_axes.len(), 6);
+    }
+
+    #[test]
+    fn test_degenerate_inputs() {
+        assert!(rotational_scores(&[], 6).is_empty());
+        assert_eq!(rotational_order(&[Point { x: 1.0, y: 1.0 }], 6), (1, 1.0));
+        assert!(mirror_axes(&[], 90).is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+//! Attractors — strange attractors of 2D iterated maps.
+//!
+//!   Peter de Jong:  x' = sin(ay) − cos(bx),     y' = sin(cx) − cos(dy)
+//!   Clifford:       x' = sin(ay) + c·cos(ax),   y' = sin(bx) + d·cos(by)
+//!   Tinkerbell:     x' = x² − y² + ax + by,     y' = 2xy + cx + dy
+//!
+//! A few million iterates of one of these maps fill a delicate, veiled
+//! shape. Plotted as dots it saturates into a blob, so the orbit is binned
+//! into a [`Density`] raster instead and drawn with log-scaled brightness.
+//! Most parameter draws settle onto a fixed point or a short cycle;
+//! [`explore`] keeps drawing until the largest Lyapunov exponent says the
+//! orbit is chaotic.
+
+use crate::prelude::*;
+use crate::error::{check_count, MathaturaError, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::Theme;
+use super::fractals::{Point, SimpleRng};
+
+/// Most iterates [`density`] will accumulate.
+pub const MAX_ATTRACTOR_POINTS: usize = 200_000_000;
+
+/// Iterates dropped before an orbit counts, so it has reached the attractor.
+const TRANSIENT: usize = 1000;
+
+/// Iterates [`explore`] spends on each Lyapunov estimate.
+const EXPLORE_STEPS: usize = 20_000;
+
+/// Smallest Lyapunov exponent [`explore`] accepts as chaos, in nats per
+/// iterate; a little above zero so quasi-periodic rings are turned down.
+pub const MIN_LYAPUNOV: f64 = 0.01;
+
+/// Any orbit this far out has escaped to infinity.
+const ESCAPE_RADIUS: f64 = 1e6;
+
+/// One of the built-in 2D maps with its four coefficients.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrangeMap {
+    DeJong { a: f64, b: f64, c: f64, d: f64 },
+    Clifford { a: f64, b: f64, c: f64, d: f64 },
+    Tinkerbell { a: f64, b: f64, c: f64, d: f64 },
+}
+
+impl StrangeMap {
+    /// Names accepted by [`StrangeMap::from_name`].
+    pub const NAMES: [&'static str; 3] = ["dejong", "clifford", "tinkerbell"];
+
+    /// The map with a well-known chaotic parameter set, or `None` for an
+    /// unknown name.
+    pub fn from_name(name: &str) -> Option<StrangeMap> {
+        Some(match name {
+            "dejong" | "de-jong" => StrangeMap::DeJong { a: 1.4, b: -2.3, c: 2.4, d: -2.1 },
+            "clifford" => StrangeMap::Clifford { a: -1.4, b: 1.6, c: 1.0, d: 0.7 },
+            "tinkerbell" => StrangeMap::Tinkerbell { a: 0.9, b: -0.6013, c: 2.0, d: 0.5 },
+            _ => return None,
+        })
+    }
+
+    /// The same kind of map with coefficients `[a, b, c, d]`.
+    pub fn with_coefficients(&self, [a, b, c, d]: [f64; 4]) -> StrangeMap {
+        match self {
+            StrangeMap::DeJong { .. } => StrangeMap::DeJong { a, b, c, d },
+            StrangeMap::Clifford { .. } => StrangeMap::Clifford { a, b, c, d },
+            StrangeMap::Tinkerbell { .. } => StrangeMap::Tinkerbell { a, b, c, d },
+        }
+    }
+
+    /// The coefficients `[a, b, c, d]`.
+    pub fn coefficients(&self) -> [f64; 4] {
+        match *self {
+            StrangeMap::DeJong { a, b, c, d } | StrangeMap::Clifford { a, b, c, d } | StrangeMap::Tinkerbell { a, b, c, d } => [a, b, c, d],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StrangeMap::DeJong { .. } => "dejong",
+            StrangeMap::Clifford { .. } => "clifford",
+            StrangeMap::Tinkerbell { .. } => "tinkerbell",
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in ["a", "b", "c", "d"].into_iter().zip(self.coefficients()) {
+            if !value.is_finite() {
+                return Err(MathaturaError::InvalidParameter { name, value, expected: "a finite number" });
+            }
+        }
+        Ok(())
+    }
+
+    /// Where the orbit starts. The trigonometric maps are bounded from
+    /// anywhere; Tinkerbell only from inside its basin.
+    pub fn initial(&self) -> Point {
+        match self {
+            StrangeMap::Tinkerbell { .. } => Point { x: -0.72, y: -0.64 },
+            _ => Point { x: 0.1, y: 0.1 },
+        }
+    }
+
+    /// Coefficients [`explore`] draws from uniformly, `(low, high)` each.
+    pub fn coefficient_range(&self) -> (f64, f64) {
+        match self {
+            StrangeMap::Tinkerbell { .. } => (-1.0, 2.0),
+            _ => (-3.0, 3.0),
+        }
+    }
+
+    /// One iterate.
+    pub fn step(&self, p: Point) -> Point {
+        let Point { x, y } = p;
+        match *self {
+            StrangeMap::DeJong { a, b, c, d } => Point { x: (a * y).sin() - (b * x).cos(), y: (c * x).sin() - (d * y).cos() },
+            StrangeMap::Clifford { a, b, c, d } => Point { x: (a * y).sin() + c * (a * x).cos(), y: (b * x).sin() + d * (b * y).cos() },
+            StrangeMap::Tinkerbell { a, b, c, d } => Point { x: x * x - y * y + a * x + b * y, y: 2.0 * x * y + c * x + d * y },
+        }
+    }
+
+    /// The Jacobian `[[∂x'/∂x, ∂x'/∂y], [∂y'/∂x, ∂y'/∂y]]` at `p`.
+    pub fn jacobian(&self, p: Point) -> [[f64; 2]; 2] {
+        let Point { x, y } = p;
+        match *self {
+            StrangeMap::DeJong { a, b, c, d } => [[b * (b * x).sin(), a * (a * y).cos()], [c * (c * x).cos(), d * (d * y).sin()]],
+            StrangeMap::Clifford { a, b, c, d } => {
+                [[-c * a * (a * x).sin(), a * (a * y).cos()], [b * (b * x).cos(), -d * b * (b * y).sin()]]
+            }
+            StrangeMap::Tinkerbell { a, b, c, d } => [[2.0 * x + a, b - 2.0 * y], [2.0 * y + c, 2.0 * x + d]],
+        }
+    }
+}
+
+/// Endless orbit of `map` from its starting point, transient included.
+pub fn orbit_iter(map: &StrangeMap) -> impl Iterator<Item = Point> + '_ {
+    core::iter::successors(Some(map.initial()), move |&p| Some(map.step(p)))
+}
+
+/// `steps` iterates of `map` once it has settled onto the attractor.
+pub fn orbit(map: &StrangeMap, steps: usize) -> Vec<Point> {
+    orbit_iter(map).skip(TRANSIENT).take(steps).collect()
+}
+
+fn escaped(p: Point) -> bool {
+    !(p.x.abs() < ESCAPE_RADIUS && p.y.abs() < ESCAPE_RADIUS)
+}
+
+/// Largest Lyapunov exponent of `map` over `steps` iterates, in nats per
+/// iterate: a tangent vector is carried along the orbit by the Jacobian
+/// and renormalized every step. Positive means chaos; `None` if the orbit
+/// escapes to infinity.
+pub fn lyapunov(map: &StrangeMap, steps: usize) -> Option<f64> {
+    let mut points = orbit_iter(map).skip(TRANSIENT);
+    let (mut vx, mut vy) = (1.0, 0.0);
+    let mut sum = 0.0;
+    for _ in 0..steps {
+        let p = points.next()?;
+        if escaped(p) {
+            return None;
+        }
+        let [[j00, j01], [j10, j11]] = map.jacobian(p);
+        let (nx, ny) = (j00 * vx + j01 * vy, j10 * vx + j11 * vy);
+        let length = nx.hypot(ny);
+        if length == 0.0 {
+            // Collapsed onto a superstable point
+            return Some(f64::NEG_INFINITY);
+        }
+        sum += length.ln();
+        (vx, vy) = (nx / length, ny / length);
+    }
+    Some(if steps == 0 { 0.0 } else { sum / steps as f64 })
+}
+
+/// Draw random coefficients for the kind of map `like` is until the orbit
+/// stays bounded with a Lyapunov exponent of at least [`MIN_LYAPUNOV`],
+/// returning the map and its exponent. `None` after `attempts` rejections.
+pub fn explore(like: &StrangeMap, seed: u64, attempts: usize) -> Option<(StrangeMap, f64)> {
+    let mut rng = SimpleRng::new(seed);
+    let (low, high) = like.coefficient_range();
+    (0..attempts).find_map(|_| {
+        let map = like.with_coefficients(core::array::from_fn(|_| low + (high - low) * rng.next_f64()));
+        let lambda = lyapunov(&map, EXPLORE_STEPS)?;
+        (lambda >= MIN_LYAPUNOV).then_some((map, lambda))
+    })
+}
+
+/// How often the orbit visited each cell of a raster over its bounding box.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Density {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major visit counts, top row first
+    pub counts: Vec<u32>,
+}
+
+impl Density {
+    /// Brightness of every cell in [0, 1]: ln(1 + count) over the busiest
+    /// cell's, so the faint outer veils show next to the dense core.
+    pub fn shades(&self) -> Vec<f64> {
+        let top = (f64::from(self.counts.iter().copied().max().unwrap_or(0))).ln_1p();
+        self.counts.iter().map(|&c| if top > 0.0 { f64::from(c).ln_1p() / top } else { 0.0 }).collect()
+    }
+
+    /// RGB bytes of the shades through `color`.
+    pub fn to_rgb(&self, color: impl Fn(f64) -> [u8; 3]) -> Vec<u8> {
+        self.shades().into_iter().flat_map(color).collect()
+    }
+}
+
+impl Generator for Density {
+    /// The shades as a scalar grid.
+    fn generate(&self) -> Geometry {
+        Geometry::Grid { cols: self.width, rows: self.height, values: self.shades() }
+    }
+}
+
+/// Bin `points` iterates of `map` into a raster whose longer side is `size`
+/// cells, framing the attractor's bounding box (found from a first look at
+/// the orbit) with a small margin.
+pub fn density(map: &StrangeMap, points: usize, size: usize) -> Result<Density> {
+    check_count("points", points, MAX_ATTRACTOR_POINTS)?;
+    map.validate()?;
+    let (mut x0, mut x1, mut y0, mut y1) = (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+    for p in orbit_iter(map).skip(TRANSIENT).take(points.min(100_000)) {
+        if escaped(p) {
+            return Err(MathaturaError::InvalidParameter { name: "a", value: map.coefficients()[0], expected: "coefficients whose orbit stays bounded" });
+        }
+        (x0, x1, y0, y1) = (x0.min(p.x), x1.max(p.x), y0.min(p.y), y1.max(p.y));
+    }
+    let span = (x1 - x0).max(y1 - y0).max(1e-9) * 1.04;
+    let (cx, cy) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+    let scale = size as f64 / span;
+    let width = (((x1 - x0) * 1.04 * scale).ceil() as usize).clamp(1, size.max(1));
+    let height = (((y1 - y0) * 1.04 * scale).ceil() as usize).clamp(1, size.max(1));
+    let mut counts = vec![0u32; width * height];
+    for p in orbit_iter(map).skip(TRANSIENT).take(points) {
+        let col = ((p.x - cx) * scale + width as f64 / 2.0).floor();
+        // y up
+        let row = ((cy - p.y) * scale + height as f64 / 2.0).floor();
+        if col >= 0.0 && row >= 0.0 && (col as usize) < width && (row as usize) < height {
+            let k = row as usize * width + col as usize;
+            counts[k] = counts[k].saturating_add(1);
+        }
+    }
+    Ok(Density { width, height, counts })
+}
+
+/// Render a density raster, light on a dark ground, one `cell`-pixel square
+/// per bin. `color` maps a shade in [0, 1] to a CSS color.
+pub fn density_to_svg(density: &Density, cell: f64, color: impl Fn(f64) -> String) -> String {
+    density_to_svg_with_theme(density, cell, color, &Theme::dark())
+}
+
+/// [`density_to_svg`] on `theme`'s background.
+pub fn density_to_svg_with_theme(density: &Density, cell: f64, color: impl Fn(f64) -> String, theme: &Theme) -> String {
+    crate::render::raster_svg_with_theme(density.width, density.height, cell, &density.shades(), color, theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_maps_are_chaotic() {
+        for name in StrangeMap::NAMES {
+            let map = StrangeMap::from_name(name).unwrap();
+            assert_eq!(map.name(), name);
+            assert!(map.validate().is_ok());
+            let lambda = lyapunov(&map, 50_000).unwrap();
+            assert!(lambda > MIN_LYAPUNOV, "{name}: {lambda}");
+            assert!(orbit(&map, 1000).iter().all(|p| p.x.is_finite() && p.y.abs() < 10.0));
+        }
+        assert!(StrangeMap::from_name("lorenz").is_none());
+        let nan = StrangeMap::from_name("clifford").unwrap().with_coefficients([f64::NAN, 1.0, 1.0, 1.0]);
+        assert!(nan.validate().is_err());
+    }
+
+    #[test]
+    fn test_jacobian_matches_differences() {
+        let h = 1e-6;
+        for name in StrangeMap::NAMES {
+            let map = StrangeMap::from_name(name).unwrap();
+            let p = Point { x: 0.3, y: -0.2 };
+            let j = map.jacobian(p);
+            let (fx, fy) = (map.step(Point { x: p.x + h, ..p }), map.step(Point { y: p.y + h, ..p }));
+            let f = map.step(p);
+            let numeric = [[(fx.x - f.x) / h, (fy.x - f.x) / h], [(fx.y - f.y) / h, (fy.y - f.y) / h]];
+            for (row, expected) in j.iter().zip(numeric) {
+                for (a, b) in row.iter().zip(expected) {
+                    assert!((a - b).abs() < 1e-4, "{name}: {j:?} vs {numeric:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lyapunov_rejects_order() {
+        // All-zero de Jong coefficients pin the orbit at (−1, −1)
+        let still = StrangeMap::DeJong { a: 0.0, b: 0.0, c: 0.0, d: 0.0 };
+        assert!(lyapunov(&still, 1000).unwrap() < 0.0);
+        let runaway = StrangeMap::Tinkerbell { a: 3.0, b: 3.0, c: 3.0, d: 3.0 };
+        assert_eq!(lyapunov(&runaway, 1000), None);
+        assert!(density(&runaway, 1000, 10).is_err());
+    }
+
+    #[test]
+    fn test_explore_finds_chaos() {
+        for name in StrangeMap::NAMES {
+            let (map, lambda) = explore(&StrangeMap::from_name(name).unwrap(), 7, 500).unwrap();
+            assert_eq!(map.name(), name);
+            assert!(lambda >= MIN_LYAPUNOV);
+            assert_eq!(lyapunov(&map, EXPLORE_STEPS), Some(lambda));
+        }
+        // Same seed, same find
+        let like = StrangeMap::from_name("dejong").unwrap();
+        assert_eq!(explore(&like, 3, 100), explore(&like, 3, 100));
+        assert_eq!(explore(&like, 3, 0), None);
+    }
+
+    #[test]
+    fn test_density() {
+        let map = StrangeMap::from_name("dejong").unwrap();
+        let grid = density(&map, 200_000, 120).unwrap();
+        assert_eq!(grid.width.max(grid.height), 120);
+        assert_eq!(grid.counts.len(), grid.width * grid.height);
+        // Every iterate lands inside the frame
+        assert_eq!(grid.counts.iter().map(|&c| c as usize).sum::<usize>(), 200_000);
+        let shades = grid.shades();
+        assert!(shades.iter().all(|s| (0.0..=1.0).contains(s)));
+        assert!(shades.contains(&1.0));
+        assert_eq!(grid.to_rgb(|_| [1, 2, 3]).len(), 3 * shades.len());
+        assert!(density(&map, MAX_ATTRACTOR_POINTS + 1, 10).is_err());
+        let svg = density_to_svg(&grid, 2.0, crate::render::magma);
+        assert!(svg.contains("<rect"));
+    }
+}
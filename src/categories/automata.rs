@@ -0,0 +1,309 @@
+//! Elementary cellular automata — the cone snail's shell.
+//!
+//! A row of cells, each 0 or 1, updates by looking at itself and its two
+//! neighbors. The eight possible neighborhoods index the bits of an 8-bit
+//! rule number (Wolfram 1983):
+//!   new = (rule >> (4·left + 2·center + right)) & 1
+//! Stacking the rows gives a space-time diagram. Rule 30's chaotic cascade of
+//! triangles closely matches the pigment bands on *Conus textile*, whose
+//! mantle edge lays down shell one line at a time with a similar local rule.
+//!
+//! Totalistic rules with k colors use only the neighborhood sum: digit
+//! `sum` (base k) of the rule code gives the new color.
+
+use crate::prelude::*;
+use crate::error::{check_range, MathaturaError, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::colormap::Colormap;
+use crate::render::theme::Theme;
+use super::fractals::SimpleRng;
+
+/// A one-dimensional, radius-1 update rule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rule {
+    /// Wolfram elementary rule 0–255
+    Elementary(u8),
+    /// k-color totalistic rule with the given Wolfram code
+    Totalistic { colors: u8, code: u64 },
+}
+
+impl Rule {
+    /// Number of cell states.
+    pub fn colors(&self) -> u8 {
+        match *self {
+            Rule::Elementary(_) => 2,
+            Rule::Totalistic { colors, .. } => colors.max(2),
+        }
+    }
+
+    /// A k-color totalistic rule, checked with [`Rule::validate`].
+    pub fn totalistic(colors: u8, code: u64) -> Result<Rule> {
+        let rule = Rule::Totalistic { colors, code };
+        rule.validate()?;
+        Ok(rule)
+    }
+
+    /// Largest valid code for a k-color totalistic rule (k^(3k−2) − 1),
+    /// `u64::MAX` where that no longer fits.
+    pub fn max_totalistic_code(colors: u8) -> u64 {
+        let k = colors.max(2) as u64;
+        k.checked_pow(3 * k as u32 - 2).map_or(u64::MAX, |n| n - 1)
+    }
+
+    /// Check that a totalistic rule has 2 to [`MAX_COLORS`] colors and a
+    /// code below k^(3k−2); every elementary rule is valid.
+    pub fn validate(&self) -> Result<()> {
+        if let Rule::Totalistic { colors, code } = *self {
+            check_range("colors", f64::from(colors), 2.0..=f64::from(MAX_COLORS), "2 to 6 colors")?;
+            let max = Rule::max_totalistic_code(colors);
+            if code > max {
+                return Err(MathaturaError::InvalidParameter {
+                    name: "code",
+                    value: code as f64,
+                    expected: "a totalistic code from 0 to k^(3k-2) - 1",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// New state of the center cell; an invalid rule (see
+    /// [`Rule::validate`]) turns every cell to 0.
+    pub fn apply(&self, left: u8, center: u8, right: u8) -> u8 {
+        if self.validate().is_err() {
+            return 0;
+        }
+        let (left, center, right) = (u32::from(left), u32::from(center), u32::from(right));
+        match *self {
+            Rule::Elementary(rule) => rule.checked_shr(4 * left + 2 * center + right).unwrap_or(0) & 1,
+            Rule::Totalistic { code, .. } => {
+                let k = self.colors() as u64;
+                // Sums past the last digit, from cells outside the rule's
+                // states, select a zero digit
+                k.checked_pow(left + center + right).map_or(0, |place| ((code / place) % k) as u8)
+            }
+        }
+    }
+}
+
+/// Most colors of a totalistic [`Rule`]; 6 colors already allow 6^16 codes.
+pub const MAX_COLORS: u8 = 6;
+
+/// How the first row is seeded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InitialState {
+    /// A single live cell in the middle
+    SingleCell,
+    /// Uniformly random states from the given seed
+    Random(u64),
+}
+
+/// First row for a rule and initial state.
+pub fn initial_row(rule: &Rule, width: usize, init: InitialState) -> Vec<u8> {
+    match init {
+        InitialState::SingleCell => {
+            let mut row = vec![0; width];
+            if width > 0 {
+                row[width / 2] = 1;
+            }
+            row
+        }
+        InitialState::Random(seed) => {
+            let mut rng = SimpleRng::new(seed);
+            (0..width).map(|_| rng.next_usize(rule.colors() as usize) as u8).collect()
+        }
+    }
+}
+
+/// One update with periodic boundaries.
+pub fn step(rule: &Rule, row: &[u8]) -> Vec<u8> {
+    let n = row.len();
+    (0..n)
+        .map(|i| rule.apply(row[(i + n - 1) % n], row[i], row[(i + 1) % n]))
+        .collect()
+}
+
+/// Space-time diagram: `generations` rows, starting with the initial row.
+pub fn evolve(rule: &Rule, width: usize, generations: usize, init: InitialState) -> Vec<Vec<u8>> {
+    let mut rows = Vec::with_capacity(generations);
+    let mut row = initial_row(rule, width, init);
+    for _ in 0..generations {
+        let next = step(rule, &row);
+        rows.push(row);
+        row = next;
+    }
+    rows
+}
+
+/// Fraction of non-zero cells in each generation.
+pub fn density(rows: &[Vec<u8>]) -> Vec<f64> {
+    rows.iter()
+        .map(|r| r.iter().filter(|&&c| c != 0).count() as f64 / r.len().max(1) as f64)
+        .collect()
+}
+
+/// Color scheme for space-time diagrams.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Palette {
+    /// Bright cells on the dark background
+    Mono,
+    /// Cream and chocolate bands of a cone-snail shell
+    Shell,
+}
+
+/// A space-time diagram and its starting row, ready to generate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagram {
+    pub rule: Rule,
+    pub width: usize,
+    pub generations: usize,
+    pub init: InitialState,
+}
+
+impl Generator for Diagram {
+    /// Cell states, one grid row per generation.
+    fn generate(&self) -> Geometry {
+        let rows = evolve(&self.rule, self.width, self.generations, self.init);
+        Geometry::Grid {
+            cols: self.width,
+            rows: rows.len(),
+            values: rows.iter().flatten().map(|&c| c as f64).collect(),
+        }
+    }
+}
+
+/// Render a space-time diagram, time flowing downward.
+pub fn to_svg(rows: &[Vec<u8>], colors: u8, palette: Palette, cell: f64) -> String {
+    to_svg_with_theme(rows, colors, palette, cell, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`, its palette standing in for the colormap of
+/// [`to_svg_with_colormap`] if it has one; the empty state of
+/// [`Palette::Mono`] takes the theme's background.
+pub fn to_svg_with_theme(rows: &[Vec<u8>], colors: u8, palette: Palette, cell: f64, theme: &Theme) -> String {
+    if let Some(gradient) = &theme.palette {
+        return diagram(rows, colors, cell, |t| gradient.css(t), theme);
+    }
+    let empty = theme.background_css();
+    let color = |t: f64| match palette {
+        Palette::Mono if t == 0.0 => empty.clone(),
+        Palette::Mono => crate::render::viridis(0.3 + 0.7 * t),
+        Palette::Shell => crate::render::hsl(30.0 - 8.0 * t, 55.0 - 10.0 * t, 88.0 - 72.0 * t),
+    };
+    diagram(rows, colors, cell, color, theme)
+}
+
+/// Render a space-time diagram with cell states spread evenly along `colormap`.
+pub fn to_svg_with_colormap(rows: &[Vec<u8>], colors: u8, cell: f64, colormap: &dyn Colormap) -> String {
+    diagram(rows, colors, cell, |t| colormap.css(t), &Theme::dark())
+}
+
+fn diagram(rows: &[Vec<u8>], colors: u8, cell: f64, color: impl Fn(f64) -> String, theme: &Theme) -> String {
+    let width = rows.first().map_or(0, |r| r.len());
+    let top = (colors.max(2) - 1) as f64;
+    let values: Vec<f64> = rows.iter().flatten().map(|&c| c as f64 / top).collect();
+    crate::render::raster_svg_with_theme(width, rows.len(), cell, &values, color, theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_bits() {
+        let r = Rule::Elementary(30);
+        // 30 = 00011110: neighborhoods 001, 010, 011, 100 → 1
+        let expected = [0, 1, 1, 1, 1, 0, 0, 0];
+        for (i, &e) in expected.iter().enumerate() {
+            let (l, c, rr) = ((i >> 2) as u8 & 1, (i >> 1) as u8 & 1, i as u8 & 1);
+            assert_eq!(r.apply(l, c, rr), e, "Neighborhood {:03b}", i);
+        }
+    }
+
+    #[test]
+    fn test_rule30_center_column() {
+        // OEIS A051023
+        let expected = [1, 1, 0, 1, 1, 1, 0, 0, 1, 1, 0, 0, 0, 1, 0, 1, 1, 0, 0, 1];
+        let rows = evolve(&Rule::Elementary(30), 41, 20, InitialState::SingleCell);
+        let column: Vec<u8> = rows.iter().map(|r| r[20]).collect();
+        assert_eq!(column, expected);
+    }
+
+    #[test]
+    fn test_rule90_sierpinski() {
+        // Live cells in row n of Rule 90 = 2^(number of ones in n)
+        let rows = evolve(&Rule::Elementary(90), 129, 64, InitialState::SingleCell);
+        for (n, row) in rows.iter().enumerate() {
+            let live = row.iter().filter(|&&c| c == 1).count();
+            assert_eq!(live, 1 << (n as u32).count_ones());
+        }
+    }
+
+    #[test]
+    fn test_trivial_rules() {
+        let init = InitialState::Random(5);
+        let rows = evolve(&Rule::Elementary(204), 50, 10, init);
+        assert!(rows.iter().all(|r| *r == rows[0]), "Rule 204 is the identity");
+        let rows = evolve(&Rule::Elementary(0), 50, 3, init);
+        assert!(rows[1].iter().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn test_totalistic_matches_elementary() {
+        // Two-color totalistic code 6 (sum 1 or 2 → alive) is elementary rule 126
+        let tot = evolve(&Rule::Totalistic { colors: 2, code: 6 }, 60, 30, InitialState::Random(9));
+        let ele = evolve(&Rule::Elementary(126), 60, 30, InitialState::Random(9));
+        assert_eq!(tot, ele);
+    }
+
+    #[test]
+    fn test_totalistic_three_colors() {
+        let rule = Rule::Totalistic { colors: 3, code: 1599 };
+        assert_eq!(Rule::max_totalistic_code(3), 2186);
+        let rows = evolve(&rule, 101, 50, InitialState::SingleCell);
+        assert!(rows.iter().flatten().all(|&c| c < 3));
+        assert!(rows.iter().flatten().any(|&c| c == 2));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Rule::totalistic(3, 2186).is_ok());
+        assert!(Rule::totalistic(3, 2187).is_err());
+        assert!(Rule::totalistic(1, 0).is_err());
+        assert!(Rule::totalistic(7, 0).is_err());
+        // Used to overflow computing the maximum and summing the cells
+        assert_eq!(Rule::max_totalistic_code(20), u64::MAX);
+        assert_eq!(Rule::Totalistic { colors: 90, code: 5 }.apply(89, 89, 89), 0);
+        assert_eq!(Rule::Totalistic { colors: 6, code: u64::MAX }.apply(0, 0, 0), 0);
+        assert_eq!(Rule::Totalistic { colors: 2, code: 6 }.apply(200, 200, 200), 0);
+        assert_eq!(Rule::Elementary(255).apply(9, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_random_init_deterministic() {
+        let rule = Rule::Elementary(110);
+        let a = initial_row(&rule, 100, InitialState::Random(1));
+        assert_eq!(a, initial_row(&rule, 100, InitialState::Random(1)));
+        assert_ne!(a, initial_row(&rule, 100, InitialState::Random(2)));
+    }
+
+    #[test]
+    fn test_rule30_density_near_half() {
+        let rows = evolve(&Rule::Elementary(30), 400, 200, InitialState::Random(3));
+        let d = density(&rows[100..]);
+        let mean = d.iter().sum::<f64>() / d.len() as f64;
+        assert!((mean - 0.5).abs() < 0.05, "Rule 30 density {}", mean);
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let rows = evolve(&Rule::Elementary(30), 61, 30, InitialState::SingleCell);
+        let svg = to_svg(&rows, 2, Palette::Shell, 4.0);
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("<rect"));
+    }
+}
@@ -2,9 +2,43 @@
 //!
 //! "Does the flap of a butterfly's wings in Brazil set off a tornado in Texas?"
 //! — Edward Lorenz
+//!
+//! Lorenz's butterfly is one of a family of three-dimensional flows with
+//! strange attractors: Rössler's single folded band, Aizawa's apple-cored
+//! sphere, Halvorsen's cyclically symmetric propeller, Thomas's labyrinth of
+//! sines, and Chen's double scroll. Each is an [`Attractor3D`], so the same
+//! integrator and [`trajectory_to_svg`] serve them all.
+//!
+//! Trajectories are integrated with classic fourth-order Runge–Kutta by
+//! default. Forward Euler, kept for comparison, visibly distorts the
+//! butterfly at dt = 0.01; adaptive Dormand–Prince RK45 subdivides each step
+//! until it meets a tolerance. See [`Integrator`].
+//!
+//! On the discrete side sit the logistic map and two planar maps: Hénon's
+//! folded horseshoe and the Ikeda map of light circulating in an optical
+//! ring cavity. Both are [`DiscreteMap`]s, drawn as orbits or as basins of
+//! attraction — which starting points fall onto the attractor and which
+//! fly off to infinity.
+//!
+//! Gravity is chaotic too once a third body joins in. [`NBody`] integrates
+//! point masses in the plane with a symplectic leapfrog, from the stable
+//! figure-eight choreography to Burrau's Pythagorean problem, which ends
+//! with one body flung out of the system.
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, check_range, Result};
+use crate::float::{cast, Float};
+use crate::geometry::{Generator, Geometry};
+use crate::parallel::for_each_row;
+use crate::render::canvas::Dots;
+use crate::render::colormap::Colormap;
+use crate::render::Projection;
+use crate::render::theme::{self, Theme};
+use super::fractals::{Point, Region};
 
 /// A 3D point for Lorenz attractor.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point3D {
     pub x: f64,
     pub y: f64,
@@ -12,7 +46,8 @@ pub struct Point3D {
 }
 
 /// Parameters for the Lorenz system.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LorenzParams {
     pub sigma: f64,
     pub rho: f64,
@@ -31,28 +66,636 @@ impl Default for LorenzParams {
     }
 }
 
-/// Simulate the Lorenz attractor.
+impl LorenzParams {
+    /// Require positive σ and β, a finite ρ, and a time step small enough for
+    /// the fixed-step integrators to stay on the attractor.
+    pub fn validate(&self) -> Result<()> {
+        check_positive("sigma", self.sigma)?;
+        check_range("rho", self.rho, f64::MIN..=f64::MAX, "a finite number")?;
+        check_positive("beta", self.beta)?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.05, "0 < dt ≤ 0.05")
+    }
+}
+
+/// Scheme for advancing an ODE by one time step.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Integrator {
+    /// Forward Euler: one derivative per step, first-order accurate
+    Euler,
+    /// Classic Runge–Kutta: four derivatives per step, fourth-order accurate
+    #[default]
+    Rk4,
+    /// Dormand–Prince 5(4), splitting each step into as many substeps as it
+    /// takes to keep the estimated error below `tolerance` (relative to the
+    /// state's magnitude, plus one)
+    Rk45 { tolerance: f64 },
+}
+
+/// `p + h Σ cᵢkᵢ`.
+fn combine<F: Float>(p: [F; 3], h: F, terms: &[(f64, &[F; 3])]) -> [F; 3] {
+    let mut out = p;
+    for &(c, k) in terms {
+        let c = cast::<F>(c) * h;
+        for i in 0..3 {
+            out[i] = out[i] + c * k[i];
+        }
+    }
+    out
+}
+
+/// Smallest RK45 substep, as a fraction of the whole step.
+const RK45_MIN_FRACTION: f64 = 1e-6;
+
+impl Integrator {
+    /// Advance `p` by `dt` along the autonomous flow `f`.
+    pub fn step<F: Float>(&self, f: impl Fn([F; 3]) -> [F; 3], p: [F; 3], dt: F) -> [F; 3] {
+        match *self {
+            Integrator::Euler => combine(p, dt, &[(1.0, &f(p))]),
+            Integrator::Rk4 => {
+                let half = dt * cast(0.5);
+                let k1 = f(p);
+                let k2 = f(combine(p, half, &[(1.0, &k1)]));
+                let k3 = f(combine(p, half, &[(1.0, &k2)]));
+                let k4 = f(combine(p, dt, &[(1.0, &k3)]));
+                combine(p, dt / cast(6.0), &[(1.0, &k1), (2.0, &k2), (2.0, &k3), (1.0, &k4)])
+            }
+            Integrator::Rk45 { tolerance } => {
+                let (tolerance, min_h) = (cast::<F>(tolerance.max(1e-15)), dt * cast(RK45_MIN_FRACTION));
+                let (mut p, mut t, mut h) = (p, F::zero(), dt);
+                while t < dt {
+                    h = h.min(dt - t);
+                    let (next, error) = dormand_prince(&f, p, h);
+                    let scale = p.iter().fold(F::one(), |m, v| m.max(v.abs() + F::one()));
+                    let ratio = error / (tolerance * scale);
+                    if ratio <= F::one() || h <= min_h || !ratio.is_finite() {
+                        p = next;
+                        t = t + h;
+                        if !ratio.is_finite() {
+                            break;
+                        }
+                    }
+                    // Standard step-size controller for a fifth-order method
+                    let grow = if ratio > F::zero() { cast::<F>(0.9) * ratio.powf(cast(-0.2)) } else { cast(5.0) };
+                    h = (h * grow.max(cast(0.2)).min(cast(5.0))).max(min_h);
+                }
+                p
+            }
+        }
+    }
+}
+
+/// One Dormand–Prince step: the fifth-order solution and the largest
+/// component of its difference from the embedded fourth-order one.
+fn dormand_prince<F: Float>(f: &impl Fn([F; 3]) -> [F; 3], p: [F; 3], h: F) -> ([F; 3], F) {
+    let k1 = f(p);
+    let k2 = f(combine(p, h, &[(1.0 / 5.0, &k1)]));
+    let k3 = f(combine(p, h, &[(3.0 / 40.0, &k1), (9.0 / 40.0, &k2)]));
+    let k4 = f(combine(p, h, &[(44.0 / 45.0, &k1), (-56.0 / 15.0, &k2), (32.0 / 9.0, &k3)]));
+    let k5 = f(combine(
+        p,
+        h,
+        &[(19372.0 / 6561.0, &k1), (-25360.0 / 2187.0, &k2), (64448.0 / 6561.0, &k3), (-212.0 / 729.0, &k4)],
+    ));
+    let k6 = f(combine(
+        p,
+        h,
+        &[(9017.0 / 3168.0, &k1), (-355.0 / 33.0, &k2), (46732.0 / 5247.0, &k3), (49.0 / 176.0, &k4), (-5103.0 / 18656.0, &k5)],
+    ));
+    let next = combine(
+        p,
+        h,
+        &[(35.0 / 384.0, &k1), (500.0 / 1113.0, &k3), (125.0 / 192.0, &k4), (-2187.0 / 6784.0, &k5), (11.0 / 84.0, &k6)],
+    );
+    let k7 = f(next);
+    let zero = [F::zero(); 3];
+    let error = combine(
+        zero,
+        h,
+        &[
+            (71.0 / 57600.0, &k1),
+            (-71.0 / 16695.0, &k3),
+            (71.0 / 1920.0, &k4),
+            (-17253.0 / 339200.0, &k5),
+            (22.0 / 525.0, &k6),
+            (-1.0 / 40.0, &k7),
+        ],
+    );
+    (next, error.iter().fold(F::zero(), |m, e| m.max(e.abs())))
+}
+
+/// Simulate the Lorenz attractor with fourth-order Runge–Kutta.
 ///
 /// dx/dt = σ(y - x)
 /// dy/dt = x(ρ - z) - y
 /// dz/dt = xy - βz
 pub fn lorenz_attractor(params: &LorenzParams, steps: usize, initial: Point3D) -> Vec<Point3D> {
-    let mut points = Vec::with_capacity(steps);
-    let mut p = initial;
-    points.push(p);
-
-    for _ in 1..steps {
-        let dx = params.sigma * (p.y - p.x);
-        let dy = p.x * (params.rho - p.z) - p.y;
-        let dz = p.x * p.y - params.beta * p.z;
-        p = Point3D {
-            x: p.x + dx * params.dt,
-            y: p.y + dy * params.dt,
-            z: p.z + dz * params.dt,
-        };
-        points.push(p);
+    // The initial point is always included, even for zero steps
+    lorenz_iter(params, initial).take(steps.max(1)).collect()
+}
+
+/// Endless Lorenz trajectory starting at (and including) `initial`.
+pub fn lorenz_iter(params: &LorenzParams, initial: Point3D) -> LorenzIter {
+    LorenzIter::new(params, initial)
+}
+
+/// [`lorenz_iter`] computed at any float precision.
+pub fn lorenz_iter_as<F: Float>(params: &LorenzParams, initial: Point3D) -> impl Iterator<Item = [F; 3]> {
+    let mut lorenz = LorenzIter::<F>::new(params, initial);
+    core::iter::from_fn(move || Some(lorenz.step()))
+}
+
+/// The Lorenz flow integrated with RK4 as an endless iterator of
+/// [`Point3D`]s: the starting point, then one point per `dt`.
+///
+/// Only the current point is kept, so millions of steps can be streamed
+/// into a renderer or file. `F` is the working precision.
+#[derive(Debug, Clone)]
+pub struct LorenzIter<F = f64> {
+    /// σ, ρ, β and dt
+    coefficients: [F; 4],
+    point: [F; 3],
+}
+
+impl<F: Float> LorenzIter<F> {
+    pub fn new(params: &LorenzParams, initial: Point3D) -> Self {
+        LorenzIter {
+            coefficients: [params.sigma, params.rho, params.beta, params.dt].map(cast::<F>),
+            point: [initial.x, initial.y, initial.z].map(cast::<F>),
+        }
+    }
+
+    /// Return the current point and advance one step.
+    fn step(&mut self) -> [F; 3] {
+        let [sigma, rho, beta, dt] = self.coefficients;
+        let flow = |[x, y, z]: [F; 3]| [sigma * (y - x), x * (rho - z) - y, x * y - beta * z];
+        let current = self.point;
+        self.point = Integrator::Rk4.step(flow, current, dt);
+        current
+    }
+}
+
+impl Iterator for LorenzIter {
+    type Item = Point3D;
+
+    fn next(&mut self) -> Option<Point3D> {
+        let [x, y, z] = self.step();
+        Some(Point3D { x, y, z })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// A plane to project 3D trajectories onto, named by its (horizontal,
+/// vertical) axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Plane {
+    XY,
+    XZ,
+    YZ,
+}
+
+impl Plane {
+    /// Coordinates of `p` in the plane, vertical axis up.
+    pub fn project(&self, p: &Point3D) -> (f64, f64) {
+        match self {
+            Plane::XY => (p.x, p.y),
+            Plane::XZ => (p.x, p.z),
+            Plane::YZ => (p.y, p.z),
+        }
+    }
+}
+
+/// How a 3D trajectory is flattened for drawing: straight onto a
+/// coordinate plane, or through a camera looking from any direction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum View {
+    Plane(Plane),
+    Camera(Projection),
+}
+
+impl View {
+    pub const NAMES: [&'static str; 5] = ["xy", "xz", "yz", "isometric", "orthographic"];
+
+    /// Look up a view by name; `orthographic` looks from `azimuth` degrees
+    /// around the z axis and `elevation` degrees above the xy-plane.
+    pub fn from_name(name: &str, azimuth: f64, elevation: f64) -> Option<View> {
+        match name {
+            "xy" => Some(View::Plane(Plane::XY)),
+            "xz" => Some(View::Plane(Plane::XZ)),
+            "yz" => Some(View::Plane(Plane::YZ)),
+            "isometric" => Some(View::Camera(Projection::Isometric)),
+            "orthographic" => Some(View::Camera(Projection::Orthographic { azimuth, elevation })),
+            _ => None,
+        }
+    }
+
+    /// Screen coordinates of every point, vertical axis up. Camera views
+    /// are centered on the points' mean.
+    pub fn project(&self, points: &[Point3D]) -> Vec<(f64, f64)> {
+        match self {
+            View::Plane(plane) => points.iter().map(|p| plane.project(p)).collect(),
+            View::Camera(projection) => projection.project(points).into_iter().map(|[x, y, _]| (x, -y)).collect(),
+        }
+    }
+}
+
+impl From<Plane> for View {
+    fn from(plane: Plane) -> View {
+        View::Plane(plane)
+    }
+}
+
+impl From<Projection> for View {
+    fn from(projection: Projection) -> View {
+        View::Camera(projection)
+    }
+}
+
+/// A continuous 3D flow with a strange attractor.
+pub trait Attractor3D {
+    fn name(&self) -> &'static str;
+
+    /// The vector field: (dx/dt, dy/dt, dz/dt) at `p`.
+    fn velocity(&self, p: &Point3D) -> Point3D;
+
+    /// Integration time step.
+    fn dt(&self) -> f64;
+
+    /// A starting point that settles onto the attractor.
+    fn initial(&self) -> Point3D {
+        Point3D { x: 1.0, y: 1.0, z: 1.0 }
+    }
+
+    /// The projection that shows the attractor's shape best.
+    fn plane(&self) -> Plane {
+        Plane::XZ
+    }
+}
+
+/// Endless trajectory of any attractor starting at (and including)
+/// `initial`, one point per `dt`.
+pub fn trajectory_iter<A: Attractor3D + ?Sized>(
+    attractor: &A,
+    initial: Point3D,
+    integrator: Integrator,
+) -> impl Iterator<Item = Point3D> + '_ {
+    let dt = attractor.dt();
+    let flow = move |[x, y, z]: [f64; 3]| {
+        let v = attractor.velocity(&Point3D { x, y, z });
+        [v.x, v.y, v.z]
+    };
+    core::iter::successors(Some(initial), move |p| {
+        let [x, y, z] = integrator.step(flow, [p.x, p.y, p.z], dt);
+        Some(Point3D { x, y, z })
+    })
+}
+
+/// `steps` points of an attractor's trajectory from its own starting point.
+pub fn trajectory<A: Attractor3D + ?Sized>(attractor: &A, steps: usize, integrator: Integrator) -> Vec<Point3D> {
+    trajectory_iter(attractor, attractor.initial(), integrator).take(steps.max(1)).collect()
+}
+
+impl Attractor3D for LorenzParams {
+    fn name(&self) -> &'static str {
+        "lorenz"
+    }
+
+    fn velocity(&self, p: &Point3D) -> Point3D {
+        Point3D { x: self.sigma * (p.y - p.x), y: p.x * (self.rho - p.z) - p.y, z: p.x * p.y - self.beta * p.z }
+    }
+
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+}
+
+/// Parameters for the Rössler system, a single band stretched and folded
+/// back on itself.
+///
+/// dx/dt = −y − z
+/// dy/dt = x + ay
+/// dz/dt = b + z(x − c)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RosslerParams {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub dt: f64,
+}
+
+impl Default for RosslerParams {
+    fn default() -> Self {
+        RosslerParams { a: 0.2, b: 0.2, c: 5.7, dt: 0.02 }
+    }
+}
+
+impl RosslerParams {
+    pub fn validate(&self) -> Result<()> {
+        check_range("a", self.a, f64::MIN..=f64::MAX, "a finite number")?;
+        check_range("b", self.b, f64::MIN..=f64::MAX, "a finite number")?;
+        check_positive("c", self.c)?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.05, "0 < dt ≤ 0.05")
+    }
+}
+
+impl Attractor3D for RosslerParams {
+    fn name(&self) -> &'static str {
+        "rossler"
+    }
+
+    fn velocity(&self, p: &Point3D) -> Point3D {
+        Point3D { x: -p.y - p.z, y: p.x + self.a * p.y, z: self.b + p.z * (p.x - self.c) }
+    }
+
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    fn plane(&self) -> Plane {
+        Plane::XY
+    }
+}
+
+/// Parameters for the Aizawa system: trajectories spiral over a sphere and
+/// escape down a tube through its axis.
+///
+/// dx/dt = (z − b)x − dy
+/// dy/dt = dx + (z − b)y
+/// dz/dt = c + az − z³/3 − (x² + y²)(1 + ez) + fzx³
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AizawaParams {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+    pub dt: f64,
+}
+
+impl Default for AizawaParams {
+    fn default() -> Self {
+        AizawaParams { a: 0.95, b: 0.7, c: 0.6, d: 3.5, e: 0.25, f: 0.1, dt: 0.01 }
+    }
+}
+
+impl AizawaParams {
+    pub fn validate(&self) -> Result<()> {
+        for (name, v) in [("a", self.a), ("b", self.b), ("c", self.c), ("d", self.d), ("e", self.e), ("f", self.f)] {
+            check_range(name, v, f64::MIN..=f64::MAX, "a finite number")?;
+        }
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.05, "0 < dt ≤ 0.05")
+    }
+}
+
+impl Attractor3D for AizawaParams {
+    fn name(&self) -> &'static str {
+        "aizawa"
+    }
+
+    fn velocity(&self, p: &Point3D) -> Point3D {
+        let Point3D { x, y, z } = *p;
+        Point3D {
+            x: (z - self.b) * x - self.d * y,
+            y: self.d * x + (z - self.b) * y,
+            z: self.c + self.a * z - z * z * z / 3.0 - (x * x + y * y) * (1.0 + self.e * z) + self.f * z * x * x * x,
+        }
+    }
+
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    fn initial(&self) -> Point3D {
+        Point3D { x: 0.1, y: 0.0, z: 0.0 }
+    }
+}
+
+/// Parameters for the Halvorsen system, symmetric under cycling x → y → z
+/// and so drawn as three interlocking lobes.
+///
+/// dx/dt = −ax − 4y − 4z − y²  (and cyclically for y and z)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HalvorsenParams {
+    pub a: f64,
+    pub dt: f64,
+}
+
+impl Default for HalvorsenParams {
+    fn default() -> Self {
+        HalvorsenParams { a: 1.89, dt: 0.005 }
+    }
+}
+
+impl HalvorsenParams {
+    pub fn validate(&self) -> Result<()> {
+        check_positive("a", self.a)?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.02, "0 < dt ≤ 0.02")
+    }
+}
+
+impl Attractor3D for HalvorsenParams {
+    fn name(&self) -> &'static str {
+        "halvorsen"
+    }
+
+    fn velocity(&self, p: &Point3D) -> Point3D {
+        let a = self.a;
+        Point3D {
+            x: -a * p.x - 4.0 * p.y - 4.0 * p.z - p.y * p.y,
+            y: -a * p.y - 4.0 * p.z - 4.0 * p.x - p.z * p.z,
+            z: -a * p.z - 4.0 * p.x - 4.0 * p.y - p.x * p.x,
+        }
+    }
+
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    fn initial(&self) -> Point3D {
+        Point3D { x: -1.48, y: -1.51, z: 2.04 }
+    }
+
+    fn plane(&self) -> Plane {
+        Plane::XY
+    }
+}
+
+/// Parameters for Thomas' cyclically symmetric attractor, a particle
+/// drifting through a lattice of sines with friction `b`.
+///
+/// dx/dt = sin y − bx  (and cyclically for y and z)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThomasParams {
+    pub b: f64,
+    pub dt: f64,
+}
+
+impl Default for ThomasParams {
+    fn default() -> Self {
+        ThomasParams { b: 0.208186, dt: 0.05 }
+    }
+}
+
+impl ThomasParams {
+    /// Chaos needs a little friction: above b ≈ 0.33 the orbit settles.
+    pub fn validate(&self) -> Result<()> {
+        check_range("b", self.b, 0.0..=1.0, "0 to 1")?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.1, "0 < dt ≤ 0.1")
+    }
+}
+
+impl Attractor3D for ThomasParams {
+    fn name(&self) -> &'static str {
+        "thomas"
+    }
+
+    fn velocity(&self, p: &Point3D) -> Point3D {
+        Point3D { x: p.y.sin() - self.b * p.x, y: p.z.sin() - self.b * p.y, z: p.x.sin() - self.b * p.z }
+    }
+
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    fn initial(&self) -> Point3D {
+        Point3D { x: 1.1, y: 1.1, z: -0.01 }
+    }
+
+    fn plane(&self) -> Plane {
+        Plane::XY
+    }
+}
+
+/// Parameters for the Chen system, a close relative of Lorenz's with a
+/// tighter double scroll.
+///
+/// dx/dt = a(y − x)
+/// dy/dt = (c − a)x − xz + cy
+/// dz/dt = xy − bz
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChenParams {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub dt: f64,
+}
+
+impl Default for ChenParams {
+    fn default() -> Self {
+        ChenParams { a: 35.0, b: 3.0, c: 28.0, dt: 0.002 }
+    }
+}
+
+impl ChenParams {
+    pub fn validate(&self) -> Result<()> {
+        check_positive("a", self.a)?;
+        check_positive("b", self.b)?;
+        check_range("c", self.c, f64::MIN..=f64::MAX, "a finite number")?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.01, "0 < dt ≤ 0.01")
+    }
+}
+
+impl Attractor3D for ChenParams {
+    fn name(&self) -> &'static str {
+        "chen"
+    }
+
+    fn velocity(&self, p: &Point3D) -> Point3D {
+        Point3D { x: self.a * (p.y - p.x), y: (self.c - self.a) * p.x - p.x * p.z + self.c * p.y, z: p.x * p.y - self.b * p.z }
+    }
+
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    fn initial(&self) -> Point3D {
+        Point3D { x: -0.1, y: 0.5, z: -0.6 }
+    }
+}
+
+/// Any of the built-in attractors, chosen by name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Attractor {
+    Lorenz(LorenzParams),
+    Rossler(RosslerParams),
+    Aizawa(AizawaParams),
+    Halvorsen(HalvorsenParams),
+    Thomas(ThomasParams),
+    Chen(ChenParams),
+}
+
+impl Attractor {
+    /// Names accepted by [`Attractor::from_name`].
+    pub const NAMES: [&'static str; 6] = ["lorenz", "rossler", "aizawa", "halvorsen", "thomas", "chen"];
+
+    /// The attractor with default parameters, or `None` for an unknown name.
+    pub fn from_name(name: &str) -> Option<Attractor> {
+        Some(match name {
+            "lorenz" => Attractor::Lorenz(LorenzParams::default()),
+            "rossler" | "rössler" => Attractor::Rossler(RosslerParams::default()),
+            "aizawa" => Attractor::Aizawa(AizawaParams::default()),
+            "halvorsen" => Attractor::Halvorsen(HalvorsenParams::default()),
+            "thomas" => Attractor::Thomas(ThomasParams::default()),
+            "chen" => Attractor::Chen(ChenParams::default()),
+            _ => return None,
+        })
+    }
+
+    fn system(&self) -> &dyn Attractor3D {
+        match self {
+            Attractor::Lorenz(p) => p,
+            Attractor::Rossler(p) => p,
+            Attractor::Aizawa(p) => p,
+            Attractor::Halvorsen(p) => p,
+            Attractor::Thomas(p) => p,
+            Attractor::Chen(p) => p,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Attractor::Lorenz(p) => p.validate(),
+            Attractor::Rossler(p) => p.validate(),
+            Attractor::Aizawa(p) => p.validate(),
+            Attractor::Halvorsen(p) => p.validate(),
+            Attractor::Thomas(p) => p.validate(),
+            Attractor::Chen(p) => p.validate(),
+        }
+    }
+}
+
+impl Attractor3D for Attractor {
+    fn name(&self) -> &'static str {
+        self.system().name()
+    }
+
+    fn velocity(&self, p: &Point3D) -> Point3D {
+        self.system().velocity(p)
+    }
+
+    fn dt(&self) -> f64 {
+        self.system().dt()
+    }
+
+    fn initial(&self) -> Point3D {
+        self.system().initial()
+    }
+
+    fn plane(&self) -> Plane {
+        self.system().plane()
     }
-    points
 }
 
 /// Logistic map: x_{n+1} = r × x_n × (1 - x_n)
@@ -65,88 +708,1236 @@ pub fn logistic_map(r: f64, x0: f64, steps: usize) -> Vec<f64> {
         values.push(x);
         x = r * x * (1.0 - x);
     }
-    values
+    values
+}
+
+/// Generate bifurcation diagram data.
+///
+/// For each r value, runs the logistic map and records the attractor values.
+pub fn bifurcation_diagram(r_min: f64, r_max: f64, r_steps: usize, warmup: usize, samples: usize) -> Vec<(f64, f64)> {
+    let mut data = Vec::new();
+    for i in 0..r_steps {
+        let r = r_min + (r_max - r_min) * (i as f64) / (r_steps as f64 - 1.0);
+        let values = logistic_map(r, 0.5, warmup + samples);
+        for &v in &values[warmup..] {
+            data.push((r, v));
+        }
+    }
+    data
+}
+
+/// Compute Lyapunov exponent for logistic map at given r.
+///
+/// Positive Lyapunov exponent → chaos.
+pub fn lyapunov_exponent(r: f64, iterations: usize) -> f64 {
+    let mut x = 0.5;
+    let mut sum = 0.0;
+    for _ in 0..iterations {
+        let derivative = (r * (1.0 - 2.0 * x)).abs();
+        if derivative > 0.0 {
+            sum += derivative.ln();
+        }
+        x = r * x * (1.0 - x);
+    }
+    sum / iterations as f64
+}
+
+/// Largest Lyapunov exponent of a continuous attractor, per unit time.
+///
+/// Benettin's method: a shadow point starts a tiny distance from the
+/// trajectory, and after every step the separation's growth is logged and
+/// the shadow pulled back to that distance along the same direction. The
+/// first tenth of the `steps` lets the orbit settle onto the attractor.
+/// Lorenz gives about 0.91; anything clearly above zero is chaos.
+pub fn largest_lyapunov<A: Attractor3D + ?Sized>(attractor: &A, steps: usize, integrator: Integrator) -> f64 {
+    const SEPARATION: f64 = 1e-8;
+    let dt = attractor.dt();
+    let flow = |[x, y, z]: [f64; 3]| {
+        let v = attractor.velocity(&Point3D { x, y, z });
+        [v.x, v.y, v.z]
+    };
+    let settle = steps / 10;
+    let start = attractor.initial();
+    let mut p = [start.x, start.y, start.z];
+    for _ in 0..settle {
+        p = integrator.step(flow, p, dt);
+    }
+    let mut q = [p[0] + SEPARATION, p[1], p[2]];
+    let mut sum = 0.0;
+    let measured = steps - settle;
+    for _ in 0..measured {
+        p = integrator.step(flow, p, dt);
+        q = integrator.step(flow, q, dt);
+        let delta = [q[0] - p[0], q[1] - p[1], q[2] - p[2]];
+        let d = (delta[0] * delta[0] + delta[1] * delta[1] + delta[2] * delta[2]).sqrt();
+        if !d.is_finite() || d == 0.0 {
+            return 0.0;
+        }
+        sum += (d / SEPARATION).ln();
+        let k = SEPARATION / d;
+        q = [p[0] + delta[0] * k, p[1] + delta[1] * k, p[2] + delta[2] * k];
+    }
+    if measured == 0 { 0.0 } else { sum / (measured as f64 * dt) }
+}
+
+/// Demonstrate butterfly effect: two nearby starting points diverge.
+pub fn butterfly_effect(params: &LorenzParams, steps: usize, epsilon: f64) -> (Vec<Point3D>, Vec<Point3D>) {
+    let start1 = Point3D { x: 1.0, y: 1.0, z: 1.0 };
+    let start2 = Point3D { x: 1.0 + epsilon, y: 1.0, z: 1.0 };
+    let path1 = lorenz_attractor(params, steps, start1);
+    let path2 = lorenz_attractor(params, steps, start2);
+    (path1, path2)
+}
+
+/// Distance between two 3D points.
+pub fn distance_3d(a: &Point3D, b: &Point3D) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
+
+/// Most states a recurrence plot compares; its matrix grows with the square.
+pub const MAX_RECURRENCE_STATES: usize = 4096;
+
+/// Which pairs of states in a time series come back within a threshold of
+/// each other. Deterministic dynamics show up as lines parallel to the
+/// main diagonal (a stretch of the past repeating), noise as scattered
+/// dots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrencePlot {
+    pub size: usize,
+    /// Row-major: `cells[i * size + j]` when states i and j are close
+    pub cells: Vec<bool>,
+}
+
+impl RecurrencePlot {
+    /// Whether states `i` and `j` are within the threshold.
+    pub fn is_recurrent(&self, i: usize, j: usize) -> bool {
+        self.cells[i * self.size + j]
+    }
+
+    /// Fraction of all pairs that recur, the main diagonal included.
+    pub fn recurrence_rate(&self) -> f64 {
+        self.cells.iter().filter(|&&c| c).count() as f64 / self.cells.len().max(1) as f64
+    }
+
+    /// Fraction of recurrent pairs off the main diagonal that lie on a
+    /// diagonal line at least `min_line` long — the usual measure of how
+    /// predictable the series is. Near 1 for periodic or chaotic maps,
+    /// low for noise.
+    pub fn determinism(&self, min_line: usize) -> f64 {
+        // The plot is symmetric, so the upper triangle tells the whole story
+        let (mut recurrent, mut on_lines) = (0, 0);
+        for offset in 1..self.size {
+            let mut run = 0;
+            for i in 0..=self.size - offset {
+                if i < self.size - offset && self.is_recurrent(i, i + offset) {
+                    run += 1;
+                    continue;
+                }
+                recurrent += run;
+                if run >= min_line {
+                    on_lines += run;
+                }
+                run = 0;
+            }
+        }
+        if recurrent == 0 { 0.0 } else { on_lines as f64 / recurrent as f64 }
+    }
+}
+
+/// Time-delay embedding of a scalar series: state i is
+/// (s\[i\], s\[i + delay\], …, s\[i + (dimension − 1)·delay\]). By Takens'
+/// theorem enough dimensions rebuild the geometry of the hidden attractor.
+pub fn delay_embedding(series: &[f64], dimension: usize, delay: usize) -> Vec<Vec<f64>> {
+    let span = dimension.saturating_sub(1) * delay;
+    if dimension == 0 || series.len() <= span {
+        return Vec::new();
+    }
+    (0..series.len() - span).map(|i| (0..dimension).map(|k| series[i + k * delay]).collect()).collect()
+}
+
+/// Euclidean distance between two embedded states.
+pub fn embedded_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f64>().sqrt()
+}
+
+/// Recurrence plot of `states`: every pair compared with `distance` and
+/// kept when no farther apart than `threshold`. Works on trajectories
+/// (with [`distance_3d`]) as well as delay embeddings (with
+/// [`embedded_distance`]).
+pub fn recurrence_plot<T, D>(states: &[T], threshold: f64, distance: D) -> Result<RecurrencePlot>
+where
+    T: Sync,
+    D: Fn(&T, &T) -> f64 + Sync + Send,
+{
+    check_count("states", states.len(), MAX_RECURRENCE_STATES)?;
+    check_positive("threshold", threshold)?;
+    let size = states.len();
+    let mut cells = vec![false; size * size];
+    for_each_row(&mut cells, size, |i, row| {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = distance(&states[i], &states[j]) <= threshold;
+        }
+    });
+    Ok(RecurrencePlot { size, cells })
+}
+
+/// A chaotic map of the plane.
+///
+/// Hénon:  x' = 1 − ax² + y,  y' = bx
+/// Ikeda:  x' = 1 + u(x·cos t − y·sin t),  y' = u(x·sin t + y·cos t),
+///         t = 0.4 − 6 / (1 + x² + y²)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DiscreteMap {
+    Henon { a: f64, b: f64 },
+    Ikeda { u: f64 },
+}
+
+/// Iterates dropped before a map's orbit counts as on the attractor.
+const MAP_TRANSIENT: usize = 1000;
+
+/// Any map orbit this far out has escaped to infinity.
+const MAP_ESCAPE_RADIUS: f64 = 1e4;
+
+/// Attractor iterates [`basin_grid`] marks before testing starting points.
+const BASIN_SAMPLES: usize = 200_000;
+
+/// Longest cycle [`basin_grid`] recognizes as a competing attractor.
+const MAX_CYCLE: usize = 8;
+
+impl DiscreteMap {
+    /// Names accepted by [`DiscreteMap::from_name`].
+    pub const NAMES: [&'static str; 2] = ["henon", "ikeda"];
+
+    /// The map with its classic chaotic parameters, or `None` for an
+    /// unknown name.
+    pub fn from_name(name: &str) -> Option<DiscreteMap> {
+        Some(match name {
+            "henon" | "hénon" => DiscreteMap::Henon { a: 1.4, b: 0.3 },
+            "ikeda" => DiscreteMap::Ikeda { u: 0.9 },
+            _ => return None,
+        })
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            DiscreteMap::Henon { a, b } => {
+                check_range("a", a, 0.0..=2.0, "0 to 2")?;
+                check_range("b", b, -1.0..=1.0, "-1 to 1")
+            }
+            DiscreteMap::Ikeda { u } => check_range("u", u, 0.0..=0.999, "0 to 0.999"),
+        }
+    }
+
+    /// One iterate.
+    pub fn step(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        match *self {
+            DiscreteMap::Henon { a, b } => (1.0 - a * x * x + y, b * x),
+            DiscreteMap::Ikeda { u } => {
+                let t = 0.4 - 6.0 / (1.0 + x * x + y * y);
+                let (sin, cos) = t.sin_cos();
+                (1.0 + u * (x * cos - y * sin), u * (x * sin + y * cos))
+            }
+        }
+    }
+
+    /// The view that frames the attractor and the interesting part of its
+    /// basin.
+    pub fn region(&self) -> Region {
+        match self {
+            DiscreteMap::Henon { .. } => Region { re_min: -2.5, re_max: 2.5, im_min: -2.5, im_max: 2.5 },
+            DiscreteMap::Ikeda { .. } => Region { re_min: -1.0, re_max: 2.5, im_min: -2.5, im_max: 1.0 },
+        }
+    }
+}
+
+/// Endless orbit of `map` from `start`, transient included.
+pub fn map_orbit_iter(map: &DiscreteMap, start: (f64, f64)) -> impl Iterator<Item = (f64, f64)> + '_ {
+    core::iter::successors(Some(start), move |&p| Some(map.step(p)))
+}
+
+/// `steps` points of `map`'s attractor, traced from the origin after a
+/// transient. Points past escape are dropped, so an orbit that runs away
+/// comes back short.
+pub fn map_orbit(map: &DiscreteMap, steps: usize) -> Vec<Point> {
+    map_orbit_iter(map, (0.0, 0.0))
+        .skip(MAP_TRANSIENT)
+        .take(steps)
+        .take_while(|&(x, y)| x.hypot(y) < MAP_ESCAPE_RADIUS)
+        .map(|(x, y)| Point { x, y })
+        .collect()
+}
+
+/// What became of one starting point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Basin {
+    /// Landed on the attractor after this many iterates
+    Captured(u32),
+    /// Converged onto a fixed point or short cycle somewhere else after
+    /// this many iterates
+    Settled(u32),
+    /// Flew off to infinity after this many iterates
+    Escaped(u32),
+    /// Neither within the iteration budget
+    Undecided,
+}
+
+/// Fates of a raster of starting points.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BasinGrid {
+    pub width: usize,
+    pub height: usize,
+    pub max_iter: u32,
+    /// Row-major, top row first
+    pub cells: Vec<Basin>,
+}
+
+impl BasinGrid {
+    /// Fraction of starting points that reach the attractor.
+    pub fn captured_fraction(&self) -> f64 {
+        self.cells.iter().filter(|c| matches!(c, Basin::Captured(_))).count() as f64 / self.cells.len().max(1) as f64
+    }
+}
+
+/// Basin of attraction of `map` over `region`: every pixel's center is
+/// iterated until it lands on a pixel the attractor itself visits (or
+/// that pixel's neighbor), returns to within 1e-9 of where it was up to
+/// eight iterates earlier, escapes, or runs out of `max_iter`
+/// iterates.
+pub fn basin_grid(map: &DiscreteMap, region: &Region, width: usize, height: usize, max_iter: u32) -> BasinGrid {
+    let cell = |(x, y): (f64, f64)| -> Option<(usize, usize)> {
+        let col = (x - region.re_min) / (region.re_max - region.re_min) * width as f64;
+        let row = (region.im_max - y) / (region.im_max - region.im_min) * height as f64;
+        (col >= 0.0 && row >= 0.0 && col < width as f64 && row < height as f64).then_some((col as usize, row as usize))
+    };
+    let mut attractor = vec![false; width * height];
+    for p in map_orbit(map, BASIN_SAMPLES) {
+        if let Some((col, row)) = cell((p.x, p.y)) {
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().chain([(usize::MAX, 0), (0, usize::MAX)]) {
+                let (c, r) = (col.wrapping_add(dx), row.wrapping_add(dy));
+                if c < width && r < height {
+                    attractor[r * width + c] = true;
+                }
+            }
+        }
+    }
+    let mut cells = vec![Basin::Undecided; width * height];
+    for_each_row(&mut cells, width, |y, row| {
+        for (x, fate) in row.iter_mut().enumerate() {
+            let mut p = region.pixel(x, y, width, height);
+            let mut recent = [(f64::NAN, f64::NAN); MAX_CYCLE];
+            for n in 1..=max_iter {
+                recent[n as usize % MAX_CYCLE] = p;
+                p = map.step(p);
+                let r = p.0.hypot(p.1);
+                if r.is_nan() || r >= MAP_ESCAPE_RADIUS {
+                    *fate = Basin::Escaped(n);
+                    break;
+                }
+                if cell(p).is_some_and(|(c, r)| attractor[r * width + c]) {
+                    *fate = Basin::Captured(n);
+                    break;
+                }
+                if recent.iter().any(|q| (p.0 - q.0).hypot(p.1 - q.1) < 1e-9) {
+                    *fate = Basin::Settled(n);
+                    break;
+                }
+            }
+        }
+    });
+    BasinGrid { width, height, max_iter, cells }
+}
+
+/// Render a basin: captured points along the magma scale by how long they
+/// took to arrive, escaping ones in blues and settling ones in greens that
+/// brighten the longer they lingered, undecided ones black.
+pub fn basin_to_svg(grid: &BasinGrid, cell: f64) -> String {
+    basin_to_svg_with_theme(grid, cell, &Theme::dark())
+}
+
+/// [`basin_to_svg`] in `theme`, captured points along its palette if it has
+/// one.
+pub fn basin_to_svg_with_theme(grid: &BasinGrid, cell: f64, theme: &Theme) -> String {
+    match &theme.palette {
+        Some(palette) => draw_basin(grid, cell, |v| palette.css(1.0 - v), theme),
+        None => draw_basin(grid, cell, |v| crate::render::magma(0.95 - 0.7 * v), theme),
+    }
+}
+
+/// [`basin_to_svg`] with captured points along `colormap`, the slowest to
+/// arrive at its start.
+pub fn basin_to_svg_with_colormap(grid: &BasinGrid, cell: f64, colormap: &dyn Colormap) -> String {
+    draw_basin(grid, cell, |v| colormap.css(1.0 - v), &Theme::dark())
+}
+
+fn draw_basin(grid: &BasinGrid, cell: f64, captured: impl Fn(f64) -> String, theme: &Theme) -> String {
+    let top = f64::from(grid.max_iter).ln_1p();
+    // 32 levels per family keep runs merged; escapes are offset by 2,
+    // settled points by 4
+    let level = |n: u32| (f64::from(n).ln_1p() / top * 32.0).round() / 32.0;
+    let values: Vec<f64> = grid
+        .cells
+        .iter()
+        .map(|fate| match *fate {
+            Basin::Captured(n) => level(n),
+            Basin::Escaped(n) => 2.0 + level(n),
+            Basin::Settled(n) => 4.0 + level(n),
+            Basin::Undecided => -1.0,
+        })
+        .collect();
+    let color = |v| {
+        if v < 0.0 {
+            String::from("#000000")
+        } else if v >= 4.0 {
+            crate::render::hsl(150.0, 55.0, 10.0 + 50.0 * (v - 4.0))
+        } else if v >= 2.0 {
+            crate::render::hsl(220.0, 60.0, 8.0 + 55.0 * (v - 2.0))
+        } else {
+            captured(v)
+        }
+    };
+    crate::render::raster_svg_with_theme(grid.width, grid.height, cell, &values, color, theme)
+}
+
+/// Render a planar map's orbit as dots, colored along the magma scale by
+/// time.
+pub fn map_orbit_to_svg(points: &[Point]) -> String {
+    map_orbit_to_svg_with_dots(points, Dots::Circles)
+}
+
+/// [`map_orbit_to_svg`], its dots written as `dots` says.
+pub fn map_orbit_to_svg_with_dots(points: &[Point], dots: Dots) -> String {
+    map_orbit_to_svg_with_theme(points, dots, &Theme::dark())
+}
+
+/// [`map_orbit_to_svg_with_dots`] in `theme`, time running along its
+/// palette if it has one.
+pub fn map_orbit_to_svg_with_theme(points: &[Point], dots: Dots, theme: &Theme) -> String {
+    let projected: Vec<(f64, f64)> = points.iter().map(|p| (p.x, p.y)).collect();
+    let last = points.len().saturating_sub(1).max(1) as f64;
+    let color = |i, _, _| crate::render::magma(0.25 + 0.75 * i as f64 / last);
+    crate::render::point_cloud_svg_with_theme(&projected, 800.0, 0.6, dots, color, theme)
+}
+
+/// Most bodies [`NBody::validate`] accepts.
+pub const MAX_BODIES: usize = 64;
+
+/// Leapfrog substeps per free-fall time of the closest pair.
+pub const SUBSTEP_FRACTION: f64 = 1000.0;
+
+/// Most substeps one step is split into.
+const MAX_SUBSTEPS: usize = 100_000;
+
+/// A point mass moving in the plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Body {
+    pub mass: f64,
+    pub position: Point,
+    pub velocity: Point,
+}
+
+/// Point masses under Newtonian gravity with G = 1, advanced by
+/// kick-drift-kick leapfrog. Leapfrog is symplectic: its energy error stays
+/// bounded instead of drifting, so periodic orbits keep closing. Close
+/// encounters get shorter substeps (see [`NBody::advance`]).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NBody {
+    pub bodies: Vec<Body>,
+    /// Time step
+    pub dt: f64,
+    /// Plummer softening length, keeping close encounters finite
+    pub softening: f64,
+}
+
+/// Classic three-body configurations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThreeBody {
+    /// Chenciner and Montgomery's figure-eight: three equal masses chasing
+    /// each other around one curve, period ≈ 6.326
+    FigureEight,
+    /// Lagrange's equilateral triangle, rigidly rotating
+    Lagrange,
+    /// Burrau's problem: masses 3, 4 and 5 at rest on a 3-4-5 triangle,
+    /// a dance of close encounters until one body is flung out. When and
+    /// where depends on the last digits of every slingshot, so the ending
+    /// here is a plausible one rather than the historical one, which took
+    /// regularized coordinates to compute
+    Pythagorean,
+}
+
+impl ThreeBody {
+    /// Names accepted by [`ThreeBody::from_name`].
+    pub const NAMES: [&'static str; 3] = ["figure-eight", "lagrange", "pythagorean"];
+
+    pub fn from_name(name: &str) -> Option<ThreeBody> {
+        Some(match name {
+            "figure-eight" | "figure8" => ThreeBody::FigureEight,
+            "lagrange" => ThreeBody::Lagrange,
+            "pythagorean" | "burrau" => ThreeBody::Pythagorean,
+            _ => return None,
+        })
+    }
+
+    /// The starting state, with a time step that resolves it.
+    pub fn system(self) -> NBody {
+        let body = |mass, (x, y), (vx, vy)| Body { mass, position: Point { x, y }, velocity: Point { x: vx, y: vy } };
+        match self {
+            ThreeBody::FigureEight => {
+                let (x, y) = (0.97000436, -0.24308753);
+                let (vx, vy) = (0.93240737, 0.86473146);
+                NBody {
+                    bodies: vec![body(1.0, (x, y), (vx / 2.0, vy / 2.0)), body(1.0, (-x, -y), (vx / 2.0, vy / 2.0)), body(1.0, (0.0, 0.0), (-vx, -vy))],
+                    dt: 1e-3,
+                    softening: 0.0,
+                }
+            }
+            ThreeBody::Lagrange => {
+                // Unit circle; each body feels 1/√3 of a unit force toward the center
+                let speed = 3f64.powf(-0.25);
+                let bodies = (0..3)
+                    .map(|k| {
+                        let angle = k as f64 * core::f64::consts::TAU / 3.0;
+                        body(1.0, (angle.cos(), angle.sin()), (-speed * angle.sin(), speed * angle.cos()))
+                    })
+                    .collect();
+                NBody { bodies, dt: 1e-3, softening: 0.0 }
+            }
+            ThreeBody::Pythagorean => NBody {
+                bodies: vec![body(3.0, (1.0, 3.0), (0.0, 0.0)), body(4.0, (-2.0, -1.0), (0.0, 0.0)), body(5.0, (1.0, -1.0), (0.0, 0.0))],
+                dt: 1e-3,
+                softening: 0.0,
+            },
+        }
+    }
+
+    /// Time that shows the configuration off: two figure-eight periods,
+    /// one Lagrange turn, Burrau's system until after the escape.
+    pub fn duration(self) -> f64 {
+        match self {
+            ThreeBody::FigureEight => 12.65,
+            ThreeBody::Lagrange => 8.27,
+            ThreeBody::Pythagorean => 80.0,
+        }
+    }
+}
+
+impl NBody {
+    pub fn validate(&self) -> Result<()> {
+        check_count("bodies", self.bodies.len(), MAX_BODIES)?;
+        for body in &self.bodies {
+            check_positive("mass", body.mass)?;
+        }
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.1, "0 < dt ≤ 0.1")?;
+        check_range("softening", self.softening, 0.0..=1.0, "0 to 1")
+    }
+
+    fn accelerations(&self) -> Vec<Point> {
+        let eps2 = self.softening * self.softening;
+        let mut acc = vec![Point { x: 0.0, y: 0.0 }; self.bodies.len()];
+        for i in 0..self.bodies.len() {
+            for j in i + 1..self.bodies.len() {
+                let (a, b) = (&self.bodies[i], &self.bodies[j]);
+                let (dx, dy) = (b.position.x - a.position.x, b.position.y - a.position.y);
+                let r2 = dx * dx + dy * dy + eps2;
+                let inv_r3 = 1.0 / (r2 * r2.sqrt());
+                acc[i].x += b.mass * dx * inv_r3;
+                acc[i].y += b.mass * dy * inv_r3;
+                acc[j].x -= a.mass * dx * inv_r3;
+                acc[j].y -= a.mass * dy * inv_r3;
+            }
+        }
+        acc
+    }
+
+    fn kick(&mut self, acc: &[Point], dt: f64) {
+        for (body, a) in self.bodies.iter_mut().zip(acc) {
+            body.velocity.x += a.x * dt;
+            body.velocity.y += a.y * dt;
+        }
+    }
+
+    /// Shortest free-fall time √(r³ / (m₁ + m₂)) over all pairs.
+    fn free_fall_time(&self) -> f64 {
+        let eps2 = self.softening * self.softening;
+        let mut shortest = f64::INFINITY;
+        for (i, a) in self.bodies.iter().enumerate() {
+            for b in &self.bodies[i + 1..] {
+                let r2 = (b.position.x - a.position.x).powi(2) + (b.position.y - a.position.y).powi(2) + eps2;
+                shortest = shortest.min((r2 * r2.sqrt() / (a.mass + b.mass)).sqrt());
+            }
+        }
+        shortest
+    }
+
+    /// Advance by `steps` time steps. During a close encounter a step is
+    /// taken as several leapfrog substeps, each at most 1/[`SUBSTEP_FRACTION`]
+    /// of the closest pair's current free-fall time, so the slingshot is
+    /// resolved.
+    pub fn advance(&mut self, steps: usize) {
+        let mut acc = self.accelerations();
+        for _ in 0..steps {
+            let mut left = self.dt;
+            while left > 0.0 {
+                let dt = left.min((self.free_fall_time() / SUBSTEP_FRACTION).max(self.dt / MAX_SUBSTEPS as f64));
+                self.kick(&acc, dt / 2.0);
+                for body in &mut self.bodies {
+                    body.position.x += body.velocity.x * dt;
+                    body.position.y += body.velocity.y * dt;
+                }
+                acc = self.accelerations();
+                self.kick(&acc, dt / 2.0);
+                left -= dt;
+            }
+        }
+    }
+
+    /// Kinetic plus (softened) potential energy; conserved up to the
+    /// integrator's bounded error.
+    pub fn energy(&self) -> f64 {
+        let eps2 = self.softening * self.softening;
+        let kinetic: f64 = self.bodies.iter().map(|b| 0.5 * b.mass * (b.velocity.x.powi(2) + b.velocity.y.powi(2))).sum();
+        let mut potential = 0.0;
+        for (i, a) in self.bodies.iter().enumerate() {
+            for b in &self.bodies[i + 1..] {
+                let r2 = (b.position.x - a.position.x).powi(2) + (b.position.y - a.position.y).powi(2) + eps2;
+                potential -= a.mass * b.mass / r2.sqrt();
+            }
+        }
+        kinetic + potential
+    }
+
+    /// Total momentum, conserved exactly up to rounding.
+    pub fn momentum(&self) -> Point {
+        self.bodies.iter().fold(Point { x: 0.0, y: 0.0 }, |p, b| Point { x: p.x + b.mass * b.velocity.x, y: p.y + b.mass * b.velocity.y })
+    }
 }
 
-/// Generate bifurcation diagram data.
-///
-/// For each r value, runs the logistic map and records the attractor values.
-pub fn bifurcation_diagram(r_min: f64, r_max: f64, r_steps: usize, warmup: usize, samples: usize) -> Vec<(f64, f64)> {
-    let mut data = Vec::new();
-    for i in 0..r_steps {
-        let r = r_min + (r_max - r_min) * (i as f64) / (r_steps as f64 - 1.0);
-        let values = logistic_map(r, 0.5, warmup + samples);
-        for &v in &values[warmup..] {
-            data.push((r, v));
+/// Each body's path over `steps` time steps, sampled every `every` steps
+/// (starting position included).
+pub fn nbody_trails(system: &NBody, steps: usize, every: usize) -> Vec<Vec<Point>> {
+    let every = every.max(1);
+    let mut system = system.clone();
+    let mut trails: Vec<Vec<Point>> = system.bodies.iter().map(|b| vec![b.position]).collect();
+    let mut done = 0;
+    while done < steps {
+        let chunk = every.min(steps - done);
+        system.advance(chunk);
+        done += chunk;
+        for (trail, body) in trails.iter_mut().zip(&system.bodies) {
+            trail.push(body.position);
         }
     }
-    data
+    trails
 }
 
-/// Compute Lyapunov exponent for logistic map at given r.
-///
-/// Positive Lyapunov exponent → chaos.
-pub fn lyapunov_exponent(r: f64, iterations: usize) -> f64 {
-    let mut x = 0.5;
-    let mut sum = 0.0;
-    for _ in 0..iterations {
-        let derivative = (r * (1.0 - 2.0 * x)).abs();
-        if derivative > 0.0 {
-            sum += derivative.ln();
+/// Render body trails on a dark ground, one golden-angle hue per body and
+/// fading in from the oldest part of the path to a dot at the end, sized by
+/// `masses`. The view fits all trails, y up.
+pub fn nbody_to_svg(trails: &[Vec<Point>], masses: &[f64]) -> String {
+    nbody_to_svg_with_theme(trails, masses, &Theme::dark())
+}
+
+/// [`nbody_to_svg`] in `theme`; with a palette, bodies take it in turn.
+pub fn nbody_to_svg_with_theme(trails: &[Vec<Point>], masses: &[f64], theme: &Theme) -> String {
+    const SIZE: f64 = 800.0;
+    const MARGIN: f64 = 40.0;
+    const FADE_STEPS: usize = 16;
+    let all = || trails.iter().flatten().filter(|p| p.x.is_finite() && p.y.is_finite());
+    let (mut x0, mut x1, mut y0, mut y1) = (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+    for p in all() {
+        (x0, x1, y0, y1) = (x0.min(p.x), x1.max(p.x), y0.min(p.y), y1.max(p.y));
+    }
+    let mut content = format!(r##"<rect width="{SIZE}" height="{SIZE}" fill="{}"/>
+"##, theme.background_css());
+    if x0 > x1 {
+        return crate::render::svg_document_with_theme(SIZE as u32, SIZE as u32, &content, theme);
+    }
+    let scale = (SIZE - 2.0 * MARGIN) / (x1 - x0).max(y1 - y0).max(1e-9);
+    let (ox, oy) = ((SIZE - (x1 - x0) * scale) / 2.0, (SIZE - (y1 - y0) * scale) / 2.0);
+    let at = |p: &Point| (ox + (p.x - x0) * scale, SIZE - oy - (p.y - y0) * scale);
+    let largest = masses.iter().copied().fold(0.0, f64::max).max(1e-12);
+    for (k, trail) in trails.iter().enumerate() {
+        let hue = crate::render::golden_hue(30.0, k);
+        let t = theme::place(k, trails.len());
+        let chunk = trail.len().div_ceil(FADE_STEPS).max(1);
+        for (i, part) in trail.chunks(chunk).enumerate() {
+            // Overlap by one point so the pieces join
+            let start = (i * chunk).saturating_sub(1);
+            let points: String = trail[start..start + part.len() + usize::from(i > 0)]
+                .iter()
+                .map(|p| {
+                    let (x, y) = at(p);
+                    format!("{x:.1},{y:.1} ")
+                })
+                .collect();
+            let opacity = 0.15 + 0.85 * (i + 1) as f64 / FADE_STEPS as f64;
+            content.push_str(&format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" opacity=\"{:.2}\"/>\n",
+                points.trim_end(),
+                theme.color(t, crate::render::hsl(hue, 80.0, 62.0)),
+                theme.stroke_width(1.2),
+                opacity.min(1.0)
+            ));
+        }
+        if let Some(last) = trail.last() {
+            let (x, y) = at(last);
+            let mass = masses.get(k).copied().unwrap_or(largest);
+            let r = 3.0 + 5.0 * (mass / largest).cbrt();
+            let fill = theme.color(t, crate::render::hsl(hue, 85.0, 72.0));
+            content.push_str(&format!("<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"{r:.1}\" fill=\"{fill}\"/>\n"));
         }
-        x = r * x * (1.0 - x);
     }
-    sum / iterations as f64
+    crate::render::svg_document_with_theme(SIZE as u32, SIZE as u32, &content, theme)
 }
 
-/// Demonstrate butterfly effect: two nearby starting points diverge.
-pub fn butterfly_effect(params: &LorenzParams, steps: usize, epsilon: f64) -> (Vec<Point3D>, Vec<Point3D>) {
-    let start1 = Point3D { x: 1.0, y: 1.0, z: 1.0 };
-    let start2 = Point3D { x: 1.0 + epsilon, y: 1.0, z: 1.0 };
-    let path1 = lorenz_attractor(params, steps, start1);
-    let path2 = lorenz_attractor(params, steps, start2);
-    (path1, path2)
+/// A Lorenz trajectory, ready to generate.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LorenzOrbit {
+    pub params: LorenzParams,
+    pub steps: usize,
+    pub initial: Point3D,
 }
 
-/// Distance between two 3D points.
-pub fn distance_3d(a: &Point3D, b: &Point3D) -> f64 {
-    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+impl Default for LorenzOrbit {
+    fn default() -> Self {
+        LorenzOrbit { params: LorenzParams::default(), steps: 10000, initial: Point3D { x: 1.0, y: 1.0, z: 1.0 } }
+    }
+}
+
+impl Generator for LorenzOrbit {
+    /// The classic butterfly view: x across, z up.
+    fn generate(&self) -> Geometry {
+        let points = lorenz_attractor(&self.params, self.steps, self.initial);
+        Geometry::Polylines(vec![points.iter().map(|p| Point { x: p.x, y: -p.z }).collect()])
+    }
+}
+
+/// A trajectory of any built-in attractor, ready to generate.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Orbit {
+    pub attractor: Attractor,
+    pub steps: usize,
+}
+
+impl Generator for Orbit {
+    /// The trajectory in the attractor's preferred plane, vertical axis up.
+    fn generate(&self) -> Geometry {
+        let plane = self.attractor.plane();
+        let points = trajectory(&self.attractor, self.steps, Integrator::default());
+        Geometry::Polylines(vec![points.iter().map(|p| plane.project(p)).map(|(x, y)| Point { x, y: -y }).collect()])
+    }
 }
 
 /// Generate SVG of Lorenz attractor (XZ projection).
 pub fn lorenz_to_svg(points: &[Point3D]) -> String {
+    lorenz_to_svg_with_theme(points, &Theme::dark())
+}
+
+/// [`lorenz_to_svg`] in `theme`.
+pub fn lorenz_to_svg_with_theme(points: &[Point3D], theme: &Theme) -> String {
+    trajectory_to_svg_with_theme(points, Plane::XZ, theme)
+}
+
+/// Render any 3D trajectory seen through `view` (a [`Plane`] or a camera
+/// [`Projection`]), scaled uniformly to fit an 800×600 canvas.
+pub fn trajectory_to_svg(points: &[Point3D], view: impl Into<View>) -> String {
+    trajectory_to_svg_with_theme(points, view, &Theme::dark())
+}
+
+/// [`trajectory_to_svg`] in `theme`.
+pub fn trajectory_to_svg_with_theme(points: &[Point3D], view: impl Into<View>, theme: &Theme) -> String {
     if points.is_empty() {
         return String::from(r##"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="600"></svg>"##);
     }
     let w = 800;
     let h = 600;
-    let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
-    let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
-    let min_z = points.iter().map(|p| p.z).fold(f64::INFINITY, f64::min);
-    let max_z = points.iter().map(|p| p.z).fold(f64::NEG_INFINITY, f64::max);
-    let sx = (w - 80) as f64 / (max_x - min_x).max(1.0);
-    let sy = (h - 80) as f64 / (max_z - min_z).max(1.0);
+    let projected = fit_to_canvas(&view.into().project(points), w as f64, h as f64);
 
     let mut svg = format!(
         r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}">
-<rect width="{w}" height="{h}" fill="#0a0a1a"/>
-<polyline points=""##
+<rect width="{w}" height="{h}" fill="{}"/>
+{}<polyline points=""##,
+        theme.background_css(),
+        theme.foreground_start()
     );
 
-    for p in points {
-        let x = 40.0 + (p.x - min_x) * sx;
-        let y = h as f64 - 40.0 - (p.z - min_z) * sy;
+    for &(x, y) in &projected {
         svg.push_str(&format!("{:.1},{:.1} ", x, y));
     }
 
-    svg.push_str(r##"" fill="none" stroke="#ff6b6b" stroke-width="0.5" opacity="0.8"/>
-</svg>"##);
+    svg.push_str(&format!(
+        r##"" fill="none" stroke="{}" stroke-width="{}" opacity="0.8"/>
+{}</svg>"##,
+        theme.color(1.0, "#ff6b6b"),
+        theme.stroke_width(0.5),
+        theme.foreground_end()
+    ));
     svg
 }
 
+/// Scale projected points (vertical axis up) uniformly into a `w`×`h`
+/// canvas with a 40-pixel margin, centering the shorter extent.
+fn fit_to_canvas(projected: &[(f64, f64)], w: f64, h: f64) -> Vec<(f64, f64)> {
+    let min_u = projected.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let max_u = projected.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let min_v = projected.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_v = projected.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    let scale = ((w - 80.0) / (max_u - min_u).max(1e-9)).min((h - 80.0) / (max_v - min_v).max(1e-9));
+    // Center the shorter extent
+    let (ox, oy) = ((w - (max_u - min_u) * scale) / 2.0, (h - (max_v - min_v) * scale) / 2.0);
+    projected.iter().map(|&(u, v)| (ox + (u - min_u) * scale, h - oy - (v - min_v) * scale)).collect()
+}
+
+/// Render the two paths of [`butterfly_effect`] overlaid in contrasting
+/// colors through `view`, with an inset plotting log₁₀ of their distance
+/// against time (`dt` per step). The inset's straight rise is the
+/// exponential divergence; its slope is the largest Lyapunov exponent
+/// over ln 10, until the gap saturates at the attractor's size.
+pub fn butterfly_to_svg(path1: &[Point3D], path2: &[Point3D], dt: f64, view: impl Into<View>) -> String {
+    butterfly_to_svg_with_theme(path1, path2, dt, view, &Theme::dark())
+}
+
+/// [`butterfly_to_svg`] in `theme`; with a palette, the paths take its two
+/// ends and the inset's frame its first stop.
+pub fn butterfly_to_svg_with_theme(path1: &[Point3D], path2: &[Point3D], dt: f64, view: impl Into<View>, theme: &Theme) -> String {
+    let (w, h) = (800.0, 600.0);
+    let mut content = String::new();
+    let n = path1.len().min(path2.len());
+    if n < 2 {
+        return crate::render::svg_document_with_theme(w as u32, h as u32, &content, theme);
+    }
+    // Project both together so they share one frame
+    let both: Vec<Point3D> = path1[..n].iter().chain(&path2[..n]).copied().collect();
+    let projected = fit_to_canvas(&view.into().project(&both), w, h);
+    for (path, color) in [(&projected[..n], theme.color(0.0, "#4cc9f0")), (&projected[n..], theme.color(1.0, "#ff6b6b"))] {
+        let points: String = path.iter().map(|(x, y)| format!("{x:.1},{y:.1} ")).collect();
+        let (x, y) = path[n - 1];
+        content.push_str(&format!(
+            r##"<polyline points="{}" fill="none" stroke="{color}" stroke-width="{}" opacity="0.7"/>
+<circle cx="{x:.1}" cy="{y:.1}" r="4" fill="{color}"/>
+"##,
+            points.trim_end(),
+            theme.stroke_width(0.6)
+        ));
+    }
+
+    // Inset in the top-left corner, subsampled to at most 500 points
+    let (ix, iy, iw, ih) = (20.0, 20.0, 260.0, 150.0);
+    let every = n.div_ceil(500).max(1);
+    let logs: Vec<(f64, f64)> = (0..n)
+        .step_by(every)
+        .map(|i| (i as f64 * dt, distance_3d(&path1[i], &path2[i]).max(1e-300).log10()))
+        .collect();
+    let low = logs.iter().map(|p| p.1).fold(f64::INFINITY, f64::min).floor();
+    let high = logs.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max).ceil().max(low + 1.0);
+    let duration = ((n - 1) as f64 * dt).max(1e-12);
+    let (m, bottom, right) = (30.0, iy + ih - 20.0, ix + iw - 10.0);
+    let at = |(t, d): (f64, f64)| (ix + m + t / duration * (right - ix - m), bottom - (d - low) / (high - low) * (bottom - iy - 10.0));
+    let curve: String = logs
+        .iter()
+        .map(|&p| {
+            let (x, y) = at(p);
+            format!("{x:.1},{y:.1} ")
+        })
+        .collect();
+    content.push_str(&format!(
+        r##"<rect x="{ix}" y="{iy}" width="{iw}" height="{ih}" fill="{background}" fill-opacity="0.85" stroke="{frame}"/>
+<line x1="{left}" y1="{bottom}" x2="{right}" y2="{bottom}" stroke="{axis}"/>
+<line x1="{left}" y1="{top}" x2="{left}" y2="{bottom}" stroke="{axis}"/>
+<text x="{label_x}" y="{top_label}" fill="{label}" font-family="sans-serif" font-size="10" text-anchor="end">{high}</text>
+<text x="{label_x}" y="{bottom}" fill="{label}" font-family="sans-serif" font-size="10" text-anchor="end">{low}</text>
+<text x="{right}" y="{caption}" fill="{label}" font-family="sans-serif" font-size="10" text-anchor="end">log₁₀ distance, t = 0 … {duration:.1}</text>
+<polyline points="{curve}" fill="none" stroke="{stroke}" stroke-width="{width}"/>
+"##,
+        background = theme.background_css(),
+        frame = theme.color(0.0, "#30363d"),
+        axis = theme.color(0.0, "#555"),
+        label = theme.color(0.0, "#888"),
+        stroke = theme.color(1.0, "#ffd166"),
+        width = theme.stroke_width(1.2),
+        left = ix + m,
+        top = iy + 10.0,
+        label_x = ix + m - 4.0,
+        top_label = iy + 18.0,
+        caption = iy + ih - 6.0,
+        curve = curve.trim_end(),
+    ));
+    crate::render::svg_document_with_theme(w as u32, h as u32, &content, theme)
+}
+
+/// What a colored trajectory's hue follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrajectoryColor {
+    /// Dark to bright along the magma scale from start to finish
+    Time,
+    /// Distance covered per step along the viridis scale, slowest to
+    /// fastest: the orbit lingers (dark) near the unstable fixed points
+    Speed,
+}
+
+impl TrajectoryColor {
+    pub const NAMES: [&'static str; 2] = ["time", "speed"];
+
+    pub fn from_name(name: &str) -> Option<TrajectoryColor> {
+        match name {
+            "time" => Some(TrajectoryColor::Time),
+            "speed" => Some(TrajectoryColor::Speed),
+            _ => None,
+        }
+    }
+}
+
+/// Render a 3D trajectory as short polylines colored by time or speed,
+/// fitted to an 800×600 canvas like [`trajectory_to_svg`]. Steps are
+/// quantized to 64 color levels and consecutive steps on the same level
+/// share a polyline, so a smooth stretch stays one element.
+pub fn colored_trajectory_to_svg(points: &[Point3D], view: impl Into<View>, color: TrajectoryColor) -> String {
+    colored_trajectory_to_svg_with_theme(points, view, color, &Theme::dark())
+}
+
+/// [`colored_trajectory_to_svg`] in `theme`, time or speed running along
+/// its palette if it has one.
+pub fn colored_trajectory_to_svg_with_theme(points: &[Point3D], view: impl Into<View>, color: TrajectoryColor, theme: &Theme) -> String {
+    const LEVELS: f64 = 64.0;
+    let (w, h) = (800.0, 600.0);
+    let mut content = format!(r##"<rect width="{w}" height="{h}" fill="{}"/>
+"##, theme.background_css());
+    if points.len() < 2 {
+        return crate::render::svg_document_with_theme(w as u32, h as u32, &content, theme);
+    }
+    let projected = fit_to_canvas(&view.into().project(points), w, h);
+    // One value in 0..1 per step between consecutive points
+    let values: Vec<f64> = match color {
+        TrajectoryColor::Time => (0..points.len() - 1).map(|i| i as f64 / (points.len() - 2).max(1) as f64).collect(),
+        TrajectoryColor::Speed => {
+            let speeds: Vec<f64> = points.windows(2).map(|p| distance_3d(&p[0], &p[1])).collect();
+            let slowest = speeds.iter().copied().fold(f64::INFINITY, f64::min);
+            let fastest = speeds.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            speeds.iter().map(|v| (v - slowest) / (fastest - slowest).max(1e-12)).collect()
+        }
+    };
+    let shade = |level: f64| match (&theme.palette, color) {
+        (Some(palette), _) => palette.css(level),
+        (None, TrajectoryColor::Time) => crate::render::magma(0.25 + 0.75 * level),
+        (None, TrajectoryColor::Speed) => crate::render::viridis(level),
+    };
+    let level = |v: f64| (v * (LEVELS - 1.0)).round() / (LEVELS - 1.0);
+    let mut start = 0;
+    while start < values.len() {
+        let current = level(values[start]);
+        let mut end = start + 1;
+        while end < values.len() && level(values[end]) == current {
+            end += 1;
+        }
+        // Steps start..end join points start..=end
+        let points: String = projected[start..=end].iter().map(|(x, y)| format!("{x:.1},{y:.1} ")).collect();
+        content.push_str(&format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>\n",
+            points.trim_end(),
+            shade(current),
+            theme.stroke_width(0.6)
+        ));
+        start = end;
+    }
+    crate::render::svg_document_with_theme(w as u32, h as u32, &content, theme)
+}
+
+/// Render a trajectory as a cloud of dots rather than a line, seen through
+/// `view` and fitted to its bounding box. Dots are colored along the magma
+/// scale by time, so the order in which the orbit visits each lobe shows.
+pub fn cloud_to_svg(points: &[Point3D], view: impl Into<View>) -> String {
+    cloud_to_svg_with_dots(points, view, Dots::Circles)
+}
+
+/// [`cloud_to_svg`], its dots written as `dots` says.
+pub fn cloud_to_svg_with_dots(points: &[Point3D], view: impl Into<View>, dots: Dots) -> String {
+    cloud_to_svg_with_theme(points, view, dots, &Theme::dark())
+}
+
+/// [`cloud_to_svg_with_dots`] in `theme`, time running along its palette if
+/// it has one.
+pub fn cloud_to_svg_with_theme(points: &[Point3D], view: impl Into<View>, dots: Dots, theme: &Theme) -> String {
+    let projected = view.into().project(points);
+    let last = points.len().saturating_sub(1).max(1) as f64;
+    let color = |i, _, _| crate::render::magma(0.25 + 0.75 * i as f64 / last);
+    crate::render::point_cloud_svg_with_theme(&projected, 800.0, 0.6, dots, color, theme)
+}
+
+/// A plane `normal · p = offset` slicing through phase space, for
+/// [`poincare_section`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SectionPlane {
+    pub normal: Point3D,
+    pub offset: f64,
+}
+
+impl SectionPlane {
+    /// The plane x = `value`.
+    pub fn x(value: f64) -> SectionPlane {
+        SectionPlane { normal: Point3D { x: 1.0, y: 0.0, z: 0.0 }, offset: value }
+    }
+
+    /// The plane y = `value`.
+    pub fn y(value: f64) -> SectionPlane {
+        SectionPlane { normal: Point3D { x: 0.0, y: 1.0, z: 0.0 }, offset: value }
+    }
+
+    /// The plane z = `value`.
+    pub fn z(value: f64) -> SectionPlane {
+        SectionPlane { normal: Point3D { x: 0.0, y: 0.0, z: 1.0 }, offset: value }
+    }
+
+    /// Parse `x=0`, `y=-2.5` or `z=27`.
+    pub fn parse(text: &str) -> Option<SectionPlane> {
+        let (axis, value) = text.split_once('=')?;
+        let value: f64 = value.trim().parse().ok().filter(|v: &f64| v.is_finite())?;
+        match axis.trim() {
+            "x" => Some(SectionPlane::x(value)),
+            "y" => Some(SectionPlane::y(value)),
+            "z" => Some(SectionPlane::z(value)),
+            _ => None,
+        }
+    }
+
+    /// How far `p` lies on the normal's side, in units of the normal's
+    /// length.
+    pub fn side(&self, p: &Point3D) -> f64 {
+        self.normal.x * p.x + self.normal.y * p.y + self.normal.z * p.z - self.offset
+    }
+
+    /// The coordinate plane to draw the section in: the one the normal
+    /// leans on least, so points in the slice stay spread out.
+    pub fn view(&self) -> Plane {
+        let (x, y, z) = (self.normal.x.abs(), self.normal.y.abs(), self.normal.z.abs());
+        if z >= x && z >= y {
+            Plane::XY
+        } else if y >= x {
+            Plane::XZ
+        } else {
+            Plane::YZ
+        }
+    }
+}
+
+/// Poincaré section: where `trajectory` pierces `plane` going the normal's
+/// way, each crossing placed by linear interpolation between the steps on
+/// either side. Counting only one direction keeps the returns to the plane
+/// from piling two sheets onto one picture.
+pub fn poincare_section(trajectory: &[Point3D], plane: &SectionPlane) -> Vec<Point3D> {
+    trajectory
+        .windows(2)
+        .filter_map(|w| {
+            let (a, b) = (plane.side(&w[0]), plane.side(&w[1]));
+            (a < 0.0 && b >= 0.0).then(|| {
+                let t = a / (a - b);
+                Point3D { x: w[0].x + t * (w[1].x - w[0].x), y: w[0].y + t * (w[1].y - w[0].y), z: w[0].z + t * (w[1].z - w[0].z) }
+            })
+        })
+        .collect()
+}
+
+/// Render a Poincaré section as a scatter in `plane`'s view, dots colored
+/// along the magma scale by crossing order.
+pub fn poincare_to_svg(section: &[Point3D], plane: &SectionPlane) -> String {
+    poincare_to_svg_with_dots(section, plane, Dots::Circles)
+}
+
+/// [`poincare_to_svg`], its dots written as `dots` says.
+pub fn poincare_to_svg_with_dots(section: &[Point3D], plane: &SectionPlane, dots: Dots) -> String {
+    poincare_to_svg_with_theme(section, plane, dots, &Theme::dark())
+}
+
+/// [`poincare_to_svg_with_dots`] in `theme`, crossing order running along
+/// its palette if it has one.
+pub fn poincare_to_svg_with_theme(section: &[Point3D], plane: &SectionPlane, dots: Dots, theme: &Theme) -> String {
+    let view = plane.view();
+    let projected: Vec<(f64, f64)> = section.iter().map(|p| view.project(p)).collect();
+    let last = section.len().saturating_sub(1).max(1) as f64;
+    let color = |i, _, _| crate::render::magma(0.3 + 0.7 * i as f64 / last);
+    crate::render::point_cloud_svg_with_theme(&projected, 800.0, 1.5, dots, color, theme)
+}
+
+/// Render bifurcation data as a density-shaded scatter on an 800-wide
+/// canvas: r across, x ∈ [0, 1] up, `cols × rows` bins.
+///
+/// Each bin is shaded by the log of how many orbit points landed in it, so
+/// the bright periodic branches and the fainter chaotic bands both show.
+pub fn bifurcation_to_svg(data: &[(f64, f64)], cols: usize, rows: usize) -> String {
+    bifurcation_to_svg_with_theme(data, cols, rows, &Theme::dark())
+}
+
+/// [`bifurcation_to_svg`] in `theme`, bin density running along its palette
+/// if it has one.
+pub fn bifurcation_to_svg_with_theme(data: &[(f64, f64)], cols: usize, rows: usize, theme: &Theme) -> String {
+    match &theme.palette {
+        Some(palette) => draw_bifurcation(data, cols, rows, |t| palette.css(t), theme),
+        None => draw_bifurcation(data, cols, rows, |t| crate::render::magma(0.25 + 0.75 * t), theme),
+    }
+}
+
+/// [`bifurcation_to_svg`] with bin density running along `colormap`.
+pub fn bifurcation_to_svg_with_colormap(data: &[(f64, f64)], cols: usize, rows: usize, colormap: &dyn Colormap) -> String {
+    draw_bifurcation(data, cols, rows, |t| colormap.css(t), &Theme::dark())
+}
+
+fn draw_bifurcation(data: &[(f64, f64)], cols: usize, rows: usize, shade: impl Fn(f64) -> String, theme: &Theme) -> String {
+    let (cols, rows) = (cols.max(1), rows.max(1));
+    let r_min = data.iter().map(|d| d.0).fold(f64::INFINITY, f64::min);
+    let r_max = data.iter().map(|d| d.0).fold(f64::NEG_INFINITY, f64::max);
+    let span = (r_max - r_min).max(1e-12);
+    let mut counts = vec![0u32; cols * rows];
+    for &(r, x) in data.iter().filter(|d| d.1.is_finite()) {
+        let col = (((r - r_min) / span) * (cols - 1) as f64).round() as usize;
+        let row = ((1.0 - x.clamp(0.0, 1.0)) * (rows - 1) as f64).round() as usize;
+        counts[row * cols + col.min(cols - 1)] += 1;
+    }
+    let max = counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+    // 32 tone levels keep runs of similar bins merged
+    let tones: Vec<f64> = counts.iter().map(|&c| ((c as f64).ln_1p() / max.ln_1p() * 32.0).ceil() / 32.0).collect();
+    let background = theme.background_css();
+    let color = |t: f64| if t > 0.0 { shade(t) } else { background.clone() };
+    crate::render::raster_svg_with_theme(cols, rows, 800.0 / cols as f64, &tones, color, theme)
+}
+
+/// Render a logistic-map orbit as a time series: iterate n across, x ∈
+/// [0, 1] up, with a dot on each value while they are few enough to see.
+pub fn logistic_to_svg(values: &[f64]) -> String {
+    logistic_to_svg_with_theme(values, &Theme::dark())
+}
+
+/// [`logistic_to_svg`] in `theme`; with a palette, the axis takes its first
+/// stop, the line its middle and the dots its last.
+pub fn logistic_to_svg_with_theme(values: &[f64], theme: &Theme) -> String {
+    let (w, h, margin) = (800.0, 400.0, 40.0);
+    let step = (w - 2.0 * margin) / (values.len().max(2) - 1) as f64;
+    let at = |i: usize, x: f64| (margin + i as f64 * step, h - margin - x.clamp(0.0, 1.0) * (h - 2.0 * margin));
+    let mut content = format!(
+        r##"<rect width="{w}" height="{h}" fill="{}"/>
+<line x1="{margin}" y1="{y}" x2="{x2}" y2="{y}" stroke="{}" stroke-width="{}"/>
+<polyline points=""##,
+        theme.background_css(),
+        theme.color(0.0, "#30363d"),
+        theme.stroke_width(1.0),
+        y = h - margin,
+        x2 = w - margin
+    );
+    for (i, &x) in values.iter().enumerate() {
+        let (px, py) = at(i, x);
+        content.push_str(&format!("{px:.1},{py:.1} "));
+    }
+    content.push_str(&format!(
+        r##"" fill="none" stroke="{}" stroke-width="{}" opacity="0.8"/>
+"##,
+        theme.color(0.5, "#7fdbca"),
+        theme.stroke_width(1.0)
+    ));
+    if values.len() <= 300 {
+        let dot = theme.color(1.0, "#ffd166");
+        for (i, &x) in values.iter().enumerate() {
+            let (px, py) = at(i, x);
+            content.push_str(&format!(r##"<circle cx="{px:.1}" cy="{py:.1}" r="2" fill="{dot}"/>"##));
+        }
+    }
+    crate::render::svg_document_with_theme(w as u32, h as u32, &content, theme)
+}
+
+/// Render a recurrence plot, time running left to right and bottom to
+/// top so the main diagonal climbs to the upper right.
+pub fn recurrence_to_svg(plot: &RecurrencePlot, cell: f64) -> String {
+    recurrence_to_svg_with_theme(plot, cell, &Theme::dark())
+}
+
+/// [`recurrence_to_svg`] in `theme`, recurrences in its palette's last stop
+/// if it has one.
+pub fn recurrence_to_svg_with_theme(plot: &RecurrencePlot, cell: f64, theme: &Theme) -> String {
+    draw_recurrence(plot, cell, &theme.color(1.0, "#ffd166"), &theme.background_css(), theme)
+}
+
+/// [`recurrence_to_svg`] with recurrences in the top color of `colormap`
+/// over its bottom.
+pub fn recurrence_to_svg_with_colormap(plot: &RecurrencePlot, cell: f64, colormap: &dyn Colormap) -> String {
+    draw_recurrence(plot, cell, &colormap.css(1.0), &colormap.css(0.0), &Theme::dark())
+}
+
+fn draw_recurrence(plot: &RecurrencePlot, cell: f64, recurrent: &str, apart: &str, theme: &Theme) -> String {
+    let n = plot.size;
+    let values: Vec<f64> = (0..n * n).map(|k| f64::from(u8::from(plot.is_recurrent(k % n, n - 1 - k / n)))).collect();
+    let color = |v| String::from(if v > 0.0 { recurrent } else { apart });
+    crate::render::raster_svg_with_theme(n, n, cell, &values, color, theme)
+}
+
+/// Cobweb diagram of the logistic map: the parabola y = rx(1 − x), the
+/// diagonal y = x, and the staircase iteration from `x0` bouncing between
+/// them for `steps` iterates. Where the staircase spirals in, the fixed
+/// point at the crossing is stable; where it boxes around, the orbit has
+/// found a cycle; where it never settles, it's chaos.
+pub fn cobweb_to_svg(r: f64, x0: f64, steps: usize) -> String {
+    cobweb_to_svg_with_theme(r, x0, steps, &Theme::dark())
+}
+
+/// [`cobweb_to_svg`] in `theme`; with a palette, the frame and diagonal
+/// take its low end, the parabola its middle and the staircase its last
+/// stop.
+pub fn cobweb_to_svg_with_theme(r: f64, x0: f64, steps: usize, theme: &Theme) -> String {
+    let (size, margin) = (600.0, 40.0);
+    let span = size - 2.0 * margin;
+    let at = |x: f64, y: f64| (margin + x.clamp(0.0, 1.0) * span, size - margin - y.clamp(0.0, 1.0) * span);
+    let (left, bottom) = at(0.0, 0.0);
+    let (right, top) = at(1.0, 1.0);
+    let mut content = format!(
+        r##"<rect width="{size}" height="{size}" fill="{}"/>
+<rect x="{left}" y="{top}" width="{span}" height="{span}" fill="none" stroke="{}" stroke-width="{thin}"/>
+<line x1="{left}" y1="{bottom}" x2="{right}" y2="{top}" stroke="{}" stroke-width="{thin}" stroke-dasharray="4 4"/>
+<polyline points=""##,
+        theme.background_css(),
+        theme.color(0.0, "#30363d"),
+        theme.color(0.25, "#8b949e"),
+        thin = theme.stroke_width(1.0)
+    );
+    for i in 0..=200 {
+        let x = i as f64 / 200.0;
+        let (px, py) = at(x, r * x * (1.0 - x));
+        content.push_str(&format!("{px:.1},{py:.1} "));
+    }
+    content.push_str(&format!(
+        r##"" fill="none" stroke="{}" stroke-width="{}"/>
+<polyline points=""##,
+        theme.color(0.6, "#7fdbca"),
+        theme.stroke_width(2.0)
+    ));
+    let (px, py) = at(x0, 0.0);
+    content.push_str(&format!("{px:.1},{py:.1} "));
+    let mut x = x0;
+    for _ in 0..steps {
+        let next = r * x * (1.0 - x);
+        let (px, py) = at(x, next);
+        let (qx, qy) = at(next, next);
+        content.push_str(&format!("{px:.1},{py:.1} {qx:.1},{qy:.1} "));
+        x = next;
+    }
+    content.push_str(&format!(
+        r##"" fill="none" stroke="{}" stroke-width="{}" opacity="0.8"/>
+"##,
+        theme.color(1.0, "#ffd166"),
+        theme.stroke_width(1.0)
+    ));
+    content.push_str(&format!(r##"<circle cx="{px:.1}" cy="{py:.1}" r="4" fill="{}"/>"##, theme.color(1.0, "#ff6b6b")));
+    crate::render::svg_document_with_theme(size as u32, size as u32, &content, theme)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::f64::consts::TAU;
 
     #[test]
     fn test_lorenz_attractor_length() {
@@ -179,7 +1970,7 @@ mod tests {
         // For r=3.9, system should be chaotic (no convergence)
         let values = logistic_map(3.9, 0.5, 200);
         let last_20 = &values[180..];
-        let unique: std::collections::HashSet<u64> = last_20.iter()
+        let unique: Set<u64> = last_20.iter()
             .map(|v| (v * 1e10) as u64)
             .collect();
         assert!(unique.len() > 10, "Should have many distinct values in chaos");
@@ -206,15 +1997,49 @@ mod tests {
     #[test]
     fn test_butterfly_effect_divergence() {
         let params = LorenzParams::default();
-        let (path1, path2) = butterfly_effect(&params, 3000, 1e-10);
+        let (path1, path2) = butterfly_effect(&params, 4000, 1e-10);
         // Initially very close
         let d_start = distance_3d(&path1[0], &path2[0]);
         assert!(d_start < 1e-9);
         // Eventually diverge significantly
-        let d_end = distance_3d(&path1[2999], &path2[2999]);
+        let d_end = distance_3d(&path1[3999], &path2[3999]);
         assert!(d_end > 1.0, "Paths should diverge: distance = {}", d_end);
     }
 
+    #[test]
+    fn test_butterfly_svg() {
+        let params = LorenzParams::default();
+        let (path1, path2) = butterfly_effect(&params, 5000, 1e-10);
+        let svg = butterfly_to_svg(&path1, &path2, params.dt, Plane::XZ);
+        assert_eq!(svg.matches("<polyline").count(), 3);
+        assert!(svg.contains("#4cc9f0") && svg.contains("#ff6b6b"));
+        // The gap grows from 1e-10 to the attractor's size
+        assert!(svg.contains(">-11</text>") && svg.contains(">2</text>"), "{svg}");
+        assert!(svg.contains("t = 0 … 50.0"));
+        assert!(!butterfly_to_svg(&path1[..1], &path2, params.dt, Plane::XZ).contains("<polyline"));
+    }
+
+    #[test]
+    fn test_bifurcation_svg() {
+        let data = bifurcation_diagram(2.5, 4.0, 200, 200, 50);
+        let svg = bifurcation_to_svg(&data, 200, 100);
+        assert!(svg.contains(r##"width="800""##));
+        assert!(svg.contains("#0a0a1a"));
+        // The period-1 branch at r = 2.5 sits at x = 0.6 and is fully saturated
+        assert!(svg.contains(&crate::render::magma(1.0)));
+        assert_eq!(bifurcation_to_svg(&[], 4, 4).matches("<rect").count(), 1 + 4);
+    }
+
+    #[test]
+    fn test_logistic_svg() {
+        let orbit = logistic_map(3.2, 0.5, 50);
+        let svg = logistic_to_svg(&orbit);
+        assert_eq!(svg.matches("<circle").count(), 50);
+        // The first value sits halfway up
+        assert!(svg.contains("40.0,200.0 "), "{}", svg);
+        assert!(!logistic_to_svg(&logistic_map(3.9, 0.5, 1000)).contains("<circle"));
+    }
+
     #[test]
     fn test_lorenz_svg() {
         let params = LorenzParams::default();
@@ -223,4 +2048,398 @@ mod tests {
         assert!(svg.contains("<svg"));
         assert!(svg.contains("polyline"));
     }
+
+    #[test]
+    fn test_validate() {
+        assert!(LorenzParams::default().validate().is_ok());
+        assert!(LorenzParams { dt: 0.5, ..Default::default() }.validate().is_err());
+        assert!(LorenzParams { sigma: f64::NAN, ..Default::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_lorenz_iter_matches_vec() {
+        let params = LorenzParams::default();
+        let initial = Point3D { x: 1.0, y: 1.0, z: 1.0 };
+        let streamed: Vec<Point3D> = lorenz_iter(&params, initial).take(500).collect();
+        assert_eq!(streamed, lorenz_attractor(&params, 500, initial));
+        assert_eq!(lorenz_attractor(&params, 0, initial), vec![initial]);
+    }
+
+    #[test]
+    fn test_lorenz_iter_resumes() {
+        let params = LorenzParams::default();
+        let initial = Point3D { x: 1.0, y: 1.0, z: 1.0 };
+        let mut stream: LorenzIter = lorenz_iter(&params, initial);
+        assert_eq!(stream.next(), Some(initial));
+        let batch: Vec<Point3D> = stream.by_ref().take(99).collect();
+        assert_eq!(batch, lorenz_attractor(&params, 100, initial)[1..]);
+        assert_eq!(stream.next(), lorenz_attractor(&params, 101, initial).last().copied());
+    }
+
+    #[test]
+    fn test_lorenz_trait_matches_iter() {
+        let params = LorenzParams::default();
+        let initial = Point3D { x: 1.0, y: 1.0, z: 1.0 };
+        let generic: Vec<Point3D> = trajectory_iter(&params, initial, Integrator::Rk4).take(300).collect();
+        assert_eq!(generic, lorenz_attractor(&params, 300, initial));
+    }
+
+    #[test]
+    fn test_attractors_stay_chaotic() {
+        for name in Attractor::NAMES {
+            let attractor = Attractor::from_name(name).unwrap();
+            assert!(attractor.validate().is_ok());
+            assert_eq!(attractor.name(), name);
+            let points = trajectory(&attractor, 20_000, Integrator::Rk4);
+            let tail = &points[10_000..];
+            assert!(tail.iter().all(|p| p.x.is_finite() && p.y.is_finite() && p.z.is_finite()), "{} escaped", name);
+            // Neither settled onto a fixed point nor flung off to infinity
+            let spread = tail.iter().map(|p| distance_3d(p, &tail[0])).fold(0.0, f64::max);
+            assert!(spread > 0.5 && spread < 200.0, "{}: spread {}", name, spread);
+            let last = &tail[tail.len() - 100..];
+            assert!(last.windows(2).any(|w| distance_3d(&w[0], &w[1]) > 1e-4), "{} came to rest", name);
+        }
+        assert!(Attractor::from_name("logistic").is_none());
+    }
+
+    #[test]
+    fn test_largest_lyapunov() {
+        let lorenz = largest_lyapunov(&LorenzParams::default(), 60_000, Integrator::Rk4);
+        assert!((lorenz - 0.91).abs() < 0.1, "lorenz: {lorenz}");
+        let rossler = largest_lyapunov(&Attractor::from_name("rossler").unwrap(), 60_000, Integrator::Rk4);
+        assert!((rossler - 0.071).abs() < 0.02, "rossler: {rossler}");
+        let chen = largest_lyapunov(&Attractor::from_name("chen").unwrap(), 60_000, Integrator::Rk4);
+        assert!((chen - 2.0).abs() < 0.3, "chen: {chen}");
+        // The unit circle neither stretches nor shrinks
+        struct Circle;
+        impl Attractor3D for Circle {
+            fn name(&self) -> &'static str {
+                "circle"
+            }
+            fn velocity(&self, p: &Point3D) -> Point3D {
+                Point3D { x: -p.y, y: p.x, z: 0.0 }
+            }
+            fn dt(&self) -> f64 {
+                0.01
+            }
+        }
+        assert!(largest_lyapunov(&Circle, 20_000, Integrator::Rk4).abs() < 1e-3);
+        assert_eq!(largest_lyapunov(&Circle, 0, Integrator::Rk4), 0.0);
+    }
+
+    #[test]
+    fn test_henon_and_ikeda_orbits() {
+        for name in DiscreteMap::NAMES {
+            let map = DiscreteMap::from_name(name).unwrap();
+            assert!(map.validate().is_ok());
+            let orbit = map_orbit(&map, 5000);
+            assert_eq!(orbit.len(), 5000, "{name} escaped");
+            // Chaotic: it never settles into a short cycle
+            let last = orbit[orbit.len() - 1];
+            assert!(orbit[orbit.len() - 50..orbit.len() - 1].iter().all(|p| (p.x - last.x).hypot(p.y - last.y) > 1e-9));
+        }
+        // Hénon's attractor lies within |x| < 1.3, |y| < 0.4
+        let henon = map_orbit(&DiscreteMap::from_name("henon").unwrap(), 5000);
+        assert!(henon.iter().all(|p| p.x.abs() < 1.3 && p.y.abs() < 0.4));
+        assert_eq!(DiscreteMap::from_name("henon").unwrap().step((0.0, 0.0)), (1.0, 0.0));
+        assert!(DiscreteMap::Henon { a: 3.0, b: 0.3 }.validate().is_err());
+        assert!(DiscreteMap::Ikeda { u: 1.5 }.validate().is_err());
+        assert!(DiscreteMap::from_name("logistic").is_none());
+        let svg = map_orbit_to_svg(&map_orbit(&DiscreteMap::from_name("ikeda").unwrap(), 100));
+        assert_eq!(svg.matches("<circle").count(), 100);
+    }
+
+    #[test]
+    fn test_basins() {
+        let henon = DiscreteMap::from_name("henon").unwrap();
+        let grid = basin_grid(&henon, &henon.region(), 60, 60, 200);
+        assert_eq!(grid.cells.len(), 3600);
+        // Near the attractor starting points are captured; far out they escape
+        let at = |x: f64, y: f64| {
+            let region = henon.region();
+            let col = ((x - region.re_min) / (region.re_max - region.re_min) * 60.0) as usize;
+            let row = ((region.im_max - y) / (region.im_max - region.im_min) * 60.0) as usize;
+            grid.cells[row * 60 + col]
+        };
+        assert!(matches!(at(0.5, 0.1), Basin::Captured(_)));
+        assert!(matches!(at(2.4, 2.4), Basin::Escaped(_)));
+        let fraction = grid.captured_fraction();
+        assert!(fraction > 0.05 && fraction < 0.6, "henon basin: {fraction}");
+        // At u = 0.9 nothing escapes the Ikeda map, but a stable fixed point
+        // near (2.97, 4.15) competes with the strange attractor
+        let ikeda = DiscreteMap::from_name("ikeda").unwrap();
+        let grid = basin_grid(&ikeda, &ikeda.region(), 40, 40, 500);
+        assert!(grid.cells.iter().all(|c| matches!(c, Basin::Captured(_) | Basin::Settled(_))));
+        assert!(grid.captured_fraction() > 0.5, "ikeda basin: {}", grid.captured_fraction());
+        let fixed = map_orbit_iter(&ikeda, (2.3, -2.3)).nth(5000).unwrap();
+        let next = ikeda.step(fixed);
+        assert!((next.0 - fixed.0).hypot(next.1 - fixed.1) < 1e-9);
+        assert!(basin_to_svg(&grid, 2.0).contains("<rect"));
+    }
+
+    #[test]
+    fn test_figure_eight_closes() {
+        let start = ThreeBody::FigureEight.system();
+        assert!(start.validate().is_ok());
+        let mut system = start.clone();
+        let energy = system.energy();
+        system.advance((6.32591398 / system.dt).round() as usize);
+        for (a, b) in system.bodies.iter().zip(&start.bodies) {
+            assert!((a.position.x - b.position.x).hypot(a.position.y - b.position.y) < 0.01, "{a:?} vs {b:?}");
+        }
+        assert!((system.energy() - energy).abs() < 1e-6);
+        let p = system.momentum();
+        assert!(p.x.abs() < 1e-9 && p.y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lagrange_stays_equilateral() {
+        let mut system = ThreeBody::Lagrange.system();
+        system.advance(4000);
+        let side = |i: usize, j: usize| {
+            let (a, b) = (system.bodies[i].position, system.bodies[j].position);
+            (a.x - b.x).hypot(a.y - b.y)
+        };
+        for (i, j) in [(0, 1), (1, 2), (2, 0)] {
+            assert!((side(i, j) - 3f64.sqrt()).abs() < 1e-6, "side {}", side(i, j));
+        }
+        // Four time units are about half a turn
+        let angle = system.bodies[0].position.y.atan2(system.bodies[0].position.x);
+        assert!((angle.abs() - core::f64::consts::TAU * 4.0 / ThreeBody::Lagrange.duration()).abs() < 0.01, "{angle}");
+    }
+
+    #[test]
+    fn test_pythagorean_close_encounters() {
+        let system = ThreeBody::Pythagorean.system();
+        let energy = system.energy();
+        let steps = 30_000;
+        let trails = nbody_trails(&system, steps, 1000);
+        assert_eq!(trails.len(), 3);
+        assert_eq!(trails[0].len(), steps / 1000 + 1);
+        assert_eq!(trails[0][0], system.bodies[0].position);
+        // Substeps carry the slingshots through with energy and momentum intact
+        let mut end = system.clone();
+        end.advance(steps);
+        assert!(((end.energy() - energy) / energy).abs() < 1e-4, "{} vs {energy}", end.energy());
+        let p = end.momentum();
+        assert!(p.x.abs() < 1e-9 && p.y.abs() < 1e-9);
+        let svg = nbody_to_svg(&trails, &[3.0, 4.0, 5.0]);
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert!(svg.contains("<polyline"));
+    }
+
+    #[test]
+    fn test_nbody_validation() {
+        for name in ThreeBody::NAMES {
+            assert!(ThreeBody::from_name(name).unwrap().system().validate().is_ok());
+        }
+        let mut system = ThreeBody::FigureEight.system();
+        system.bodies[0].mass = 0.0;
+        assert!(system.validate().is_err());
+        assert!(NBody { bodies: vec![], dt: 1.0, softening: 0.0 }.validate().is_err());
+        assert!(ThreeBody::from_name("lorenz").is_none());
+    }
+
+    #[test]
+    fn test_cobweb() {
+        let svg = cobweb_to_svg(2.8, 0.2, 50);
+        let staircase = svg.split("<polyline points=\"").nth(2).unwrap();
+        let staircase = &staircase[..staircase.find('"').unwrap()];
+        // The start on the axis, then a vertical and a horizontal corner per iterate
+        assert_eq!(staircase.split_whitespace().count(), 1 + 2 * 50);
+        // r = 2.8 spirals into the fixed point 1 − 1/r, on the diagonal
+        let last = staircase.split_whitespace().last().unwrap();
+        let (x, y) = last.split_once(',').unwrap();
+        let (x, y): (f64, f64) = (x.parse().unwrap(), y.parse().unwrap());
+        assert!((x - (40.0 + (1.0 - 1.0 / 2.8) * 520.0)).abs() < 0.2);
+        assert!((x + y - 600.0).abs() < 0.2);
+        assert_eq!(cobweb_to_svg(3.9, 0.2, 0).split("<polyline").count(), 3);
+    }
+
+    #[test]
+    fn test_recurrence_plot() {
+        let embedded = delay_embedding(&[1.0, 2.0, 3.0, 4.0, 5.0], 2, 2);
+        assert_eq!(embedded, vec![vec![1.0, 3.0], vec![2.0, 4.0], vec![3.0, 5.0]]);
+        assert!(delay_embedding(&[1.0, 2.0], 3, 1).is_empty());
+        assert_eq!(embedded_distance(&[0.0, 3.0], &[4.0, 0.0]), 5.0);
+
+        // A period-4 cycle recurs exactly every fourth step
+        let periodic = delay_embedding(&logistic_map(3.5, 0.5, 1400)[1000..], 2, 1);
+        let plot = recurrence_plot(&periodic, 1e-6, |a, b| embedded_distance(a, b)).unwrap();
+        assert!(plot.is_recurrent(10, 14) && !plot.is_recurrent(10, 12));
+        assert!((plot.recurrence_rate() - 0.25).abs() < 0.01);
+        assert!(plot.determinism(2) > 0.99);
+
+        // Chaos keeps long diagonals; scrambling the same values destroys them
+        let chaotic = logistic_map(3.9, 0.3, 1500)[500..].to_vec();
+        let mut shuffled = chaotic.clone();
+        let mut state = 12345u64;
+        for i in (1..shuffled.len()).rev() {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            shuffled.swap(i, (state >> 33) as usize % (i + 1));
+        }
+        let det = |series: &[f64]| {
+            let states = delay_embedding(series, 3, 1);
+            recurrence_plot(&states, 0.1, |a, b| embedded_distance(a, b)).unwrap().determinism(2)
+        };
+        let (ordered, scrambled) = (det(&chaotic), det(&shuffled));
+        assert!(ordered > 0.8 && scrambled < 0.5, "{ordered} vs {scrambled}");
+
+        // 3D trajectories work directly, and the plot is symmetric
+        let lorenz: Vec<Point3D> = trajectory(&LorenzParams::default(), 4000, Integrator::Rk4).into_iter().step_by(10).collect();
+        let plot = recurrence_plot(&lorenz, 5.0, distance_3d).unwrap();
+        assert!((0..plot.size).all(|i| plot.is_recurrent(i, i) && plot.is_recurrent(i, (i * 7) % plot.size) == plot.is_recurrent((i * 7) % plot.size, i)));
+        assert!(recurrence_to_svg(&plot, 1.0).contains("#ffd166"));
+
+        assert!(recurrence_plot(&lorenz, 0.0, distance_3d).is_err());
+        assert!(recurrence_plot::<Point3D, _>(&[], 1.0, distance_3d).is_err());
+    }
+
+    #[test]
+    fn test_poincare_section() {
+        // A helix rising through z = 0.5 crosses y = 0 upward once a turn, at x = 1
+        let helix: Vec<Point3D> = (0..1000)
+            .map(|k| {
+                let t = k as f64 * 0.01;
+                Point3D { x: t.cos(), y: t.sin(), z: t / 10.0 }
+            })
+            .collect();
+        let crossings = poincare_section(&helix, &SectionPlane::y(0.0));
+        assert_eq!(crossings.len(), 1);
+        assert!((crossings[0].x - 1.0).abs() < 1e-4 && crossings[0].y.abs() < 1e-12);
+        assert!((crossings[0].z - core::f64::consts::TAU / 10.0).abs() < 1e-3);
+        // Flipping the normal counts the downward crossings instead, at t = π and 3π
+        let down = SectionPlane { normal: Point3D { x: 0.0, y: -1.0, z: 0.0 }, offset: 0.0 };
+        let crossings = poincare_section(&helix, &down);
+        assert_eq!(crossings.len(), 2);
+        assert!(crossings.iter().all(|p| (p.x + 1.0).abs() < 1e-4));
+        // Lorenz through z = ρ − 1: every point on the plane
+        let plane = SectionPlane::parse("z=27").unwrap();
+        let section = poincare_section(&trajectory(&LorenzParams::default(), 20_000, Integrator::Rk4), &plane);
+        assert!(section.len() > 50, "{}", section.len());
+        assert!(section.iter().all(|p| (p.z - 27.0).abs() < 1e-9));
+        assert_eq!(plane.view(), Plane::XY);
+        assert_eq!(SectionPlane::x(0.0).view(), Plane::YZ);
+        assert_eq!(poincare_to_svg(&section, &plane).matches("<circle").count(), section.len());
+        for bad in ["w=1", "z", "z=abc", "x=inf"] {
+            assert!(SectionPlane::parse(bad).is_none(), "{bad}");
+        }
+        assert!(poincare_section(&helix[..1], &plane).is_empty());
+    }
+
+    /// Unit circle: x' = −y, y' = x, starting at (1, 0).
+    fn rotate(p: [f64; 3]) -> [f64; 3] {
+        [-p[1], p[0], 0.0]
+    }
+
+    #[test]
+    fn test_integrator_orders() {
+        // Once around the circle in 64 steps: error shrinks by order as expected
+        let dt = TAU / 64.0;
+        let error = |integrator: Integrator| {
+            let p = (0..64).fold([1.0, 0.0, 0.0], |p, _| integrator.step(rotate, p, dt));
+            (p[0] - 1.0).hypot(p[1])
+        };
+        let (euler, rk4) = (error(Integrator::Euler), error(Integrator::Rk4));
+        // Euler spirals outward by (1 + dt²)^(n/2)
+        assert!((euler - ((1.0 + dt * dt).powf(32.0) - 1.0)).abs() < 0.05, "{}", euler);
+        assert!(rk4 < 1e-5, "{}", rk4);
+        let adaptive = Integrator::Rk45 { tolerance: 1e-10 };
+        let p = (0..4).fold([1.0, 0.0, 0.0], |p, _| adaptive.step(rotate, p, TAU / 4.0));
+        assert!((p[0] - 1.0).hypot(p[1]) < 1e-8, "{:?}", p);
+    }
+
+    #[test]
+    fn test_rk4_tracks_lorenz() {
+        // Against a tight RK45 reference over one time unit, RK4 at dt = 0.01 is far closer than Euler
+        let params = LorenzParams::default();
+        let initial = Point3D { x: 1.0, y: 1.0, z: 1.0 };
+        let end = |integrator| trajectory_iter(&params, initial, integrator).nth(100).unwrap();
+        let reference = end(Integrator::Rk45 { tolerance: 1e-12 });
+        let (euler, rk4) = (distance_3d(&end(Integrator::Euler), &reference), distance_3d(&end(Integrator::Rk4), &reference));
+        assert!(rk4 < 1e-3, "{}", rk4);
+        assert!(euler > 1000.0 * rk4, "{} vs {}", euler, rk4);
+        assert_eq!(lorenz_attractor(&params, 101, initial)[100], end(Integrator::Rk4));
+    }
+
+    #[test]
+    fn test_attractor_validation() {
+        assert!(RosslerParams { c: -1.0, ..Default::default() }.validate().is_err());
+        assert!(ThomasParams { b: 2.0, ..Default::default() }.validate().is_err());
+        assert!(ChenParams { dt: 0.1, ..Default::default() }.validate().is_err());
+        assert!(Attractor::Halvorsen(HalvorsenParams { a: f64::NAN, ..Default::default() }).validate().is_err());
+    }
+
+    #[test]
+    fn test_trajectory_svg_uniform_scale() {
+        // A diagonal from (0, 0) to (10, 5) in the XY plane fills the width, centered vertically
+        let points = [Point3D { x: 0.0, y: 0.0, z: 7.0 }, Point3D { x: 10.0, y: 5.0, z: 7.0 }];
+        let svg = trajectory_to_svg(&points, Plane::XY);
+        assert!(svg.contains("40.0,480.0 760.0,120.0"), "{}", svg);
+        let orbit = Orbit { attractor: Attractor::from_name("rossler").unwrap(), steps: 100 };
+        assert_eq!(orbit.generate().len(), 100);
+    }
+
+    #[test]
+    fn test_colored_trajectory() {
+        // Slow along x, then twice as fast along y
+        let mut points: Vec<Point3D> = (0..=10).map(|i| Point3D { x: i as f64, y: 0.0, z: 0.0 }).collect();
+        points.extend((1..=5).map(|i| Point3D { x: 10.0, y: 2.0 * i as f64, z: 0.0 }));
+        let svg = colored_trajectory_to_svg(&points, Plane::XY, TrajectoryColor::Speed);
+        let strokes: Vec<&str> = svg.split("stroke=\"").skip(1).map(|s| &s[..s.find('"').unwrap()]).collect();
+        assert_eq!(strokes, vec![crate::render::viridis(0.0), crate::render::viridis(1.0)]);
+        // The two pieces meet at the corner
+        assert_eq!(svg.matches("660.0,560.0").count(), 2);
+
+        let lorenz = lorenz_attractor(&LorenzParams::default(), 2000, Point3D { x: 1.0, y: 1.0, z: 1.0 });
+        let svg = colored_trajectory_to_svg(&lorenz, Projection::Isometric, TrajectoryColor::Time);
+        assert_eq!(svg.matches("<polyline").count(), 64);
+        assert!(svg.contains(&crate::render::magma(0.25)) && svg.contains(&crate::render::magma(1.0)));
+        assert!(colored_trajectory_to_svg(&lorenz[..1], Plane::XZ, TrajectoryColor::Speed).contains("<rect"));
+        assert_eq!(TrajectoryColor::from_name("speed"), Some(TrajectoryColor::Speed));
+        assert_eq!(TrajectoryColor::from_name("hue"), None);
+    }
+
+    #[test]
+    fn test_views() {
+        let points = [Point3D { x: 1.0, y: 2.0, z: 3.0 }, Point3D { x: -1.0, y: 0.0, z: 5.0 }];
+        assert_eq!(View::from(Plane::YZ).project(&points), vec![(2.0, 3.0), (0.0, 5.0)]);
+        // Looking along −y from the front is the XZ plane, centered on the mean
+        let front = View::from_name("orthographic", -90.0, 0.0).unwrap().project(&points);
+        for ((u, v), (x, z)) in front.iter().zip([(1.0, -1.0), (-1.0, 1.0)]) {
+            assert!((u - x).abs() < 1e-12 && (v - z).abs() < 1e-12, "{u},{v}");
+        }
+        // Straight down is the XY plane
+        let top = View::Camera(Projection::Orthographic { azimuth: -90.0, elevation: 90.0 }).project(&points);
+        assert!((top[0].0 - 1.0).abs() < 1e-12 && (top[0].1 - 1.0).abs() < 1e-12, "{:?}", top[0]);
+        for name in View::NAMES {
+            assert!(View::from_name(name, 30.0, 15.0).is_some());
+        }
+        assert!(View::from_name("zx", 0.0, 0.0).is_none());
+        // Any view renders, and a rotated butterfly differs from the flat one
+        let lorenz = lorenz_attractor(&LorenzParams::default(), 500, Point3D { x: 1.0, y: 1.0, z: 1.0 });
+        let iso = trajectory_to_svg(&lorenz, Projection::Isometric);
+        assert!(iso.contains("<polyline") && iso != lorenz_to_svg(&lorenz));
+        assert_eq!(cloud_to_svg(&lorenz, View::from_name("isometric", 0.0, 0.0).unwrap()).matches("<circle").count(), 500);
+    }
+
+    #[test]
+    fn test_cloud_svg_colors_by_time() {
+        let points = lorenz_attractor(&LorenzParams::default(), 500, Point3D { x: 1.0, y: 1.0, z: 1.0 });
+        let svg = cloud_to_svg(&points, Plane::XZ);
+        assert_eq!(svg.matches("<circle").count(), 500);
+        // First and last dots sit at opposite ends of the colormap
+        let fills: Vec<&str> = svg.split("fill=\"").skip(2).map(|s| &s[..s.find('"').unwrap()]).collect();
+        assert_eq!(fills.first().copied(), Some(crate::render::magma(0.25).as_str()));
+        assert_eq!(fills.last().copied(), Some(crate::render::magma(1.0).as_str()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let json = r#"{"sigma":10.0,"rho":99.96,"beta":2.6666666666666665,"dt":0.01}"#;
+        let params: LorenzParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.rho, 99.96);
+        assert_eq!(serde_json::to_string(&params).unwrap(), json);
+    }
 }
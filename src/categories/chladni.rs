@@ -0,0 +1,337 @@
+//! Chladni figures — sand on a vibrating plate.
+//!
+//! Ernst Chladni (1787) bowed metal plates sprinkled with sand. The sand
+//! bounces off the vibrating regions and settles on the nodal lines, where a
+//! standing wave never moves. For a square plate a good approximation of the
+//! modes is
+//!   u(x, y) = cos(nπx) cos(mπy) − cos(mπx) cos(nπy),   x, y ∈ [0, 1]
+//! and for a circular plate clamped at the rim
+//!   u(r, θ) = Jₙ(k r) cos(nθ),   Jₙ(k) = 0
+//! where k is the m-th zero of the Bessel function, giving n nodal diameters
+//! and m − 1 nodal circles. Mixing modes at the same pitch yields the
+//! intricate figures seen in real plates and in guitar tops.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{MathaturaError, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::parallel::map_range;
+use crate::render::theme::Theme;
+use super::fractals::Point;
+
+/// Plate shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Plate {
+    Square,
+    Circular,
+}
+
+/// One standing-wave mode. On a square plate `m` and `n` count half-waves
+/// along each axis; on a circular plate `n` counts nodal diameters and `m`
+/// the zero of Jₙ (m − 1 interior nodal circles).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mode {
+    pub m: u32,
+    pub n: u32,
+    pub weight: f64,
+}
+
+/// A superposition of modes on a plate.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chladni {
+    pub plate: Plate,
+    pub modes: Vec<Mode>,
+}
+
+impl Default for Chladni {
+    fn default() -> Self {
+        Chladni::square(3, 5)
+    }
+}
+
+/// Bessel function of the first kind Jₙ(x) from Bessel's integral,
+/// Jₙ(x) = (1/π) ∫₀^π cos(nτ − x sin τ) dτ, by Simpson's rule.
+pub fn bessel_j(n: u32, x: f64) -> f64 {
+    let steps = 200;
+    let h = PI / steps as f64;
+    let f = |t: f64| (n as f64 * t - x * t.sin()).cos();
+    let mut sum = f(0.0) + f(PI);
+    for i in 1..steps {
+        sum += f(i as f64 * h) * if i % 2 == 1 { 4.0 } else { 2.0 };
+    }
+    sum * h / 3.0 / PI
+}
+
+/// The m-th positive zero of Jₙ (m ≥ 1), by scanning and bisection.
+pub fn bessel_zero(n: u32, m: u32) -> f64 {
+    let mut found = 0;
+    let mut x = 0.1;
+    let step = 0.05;
+    let mut prev = bessel_j(n, x);
+    loop {
+        let next = bessel_j(n, x + step);
+        if prev * next < 0.0 {
+            found += 1;
+            if found >= m.max(1) {
+                let (mut lo, mut hi) = (x, x + step);
+                for _ in 0..50 {
+                    let mid = 0.5 * (lo + hi);
+                    if bessel_j(n, lo) * bessel_j(n, mid) <= 0.0 {
+                        hi = mid;
+                    } else {
+                        lo = mid;
+                    }
+                }
+                return 0.5 * (lo + hi);
+            }
+        }
+        prev = next;
+        x += step;
+    }
+}
+
+impl Chladni {
+    /// A single square-plate mode.
+    pub fn square(m: u32, n: u32) -> Self {
+        Chladni { plate: Plate::Square, modes: vec![Mode { m, n, weight: 1.0 }] }
+    }
+
+    /// A single circular-plate mode with `n` nodal diameters and `m` radial zeros.
+    pub fn circular(n: u32, m: u32) -> Self {
+        Chladni { plate: Plate::Circular, modes: vec![Mode { m, n, weight: 1.0 }] }
+    }
+
+    /// Reject modes that leave the plate flat, which have no nodal lines to draw.
+    pub fn validate(&self) -> Result<()> {
+        for md in &self.modes {
+            match self.plate {
+                // cos(nπx)cos(mπy) − cos(mπx)cos(nπy) vanishes everywhere when m = n
+                Plate::Square if md.m == md.n => {
+                    return Err(MathaturaError::InvalidParameter {
+                        name: "n",
+                        value: md.n as f64,
+                        expected: "a mode number different from m on the square plate",
+                    });
+                }
+                Plate::Circular if md.m == 0 => {
+                    return Err(MathaturaError::InvalidParameter {
+                        name: "m",
+                        value: 0.0,
+                        expected: "a Bessel zero index of at least 1 on the circular plate",
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Add another mode to the superposition.
+    pub fn with_mode(mut self, m: u32, n: u32, weight: f64) -> Self {
+        self.modes.push(Mode { m, n, weight });
+        self
+    }
+
+    /// Displacement at (x, y) in [0, 1]²; the circular plate is inscribed.
+    pub fn displacement(&self, x: f64, y: f64) -> f64 {
+        match self.plate {
+            Plate::Square => self
+                .modes
+                .iter()
+                .map(|md| {
+                    let (m, n) = (md.m as f64 * PI, md.n as f64 * PI);
+                    md.weight * ((n * x).cos() * (m * y).cos() - (m * x).cos() * (n * y).cos())
+                })
+                .sum(),
+            Plate::Circular => {
+                let (dx, dy) = (2.0 * x - 1.0, 2.0 * y - 1.0);
+                let (r, theta) = (dx.hypot(dy), dy.atan2(dx));
+                self.modes
+                    .iter()
+                    .map(|md| md.weight * bessel_j(md.n, bessel_zero(md.n, md.m) * r) * (md.n as f64 * theta).cos())
+                    .sum()
+            }
+        }
+    }
+
+    /// Sample the plate on a `resolution × resolution` grid over [0, 1]².
+    pub fn sample(&self, resolution: usize) -> Vec<f64> {
+        let n = resolution.max(2);
+        let step = 1.0 / (n - 1) as f64;
+        match self.plate {
+            Plate::Square => map_range(n * n, |k| self.displacement((k % n) as f64 * step, (k / n) as f64 * step)),
+            Plate::Circular => {
+                // Bessel zeros are costly; compute them once per mode
+                let zeros: Vec<f64> = self.modes.iter().map(|md| bessel_zero(md.n, md.m)).collect();
+                map_range(n * n, |k| {
+                    let (dx, dy) = (2.0 * (k % n) as f64 * step - 1.0, 2.0 * (k / n) as f64 * step - 1.0);
+                    let (r, theta) = (dx.hypot(dy), dy.atan2(dx));
+                    self.modes
+                        .iter()
+                        .zip(&zeros)
+                        .map(|(md, &z)| md.weight * bessel_j(md.n, z * r) * (md.n as f64 * theta).cos())
+                        .sum()
+                })
+            }
+        }
+    }
+
+    /// Nodal lines — where the sand collects — in [0, 1]² coordinates.
+    pub fn nodal_lines(&self, resolution: usize) -> Vec<Vec<Point>> {
+        let n = resolution.max(2);
+        let values = self.sample(n);
+        let scale = 1.0 / (n - 1) as f64;
+        let lines = crate::contour::marching_squares(&values, n, n, 0.0)
+            .into_iter()
+            .map(|line| line.into_iter().map(|p| Point { x: p.x * scale, y: p.y * scale }).collect());
+        match self.plate {
+            Plate::Square => lines.collect(),
+            // Split lines where they leave the plate
+            Plate::Circular => lines
+                .flat_map(|line: Vec<Point>| {
+                    line.split(|p| (2.0 * p.x - 1.0).hypot(2.0 * p.y - 1.0) > 0.995)
+                        .filter(|part| part.len() >= 2)
+                        .map(|part| part.to_vec())
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Generator for Chladni {
+    fn generate(&self) -> Geometry {
+        Geometry::Polylines(self.nodal_lines(200))
+    }
+}
+
+/// Render the nodal lines as sand on a dark plate.
+pub fn to_svg(pattern: &Chladni, lines: &[Vec<Point>]) -> String {
+    to_svg_with_theme(pattern, lines, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, the plate takes its first stop
+/// and the sand its last.
+pub fn to_svg_with_theme(pattern: &Chladni, lines: &[Vec<Point>], theme: &Theme) -> String {
+    let size = 800.0;
+    let margin = 40.0;
+    let (fill, edge) = (theme.color(0.0, "#ffffff"), theme.color(0.0, "#555"));
+    let plate = match pattern.plate {
+        Plate::Square => format!(
+            r##"<rect x="{margin}" y="{margin}" width="{w}" height="{w}" fill="{fill}" fill-opacity="0.07" stroke="{edge}"/>"##,
+            w = size - 2.0 * margin
+        ),
+        Plate::Circular => format!(
+            r##"<circle cx="{c}" cy="{c}" r="{r}" fill="{fill}" fill-opacity="0.07" stroke="{edge}"/>"##,
+            c = size / 2.0,
+            r = size / 2.0 - margin
+        ),
+    };
+    let mut d = String::new();
+    for line in lines.iter().filter(|l| l.len() >= 2) {
+        for (k, p) in line.iter().enumerate() {
+            let (x, y) = (margin + p.x * (size - 2.0 * margin), margin + p.y * (size - 2.0 * margin));
+            d.push_str(&format!("{}{:.1},{:.1}", if k == 0 { "M" } else { "L" }, x, y));
+        }
+    }
+    crate::render::svg_document_with_theme(
+        size as u32,
+        size as u32,
+        &format!(
+            r##"{plate}
+<path d="{d}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"/>"##,
+            theme.color(1.0, "#e8d5a3"),
+            theme.stroke_width(3.0)
+        ),
+        theme,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bessel_values() {
+        assert!((bessel_j(0, 0.0) - 1.0).abs() < 1e-10);
+        assert!(bessel_j(1, 0.0).abs() < 1e-10);
+        assert!((bessel_j(0, 1.0) - 0.765_197_686_6).abs() < 1e-8);
+        assert!((bessel_j(2, 3.0) - 0.486_091_260_6).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_bessel_zeros() {
+        assert!((bessel_zero(0, 1) - 2.404_825_557_7).abs() < 1e-6);
+        assert!((bessel_zero(0, 2) - 5.520_078_110_3).abs() < 1e-6);
+        assert!((bessel_zero(1, 1) - 3.831_705_970_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Chladni::default().validate().is_ok());
+        assert!(Chladni::square(0, 0).validate().is_err());
+        assert!(Chladni::square(3, 5).with_mode(4, 4, 0.5).validate().is_err());
+        assert!(Chladni::circular(0, 1).validate().is_ok());
+        assert!(Chladni::circular(2, 0).validate().is_err());
+    }
+
+    #[test]
+    fn test_square_diagonal_is_nodal() {
+        let plate = Chladni::square(2, 5);
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            assert!(plate.displacement(t, t).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_square_antisymmetry() {
+        let plate = Chladni::square(1, 4).with_mode(3, 2, 0.5);
+        let (x, y) = (0.23, 0.71);
+        assert!((plate.displacement(x, y) + plate.displacement(y, x)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_superposition_linear() {
+        let a = Chladni::square(1, 3);
+        let b = Chladni::square(2, 5);
+        let both = Chladni::square(1, 3).with_mode(2, 5, 1.0);
+        let (x, y) = (0.4, 0.15);
+        assert!((both.displacement(x, y) - a.displacement(x, y) - b.displacement(x, y)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_circular_rim_is_nodal() {
+        let plate = Chladni::circular(2, 3);
+        for i in 0..12 {
+            let a = 2.0 * PI * i as f64 / 12.0;
+            let (x, y) = (0.5 + 0.5 * a.cos(), 0.5 + 0.5 * a.sin());
+            assert!(plate.displacement(x, y).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_circular_nodal_circles() {
+        // n = 0, m = 3: two interior nodal circles, at r = j₀₁/j₀₃ and j₀₂/j₀₃
+        let plate = Chladni::circular(0, 3);
+        let lines = plate.nodal_lines(121);
+        assert_eq!(lines.len(), 2);
+        let mut radii: Vec<f64> = lines.iter().map(|l| (2.0 * l[0].x - 1.0).hypot(2.0 * l[0].y - 1.0)).collect();
+        radii.sort_by(f64::total_cmp);
+        let j3 = bessel_zero(0, 3);
+        assert!((radii[0] - bessel_zero(0, 1) / j3).abs() < 0.02);
+        assert!((radii[1] - bessel_zero(0, 2) / j3).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let plate = Chladni::square(3, 7);
+        let svg = to_svg(&plate, &plate.nodal_lines(80));
+        assert!(svg.contains("<path"));
+        assert!(svg.contains("#e8d5a3"));
+    }
+}
@@ -0,0 +1,600 @@
+//! Space colonization — leaf veins and tree crowns.
+//!
+//! Runions et al. (2005, 2007): a region is scattered with attraction
+//! points — auxin sources in a leaf blade, free space in a crown. Each point
+//! pulls on vein nodes within its influence radius; every pulled node grows
+//! one segment toward the mean direction of its attractors, and attractors a
+//! vein reaches are removed. In *open* venation an attractor pulls only its
+//! nearest node, which grows trees. In *closed* venation it pulls every node
+//! in its relative neighborhood and survives until all of them arrive, so
+//! veins converge on the same point and close into the loops of a leaf.
+//!
+//! Vein widths follow the pipe model: a parent carries all of its children,
+//! w_parentᵞ = Σ w_childᵞ, with γ = 2 for da Vinci's rule.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::{self, Theme};
+use super::fractals::{Point, SimpleRng};
+
+/// Side of the square world the domains live in, in output pixels.
+pub const SIZE: f64 = 800.0;
+
+/// Pipe-model exponent used for rendering (da Vinci's rule).
+pub const PIPE_EXPONENT: f64 = 2.0;
+
+/// Largest attractor count [`ColonizationParams::validate`] accepts.
+pub const MAX_ATTRACTORS: usize = 20_000;
+
+/// Largest iteration count [`ColonizationParams::validate`] accepts.
+pub const MAX_ITERATIONS: usize = 10_000;
+
+/// How attractors choose the nodes they pull on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Venation {
+    /// Only the nearest node; branches never rejoin
+    Open,
+    /// Every node in the relative neighborhood; converging veins form loops
+    Closed,
+}
+
+/// The region attractors are scattered in, and where growth starts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Domain {
+    /// An ovate blade with its petiole at the bottom
+    Leaf,
+    /// An elliptical crown above a trunk rooted at the ground
+    Crown,
+}
+
+const LEAF_BASE: f64 = 740.0;
+const LEAF_TIP: f64 = 60.0;
+
+impl Domain {
+    /// Half-width of the leaf blade at fraction `t` of the way from base to tip.
+    fn leaf_half_width(t: f64) -> f64 {
+        if (0.0..=1.0).contains(&t) {
+            250.0 * (PI * t).sin().powf(0.9) * (1.2 - 0.6 * t)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        match self {
+            Domain::Leaf => {
+                let t = (LEAF_BASE - p.y) / (LEAF_BASE - LEAF_TIP);
+                (p.x - SIZE / 2.0).abs() < Self::leaf_half_width(t)
+            }
+            Domain::Crown => ((p.x - 400.0) / 320.0).powi(2) + ((p.y - 300.0) / 230.0).powi(2) < 1.0,
+        }
+    }
+
+    /// Where the first node sits: the end of the petiole, or the foot of the trunk.
+    pub fn root(&self) -> Point {
+        match self {
+            Domain::Leaf => Point { x: SIZE / 2.0, y: 790.0 },
+            Domain::Crown => Point { x: SIZE / 2.0, y: 780.0 },
+        }
+    }
+
+    /// Boundary of the domain as a closed polygon of `n` points.
+    pub fn outline(&self, n: usize) -> Vec<Point> {
+        (0..n)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / n as f64;
+                match self {
+                    Domain::Leaf => {
+                        // Up one side from base to tip and back down the other
+                        let t = (1.0 - a.cos()) / 2.0;
+                        let side = if a < PI { 1.0 } else { -1.0 };
+                        Point {
+                            x: SIZE / 2.0 + side * Self::leaf_half_width(t),
+                            y: LEAF_BASE - t * (LEAF_BASE - LEAF_TIP),
+                        }
+                    }
+                    Domain::Crown => Point { x: 400.0 + 320.0 * a.cos(), y: 300.0 + 230.0 * a.sin() },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parameters for space colonization, in output pixels.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColonizationParams {
+    /// Number of attraction points scattered in the domain
+    pub attractors: usize,
+    /// Distance within which an attractor pulls on a node
+    pub influence_radius: f64,
+    /// Distance at which a node reaches an attractor and removes it
+    pub kill_distance: f64,
+    /// Length of each new vein segment
+    pub segment_length: f64,
+    pub venation: Venation,
+    pub domain: Domain,
+    /// Upper bound on growth steps
+    pub max_iterations: usize,
+}
+
+impl Default for ColonizationParams {
+    fn default() -> Self {
+        Preset::Leaf.params()
+    }
+}
+
+impl ColonizationParams {
+    /// Reject empty or oversized runs, and a kill distance outside
+    /// [segment length, influence radius]: shorter and nodes step over their
+    /// attractors forever, longer and attractors vanish before they pull.
+    pub fn validate(&self) -> Result<()> {
+        check_count("attractors", self.attractors, MAX_ATTRACTORS)?;
+        check_count("max_iterations", self.max_iterations, MAX_ITERATIONS)?;
+        check_positive("segment_length", self.segment_length)?;
+        check_positive("influence_radius", self.influence_radius)?;
+        check_range(
+            "kill_distance",
+            self.kill_distance,
+            self.segment_length..=self.influence_radius,
+            "segment_length ≤ kill_distance ≤ influence_radius",
+        )
+    }
+}
+
+/// Presets for the two classic results.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Closed venation in a leaf blade: a midrib, secondaries, and loops
+    Leaf,
+    /// Open branching filling a tree crown
+    Tree,
+}
+
+impl Preset {
+    pub fn params(self) -> ColonizationParams {
+        match self {
+            Preset::Leaf => ColonizationParams {
+                attractors: 1500,
+                influence_radius: 70.0,
+                kill_distance: 9.0,
+                segment_length: 4.0,
+                venation: Venation::Closed,
+                domain: Domain::Leaf,
+                max_iterations: 1500,
+            },
+            Preset::Tree => ColonizationParams {
+                attractors: 900,
+                influence_radius: 110.0,
+                kill_distance: 14.0,
+                segment_length: 6.0,
+                venation: Venation::Open,
+                domain: Domain::Crown,
+                max_iterations: 1500,
+            },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Leaf => "Leaf Venation",
+            Preset::Tree => "Tree Crown",
+        }
+    }
+}
+
+/// A vein node; every node but the root grew from its parent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node {
+    pub position: Point,
+    pub parent: Option<usize>,
+}
+
+/// A grown vein network.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Network {
+    /// Nodes in growth order, so parents always precede their children
+    pub nodes: Vec<Node>,
+    /// Pairs of nodes whose veins met at the same attractor (closed venation)
+    pub anastomoses: Vec<(usize, usize)>,
+    /// Attractors never reached
+    pub attractors: Vec<Point>,
+    pub domain: Domain,
+    /// Growth steps taken
+    pub iterations: usize,
+}
+
+/// Uniform buckets for radius queries over points that only ever get added.
+struct Buckets {
+    cell: f64,
+    cols: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Buckets {
+    fn new(cell: f64) -> Self {
+        let cols = (SIZE / cell).ceil() as usize + 1;
+        Buckets { cell, cols, cells: vec![Vec::new(); cols * cols] }
+    }
+
+    fn coord(&self, v: f64) -> usize {
+        ((v / self.cell).max(0.0) as usize).min(self.cols - 1)
+    }
+
+    fn insert(&mut self, p: Point, i: usize) {
+        let k = self.coord(p.y) * self.cols + self.coord(p.x);
+        self.cells[k].push(i);
+    }
+
+    /// Call `f` with every stored index whose bucket overlaps the disc.
+    fn for_each_near(&self, p: Point, r: f64, mut f: impl FnMut(usize)) {
+        let (x0, x1) = (self.coord(p.x - r), self.coord(p.x + r));
+        let (y0, y1) = (self.coord(p.y - r), self.coord(p.y + r));
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                self.cells[y * self.cols + x].iter().for_each(|&i| f(i));
+            }
+        }
+    }
+}
+
+fn dist(a: Point, b: Point) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+/// Scatter up to `count` points in the domain, no two closer than `spacing`,
+/// by dart throwing.
+pub fn scatter(domain: Domain, count: usize, spacing: f64, seed: u64) -> Vec<Point> {
+    let mut rng = SimpleRng::new(seed);
+    let mut buckets = Buckets::new(spacing.max(1.0));
+    let mut points: Vec<Point> = Vec::with_capacity(count);
+    for _ in 0..count * 30 {
+        if points.len() == count {
+            break;
+        }
+        let p = Point { x: rng.next_f64() * SIZE, y: rng.next_f64() * SIZE };
+        if !domain.contains(p) {
+            continue;
+        }
+        let mut crowded = false;
+        buckets.for_each_near(p, spacing, |i| crowded |= dist(points[i], p) < spacing);
+        if !crowded {
+            buckets.insert(p, points.len());
+            points.push(p);
+        }
+    }
+    points
+}
+
+impl Network {
+    /// Indices of nodes with no children: the vein endings.
+    pub fn tips(&self) -> Vec<usize> {
+        let mut has_child = vec![false; self.nodes.len()];
+        for n in &self.nodes {
+            if let Some(p) = n.parent {
+                has_child[p] = true;
+            }
+        }
+        (0..self.nodes.len()).filter(|&i| !has_child[i]).collect()
+    }
+
+    /// Pipe-model width of every node: tips are 1 and each parent satisfies
+    /// w_parentᵞ = Σ w_childᵞ.
+    pub fn widths(&self, exponent: f64) -> Vec<f64> {
+        let mut carried = vec![0.0f64; self.nodes.len()];
+        let mut widths = vec![1.0f64; self.nodes.len()];
+        for i in (0..self.nodes.len()).rev() {
+            if carried[i] > 0.0 {
+                widths[i] = carried[i].powf(1.0 / exponent);
+            }
+            if let Some(p) = self.nodes[i].parent {
+                carried[p] += widths[i].powf(exponent);
+            }
+        }
+        widths
+    }
+
+    /// Total length of all vein segments.
+    pub fn length(&self) -> f64 {
+        self.nodes
+            .iter()
+            .filter_map(|n| n.parent.map(|p| dist(self.nodes[p].position, n.position)))
+            .sum()
+    }
+}
+
+/// Nodes in the relative neighborhood of `s` among `near` (index, distance)
+/// pairs: v qualifies unless some u is closer to both s and v than they are
+/// to each other.
+fn relative_neighbors(nodes: &[Node], near: &[(usize, f64)]) -> Vec<(usize, f64)> {
+    near.iter()
+        .filter(|&&(v, dv)| {
+            !near.iter().any(|&(u, du)| {
+                u != v && du < dv && dist(nodes[u].position, nodes[v].position) < dv
+            })
+        })
+        .copied()
+        .collect()
+}
+
+/// Grow a vein network from the domain's root.
+pub fn grow(params: &ColonizationParams, seed: u64) -> Network {
+    let root = params.domain.root();
+    let mut net = Network {
+        nodes: vec![Node { position: root, parent: None }],
+        anastomoses: Vec::new(),
+        attractors: scatter(params.domain, params.attractors, params.kill_distance, seed),
+        domain: params.domain,
+        iterations: 0,
+    };
+    let mut index = Buckets::new(params.influence_radius);
+    index.insert(root, 0);
+
+    while net.iterations < params.max_iterations && !net.attractors.is_empty() {
+        net.iterations += 1;
+        let mut pull = vec![(0.0, 0.0); net.nodes.len()];
+        let mut in_reach = false;
+        let mut reached = vec![false; net.attractors.len()];
+        for (k, &s) in net.attractors.iter().enumerate() {
+            let mut near = Vec::new();
+            index.for_each_near(s, params.influence_radius, |i| {
+                let d = dist(net.nodes[i].position, s);
+                if d < params.influence_radius {
+                    near.push((i, d));
+                }
+            });
+            if near.is_empty() {
+                continue;
+            }
+            in_reach = true;
+            let targets = match params.venation {
+                Venation::Open => near.iter().copied().min_by(|a, b| a.1.total_cmp(&b.1)).into_iter().collect(),
+                Venation::Closed => relative_neighbors(&net.nodes, &near),
+            };
+            if targets.iter().all(|&(_, d)| d < params.kill_distance) {
+                reached[k] = true;
+                // Veins from different branches arriving together close a loop
+                for pair in targets.windows(2) {
+                    let (a, b) = (pair[0].0, pair[1].0);
+                    if net.nodes[a].parent != Some(b) && net.nodes[b].parent != Some(a) {
+                        net.anastomoses.push((a.min(b), a.max(b)));
+                    }
+                }
+                continue;
+            }
+            for &(v, d) in targets.iter().filter(|&&(_, d)| d >= params.kill_distance) {
+                let p = net.nodes[v].position;
+                pull[v].0 += (s.x - p.x) / d;
+                pull[v].1 += (s.y - p.y) / d;
+            }
+        }
+        let removed = reached.iter().any(|&r| r);
+        let mut k = 0;
+        net.attractors.retain(|_| {
+            k += 1;
+            !reached[k - 1]
+        });
+
+        let mut grew = false;
+        for (v, &(dx, dy)) in pull.iter().enumerate() {
+            let norm = dx.hypot(dy);
+            if norm < 1e-9 {
+                continue;
+            }
+            let p = net.nodes[v].position;
+            let q = Point { x: p.x + params.segment_length * dx / norm, y: p.y + params.segment_length * dy / norm };
+            let mut duplicate = false;
+            index.for_each_near(q, params.segment_length, |i| {
+                duplicate |= dist(net.nodes[i].position, q) < 0.25 * params.segment_length
+            });
+            if !duplicate {
+                index.insert(q, net.nodes.len());
+                net.nodes.push(Node { position: q, parent: Some(v) });
+                grew = true;
+            }
+        }
+
+        if grew || removed {
+            continue;
+        }
+        if !in_reach {
+            // Nothing in reach yet: extend the newest node toward the attractors,
+            // which grows the petiole or the trunk
+            let n = net.attractors.len() as f64;
+            let target = Point {
+                x: net.attractors.iter().map(|a| a.x).sum::<f64>() / n,
+                y: net.attractors.iter().map(|a| a.y).sum::<f64>() / n,
+            };
+            let tip = net.nodes.len() - 1;
+            let p = net.nodes[tip].position;
+            let d = dist(p, target);
+            if d < params.segment_length {
+                break;
+            }
+            let q = Point {
+                x: p.x + params.segment_length * (target.x - p.x) / d,
+                y: p.y + params.segment_length * (target.y - p.y) / d,
+            };
+            index.insert(q, net.nodes.len());
+            net.nodes.push(Node { position: q, parent: Some(tip) });
+        } else {
+            // Pulls cancelled out or only hit existing nodes: growth has stalled
+            break;
+        }
+    }
+    net
+}
+
+impl Generator for Network {
+    /// Every vein segment and loop closure as a two-point polyline.
+    fn generate(&self) -> Geometry {
+        let segments = self
+            .nodes
+            .iter()
+            .filter_map(|n| n.parent.map(|p| vec![self.nodes[p].position, n.position]));
+        let loops = self.anastomoses.iter().map(|&(a, b)| vec![self.nodes[a].position, self.nodes[b].position]);
+        Geometry::Polylines(segments.chain(loops).collect())
+    }
+}
+
+/// Render the network with pipe-model widths tapering from `max_width` at the
+/// root down to hairline vein endings; leaves get their blade drawn beneath.
+pub fn to_svg(net: &Network, max_width: f64) -> String {
+    to_svg_with_theme(net, max_width, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, the blade takes its first stop
+/// and veins run along the rest from thinnest to thickest.
+pub fn to_svg_with_theme(net: &Network, max_width: f64, theme: &Theme) -> String {
+    let widths = net.widths(PIPE_EXPONENT);
+    let widest = widths.iter().copied().fold(1.0, f64::max);
+    let stroke = |w: f64| (max_width * w / widest).max(0.4);
+    let (vein, blade) = match net.domain {
+        Domain::Leaf => ("#d9e8a6", Some(("#1d3f25", "#3f7a45"))),
+        Domain::Crown => ("#b8926a", None),
+    };
+
+    let mut content = String::new();
+    if let Some((fill, edge)) = blade {
+        let points: Vec<String> =
+            net.domain.outline(240).iter().map(|p| format!("{:.1},{:.1}", p.x, p.y)).collect();
+        content.push_str(&format!(
+            r##"<polygon points="{}" fill="{}" stroke="{}" stroke-width="{}"/>
+"##,
+            points.join(" "),
+            theme.color(0.0, fill),
+            theme.color(0.4, edge),
+            theme.stroke_width(2.0)
+        ));
+    }
+
+    // One path per stroke width, thin veins first so thick ones lie on top
+    let mut paths: Map<u32, String> = Map::new();
+    let mut segment = |a: Point, b: Point, w: f64| {
+        let d = paths.entry((stroke(w) * 10.0).round() as u32).or_default();
+        d.push_str(&format!("M{:.1},{:.1}L{:.1},{:.1}", a.x, a.y, b.x, b.y));
+    };
+    for (i, n) in net.nodes.iter().enumerate() {
+        if let Some(p) = n.parent {
+            segment(net.nodes[p].position, n.position, widths[i]);
+        }
+    }
+    for &(a, b) in &net.anastomoses {
+        segment(net.nodes[a].position, net.nodes[b].position, widths[a].min(widths[b]));
+    }
+    let mut keys: Vec<u32> = paths.keys().copied().collect();
+    keys.sort_unstable();
+    for (k, &w) in keys.iter().enumerate() {
+        content.push_str(&format!(
+            r##"<path d="{d}" fill="none" stroke="{}" stroke-width="{:.1}" stroke-linecap="round"/>
+"##,
+            theme.color(0.4 + 0.6 * theme::place(k, keys.len()), vein),
+            theme.stroke_width(w as f64 / 10.0),
+            d = paths[&w]
+        ));
+    }
+    crate::render::svg_document_with_theme(SIZE as u32, SIZE as u32, &content, theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small(preset: Preset) -> ColonizationParams {
+        ColonizationParams { attractors: 200, ..preset.params() }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Preset::Leaf.params().validate().is_ok());
+        assert!(Preset::Tree.params().validate().is_ok());
+        assert!(ColonizationParams { attractors: 0, ..Default::default() }.validate().is_err());
+        assert!(ColonizationParams { kill_distance: 2.0, ..Default::default() }.validate().is_err());
+        assert!(ColonizationParams { kill_distance: 100.0, ..Default::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_domains() {
+        let center = Point { x: 400.0, y: 400.0 };
+        assert!(Domain::Leaf.contains(center));
+        assert!(Domain::Crown.contains(Point { x: 400.0, y: 300.0 }));
+        assert!(!Domain::Leaf.contains(Point { x: 40.0, y: 400.0 }));
+        assert!(!Domain::Crown.contains(Domain::Crown.root()));
+        assert_eq!(Domain::Leaf.outline(100).len(), 100);
+    }
+
+    #[test]
+    fn test_scatter_spacing() {
+        let points = scatter(Domain::Leaf, 300, 15.0, 3);
+        assert_eq!(points.len(), 300);
+        assert!(points.iter().all(|&p| Domain::Leaf.contains(p)));
+        for (i, &a) in points.iter().enumerate() {
+            assert!(points[i + 1..].iter().all(|&b| dist(a, b) >= 15.0));
+        }
+    }
+
+    #[test]
+    fn test_open_growth_is_a_tree() {
+        let params = small(Preset::Tree);
+        let net = grow(&params, 42);
+        assert!(net.attractors.len() < params.attractors / 10, "{} left", net.attractors.len());
+        assert!(net.anastomoses.is_empty());
+        assert_eq!(net.nodes[0].parent, None);
+        for (i, n) in net.nodes.iter().enumerate().skip(1) {
+            let p = n.parent.unwrap();
+            assert!(p < i);
+            assert!((dist(net.nodes[p].position, n.position) - params.segment_length).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_closed_venation_forms_loops() {
+        let net = grow(&small(Preset::Leaf), 42);
+        assert!(!net.anastomoses.is_empty());
+        assert!(net.anastomoses.iter().all(|&(a, b)| a < b && b < net.nodes.len()));
+        let open = ColonizationParams { venation: Venation::Open, ..small(Preset::Leaf) };
+        assert!(grow(&open, 42).anastomoses.is_empty());
+    }
+
+    #[test]
+    fn test_pipe_model_widths() {
+        let net = grow(&small(Preset::Tree), 7);
+        let widths = net.widths(2.0);
+        // With γ = 2 every tip contributes 1 to the squared root width
+        assert!((widths[0].powi(2) - net.tips().len() as f64).abs() < 1e-6);
+        for (i, n) in net.nodes.iter().enumerate() {
+            if let Some(p) = n.parent {
+                assert!(widths[p] >= widths[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let a = grow(&small(Preset::Tree), 9);
+        let b = grow(&small(Preset::Tree), 9);
+        assert_eq!(a.nodes, b.nodes);
+        assert!(a.length() > 0.0);
+    }
+
+    #[test]
+    fn test_svg_tapers() {
+        let net = grow(&small(Preset::Tree), 42);
+        let svg = to_svg(&net, 10.0);
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains(r#"stroke-width="10.0""#));
+        assert!(svg.matches("<path").count() > 3);
+        match net.generate() {
+            Geometry::Polylines(lines) => assert_eq!(lines.len(), net.nodes.len() - 1),
+            _ => panic!("expected polylines"),
+        }
+    }
+}
@@ -0,0 +1,391 @@
+//! Diffusion-limited aggregation — coral, frost, and mineral dendrites.
+//!
+//! Witten & Sander (1981): particles random-walk until they touch a growing
+//! cluster and stick. Tips catch wandering particles before they can reach
+//! the fjords between branches, so the cluster grows into a fractal with
+//! dimension ≈ 1.71 — the same branching seen in manganese dendrites on rock,
+//! frost on glass, electrodeposits, and some corals.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_count, check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::colormap::Colormap;
+use crate::render::theme::Theme;
+use super::fractals::{box_counting_dimension, estimate_dimension, Point, SimpleRng};
+
+/// Where the cluster starts growing from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Seed {
+    /// A single particle in the center; walkers arrive from a surrounding circle
+    Point,
+    /// A full bottom row; walkers rain down from above (periodic in x)
+    Line,
+    /// A ring around the edge; walkers start in the middle and grow it inward
+    Circle,
+}
+
+/// Parameters for DLA growth.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DlaParams {
+    /// Number of particles to attach
+    pub particles: usize,
+    /// Probability of sticking on each contact (1.0 = classic DLA; lower = denser)
+    pub stickiness: f64,
+    pub seed: Seed,
+}
+
+impl Default for DlaParams {
+    fn default() -> Self {
+        Preset::Coral.params()
+    }
+}
+
+/// Largest cluster [`DlaParams::validate`] accepts.
+pub const MAX_PARTICLES: usize = 200_000;
+
+impl DlaParams {
+    /// Reject empty or oversized clusters and a stickiness of zero, which
+    /// would leave walkers wandering forever.
+    pub fn validate(&self) -> Result<()> {
+        check_count("particles", self.particles, MAX_PARTICLES)?;
+        check_range("stickiness", self.stickiness, f64::MIN_POSITIVE..=1.0, "0 < stickiness ≤ 1")
+    }
+}
+
+/// Presets for familiar natural aggregates.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Open radial branching from a point
+    Coral,
+    /// Dense, feathery growth inward from a frame
+    Frost,
+    /// Tree-like manganese dendrites rising from a crack
+    Mineral,
+}
+
+impl Preset {
+    pub fn params(self) -> DlaParams {
+        match self {
+            Preset::Coral => DlaParams { particles: 4000, stickiness: 1.0, seed: Seed::Point },
+            Preset::Frost => DlaParams { particles: 6000, stickiness: 0.3, seed: Seed::Circle },
+            Preset::Mineral => DlaParams { particles: 4000, stickiness: 0.8, seed: Seed::Line },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Coral => "Coral",
+            Preset::Frost => "Frost",
+            Preset::Mineral => "Mineral Dendrite",
+        }
+    }
+}
+
+/// A DLA cluster on a square lattice.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cluster {
+    pub width: usize,
+    pub height: usize,
+    /// Arrival order of the particle in each cell, if occupied
+    pub cells: Vec<Option<usize>>,
+    /// Occupied cells in arrival order
+    pub particles: Vec<(usize, usize)>,
+    pub seed: Seed,
+    /// Number of cells occupied by the seed itself
+    seed_cells: usize,
+}
+
+const STEPS: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+impl Cluster {
+    /// Create the seed structure.
+    pub fn new(width: usize, height: usize, seed: Seed) -> Self {
+        let mut cluster = Cluster {
+            width,
+            height,
+            cells: vec![None; width * height],
+            particles: Vec::new(),
+            seed,
+            seed_cells: 0,
+        };
+        match seed {
+            Seed::Point => cluster.occupy(width / 2, height / 2),
+            Seed::Line => (0..width).for_each(|x| cluster.occupy(x, height - 1)),
+            Seed::Circle => {
+                let r = width.min(height) as f64 / 2.0 - 2.0;
+                let steps = (2.0 * PI * r * 2.0) as usize;
+                for i in 0..steps {
+                    let a = 2.0 * PI * i as f64 / steps as f64;
+                    let (x, y) = (width as f64 / 2.0 + r * a.cos(), height as f64 / 2.0 + r * a.sin());
+                    if cluster.cells[y as usize * width + x as usize].is_none() {
+                        cluster.occupy(x as usize, y as usize);
+                    }
+                }
+            }
+        }
+        cluster.seed_cells = cluster.particles.len();
+        cluster
+    }
+
+    fn occupy(&mut self, x: usize, y: usize) {
+        self.cells[y * self.width + x] = Some(self.particles.len());
+        self.particles.push((x, y));
+    }
+
+    fn occupied(&self, x: isize, y: isize) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && self.cells[y as usize * self.width + x as usize].is_some()
+    }
+
+    fn touches(&self, x: isize, y: isize) -> bool {
+        STEPS.iter().any(|&(dx, dy)| self.occupied(x + dx, y + dy))
+    }
+
+    fn center(&self) -> (f64, f64) {
+        (self.width as f64 / 2.0, self.height as f64 / 2.0)
+    }
+
+    /// Radius of the cluster around the center (Point), height above the floor
+    /// (Line), or inward reach from the ring (Circle).
+    pub fn extent(&self) -> f64 {
+        let (cx, cy) = self.center();
+        let dist = |&(x, y): &(usize, usize)| (x as f64 - cx).hypot(y as f64 - cy);
+        match self.seed {
+            Seed::Point => self.particles.iter().map(dist).fold(0.0, f64::max),
+            Seed::Line => self.particles.iter().map(|&(_, y)| (self.height - 1 - y) as f64).fold(0.0, f64::max),
+            Seed::Circle => {
+                let ring = self.width.min(self.height) as f64 / 2.0 - 2.0;
+                ring - self.particles.iter().map(dist).fold(f64::INFINITY, f64::min)
+            }
+        }
+    }
+
+    /// Launch position for a new walker, or `None` once the cluster fills the grid.
+    fn launch(&self, rng: &mut SimpleRng) -> Option<(isize, isize)> {
+        let (cx, cy) = self.center();
+        let extent = self.extent();
+        let a = rng.next_f64() * 2.0 * PI;
+        match self.seed {
+            Seed::Point => {
+                let r = extent + 5.0;
+                (r + 10.0 < self.width.min(self.height) as f64 / 2.0)
+                    .then(|| ((cx + r * a.cos()) as isize, (cy + r * a.sin()) as isize))
+            }
+            Seed::Line => {
+                let y = self.height as f64 - 1.0 - extent - 5.0;
+                (y > 10.0).then(|| (rng.next_usize(self.width) as isize, y as isize))
+            }
+            Seed::Circle => {
+                let ring = self.width.min(self.height) as f64 / 2.0 - 2.0;
+                let r = (ring - extent - 5.0).max(0.0) * rng.next_f64().sqrt();
+                (ring - extent > 4.0).then(|| ((cx + r * a.cos()) as isize, (cy + r * a.sin()) as isize))
+            }
+        }
+    }
+
+    /// Whether a walker has wandered too far and should be relaunched.
+    fn escaped(&self, x: isize, y: isize, extent: f64) -> bool {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return true;
+        }
+        let (cx, cy) = self.center();
+        match self.seed {
+            Seed::Point => (x as f64 - cx).hypot(y as f64 - cy) > 2.0 * extent + 20.0,
+            Seed::Line => ((self.height - 1) as f64 - y as f64) > extent + 25.0,
+            Seed::Circle => false,
+        }
+    }
+
+    /// Release walkers until `params.particles` have attached or the grid is full.
+    pub fn grow(&mut self, params: &DlaParams, rng: &mut SimpleRng) {
+        let target = self.particles.len() + params.particles;
+        'walkers: while self.particles.len() < target {
+            let Some((mut x, mut y)) = self.launch(rng) else { break };
+            let extent = self.extent();
+            loop {
+                if self.occupied(x, y) {
+                    continue 'walkers;
+                }
+                if self.touches(x, y) && rng.next_f64() < params.stickiness {
+                    self.occupy(x as usize, y as usize);
+                    continue 'walkers;
+                }
+                let (dx, dy) = STEPS[rng.next_usize(4)];
+                let (nx, ny) = (x + dx, y + dy);
+                if self.occupied(nx, ny) {
+                    continue;
+                }
+                x = nx;
+                y = ny;
+                if self.seed == Seed::Line {
+                    x = x.rem_euclid(self.width as isize);
+                }
+                if self.escaped(x, y, extent) {
+                    continue 'walkers;
+                }
+            }
+        }
+    }
+
+    /// Box-counting fractal dimension of the attached particles. Line and ring
+    /// seeds are excluded since they would bias the estimate toward 1.
+    pub fn fractal_dimension(&self) -> f64 {
+        let points: Vec<Point> = self.particles[self.seed_cells..]
+            .iter()
+            .map(|&(x, y)| Point { x: x as f64, y: y as f64 })
+            .collect();
+        let extent = self.extent().max(4.0);
+        let mut sizes = Vec::new();
+        // Single-cell boxes only see one-pixel-wide branches, so start above them
+        let mut s = 4.0;
+        while s < extent / 2.0 {
+            sizes.push(s);
+            s *= 2.0;
+        }
+        estimate_dimension(&box_counting_dimension(&points, &sizes))
+    }
+}
+
+/// Grow a cluster on a `width × height` lattice.
+pub fn grow(width: usize, height: usize, params: &DlaParams, seed: u64) -> Cluster {
+    let mut cluster = Cluster::new(width, height, params.seed);
+    let mut rng = SimpleRng::new(seed);
+    cluster.grow(params, &mut rng);
+    cluster
+}
+
+impl Generator for Cluster {
+    /// Particle cells in arrival order.
+    fn generate(&self) -> Geometry {
+        Geometry::Points(self.particles.iter().map(|&(x, y)| Point { x: x as f64, y: y as f64 }).collect())
+    }
+}
+
+/// Render the cluster colored by arrival order: old particles deep, new tips bright.
+pub fn to_svg(cluster: &Cluster, cell: f64) -> String {
+    to_svg_with_theme(cluster, cell, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`, arrival order running along its palette if it
+/// has one.
+pub fn to_svg_with_theme(cluster: &Cluster, cell: f64, theme: &Theme) -> String {
+    match &theme.palette {
+        Some(palette) => render(cluster, cell, |t| palette.css(t), theme),
+        None => render(cluster, cell, |t| crate::render::viridis(0.15 + 0.85 * t), theme),
+    }
+}
+
+/// [`to_svg`] with arrival order running along `colormap`.
+pub fn to_svg_with_colormap(cluster: &Cluster, cell: f64, colormap: &dyn Colormap) -> String {
+    render(cluster, cell, |t| colormap.css(t), &Theme::dark())
+}
+
+fn render(cluster: &Cluster, cell: f64, color: impl Fn(f64) -> String, theme: &Theme) -> String {
+    let n = cluster.particles.len().max(1) as f64;
+    let values: Vec<f64> = cluster
+        .cells
+        .iter()
+        .map(|c| c.map_or(-1.0, |order| order as f64 / n))
+        .collect();
+    let background = theme.background_css();
+    let color = |t| if t < 0.0 { background.clone() } else { color(t) };
+    crate::render::raster_svg_with_theme(cluster.width, cluster.height, cell, &values, color, theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_shapes() {
+        assert_eq!(Cluster::new(50, 50, Seed::Point).particles.len(), 1);
+        assert_eq!(Cluster::new(50, 40, Seed::Line).particles.len(), 50);
+        assert!(Cluster::new(50, 50, Seed::Circle).particles.len() > 100);
+    }
+
+    #[test]
+    fn test_growth_attaches_particles() {
+        let params = DlaParams { particles: 300, ..Default::default() };
+        let cluster = grow(120, 120, &params, 42);
+        assert_eq!(cluster.particles.len(), 301);
+    }
+
+    #[test]
+    fn test_particles_connected() {
+        let params = DlaParams { particles: 300, ..Default::default() };
+        let cluster = grow(120, 120, &params, 7);
+        // Every particle after the seed touches an earlier one
+        for (i, &(x, y)) in cluster.particles.iter().enumerate().skip(1) {
+            let touches_earlier = STEPS.iter().any(|&(dx, dy)| {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                cluster.occupied(nx, ny) && cluster.cells[ny as usize * 120 + nx as usize].unwrap() < i
+            });
+            assert!(touches_earlier, "Particle {} is floating", i);
+        }
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let params = DlaParams { particles: 100, ..Default::default() };
+        let a = grow(80, 80, &params, 3);
+        let b = grow(80, 80, &params, 3);
+        assert_eq!(a.particles, b.particles);
+    }
+
+    #[test]
+    fn test_point_dla_dimension() {
+        let params = DlaParams { particles: 2500, ..Default::default() };
+        let cluster = grow(300, 300, &params, 11);
+        let d = cluster.fractal_dimension();
+        assert!(d > 1.4 && d < 1.95, "DLA dimension should be ≈ 1.7, got {}", d);
+    }
+
+    #[test]
+    fn test_line_seed_grows_upward() {
+        let params = Preset::Mineral.params();
+        let params = DlaParams { particles: 400, ..params };
+        let cluster = grow(100, 100, &params, 5);
+        assert!(cluster.extent() > 3.0);
+    }
+
+    #[test]
+    fn test_ring_seed_grows_inward() {
+        let params = DlaParams { particles: 400, ..Preset::Frost.params() };
+        let cluster = grow(100, 100, &params, 5);
+        let bare = Cluster::new(100, 100, Seed::Circle);
+        assert!(cluster.extent() > bare.extent() + 3.0);
+    }
+
+    #[test]
+    fn test_stops_when_full() {
+        let params = DlaParams { particles: 100_000, ..Default::default() };
+        let cluster = grow(40, 40, &params, 1);
+        assert!(cluster.particles.len() < 1600);
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let params = DlaParams { particles: 50, ..Default::default() };
+        let svg = to_svg(&grow(60, 60, &params, 2), 4.0);
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("<rect"));
+    }
+
+    #[test]
+    fn test_validate() {
+        for preset in [Preset::Coral, Preset::Frost, Preset::Mineral] {
+            assert!(preset.params().validate().is_ok());
+        }
+        assert!(DlaParams { stickiness: 0.0, ..Default::default() }.validate().is_err());
+        assert!(DlaParams { particles: 0, ..Default::default() }.validate().is_err());
+    }
+}
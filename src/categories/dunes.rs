@@ -0,0 +1,388 @@
+//! Sand dunes — Werner's slab model of wind-blown sand.
+//!
+//! Werner (1995) reduced dune formation to a few rules on a grid of stacked
+//! sand slabs. The wind blows along +x. Repeatedly:
+//!   1. pick a random cell; if it has sand and is not sheltered, lift a slab
+//!   2. carry it L cells downwind; it lands with probability p_sand on sand
+//!      (p_bare on bare ground, always in a wind shadow), otherwise hops again
+//!   3. avalanche wherever the slope exceeds the angle of repose
+//!
+//! Cells in the lee of a crest — below a 15° line from an upwind peak — are
+//! shadowed: sand piles up there but cannot be eroded. With plenty of sand,
+//! crests line up across the wind as transverse dunes; with sparse sand on
+//! hard ground they break up into isolated dunes drifting over bare rock.
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::colormap::Colormap;
+use crate::render::theme::Theme;
+use super::fractals::SimpleRng;
+
+/// Parameters for the Werner model. Heights are in slabs, distances in cells.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DuneParams {
+    /// Hop length L downwind
+    pub hop: usize,
+    /// Deposition probability on sand
+    pub p_sand: f64,
+    /// Deposition probability on bare ground
+    pub p_bare: f64,
+    /// Shadow line drop per cell downwind of a peak (slabs)
+    pub shadow_slope: f64,
+    /// Largest height difference between neighbors before an avalanche (slabs)
+    pub repose: u32,
+    /// Mean initial sand depth (slabs)
+    pub sand_depth: f64,
+}
+
+impl Default for DuneParams {
+    fn default() -> Self {
+        Preset::Transverse.params()
+    }
+}
+
+impl DuneParams {
+    /// Require a hop of 1–64 cells, probabilities in 0–1 with some chance of
+    /// landing on sand, a positive shadow slope, and a sane sand depth.
+    pub fn validate(&self) -> Result<()> {
+        check_count("hop", self.hop, 64)?;
+        check_range("p_sand", self.p_sand, f64::MIN_POSITIVE..=1.0, "0 < p_sand ≤ 1")?;
+        check_range("p_bare", self.p_bare, 0.0..=1.0, "0 to 1")?;
+        check_positive("shadow_slope", self.shadow_slope)?;
+        check_range("sand_depth", self.sand_depth, 0.0..=1000.0, "0 to 1000 slabs")
+    }
+}
+
+/// Presets for common bedforms.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Small, closely spaced ripples from short hops
+    Ripples,
+    /// Long crests across the wind over a thick sand sea
+    Transverse,
+    /// Isolated dunes migrating over bare ground
+    Barchans,
+}
+
+impl Preset {
+    pub fn params(self) -> DuneParams {
+        match self {
+            Preset::Ripples => DuneParams { hop: 2, p_sand: 0.6, p_bare: 0.4, shadow_slope: 0.5, repose: 1, sand_depth: 3.0 },
+            Preset::Transverse => DuneParams { hop: 5, p_sand: 0.6, p_bare: 0.4, shadow_slope: 0.5, repose: 2, sand_depth: 3.0 },
+            Preset::Barchans => DuneParams { hop: 5, p_sand: 0.6, p_bare: 0.4, shadow_slope: 0.5, repose: 2, sand_depth: 1.0 },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Ripples => "Ripples",
+            Preset::Transverse => "Transverse Dunes",
+            Preset::Barchans => "Barchans",
+        }
+    }
+}
+
+/// A periodic field of sand slabs.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DuneField {
+    pub width: usize,
+    pub height: usize,
+    pub slabs: Vec<u32>,
+    /// Upper bound on the tallest stack, which limits the shadow search
+    ceiling: u32,
+    rng: SimpleRng,
+}
+
+impl DuneField {
+    /// Scatter `sand_depth × cells` slabs at random.
+    pub fn new(width: usize, height: usize, params: &DuneParams, seed: u64) -> Self {
+        let mut rng = SimpleRng::new(seed);
+        let mut slabs = vec![0; width * height];
+        let total = (params.sand_depth * (width * height) as f64) as usize;
+        for _ in 0..total {
+            slabs[rng.next_usize(width * height)] += 1;
+        }
+        let mut field = DuneField { width, height, slabs, ceiling: 0, rng };
+        field.refresh_ceiling();
+        field
+    }
+
+    /// Recompute the height bound after `slabs` was edited directly.
+    fn refresh_ceiling(&mut self) {
+        self.ceiling = self.slabs.iter().copied().max().unwrap_or(0);
+    }
+
+    fn index(&self, x: isize, y: isize) -> usize {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        y * self.width + x
+    }
+
+    /// Whether a cell lies in the wind shadow of an upwind crest.
+    pub fn in_shadow(&self, x: usize, y: usize, params: &DuneParams) -> bool {
+        let h = self.slabs[y * self.width + x] as f64;
+        let reach = ((self.ceiling as f64 - h) / params.shadow_slope).ceil() as usize;
+        (1..=reach.min(self.width - 1)).any(|d| {
+            let upwind = self.slabs[self.index(x as isize - d as isize, y as isize)] as f64;
+            upwind - d as f64 * params.shadow_slope > h
+        })
+    }
+
+    /// Move slabs downhill from cell `i` until no neighbor is too far below it.
+    fn slide_from(&mut self, i: usize, params: &DuneParams) {
+        let mut stack = vec![i];
+        while let Some(i) = stack.pop() {
+            let (x, y) = ((i % self.width) as isize, (i / self.width) as isize);
+            let lowest = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .iter()
+                .map(|&(dx, dy)| self.index(x + dx, y + dy))
+                .min_by_key(|&j| self.slabs[j])
+                .unwrap_or(i);
+            if self.slabs[i] > self.slabs[lowest] + params.repose {
+                self.slabs[i] -= 1;
+                self.slabs[lowest] += 1;
+                stack.push(i);
+                stack.push(lowest);
+            }
+        }
+    }
+
+    /// Move slabs into cell `i` from any neighbor standing too far above it.
+    fn fill_into(&mut self, i: usize, params: &DuneParams) {
+        let mut stack = vec![i];
+        while let Some(i) = stack.pop() {
+            let (x, y) = ((i % self.width) as isize, (i / self.width) as isize);
+            let highest = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .iter()
+                .map(|&(dx, dy)| self.index(x + dx, y + dy))
+                .max_by_key(|&j| self.slabs[j])
+                .unwrap_or(i);
+            if self.slabs[highest] > self.slabs[i] + params.repose {
+                self.slabs[highest] -= 1;
+                self.slabs[i] += 1;
+                stack.push(i);
+                stack.push(highest);
+            }
+        }
+    }
+
+    /// One erosion–transport–deposition event at a random cell.
+    pub fn event(&mut self, params: &DuneParams) {
+        let i = self.rng.next_usize(self.slabs.len());
+        let (x, y) = (i % self.width, i / self.width);
+        if self.slabs[i] == 0 || self.in_shadow(x, y, params) {
+            return;
+        }
+        self.slabs[i] -= 1;
+        self.fill_into(i, params);
+
+        let mut x = x;
+        // Bound the flight so a slab can't hop forever over bare ground
+        for _ in 0..10 * self.width {
+            x = (x + params.hop.max(1)) % self.width;
+            let j = y * self.width + x;
+            let p = if self.in_shadow(x, y, params) {
+                1.0
+            } else if self.slabs[j] > 0 {
+                params.p_sand
+            } else {
+                params.p_bare
+            };
+            if self.rng.next_f64() < p {
+                self.slabs[j] += 1;
+                self.ceiling = self.ceiling.max(self.slabs[j]);
+                self.slide_from(j, params);
+                return;
+            }
+        }
+        self.slabs[i] += 1;
+    }
+
+    /// Run `cycles` sweeps of one event per cell on average.
+    pub fn simulate(&mut self, params: &DuneParams, cycles: usize) {
+        self.refresh_ceiling();
+        for _ in 0..cycles * self.slabs.len() {
+            self.event(params);
+        }
+    }
+
+    /// Total slabs (conserved by every rule).
+    pub fn total(&self) -> u64 {
+        self.slabs.iter().map(|&s| s as u64).sum()
+    }
+
+    /// Height autocorrelation at `lag` cells along x (downwind) and y
+    /// (crosswind), averaged over the field. Transverse dunes decorrelate
+    /// quickly downwind but stay correlated along their crests.
+    pub fn correlation(&self, lag: usize) -> (f64, f64) {
+        let n = self.slabs.len() as f64;
+        let mean = self.total() as f64 / n;
+        let var = self.slabs.iter().map(|&s| (s as f64 - mean).powi(2)).sum::<f64>() / n;
+        if var <= 0.0 {
+            return (0.0, 0.0);
+        }
+        let mut along_x = 0.0;
+        let mut along_y = 0.0;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let h = self.slabs[y * self.width + x] as f64 - mean;
+                along_x += h * (self.slabs[self.index((x + lag) as isize, y as isize)] as f64 - mean);
+                along_y += h * (self.slabs[self.index(x as isize, (y + lag) as isize)] as f64 - mean);
+            }
+        }
+        (along_x / n / var, along_y / n / var)
+    }
+
+    /// Record `frames` snapshots of the slab heights, `cycles_per_frame` apart.
+    pub fn time_lapse(&mut self, params: &DuneParams, frames: usize, cycles_per_frame: usize) -> Vec<Vec<u32>> {
+        (0..frames)
+            .map(|_| {
+                self.simulate(params, cycles_per_frame);
+                self.slabs.clone()
+            })
+            .collect()
+    }
+}
+
+impl Generator for DuneField {
+    fn generate(&self) -> Geometry {
+        Geometry::Grid { cols: self.width, rows: self.height, values: self.slabs.iter().map(|&s| s as f64).collect() }
+    }
+}
+
+/// Render slab heights as hillshaded sand lit from the low evening sun.
+pub fn to_svg(slabs: &[u32], width: usize, height: usize, cell: f64) -> String {
+    to_svg_with_theme(slabs, width, height, cell, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`, its palette standing in for the colormap of
+/// [`to_svg_with_colormap`] if it has one.
+pub fn to_svg_with_theme(slabs: &[u32], width: usize, height: usize, cell: f64, theme: &Theme) -> String {
+    match &theme.palette {
+        Some(palette) => render(slabs, width, height, cell, |_, light| palette.css(light), theme),
+        None => {
+            let sand = |band: f64, light: f64| crate::render::hsl(34.0 - 6.0 * band, 55.0 + 10.0 * band, 12.0 + 60.0 * light);
+            render(slabs, width, height, cell, sand, theme)
+        }
+    }
+}
+
+/// [`to_svg`] with the hillshade running along `colormap`, shadow to sunlit
+/// slope.
+pub fn to_svg_with_colormap(slabs: &[u32], width: usize, height: usize, cell: f64, colormap: &dyn Colormap) -> String {
+    render(slabs, width, height, cell, |_, light| colormap.css(light), &Theme::dark())
+}
+
+fn render(slabs: &[u32], width: usize, height: usize, cell: f64, color: impl Fn(f64, f64) -> String, theme: &Theme) -> String {
+    let heights: Vec<f64> = slabs.iter().map(|&s| s as f64).collect();
+    let max = heights.iter().cloned().fold(1.0, f64::max);
+    let shade = crate::render::hillshade(&heights, width, height, 0.5, -2.0, 0.5);
+    let values: Vec<f64> = shade
+        .iter()
+        .zip(&heights)
+        // Pack height band and shade level into one value so runs can merge
+        .map(|(s, h)| (h / max * 5.0).round() * 100.0 + (s * 20.0).round())
+        .collect();
+    let ground = theme.background_css();
+    let color = |v: f64| {
+        let (band, light) = ((v / 100.0).floor() / 5.0, (v % 100.0) / 20.0);
+        if band == 0.0 && light == 0.0 {
+            ground.clone()
+        } else {
+            color(band, light)
+        }
+    };
+    crate::render::raster_svg_with_theme(width, height, cell, &values, color, theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_sand() {
+        let params = DuneParams::default();
+        let field = DuneField::new(40, 30, &params, 1);
+        assert_eq!(field.total(), 3600);
+    }
+
+    #[test]
+    fn test_slabs_conserved() {
+        let params = DuneParams::default();
+        let mut field = DuneField::new(32, 32, &params, 2);
+        let before = field.total();
+        field.simulate(&params, 5);
+        assert_eq!(field.total(), before);
+    }
+
+    #[test]
+    fn test_shadow_behind_crest() {
+        let params = DuneParams::default();
+        let mut field = DuneField::new(20, 3, &DuneParams { sand_depth: 0.0, ..params }, 3);
+        field.slabs[5 + 20] = 6;
+        field.refresh_ceiling();
+        assert!(field.in_shadow(6, 1, &params));
+        assert!(field.in_shadow(14, 1, &params));
+        assert!(!field.in_shadow(18, 1, &params));
+        assert!(!field.in_shadow(4, 1, &params));
+    }
+
+    #[test]
+    fn test_avalanche_limits_slope() {
+        let params = DuneParams::default();
+        let mut field = DuneField::new(11, 11, &DuneParams { sand_depth: 0.0, ..params }, 4);
+        field.slabs[60] = 20;
+        field.slide_from(60, &params);
+        assert_eq!(field.total(), 20);
+        for y in 0..11isize {
+            for x in 0..11isize {
+                let h = field.slabs[field.index(x, y)];
+                for (dx, dy) in [(1, 0), (0, 1)] {
+                    let n = field.slabs[field.index(x + dx, y + dy)];
+                    assert!(h.abs_diff(n) <= params.repose, "Slope too steep at ({}, {})", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_transverse_crests_form() {
+        let params = Preset::Transverse.params();
+        let mut field = DuneField::new(60, 60, &params, 5);
+        field.simulate(&params, 60);
+        let (downwind, crosswind) = field.correlation(4);
+        assert!(crosswind > downwind, "Crests should run across the wind: {} vs {}", crosswind, downwind);
+    }
+
+    #[test]
+    fn test_time_lapse() {
+        let params = Preset::Ripples.params();
+        let mut field = DuneField::new(24, 24, &params, 6);
+        let frames = field.time_lapse(&params, 3, 2);
+        assert_eq!(frames.len(), 3);
+        assert_ne!(frames[0], frames[2]);
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let params = Preset::Barchans.params();
+        let mut field = DuneField::new(30, 30, &params, 7);
+        field.simulate(&params, 2);
+        let svg = to_svg(&field.slabs, 30, 30, 4.0);
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("<rect"));
+    }
+
+    #[test]
+    fn test_validate() {
+        for preset in [Preset::Ripples, Preset::Transverse, Preset::Barchans] {
+            assert!(preset.params().validate().is_ok());
+        }
+        assert!(DuneParams { hop: 0, ..Default::default() }.validate().is_err());
+        assert!(DuneParams { p_sand: 0.0, ..Default::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,448 @@
+//! Fourier epicycles — drawing any closed outline with rotating circles.
+//!
+//! A closed path z(t) in the complex plane can be written as a sum of
+//! circular motions:
+//!   z(t) = Σ cₖ e^(2πikt)
+//! Each term is a circle of radius |cₖ| spinning k times per period. Chained
+//! tip to tail, the circles trace the original shape — a leaf, a shell, a
+//! coastline — and truncating the series shows how detail emerges term by term.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::Theme;
+use super::fractals::Point;
+
+/// One Fourier term: a circle of radius `amplitude` turning `frequency` times per period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coefficient {
+    pub frequency: i64,
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Coefficient {
+    pub fn amplitude(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    pub fn phase(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    /// Position of this term's tip at time t ∈ [0, 1).
+    pub fn at(&self, t: f64) -> Point {
+        let (s, c) = (2.0 * PI * self.frequency as f64 * t).sin_cos();
+        Point { x: self.re * c - self.im * s, y: self.re * s + self.im * c }
+    }
+}
+
+/// Parse a point list from text such as `"0,0 10,0 10,10"` or a simple
+/// SVG path `"M0 0 L10 0 L10 10 Z"`. Command letters are ignored; numbers are
+/// taken in (x, y) pairs. As in SVG, a sign or a second decimal point starts
+/// a new number, so `"10-5"` is 10 and −5 and `".5.5"` is 0.5 and 0.5.
+pub fn parse_points(text: &str) -> Vec<Point> {
+    numbers(text).chunks_exact(2).map(|c| Point { x: c[0], y: c[1] }).collect()
+}
+
+/// Every number in `text`, scanned with SVG's number grammar.
+fn numbers(text: &str) -> Vec<f64> {
+    let bytes = text.as_bytes();
+    let digits = |mut i: usize| {
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        i
+    };
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let sign = usize::from(matches!(bytes[i], b'+' | b'-'));
+        let int = digits(start + sign);
+        let mut end = int;
+        if bytes.get(end) == Some(&b'.') {
+            end = digits(end + 1);
+        }
+        // A mantissa needs a digit on one side of the point
+        if int == start + sign && end <= int + 1 {
+            i = start + 1;
+            continue;
+        }
+        if matches!(bytes.get(end), Some(b'e' | b'E')) {
+            let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+            let exponent = digits(end + 1 + sign);
+            if exponent > end + 1 + sign {
+                end = exponent;
+            }
+        }
+        out.extend(text[start..end].parse::<f64>().ok());
+        i = end;
+    }
+    out
+}
+
+/// Resample a closed polyline to `n` points evenly spaced by arc length.
+pub fn resample_closed(path: &[Point], n: usize) -> Vec<Point> {
+    if path.len() < 2 || n == 0 {
+        return path.to_vec();
+    }
+    let mut closed = path.to_vec();
+    closed.push(path[0]);
+    let mut cumulative = vec![0.0];
+    for w in closed.windows(2) {
+        let d = (w[1].x - w[0].x).hypot(w[1].y - w[0].y);
+        cumulative.push(cumulative.last().unwrap_or(&0.0) + d);
+    }
+    let total = *cumulative.last().unwrap_or(&0.0);
+    if total <= 0.0 {
+        return vec![path[0]; n];
+    }
+    let mut out = Vec::with_capacity(n);
+    let mut seg = 0;
+    for i in 0..n {
+        let target = total * i as f64 / n as f64;
+        while seg + 1 < cumulative.len() - 1 && cumulative[seg + 1] < target {
+            seg += 1;
+        }
+        let len = cumulative[seg + 1] - cumulative[seg];
+        let f = if len > 0.0 { (target - cumulative[seg]) / len } else { 0.0 };
+        let (a, b) = (closed[seg], closed[seg + 1]);
+        out.push(Point { x: a.x + (b.x - a.x) * f, y: a.y + (b.y - a.y) * f });
+    }
+    out
+}
+
+/// Discrete Fourier transform of a closed path, sorted by decreasing amplitude.
+///
+/// Frequencies run from -N/2 to N/2 so the circles spin both ways, which is
+/// what lets a handful of terms trace a compact shape.
+pub fn fourier_series(path: &[Point]) -> Vec<Coefficient> {
+    let n = path.len();
+    if n == 0 {
+        return vec![];
+    }
+    let half = n as i64 / 2;
+    let mut coeffs: Vec<Coefficient> = (-half..n as i64 - half)
+        .map(|k| {
+            let (mut re, mut im) = (0.0, 0.0);
+            for (j, p) in path.iter().enumerate() {
+                let (s, c) = (-2.0 * PI * k as f64 * j as f64 / n as f64).sin_cos();
+                re += p.x * c - p.y * s;
+                im += p.x * s + p.y * c;
+            }
+            Coefficient { frequency: k, re: re / n as f64, im: im / n as f64 }
+        })
+        .collect();
+    coeffs.sort_by(|a, b| b.amplitude().total_cmp(&a.amplitude()));
+    coeffs
+}
+
+/// Centers of the epicycle chain at time t, starting at the origin.
+///
+/// The returned list has one more point than there are terms; the last point
+/// is the pen position.
+pub fn epicycle_chain(coeffs: &[Coefficient], t: f64) -> Vec<Point> {
+    let mut chain = Vec::with_capacity(coeffs.len() + 1);
+    let mut pos = Point { x: 0.0, y: 0.0 };
+    chain.push(pos);
+    for c in coeffs {
+        let d = c.at(t);
+        pos = Point { x: pos.x + d.x, y: pos.y + d.y };
+        chain.push(pos);
+    }
+    chain
+}
+
+/// Reconstruct the path from the given terms at `samples` evenly spaced times.
+pub fn reconstruct(coeffs: &[Coefficient], samples: usize) -> Vec<Point> {
+    (0..samples)
+        .map(|i| {
+            let t = i as f64 / samples as f64;
+            coeffs.iter().fold(Point { x: 0.0, y: 0.0 }, |acc, c| {
+                let d = c.at(t);
+                Point { x: acc.x + d.x, y: acc.y + d.y }
+            })
+        })
+        .collect()
+}
+
+/// A sample leaf outline built from the superformula, for demos.
+pub fn leaf_outline(n: usize) -> Vec<Point> {
+    use super::superformula::{outline, Preset};
+    outline(&Preset::Leaf.params(), n)
+}
+
+impl Generator for Vec<Coefficient> {
+    /// The closed curve traced by the full set of epicycles.
+    fn generate(&self) -> Geometry {
+        Geometry::Polygons(vec![reconstruct(self, 512)])
+    }
+}
+
+/// Scale that fits points centered on the origin into a `size`-pixel square.
+fn fit_scale<'a>(size: f64, points: impl Iterator<Item = &'a Point>) -> f64 {
+    let extent = points.map(|p| p.x.abs().max(p.y.abs())).fold(1e-9, f64::max);
+    (size / 2.0 - 30.0) / extent
+}
+
+/// Render the epicycles at time t ∈ [0, 1]: the target outline, the path traced
+/// so far, and the chain of circles.
+pub fn frame_to_svg(target: &[Point], coeffs: &[Coefficient], t: f64) -> String {
+    frame_to_svg_with_theme(target, coeffs, t, &Theme::dark())
+}
+
+/// [`frame_to_svg`] in `theme`; with a palette, the target takes its first
+/// stop, the circles and arm steps up it and the traced path its last.
+pub fn frame_to_svg_with_theme(target: &[Point], coeffs: &[Coefficient], t: f64, theme: &Theme) -> String {
+    let size = 800.0;
+    let traced = reconstruct(coeffs, 600);
+    let chain = epicycle_chain(coeffs, t);
+    let scale = fit_scale(size, target.iter().chain(chain.iter()));
+    let c = size / 2.0;
+    let map = |p: &Point| format!("{:.1},{:.1}", c + p.x * scale, c - p.y * scale);
+    let join = |pts: &[Point]| pts.iter().map(map).collect::<Vec<_>>().join(" ");
+
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}<polygon points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-dasharray="4 4"/>
+"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        join(target),
+        theme.color(0.0, "#2e7d32"),
+        theme.stroke_width(1.0)
+    );
+    let drawn = ((t.clamp(0.0, 1.0) * traced.len() as f64) as usize).min(traced.len());
+    if drawn > 1 {
+        svg.push_str(&format!(
+            r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>
+"##,
+            join(&traced[..drawn]),
+            theme.color(1.0, "#ffd54f"),
+            theme.stroke_width(2.0)
+        ));
+    }
+    for (coef, center) in coeffs.iter().zip(chain.iter()) {
+        let r = coef.amplitude() * scale;
+        if r < 0.5 {
+            continue;
+        }
+        svg.push_str(&format!(
+            r##"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="none" stroke="{}" stroke-width="{}" opacity="0.5"/>
+"##,
+            c + center.x * scale,
+            c - center.y * scale,
+            r,
+            theme.color(0.4, "#4fc3f7"),
+            theme.stroke_width(0.6)
+        ));
+    }
+    svg.push_str(&format!(
+        r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>
+{}</svg>"##,
+        join(&chain),
+        theme.color(0.7, "#e1f5fe"),
+        theme.stroke_width(1.0),
+        theme.foreground_end()
+    ));
+    svg
+}
+
+/// Render `frames` animation frames evenly spaced over one period.
+pub fn animation_frames(target: &[Point], coeffs: &[Coefficient], frames: usize) -> Vec<String> {
+    animation_frames_with_theme(target, coeffs, frames, &Theme::dark())
+}
+
+/// [`animation_frames`] in `theme`.
+pub fn animation_frames_with_theme(target: &[Point], coeffs: &[Coefficient], frames: usize, theme: &Theme) -> Vec<String> {
+    (1..=frames)
+        .map(|i| frame_to_svg_with_theme(target, coeffs, i as f64 / frames as f64, theme))
+        .collect()
+}
+
+/// A single self-playing SVG: the circles turn and the pen draws the curve
+/// over `duration` seconds, looping forever. Motion is sampled at `keyframes`
+/// evenly spaced times and interpolated linearly by the viewer (SMIL).
+pub fn animated_svg(target: &[Point], coeffs: &[Coefficient], duration: f64, keyframes: usize) -> String {
+    animated_svg_with_theme(target, coeffs, duration, keyframes, &Theme::dark())
+}
+
+/// [`animated_svg`] in `theme`, colored as [`frame_to_svg_with_theme`].
+pub fn animated_svg_with_theme(target: &[Point], coeffs: &[Coefficient], duration: f64, keyframes: usize, theme: &Theme) -> String {
+    let size = 800.0;
+    let keyframes = keyframes.max(2);
+    let chains: Vec<Vec<Point>> =
+        (0..=keyframes).map(|i| epicycle_chain(coeffs, i as f64 / keyframes as f64)).collect();
+    let scale = fit_scale(size, target.iter().chain(chains.iter().flatten()));
+    let c = size / 2.0;
+    let map = |p: &Point| format!("{:.1},{:.1}", c + p.x * scale, c - p.y * scale);
+    let join = |pts: &[Point]| pts.iter().map(map).collect::<Vec<_>>().join(" ");
+    let timing = format!(r#"dur="{duration}s" repeatCount="indefinite""#);
+
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}<polygon points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-dasharray="4 4"/>
+<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" pathLength="1" stroke-dasharray="1 1">
+<animate attributeName="stroke-dashoffset" values="1;0" {timing}/>
+</polyline>
+"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        join(target),
+        theme.color(0.0, "#2e7d32"),
+        theme.stroke_width(1.0),
+        join(&reconstruct(coeffs, 600)),
+        theme.color(1.0, "#ffd54f"),
+        theme.stroke_width(2.0)
+    );
+    for (k, coef) in coeffs.iter().enumerate() {
+        let r = coef.amplitude() * scale;
+        if r < 0.5 {
+            continue;
+        }
+        let values = |axis: &dyn Fn(&Point) -> f64| {
+            chains.iter().map(|chain| format!("{:.1}", axis(&chain[k]))).collect::<Vec<_>>().join(";")
+        };
+        svg.push_str(&format!(
+            r##"<circle r="{r:.1}" fill="none" stroke="{}" stroke-width="{}" opacity="0.5">
+<animate attributeName="cx" values="{}" {timing}/>
+<animate attributeName="cy" values="{}" {timing}/>
+</circle>
+"##,
+            theme.color(0.4, "#4fc3f7"),
+            theme.stroke_width(0.6),
+            values(&|p| c + p.x * scale),
+            values(&|p| c - p.y * scale)
+        ));
+    }
+    let arms: Vec<String> = chains.iter().map(|chain| join(chain)).collect();
+    svg.push_str(&format!(
+        r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}">
+<animate attributeName="points" values="{}" {timing}/>
+</polyline>
+{}</svg>"##,
+        arms[0],
+        theme.color(0.7, "#e1f5fe"),
+        theme.stroke_width(1.0),
+        arms.join(";"),
+        theme.foreground_end()
+    ));
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Vec<Point> {
+        vec![
+            Point { x: -1.0, y: -1.0 },
+            Point { x: 1.0, y: -1.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: -1.0, y: 1.0 },
+        ]
+    }
+
+    #[test]
+    fn test_parse_points() {
+        let a = parse_points("0,0 10,0 10,10");
+        assert_eq!(a.len(), 3);
+        let b = parse_points("M0 0 L10 -5 L3.5 2 Z");
+        assert_eq!(b.len(), 3);
+        assert_eq!(b[1], Point { x: 10.0, y: -5.0 });
+        let c = parse_points("M10-5L.5.5 1e-1-2E+1");
+        assert_eq!(c, vec![Point { x: 10.0, y: -5.0 }, Point { x: 0.5, y: 0.5 }, Point { x: 0.1, y: -20.0 }]);
+    }
+
+    #[test]
+    fn test_resample_even_spacing() {
+        let pts = resample_closed(&square(), 16);
+        assert_eq!(pts.len(), 16);
+        // Perimeter 8 / 16 samples = 0.5 spacing
+        for w in pts.windows(2) {
+            let d = (w[1].x - w[0].x).hypot(w[1].y - w[0].y);
+            assert!((d - 0.5).abs() < 1e-9, "Spacing {}", d);
+        }
+    }
+
+    #[test]
+    fn test_circle_single_term() {
+        let circle: Vec<Point> = (0..64)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / 64.0;
+                Point { x: 3.0 * a.cos(), y: 3.0 * a.sin() }
+            })
+            .collect();
+        let coeffs = fourier_series(&circle);
+        assert_eq!(coeffs[0].frequency, 1);
+        assert!((coeffs[0].amplitude() - 3.0).abs() < 1e-9);
+        assert!(coeffs[1].amplitude() < 1e-9);
+    }
+
+    #[test]
+    fn test_full_series_reconstructs_exactly() {
+        let path = resample_closed(&leaf_outline(200), 64);
+        let coeffs = fourier_series(&path);
+        let rebuilt = reconstruct(&coeffs, 64);
+        for (a, b) in path.iter().zip(rebuilt.iter()) {
+            assert!((a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_more_terms_less_error() {
+        let path = resample_closed(&square(), 128);
+        let coeffs = fourier_series(&path);
+        let error = |terms: usize| {
+            reconstruct(&coeffs[..terms], 128)
+                .iter()
+                .zip(path.iter())
+                .map(|(a, b)| (a.x - b.x).hypot(a.y - b.y))
+                .sum::<f64>()
+        };
+        assert!(error(20) < error(5));
+        assert!(error(5) < error(1));
+    }
+
+    #[test]
+    fn test_chain_ends_at_pen() {
+        let path = resample_closed(&square(), 32);
+        let coeffs = fourier_series(&path);
+        let chain = epicycle_chain(&coeffs, 0.0);
+        assert_eq!(chain.len(), coeffs.len() + 1);
+        let pen = chain.last().unwrap();
+        assert!((pen.x - path[0].x).abs() < 1e-9 && (pen.y - path[0].y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_animation_frames() {
+        let target = resample_closed(&leaf_outline(100), 64);
+        let coeffs = fourier_series(&target);
+        let frames = animation_frames(&target, &coeffs[..10], 5);
+        assert_eq!(frames.len(), 5);
+        assert!(frames.iter().all(|f| f.contains("<circle")));
+    }
+
+    #[test]
+    fn test_animated_svg() {
+        let target = resample_closed(&leaf_outline(100), 64);
+        let coeffs = fourier_series(&target);
+        let svg = animated_svg(&target, &coeffs[..10], 8.0, 30);
+        assert!(svg.contains(r#"dur="8s" repeatCount="indefinite""#));
+        assert!(svg.contains(r#"attributeName="stroke-dashoffset""#));
+        // One cx and one cy track per visible circle, plus the arm and the pen
+        let circles = svg.matches("<circle").count();
+        assert!(circles > 0);
+        assert_eq!(svg.matches("<animate").count(), 2 * circles + 2);
+        // The loop closes: first and last keyframes of the arm coincide
+        let arm = svg.rsplit(r#"attributeName="points" values=""#).next().unwrap();
+        let frames: Vec<&str> = arm.split('"').next().unwrap().split(';').collect();
+        assert_eq!(frames.len(), 31);
+        assert_eq!(frames[0], frames[30]);
+    }
+}
@@ -0,0 +1,362 @@
+//! Firefly synchronization — the Kuramoto model.
+//!
+//! Along rivers in Southeast Asia, thousands of male fireflies flash in
+//! unison. Each insect is an oscillator with its own natural rhythm ωᵢ that
+//! nudges its phase toward the neighbors it can see:
+//!   dθᵢ/dt = ωᵢ + (K / nᵢ) Σⱼ sin(θⱼ − θᵢ)
+//! The order parameter r e^(iψ) = (1/N) Σ e^(iθⱼ) measures coherence. Below
+//! a critical coupling K_c the swarm flickers randomly (r ≈ 0); above it a
+//! synchronized cluster spontaneously forms and r jumps toward 1.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::Theme;
+use super::fractals::{Point, SimpleRng};
+
+/// Parameters for a swarm of coupled fireflies in the unit square.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KuramotoParams {
+    /// Number of fireflies
+    pub count: usize,
+    /// Coupling strength K
+    pub coupling: f64,
+    /// Mean natural frequency (radians per unit time)
+    pub mean_frequency: f64,
+    /// Half-width γ of the uniform natural-frequency distribution
+    pub frequency_spread: f64,
+    /// How far a firefly can see (unit-square lengths; ≥ √2 is all-to-all)
+    pub coupling_radius: f64,
+    /// Integration time step
+    pub dt: f64,
+}
+
+impl Default for KuramotoParams {
+    fn default() -> Self {
+        Self {
+            count: 300,
+            coupling: 1.5,
+            mean_frequency: 2.0 * PI,
+            frequency_spread: 0.5,
+            coupling_radius: 0.3,
+            dt: 0.02,
+        }
+    }
+}
+
+/// Largest swarm [`KuramotoParams::validate`] accepts; neighbor lists are O(n²).
+pub const MAX_FIREFLIES: usize = 10_000;
+
+impl KuramotoParams {
+    /// Critical coupling for all-to-all coupling with uniform frequencies:
+    /// K_c = 2 / (π g(0)) = 4γ / π.
+    pub fn critical_coupling(&self) -> f64 {
+        4.0 * self.frequency_spread / PI
+    }
+
+    /// Reject empty or oversized swarms, a negative frequency spread, a
+    /// non-positive radius, and time steps too coarse for Heun's method.
+    pub fn validate(&self) -> Result<()> {
+        check_count("count", self.count, MAX_FIREFLIES)?;
+        check_range("coupling", self.coupling, f64::MIN..=f64::MAX, "a finite number")?;
+        check_range("frequency_spread", self.frequency_spread, 0.0..=f64::MAX, "a non-negative number")?;
+        check_positive("coupling_radius", self.coupling_radius)?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.5, "0 < dt ≤ 0.5")
+    }
+}
+
+/// A field of fireflies, each with a position, phase, and natural frequency.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Swarm {
+    pub positions: Vec<Point>,
+    pub phases: Vec<f64>,
+    pub frequencies: Vec<f64>,
+    /// Indices of the fireflies each one can see
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl Swarm {
+    /// Scatter fireflies uniformly with random phases.
+    pub fn new(params: &KuramotoParams, seed: u64) -> Self {
+        let mut rng = SimpleRng::new(seed);
+        let n = params.count;
+        let positions: Vec<Point> = (0..n).map(|_| Point { x: rng.next_f64(), y: rng.next_f64() }).collect();
+        let phases = (0..n).map(|_| rng.next_f64() * 2.0 * PI).collect();
+        let frequencies = (0..n)
+            .map(|_| params.mean_frequency + params.frequency_spread * (2.0 * rng.next_f64() - 1.0))
+            .collect();
+        let r2 = params.coupling_radius * params.coupling_radius;
+        let neighbors = (0..n)
+            .map(|i| {
+                (0..n)
+                    .filter(|&j| {
+                        let (dx, dy) = (positions[i].x - positions[j].x, positions[i].y - positions[j].y);
+                        j != i && dx * dx + dy * dy <= r2
+                    })
+                    .collect()
+            })
+            .collect();
+        Swarm { positions, phases, frequencies, neighbors }
+    }
+
+    /// Phase velocities dθ/dt for the given phases.
+    fn velocities(&self, phases: &[f64], coupling: f64) -> Vec<f64> {
+        (0..phases.len())
+            .map(|i| {
+                let nbrs = &self.neighbors[i];
+                let pull: f64 = nbrs.iter().map(|&j| (phases[j] - phases[i]).sin()).sum();
+                self.frequencies[i] + coupling * pull / nbrs.len().max(1) as f64
+            })
+            .collect()
+    }
+
+    /// Advance one step with second-order Runge–Kutta (Heun).
+    pub fn step(&mut self, params: &KuramotoParams) {
+        let dt = params.dt;
+        let k1 = self.velocities(&self.phases, params.coupling);
+        let mid: Vec<f64> = self.phases.iter().zip(&k1).map(|(p, v)| p + dt * v).collect();
+        let k2 = self.velocities(&mid, params.coupling);
+        for ((p, a), b) in self.phases.iter_mut().zip(k1).zip(k2) {
+            *p = (*p + 0.5 * dt * (a + b)).rem_euclid(2.0 * PI);
+        }
+    }
+
+    /// Order parameter (r, ψ): coherence in [0, 1] and the mean phase.
+    pub fn order_parameter(&self) -> (f64, f64) {
+        let n = self.phases.len().max(1) as f64;
+        let (s, c) = self
+            .phases
+            .iter()
+            .fold((0.0, 0.0), |(s, c), p| (s + p.sin(), c + p.cos()));
+        ((s / n).hypot(c / n), s.atan2(c))
+    }
+
+    /// Run for n steps, returning r after each step.
+    pub fn simulate(&mut self, params: &KuramotoParams, steps: usize) -> Vec<f64> {
+        (0..steps)
+            .map(|_| {
+                self.step(params);
+                self.order_parameter().0
+            })
+            .collect()
+    }
+
+    /// Run for n steps, recording every firefly's phase every `every` steps.
+    pub fn simulate_with_history(&mut self, params: &KuramotoParams, steps: usize, every: usize) -> Vec<Vec<f64>> {
+        let every = every.max(1);
+        let mut history = vec![self.phases.clone()];
+        for i in 1..=steps {
+            self.step(params);
+            if i % every == 0 {
+                history.push(self.phases.clone());
+            }
+        }
+        history
+    }
+
+    /// Brightness of each firefly: a short flash as its phase passes zero.
+    pub fn brightness(&self) -> Vec<f64> {
+        self.phases.iter().map(|p| ((p.cos() + 1.0) / 2.0).powi(8)).collect()
+    }
+}
+
+impl Generator for Swarm {
+    /// Firefly positions; phases are left out.
+    fn generate(&self) -> Geometry {
+        Geometry::Points(self.positions.clone())
+    }
+}
+
+/// Render the field: each firefly glows with a hue for its phase and
+/// brightness for its flash.
+pub fn field_to_svg(swarm: &Swarm) -> String {
+    field_to_svg_with_theme(swarm, &Theme::dark())
+}
+
+/// [`field_to_svg`] in `theme`; with a palette, phase runs along it.
+pub fn field_to_svg_with_theme(swarm: &Swarm, theme: &Theme) -> String {
+    let size = 800.0;
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
+    );
+    for ((p, phase), glow) in swarm.positions.iter().zip(&swarm.phases).zip(swarm.brightness()) {
+        svg.push_str(&format!(
+            r##"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}" opacity="{:.2}"/>
+"##,
+            20.0 + p.x * (size - 40.0),
+            20.0 + p.y * (size - 40.0),
+            3.0 + 5.0 * glow,
+            theme.color(phase / (2.0 * PI), crate::render::hsl(phase.to_degrees(), 90.0, 45.0 + 30.0 * glow)),
+            0.4 + 0.6 * glow
+        ));
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Render phase as color over time: one column per firefly (sorted by
+/// natural frequency), one row per recorded time, time flowing downward.
+pub fn timeline_to_svg(swarm: &Swarm, history: &[Vec<f64>]) -> String {
+    timeline_to_svg_with_theme(swarm, history, &Theme::dark())
+}
+
+/// [`timeline_to_svg`] in `theme`; with a palette, phase runs along it.
+pub fn timeline_to_svg_with_theme(swarm: &Swarm, history: &[Vec<f64>], theme: &Theme) -> String {
+    let mut order: Vec<usize> = (0..swarm.frequencies.len()).collect();
+    order.sort_by(|&a, &b| swarm.frequencies[a].total_cmp(&swarm.frequencies[b]));
+    let cols = order.len();
+    let cell = (800.0 / cols.max(1) as f64).max(1.0);
+    // Quantize phases to 24 hues so neighboring cells merge into runs
+    let values: Vec<f64> = history
+        .iter()
+        .flat_map(|row| order.iter().map(move |&i| (row[i] / (2.0 * PI) * 24.0).floor()))
+        .collect();
+    let color = |h: f64| theme.color(h / 23.0, crate::render::hsl(h * 15.0, 80.0, 55.0));
+    crate::render::raster_svg_with_theme(cols, history.len(), cell, &values, color, theme)
+}
+
+/// Plot the order parameter r(t) with the 0 and 1 guides.
+pub fn order_plot_to_svg(order: &[f64], dt: f64) -> String {
+    order_plot_to_svg_with_theme(order, dt, &Theme::dark())
+}
+
+/// [`order_plot_to_svg`] in `theme`; with a palette, the guides take its
+/// first stop and the curve its last.
+pub fn order_plot_to_svg_with_theme(order: &[f64], dt: f64, theme: &Theme) -> String {
+    let (w, h, m) = (800.0, 400.0, 40.0);
+    let n = order.len().max(2);
+    let coords: Vec<String> = order
+        .iter()
+        .enumerate()
+        .map(|(i, r)| format!("{:.1},{:.1}", m + (w - 2.0 * m) * i as f64 / (n - 1) as f64, h - m - (h - 2.0 * m) * r))
+        .collect();
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+<rect width="{w}" height="{h}" fill="{}"/>
+{}<line x1="{m}" y1="{top}" x2="{right}" y2="{top}" stroke="{}" stroke-dasharray="4 4"/>
+<line x1="{m}" y1="{bottom}" x2="{right}" y2="{bottom}" stroke="{}"/>
+<text x="{m}" y="{label}" fill="{}" font-family="sans-serif" font-size="12">r(t), t = 0 … {t:.1}</text>
+<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>
+{}</svg>"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        theme.color(0.0, "#333"),
+        theme.color(0.0, "#666"),
+        theme.color(0.0, "#888"),
+        coords.join(" "),
+        theme.color(1.0, "#ffeb3b"),
+        theme.stroke_width(2.0),
+        theme.foreground_end(),
+        top = m,
+        bottom = h - m,
+        right = w - m,
+        label = m - 10.0,
+        t = order.len() as f64 * dt,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn global(coupling: f64) -> KuramotoParams {
+        KuramotoParams { count: 100, coupling, coupling_radius: 2.0, ..Default::default() }
+    }
+
+    #[test]
+    fn test_initial_state() {
+        let swarm = Swarm::new(&KuramotoParams::default(), 42);
+        assert_eq!(swarm.phases.len(), 300);
+        let p = KuramotoParams::default();
+        for &w in &swarm.frequencies {
+            assert!((w - p.mean_frequency).abs() <= p.frequency_spread);
+        }
+    }
+
+    #[test]
+    fn test_order_parameter_bounds() {
+        let mut swarm = Swarm::new(&global(0.0), 1);
+        assert!(swarm.order_parameter().0 < 0.2, "Random phases should be incoherent");
+        swarm.phases.iter_mut().for_each(|p| *p = 1.0);
+        let (r, psi) = swarm.order_parameter();
+        assert!((r - 1.0).abs() < 1e-12);
+        assert!((psi - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_strong_coupling_synchronizes() {
+        let params = global(3.0);
+        let mut swarm = Swarm::new(&params, 2);
+        let order = swarm.simulate(&params, 1000);
+        assert!(order.last().unwrap() > &0.9, "r = {}", order.last().unwrap());
+    }
+
+    #[test]
+    fn test_no_coupling_stays_incoherent() {
+        let params = global(0.0);
+        let mut swarm = Swarm::new(&params, 3);
+        let order = swarm.simulate(&params, 600);
+        let mean = order[300..].iter().sum::<f64>() / 300.0;
+        assert!(mean < 0.3, "r = {}", mean);
+    }
+
+    #[test]
+    fn test_transition_above_critical_coupling() {
+        let below = global(0.5 * global(0.0).critical_coupling());
+        let above = global(3.0 * global(0.0).critical_coupling());
+        let final_r = |p: &KuramotoParams| {
+            let mut swarm = Swarm::new(p, 4);
+            let order = swarm.simulate(p, 800);
+            order[500..].iter().sum::<f64>() / 300.0
+        };
+        assert!(final_r(&above) > final_r(&below) + 0.4);
+    }
+
+    #[test]
+    fn test_local_coupling_neighbors() {
+        let params = KuramotoParams { coupling_radius: 0.1, ..Default::default() };
+        let swarm = Swarm::new(&params, 5);
+        for (i, nbrs) in swarm.neighbors.iter().enumerate() {
+            for &j in nbrs {
+                let (a, b) = (swarm.positions[i], swarm.positions[j]);
+                assert!((a.x - b.x).hypot(a.y - b.y) <= 0.1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_history_rows() {
+        let params = global(1.0);
+        let mut swarm = Swarm::new(&params, 6);
+        let history = swarm.simulate_with_history(&params, 100, 10);
+        assert_eq!(history.len(), 11);
+        assert!(history.iter().all(|row| row.len() == 100));
+    }
+
+    #[test]
+    fn test_svg_outputs() {
+        let params = global(2.0);
+        let mut swarm = Swarm::new(&params, 7);
+        let history = swarm.simulate_with_history(&params, 50, 5);
+        assert_eq!(field_to_svg(&swarm).matches("<circle").count(), 100);
+        assert!(timeline_to_svg(&swarm, &history).contains("<rect"));
+        let order = swarm.simulate(&params, 20);
+        assert!(order_plot_to_svg(&order, params.dt).contains("<polyline"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(KuramotoParams::default().validate().is_ok());
+        assert!(KuramotoParams { count: MAX_FIREFLIES + 1, ..Default::default() }.validate().is_err());
+        assert!(KuramotoParams { dt: 0.0, ..Default::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,511 @@
+//! Flocking — starling murmurations from three local rules.
+//!
+//! Craig Reynolds' boids (1987) steer using only their neighbors:
+//!   separation — avoid crowding nearby flockmates
+//!   alignment  — match the average heading of neighbors
+//!   cohesion   — move toward the local center of mass
+//! An optional predator (a falcon) chases the flock, and the evasive waves
+//! it triggers are what make real murmurations ripple.
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::{self, Theme};
+use super::fractals::{Point, SimpleRng};
+
+/// A single bird (or the predator): position and velocity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Boid {
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+}
+
+impl Boid {
+    pub fn speed(&self) -> f64 {
+        self.vx.hypot(self.vy)
+    }
+}
+
+/// Parameters for the boids simulation.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlockParams {
+    /// Number of boids
+    pub count: usize,
+    /// Radius within which neighbors are considered for alignment and cohesion
+    pub perception_radius: f64,
+    /// Radius within which boids push each other apart
+    pub separation_radius: f64,
+    pub separation_weight: f64,
+    pub alignment_weight: f64,
+    pub cohesion_weight: f64,
+    pub min_speed: f64,
+    pub max_speed: f64,
+    /// Whether a predator chases the flock
+    pub predator: bool,
+    /// Distance at which boids start fleeing the predator
+    pub flee_radius: f64,
+    pub flee_weight: f64,
+    /// Size of the arena; boids turn back softly near the edges
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Default for FlockParams {
+    fn default() -> Self {
+        Self {
+            count: 300,
+            perception_radius: 40.0,
+            separation_radius: 12.0,
+            separation_weight: 1.5,
+            alignment_weight: 1.0,
+            cohesion_weight: 0.8,
+            min_speed: 2.0,
+            max_speed: 4.0,
+            predator: false,
+            flee_radius: 80.0,
+            flee_weight: 3.0,
+            width: 800.0,
+            height: 600.0,
+        }
+    }
+}
+
+/// Largest flock [`FlockParams::validate`] accepts; neighbor search is O(n²).
+pub const MAX_BOIDS: usize = 10_000;
+
+impl FlockParams {
+    /// Reject empty or oversized flocks, non-positive radii and arenas, and
+    /// a speed range with min above max.
+    pub fn validate(&self) -> Result<()> {
+        check_count("count", self.count, MAX_BOIDS)?;
+        check_positive("perception_radius", self.perception_radius)?;
+        check_positive("separation_radius", self.separation_radius)?;
+        check_positive("max_speed", self.max_speed)?;
+        check_range("min_speed", self.min_speed, 0.0..=self.max_speed, "between 0 and max_speed")?;
+        check_positive("width", self.width)?;
+        check_positive("height", self.height)
+    }
+}
+
+/// A flock of boids with an optional predator.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Flock {
+    pub boids: Vec<Boid>,
+    pub predator: Option<Boid>,
+}
+
+impl Flock {
+    /// Scatter boids with random headings in the middle of the arena.
+    pub fn new(params: &FlockParams, seed: u64) -> Self {
+        let mut rng = SimpleRng::new(seed);
+        let boids = (0..params.count)
+            .map(|_| {
+                let angle = rng.next_f64() * core::f64::consts::TAU;
+                let speed = params.min_speed + rng.next_f64() * (params.max_speed - params.min_speed);
+                Boid {
+                    x: params.width * (0.25 + 0.5 * rng.next_f64()),
+                    y: params.height * (0.25 + 0.5 * rng.next_f64()),
+                    vx: speed * angle.cos(),
+                    vy: speed * angle.sin(),
+                }
+            })
+            .collect();
+        let predator = params.predator.then_some(Boid { x: 0.0, y: 0.0, vx: 1.0, vy: 1.0 });
+        Flock { boids, predator }
+    }
+
+    /// Advance every boid by one time step.
+    pub fn step(&mut self, params: &FlockParams) {
+        let perception2 = params.perception_radius.powi(2);
+        let separation2 = params.separation_radius.powi(2);
+        let margin = params.width.min(params.height) * 0.1;
+
+        let mut next = self.boids.clone();
+        for (i, b) in self.boids.iter().enumerate() {
+            let (mut sep_x, mut sep_y) = (0.0, 0.0);
+            let (mut ali_x, mut ali_y) = (0.0, 0.0);
+            let (mut coh_x, mut coh_y) = (0.0, 0.0);
+            let mut neighbors = 0;
+            for (j, o) in self.boids.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let (dx, dy) = (o.x - b.x, o.y - b.y);
+                let d2 = dx * dx + dy * dy;
+                if d2 < perception2 {
+                    ali_x += o.vx;
+                    ali_y += o.vy;
+                    coh_x += o.x;
+                    coh_y += o.y;
+                    neighbors += 1;
+                }
+                if d2 < separation2 && d2 > 0.0 {
+                    sep_x -= dx / d2;
+                    sep_y -= dy / d2;
+                }
+            }
+
+            let (mut ax, mut ay) = (sep_x * params.separation_weight, sep_y * params.separation_weight);
+            if neighbors > 0 {
+                let n = neighbors as f64;
+                ax += (ali_x / n - b.vx) * params.alignment_weight * 0.05;
+                ay += (ali_y / n - b.vy) * params.alignment_weight * 0.05;
+                ax += (coh_x / n - b.x) * params.cohesion_weight * 0.005;
+                ay += (coh_y / n - b.y) * params.cohesion_weight * 0.005;
+            }
+            if let Some(p) = &self.predator {
+                let (dx, dy) = (b.x - p.x, b.y - p.y);
+                let d = dx.hypot(dy);
+                if d < params.flee_radius && d > 0.0 {
+                    let push = (1.0 - d / params.flee_radius) * params.flee_weight;
+                    ax += dx / d * push;
+                    ay += dy / d * push;
+                }
+            }
+            // Soft walls: turn back toward the arena
+            if b.x < margin { ax += 0.2; }
+            if b.x > params.width - margin { ax -= 0.2; }
+            if b.y < margin { ay += 0.2; }
+            if b.y > params.height - margin { ay -= 0.2; }
+
+            let (vx, vy) = limit_speed(b.vx + ax, b.vy + ay, params.min_speed, params.max_speed);
+            next[i] = Boid { x: b.x + vx, y: b.y + vy, vx, vy };
+        }
+
+        if let Some(p) = self.predator {
+            // The predator chases the nearest boid, slightly faster than the flock
+            let target = next
+                .iter()
+                .min_by(|a, b| {
+                    let da = (a.x - p.x).hypot(a.y - p.y);
+                    let db = (b.x - p.x).hypot(b.y - p.y);
+                    da.total_cmp(&db)
+                })
+                .copied();
+            if let Some(t) = target {
+                let (dx, dy) = (t.x - p.x, t.y - p.y);
+                let d = dx.hypot(dy).max(1e-9);
+                let speed = params.max_speed * 1.2;
+                let (vx, vy) = limit_speed(p.vx + dx / d * 0.5, p.vy + dy / d * 0.5, 0.0, speed);
+                self.predator = Some(Boid { x: p.x + vx, y: p.y + vy, vx, vy });
+            }
+        }
+        self.boids = next;
+    }
+
+    /// Run the simulation, recording every boid's path.
+    ///
+    /// Returns one trail per boid with `steps + 1` positions.
+    pub fn simulate_with_trails(&mut self, params: &FlockParams, steps: usize) -> Vec<Vec<Point>> {
+        let mut trails: Vec<Vec<Point>> = self.boids.iter().map(|b| vec![Point { x: b.x, y: b.y }]).collect();
+        for _ in 0..steps {
+            self.step(params);
+            for (trail, b) in trails.iter_mut().zip(self.boids.iter()) {
+                trail.push(Point { x: b.x, y: b.y });
+            }
+        }
+        trails
+    }
+
+    /// Run the simulation for n steps.
+    pub fn simulate(&mut self, params: &FlockParams, steps: usize) {
+        for _ in 0..steps {
+            self.step(params);
+        }
+    }
+
+    /// Polarization: length of the mean unit heading, 0 (disordered) to 1 (aligned).
+    pub fn polarization(&self) -> f64 {
+        if self.boids.is_empty() {
+            return 0.0;
+        }
+        let (mut sx, mut sy) = (0.0, 0.0);
+        for b in &self.boids {
+            let s = b.speed().max(1e-12);
+            sx += b.vx / s;
+            sy += b.vy / s;
+        }
+        sx.hypot(sy) / self.boids.len() as f64
+    }
+
+    /// Mean and standard deviation of each boid's nearest-neighbor distance.
+    pub fn nearest_neighbor_stats(&self) -> (f64, f64) {
+        if self.boids.len() < 2 {
+            return (0.0, 0.0);
+        }
+        let dists: Vec<f64> = self
+            .boids
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                self.boids
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, b)| (a.x - b.x).hypot(a.y - b.y))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+        let n = dists.len() as f64;
+        let mean = dists.iter().sum::<f64>() / n;
+        let var = dists.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n;
+        (mean, var.sqrt())
+    }
+}
+
+fn limit_speed(vx: f64, vy: f64, min: f64, max: f64) -> (f64, f64) {
+    let s = vx.hypot(vy);
+    if s < 1e-12 {
+        return (min, 0.0);
+    }
+    let clamped = s.clamp(min, max);
+    (vx / s * clamped, vy / s * clamped)
+}
+
+/// Accumulate point density on a `cols × rows` grid covering the arena.
+pub fn density_grid(points: &[Point], params: &FlockParams, cols: usize, rows: usize) -> Vec<f64> {
+    let mut grid = vec![0.0; cols * rows];
+    for p in points {
+        let cx = (p.x / params.width * cols as f64).floor();
+        let cy = (p.y / params.height * rows as f64).floor();
+        if cx >= 0.0 && cy >= 0.0 && (cx as usize) < cols && (cy as usize) < rows {
+            grid[cy as usize * cols + cx as usize] += 1.0;
+        }
+    }
+    grid
+}
+
+fn svg_header(params: &FlockParams, theme: &Theme) -> String {
+    let (w, h) = (params.width, params.height);
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+<rect width="{w}" height="{h}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
+    )
+}
+
+impl Generator for Flock {
+    /// Boid positions; the predator is left out.
+    fn generate(&self) -> Geometry {
+        Geometry::Points(self.boids.iter().map(|b| Point { x: b.x, y: b.y }).collect())
+    }
+}
+
+/// Render the flock as oriented triangles (plus the predator in red).
+pub fn snapshot_to_svg(flock: &Flock, params: &FlockParams) -> String {
+    snapshot_to_svg_with_theme(flock, params, &Theme::dark())
+}
+
+/// [`snapshot_to_svg`] in `theme`; with a palette, boids take its middle
+/// and the predator its last stop.
+pub fn snapshot_to_svg_with_theme(flock: &Flock, params: &FlockParams, theme: &Theme) -> String {
+    let mut svg = svg_header(params, theme);
+    let triangle = |b: &Boid, size: f64| {
+        let a = b.vy.atan2(b.vx);
+        let pts: Vec<String> = [(0.0, 1.0), (2.5, 0.5), (-2.5, 0.5)]
+            .iter()
+            .map(|&(da, r): &(f64, f64)| {
+                let ang = a + da;
+                format!("{:.1},{:.1}", b.x + size * r * ang.cos(), b.y + size * r * ang.sin())
+            })
+            .collect();
+        pts.join(" ")
+    };
+    for b in &flock.boids {
+        svg.push_str(&format!(
+            r##"<polygon points="{}" fill="{}" opacity="0.85"/>
+"##,
+            triangle(b, 5.0),
+            theme.color(0.5, "#cfd8dc")
+        ));
+    }
+    if let Some(p) = &flock.predator {
+        svg.push_str(&format!(
+            r##"<polygon points="{}" fill="{}"/>
+"##,
+            triangle(p, 12.0),
+            theme.color(1.0, "#ff5252")
+        ));
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Render recorded trails, fading from transparent (old) to bright (recent).
+pub fn trails_to_svg(trails: &[Vec<Point>], params: &FlockParams) -> String {
+    trails_to_svg_with_theme(trails, params, &Theme::dark())
+}
+
+/// [`trails_to_svg`] in `theme`; with a palette, trails run along it from
+/// old to recent.
+pub fn trails_to_svg_with_theme(trails: &[Vec<Point>], params: &FlockParams, theme: &Theme) -> String {
+    let mut svg = svg_header(params, theme);
+    for trail in trails {
+        let segments = 4;
+        let len = trail.len();
+        for s in 0..segments {
+            let start = len * s / segments;
+            let end = (len * (s + 1) / segments + 1).min(len);
+            if end - start < 2 {
+                continue;
+            }
+            let coords: Vec<String> = trail[start..end].iter().map(|p| format!("{:.1},{:.1}", p.x, p.y)).collect();
+            svg.push_str(&format!(
+                r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-opacity="{:.2}"/>
+"##,
+                coords.join(" "),
+                theme.color(theme::place(s, segments), "#90caf9"),
+                theme.stroke_width(0.6),
+                0.1 + 0.5 * (s + 1) as f64 / segments as f64
+            ));
+        }
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Render accumulated positions as a log-scaled density map — the smoky
+/// look of a murmuration photographed at dusk.
+pub fn density_to_svg(points: &[Point], params: &FlockParams, cols: usize, rows: usize) -> String {
+    density_to_svg_with_theme(points, params, cols, rows, &Theme::dark())
+}
+
+/// [`density_to_svg`] in `theme`, its palette standing in for magma if it
+/// has one.
+pub fn density_to_svg_with_theme(points: &[Point], params: &FlockParams, cols: usize, rows: usize, theme: &Theme) -> String {
+    let grid = density_grid(points, params, cols, rows);
+    let max = grid.iter().cloned().fold(0.0, f64::max).max(1.0);
+    let (cw, ch) = (params.width / cols as f64, params.height / rows as f64);
+    let mut svg = svg_header(params, theme);
+    for y in 0..rows {
+        for x in 0..cols {
+            let v = grid[y * cols + x];
+            if v <= 0.0 {
+                continue;
+            }
+            let t = (1.0 + v).ln() / (1.0 + max).ln();
+            svg.push_str(&format!(
+                r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>
+"##,
+                x as f64 * cw,
+                y as f64 * ch,
+                cw + 0.1,
+                ch + 0.1,
+                theme.color(t, crate::render::magma(t))
+            ));
+        }
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small() -> FlockParams {
+        FlockParams { count: 60, ..Default::default() }
+    }
+
+    #[test]
+    fn test_flock_creation() {
+        let flock = Flock::new(&small(), 42);
+        assert_eq!(flock.boids.len(), 60);
+        assert!(flock.predator.is_none());
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let params = small();
+        let mut a = Flock::new(&params, 7);
+        let mut b = Flock::new(&params, 7);
+        a.simulate(&params, 50);
+        b.simulate(&params, 50);
+        assert_eq!(a.boids, b.boids);
+    }
+
+    #[test]
+    fn test_speed_limits() {
+        let params = small();
+        let mut flock = Flock::new(&params, 1);
+        flock.simulate(&params, 100);
+        for b in &flock.boids {
+            assert!(b.speed() >= params.min_speed - 1e-9 && b.speed() <= params.max_speed + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_alignment_increases_polarization() {
+        let params = small();
+        let mut flock = Flock::new(&params, 3);
+        let before = flock.polarization();
+        flock.simulate(&params, 300);
+        assert!(flock.polarization() > before, "{} → {}", before, flock.polarization());
+    }
+
+    #[test]
+    fn test_separation_keeps_distance() {
+        let params = small();
+        let mut flock = Flock::new(&params, 5);
+        flock.simulate(&params, 200);
+        let (mean, std) = flock.nearest_neighbor_stats();
+        assert!(mean > 1.0, "Boids collapsed: mean NN = {}", mean);
+        assert!(std >= 0.0);
+    }
+
+    #[test]
+    fn test_predator_chases() {
+        let params = FlockParams { predator: true, ..small() };
+        let mut flock = Flock::new(&params, 9);
+        flock.simulate(&params, 150);
+        let p = flock.predator.unwrap();
+        let nearest = flock.boids.iter().map(|b| (b.x - p.x).hypot(b.y - p.y)).fold(f64::INFINITY, f64::min);
+        assert!(nearest < params.flee_radius * 2.0, "Predator lost the flock: {}", nearest);
+    }
+
+    #[test]
+    fn test_trails_length() {
+        let params = small();
+        let mut flock = Flock::new(&params, 2);
+        let trails = flock.simulate_with_trails(&params, 20);
+        assert_eq!(trails.len(), 60);
+        assert!(trails.iter().all(|t| t.len() == 21));
+    }
+
+    #[test]
+    fn test_density_grid_counts() {
+        let params = small();
+        let pts = vec![Point { x: 10.0, y: 10.0 }, Point { x: 11.0, y: 11.0 }, Point { x: 700.0, y: 500.0 }];
+        let grid = density_grid(&pts, &params, 8, 6);
+        assert_eq!(grid.iter().sum::<f64>(), 3.0);
+        assert_eq!(grid[0], 2.0);
+    }
+
+    #[test]
+    fn test_svg_outputs() {
+        let params = FlockParams { predator: true, ..small() };
+        let mut flock = Flock::new(&params, 4);
+        let trails = flock.simulate_with_trails(&params, 10);
+        assert!(snapshot_to_svg(&flock, &params).contains("#ff5252"));
+        assert!(trails_to_svg(&trails, &params).contains("<polyline"));
+        let all: Vec<Point> = trails.concat();
+        assert!(density_to_svg(&all, &params, 40, 30).contains("<rect"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(FlockParams::default().validate().is_ok());
+        assert!(FlockParams { count: 0, ..Default::default() }.validate().is_err());
+        assert!(FlockParams { min_speed: 5.0, max_speed: 4.0, ..Default::default() }.validate().is_err());
+    }
+}
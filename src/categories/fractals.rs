@@ -3,10 +3,24 @@
 //! Nature is full of fractals: ferns, coastlines, blood vessels, lightning,
 //! romanesco broccoli, and snowflakes.
 
-use std::f64::consts::PI;
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::categories::chaos::Point3D;
+use crate::error::{check_count, check_range, MathaturaError, Result};
+use crate::fixed::Fixed;
+use crate::float::{cast, Float};
+use crate::geometry::{Generator, Geometry, Style};
+use crate::parallel::for_each_row;
+use crate::progress::{collect_with_progress, Progress};
+use crate::render::canvas::{Canvas, Dots, SvgCanvas};
+use crate::render::mesh::Mesh;
+use crate::render::Projection;
+use crate::render::theme::{self, Theme};
 
 /// A 2D point.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub x: f64,
     pub y: f64,
@@ -14,6 +28,7 @@ pub struct Point {
 
 /// Affine transformation for IFS (Iterated Function Systems).
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AffineTransform {
     pub a: f64,
     pub b: f64,
@@ -49,66 +64,318 @@ pub fn barnsley_fern_transforms() -> Vec<AffineTransform> {
 
 /// Generate Barnsley fern points using the chaos game.
 pub fn barnsley_fern(iterations: usize, seed: u64) -> Vec<Point> {
-    let transforms = barnsley_fern_transforms();
-    let mut points = Vec::with_capacity(iterations);
-    let mut p = Point { x: 0.0, y: 0.0 };
-    let mut rng = SimpleRng::new(seed);
+    barnsley_fern_iter(seed).take(iterations).collect()
+}
 
-    for _ in 0..iterations {
-        let r = rng.next_f64();
+/// Generate Barnsley fern points, reporting progress about every 1%.
+pub fn barnsley_fern_with_progress(iterations: usize, seed: u64, progress: &mut impl Progress) -> Result<Vec<Point>> {
+    collect_with_progress(barnsley_fern_iter(seed), iterations, progress)
+}
+
+/// Endless stream of Barnsley fern points, for rendering or analysing
+/// millions of points without holding them all in memory.
+pub fn barnsley_fern_iter(seed: u64) -> FernIter {
+    FernIter::new(seed)
+}
+
+/// [`barnsley_fern_iter`] computed at any float precision.
+pub fn barnsley_fern_iter_as<F: Float>(seed: u64) -> impl Iterator<Item = [F; 2]> {
+    let mut fern = FernIter::<F>::new(seed);
+    core::iter::from_fn(move || Some(fern.step()))
+}
+
+/// The Barnsley fern chaos game as an endless iterator of [`Point`]s.
+///
+/// Holds only the current point and the RNG, so it can be stored in a
+/// struct, cloned to fork the walk, or fed straight to a renderer. `F` is
+/// the precision the affine maps run at.
+#[derive(Debug, Clone)]
+pub struct FernIter<F = f64> {
+    transforms: [([F; 6], f64); 4],
+    position: [F; 2],
+    rng: SimpleRng,
+}
+
+impl<F: Float> FernIter<F> {
+    /// Start at the origin, choosing maps with `seed`.
+    pub fn new(seed: u64) -> Self {
+        let maps = barnsley_fern_transforms();
+        let transforms = core::array::from_fn(|i| {
+            let t = &maps[i];
+            ([t.a, t.b, t.c, t.d, t.e, t.f].map(cast::<F>), t.probability)
+        });
+        FernIter { transforms, position: [F::zero(); 2], rng: SimpleRng::new(seed) }
+    }
+
+    /// Apply one map, picked by probability, and return the new position.
+    fn step(&mut self) -> [F; 2] {
+        let r = self.rng.next_f64();
         let mut cumulative = 0.0;
-        let mut transform = &transforms[0];
-        for t in &transforms {
-            cumulative += t.probability;
+        let mut chosen = &self.transforms[0].0;
+        for (matrix, probability) in &self.transforms {
+            cumulative += probability;
             if r < cumulative {
-                transform = t;
+                chosen = matrix;
                 break;
             }
         }
-        p = transform.apply(p);
-        points.push(p);
+        let [a, b, c, d, e, f] = *chosen;
+        let [x, y] = self.position;
+        self.position = [a * x + b * y + e, c * x + d * y + f];
+        self.position
+    }
+}
+
+impl Iterator for FernIter {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        let [x, y] = self.step();
+        Some(Point { x, y })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
     }
-    points
 }
 
 /// Koch snowflake: recursive line subdivision.
+#[deprecated(note = "grows as 4ⁿ with no depth limit; use `checked_koch_snowflake`")]
 pub fn koch_snowflake(iterations: usize) -> Vec<Point> {
-    // Start with an equilateral triangle
-    let s = 300.0;
-    let h = s * (3.0_f64).sqrt() / 2.0;
-    let mut points = vec![
-        Point { x: 0.0, y: h * 2.0 / 3.0 },
-        Point { x: s / 2.0, y: -h / 3.0 },
-        Point { x: -s / 2.0, y: -h / 3.0 },
-        Point { x: 0.0, y: h * 2.0 / 3.0 }, // close
-    ];
+    koch_variant(&KochVariant::Snowflake, iterations)
+}
+
+/// Deepest subdivision [`checked_koch_snowflake`] accepts (3·4⁸ segments).
+pub const MAX_KOCH_ITERATIONS: usize = 8;
+
+/// Most edges [`checked_koch_variant`] builds, the snowflake's 3·4⁸.
+pub const MAX_KOCH_SEGMENTS: usize = 3 * 4usize.pow(MAX_KOCH_ITERATIONS as u32);
+
+/// Koch snowflake that refuses depths whose 4ⁿ growth would exhaust memory.
+pub fn checked_koch_snowflake(iterations: usize) -> Result<Vec<Point>> {
+    checked_koch_variant(&KochVariant::Snowflake, iterations)
+}
+
+/// A Koch-style curve: a closed initiator polygon whose every edge is
+/// replaced, at each iteration, by a scaled copy of a generator polyline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KochVariant {
+    /// Triangle with a bump on the outer middle third of every edge
+    Snowflake,
+    /// Triangle with the bumps turned inward
+    AntiSnowflake,
+    /// Cesàro fractal: a square with inward tents whose apex angle is
+    /// `angle` degrees; 60° gives equilateral bumps, 85° the classic look
+    Cesaro { angle: f64 },
+    /// Quadratic Koch (Minkowski) island: a square whose edges become an
+    /// 8-segment square wave, enclosing the same area at every depth
+    QuadraticIsland,
+}
+
+impl KochVariant {
+    /// Variant names for the CLI.
+    pub const NAMES: [&'static str; 4] = ["snowflake", "anti", "cesaro", "quadratic"];
+
+    /// Variant by CLI name; the Cesàro fractal gets `angle`.
+    pub fn from_name(name: &str, angle: f64) -> Option<KochVariant> {
+        match name {
+            "snowflake" => Some(KochVariant::Snowflake),
+            "anti" => Some(KochVariant::AntiSnowflake),
+            "cesaro" => Some(KochVariant::Cesaro { angle }),
+            "quadratic" => Some(KochVariant::QuadraticIsland),
+            _ => None,
+        }
+    }
+
+    /// The Cesàro apex angle must leave the tents room to open and close.
+    pub fn validate(&self) -> Result<()> {
+        if let KochVariant::Cesaro { angle } = *self {
+            check_range("angle", angle, 1.0..=179.0, "an apex angle from 1 to 179 degrees")?;
+        }
+        Ok(())
+    }
+
+    /// The closed starting polygon, 300 units across, traced clockwise so
+    /// the left of every edge is the outside.
+    pub fn initiator(&self) -> Vec<Point> {
+        let s = 300.0;
+        match self {
+            KochVariant::Snowflake | KochVariant::AntiSnowflake => {
+                let h = s * 3.0_f64.sqrt() / 2.0;
+                let top = Point { x: 0.0, y: h * 2.0 / 3.0 };
+                vec![top, Point { x: s / 2.0, y: -h / 3.0 }, Point { x: -s / 2.0, y: -h / 3.0 }, top]
+            }
+            KochVariant::Cesaro { .. } | KochVariant::QuadraticIsland => {
+                let r = s / 2.0;
+                let corner = Point { x: -r, y: r };
+                vec![corner, Point { x: r, y: r }, Point { x: r, y: -r }, Point { x: -r, y: -r }, corner]
+            }
+        }
+    }
+
+    /// The polyline each edge becomes, running from (0, 0) to (1, 0) with
+    /// positive y on the edge's left.
+    pub fn generator(&self) -> Vec<(f64, f64)> {
+        let bump = |height: f64| vec![(0.0, 0.0), (1.0 / 3.0, 0.0), (0.5, height), (2.0 / 3.0, 0.0), (1.0, 0.0)];
+        let peak = 3.0_f64.sqrt() / 6.0;
+        match *self {
+            KochVariant::Snowflake => bump(peak),
+            KochVariant::AntiSnowflake => bump(-peak),
+            KochVariant::Cesaro { angle } => {
+                // Four equal segments; the middle two meet at the apex
+                let half = angle.to_radians() / 2.0;
+                let a = 1.0 / (2.0 + 2.0 * half.sin());
+                vec![(0.0, 0.0), (a, 0.0), (0.5, -a * half.cos()), (1.0 - a, 0.0), (1.0, 0.0)]
+            }
+            KochVariant::QuadraticIsland => vec![
+                (0.0, 0.0),
+                (0.25, 0.0),
+                (0.25, 0.25),
+                (0.5, 0.25),
+                (0.5, 0.0),
+                (0.5, -0.25),
+                (0.75, -0.25),
+                (0.75, 0.0),
+                (1.0, 0.0),
+            ],
+        }
+    }
+
+    /// Deepest iteration that stays within [`MAX_KOCH_SEGMENTS`] edges.
+    pub fn max_iterations(&self) -> usize {
+        let (mut edges, growth) = (self.initiator().len() - 1, self.generator().len() - 1);
+        let mut depth = 0;
+        while edges * growth <= MAX_KOCH_SEGMENTS {
+            edges *= growth;
+            depth += 1;
+        }
+        depth
+    }
+}
 
+/// Closed outline of a Koch-style curve after `iterations` rounds of edge
+/// replacement, ending where it starts.
+pub fn koch_variant(variant: &KochVariant, iterations: usize) -> Vec<Point> {
+    let generator = variant.generator();
+    let mut points = variant.initiator();
     for _ in 0..iterations {
-        let mut new_points = Vec::new();
+        let mut new_points = Vec::with_capacity((points.len() - 1) * (generator.len() - 1) + 1);
         for window in points.windows(2) {
             let (p1, p2) = (window[0], window[1]);
-            let dx = p2.x - p1.x;
-            let dy = p2.y - p1.y;
-            // Divide segment into thirds
-            let a = p1;
-            let b = Point { x: p1.x + dx / 3.0, y: p1.y + dy / 3.0 };
-            let d = Point { x: p1.x + 2.0 * dx / 3.0, y: p1.y + 2.0 * dy / 3.0 };
-            // Peak of equilateral triangle
-            let c = Point {
-                x: b.x + dx / 3.0 * (PI / 3.0).cos() - dy / 3.0 * (PI / 3.0).sin(),
-                y: b.y + dx / 3.0 * (PI / 3.0).sin() + dy / 3.0 * (PI / 3.0).cos(),
-            };
-            new_points.push(a);
-            new_points.push(b);
-            new_points.push(c);
-            new_points.push(d);
-        }
-        new_points.push(*points.last().unwrap());
+            let (dx, dy) = (p2.x - p1.x, p2.y - p1.y);
+            // Map the generator onto the edge; its last point is the next edge's first
+            for &(u, v) in &generator[..generator.len() - 1] {
+                new_points.push(Point { x: p1.x + u * dx - v * dy, y: p1.y + u * dy + v * dx });
+            }
+        }
+        // The outline is closed, so it ends where it starts
+        new_points.push(points[0]);
         points = new_points;
     }
     points
 }
 
+/// [`koch_variant`] that refuses depths past [`KochVariant::max_iterations`]
+/// and invalid Cesàro angles.
+pub fn checked_koch_variant(variant: &KochVariant, iterations: usize) -> Result<Vec<Point>> {
+    variant.validate()?;
+    let limit = variant.max_iterations();
+    if iterations > limit {
+        return Err(MathaturaError::TooLarge { name: "iterations", requested: iterations, limit });
+    }
+    Ok(koch_variant(variant, iterations))
+}
+
+/// The `n`-th Fibonacci word over {0, 1}: S₁ = "1", S₂ = "0" and
+/// Sₙ = Sₙ₋₁Sₙ₋₂, so Sₙ has F(n) letters and the 0s outnumber the 1s by
+/// the golden ratio. Empty for `n` = 0.
+pub fn fibonacci_word(n: usize) -> String {
+    let (mut older, mut word) = (String::new(), String::from("1"));
+    if n == 0 {
+        return older;
+    }
+    if n >= 2 {
+        (older, word) = (word, String::from("0"));
+    }
+    for _ in 2..n {
+        let next = format!("{word}{older}");
+        older = core::mem::replace(&mut word, next);
+    }
+    word
+}
+
+/// Deepest word [`checked_fibonacci_word_fractal`] accepts (F₃₀ = 832 040 segments).
+pub const MAX_FIBONACCI_WORD_ITERATIONS: usize = 30;
+
+/// Fibonacci word fractal: read Sₙ as turtle moves, stepping one unit per
+/// letter and, after the k-th letter, turning 90° left on a 0 when k is even
+/// and right on a 0 when k is odd. The path never crosses itself and
+/// repeats its shape at scales a factor of 1 + √2 apart.
+pub fn fibonacci_word_fractal(iterations: usize) -> Vec<Point> {
+    let word = fibonacci_word(iterations);
+    let (mut x, mut y, mut dx, mut dy) = (0.0, 0.0, 0.0, 1.0);
+    let mut points = Vec::with_capacity(word.len() + 1);
+    points.push(Point { x, y });
+    for (k, letter) in word.bytes().enumerate().map(|(i, b)| (i + 1, b)) {
+        (x, y) = (x + dx, y + dy);
+        points.push(Point { x, y });
+        if letter == b'0' {
+            (dx, dy) = if k % 2 == 0 { (-dy, dx) } else { (dy, -dx) };
+        }
+    }
+    points
+}
+
+/// [`fibonacci_word_fractal`] that refuses words long enough to exhaust memory.
+pub fn checked_fibonacci_word_fractal(iterations: usize) -> Result<Vec<Point>> {
+    if iterations > MAX_FIBONACCI_WORD_ITERATIONS {
+        return Err(MathaturaError::TooLarge { name: "iterations", requested: iterations, limit: MAX_FIBONACCI_WORD_ITERATIONS });
+    }
+    Ok(fibonacci_word_fractal(iterations))
+}
+
+/// Draw a Fibonacci word path fitted into a 700px frame, shading from gold
+/// to amber along its length.
+pub fn fibonacci_word_to_svg(points: &[Point]) -> String {
+    fibonacci_word_to_svg_with_theme(points, &Theme::dark())
+}
+
+/// [`fibonacci_word_to_svg`] in `theme`; with a palette, the path runs
+/// along it from start to end.
+pub fn fibonacci_word_to_svg_with_theme(points: &[Point], theme: &Theme) -> String {
+    let (size, margin) = (700.0, 20.0);
+    if points.len() < 2 {
+        return crate::render::svg_document_with_theme(size as u32, size as u32, "", theme);
+    }
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+    for p in points {
+        (min_x, max_x, min_y, max_y) = (min_x.min(p.x), max_x.max(p.x), min_y.min(p.y), max_y.max(p.y));
+    }
+    let scale = (size - 2.0 * margin) / (max_x - min_x).max(max_y - min_y).max(1.0);
+    let (width, height) = ((max_x - min_x) * scale + 2.0 * margin, (max_y - min_y) * scale + 2.0 * margin);
+    let stroke = theme.stroke_width((scale * 0.4).clamp(0.3, 2.5));
+    // A handful of colored stretches keeps the document small
+    let stretches = 12.min(points.len() - 1);
+    let mut content = String::new();
+    for i in 0..stretches {
+        let (from, to) = (i * (points.len() - 1) / stretches, (i + 1) * (points.len() - 1) / stretches);
+        let path: Vec<String> = points[from..=to]
+            .iter()
+            .map(|p| format!("{:.1},{:.1}", margin + (p.x - min_x) * scale, height - margin - (p.y - min_y) * scale))
+            .collect();
+        let color = theme.color(theme::place(i, stretches), crate::render::hsl(50.0 - 25.0 * i as f64 / stretches as f64, 90.0, 60.0));
+        content.push_str(&format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"{stroke:.2}\" stroke-linejoin=\"round\"/>\n",
+            path.join(" ")
+        ));
+    }
+    crate::render::svg_document_with_theme(width.round() as u32, height.round() as u32, &content, theme)
+}
+
+/// Most points the CLI asks of a chaos-game generator (fern, Sierpinski).
+pub const MAX_CHAOS_GAME_POINTS: usize = 50_000_000;
+
 /// Sierpinski triangle via chaos game.
 pub fn sierpinski_triangle(iterations: usize, seed: u64) -> Vec<Point> {
     let vertices = [
@@ -131,6 +398,224 @@ pub fn sierpinski_triangle(iterations: usize, seed: u64) -> Vec<Point> {
     points
 }
 
+/// Deepest Sierpinski carpet [`sierpinski_carpet`] builds (8⁶ squares on a 729² grid).
+pub const MAX_CARPET_DEPTH: usize = 6;
+
+/// Deepest Menger sponge [`menger_sponge`] builds (20⁴ = 160 000 cubes).
+pub const MAX_MENGER_DEPTH: usize = 4;
+
+/// Whether cell (x, y) of a 3ⁿ × 3ⁿ grid is part of the Sierpinski carpet:
+/// a cell is cut out when, at some scale, it sits in the middle third both
+/// across and down.
+pub fn in_sierpinski_carpet(mut x: usize, mut y: usize) -> bool {
+    while x > 0 || y > 0 {
+        if x % 3 == 1 && y % 3 == 1 {
+            return false;
+        }
+        (x, y) = (x / 3, y / 3);
+    }
+    true
+}
+
+/// Sierpinski carpet after `depth` subdivisions of the unit square into
+/// nine, each time keeping all but the middle: the lower-left corners of the
+/// 8ⁿ remaining squares, each 3⁻ⁿ wide.
+pub fn sierpinski_carpet(depth: usize) -> Result<Vec<Point>> {
+    if depth > MAX_CARPET_DEPTH {
+        return Err(MathaturaError::TooLarge { name: "depth", requested: depth, limit: MAX_CARPET_DEPTH });
+    }
+    let mut squares = vec![Point { x: 0.0, y: 0.0 }];
+    let mut side = 1.0;
+    for _ in 0..depth {
+        side /= 3.0;
+        squares = squares
+            .iter()
+            .flat_map(|p| {
+                (0..9).filter(|&k| k != 4).map(move |k| Point { x: p.x + (k % 3) as f64 * side, y: p.y + (k / 3) as f64 * side })
+            })
+            .collect();
+    }
+    Ok(squares)
+}
+
+/// Draw the carpet of the given depth on a 729px square, cut-out holes in
+/// the background color and the cloth shading from teal to violet.
+pub fn carpet_to_svg(depth: usize) -> String {
+    carpet_to_svg_with_theme(depth, &Theme::dark())
+}
+
+/// [`carpet_to_svg`] in `theme`; with a palette, the cloth shades along it
+/// from top to bottom.
+pub fn carpet_to_svg_with_theme(depth: usize, theme: &Theme) -> String {
+    let n = 3usize.pow(depth.min(MAX_CARPET_DEPTH) as u32);
+    // Rows share one color, so solid runs merge into single rects
+    let values: Vec<f64> = (0..n * n).map(|k| if in_sierpinski_carpet(k % n, k / n) { (k / n) as f64 / n as f64 } else { -1.0 }).collect();
+    let hole = theme.background_css();
+    let color = |t: f64| {
+        if t < 0.0 { hole.clone() } else { theme.color(t, crate::render::hsl(170.0 + 110.0 * t, 65.0, 55.0)) }
+    };
+    crate::render::raster_svg_with_theme(n, n, 729.0 / n as f64, &values, color, theme)
+}
+
+/// Whether cube (x, y, z) of a 3ⁿ grid is part of the Menger sponge: a cube
+/// is removed when, at some scale, at least two of its coordinates are in
+/// the middle third, which drills the cross-shaped tunnels.
+pub fn in_menger_sponge(mut x: usize, mut y: usize, mut z: usize) -> bool {
+    while x > 0 || y > 0 || z > 0 {
+        if (x % 3 == 1) as u8 + (y % 3 == 1) as u8 + (z % 3 == 1) as u8 >= 2 {
+            return false;
+        }
+        (x, y, z) = (x / 3, y / 3, z / 3);
+    }
+    true
+}
+
+/// Menger sponge after `depth` subdivisions: the 20ⁿ cubes left on a 3ⁿ
+/// grid, as integer coordinates.
+pub fn menger_sponge(depth: usize) -> Result<Vec<[usize; 3]>> {
+    if depth > MAX_MENGER_DEPTH {
+        return Err(MathaturaError::TooLarge { name: "depth", requested: depth, limit: MAX_MENGER_DEPTH });
+    }
+    let mut cubes = vec![[0; 3]];
+    for _ in 0..depth {
+        cubes = cubes
+            .iter()
+            .flat_map(|c| {
+                (0..27).map(|k| [k % 3, k / 3 % 3, k / 9]).filter(|&[x, y, z]| in_menger_sponge(x, y, z)).map(move |[x, y, z]| {
+                    [3 * c[0] + x, 3 * c[1] + y, 3 * c[2] + z]
+                })
+            })
+            .collect();
+    }
+    Ok(cubes)
+}
+
+/// A face of the sponge's surface: four lattice corners, counter-clockwise
+/// seen from outside, and the axis and side it faces.
+struct CubeFace {
+    corners: [[usize; 3]; 4],
+    axis: usize,
+    outward: bool,
+}
+
+/// Every cube face with empty space (or the outside) beyond it.
+fn menger_faces(depth: usize) -> Result<Vec<CubeFace>> {
+    let cubes = menger_sponge(depth)?;
+    let n = 3usize.pow(depth as u32);
+    let mut faces = Vec::new();
+    for cube in &cubes {
+        for axis in 0..3 {
+            // With (axis, u, w) cyclic, u × w points along +axis
+            let (u, w) = ((axis + 1) % 3, (axis + 2) % 3);
+            for outward in [false, true] {
+                let mut beyond = *cube;
+                let open = if outward {
+                    beyond[axis] += 1;
+                    beyond[axis] == n || !in_menger_sponge(beyond[0], beyond[1], beyond[2])
+                } else {
+                    cube[axis] == 0 || {
+                        beyond[axis] -= 1;
+                        !in_menger_sponge(beyond[0], beyond[1], beyond[2])
+                    }
+                };
+                if !open {
+                    continue;
+                }
+                let corner = |du: usize, dw: usize| {
+                    let mut c = *cube;
+                    c[axis] += outward as usize;
+                    c[u] += du;
+                    c[w] += dw;
+                    c
+                };
+                let mut corners = [corner(0, 0), corner(1, 0), corner(1, 1), corner(0, 1)];
+                if !outward {
+                    corners.reverse();
+                }
+                faces.push(CubeFace { corners, axis, outward });
+            }
+        }
+    }
+    Ok(faces)
+}
+
+/// The sponge's surface as a closed triangle mesh `size` units wide, ready
+/// for [`Mesh::to_obj`] or
+/// [`Mesh::to_stl`]. Only faces between
+/// solid and empty space are kept, and corners are shared.
+pub fn menger_mesh(depth: usize, size: f64) -> Result<Mesh> {
+    let faces = menger_faces(depth)?;
+    let unit = size / 3usize.pow(depth as u32) as f64;
+    let mut mesh = Mesh::default();
+    let mut index: Map<[usize; 3], usize> = Map::new();
+    for face in &faces {
+        let [a, b, c, d] = face.corners.map(|corner| {
+            *index.entry(corner).or_insert_with(|| {
+                let [x, y, z] = corner.map(|v| v as f64 * unit);
+                mesh.vertices.push(Point3D { x, y, z });
+                mesh.vertices.len() - 1
+            })
+        });
+        mesh.triangles.push([a, b, c]);
+        mesh.triangles.push([a, c, d]);
+    }
+    Ok(mesh)
+}
+
+/// Draw the sponge's visible faces as shaded quads, far ones first, lit
+/// from above and the viewer's left.
+pub fn menger_to_svg(depth: usize, projection: &Projection) -> Result<String> {
+    menger_to_svg_with_theme(depth, projection, &Theme::dark())
+}
+
+/// [`menger_to_svg`] in `theme`; with a palette, the faces take it from the
+/// darkest, at 0, to the tops at 1.
+pub fn menger_to_svg_with_theme(depth: usize, projection: &Projection, theme: &Theme) -> Result<String> {
+    let faces = menger_faces(depth)?;
+    let points: Vec<Point3D> = faces
+        .iter()
+        .flat_map(|f| f.corners.map(|[x, y, z]| Point3D { x: x as f64, y: y as f64, z: z as f64 }))
+        .collect();
+    let projected = projection.project(&points);
+    let (size, margin) = (700.0, 20.0);
+    let extent = projected.iter().fold(1e-9, |m, p| f64::max(m, p[0].abs().max(p[1].abs())));
+    let scale = (size / 2.0 - margin) / extent;
+    let mut visible: Vec<(f64, usize)> = faces
+        .iter()
+        .enumerate()
+        .filter_map(|(i, _)| {
+            let q = &projected[4 * i..4 * i + 4];
+            // Counter-clockwise from outside stays counter-clockwise on screen when
+            // facing us, which is a negative shoelace sum with y pointing down
+            let area: f64 = (0..4).map(|k| q[k][0] * q[(k + 1) % 4][1] - q[(k + 1) % 4][0] * q[k][1]).sum();
+            (area < 0.0).then(|| (q.iter().map(|p| p[2]).sum::<f64>(), i))
+        })
+        .collect();
+    visible.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let mut content = String::new();
+    for (_, i) in visible {
+        let face = &faces[i];
+        // Tops brightest, then the two side families
+        let light = match (face.axis, face.outward) {
+            (2, true) => 72.0,
+            (2, false) => 25.0,
+            (0, _) => 55.0,
+            _ => 42.0,
+        };
+        let corners: Vec<String> = projected[4 * i..4 * i + 4]
+            .iter()
+            .map(|p| format!("{:.1},{:.1}", size / 2.0 + p[0] * scale, size / 2.0 + p[1] * scale))
+            .collect();
+        let fill = theme.color((light - 25.0) / 47.0, crate::render::hsl(28.0, 45.0, light));
+        content.push_str(&format!(
+            "<polygon points=\"{}\" fill=\"{fill}\" stroke=\"{fill}\" stroke-width=\"{}\"/>\n",
+            corners.join(" "),
+            theme.stroke_width(0.3)
+        ));
+    }
+    Ok(crate::render::svg_document_with_theme(size as u32, size as u32, &content, theme))
+}
+
 /// Mandelbrot set: test if point c = (cx, cy) is in the set.
 /// Returns iteration count (0 = in set, >0 = escaped at that iteration).
 pub fn mandelbrot_escape(cx: f64, cy: f64, max_iter: u32) -> u32 {
@@ -148,11 +633,671 @@ pub fn mandelbrot_escape(cx: f64, cy: f64, max_iter: u32) -> u32 {
     0
 }
 
+/// Squared escape radius for smooth iteration counts; a large radius makes
+/// the log-log correction accurate to well under one band.
+const SMOOTH_BAILOUT_SQ: f64 = 65536.0;
+
+/// Continuous escape time of c = (cx, cy): 0 inside the set, otherwise
+/// n + 1 − log₂(ln |zₙ|), which varies smoothly across the integer bands of
+/// [`mandelbrot_escape`].
+pub fn mandelbrot_smooth(cx: f64, cy: f64, max_iter: u32) -> f64 {
+    escape_smooth((0.0, 0.0), (cx, cy), max_iter)
+}
+
+/// Continuous escape time of z₀ = (zx, zy) under z → z² + c for the Julia
+/// set of c = (cx, cy), on the same scale as [`mandelbrot_smooth`].
+pub fn julia_smooth(zx: f64, zy: f64, cx: f64, cy: f64, max_iter: u32) -> f64 {
+    escape_smooth((zx, zy), (cx, cy), max_iter)
+}
+
+/// Continuous escape time of c = (cx, cy) for the Burning Ship, which folds
+/// z into the first quadrant before squaring: z → (|Re z| + i|Im z|)² + c.
+pub fn burning_ship_smooth(cx: f64, cy: f64, max_iter: u32) -> f64 {
+    escape_smooth_with((0.0, 0.0), max_iter, 2.0, |zx, zy| {
+        let (zx, zy) = (zx.abs(), zy.abs());
+        (zx * zx - zy * zy + cx, 2.0 * zx * zy + cy)
+    })
+}
+
+/// Continuous escape time of c = (cx, cy) for the Multibrot set of z → zᵈ + c,
+/// `power` d > 1 and not necessarily whole; d = 2 is the Mandelbrot set.
+pub fn multibrot_smooth(cx: f64, cy: f64, power: f64, max_iter: u32) -> f64 {
+    // Whole powers multiply out exactly, keeping real orbits real
+    let whole = (power.fract() == 0.0 && power <= 64.0).then_some(power as u32);
+    escape_smooth_with((0.0, 0.0), max_iter, power, |zx, zy| {
+        let (px, py) = match whole {
+            Some(d) => (1..d).fold((zx, zy), |(px, py), _| (px * zx - py * zy, px * zy + py * zx)),
+            None => {
+                // zᵈ in polar form; the origin stays put for any d > 0
+                let r = (zx * zx + zy * zy).powf(power / 2.0);
+                let theta = zy.atan2(zx) * power;
+                (r * theta.cos(), r * theta.sin())
+            }
+        };
+        (px + cx, py + cy)
+    })
+}
+
+/// Smooth escape time of the orbit of z₀ under z → z² + c; 0 if it stays
+/// bounded for `max_iter` steps.
+fn escape_smooth(z0: (f64, f64), (cx, cy): (f64, f64), max_iter: u32) -> f64 {
+    escape_smooth_with(z0, max_iter, 2.0, |zx, zy| (zx * zx - zy * zy + cx, 2.0 * zx * zy + cy))
+}
+
+/// Smooth escape time of the orbit of z₀ under `step`, a map that grows like
+/// |z|^`degree` far out; 0 if it stays bounded for `max_iter` steps.
+fn escape_smooth_with((mut zx, mut zy): (f64, f64), max_iter: u32, degree: f64, step: impl Fn(f64, f64) -> (f64, f64)) -> f64 {
+    let ln_degree = degree.ln();
+    for i in 0..max_iter {
+        let r2 = zx * zx + zy * zy;
+        if r2 > SMOOTH_BAILOUT_SQ {
+            // ln|z| = ½ ln|z|²; stays positive for all but enormous c
+            let nu = i as f64 + 1.0 - (0.5 * r2.ln()).ln() / ln_degree;
+            return nu.max(f64::MIN_POSITIVE);
+        }
+        (zx, zy) = step(zx, zy);
+    }
+    0.0
+}
+
+/// A rectangle of the complex plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Region {
+    pub re_min: f64,
+    pub re_max: f64,
+    pub im_min: f64,
+    pub im_max: f64,
+}
+
+impl Region {
+    /// The whole Mandelbrot set with a small margin.
+    pub const MANDELBROT: Region = Region { re_min: -2.5, re_max: 1.0, im_min: -1.25, im_max: 1.25 };
+
+    /// The whole Burning Ship.
+    pub const BURNING_SHIP: Region = Region { re_min: -2.5, re_max: 1.5, im_min: -2.0, im_max: 1.0 };
+
+    /// A region `span` wide around `center`, `aspect` (height / width) as tall.
+    pub fn centered(re: f64, im: f64, span: f64, aspect: f64) -> Region {
+        let (half_w, half_h) = (span / 2.0, span * aspect / 2.0);
+        Region { re_min: re - half_w, re_max: re + half_w, im_min: im - half_h, im_max: im + half_h }
+    }
+
+    /// The complex number at the center of pixel (x, y) of a `width × height`
+    /// raster, with the imaginary axis pointing up.
+    pub fn pixel(&self, x: usize, y: usize, width: usize, height: usize) -> (f64, f64) {
+        let re = self.re_min + (x as f64 + 0.5) / width.max(1) as f64 * (self.re_max - self.re_min);
+        let im = self.im_max - (y as f64 + 0.5) / height.max(1) as f64 * (self.im_max - self.im_min);
+        (re, im)
+    }
+}
+
+/// Smooth escape times over a raster, row-major; 0 marks points in the set.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EscapeGrid {
+    pub width: usize,
+    pub height: usize,
+    pub max_iter: u32,
+    pub counts: Vec<f64>,
+}
+
+impl EscapeGrid {
+    /// Color position in [0, 1] of every pixel, or `None` inside the set.
+    /// Escape times are log-scaled so the slow-escaping filaments near the
+    /// boundary don't all crowd into the top of the colormap, and counted
+    /// from the fastest-escaping pixel so deep zooms, where nothing escapes
+    /// early, still spread across the whole colormap.
+    pub fn shades(&self) -> Vec<Option<f64>> {
+        let lo = self.escape_range().map_or(0.0, |r| r.lo);
+        self.shades_in(ColorRange { lo, hi: self.max_iter as f64 })
+    }
+
+    /// Color positions with escape times log-scaled across `range` and
+    /// clamped to [0, 1], `None` inside the set.
+    pub fn shades_in(&self, range: ColorRange) -> Vec<Option<f64>> {
+        let top = (range.hi - range.lo).max(1.0).ln_1p();
+        self.counts.iter().map(|&n| (n > 0.0).then(|| ((n - range.lo).max(0.0).ln_1p() / top).min(1.0))).collect()
+    }
+
+    /// From just below the fastest escape to the slowest, or `None` if no
+    /// pixel escapes.
+    pub fn escape_range(&self) -> Option<ColorRange> {
+        let escaped = self.counts.iter().copied().filter(|&n| n > 0.0);
+        let (lo, hi) = escaped.fold((f64::INFINITY, 0.0_f64), |(lo, hi), n| (lo.min(n), hi.max(n)));
+        lo.is_finite().then(|| ColorRange { lo: (lo - 1.0).max(0.0), hi })
+    }
+
+    /// Fraction of pixels inside the set.
+    pub fn inside_fraction(&self) -> f64 {
+        self.counts.iter().filter(|&&n| n == 0.0).count() as f64 / self.counts.len().max(1) as f64
+    }
+
+    /// RGB bytes, row-major, with the set in black.
+    pub fn to_rgb(&self, color: impl Fn(f64) -> [u8; 3]) -> Vec<u8> {
+        rgb_pixels(self.shades(), color)
+    }
+
+    /// [`to_rgb`](Self::to_rgb) with escape times spread across `range`.
+    pub fn to_rgb_in(&self, range: ColorRange, color: impl Fn(f64) -> [u8; 3]) -> Vec<u8> {
+        rgb_pixels(self.shades_in(range), color)
+    }
+}
+
+fn rgb_pixels(shades: Vec<Option<f64>>, color: impl Fn(f64) -> [u8; 3]) -> Vec<u8> {
+    shades.into_iter().flat_map(|t| t.map_or([0, 0, 0], &color)).collect()
+}
+
+/// The escape times mapped to the two ends of the colormap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorRange {
+    /// Escape time drawn at the bottom of the colormap
+    pub lo: f64,
+    /// Escape time drawn at the top
+    pub hi: f64,
+}
+
+impl ColorRange {
+    /// The range `weight` of the way from `self` to `target`.
+    pub fn toward(self, target: ColorRange, weight: f64) -> ColorRange {
+        ColorRange { lo: self.lo + (target.lo - self.lo) * weight, hi: self.hi + (target.hi - self.hi) * weight }
+    }
+}
+
+/// Smooth escape times of the Mandelbrot set over `region` at `width × height` pixels.
+///
+/// Rows are computed concurrently with the `parallel` feature; every pixel
+/// is independent, so the grid is identical either way.
+pub fn mandelbrot_grid(region: &Region, width: usize, height: usize, max_iter: u32) -> EscapeGrid {
+    escape_grid(region, width, height, max_iter, |re, im| mandelbrot_smooth(re, im, max_iter))
+}
+
+/// Smooth escape times of the Julia set of `c` = (re, im) over `region`,
+/// rendered like [`mandelbrot_grid`].
+pub fn julia_grid(region: &Region, width: usize, height: usize, c: (f64, f64), max_iter: u32) -> EscapeGrid {
+    escape_grid(region, width, height, max_iter, |re, im| julia_smooth(re, im, c.0, c.1, max_iter))
+}
+
+/// Smooth escape times of the Burning Ship over `region`, rendered like
+/// [`mandelbrot_grid`]. With the imaginary axis pointing up the ship sails
+/// upside down, its hull along the top of [`Region::BURNING_SHIP`].
+pub fn burning_ship_grid(region: &Region, width: usize, height: usize, max_iter: u32) -> EscapeGrid {
+    escape_grid(region, width, height, max_iter, |re, im| burning_ship_smooth(re, im, max_iter))
+}
+
+/// Smooth escape times of the Multibrot set of z → z^`power` + c over
+/// `region`, rendered like [`mandelbrot_grid`].
+pub fn multibrot_grid(region: &Region, width: usize, height: usize, power: f64, max_iter: u32) -> EscapeGrid {
+    escape_grid(region, width, height, max_iter, |re, im| multibrot_smooth(re, im, power, max_iter))
+}
+
+/// Zoom past which [`mandelbrot_grid`] turns to blocks and the CLI switches
+/// to [`mandelbrot_deep_grid`].
+pub const DEEP_ZOOM: f64 = 1e10;
+
+/// Deepest zoom [`mandelbrot_deep_grid`] supports: pixel offsets are `f64`,
+/// so spans have to stay well clear of its smallest exponent.
+pub const MAX_DEEP_ZOOM: f64 = 1e280;
+
+/// Fractional bits a reference orbit needs for `width` pixels across `span`:
+/// enough to resolve one pixel with 64 bits to spare.
+pub fn deep_zoom_bits(span: f64, width: usize) -> u32 {
+    let pixel = span / width.max(1) as f64;
+    (64.0 - pixel.log2()).clamp(64.0, 4096.0).ceil() as u32
+}
+
+/// Smooth escape times of the Mandelbrot set in a `span`-wide view centered
+/// on (`re`, `im`), for zooms far beyond `f64`.
+///
+/// Only the center's orbit is iterated exactly, in [`Fixed`] point at
+/// [`deep_zoom_bits`] precision. Every pixel follows the difference δ from
+/// that reference, δ → 2Zδ + δ² + δc, which stays tiny enough for `f64`.
+/// Whenever the pixel's orbit comes closer to 0 than to the reference, or
+/// the reference escapes, it is rebased onto the start of the reference
+/// orbit, which avoids the glitches of plain perturbation. At moderate zooms
+/// the grid matches [`mandelbrot_grid`] up to rounding.
+pub fn mandelbrot_deep_grid(re: &Fixed, im: &Fixed, span: f64, width: usize, height: usize, max_iter: u32) -> EscapeGrid {
+    let bits = deep_zoom_bits(span, width).max(re.bits()).max(im.bits());
+    let orbit = reference_orbit(&re.with_bits(bits), &im.with_bits(bits), max_iter);
+    let offsets = Region::centered(0.0, 0.0, span, height as f64 / width.max(1) as f64);
+    escape_grid(&offsets, width, height, max_iter, |dcx, dcy| perturbed_smooth(&orbit, (dcx, dcy), max_iter))
+}
+
+/// The orbit Z₀ = 0, Z₁ = c, … of c = (cx, cy) in full precision, rounded
+/// to `f64`, up to and including the first escaped point.
+fn reference_orbit(cx: &Fixed, cy: &Fixed, max_iter: u32) -> Vec<(f64, f64)> {
+    let (mut zx, mut zy) = (Fixed::zero(cx.bits()), Fixed::zero(cx.bits()));
+    let mut orbit = vec![(0.0, 0.0)];
+    for _ in 0..max_iter {
+        let (zx2, zy2) = (&zx * &zx, &zy * &zy);
+        let zxy = &zx * &zy;
+        zy = &(&zxy + &zxy) + cy;
+        zx = &(&zx2 - &zy2) + cx;
+        let z = (zx.to_f64(), zy.to_f64());
+        orbit.push(z);
+        if z.0 * z.0 + z.1 * z.1 > SMOOTH_BAILOUT_SQ {
+            break;
+        }
+    }
+    orbit
+}
+
+/// Smooth escape time of the pixel `dc` away from the reference `orbit`,
+/// on the scale of [`mandelbrot_smooth`].
+fn perturbed_smooth(orbit: &[(f64, f64)], (dcx, dcy): (f64, f64), max_iter: u32) -> f64 {
+    let (mut dx, mut dy) = (0.0, 0.0);
+    let mut m = 0;
+    for i in 0..max_iter {
+        let (zx, zy) = (orbit[m].0 + dx, orbit[m].1 + dy);
+        let r2 = zx * zx + zy * zy;
+        if r2 > SMOOTH_BAILOUT_SQ {
+            let nu = i as f64 + 1.0 - (0.5 * r2.ln()).log2();
+            return nu.max(f64::MIN_POSITIVE);
+        }
+        // Rebase: carry on from Z₀ = 0 with the whole of z as the difference
+        if r2 < dx * dx + dy * dy || m + 1 == orbit.len() {
+            (dx, dy, m) = (zx, zy, 0);
+        }
+        let (rx, ry) = orbit[m];
+        (dx, dy) = (
+            2.0 * (rx * dx - ry * dy) + dx * dx - dy * dy + dcx,
+            2.0 * (rx * dy + ry * dx) + 2.0 * dx * dy + dcy,
+        );
+        m += 1;
+    }
+    0.0
+}
+
+/// Fill an [`EscapeGrid`] row by row with `escape(re, im)` at each pixel center.
+fn escape_grid(region: &Region, width: usize, height: usize, max_iter: u32, escape: impl Fn(f64, f64) -> f64 + Sync + Send) -> EscapeGrid {
+    EscapeGrid { width, height, max_iter, counts: sample_region(region, width, height, escape) }
+}
+
+/// `f(re, im)` at every pixel center of `region`, row-major, rows in parallel.
+fn sample_region(region: &Region, width: usize, height: usize, f: impl Fn(f64, f64) -> f64 + Sync + Send) -> Vec<f64> {
+    let mut values = vec![0.0; width * height];
+    for_each_row(&mut values, width, |y, row| {
+        for (x, value) in row.iter_mut().enumerate() {
+            let (re, im) = region.pixel(x, y, width, height);
+            *value = f(re, im);
+        }
+    });
+    values
+}
+
+/// A shape the orbit is measured against for orbit-trap coloring: each pixel
+/// is shaded by how close its orbit ever comes to the trap, which lights up
+/// the interior of the set with petals and rings where escape time is flat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OrbitTrap {
+    /// Distance to the point (re, im)
+    Point { re: f64, im: f64 },
+    /// Distance to the line through (re, im) at `angle` radians
+    Line { re: f64, im: f64, angle: f64 },
+    /// Distance to the circle of `radius` around (re, im)
+    Circle { re: f64, im: f64, radius: f64 },
+}
+
+impl OrbitTrap {
+    /// Trap names for the CLI.
+    pub const NAMES: [&'static str; 3] = ["point", "line", "circle"];
+
+    /// The default trap of each kind: the origin, the imaginary axis, or the
+    /// circle of radius ½ around the origin.
+    pub fn from_name(name: &str) -> Option<OrbitTrap> {
+        match name {
+            "point" => Some(OrbitTrap::Point { re: 0.0, im: 0.0 }),
+            "line" => Some(OrbitTrap::Line { re: 0.0, im: 0.0, angle: PI / 2.0 }),
+            "circle" => Some(OrbitTrap::Circle { re: 0.0, im: 0.0, radius: 0.5 }),
+            _ => None,
+        }
+    }
+
+    /// Distance from z = (zx, zy) to the trap.
+    pub fn distance(&self, zx: f64, zy: f64) -> f64 {
+        match *self {
+            OrbitTrap::Point { re, im } => (zx - re).hypot(zy - im),
+            OrbitTrap::Line { re, im, angle } => ((zx - re) * angle.sin() - (zy - im) * angle.cos()).abs(),
+            OrbitTrap::Circle { re, im, radius } => ((zx - re).hypot(zy - im) - radius).abs(),
+        }
+    }
+}
+
+/// Closest approach of the orbit of z₀ under z → z² + c to `trap`, over at
+/// most `max_iter` steps or until the orbit escapes.
+fn trap_distance((mut zx, mut zy): (f64, f64), (cx, cy): (f64, f64), max_iter: u32, trap: &OrbitTrap) -> f64 {
+    let mut closest = f64::INFINITY;
+    for _ in 0..max_iter {
+        let (zx2, zy2) = (zx * zx, zy * zy);
+        if zx2 + zy2 > SMOOTH_BAILOUT_SQ {
+            break;
+        }
+        zy = 2.0 * zx * zy + cy;
+        zx = zx2 - zy2 + cx;
+        closest = closest.min(trap.distance(zx, zy));
+    }
+    closest
+}
+
+/// Orbit-trap distances over a raster, row-major.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrapGrid {
+    pub width: usize,
+    pub height: usize,
+    pub distances: Vec<f64>,
+}
+
+impl TrapGrid {
+    /// Color position in [0, 1] of every pixel, 1 where the orbit touches
+    /// the trap and fading exponentially with distance.
+    pub fn shades(&self) -> Vec<f64> {
+        self.distances.iter().map(|d| (-TRAP_FALLOFF * d).exp()).collect()
+    }
+
+    /// RGB bytes, row-major.
+    pub fn to_rgb(&self, color: impl Fn(f64) -> [u8; 3]) -> Vec<u8> {
+        self.shades().into_iter().flat_map(color).collect()
+    }
+}
+
+/// How fast trap shading fades with distance; about 1/6 of the brightness
+/// is left a quarter unit from the trap.
+const TRAP_FALLOFF: f64 = 7.0;
+
+/// Orbit-trap distances of the Mandelbrot set over `region`.
+pub fn mandelbrot_trap_grid(region: &Region, width: usize, height: usize, max_iter: u32, trap: &OrbitTrap) -> TrapGrid {
+    TrapGrid { width, height, distances: sample_region(region, width, height, |re, im| trap_distance((0.0, 0.0), (re, im), max_iter, trap)) }
+}
+
+/// Orbit-trap distances of the Julia set of `c` = (re, im) over `region`.
+pub fn julia_trap_grid(region: &Region, width: usize, height: usize, c: (f64, f64), max_iter: u32, trap: &OrbitTrap) -> TrapGrid {
+    TrapGrid { width, height, distances: sample_region(region, width, height, |re, im| trap_distance((re, im), c, max_iter, trap)) }
+}
+
+/// Render orbit-trap shades with a colormap such as [`render::magma`](crate::render::magma).
+pub fn trap_to_svg(grid: &TrapGrid, cell: f64, color: impl Fn(f64) -> String) -> String {
+    trap_to_svg_with_theme(grid, cell, color, &Theme::dark())
+}
+
+/// [`trap_to_svg`] on `theme`'s background.
+pub fn trap_to_svg_with_theme(grid: &TrapGrid, cell: f64, color: impl Fn(f64) -> String, theme: &Theme) -> String {
+    crate::render::raster_svg_with_theme(grid.width, grid.height, cell, &grid.shades(), color, theme)
+}
+
+/// Render escape times with a colormap such as [`render::magma`](crate::render::magma),
+/// the set in black.
+pub fn escape_to_svg(grid: &EscapeGrid, cell: f64, color: impl Fn(f64) -> String) -> String {
+    escape_to_svg_with_theme(grid, cell, color, &Theme::dark())
+}
+
+/// [`escape_to_svg`] on `theme`'s background.
+pub fn escape_to_svg_with_theme(grid: &EscapeGrid, cell: f64, color: impl Fn(f64) -> String, theme: &Theme) -> String {
+    shades_to_svg(grid, grid.shades(), cell, color, theme)
+}
+
+/// [`escape_to_svg`] with escape times spread across `range`, so frames of
+/// an animation can share one color scale.
+pub fn escape_to_svg_in(grid: &EscapeGrid, range: ColorRange, cell: f64, color: impl Fn(f64) -> String) -> String {
+    escape_to_svg_in_with_theme(grid, range, cell, color, &Theme::dark())
+}
+
+/// [`escape_to_svg_in`] on `theme`'s background.
+pub fn escape_to_svg_in_with_theme(grid: &EscapeGrid, range: ColorRange, cell: f64, color: impl Fn(f64) -> String, theme: &Theme) -> String {
+    shades_to_svg(grid, grid.shades_in(range), cell, color, theme)
+}
+
+fn shades_to_svg(grid: &EscapeGrid, shades: Vec<Option<f64>>, cell: f64, color: impl Fn(f64) -> String, theme: &Theme) -> String {
+    // Inside points carry a sentinel below the colormap's range
+    let values: Vec<f64> = shades.into_iter().map(|t| t.unwrap_or(-1.0)).collect();
+    let color = |t: f64| if t < 0.0 { String::from("#000000") } else { color(t) };
+    crate::render::raster_svg_with_theme(grid.width, grid.height, cell, &values, color, theme)
+}
+
+/// Most frames [`ZoomParams::validate`] accepts.
+pub const MAX_ZOOM_FRAMES: usize = 10_000;
+
+/// How far each frame's color range moves toward the frame's own escape
+/// range. Following it exactly makes the palette flicker as single slow
+/// pixels come and go; easing in keeps it steady while still tracking the
+/// climb in escape times as the view dives deeper.
+const ZOOM_COLOR_EASING: f64 = 0.25;
+
+/// The set a zoom animation dives into.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ZoomTarget {
+    Mandelbrot,
+    /// The Julia set of c = (re, im)
+    Julia { c: (f64, f64) },
+}
+
+/// An exponential zoom from `start_zoom` to `end_zoom` onto (`re`, `im`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoomParams {
+    pub target: ZoomTarget,
+    pub re: Fixed,
+    pub im: Fixed,
+    /// Magnification of the first frame, 1 showing the whole set
+    pub start_zoom: f64,
+    /// Magnification of the last frame
+    pub end_zoom: f64,
+    pub frames: usize,
+    pub width: usize,
+    pub height: usize,
+    pub max_iter: u32,
+}
+
+impl ZoomParams {
+    /// Check the frame count and that both zooms are in reach: perturbation
+    /// takes the Mandelbrot set to [`MAX_DEEP_ZOOM`], Julia sets stop at
+    /// [`DEEP_ZOOM`].
+    pub fn validate(&self) -> Result<()> {
+        let deepest = match self.target {
+            ZoomTarget::Mandelbrot => MAX_DEEP_ZOOM,
+            ZoomTarget::Julia { .. } => DEEP_ZOOM,
+        };
+        check_range("start_zoom", self.start_zoom, f64::MIN_POSITIVE..=deepest, "a positive zoom within the set's precision")?;
+        check_range("end_zoom", self.end_zoom, f64::MIN_POSITIVE..=deepest, "a positive zoom within the set's precision")?;
+        check_count("frames", self.frames, MAX_ZOOM_FRAMES)?;
+        check_range("max_iter", self.max_iter as f64, 1.0..=1e6, "1 to 1000000 iterations")?;
+        if self.frames == 0 || self.width == 0 || self.height == 0 {
+            return Err(MathaturaError::Empty { name: "zoom frames" });
+        }
+        Ok(())
+    }
+
+    /// Magnification of frame `i`, growing by the same factor every frame.
+    pub fn zoom_at(&self, i: usize) -> f64 {
+        let t = if self.frames > 1 { i as f64 / (self.frames - 1) as f64 } else { 0.0 };
+        self.start_zoom * (self.end_zoom / self.start_zoom).powf(t)
+    }
+}
+
+/// One rendered frame of a [`ZoomAnimation`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoomFrame {
+    pub zoom: f64,
+    pub grid: EscapeGrid,
+    /// Color scale eased from the previous frames; pass it to
+    /// [`escape_to_svg_in`] or [`EscapeGrid::to_rgb_in`]
+    pub range: ColorRange,
+}
+
+/// Frames of an exponential zoom, rendered one at a time as the iterator
+/// advances; see [`zoom_animation`].
+#[derive(Debug, Clone)]
+pub struct ZoomAnimation<'a> {
+    params: &'a ZoomParams,
+    frame: usize,
+    range: Option<ColorRange>,
+}
+
+/// Render the frames of a zoom into `params.re + params.im·i`, each a
+/// `3.5 / zoom`-wide view. Mandelbrot frames deeper than [`DEEP_ZOOM`] use
+/// [`mandelbrot_deep_grid`], so the target can be given to hundreds of digits.
+pub fn zoom_animation(params: &ZoomParams) -> ZoomAnimation<'_> {
+    ZoomAnimation { params, frame: 0, range: None }
+}
+
+impl Iterator for ZoomAnimation<'_> {
+    type Item = ZoomFrame;
+
+    fn next(&mut self) -> Option<ZoomFrame> {
+        let p = self.params;
+        if self.frame >= p.frames {
+            return None;
+        }
+        let zoom = p.zoom_at(self.frame);
+        self.frame += 1;
+        let span = 3.5 / zoom;
+        let grid = match p.target {
+            ZoomTarget::Mandelbrot if zoom > DEEP_ZOOM => mandelbrot_deep_grid(&p.re, &p.im, span, p.width, p.height, p.max_iter),
+            target => {
+                let region = Region::centered(p.re.to_f64(), p.im.to_f64(), span, p.height as f64 / p.width as f64);
+                match target {
+                    ZoomTarget::Julia { c } => julia_grid(&region, p.width, p.height, c, p.max_iter),
+                    ZoomTarget::Mandelbrot => mandelbrot_grid(&region, p.width, p.height, p.max_iter),
+                }
+            }
+        };
+        // Frames with nothing escaping keep the previous scale
+        let fallback = ColorRange { lo: 0.0, hi: p.max_iter as f64 };
+        let target = grid.escape_range().or(self.range).unwrap_or(fallback);
+        let range = self.range.map_or(target, |r| r.toward(target, ZOOM_COLOR_EASING));
+        self.range = Some(range);
+        Some(ZoomFrame { zoom, grid, range })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.params.frames.saturating_sub(self.frame);
+        (left, Some(left))
+    }
+}
+
+/// Newton steps before a pixel is given up as non-convergent.
+pub const NEWTON_MAX_ITER: u32 = 64;
+
+/// Most roots [`newton_fractal`] is asked to handle by the CLI.
+pub const MAX_NEWTON_ROOTS: usize = 32;
+
+/// Distance from a root at which Newton's method counts as converged.
+const NEWTON_TOLERANCE: f64 = 1e-6;
+
+/// Basins of attraction of Newton's method over a raster, row-major.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NewtonGrid {
+    pub width: usize,
+    pub height: usize,
+    pub roots: Vec<(f64, f64)>,
+    /// Index into `roots` each pixel converges to, `None` if it doesn't
+    pub basins: Vec<Option<usize>>,
+    /// Steps taken to converge (or [`NEWTON_MAX_ITER`])
+    pub iterations: Vec<u32>,
+}
+
+impl NewtonGrid {
+    /// Number of pixels in each root's basin.
+    pub fn basin_sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.roots.len()];
+        for &k in self.basins.iter().flatten() {
+            sizes[k] += 1;
+        }
+        sizes
+    }
+
+    /// Fraction of pixels that converged to some root.
+    pub fn converged_fraction(&self) -> f64 {
+        self.basins.iter().filter(|b| b.is_some()).count() as f64 / self.basins.len().max(1) as f64
+    }
+}
+
+/// The `n` roots of zⁿ − 1, starting at 1 and going counter-clockwise.
+pub fn roots_of_unity(n: usize) -> Vec<(f64, f64)> {
+    (0..n)
+        .map(|k| {
+            let a = 2.0 * PI * k as f64 / n as f64;
+            (a.cos(), a.sin())
+        })
+        .collect()
+}
+
+/// Newton's method on the polynomial with the given roots from each pixel of
+/// `region`, recording which root it lands on and how quickly.
+///
+/// For p(z) = ∏(z − rₖ) the step z − p/p′ is z − 1 / Σ 1/(z − rₖ), so the
+/// polynomial never has to be expanded. Rows run concurrently with the
+/// `parallel` feature, giving the same grid either way.
+pub fn newton_fractal(polynomial_roots: &[(f64, f64)], region: &Region, width: usize, height: usize) -> NewtonGrid {
+    let mut cells = vec![(None, NEWTON_MAX_ITER); width * height];
+    for_each_row(&mut cells, width, |y, row| {
+        for (x, cell) in row.iter_mut().enumerate() {
+            *cell = newton_basin(polynomial_roots, region.pixel(x, y, width, height));
+        }
+    });
+    let (basins, iterations) = cells.into_iter().unzip();
+    NewtonGrid { width, height, roots: polynomial_roots.to_vec(), basins, iterations }
+}
+
+/// The root Newton's method reaches from `z`, and the steps it took.
+fn newton_basin(roots: &[(f64, f64)], (mut zx, mut zy): (f64, f64)) -> (Option<usize>, u32) {
+    for i in 0..NEWTON_MAX_ITER {
+        if let Some(k) = roots.iter().position(|&(rx, ry)| (zx - rx).hypot(zy - ry) < NEWTON_TOLERANCE) {
+            return (Some(k), i);
+        }
+        // Σ 1/(z − rₖ), each term the conjugate over the squared modulus
+        let (mut sx, mut sy) = (0.0, 0.0);
+        for &(rx, ry) in roots {
+            let (dx, dy) = (zx - rx, zy - ry);
+            let d2 = dx * dx + dy * dy;
+            sx += dx / d2;
+            sy -= dy / d2;
+        }
+        let s2 = sx * sx + sy * sy;
+        if !(s2 > 0.0 && s2.is_finite()) {
+            break;
+        }
+        zx -= sx / s2;
+        zy += sy / s2;
+    }
+    (None, NEWTON_MAX_ITER)
+}
+
+/// Render Newton basins with one golden-angle hue per root, darker the more
+/// steps a pixel took; pixels that never converge are black.
+pub fn newton_to_svg(grid: &NewtonGrid, cell: f64) -> String {
+    newton_to_svg_with_theme(grid, cell, &Theme::dark())
+}
+
+/// [`newton_to_svg`] in `theme`; with a palette, each root's basin takes it
+/// at its hue's place around the wheel, without the shading.
+pub fn newton_to_svg_with_theme(grid: &NewtonGrid, cell: f64, theme: &Theme) -> String {
+    // Root index in the integer part, brightness in the fraction
+    let top = (NEWTON_MAX_ITER as f64).ln_1p();
+    let values: Vec<f64> = grid
+        .basins
+        .iter()
+        .zip(&grid.iterations)
+        .map(|(basin, &n)| basin.map_or(-1.0, |k| k as f64 + 0.9 * (1.0 - (n as f64).ln_1p() / top)))
+        .collect();
+    let color = |v: f64| {
+        if v < 0.0 {
+            return String::from("#000000");
+        }
+        let hue = crate::render::golden_hue(30.0, v as usize);
+        theme.color(hue / 360.0, crate::render::hsl(hue, 70.0, 10.0 + 60.0 * v.fract() / 0.9))
+    };
+    crate::render::raster_svg_with_theme(grid.width, grid.height, cell, &values, color, theme)
+}
+
 /// Calculate fractal dimension estimate using box-counting.
 pub fn box_counting_dimension(points: &[Point], box_sizes: &[f64]) -> Vec<(f64, f64)> {
     let mut results = Vec::new();
     for &size in box_sizes {
-        let mut boxes = std::collections::HashSet::new();
+        let mut boxes = Set::new();
         for p in points {
             let bx = (p.x / size).floor() as i64;
             let by = (p.y / size).floor() as i64;
@@ -165,6 +1310,48 @@ pub fn box_counting_dimension(points: &[Point], box_sizes: &[f64]) -> Vec<(f64,
     results
 }
 
+/// Box counting on a `width`-wide raster: for every box side in cells,
+/// the number of `size`×`size` boxes holding at least one set cell, as
+/// `(ln size, ln count)` pairs ready for [`estimate_dimension`]. Unlike
+/// [`box_counting_dimension`] this sees area-filling patterns — a
+/// thresholded Turing grid, a cellular automaton's space-time diagram, an
+/// escape-time mask — at their own resolution.
+pub fn box_counting_dimension_raster(mask: &[bool], width: usize, box_sizes: &[usize]) -> Vec<(f64, f64)> {
+    let mut results = Vec::new();
+    if width == 0 {
+        return results;
+    }
+    for &size in box_sizes.iter().filter(|&&s| s > 0) {
+        let mut boxes = Set::new();
+        for (k, _) in mask.iter().enumerate().filter(|(_, &set)| set) {
+            boxes.insert((k % width / size, k / width / size));
+        }
+        if !boxes.is_empty() {
+            results.push(((size as f64).ln(), (boxes.len() as f64).ln()));
+        }
+    }
+    results
+}
+
+/// [`box_counting_dimension_raster`] over the cells of a scalar raster at
+/// or above `threshold`.
+pub fn box_counting_dimension_scalar(values: &[f64], width: usize, threshold: f64, box_sizes: &[usize]) -> Vec<(f64, f64)> {
+    let mask: Vec<bool> = values.iter().map(|&v| v >= threshold).collect();
+    box_counting_dimension_raster(&mask, width, box_sizes)
+}
+
+/// Power-of-two box sides for a `width`×`height` raster, from single cells
+/// up to a quarter of the shorter side so the largest boxes still count.
+pub fn raster_box_sizes(width: usize, height: usize) -> Vec<usize> {
+    let mut sizes = Vec::new();
+    let mut size = 1;
+    while size * 4 <= width.min(height) {
+        sizes.push(size);
+        size *= 2;
+    }
+    sizes
+}
+
 /// Estimate fractal dimension from box-counting data.
 pub fn estimate_dimension(data: &[(f64, f64)]) -> f64 {
     if data.len() < 2 {
@@ -183,39 +1370,93 @@ pub fn estimate_dimension(data: &[(f64, f64)]) -> f64 {
     -((n * sum_xy - sum_x * sum_y) / denom)
 }
 
+/// A fractal and its iteration settings, ready to generate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fractal {
+    /// Barnsley fern from `points` chaos-game steps
+    Fern { points: usize, seed: u64 },
+    /// Koch snowflake after `iterations` subdivisions
+    Koch { iterations: usize },
+    /// Sierpinski triangle from `points` chaos-game steps
+    Sierpinski { points: usize, seed: u64 },
+    /// Mandelbrot set over `region`, as log-scaled escape counts (0 inside)
+    Mandelbrot { region: Region, width: usize, height: usize, max_iter: u32 },
+}
+
+impl Generator for Fractal {
+    fn generate(&self) -> Geometry {
+        // The generators above are y-up
+        let flip = |points: Vec<Point>| -> Vec<Point> { points.into_iter().map(|p| Point { x: p.x, y: -p.y }).collect() };
+        match *self {
+            Fractal::Fern { points, seed } => Geometry::Points(flip(barnsley_fern(points, seed))),
+            Fractal::Koch { iterations } => Geometry::Polygons(vec![flip(koch_variant(&KochVariant::Snowflake, iterations))]),
+            Fractal::Sierpinski { points, seed } => Geometry::Points(flip(sierpinski_triangle(points, seed))),
+            Fractal::Mandelbrot { region, width, height, max_iter } => {
+                let grid = mandelbrot_grid(&region, width, height, max_iter);
+                let values = grid.shades().into_iter().map(|t| t.unwrap_or(0.0)).collect();
+                Geometry::Grid { cols: width, rows: height, values }
+            }
+        }
+    }
+}
+
 /// Generate SVG for Barnsley fern.
 pub fn fern_to_svg(points: &[Point]) -> String {
+    fern_to_svg_with_dots(points, Dots::Circles)
+}
+
+/// [`fern_to_svg`], its dots written as `dots` says.
+pub fn fern_to_svg_with_dots(points: &[Point], dots: Dots) -> String {
+    fern_to_svg_with_theme(points, dots, &Theme::dark())
+}
+
+/// [`fern_to_svg_with_dots`] in `theme`; with a palette, the fern runs
+/// along it from its foot to its tip.
+pub fn fern_to_svg_with_theme(points: &[Point], dots: Dots, theme: &Theme) -> String {
     if points.is_empty() {
         return String::from(r##"<svg xmlns="http://www.w3.org/2000/svg" width="600" height="800"></svg>"##);
     }
-    let w = 600;
-    let h = 800;
+    let (w, h) = (600.0, 800.0);
     // Fern coords: x in [-2.5, 2.5], y in [0, 10]
-    let scale_x = w as f64 / 5.5;
-    let scale_y = h as f64 / 11.0;
-
-    let mut svg = format!(
-        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
-<rect width="{w}" height="{h}" fill="#0a0a1a"/>
-"##
-    );
+    let scale_x = w / 5.5;
+    let scale_y = h / 11.0;
 
+    let mut canvas = SvgCanvas::new(w, h).with_dots(dots).with_theme(theme);
     for p in points {
-        let sx = (p.x + 2.75) * scale_x;
-        let sy = h as f64 - (p.y * scale_y);
+        let center = Point { x: (p.x + 2.75) * scale_x, y: h - p.y * scale_y };
         let green = 100 + ((p.y / 10.0) * 155.0) as u8;
-        svg.push_str(&format!(
-            r##"<circle cx="{:.1}" cy="{:.1}" r="0.5" fill="rgb(30,{green},50)" opacity="0.7"/>
-"##,
-            sx, sy
-        ));
+        let fill = theme.color((p.y / 10.0).clamp(0.0, 1.0), format!("rgb(30,{green},50)"));
+        canvas.circle(center, 0.5, &Style::fill(fill).with_opacity(0.7));
     }
-    svg.push_str("</svg>");
-    svg
+    canvas.finish()
+}
+
+/// Plot chaos-game Sierpinski points fitted to their bounding box, hue
+/// sweeping from teal at the left corner to violet at the right.
+pub fn sierpinski_to_svg(points: &[Point]) -> String {
+    sierpinski_to_svg_with_dots(points, Dots::Circles)
+}
+
+/// [`sierpinski_to_svg`], its dots written as `dots` says.
+pub fn sierpinski_to_svg_with_dots(points: &[Point], dots: Dots) -> String {
+    sierpinski_to_svg_with_theme(points, dots, &Theme::dark())
+}
+
+/// [`sierpinski_to_svg_with_dots`] in `theme`.
+pub fn sierpinski_to_svg_with_theme(points: &[Point], dots: Dots, theme: &Theme) -> String {
+    let xy: Vec<(f64, f64)> = points.iter().map(|p| (p.x, p.y)).collect();
+    let color = |_, u: f64, v: f64| crate::render::hsl(170.0 + 110.0 * u, 70.0, 45.0 + 20.0 * v);
+    crate::render::point_cloud_svg_with_theme(&xy, 600.0, 0.6, dots, color, theme)
 }
 
 /// Generate SVG for Koch snowflake.
 pub fn koch_to_svg(points: &[Point]) -> String {
+    koch_to_svg_with_theme(points, &Theme::dark())
+}
+
+/// [`koch_to_svg`] in `theme`.
+pub fn koch_to_svg_with_theme(points: &[Point], theme: &Theme) -> String {
     let w = 700;
     let h = 700;
     let cx = w as f64 / 2.0;
@@ -223,20 +1464,41 @@ pub fn koch_to_svg(points: &[Point]) -> String {
 
     let mut svg = format!(
         r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
-<rect width="{w}" height="{h}" fill="#0a0a2e"/>
-<polygon points=""##
+<rect width="{w}" height="{h}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
     );
+    let xy = |p: &Point| format!("{:.1},{:.1} ", cx + p.x, cy - p.y);
+    let width = theme.stroke_width(1.5);
 
-    for p in points {
-        svg.push_str(&format!("{:.1},{:.1} ", cx + p.x, cy - p.y));
+    if theme.palette.is_none() {
+        let outline: String = points.iter().map(xy).collect();
+        svg.push_str(&format!(r##"<polygon points="{outline}" fill="none" stroke="#4fc3f7" stroke-width="{width}"/>
+"##));
+    } else {
+        // The palette runs once around the outline in 64 pieces, each sharing
+        // its last point with the next
+        let ring: Vec<&Point> = points.iter().chain(points.first()).collect();
+        let edges = ring.len().saturating_sub(1);
+        let chunk = edges.div_ceil(64).max(1);
+        for (k, start) in (0..edges).step_by(chunk).enumerate() {
+            let piece: String = ring[start..=(start + chunk).min(edges)].iter().map(|p| xy(p)).collect();
+            svg.push_str(&format!(
+                r##"<polyline points="{piece}" fill="none" stroke="{}" stroke-width="{width}" stroke-linejoin="round"/>
+"##,
+                theme.color(theme::place(k, edges.div_ceil(chunk)), "#4fc3f7")
+            ));
+        }
     }
-
-    svg.push_str(r##"" fill="none" stroke="#4fc3f7" stroke-width="1.5"/>
-</svg>"##);
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
     svg
 }
 
 /// Simple deterministic RNG (xorshift64) for reproducible fractals.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimpleRng {
     state: u64,
 }
@@ -266,6 +1528,12 @@ impl SimpleRng {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_checked_koch_limit() {
+        assert_eq!(checked_koch_snowflake(2).unwrap().len(), checked_koch_snowflake(2).unwrap().len());
+        assert!(checked_koch_snowflake(MAX_KOCH_ITERATIONS + 1).is_err());
+    }
+
     #[test]
     fn test_barnsley_fern_bounds() {
         let points = barnsley_fern(10000, 42);
@@ -290,19 +1558,87 @@ mod tests {
 
     #[test]
     fn test_koch_snowflake_initial() {
-        let points = koch_snowflake(0);
+        let points = checked_koch_snowflake(0).unwrap();
         assert_eq!(points.len(), 4); // triangle + closing point
     }
 
     #[test]
     fn test_koch_snowflake_growth() {
-        let p0 = koch_snowflake(0);
-        let p1 = koch_snowflake(1);
-        let p2 = koch_snowflake(2);
+        let p0 = checked_koch_snowflake(0).unwrap();
+        let p1 = checked_koch_snowflake(1).unwrap();
+        let p2 = checked_koch_snowflake(2).unwrap();
         assert!(p1.len() > p0.len());
         assert!(p2.len() > p1.len());
     }
 
+    #[test]
+    fn test_koch_variants() {
+        let area = |points: &[Point]| points.windows(2).map(|w| w[0].x * w[1].y - w[1].x * w[0].y).sum::<f64>().abs() / 2.0;
+        let triangle = area(&checked_koch_snowflake(0).unwrap());
+        // Each round adds (or removes) 3·4ⁿ⁻¹ bumps of 1/9ⁿ the triangle
+        assert!((area(&checked_koch_snowflake(1).unwrap()) / triangle - 4.0 / 3.0).abs() < 1e-12);
+        assert!((area(&koch_variant(&KochVariant::AntiSnowflake, 1)) / triangle - 2.0 / 3.0).abs() < 1e-12);
+        // The Minkowski island's square wave trades area in and out evenly
+        let island = koch_variant(&KochVariant::QuadraticIsland, 3);
+        assert_eq!(island.len(), 4 * 8usize.pow(3) + 1);
+        assert!((area(&island) - 300.0 * 300.0).abs() < 1e-6);
+        assert_eq!(island.first(), island.last());
+        // A Cesàro edge keeps its ends and four equal segments
+        let cesaro = KochVariant::Cesaro { angle: 85.0 };
+        let g = cesaro.generator();
+        let lengths: Vec<f64> = g.windows(2).map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1)).collect();
+        assert!(lengths.iter().all(|l| (l - lengths[0]).abs() < 1e-12));
+        assert_eq!(g.last(), Some(&(1.0, 0.0)));
+        assert!(area(&koch_variant(&cesaro, 2)) < 300.0 * 300.0);
+        // Limits follow each variant's growth
+        assert_eq!(KochVariant::Snowflake.max_iterations(), MAX_KOCH_ITERATIONS);
+        assert_eq!(KochVariant::QuadraticIsland.max_iterations(), 5);
+        assert!(checked_koch_variant(&KochVariant::QuadraticIsland, 6).is_err());
+        assert!(checked_koch_variant(&KochVariant::Cesaro { angle: 180.0 }, 1).is_err());
+        assert_eq!(KochVariant::from_name("cesaro", 70.0), Some(KochVariant::Cesaro { angle: 70.0 }));
+    }
+
+    #[test]
+    fn test_sierpinski_carpet() {
+        let squares = sierpinski_carpet(3).unwrap();
+        assert_eq!(squares.len(), 512);
+        // Subdivision and the base-3 test agree on every cell
+        let mut cells: Vec<(usize, usize)> = squares.iter().map(|p| ((p.x * 27.0).round() as usize, (p.y * 27.0).round() as usize)).collect();
+        cells.sort_unstable();
+        let mut expected: Vec<(usize, usize)> = (0..27 * 27).map(|k| (k % 27, k / 27)).filter(|&(x, y)| in_sierpinski_carpet(x, y)).collect();
+        expected.sort_unstable();
+        assert_eq!(cells, expected);
+        assert!(!in_sierpinski_carpet(13, 13) && !in_sierpinski_carpet(4, 10) && in_sierpinski_carpet(2, 4));
+        assert!(sierpinski_carpet(MAX_CARPET_DEPTH + 1).is_err());
+        assert!(carpet_to_svg(2).contains("<rect"));
+    }
+
+    #[test]
+    fn test_menger_sponge() {
+        assert_eq!(menger_sponge(0).unwrap(), vec![[0, 0, 0]]);
+        let cubes = menger_sponge(2).unwrap();
+        assert_eq!(cubes.len(), 400);
+        assert!(cubes.iter().all(|&[x, y, z]| in_menger_sponge(x, y, z)));
+        assert!(!in_menger_sponge(1, 1, 0) && in_menger_sponge(1, 0, 0));
+        assert!(menger_sponge(MAX_MENGER_DEPTH + 1).is_err());
+        // Surface area 2(20/9)ⁿ + 4(8/9)ⁿ of the unit cube: 1056 faces at depth 2
+        let mesh = menger_mesh(2, 9.0).unwrap();
+        assert_eq!(mesh.triangles.len(), 2 * 1056);
+        assert!(mesh.boundary_edges().is_empty());
+        // Every face points away from the solid
+        for t in 0..mesh.triangles.len() {
+            let normal = mesh.normal(t);
+            let [a, b, c] = mesh.triangles[t].map(|k| mesh.vertices[k]);
+            let center = [(a.x + b.x + c.x) / 3.0, (a.y + b.y + c.y) / 3.0, (a.z + b.z + c.z) / 3.0];
+            let inside = [center[0] - 0.01 * normal.x, center[1] - 0.01 * normal.y, center[2] - 0.01 * normal.z];
+            assert!(in_menger_sponge(inside[0] as usize, inside[1] as usize, inside[2] as usize));
+        }
+        let svg = menger_to_svg(1, &Projection::Isometric).unwrap();
+        // From a corner three of the six face directions show
+        let drawn = svg.matches("<polygon").count();
+        assert!(drawn > 0 && drawn < 72);
+    }
+
     #[test]
     fn test_sierpinski_triangle() {
         let points = sierpinski_triangle(1000, 42);
@@ -325,6 +1661,233 @@ mod tests {
         assert!(mandelbrot_escape(5.0, 5.0, 100) > 0);
     }
 
+    #[test]
+    fn test_mandelbrot_grid() {
+        let grid = mandelbrot_grid(&Region::MANDELBROT, 70, 50, 100);
+        assert_eq!(grid.counts.len(), 70 * 50);
+        // The main cardioid around −0.25 is inside, the corners escape at once
+        let (x, y) = (((-0.25 + 2.5) / 3.5 * 70.0) as usize, 25);
+        assert_eq!(grid.counts[y * 70 + x], 0.0);
+        assert!(grid.counts[0] > 0.0 && grid.counts[0] < 3.0);
+        // Symmetric about the real axis
+        for k in 0..25 * 70 {
+            let mirror = (49 - k / 70) * 70 + k % 70;
+            assert!((grid.counts[k] - grid.counts[mirror]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_escape_grid_matches_pixels() {
+        // Row-parallel filling lands every value at its own pixel
+        let region = Region::centered(-0.7, 0.3, 0.5, 0.6);
+        let grid = mandelbrot_grid(&region, 40, 24, 200);
+        for (k, &count) in grid.counts.iter().enumerate() {
+            let (re, im) = region.pixel(k % 40, k / 40, 40, 24);
+            assert_eq!(count, mandelbrot_smooth(re, im, 200));
+        }
+    }
+
+    #[test]
+    fn test_julia_grid() {
+        // Pixel centers at odd sixteenths are exact, so mirrored pixels are exact negatives
+        let region = Region { re_min: -2.0, re_max: 2.0, im_min: -2.0, im_max: 2.0 };
+        // For c = 0 the filled Julia set is the closed unit disc
+        let grid = julia_grid(&region, 32, 32, (0.0, 0.0), 100);
+        for (k, &count) in grid.counts.iter().enumerate() {
+            let (re, im) = region.pixel(k % 32, k / 32, 32, 32);
+            assert_eq!(count == 0.0, re.hypot(im) <= 1.0, "pixel {k}");
+        }
+        // Julia sets are symmetric under z → −z
+        let grid = julia_grid(&region, 32, 32, (-0.8, 0.156), 100);
+        let n = grid.counts.len();
+        assert!((0..n).all(|k| grid.counts[k] == grid.counts[n - 1 - k]));
+    }
+
+    #[test]
+    fn test_burning_ship_and_multibrot() {
+        // On the real axis the fold does nothing, so the ship matches the Mandelbrot set
+        for k in 0..40 {
+            let cx = -2.2 + 0.07 * k as f64;
+            assert_eq!(burning_ship_smooth(cx, 0.0, 200), mandelbrot_smooth(cx, 0.0, 200), "c = {cx}");
+        }
+        // d = 2 is the Mandelbrot set; fractional powers go the polar way
+        for &(cx, cy) in &[(-0.75, 0.1), (0.3, 0.5), (-1.5, 0.0), (0.25, 0.0), (-0.1, 0.9)] {
+            assert_eq!(multibrot_smooth(cx, cy, 2.0, 300), mandelbrot_smooth(cx, cy, 300), "c = ({cx}, {cy})");
+        }
+        let (a, b) = (multibrot_smooth(0.3, 0.5, 2.0 + 1e-12, 300), mandelbrot_smooth(0.3, 0.5, 300));
+        assert!((a - b).abs() < 1e-6, "{a} vs {b}");
+        // z⁵ + c has the fourfold symmetry c → ic
+        let (a, b) = (multibrot_smooth(0.9, 0.3, 5.0, 100), multibrot_smooth(-0.3, 0.9, 5.0, 100));
+        assert!((a - b).abs() < 1e-6);
+        let region = Region::BURNING_SHIP;
+        let grid = burning_ship_grid(&region, 40, 30, 100);
+        assert!(grid.inside_fraction() > 0.05 && grid.inside_fraction() < 0.5);
+    }
+
+    #[test]
+    fn test_orbit_traps() {
+        let point = OrbitTrap::from_name("point").unwrap();
+        let line = OrbitTrap::from_name("line").unwrap();
+        let circle = OrbitTrap::from_name("circle").unwrap();
+        assert_eq!(point.distance(3.0, 4.0), 5.0);
+        assert!((line.distance(-2.0, 7.0) - 2.0).abs() < 1e-12);
+        assert_eq!(circle.distance(0.0, -2.0), 1.5);
+        assert!(OrbitTrap::from_name("star").is_none());
+        // c = 0 sits on the origin forever; c = −1 cycles 0 → −1 → 0 through the trap
+        assert_eq!(trap_distance((0.0, 0.0), (0.0, 0.0), 10, &point), 0.0);
+        assert_eq!(trap_distance((0.0, 0.0), (-1.0, 0.0), 10, &point), 0.0);
+        // Traps shade the interior too, unlike escape time
+        let region = Region::MANDELBROT;
+        let grid = mandelbrot_trap_grid(&region, 35, 25, 100, &circle);
+        let escape = mandelbrot_grid(&region, 35, 25, 100);
+        let shades = grid.shades();
+        let inside: Vec<f64> = (0..shades.len()).filter(|&k| escape.counts[k] == 0.0).map(|k| shades[k]).collect();
+        assert!(inside.iter().any(|&t| t > 0.5) && inside.iter().any(|&t| t < 0.5));
+        assert!(shades.iter().all(|t| (0.0..=1.0).contains(t)));
+        assert_eq!(grid.to_rgb(crate::render::magma_rgb).len(), 35 * 25 * 3);
+    }
+
+    #[test]
+    fn test_deep_grid_matches_f64_grid() {
+        let (re, im, span) = (-0.743, 0.131, 0.02);
+        let region = Region::centered(re, im, span, 0.75);
+        let plain = mandelbrot_grid(&region, 40, 30, 500);
+        let bits = deep_zoom_bits(span, 40);
+        let deep = mandelbrot_deep_grid(&Fixed::from_f64(re, bits), &Fixed::from_f64(im, bits), span, 40, 30, 500);
+        let close = plain.counts.iter().zip(&deep.counts).filter(|(a, b)| (*a - *b).abs() < 1e-6).count();
+        assert!(close >= 1190, "{close} of 1200 pixels agree");
+    }
+
+    #[test]
+    fn test_deep_zoom_past_f64() {
+        // At a 1e-20 span the pixels are far closer than f64 can tell apart near −0.74
+        let (re, im) = ("-0.743643887037158704752191506114774", "0.131825904205311970493132056385139");
+        let bits = deep_zoom_bits(1e-20, 16);
+        assert!(bits > 128);
+        let deep = mandelbrot_deep_grid(&Fixed::parse(re, bits).unwrap(), &Fixed::parse(im, bits).unwrap(), 1e-20, 16, 12, 12_000);
+        let region = Region::centered(re.parse().unwrap(), im.parse().unwrap(), 1e-20, 0.75);
+        let plain = mandelbrot_grid(&region, 16, 12, 12_000);
+        let distinct = |counts: &[f64]| {
+            let mut bits: Vec<u64> = counts.iter().map(|c| c.to_bits()).collect();
+            bits.sort_unstable();
+            bits.dedup();
+            bits.len()
+        };
+        // Plain f64 collapses the view onto a handful of points; the deep grid resolves every pixel
+        assert!(distinct(&plain.counts) <= 4);
+        assert!(distinct(&deep.counts) > 150, "{} distinct values", distinct(&deep.counts));
+        assert_eq!(deep.inside_fraction(), 0.0);
+    }
+
+    #[test]
+    fn test_zoom_animation() {
+        let bits = 96;
+        let mut params = ZoomParams {
+            target: ZoomTarget::Mandelbrot,
+            re: Fixed::parse("-0.7436438870371587", bits).unwrap(),
+            im: Fixed::parse("0.1318259042053119", bits).unwrap(),
+            start_zoom: 1.0,
+            end_zoom: 1e4,
+            frames: 5,
+            width: 20,
+            height: 14,
+            max_iter: 400,
+        };
+        params.validate().unwrap();
+        // Every frame magnifies by the same factor
+        let zooms: Vec<f64> = (0..5).map(|i| params.zoom_at(i)).collect();
+        assert_eq!(zooms[0], 1.0);
+        assert!(zooms.iter().zip([1.0, 10.0, 100.0, 1000.0, 10000.0]).all(|(z, want)| (z / want - 1.0).abs() < 1e-12));
+        let frames: Vec<ZoomFrame> = zoom_animation(&params).collect();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0].range, frames[0].grid.escape_range().unwrap());
+        assert_eq!(frames[2].grid, mandelbrot_grid(&Region::centered(params.re.to_f64(), params.im.to_f64(), 3.5 / 100.0, 0.7), 20, 14, 400));
+        // Later frames ease a quarter of the way toward their own range
+        for pair in frames.windows(2) {
+            let own = pair[1].grid.escape_range().unwrap();
+            assert_eq!(pair[1].range, pair[0].range.toward(own, ZOOM_COLOR_EASING));
+            assert!(escape_to_svg_in(&pair[1].grid, pair[1].range, 1.0, crate::render::magma).contains("<rect"));
+        }
+        params.frames = 0;
+        assert!(params.validate().is_err());
+        params.frames = 5;
+        params.target = ZoomTarget::Julia { c: (-0.8, 0.156) };
+        params.end_zoom = 1e12;
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_fibonacci_word_fractal() {
+        assert_eq!(fibonacci_word(0), "");
+        assert_eq!(fibonacci_word(1), "1");
+        assert_eq!(fibonacci_word(2), "0");
+        assert_eq!(fibonacci_word(7), "0100101001001");
+        assert_eq!(fibonacci_word(20).len(), 6765);
+        let points = fibonacci_word_fractal(12);
+        assert_eq!(points.len(), 145);
+        // Unit steps on the lattice, never revisiting a corner
+        assert!(points.windows(2).all(|w| ((w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs()) == 1.0));
+        let mut seen: Vec<(i64, i64)> = points.iter().map(|p| (p.x as i64, p.y as i64)).collect();
+        seen.sort_unstable();
+        seen.dedup();
+        assert_eq!(seen.len(), points.len());
+        assert!(checked_fibonacci_word_fractal(MAX_FIBONACCI_WORD_ITERATIONS + 1).is_err());
+        let svg = fibonacci_word_to_svg(&points);
+        assert_eq!(svg.matches("<polyline").count(), 12);
+    }
+
+    #[test]
+    fn test_newton_fractal() {
+        let roots = roots_of_unity(3);
+        let region = Region { re_min: -2.0, re_max: 2.0, im_min: -2.0, im_max: 2.0 };
+        let grid = newton_fractal(&roots, &region, 32, 32);
+        // Pixels right next to a root fall straight into its basin
+        for (k, &(rx, ry)) in roots.iter().enumerate() {
+            assert_eq!(newton_basin(&roots, (rx + 0.01, ry - 0.01)).0, Some(k));
+        }
+        // z³ − 1 is real, so the basins mirror across the real axis: root 1 ↔ root 2
+        for y in 0..32 {
+            for x in 0..32 {
+                let mirrored = grid.basins[(31 - y) * 32 + x].map(|k| (3 - k) % 3);
+                assert_eq!(grid.basins[y * 32 + x], mirrored, "pixel ({x}, {y})");
+            }
+        }
+        let sizes = grid.basin_sizes();
+        assert_eq!(sizes[1], sizes[2]);
+        assert!(grid.converged_fraction() > 0.95);
+        assert!(newton_to_svg(&grid, 2.0).contains("width=\"64\""));
+        // Without roots nothing converges
+        assert_eq!(newton_fractal(&[], &region, 4, 4).converged_fraction(), 0.0);
+    }
+
+    #[test]
+    fn test_smooth_escape_is_continuous() {
+        assert_eq!(mandelbrot_smooth(0.0, 0.0, 100), 0.0);
+        // Walking outward along the real axis, the integer count jumps between
+        // bands but the smooth count never moves by more than a fraction of one
+        let samples: Vec<f64> = (0..=400).map(|i| mandelbrot_smooth(0.3 + i as f64 * 0.0005, 0.0, 500)).collect();
+        assert!(samples.iter().all(|&v| v > 0.0));
+        let largest_step = samples.windows(2).map(|w| (w[0] - w[1]).abs()).fold(0.0, f64::max);
+        let bands: Set<u32> = (0..=400).map(|i| mandelbrot_escape(0.3 + i as f64 * 0.0005, 0.0, 500)).collect();
+        assert!(bands.len() > 5);
+        assert!(largest_step < 0.1, "{}", largest_step);
+        // Escape times fall as c moves away from the set
+        assert!(samples[0] > samples[400]);
+    }
+
+    #[test]
+    fn test_escape_rendering() {
+        let region = Region::centered(-0.75, 0.0, 3.0, 0.5);
+        assert_eq!(region.pixel(0, 0, 2, 1), (-1.5, 0.0));
+        let grid = mandelbrot_grid(&region, 40, 20, 50);
+        let rgb = grid.to_rgb(crate::render::magma_rgb);
+        assert_eq!(rgb.len(), 40 * 20 * 3);
+        assert!(rgb.chunks(3).any(|c| c == [0, 0, 0]));
+        assert!(escape_to_svg(&grid, 2.0, crate::render::magma).contains("#000000"));
+        let shape = Fractal::Mandelbrot { region, width: 40, height: 20, max_iter: 50 }.generate();
+        assert_eq!(shape.len(), 800);
+    }
+
     #[test]
     fn test_box_counting() {
         let points: Vec<Point> = (0..100).map(|i| {
@@ -336,6 +1899,29 @@ mod tests {
         assert!(!result.is_empty());
     }
 
+    #[test]
+    fn test_box_counting_raster() {
+        assert_eq!(raster_box_sizes(64, 40), vec![1, 2, 4, 8]);
+        assert!(box_counting_dimension_raster(&[], 0, &[1, 2]).is_empty());
+        let filled = vec![true; 64 * 64];
+        let dim = estimate_dimension(&box_counting_dimension_raster(&filled, 64, &raster_box_sizes(64, 64)));
+        assert!((dim - 2.0).abs() < 1e-9, "filled square: {dim}");
+        let diagonal: Vec<bool> = (0..64 * 64).map(|k| k % 64 == k / 64).collect();
+        let dim = estimate_dimension(&box_counting_dimension_raster(&diagonal, 64, &raster_box_sizes(64, 64)));
+        assert!((dim - 1.0).abs() < 1e-9, "diagonal: {dim}");
+        // Boxes aligned with the carpet's own thirds count it exactly
+        let carpet: Vec<bool> = (0..81 * 81).map(|k| in_sierpinski_carpet(k % 81, k / 81)).collect();
+        let dim = estimate_dimension(&box_counting_dimension_raster(&carpet, 81, &[1, 3, 9, 27]));
+        assert!((dim - 8f64.ln() / 3f64.ln()).abs() < 1e-9, "carpet: {dim}");
+        // Rule 90 from one cell draws a Sierpinski triangle in space-time;
+        // boxes that don't line up with it make the estimate rougher
+        let rule = super::super::automata::Rule::Elementary(90);
+        let rows = super::super::automata::evolve(&rule, 257, 128, super::super::automata::InitialState::SingleCell);
+        let values: Vec<f64> = rows.iter().flatten().map(|&c| f64::from(c)).collect();
+        let dim = estimate_dimension(&box_counting_dimension_scalar(&values, 257, 0.5, &raster_box_sizes(257, rows.len())));
+        assert!((dim - 3f64.ln() / 2f64.ln()).abs() < 0.2, "rule 90: {dim}");
+    }
+
     #[test]
     fn test_estimate_dimension_line() {
         // A line should have dimension ≈ 1.0
@@ -363,11 +1949,22 @@ mod tests {
         let svg = fern_to_svg(&points);
         assert!(svg.contains("<svg"));
         assert!(svg.contains("</svg>"));
+        assert_eq!(svg.matches("<circle").count(), 100);
+        let batched = fern_to_svg_with_dots(&points, Dots::Batched);
+        assert!(batched.contains("stroke-linecap=\"round\"") && batched.len() < svg.len(), "{batched}");
+    }
+
+    #[test]
+    fn test_sierpinski_svg_fills_frame() {
+        // The triangle is 520 wide and 450 tall, so its width takes the 600 pixels
+        let svg = sierpinski_to_svg(&sierpinski_triangle(3000, 42));
+        assert!(svg.contains("width=\"600\""));
+        assert_eq!(svg.matches("<circle").count(), 3000);
     }
 
     #[test]
     fn test_koch_svg() {
-        let points = koch_snowflake(2);
+        let points = checked_koch_snowflake(2).unwrap();
         let svg = koch_to_svg(&points);
         assert!(svg.contains("<svg"));
         assert!(svg.contains("<polygon"));
@@ -381,4 +1978,36 @@ mod tests {
             assert_eq!(a.next_u64(), b.next_u64());
         }
     }
+
+    #[test]
+    fn test_fern_with_progress() {
+        let mut last = 0;
+        let points = barnsley_fern_with_progress(5000, 3, &mut |done, _| {
+            last = done;
+            true
+        })
+        .unwrap();
+        assert_eq!(points, barnsley_fern(5000, 3));
+        assert_eq!(last, 5000);
+        assert!(barnsley_fern_with_progress(5000, 3, &mut |done, _| done < 1000).is_err());
+    }
+
+    #[test]
+    fn test_fern_iter_matches_vec() {
+        let streamed: Vec<Point> = barnsley_fern_iter(9).take(1000).collect();
+        assert_eq!(streamed, barnsley_fern(1000, 9));
+        // Skipping ahead needs no allocation
+        let far = barnsley_fern_iter(9).nth(200_000).unwrap();
+        assert!(far.y >= 0.0 && far.y <= 10.1);
+    }
+
+    #[test]
+    fn test_fern_iter_clone_forks_walk() {
+        let mut walk: FernIter = barnsley_fern_iter(4);
+        walk.nth(99);
+        let fork = walk.clone();
+        let rest: Vec<Point> = walk.take(50).collect();
+        assert_eq!(rest, fork.take(50).collect::<Vec<_>>());
+        assert_eq!(rest, barnsley_fern(150, 4)[100..]);
+    }
 }
@@ -0,0 +1,262 @@
+//! Lissajous figures and harmonographs — pendulums drawing organic curves.
+//!
+//! A Lissajous figure combines two perpendicular oscillations:
+//!   x = A sin(a t + δ),  y = B sin(b t)
+//!
+//! A harmonograph adds friction: each pendulum contributes
+//!   A sin(f t + p) e^(-d t)
+//! so the pen spirals inward as the swings decay. Slightly detuned frequency
+//! ratios make the figure precess, producing shell- and flower-like forms.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::Theme;
+use super::fractals::Point;
+
+/// A point on a traced curve with its time stamp.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TracePoint {
+    pub x: f64,
+    pub y: f64,
+    pub t: f64,
+}
+
+/// Parameters for a Lissajous figure with integer frequency ratio a:b.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LissajousParams {
+    pub a: u32,
+    pub b: u32,
+    /// Phase offset δ in radians
+    pub delta: f64,
+    pub amp_x: f64,
+    pub amp_y: f64,
+}
+
+impl Default for LissajousParams {
+    fn default() -> Self {
+        Self { a: 3, b: 2, delta: PI / 2.0, amp_x: 1.0, amp_y: 1.0 }
+    }
+}
+
+/// Trace a closed Lissajous figure over one full period t ∈ [0, 2π].
+pub fn lissajous(params: &LissajousParams, num_points: usize) -> Vec<TracePoint> {
+    (0..=num_points)
+        .map(|i| {
+            let t = 2.0 * PI * i as f64 / num_points.max(1) as f64;
+            TracePoint {
+                x: params.amp_x * (params.a as f64 * t + params.delta).sin(),
+                y: params.amp_y * (params.b as f64 * t).sin(),
+                t,
+            }
+        })
+        .collect()
+}
+
+/// A single damped pendulum.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pendulum {
+    pub amplitude: f64,
+    /// Angular frequency (radians per time unit)
+    pub frequency: f64,
+    /// Phase in radians
+    pub phase: f64,
+    /// Exponential damping rate
+    pub damping: f64,
+}
+
+impl Pendulum {
+    pub fn displacement(&self, t: f64) -> f64 {
+        self.amplitude * (self.frequency * t + self.phase).sin() * (-self.damping * t).exp()
+    }
+}
+
+/// A harmonograph: pendulums summed along each axis.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Harmonograph {
+    pub x: Vec<Pendulum>,
+    pub y: Vec<Pendulum>,
+}
+
+impl Default for Harmonograph {
+    /// A classic lateral two-pendulum harmonograph tuned close to 3:2.
+    fn default() -> Self {
+        Self::two_pendulum(3.0, 2.01, PI / 2.0, 0.004)
+    }
+}
+
+impl Harmonograph {
+    /// One pendulum per axis: a decaying, precessing Lissajous figure.
+    pub fn two_pendulum(fx: f64, fy: f64, phase: f64, damping: f64) -> Self {
+        Harmonograph {
+            x: vec![Pendulum { amplitude: 1.0, frequency: fx, phase, damping }],
+            y: vec![Pendulum { amplitude: 1.0, frequency: fy, phase: 0.0, damping }],
+        }
+    }
+
+    /// Two lateral pendulums plus a rotary pendulum moving the paper in a circle.
+    ///
+    /// The rotary pendulum contributes sin to x and cos to y at the same
+    /// frequency, which turns the figure into spiralling rosettes.
+    pub fn three_pendulum(fx: f64, fy: f64, f_rotary: f64, damping: f64) -> Self {
+        let rotary = |phase| Pendulum { amplitude: 0.5, frequency: f_rotary, phase, damping };
+        Harmonograph {
+            x: vec![Pendulum { amplitude: 0.5, frequency: fx, phase: PI / 2.0, damping }, rotary(0.0)],
+            y: vec![Pendulum { amplitude: 0.5, frequency: fy, phase: 0.0, damping }, rotary(PI / 2.0)],
+        }
+    }
+
+    pub fn position(&self, t: f64) -> (f64, f64) {
+        (
+            self.x.iter().map(|p| p.displacement(t)).sum(),
+            self.y.iter().map(|p| p.displacement(t)).sum(),
+        )
+    }
+
+    /// Trace the pen over t ∈ [0, duration].
+    pub fn trace(&self, duration: f64, num_points: usize) -> Vec<TracePoint> {
+        (0..=num_points)
+            .map(|i| {
+                let t = duration * i as f64 / num_points.max(1) as f64;
+                let (x, y) = self.position(t);
+                TracePoint { x, y, t }
+            })
+            .collect()
+    }
+}
+
+impl Generator for LissajousParams {
+    fn generate(&self) -> Geometry {
+        Geometry::Polylines(vec![lissajous(self, 4000).iter().map(|p| Point { x: p.x, y: p.y }).collect()])
+    }
+}
+
+/// Render a trace as SVG, fading hue and opacity over time.
+///
+/// The curve is split into short polylines so early strokes are bright and
+/// late, decayed strokes fade out like drying ink.
+pub fn to_svg(points: &[TracePoint], hue_start: f64, hue_end: f64) -> String {
+    to_svg_with_theme(points, hue_start, hue_end, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, the trace runs along it in time.
+pub fn to_svg_with_theme(points: &[TracePoint], hue_start: f64, hue_end: f64, theme: &Theme) -> String {
+    let size = 800.0;
+    let extent = points.iter().map(|p| p.x.abs().max(p.y.abs())).fold(1e-9, f64::max);
+    let scale = (size / 2.0 - 30.0) / extent;
+    let c = size / 2.0;
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
+    );
+    if points.len() < 2 {
+        svg.push_str(theme.foreground_end());
+        svg.push_str("</svg>");
+        return svg;
+    }
+    let chunk = (points.len() / 300).max(2);
+    let t_max = points.last().map(|p| p.t).unwrap_or(1.0).max(1e-9);
+    let mut start = 0;
+    while start + 1 < points.len() {
+        let end = (start + chunk).min(points.len() - 1);
+        let f = points[start].t / t_max;
+        let coords: Vec<String> = points[start..=end]
+            .iter()
+            .map(|p| format!("{:.1},{:.1}", c + p.x * scale, c - p.y * scale))
+            .collect();
+        svg.push_str(&format!(
+            r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-opacity="{:.2}"/>
+"##,
+            coords.join(" "),
+            theme.color(f, crate::render::hsl(hue_start + (hue_end - hue_start) * f, 75.0, 60.0)),
+            theme.stroke_width(1.0),
+            1.0 - 0.8 * f
+        ));
+        start = end;
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lissajous_closes() {
+        let pts = lissajous(&LissajousParams::default(), 500);
+        let (first, last) = (pts[0], pts[pts.len() - 1]);
+        assert!((first.x - last.x).abs() < 1e-9 && (first.y - last.y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lissajous_bounded() {
+        let p = LissajousParams { a: 5, b: 4, delta: 0.3, amp_x: 2.0, amp_y: 3.0 };
+        for pt in lissajous(&p, 1000) {
+            assert!(pt.x.abs() <= 2.0 + 1e-12 && pt.y.abs() <= 3.0 + 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_one_to_one_ellipse() {
+        // a = b with δ = π/2 traces a circle
+        let p = LissajousParams { a: 1, b: 1, delta: PI / 2.0, amp_x: 1.0, amp_y: 1.0 };
+        for pt in lissajous(&p, 100) {
+            assert!(((pt.x * pt.x + pt.y * pt.y).sqrt() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_harmonograph_decays() {
+        let h = Harmonograph::default();
+        let pts = h.trace(1000.0, 20000);
+        let early = pts[..1000].iter().map(|p| p.x.hypot(p.y)).fold(0.0, f64::max);
+        let late = pts[19000..].iter().map(|p| p.x.hypot(p.y)).fold(0.0, f64::max);
+        assert!(late < early * 0.1, "Swing should decay: {} vs {}", early, late);
+    }
+
+    #[test]
+    fn test_undamped_matches_lissajous() {
+        let h = Harmonograph::two_pendulum(3.0, 2.0, PI / 2.0, 0.0);
+        let l = LissajousParams::default();
+        let a = h.trace(2.0 * PI, 200);
+        let b = lissajous(&l, 200);
+        for (p, q) in a.iter().zip(b.iter()) {
+            assert!((p.x - q.x).abs() < 1e-9 && (p.y - q.y).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_three_pendulum_starts_bounded() {
+        let h = Harmonograph::three_pendulum(2.0, 3.0, 1.0, 0.01);
+        assert_eq!(h.x.len(), 2);
+        for p in h.trace(100.0, 1000) {
+            assert!(p.x.abs() <= 1.0 + 1e-9 && p.y.abs() <= 1.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_trace_times_increase() {
+        let pts = Harmonograph::default().trace(50.0, 100);
+        assert_eq!(pts.len(), 101);
+        assert!(pts.windows(2).all(|w| w[1].t > w[0].t));
+    }
+
+    #[test]
+    fn test_svg_fading() {
+        let pts = Harmonograph::default().trace(200.0, 3000);
+        let svg = to_svg(&pts, 200.0, 320.0);
+        assert!(svg.contains("<polyline"));
+        assert!(svg.contains("stroke-opacity=\"1.00\""));
+        assert!(svg.matches("<polyline").count() > 10);
+    }
+}
@@ -0,0 +1,499 @@
+//! Iterated function systems — fractals as the fixed point of a few maps.
+//!
+//! A handful of contracting affine maps, each shrinking the plane onto a
+//! piece of itself, has exactly one shape it leaves unchanged: the
+//! attractor. The chaos game finds it by applying randomly chosen maps to a
+//! single point over and over; after a few steps every point lands on the
+//! attractor. Barnsley's fern is four maps; a maple leaf, a Sierpinski
+//! carpet or a spiral are just different lists, several of them built in as
+//! [`Preset`]s.
+//!
+//! Systems are written one map per line,
+//!
+//! ```text
+//! # Sierpinski triangle
+//! name: Sierpinski
+//! #  a    b    c    d    e     f     p
+//!   0.5  0    0    0.5  0     0     0.33
+//!   0.5  0    0    0.5  0.5   0     0.33
+//!   0.5  0    0    0.5  0.25  0.43  0.34
+//! ```
+//!
+//! or as JSON (`{"name": …, "transforms": [[a, b, c, d, e, f, p], …]}`,
+//! each map also allowed as an object with fields `a`–`f` and
+//! `probability`).
+
+use crate::prelude::*;
+use crate::error::{check_count, MathaturaError, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::canvas::Dots;
+use crate::render::theme::Theme;
+use super::fractals::{barnsley_fern_transforms, AffineTransform, Point, SimpleRng};
+
+/// Most maps a system may have.
+pub const MAX_TRANSFORMS: usize = 256;
+
+/// Points discarded while the chaos game settles onto the attractor.
+const TRANSIENT: usize = 20;
+
+/// Chaos-game points drawn by [`Generator::generate`].
+pub const GENERATED_POINTS: usize = 50_000;
+
+/// Side of the longer edge of [`to_svg`] images.
+const SIZE: f64 = 600.0;
+
+/// An iterated function system: affine maps, each applied with its
+/// probability.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IfsSystem {
+    pub name: String,
+    pub transforms: Vec<AffineTransform>,
+}
+
+impl IfsSystem {
+    /// Barnsley's fern.
+    pub fn fern() -> Self {
+        IfsSystem { name: "Barnsley fern".to_string(), transforms: barnsley_fern_transforms() }
+    }
+
+    /// Require at least one map, non-negative probabilities with a positive
+    /// total, and maps that contract the plane, without which the chaos game
+    /// runs off to infinity.
+    pub fn validate(&self) -> Result<()> {
+        check_count("transforms", self.transforms.len(), MAX_TRANSFORMS)?;
+        let mut total = 0.0;
+        for t in &self.transforms {
+            let coefficients = [t.a, t.b, t.c, t.d, t.e, t.f];
+            if let Some(&value) = coefficients.iter().find(|v| !v.is_finite()) {
+                return Err(MathaturaError::InvalidParameter { name: "transform", value, expected: "finite coefficients" });
+            }
+            if !(t.probability.is_finite() && t.probability >= 0.0) {
+                return Err(MathaturaError::InvalidParameter { name: "probability", value: t.probability, expected: "a non-negative number" });
+            }
+            let stretch = stretch(t);
+            if stretch >= 1.0 {
+                return Err(MathaturaError::InvalidParameter { name: "transform", value: stretch, expected: "a contraction, stretching by less than 1" });
+            }
+            total += t.probability;
+        }
+        if total > 0.0 {
+            Ok(())
+        } else {
+            Err(MathaturaError::InvalidParameter { name: "probability", value: total, expected: "a positive total" })
+        }
+    }
+}
+
+/// Built-in systems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Barnsley's fern: a stem and three leaflet maps
+    Fern,
+    /// A lobed maple leaf
+    Maple,
+    /// Sierpinski carpet: a square keeping eight of its nine thirds
+    Carpet,
+    /// A branching tree: trunk, two rotated crowns and a twig
+    Tree,
+    /// A spiral arm of ever smaller spirals
+    Spiral,
+    /// Cantor dust: the four corner ninths of a square, forever
+    CantorDust,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 6] = [Preset::Fern, Preset::Maple, Preset::Carpet, Preset::Tree, Preset::Spiral, Preset::CantorDust];
+
+    /// Look a preset up by its CLI name.
+    pub fn from_name(name: &str) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Fern => "fern",
+            Preset::Maple => "maple",
+            Preset::Carpet => "carpet",
+            Preset::Tree => "tree",
+            Preset::Spiral => "spiral",
+            Preset::CantorDust => "cantor",
+        }
+    }
+
+    /// Base hue for [`to_svg_with_hue`].
+    pub fn hue(self) -> f64 {
+        match self {
+            Preset::Fern | Preset::Tree => 120.0,
+            Preset::Maple => 20.0,
+            Preset::Carpet => 200.0,
+            Preset::Spiral => 280.0,
+            Preset::CantorDust => 45.0,
+        }
+    }
+
+    pub fn system(self) -> IfsSystem {
+        let maps = |rows: &[[f64; 7]]| {
+            rows.iter().map(|&[a, b, c, d, e, f, probability]| AffineTransform { a, b, c, d, e, f, probability }).collect()
+        };
+        let third = 1.0 / 3.0;
+        let thirds = |cells: &[(f64, f64)]| {
+            let p = 1.0 / cells.len() as f64;
+            maps(&cells.iter().map(|&(i, j)| [third, 0.0, 0.0, third, i * third, j * third, p]).collect::<Vec<_>>())
+        };
+        let (name, transforms) = match self {
+            Preset::Fern => return IfsSystem::fern(),
+            Preset::Maple => (
+                "Maple leaf",
+                maps(&[
+                    [0.14, 0.01, 0.0, 0.51, -0.08, -1.31, 0.10],
+                    [0.43, 0.52, -0.45, 0.5, 1.49, -0.75, 0.35],
+                    [0.45, -0.49, 0.47, 0.47, -1.62, -0.74, 0.35],
+                    [0.49, 0.0, 0.0, 0.51, 0.02, 1.62, 0.20],
+                ]),
+            ),
+            Preset::Carpet => (
+                "Sierpinski carpet",
+                thirds(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (0.0, 1.0), (2.0, 1.0), (0.0, 2.0), (1.0, 2.0), (2.0, 2.0)]),
+            ),
+            Preset::Tree => (
+                "Fractal tree",
+                maps(&[
+                    [0.05, 0.0, 0.0, 0.6, 0.0, 0.0, 0.1],
+                    [0.05, 0.0, 0.0, -0.5, 0.0, 1.0, 0.1],
+                    [0.46, -0.321, 0.386, 0.383, 0.0, 0.6, 0.2],
+                    [0.47, -0.154, 0.171, 0.423, 0.0, 1.1, 0.2],
+                    [0.433, 0.275, -0.25, 0.476, 0.0, 1.0, 0.2],
+                    [0.421, 0.257, -0.353, 0.306, 0.0, 0.7, 0.2],
+                ]),
+            ),
+            Preset::Spiral => (
+                "Spiral",
+                maps(&[
+                    [0.787879, -0.424242, 0.242424, 0.859848, 1.758647, 1.408065, 0.90],
+                    [-0.121212, 0.257576, 0.151515, 0.05303, -6.721654, 1.377236, 0.05],
+                    [0.181818, -0.136364, 0.090909, 0.181818, 6.086107, 1.568035, 0.05],
+                ]),
+            ),
+            Preset::CantorDust => ("Cantor dust", thirds(&[(0.0, 0.0), (2.0, 0.0), (0.0, 2.0), (2.0, 2.0)])),
+        };
+        IfsSystem { name: name.to_string(), transforms }
+    }
+}
+
+impl core::str::FromStr for IfsSystem {
+    type Err = MathaturaError;
+
+    fn from_str(source: &str) -> Result<IfsSystem> {
+        parse(source)
+    }
+}
+
+/// Largest factor by which the linear part of `t` stretches any vector.
+fn stretch(t: &AffineTransform) -> f64 {
+    let sum = t.a * t.a + t.b * t.b + t.c * t.c + t.d * t.d;
+    let det = t.a * t.d - t.b * t.c;
+    ((sum + (sum * sum - 4.0 * det * det).max(0.0).sqrt()) / 2.0).sqrt()
+}
+
+/// Give maps without a probability weights proportional to the area they
+/// keep, |ad − bc|, so the attractor fills in evenly. Maps that flatten the
+/// plane, like the fern's stem, still get a small share.
+fn default_probabilities(transforms: &mut [AffineTransform]) {
+    let areas: Vec<f64> = transforms.iter().map(|t| (t.a * t.d - t.b * t.c).abs()).collect();
+    let floor = 0.01 * areas.iter().copied().fold(0.0, f64::max).max(1e-9);
+    let weights: Vec<f64> = areas.iter().map(|&a| a.max(floor)).collect();
+    let total: f64 = weights.iter().sum();
+    for (t, w) in transforms.iter_mut().zip(weights) {
+        t.probability = w / total;
+    }
+}
+
+/// A map from six coefficients and an optional probability, or `None` for
+/// any other count.
+fn transform(numbers: &[f64]) -> Option<(AffineTransform, bool)> {
+    let (coefficients, probability) = match numbers.len() {
+        6 => (numbers, None),
+        7 => (&numbers[..6], Some(numbers[6])),
+        _ => return None,
+    };
+    let [a, b, c, d, e, f]: [f64; 6] = coefficients.try_into().ok()?;
+    Some((AffineTransform { a, b, c, d, e, f, probability: probability.unwrap_or(0.0) }, probability.is_some()))
+}
+
+/// Finish a parsed system: fill in missing probabilities, or refuse a mix.
+fn complete(name: String, mut transforms: Vec<(AffineTransform, bool)>, line: usize) -> Result<IfsSystem> {
+    if transforms.is_empty() {
+        return Err(MathaturaError::Parse { line, message: "no transforms given".to_string() });
+    }
+    let given = transforms.iter().filter(|(_, p)| *p).count();
+    if given != 0 && given != transforms.len() {
+        return Err(MathaturaError::Parse { line, message: "give a probability for every transform or for none".to_string() });
+    }
+    let mut transforms: Vec<AffineTransform> = transforms.drain(..).map(|(t, _)| t).collect();
+    if given == 0 {
+        default_probabilities(&mut transforms);
+    }
+    Ok(IfsSystem { name, transforms })
+}
+
+/// Parse a system from its text definition.
+///
+/// One map per line as six coefficients `a b c d e f`, taking (x, y) to
+/// (ax + by + e, cx + dy + f), optionally followed by its probability;
+/// numbers may be separated by spaces or commas. Give probabilities for all
+/// maps or for none, in which case they follow the area each map keeps.
+/// `name: …` sets the name; blank lines and `#` comments are ignored.
+/// Errors name the offending line.
+pub fn parse(source: &str) -> Result<IfsSystem> {
+    let mut name = "Custom".to_string();
+    let mut transforms = Vec::new();
+    for (index, raw) in source.lines().enumerate() {
+        let line = index + 1;
+        let fail = |message: String| MathaturaError::Parse { line, message };
+        let text = raw.split('#').next().unwrap_or("").trim();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some((key, value)) = text.split_once([':', '=']) {
+            match key.trim() {
+                "name" => name = value.trim().to_string(),
+                key => return Err(fail(format!("unknown setting '{key}'; expected name"))),
+            }
+            continue;
+        }
+        let numbers = text
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(|| fail(format!("expected a number, found '{s}'"))))
+            .collect::<Result<Vec<f64>>>()?;
+        let map = transform(&numbers).ok_or_else(|| fail(format!("expected 6 coefficients and an optional probability, found {} numbers", numbers.len())))?;
+        transforms.push(map);
+    }
+    complete(name, transforms, source.lines().count().max(1))
+}
+
+/// Parse a system from JSON: an object with an optional `name` and a
+/// `transforms` array whose maps are `[a, b, c, d, e, f]` or
+/// `[a, b, c, d, e, f, p]` arrays, or objects with fields `a`–`f` and an
+/// optional `probability`.
+#[cfg(feature = "std")]
+pub fn from_json(source: &str) -> Result<IfsSystem> {
+    use serde_json::Value;
+    let fail = |message: String| MathaturaError::Parse { line: 0, message };
+    let root: Value = serde_json::from_str(source).map_err(|e| MathaturaError::Parse { line: e.line(), message: e.to_string() })?;
+    let name = root.get("name").and_then(Value::as_str).unwrap_or("Custom").to_string();
+    let Some(maps) = root.get("transforms").and_then(Value::as_array) else {
+        return Err(fail("expected an object with a \"transforms\" array".to_string()));
+    };
+    let mut transforms = Vec::new();
+    for (i, map) in maps.iter().enumerate() {
+        let number = |v: Option<&Value>| v.and_then(Value::as_f64);
+        let numbers: Option<Vec<f64>> = match map {
+            Value::Array(items) => items.iter().map(|v| number(Some(v))).collect(),
+            Value::Object(fields) => {
+                let mut numbers: Option<Vec<f64>> = ["a", "b", "c", "d", "e", "f"].iter().map(|k| number(fields.get(*k))).collect();
+                if let (Some(numbers), Some(p)) = (numbers.as_mut(), fields.get("probability")) {
+                    numbers.push(number(Some(p)).unwrap_or(f64::NAN));
+                }
+                numbers
+            }
+            _ => None,
+        };
+        let map = numbers
+            .as_deref()
+            .and_then(transform)
+            .ok_or_else(|| fail(format!("transform {}: expected [a, b, c, d, e, f] with an optional probability", i + 1)))?;
+        transforms.push(map);
+    }
+    complete(name, transforms, 0)
+}
+
+/// The chaos game: `iterations` points on the attractor of `ifs`.
+pub fn chaos_game(ifs: &IfsSystem, iterations: usize, seed: u64) -> Vec<Point> {
+    chaos_game_iter(ifs, seed).take(iterations).collect()
+}
+
+/// Endless chaos game over `ifs`, starting once the walk has settled onto
+/// the attractor. Yields nothing for a system without maps.
+pub fn chaos_game_iter(ifs: &IfsSystem, seed: u64) -> impl Iterator<Item = Point> {
+    let total: f64 = ifs.transforms.iter().map(|t| t.probability).sum();
+    let mut cumulative = 0.0;
+    let maps: Vec<(f64, AffineTransform)> = ifs
+        .transforms
+        .iter()
+        .map(|t| {
+            cumulative += t.probability / total;
+            (cumulative, *t)
+        })
+        .collect();
+    let mut rng = SimpleRng::new(seed);
+    let mut p = Point { x: 0.0, y: 0.0 };
+    core::iter::from_fn(move || {
+        let last = maps.last()?;
+        let r = rng.next_f64();
+        let (_, map) = maps.iter().find(|(c, _)| r < *c).unwrap_or(last);
+        p = map.apply(p);
+        Some(p)
+    })
+    .skip(TRANSIENT)
+}
+
+impl Generator for IfsSystem {
+    /// [`GENERATED_POINTS`] chaos-game points with seed 0, flipped y-down;
+    /// nothing for a system that fails [`IfsSystem::validate`], whose error
+    /// [`try_generate`](Generator::try_generate) reports instead.
+    fn generate(&self) -> Geometry {
+        self.try_generate().unwrap_or(Geometry::Points(Vec::new()))
+    }
+
+    fn try_generate(&self) -> Result<Geometry> {
+        self.validate()?;
+        let points = chaos_game(self, GENERATED_POINTS, 0);
+        Ok(Geometry::Points(points.into_iter().map(|p| Point { x: p.x, y: -p.y }).collect()))
+    }
+}
+
+/// Plot chaos-game points scaled to fit their bounding box, shaded from
+/// deep green at the bottom to pale green at the top.
+pub fn to_svg(points: &[Point]) -> String {
+    to_svg_with_hue(points, 120.0)
+}
+
+/// [`to_svg`] around another base hue: points run from dark at the bottom,
+/// 20° above `hue`, to light at the top, 20° below.
+pub fn to_svg_with_hue(points: &[Point], hue: f64) -> String {
+    to_svg_with_dots(points, hue, Dots::Circles)
+}
+
+/// [`to_svg_with_hue`], its dots written as `dots` says.
+pub fn to_svg_with_dots(points: &[Point], hue: f64, dots: Dots) -> String {
+    to_svg_with_theme(points, hue, dots, &Theme::dark())
+}
+
+/// [`to_svg_with_dots`] in `theme`.
+pub fn to_svg_with_theme(points: &[Point], hue: f64, dots: Dots, theme: &Theme) -> String {
+    let xy: Vec<(f64, f64)> = points.iter().map(|p| (p.x, p.y)).collect();
+    let color = |_, _, v: f64| crate::render::hsl(hue + 20.0 - 40.0 * v + 360.0, 60.0, 30.0 + 35.0 * v);
+    crate::render::point_cloud_svg_with_theme(&xy, SIZE, 0.5, dots, color, theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIERPINSKI: &str = "\
+# Sierpinski triangle
+name: Sierpinski
+0.5 0 0 0.5 0    0    0.33
+0.5 0 0 0.5 0.5  0    0.33
+0.5 0 0 0.5 0.25 0.5  0.34
+";
+
+    #[test]
+    fn test_parse_text() {
+        let ifs = parse(SIERPINSKI).unwrap();
+        assert_eq!(ifs.name, "Sierpinski");
+        assert_eq!(ifs.transforms.len(), 3);
+        assert_eq!(ifs.transforms[2].e, 0.25);
+        assert_eq!(ifs.transforms[2].probability, 0.34);
+        assert!(ifs.validate().is_ok());
+        // Commas work too, and missing probabilities follow the kept area
+        let ifs: IfsSystem = "0.5,0,0,0.5,0,0\n0.25,0,0,0.25,1,1".parse().unwrap();
+        let p: Vec<f64> = ifs.transforms.iter().map(|t| t.probability).collect();
+        assert!((p[0] - 0.8).abs() < 1e-12 && (p[1] - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let line = |source: &str| match parse(source) {
+            Err(MathaturaError::Parse { line, .. }) => line,
+            other => panic!("expected a parse error, got {other:?}"),
+        };
+        assert_eq!(line("0.5 0 0 0.5 0 0 1\n0.5 0 0 0.5 0 x 1"), 2);
+        assert_eq!(line("\n\n0.5 0 0 0.5 0"), 3);
+        assert_eq!(line("color: red"), 1);
+        assert_eq!(line("0.5 0 0 0.5 0 0 1\n0.5 0 0 0.5 1 0"), 2);
+        assert_eq!(line("# nothing\n"), 1);
+    }
+
+    #[test]
+    fn test_validate_rejects_expanding_maps() {
+        let mut ifs = IfsSystem::fern();
+        assert!(ifs.validate().is_ok());
+        ifs.transforms[1].a = 1.5;
+        assert!(matches!(ifs.validate(), Err(MathaturaError::InvalidParameter { name: "transform", .. })));
+        let mut ifs = IfsSystem::fern();
+        ifs.transforms[0].probability = -0.1;
+        assert!(ifs.validate().is_err());
+        assert!(IfsSystem { name: String::new(), transforms: vec![] }.validate().is_err());
+        assert!(ifs.try_generate().is_err());
+        assert!(matches!(ifs.generate(), Geometry::Points(ref points) if points.is_empty()));
+    }
+
+    #[test]
+    fn test_chaos_game_stays_on_attractor() {
+        // Every point of the Sierpinski triangle lies in the unit triangle
+        let ifs = parse(SIERPINSKI).unwrap();
+        let points = chaos_game(&ifs, 5000, 7);
+        assert_eq!(points.len(), 5000);
+        assert!(points.iter().all(|p| p.y >= -1e-9 && p.y <= 2.0 * p.x + 1e-9 && p.y <= 2.0 * (1.0 - p.x) + 1e-9));
+        assert_eq!(points, chaos_game(&ifs, 5000, 7));
+        assert!(chaos_game(&IfsSystem { name: String::new(), transforms: vec![] }, 10, 1).is_empty());
+    }
+
+    #[test]
+    fn test_presets_are_valid() {
+        for preset in Preset::ALL {
+            assert_eq!(Preset::from_name(preset.name()), Some(preset));
+            let ifs = preset.system();
+            assert!(ifs.validate().is_ok(), "{}", ifs.name);
+            let total: f64 = ifs.transforms.iter().map(|t| t.probability).sum();
+            assert!((total - 1.0).abs() < 1e-9, "{}", ifs.name);
+        }
+        assert_eq!(Preset::from_name("oak"), None);
+    }
+
+    #[test]
+    fn test_carpet_and_dust_leave_holes() {
+        let inside = |p: &Point, lo: f64, hi: f64| p.x > lo && p.x < hi && p.y > lo && p.y < hi;
+        let carpet = chaos_game(&Preset::Carpet.system(), 5000, 2);
+        assert!(carpet.iter().all(|p| !inside(p, 1.0 / 3.0 + 1e-9, 2.0 / 3.0 - 1e-9)));
+        // Cantor dust also misses the middle third of each axis
+        let dust = chaos_game(&Preset::CantorDust.system(), 5000, 2);
+        let middle = |v: f64| v > 1.0 / 3.0 + 1e-9 && v < 2.0 / 3.0 - 1e-9;
+        assert!(dust.iter().all(|p| !middle(p.x) && !middle(p.y)));
+    }
+
+    #[test]
+    fn test_fern_matches_fern_bounds() {
+        let points = chaos_game(&IfsSystem::fern(), 20_000, 3);
+        assert!(points.iter().all(|p| p.x.abs() < 2.8 && (0.0..10.1).contains(&p.y)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_from_json() {
+        let json = r#"{"name": "Sierpinski", "transforms": [
+            [0.5, 0, 0, 0.5, 0, 0],
+            {"a": 0.5, "b": 0, "c": 0, "d": 0.5, "e": 0.5, "f": 0},
+            [0.5, 0, 0, 0.5, 0.25, 0.5]
+        ]}"#;
+        let ifs = from_json(json).unwrap();
+        assert_eq!(ifs.name, "Sierpinski");
+        assert_eq!(ifs.transforms[1].e, 0.5);
+        assert!(ifs.transforms.iter().all(|t| (t.probability - 1.0 / 3.0).abs() < 1e-12));
+        assert!(from_json(r#"{"transforms": [[1, 2]]}"#).is_err());
+        assert!(matches!(from_json("{\n  \"transforms\": ["), Err(MathaturaError::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn test_svg_fits_bounding_box() {
+        let svg = to_svg(&chaos_game(&parse(SIERPINSKI).unwrap(), 2000, 1));
+        // The triangle is a touch taller than wide, so its height fills the frame
+        assert!(svg.contains("width=\"597\" height=\"600\""));
+        assert!(svg.matches("<circle").count() == 2000);
+        assert!(to_svg(&[]).contains("<svg"));
+    }
+}
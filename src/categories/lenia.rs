@@ -0,0 +1,504 @@
+//! Lenia — continuous cellular automata with lifelike "creatures".
+//!
+//! Bert Chan (2019) generalized the Game of Life to continuous states,
+//! space, and time. Each cell holds a value A ∈ [0, 1]; every step it sees a
+//! smooth ring-shaped weighted average of its neighborhood,
+//!   U = K ∗ A
+//! and grows or shrinks according to a bell-shaped growth function,
+//!   A ← clip(A + dt · (2 exp(−(U − μ)² / 2σ²) − 1), 0, 1)
+//! Narrow parameter windows host self-organizing gliders, rotators, and
+//! dividing blobs that look strikingly like microorganisms under a microscope.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::colormap::{Colormap, MAGMA};
+use crate::render::theme::Theme;
+use super::fractals::SimpleRng;
+
+/// Parameters for the kernel and growth function.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeniaParams {
+    /// Kernel radius R in cells
+    pub radius: f64,
+    /// Time step (1/T)
+    pub dt: f64,
+    /// Growth center μ
+    pub mu: f64,
+    /// Growth width σ
+    pub sigma: f64,
+    /// Peak heights of the kernel's concentric rings (unused entries are ignored)
+    pub rings: [f64; 3],
+    /// Number of kernel rings in use (1–3)
+    pub ring_count: usize,
+}
+
+impl Default for LeniaParams {
+    fn default() -> Self {
+        Preset::Orbium.params()
+    }
+}
+
+/// Parameter sets for known Lenia creatures.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// A smooth glider, the "amoeba" of Lenia
+    Orbium,
+    /// Three-ring kernel with Chan's Hydrogeminium parameters (at a finer time
+    /// step); a noise patch settles into a stationary, slowly breathing body
+    Hydrogeminium,
+}
+
+impl Preset {
+    pub fn params(self) -> LeniaParams {
+        match self {
+            Preset::Orbium => LeniaParams {
+                radius: 13.0,
+                dt: 0.1,
+                mu: 0.15,
+                sigma: 0.015,
+                rings: [1.0, 0.0, 0.0],
+                ring_count: 1,
+            },
+            Preset::Hydrogeminium => LeniaParams {
+                radius: 18.0,
+                dt: 0.1,
+                mu: 0.26,
+                sigma: 0.036,
+                rings: [0.5, 1.0, 2.0 / 3.0],
+                ring_count: 3,
+            },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Orbium => "Orbium",
+            Preset::Hydrogeminium => "Hydrogeminium",
+        }
+    }
+}
+
+/// Orbium cell pattern from Chan's creature catalogue (20×20, scale R = 13).
+#[rustfmt::skip]
+pub const ORBIUM: [[f64; 20]; 20] = [
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.1, 0.14, 0.1, 0.0, 0.0, 0.03, 0.03, 0.0, 0.0, 0.3, 0.0, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.08, 0.24, 0.3, 0.3, 0.18, 0.14, 0.15, 0.16, 0.15, 0.09, 0.2, 0.0, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.15, 0.34, 0.44, 0.46, 0.38, 0.18, 0.14, 0.11, 0.13, 0.19, 0.18, 0.45, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.06, 0.13, 0.39, 0.5, 0.5, 0.37, 0.06, 0.0, 0.0, 0.0, 0.02, 0.16, 0.68, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.11, 0.17, 0.17, 0.33, 0.4, 0.38, 0.28, 0.14, 0.0, 0.0, 0.0, 0.0, 0.0, 0.18, 0.42, 0.0, 0.0],
+    [0.0, 0.0, 0.09, 0.18, 0.13, 0.06, 0.08, 0.26, 0.32, 0.32, 0.27, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.82, 0.0, 0.0],
+    [0.27, 0.0, 0.16, 0.12, 0.0, 0.0, 0.0, 0.25, 0.38, 0.44, 0.45, 0.34, 0.0, 0.0, 0.0, 0.0, 0.0, 0.22, 0.17, 0.0],
+    [0.0, 0.07, 0.2, 0.02, 0.0, 0.0, 0.0, 0.31, 0.48, 0.57, 0.6, 0.57, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.49, 0.0],
+    [0.0, 0.59, 0.19, 0.0, 0.0, 0.0, 0.0, 0.2, 0.57, 0.69, 0.76, 0.76, 0.49, 0.0, 0.0, 0.0, 0.0, 0.0, 0.36, 0.0],
+    [0.0, 0.58, 0.19, 0.0, 0.0, 0.0, 0.0, 0.0, 0.67, 0.83, 0.9, 0.92, 0.87, 0.12, 0.0, 0.0, 0.0, 0.0, 0.22, 0.07],
+    [0.0, 0.0, 0.46, 0.0, 0.0, 0.0, 0.0, 0.0, 0.7, 0.93, 1.0, 1.0, 1.0, 0.61, 0.0, 0.0, 0.0, 0.0, 0.18, 0.11],
+    [0.0, 0.0, 0.82, 0.0, 0.0, 0.0, 0.0, 0.0, 0.47, 1.0, 1.0, 0.98, 1.0, 0.96, 0.27, 0.0, 0.0, 0.0, 0.19, 0.1],
+    [0.0, 0.0, 0.46, 0.0, 0.0, 0.0, 0.0, 0.0, 0.25, 1.0, 1.0, 0.84, 0.92, 0.97, 0.54, 0.14, 0.04, 0.1, 0.21, 0.05],
+    [0.0, 0.0, 0.0, 0.4, 0.0, 0.0, 0.0, 0.0, 0.09, 0.8, 1.0, 0.82, 0.8, 0.85, 0.63, 0.31, 0.18, 0.19, 0.2, 0.01],
+    [0.0, 0.0, 0.0, 0.36, 0.1, 0.0, 0.0, 0.0, 0.05, 0.54, 0.86, 0.79, 0.74, 0.72, 0.6, 0.39, 0.28, 0.24, 0.13, 0.0],
+    [0.0, 0.0, 0.0, 0.01, 0.3, 0.07, 0.0, 0.0, 0.08, 0.36, 0.64, 0.7, 0.64, 0.6, 0.51, 0.39, 0.29, 0.19, 0.04, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.1, 0.24, 0.14, 0.1, 0.15, 0.29, 0.45, 0.53, 0.52, 0.46, 0.4, 0.31, 0.21, 0.08, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.08, 0.21, 0.21, 0.22, 0.29, 0.36, 0.39, 0.37, 0.33, 0.26, 0.18, 0.09, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.03, 0.13, 0.19, 0.22, 0.24, 0.24, 0.23, 0.18, 0.13, 0.05, 0.0, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.02, 0.06, 0.08, 0.09, 0.07, 0.05, 0.01, 0.0, 0.0, 0.0, 0.0, 0.0],
+];
+
+impl LeniaParams {
+    /// Kernel weight at distance d (unnormalized): smooth bumps on concentric rings.
+    pub fn kernel(&self, d: f64) -> f64 {
+        let r = d / self.radius;
+        if r <= 0.0 || r >= 1.0 {
+            return 0.0;
+        }
+        let count = self.ring_count.clamp(1, 3);
+        let br = count as f64 * r;
+        let ring = (br as usize).min(count - 1);
+        let x = br - ring as f64;
+        if x <= 0.0 || x >= 1.0 {
+            return 0.0;
+        }
+        self.rings[ring] * (4.0 - 1.0 / (x * (1.0 - x))).exp()
+    }
+
+    /// Growth function mapping neighborhood potential to a rate in [−1, 1].
+    pub fn growth(&self, u: f64) -> f64 {
+        2.0 * (-(u - self.mu).powi(2) / (2.0 * self.sigma * self.sigma)).exp() - 1.0
+    }
+
+    /// Require a kernel radius of 1–64 cells, 1–3 rings, a positive growth
+    /// width, and a time step in (0, 1].
+    pub fn validate(&self) -> Result<()> {
+        check_range("radius", self.radius, 1.0..=64.0, "1 to 64 cells")?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=1.0, "0 < dt ≤ 1")?;
+        check_positive("sigma", self.sigma)?;
+        check_count("ring_count", self.ring_count, 3)
+    }
+}
+
+/// In-place radix-2 FFT over `re`/`im` (length must be a power of two).
+fn fft(re: &mut [f64], im: &mut [f64], inverse: bool) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let (ws, wc) = (sign * 2.0 * PI / len as f64).sin_cos();
+        for start in (0..n).step_by(len) {
+            let (mut cr, mut ci) = (1.0, 0.0);
+            for k in 0..len / 2 {
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * cr - im[b] * ci;
+                let ti = re[b] * ci + im[b] * cr;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+                (cr, ci) = (cr * wc - ci * ws, cr * ws + ci * wc);
+            }
+        }
+        len <<= 1;
+    }
+    if inverse {
+        for v in re.iter_mut().chain(im.iter_mut()) {
+            *v /= n as f64;
+        }
+    }
+}
+
+/// 2D FFT: rows, then columns.
+fn fft2(re: &mut [f64], im: &mut [f64], width: usize, height: usize, inverse: bool) {
+    for y in 0..height {
+        let row = y * width..(y + 1) * width;
+        fft(&mut re[row.clone()], &mut im[row], inverse);
+    }
+    let (mut cr, mut ci) = (vec![0.0; height], vec![0.0; height]);
+    for x in 0..width {
+        for y in 0..height {
+            cr[y] = re[y * width + x];
+            ci[y] = im[y * width + x];
+        }
+        fft(&mut cr, &mut ci, inverse);
+        for y in 0..height {
+            re[y * width + x] = cr[y];
+            im[y * width + x] = ci[y];
+        }
+    }
+}
+
+/// A Lenia world on a torus.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lenia {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<f64>,
+    pub params: LeniaParams,
+    kernel_re: Vec<f64>,
+    kernel_im: Vec<f64>,
+}
+
+impl Lenia {
+    /// Empty world; sizes are rounded up to powers of two for the FFT.
+    pub fn new(width: usize, height: usize, params: LeniaParams) -> Self {
+        let (width, height) = (width.max(2).next_power_of_two(), height.max(2).next_power_of_two());
+        let mut kernel_re = vec![0.0; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                // Wrapped offsets so the kernel is centered on cell (0, 0)
+                let dx = if x > width / 2 { x as f64 - width as f64 } else { x as f64 };
+                let dy = if y > height / 2 { y as f64 - height as f64 } else { y as f64 };
+                kernel_re[y * width + x] = params.kernel(dx.hypot(dy));
+            }
+        }
+        let total: f64 = kernel_re.iter().sum();
+        if total > 0.0 {
+            kernel_re.iter_mut().for_each(|k| *k /= total);
+        }
+        let mut kernel_im = vec![0.0; width * height];
+        fft2(&mut kernel_re, &mut kernel_im, width, height, false);
+        Lenia { width, height, cells: vec![0.0; width * height], params, kernel_re, kernel_im }
+    }
+
+    /// World seeded with the preset's creature at the center.
+    pub fn from_preset(preset: Preset, width: usize, height: usize, seed: u64) -> Self {
+        let mut world = Lenia::new(width, height, preset.params());
+        match preset {
+            Preset::Orbium => {
+                // Centered, the offset wrapping on worlds narrower than the creature
+                let half = ORBIUM.len() / 2;
+                let corner = |side: usize| (side / 2 + side - half % side) % side;
+                world.place(&ORBIUM, corner(world.width), corner(world.height));
+            }
+            Preset::Hydrogeminium => world.seed_patch(28, seed),
+        }
+        world
+    }
+
+    /// Copy a pattern into the world with its top-left corner at (x, y), wrapping at the edges.
+    pub fn place<const N: usize>(&mut self, pattern: &[[f64; N]], x: usize, y: usize) {
+        for (dy, row) in pattern.iter().enumerate() {
+            for (dx, &v) in row.iter().enumerate() {
+                let (px, py) = ((x + dx) % self.width, (y + dy) % self.height);
+                self.cells[py * self.width + px] = v;
+            }
+        }
+    }
+
+    /// Fill a centered square patch of side `size` with random values — the
+    /// usual way to discover creatures, which then self-organize from the noise.
+    pub fn seed_patch(&mut self, size: usize, seed: u64) {
+        let mut rng = SimpleRng::new(seed);
+        let size = size.min(self.width).min(self.height);
+        let (x0, y0) = ((self.width - size) / 2, (self.height - size) / 2);
+        for y in y0..y0 + size {
+            for x in x0..x0 + size {
+                self.cells[y * self.width + x] = rng.next_f64();
+            }
+        }
+    }
+
+    /// Neighborhood potential U = K ∗ A, computed by FFT.
+    pub fn potential(&self) -> Vec<f64> {
+        let mut re = self.cells.clone();
+        let mut im = vec![0.0; re.len()];
+        fft2(&mut re, &mut im, self.width, self.height, false);
+        for i in 0..re.len() {
+            let (a, b) = (re[i], im[i]);
+            re[i] = a * self.kernel_re[i] - b * self.kernel_im[i];
+            im[i] = a * self.kernel_im[i] + b * self.kernel_re[i];
+        }
+        fft2(&mut re, &mut im, self.width, self.height, true);
+        re
+    }
+
+    /// Advance one time step.
+    pub fn step(&mut self) {
+        let u = self.potential();
+        let p = self.params;
+        for (a, u) in self.cells.iter_mut().zip(u) {
+            *a = (*a + p.dt * p.growth(u)).clamp(0.0, 1.0);
+        }
+    }
+
+    /// Run for n steps.
+    pub fn simulate(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Total mass Σ A.
+    pub fn mass(&self) -> f64 {
+        self.cells.iter().sum()
+    }
+
+    /// Mass-weighted center on the torus (circular mean), or `None` if empty.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        let mass = self.mass();
+        if mass <= 0.0 {
+            return None;
+        }
+        let circular = |coord: &dyn Fn(usize) -> usize, n: usize| {
+            let (mut s, mut c) = (0.0, 0.0);
+            for (i, &a) in self.cells.iter().enumerate() {
+                let angle = 2.0 * PI * coord(i) as f64 / n as f64;
+                s += a * angle.sin();
+                c += a * angle.cos();
+            }
+            (s.atan2(c).rem_euclid(2.0 * PI)) * n as f64 / (2.0 * PI)
+        };
+        let w = self.width;
+        Some((circular(&|i| i % w, self.width), circular(&|i| i / w, self.height)))
+    }
+}
+
+impl Generator for Lenia {
+    fn generate(&self) -> Geometry {
+        Geometry::Grid { cols: self.width, rows: self.height, values: self.cells.clone() }
+    }
+}
+
+/// Render the world with a magma color map.
+pub fn to_svg(world: &Lenia, cell: f64) -> String {
+    to_svg_with_colormap(world, cell, &MAGMA)
+}
+
+/// Render the world along `colormap`, empty cells left as background.
+pub fn to_svg_with_colormap(world: &Lenia, cell: f64, colormap: &dyn Colormap) -> String {
+    render(world, cell, colormap, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`, along its palette if it has one.
+pub fn to_svg_with_theme(world: &Lenia, cell: f64, theme: &Theme) -> String {
+    match &theme.palette {
+        Some(palette) => render(world, cell, palette, theme),
+        None => render(world, cell, &MAGMA, theme),
+    }
+}
+
+fn render(world: &Lenia, cell: f64, colormap: &dyn Colormap, theme: &Theme) -> String {
+    let background = theme.background_css();
+    let color = |a: f64| {
+        if a < 0.02 {
+            background.clone()
+        } else {
+            // Quantize so runs of similar values merge into one rect
+            colormap.css((a * 24.0).round() / 24.0)
+        }
+    };
+    crate::render::raster_svg_with_theme(world.width, world.height, cell, &world.cells, color, theme)
+}
+
+/// Render `frames` frames, advancing `steps_per_frame` between each.
+pub fn animation_frames(world: &mut Lenia, frames: usize, steps_per_frame: usize) -> Vec<String> {
+    animation_frames_with_theme(world, frames, steps_per_frame, &Theme::dark())
+}
+
+/// [`animation_frames`] in `theme`.
+pub fn animation_frames_with_theme(world: &mut Lenia, frames: usize, steps_per_frame: usize, theme: &Theme) -> Vec<String> {
+    (0..frames)
+        .map(|_| {
+            world.simulate(steps_per_frame);
+            to_svg_with_theme(world, 4.0, theme)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fft_roundtrip() {
+        let original: Vec<f64> = (0..16).map(|i| (i as f64 * 0.7).sin()).collect();
+        let mut re = original.clone();
+        let mut im = vec![0.0; 16];
+        fft2(&mut re, &mut im, 4, 4, false);
+        fft2(&mut re, &mut im, 4, 4, true);
+        for (a, b) in re.iter().zip(original.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_kernel_shape() {
+        let p = Preset::Orbium.params();
+        assert_eq!(p.kernel(0.0), 0.0);
+        assert_eq!(p.kernel(13.0), 0.0);
+        // Single ring peaks at half the radius with height 1
+        assert!((p.kernel(6.5) - 1.0).abs() < 1e-12);
+        assert!(p.kernel(3.0) < p.kernel(6.0));
+    }
+
+    #[test]
+    fn test_growth_function() {
+        let p = Preset::Orbium.params();
+        assert!((p.growth(p.mu) - 1.0).abs() < 1e-12);
+        assert!(p.growth(0.0) < -0.99);
+        assert!(p.growth(1.0) < -0.99);
+    }
+
+    #[test]
+    fn test_potential_matches_direct_convolution() {
+        let params = LeniaParams { radius: 3.0, ..Default::default() };
+        let mut world = Lenia::new(16, 16, params);
+        world.seed_patch(6, 1);
+        let u = world.potential();
+        let total: f64 = (-3..=3)
+            .flat_map(|dy: i32| (-3..=3).map(move |dx: i32| params.kernel((dx as f64).hypot(dy as f64))))
+            .sum();
+        let (x, y) = (7usize, 9usize);
+        let mut direct = 0.0;
+        for dy in -3i32..=3 {
+            for dx in -3i32..=3 {
+                let nx = (x as i32 + dx).rem_euclid(16) as usize;
+                let ny = (y as i32 + dy).rem_euclid(16) as usize;
+                direct += params.kernel((dx as f64).hypot(dy as f64)) * world.cells[ny * 16 + nx];
+            }
+        }
+        assert!((u[y * 16 + x] - direct / total).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty_world_stays_empty() {
+        let mut world = Lenia::new(32, 32, Preset::Orbium.params());
+        world.simulate(5);
+        assert_eq!(world.mass(), 0.0);
+        assert_eq!(world.centroid(), None);
+    }
+
+    #[test]
+    fn test_sizes_rounded_to_power_of_two() {
+        let world = Lenia::new(100, 60, Preset::Orbium.params());
+        assert_eq!((world.width, world.height), (128, 64));
+    }
+
+    #[test]
+    fn test_preset_on_small_world() {
+        // Smaller than Orbium itself: it wraps instead of underflowing
+        let world = Lenia::from_preset(Preset::Orbium, 8, 8, 0);
+        assert_eq!(world.cells.len(), 64);
+        assert!(world.mass() > 0.0);
+        let world = Lenia::from_preset(Preset::Orbium, 64, 64, 0);
+        assert_eq!(world.cells[22 * 64 + 22..22 * 64 + 42], ORBIUM[0]);
+    }
+
+    #[test]
+    fn test_orbium_glides() {
+        let mut world = Lenia::from_preset(Preset::Orbium, 64, 64, 0);
+        world.simulate(50);
+        let (mass, start) = (world.mass(), world.centroid().unwrap());
+        world.simulate(100);
+        let end = world.centroid().unwrap();
+        assert!((world.mass() - mass).abs() < 0.05 * mass, "Orbium should keep its mass");
+        let dx = (end.0 - start.0).abs().min(64.0 - (end.0 - start.0).abs());
+        let dy = (end.1 - start.1).abs().min(64.0 - (end.1 - start.1).abs());
+        assert!(dx.hypot(dy) > 5.0, "Orbium should glide");
+    }
+
+    #[test]
+    fn test_hydrogeminium_settles() {
+        let mut world = Lenia::from_preset(Preset::Hydrogeminium, 128, 128, 1);
+        world.simulate(200);
+        let mass = world.mass();
+        world.simulate(100);
+        assert!(mass > 100.0, "Creature died out");
+        assert!((world.mass() - mass).abs() < 0.1 * mass);
+        let (cx, cy) = world.centroid().unwrap();
+        assert!((cx - 64.0).hypot(cy - 64.0) < 8.0);
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let mut world = Lenia::new(32, 32, Preset::Orbium.params());
+        world.seed_patch(10, 3);
+        let frames = animation_frames(&mut world, 2, 1);
+        assert_eq!(frames.len(), 2);
+        assert!(frames[1].contains("<rect"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Preset::Orbium.params().validate().is_ok());
+        assert!(Preset::Hydrogeminium.params().validate().is_ok());
+        assert!(LeniaParams { ring_count: 4, ..Default::default() }.validate().is_err());
+        assert!(LeniaParams { sigma: 0.0, ..Default::default() }.validate().is_err());
+    }
+}
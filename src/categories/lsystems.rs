@@ -2,11 +2,26 @@
 //!
 //! Trees, ferns, rivers, lungs, blood vessels, and lightning all share
 //! fractal branching patterns that can be described by simple rewriting rules.
+//!
+//! Strings are drawn by a turtle. In the plane, `+` and `-` turn it; the 3D
+//! turtle of [`interpret_3d`] also pitches (`&` down, `^` up), rolls (`\`
+//! left, `/` right) and turns around (`|`), following *The Algorithmic
+//! Beauty of Plants*, and [`to_svg_3d`] views the result through a
+//! [`Projection`].
+
+use core::f64::consts::PI;
 
-use std::f64::consts::PI;
+use crate::prelude::*;
+use crate::error::{MathaturaError, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::Projection;
+use crate::render::theme::Theme;
+use super::chaos::Point3D;
+use super::fractals::Point;
 
 /// A turtle graphics command produced by interpreting an L-system string.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TurtleCommand {
     Forward(f64),
     TurnLeft(f64),
@@ -17,6 +32,7 @@ pub enum TurtleCommand {
 
 /// An L-system rule: character → replacement string.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rule {
     pub from: char,
     pub to: String,
@@ -24,6 +40,7 @@ pub struct Rule {
 
 /// An L-system definition.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LSystem {
     pub name: String,
     pub axiom: String,
@@ -35,6 +52,7 @@ pub struct LSystem {
 
 /// A line segment produced by turtle interpretation.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Segment {
     pub x1: f64,
     pub y1: f64,
@@ -43,6 +61,15 @@ pub struct Segment {
     pub depth: usize,
 }
 
+/// A line segment drawn by the 3D turtle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment3D {
+    pub start: Point3D,
+    pub end: Point3D,
+    pub depth: usize,
+}
+
 /// Predefined L-systems.
 pub fn tree() -> LSystem {
     LSystem {
@@ -113,29 +140,203 @@ pub fn plant() -> LSystem {
     }
 }
 
+/// A bush whose branches fan out in three directions at every node, rolled
+/// by a different amount each time (ABOP figure 1.25, without leaves).
+pub fn tree_3d() -> LSystem {
+    LSystem {
+        name: "3D Tree".to_string(),
+        axiom: "A".to_string(),
+        rules: vec![
+            Rule { from: 'A', to: "[&FA]/////[&FA]///////[&FA]".to_string() },
+            Rule { from: 'F', to: "S/////F".to_string() },
+            Rule { from: 'S', to: "F".to_string() },
+        ],
+        angle: 22.5,
+        step_length: 4.0,
+        length_factor: 1.0,
+    }
+}
+
+/// Branching coral: three branches at every node, pitched out by different
+/// amounts, bent back toward the vertical and rolled so that no two levels
+/// line up.
+pub fn coral() -> LSystem {
+    LSystem {
+        name: "Coral".to_string(),
+        axiom: "X".to_string(),
+        rules: vec![
+            Rule { from: 'X', to: "FF[&&F^//X]/////[&F^^/X]////[&&&F^^X]".to_string() },
+            Rule { from: 'F', to: "F".to_string() },
+        ],
+        angle: 24.0,
+        step_length: 3.0,
+        length_factor: 1.0,
+    }
+}
+
+/// Whether the system's symbols include 3D turtle commands.
+pub fn is_3d(system: &LSystem) -> bool {
+    let symbols = |s: &str| s.contains(['&', '^', '\\', '/', '|']);
+    symbols(&system.axiom) || system.rules.iter().any(|rule| symbols(&rule.to))
+}
+
+/// Turtle commands allowed in axioms and rules besides letters and digits.
+const COMMANDS: &str = "+-[]&^\\/|";
+
+/// Parse an L-system from its text definition.
+///
+/// One setting or rule per line; blank lines and `#` comments are ignored.
+///
+/// ```text
+/// # Bushy weed
+/// name: Weed
+/// axiom: X
+/// angle: 25       # degrees
+/// step: 4
+/// X -> F+[[X]-X]-F[-FX]+X
+/// F -> FF
+/// ```
+///
+/// Settings are `name`, `axiom` (required), `angle` (default 90), `step`
+/// (default 5) and `length_factor` (default 1), as `key: value` or
+/// `key = value`. Rules map one letter or digit to its replacement.
+/// Symbols are letters, digits and the turtle commands `+ - [ ] & ^ \ / |`;
+/// spaces inside strings are ignored. Errors name the offending line.
+pub fn parse(source: &str) -> Result<LSystem> {
+    let mut system = LSystem {
+        name: "Custom".to_string(),
+        axiom: String::new(),
+        rules: Vec::new(),
+        angle: 90.0,
+        step_length: 5.0,
+        length_factor: 1.0,
+    };
+    let mut axiom_line = None;
+    for (index, raw) in source.lines().enumerate() {
+        let line = index + 1;
+        let fail = |message: String| MathaturaError::Parse { line, message };
+        let text = raw.split('#').next().unwrap_or("").trim();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some((from, to)) = text.split_once("->") {
+            let mut from_chars = from.trim().chars();
+            let from = match (from_chars.next(), from_chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() => c,
+                (None, _) => return Err(fail("rule has no symbol before '->'".to_string())),
+                _ => return Err(fail(format!("rule must rewrite a single letter or digit, found '{}'", from.trim()))),
+            };
+            if system.rules.iter().any(|rule| rule.from == from) {
+                return Err(fail(format!("second rule for '{from}'")));
+            }
+            let to = symbols(to).map_err(|m| fail(format!("{m} in rule for '{from}'")))?;
+            system.rules.push(Rule { from, to });
+            continue;
+        }
+        let Some((key, value)) = text.split_once([':', '=']) else {
+            return Err(fail(format!("expected 'key: value' or 'X -> replacement', found '{text}'")));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let number = |name: &str| {
+            value.parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(|| fail(format!("{name} must be a number, found '{value}'")))
+        };
+        match key {
+            "name" => system.name = value.to_string(),
+            "axiom" => {
+                system.axiom = symbols(value).map_err(|m| fail(format!("{m} in axiom")))?;
+                axiom_line = Some(line);
+            }
+            "angle" => system.angle = number(key)?,
+            "step" | "step_length" => {
+                system.step_length = number(key)?;
+                if system.step_length <= 0.0 {
+                    return Err(fail(format!("step must be positive, found {value}")));
+                }
+            }
+            "length_factor" => system.length_factor = number(key)?,
+            _ => return Err(fail(format!("unknown setting '{key}'; expected name, axiom, angle, step or length_factor"))),
+        }
+    }
+    match axiom_line {
+        Some(_) if !system.axiom.is_empty() => Ok(system),
+        Some(line) => Err(MathaturaError::Parse { line, message: "axiom is empty".to_string() }),
+        None => Err(MathaturaError::Parse { line: source.lines().count().max(1), message: "no axiom given".to_string() }),
+    }
+}
+
+/// A symbol string with spaces removed, checked for unknown symbols and
+/// unbalanced brackets.
+fn symbols(text: &str) -> core::result::Result<String, String> {
+    let mut open = 0usize;
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars().filter(|c| !c.is_whitespace()) {
+        if !c.is_ascii_alphanumeric() && !COMMANDS.contains(c) {
+            return Err(format!("unknown symbol '{c}' (expected letters, digits or + - [ ] & ^ \\ / |)"));
+        }
+        match c {
+            '[' => open += 1,
+            ']' if open == 0 => return Err("']' without a matching '['".to_string()),
+            ']' => open -= 1,
+            _ => {}
+        }
+        out.push(c);
+    }
+    if open > 0 {
+        return Err(format!("{open} unclosed '['"));
+    }
+    Ok(out)
+}
+
+impl core::str::FromStr for LSystem {
+    type Err = MathaturaError;
+
+    fn from_str(source: &str) -> Result<LSystem> {
+        parse(source)
+    }
+}
+
 /// Apply L-system rules for n iterations.
+#[deprecated(note = "the string grows without bound; use `checked_generate`")]
 pub fn generate(system: &LSystem, iterations: usize) -> String {
+    derive(system, iterations)
+}
+
+fn derive(system: &LSystem, iterations: usize) -> String {
     let mut current = system.axiom.clone();
     for _ in 0..iterations {
-        let mut next = String::with_capacity(current.len() * 2);
-        for ch in current.chars() {
-            let mut matched = false;
-            for rule in &system.rules {
-                if ch == rule.from {
-                    next.push_str(&rule.to);
-                    matched = true;
-                    break;
-                }
-            }
-            if !matched {
-                next.push(ch);
-            }
-        }
-        current = next;
+        current = rewrite(system, &current);
     }
     current
 }
 
+/// Longest string [`checked_generate`] will build.
+pub const MAX_SYMBOLS: usize = 10_000_000;
+
+/// Apply L-system rules for n iterations, failing once the string outgrows
+/// [`MAX_SYMBOLS`] instead of exhausting memory on deep iterations.
+pub fn checked_generate(system: &LSystem, iterations: usize) -> Result<String> {
+    let mut current = system.axiom.clone();
+    for _ in 0..iterations {
+        current = rewrite(system, &current);
+        if current.len() > MAX_SYMBOLS {
+            return Err(MathaturaError::TooLarge { name: "L-system length", requested: current.len(), limit: MAX_SYMBOLS });
+        }
+    }
+    Ok(current)
+}
+
+/// One parallel rewriting pass over every symbol — a single derivation step.
+pub fn rewrite(system: &LSystem, current: &str) -> String {
+    let mut next = String::with_capacity(current.len() * 2);
+    for ch in current.chars() {
+        match system.rules.iter().find(|rule| rule.from == ch) {
+            Some(rule) => next.push_str(&rule.to),
+            None => next.push(ch),
+        }
+    }
+    next
+}
+
 /// Interpret an L-system string using turtle graphics.
 pub fn interpret(system: &LSystem, lstring: &str) -> Vec<Segment> {
     let mut segments = Vec::new();
@@ -176,6 +377,96 @@ pub fn interpret(system: &LSystem, lstring: &str) -> Vec<Segment> {
     segments
 }
 
+/// `v` rotated by `angle` radians about the unit `axis` (Rodrigues).
+fn rotate(v: [f64; 3], axis: [f64; 3], angle: f64) -> [f64; 3] {
+    let (sin, cos) = angle.sin_cos();
+    let dot = axis[0] * v[0] + axis[1] * v[1] + axis[2] * v[2];
+    let cross = [axis[1] * v[2] - axis[2] * v[1], axis[2] * v[0] - axis[0] * v[2], axis[0] * v[1] - axis[1] * v[0]];
+    core::array::from_fn(|i| v[i] * cos + cross[i] * sin + axis[i] * dot * (1.0 - cos))
+}
+
+/// Interpret an L-system string with a 3D turtle, z up.
+///
+/// The turtle starts at the origin heading up. Besides the drawing symbols
+/// and brackets of [`interpret`]: `+`/`-` turn left and right about the
+/// turtle's up vector, `&`/`^` pitch down and up about its left vector,
+/// `\`/`/` roll left and right about its heading, and `|` turns around.
+pub fn interpret_3d(system: &LSystem, lstring: &str) -> Vec<Segment3D> {
+    let mut segments = Vec::new();
+    let turn = system.angle.to_radians();
+    // Heading, left and up: an orthonormal, right-handed frame (H × L = U)
+    let (mut position, mut heading, mut left, mut up) = ([0.0; 3], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0], [-1.0, 0.0, 0.0]);
+    let mut stack = Vec::new();
+    let mut depth = 0;
+    let point = |p: [f64; 3]| Point3D { x: p[0], y: p[1], z: p[2] };
+    for ch in lstring.chars() {
+        match ch {
+            'F' | '0' | '1' | 'A' | 'B' => {
+                let next = core::array::from_fn(|i| position[i] + system.step_length * heading[i]);
+                segments.push(Segment3D { start: point(position), end: point(next), depth });
+                position = next;
+            }
+            '+' | '-' | '|' => {
+                let angle = match ch {
+                    '+' => turn,
+                    '-' => -turn,
+                    _ => PI,
+                };
+                (heading, left) = (rotate(heading, up, angle), rotate(left, up, angle));
+            }
+            '&' | '^' => {
+                let angle = if ch == '&' { turn } else { -turn };
+                (heading, up) = (rotate(heading, left, angle), rotate(up, left, angle));
+            }
+            '\\' | '/' => {
+                let angle = if ch == '\\' { -turn } else { turn };
+                (left, up) = (rotate(left, heading, angle), rotate(up, heading, angle));
+            }
+            '[' => {
+                stack.push((position, heading, left, up, depth));
+                depth += 1;
+            }
+            ']' => {
+                if let Some(state) = stack.pop() {
+                    (position, heading, left, up, depth) = state;
+                }
+            }
+            _ => {}
+        }
+    }
+    segments
+}
+
+/// 3D segments flattened by `projection`, in turtle order, in scene units
+/// with y down.
+pub fn project_segments(segments: &[Segment3D], projection: &Projection) -> Vec<Segment> {
+    projected(segments, projection).into_iter().map(|(s, _)| s).collect()
+}
+
+/// Projected segments with the depth of their midpoints.
+fn projected(segments: &[Segment3D], projection: &Projection) -> Vec<(Segment, f64)> {
+    let ends: Vec<Point3D> = segments.iter().flat_map(|s| [s.start, s.end]).collect();
+    projection
+        .project(&ends)
+        .chunks(2)
+        .zip(segments)
+        .map(|(p, s)| (Segment { x1: p[0][0], y1: p[0][1], x2: p[1][0], y2: p[1][1], depth: s.depth }, (p[0][2] + p[1][2]) / 2.0))
+        .collect()
+}
+
+/// Render 3D segments through `projection`, far segments first so nearer
+/// branches are drawn over them.
+pub fn to_svg_3d(segments: &[Segment3D], max_depth_val: usize, projection: &Projection) -> String {
+    to_svg_3d_with_theme(segments, max_depth_val, projection, &Theme::dark())
+}
+
+/// [`to_svg_3d`] in `theme`, colored as [`to_svg_with_theme`].
+pub fn to_svg_3d_with_theme(segments: &[Segment3D], max_depth_val: usize, projection: &Projection, theme: &Theme) -> String {
+    let mut flat = projected(segments, projection);
+    flat.sort_by(|a, b| b.1.total_cmp(&a.1));
+    to_svg_with_theme(&flat.into_iter().map(|(s, _)| s).collect::<Vec<_>>(), max_depth_val, theme)
+}
+
 /// Calculate total length of all segments.
 pub fn total_length(segments: &[Segment]) -> f64 {
     segments.iter().map(|s| {
@@ -194,8 +485,72 @@ pub fn max_depth(segments: &[Segment]) -> usize {
     segments.iter().map(|s| s.depth).max().unwrap_or(0)
 }
 
+/// An L-system expanded to a fixed depth, ready to generate.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Derivation {
+    pub system: LSystem,
+    pub iterations: usize,
+}
+
+impl Generator for Derivation {
+    /// One two-point polyline per drawn segment.
+    fn generate(&self) -> Geometry {
+        let segments = interpret(&self.system, &derive(&self.system, self.iterations));
+        Geometry::Polylines(
+            segments
+                .iter()
+                .map(|s| vec![Point { x: s.x1, y: s.y1 }, Point { x: s.x2, y: s.y2 }])
+                .collect(),
+        )
+    }
+}
+
+/// How far along the plant each segment starts: the turtle's path length
+/// from the root, following branches rather than drawing order.
+///
+/// A segment picks up where the segment ending at its start point left off;
+/// after a `]` that is the point the branch was pushed from.
+pub fn growth_distances(segments: &[Segment]) -> Vec<f64> {
+    // Keyed by exact coordinates: popped turtle positions are bit-identical to the segment ends they saved
+    let mut reached: Map<(u64, u64), f64> = Map::new();
+    segments
+        .iter()
+        .map(|s| {
+            let start = reached.get(&(s.x1.to_bits(), s.y1.to_bits())).copied().unwrap_or(0.0);
+            reached.insert((s.x2.to_bits(), s.y2.to_bits()), start + (s.x2 - s.x1).hypot(s.y2 - s.y1));
+            start
+        })
+        .collect()
+}
+
 /// Generate SVG of L-system segments.
 pub fn to_svg(segments: &[Segment], max_depth_val: usize) -> String {
+    to_svg_with_theme(segments, max_depth_val, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, branches take it by depth, the
+/// trunk at 0 and the deepest twigs at 1.
+pub fn to_svg_with_theme(segments: &[Segment], max_depth_val: usize, theme: &Theme) -> String {
+    render_svg(segments, max_depth_val, None, theme)
+}
+
+/// SVG in which the plant grows from the root over `seconds`.
+///
+/// Every segment is drawn in by a SMIL `stroke-dashoffset` animation that
+/// starts when growth reaches its base, so all branches extend at the same
+/// speed and side shoots sprout as the main stem passes them. Curves without
+/// branches (Koch, dragon) are traced from end to end.
+pub fn to_animated_svg(segments: &[Segment], max_depth_val: usize, seconds: f64) -> String {
+    to_animated_svg_with_theme(segments, max_depth_val, seconds, &Theme::dark())
+}
+
+/// [`to_animated_svg`] in `theme`, colored as [`to_svg_with_theme`].
+pub fn to_animated_svg_with_theme(segments: &[Segment], max_depth_val: usize, seconds: f64, theme: &Theme) -> String {
+    render_svg(segments, max_depth_val, Some(seconds.max(0.1)), theme)
+}
+
+fn render_svg(segments: &[Segment], max_depth_val: usize, seconds: Option<f64>, theme: &Theme) -> String {
     if segments.is_empty() {
         return String::from(r##"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="800"></svg>"##);
     }
@@ -213,24 +568,47 @@ pub fn to_svg(segments: &[Segment], max_depth_val: usize) -> String {
 
     let mut svg = format!(
         r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}">
-<rect width="{w}" height="{h}" fill="#0a0a1a"/>
-"##
+<rect width="{w}" height="{h}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
     );
 
     let md = max_depth_val.max(1) as f64;
-    for s in segments {
+    // One hue per branching depth
+    let palette = crate::render::golden_hues_cycling(max_depth_val + 1, 90.0, &[60.0], &[45.0]);
+    let starts = if seconds.is_some() { growth_distances(segments) } else { Vec::new() };
+    let tallest = segments
+        .iter()
+        .zip(&starts)
+        .map(|(s, d)| d + (s.x2 - s.x1).hypot(s.y2 - s.y1))
+        .fold(1e-12, f64::max);
+    for (i, s) in segments.iter().enumerate() {
         let x1 = margin + (s.x1 - min_x) * scale;
         let y1 = margin + (s.y1 - min_y) * scale;
         let x2 = margin + (s.x2 - min_x) * scale;
         let y2 = margin + (s.y2 - min_y) * scale;
         let t = s.depth as f64 / md;
-        let hue = 90.0 + t * 40.0;
-        let width = 3.0 - t * 2.5;
-        svg.push_str(&format!(
-            r##"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}" stroke="hsl({hue:.0},60%,40%)" stroke-width="{width:.1}" stroke-linecap="round"/>
+        let width = theme.stroke_width(3.0 - t * 2.5);
+        let color = theme.color(t.min(1.0), palette[s.depth.min(max_depth_val)].as_str());
+        let line = format!(
+            r##"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}" stroke="{color}" stroke-width="{width:.1}" stroke-linecap="round""##
+        );
+        match seconds {
+            Some(seconds) => {
+                // Hidden behind its own dash gap until growth reaches it
+                let len = (x2 - x1).hypot(y2 - y1);
+                let begin = starts[i] / tallest * seconds;
+                let dur = ((s.x2 - s.x1).hypot(s.y2 - s.y1) / tallest * seconds).max(0.001);
+                svg.push_str(&format!(
+                    r##"{line} stroke-dasharray="{len:.2}" stroke-dashoffset="{len:.2}"><animate attributeName="stroke-dashoffset" from="{len:.2}" to="0" begin="{begin:.3}s" dur="{dur:.3}s" fill="freeze"/></line>
 "##
-        ));
+                ));
+            }
+            None => svg.push_str(&format!("{line}/>\n")),
+        }
     }
+    svg.push_str(theme.foreground_end());
     svg.push_str("</svg>");
     svg
 }
@@ -242,25 +620,25 @@ mod tests {
     #[test]
     fn test_generate_tree() {
         let sys = tree();
-        let s0 = generate(&sys, 0);
+        let s0 = checked_generate(&sys, 0).unwrap();
         assert_eq!(s0, "0");
-        let s1 = generate(&sys, 1);
+        let s1 = checked_generate(&sys, 1).unwrap();
         assert_eq!(s1, "1[0]0");
-        let s2 = generate(&sys, 2);
+        let s2 = checked_generate(&sys, 2).unwrap();
         assert_eq!(s2, "11[1[0]0]1[0]0");
     }
 
     #[test]
     fn test_generate_koch() {
         let sys = koch_curve();
-        let s1 = generate(&sys, 1);
+        let s1 = checked_generate(&sys, 1).unwrap();
         assert_eq!(s1, "F+F-F-F+F");
     }
 
     #[test]
     fn test_string_growth() {
         let sys = plant();
-        let lengths: Vec<usize> = (0..5).map(|i| generate(&sys, i).len()).collect();
+        let lengths: Vec<usize> = (0..5).map(|i| checked_generate(&sys, i).unwrap().len()).collect();
         for w in lengths.windows(2) {
             assert!(w[1] > w[0], "String should grow each iteration");
         }
@@ -269,7 +647,7 @@ mod tests {
     #[test]
     fn test_interpret_produces_segments() {
         let sys = tree();
-        let s = generate(&sys, 3);
+        let s = checked_generate(&sys, 3).unwrap();
         let segments = interpret(&sys, &s);
         assert!(!segments.is_empty());
     }
@@ -277,7 +655,7 @@ mod tests {
     #[test]
     fn test_interpret_plant() {
         let sys = plant();
-        let s = generate(&sys, 3);
+        let s = checked_generate(&sys, 3).unwrap();
         let segments = interpret(&sys, &s);
         assert!(segments.len() > 10);
     }
@@ -285,7 +663,7 @@ mod tests {
     #[test]
     fn test_total_length() {
         let sys = tree();
-        let s = generate(&sys, 3);
+        let s = checked_generate(&sys, 3).unwrap();
         let segments = interpret(&sys, &s);
         assert!(total_length(&segments) > 0.0);
     }
@@ -293,7 +671,7 @@ mod tests {
     #[test]
     fn test_branching_depth() {
         let sys = tree();
-        let s = generate(&sys, 4);
+        let s = checked_generate(&sys, 4).unwrap();
         let segments = interpret(&sys, &s);
         assert!(max_depth(&segments) >= 2);
     }
@@ -301,7 +679,7 @@ mod tests {
     #[test]
     fn test_count_branches() {
         let sys = tree();
-        let s = generate(&sys, 3);
+        let s = checked_generate(&sys, 3).unwrap();
         let segments = interpret(&sys, &s);
         assert!(count_branches(&segments) > 0);
     }
@@ -309,7 +687,7 @@ mod tests {
     #[test]
     fn test_svg_output() {
         let sys = tree();
-        let s = generate(&sys, 3);
+        let s = checked_generate(&sys, 3).unwrap();
         let segments = interpret(&sys, &s);
         let md = max_depth(&segments);
         let svg = to_svg(&segments, md);
@@ -317,11 +695,132 @@ mod tests {
         assert!(svg.contains("<line"));
     }
 
+    #[test]
+    fn test_growth_distances() {
+        // A stem of two steps with a one-step side branch from its middle
+        let sys = LSystem { step_length: 1.0, angle: 90.0, ..tree() };
+        let segments = interpret(&sys, "F[+F]F");
+        let starts = growth_distances(&segments);
+        assert_eq!(starts.len(), 3);
+        assert!((starts[0] - 0.0).abs() < 1e-12);
+        assert!((starts[1] - 1.0).abs() < 1e-12);
+        assert!((starts[2] - 1.0).abs() < 1e-12);
+        let koch = interpret(&koch_curve(), &checked_generate(&koch_curve(), 2).unwrap());
+        let starts = growth_distances(&koch);
+        assert!(starts.windows(2).all(|w| w[1] > w[0]));
+    }
+
+    #[test]
+    fn test_animated_svg() {
+        let sys = plant();
+        let segments = interpret(&sys, &checked_generate(&sys, 3).unwrap());
+        let svg = to_animated_svg(&segments, max_depth(&segments), 5.0);
+        assert_eq!(svg.matches("<animate ").count(), segments.len());
+        assert!(svg.contains(r##"begin="0.000s""##));
+        // The last growth finishes at the requested time
+        let seconds = |tag: &str, name: &str| -> f64 {
+            tag.split(&format!("{name}=\"")).nth(1).unwrap().split('s').next().unwrap().parse().unwrap()
+        };
+        let end = svg.split("<animate ").skip(1).map(|a| seconds(a, "begin") + seconds(a, "dur")).fold(0.0, f64::max);
+        assert!((end - 5.0).abs() < 0.01, "{}", end);
+        assert!(!to_svg(&segments, 1).contains("<animate"));
+    }
+
+    #[test]
+    fn test_turtle_3d_moves() {
+        let sys = LSystem { step_length: 1.0, angle: 90.0, ..tree_3d() };
+        let end = |s: &str| interpret_3d(&sys, s).last().map(|seg| [seg.end.x, seg.end.y, seg.end.z]).unwrap();
+        let close = |a: [f64; 3], b: [f64; 3]| a.iter().zip(&b).all(|(x, y)| (x - y).abs() < 1e-12);
+        assert!(close(end("F"), [0.0, 0.0, 1.0]));
+        // Turning left about the up vector heads along the left vector
+        assert!(close(end("+F"), [0.0, 1.0, 0.0]));
+        // Pitching down heads away from the up vector; rolling first changes which way that is
+        assert!(close(end("&F"), [1.0, 0.0, 0.0]));
+        assert!(close(end("/&F"), [0.0, 1.0, 0.0]));
+        assert!(close(end("\\&F"), [0.0, -1.0, 0.0]));
+        assert!(close(end("|F"), [0.0, 0.0, -1.0]) && close(end("&|F"), [-1.0, 0.0, 0.0]));
+        // Brackets restore position and orientation
+        let segments = interpret_3d(&sys, "F[&F]F");
+        assert_eq!(segments[2].start, segments[1].start);
+        assert_eq!(segments[1].depth, 1);
+    }
+
+    #[test]
+    fn test_tree_3d_spreads() {
+        let sys = tree_3d();
+        assert!(is_3d(&sys) && is_3d(&coral()) && !is_3d(&plant()));
+        let segments = interpret_3d(&sys, &checked_generate(&sys, 5).unwrap());
+        // Branches leave the xz-plane in both directions
+        assert!(segments.iter().any(|s| s.end.y > 1.0) && segments.iter().any(|s| s.end.y < -1.0));
+        assert!(segments.iter().all(|s| s.end.z > -1e-9), "grows upward");
+        let coral = interpret_3d(&coral(), &checked_generate(&coral(), 4).unwrap());
+        assert_eq!(coral.iter().map(|s| s.depth).max(), Some(4));
+    }
+
+    #[test]
+    fn test_svg_3d() {
+        let sys = tree_3d();
+        let segments = interpret_3d(&sys, &checked_generate(&sys, 3).unwrap());
+        let iso = to_svg_3d(&segments, 3, &Projection::Isometric);
+        assert_eq!(iso.matches("<line").count(), segments.len());
+        let flat = project_segments(&segments, &Projection::Perspective { azimuth: 30.0, elevation: 10.0, distance: 4.0 });
+        assert_eq!(flat.len(), segments.len());
+        assert!(flat.iter().all(|s| s.x1.is_finite() && s.y2.is_finite()));
+    }
+
+    #[test]
+    fn test_parse_definition() {
+        let source = "# Bushy weed\nname: Weed\naxiom = X\nangle: 25  # degrees\nstep: 4\n\nX -> F+[[X]-X]-F[-FX]+X\nF -> F F\n";
+        let system = parse(source).unwrap();
+        assert_eq!(system.name, "Weed");
+        assert_eq!((system.axiom.as_str(), system.angle, system.step_length), ("X", 25.0, 4.0));
+        assert_eq!(system.rules.len(), 2);
+        assert_eq!(system.rules[1].to, "FF");
+        assert_eq!(checked_generate(&system, 3).unwrap(), checked_generate(&plant(), 3).unwrap());
+        let from_str: LSystem = "axiom: F&F\nF -> F/F".parse().unwrap();
+        assert!(is_3d(&from_str));
+        assert_eq!(from_str.angle, 90.0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let message = |source: &str| match parse(source) {
+            Err(MathaturaError::Parse { line, message }) => (line, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        assert_eq!(message("axiom: F\nF -> F[+F"), (2, "1 unclosed '[' in rule for 'F'".to_string()));
+        assert_eq!(message("axiom: F]").1, "']' without a matching '[' in axiom");
+        assert!(message("axiom: F\nF -> F@F").1.starts_with("unknown symbol '@'"));
+        assert_eq!(message("axiom: F\nFG -> F").1, "rule must rewrite a single letter or digit, found 'FG'");
+        assert_eq!(message("axiom: F\nF -> FF\nF -> F").0, 3);
+        assert!(message("axiom: F\nangle: wide").1.contains("angle must be a number"));
+        assert!(message("axim: F").1.starts_with("unknown setting 'axim'"));
+        assert_eq!(message("angle: 30\n"), (1, "no axiom given".to_string()));
+        assert!(message("axiom: F\njust words").1.starts_with("expected 'key: value'"));
+        assert!(message("axiom: F\nstep: -1").1.contains("positive"));
+    }
+
+    #[test]
+    fn test_checked_generate_limit() {
+        let system = plant();
+        assert_eq!(checked_generate(&system, 3).unwrap(), checked_generate(&system, 3).unwrap());
+        assert!(checked_generate(&system, 12).is_err());
+    }
+
     #[test]
     fn test_dragon_curve() {
         let sys = dragon_curve();
-        let s = generate(&sys, 5);
+        let s = checked_generate(&sys, 5).unwrap();
         let segments = interpret(&sys, &s);
         assert!(!segments.is_empty());
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let system = plant();
+        let json = serde_json::to_string(&system).unwrap();
+        let back: LSystem = serde_json::from_str(&json).unwrap();
+        assert_eq!(checked_generate(&back, 3).unwrap(), checked_generate(&system, 3).unwrap());
+    }
 }
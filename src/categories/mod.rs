@@ -1,6 +1,30 @@
 pub mod phyllotaxis;
 pub mod fractals;
+pub mod ifs;
 pub mod spirals;
 pub mod chaos;
+pub mod attractors;
 pub mod lsystems;
 pub mod turing;
+pub mod snowflake;
+pub mod roses;
+pub mod superformula;
+pub mod harmonograph;
+pub mod epicycles;
+pub mod spirograph;
+pub mod flocking;
+pub mod physarum;
+pub mod dla;
+pub mod colonization;
+pub mod sandpile;
+pub mod automata;
+pub mod lenia;
+pub mod fireflies;
+pub mod predator_prey;
+pub mod waves;
+pub mod chladni;
+pub mod dunes;
+pub mod terrain;
+pub mod orbweb;
+pub mod tessellations;
+pub mod symmetry;
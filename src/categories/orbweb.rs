@@ -0,0 +1,453 @@
+//! Orb webs — the spiral-and-spoke architecture of orb-weaving spiders.
+//!
+//! A garden spider builds in a fixed order: anchor threads strung into a
+//! frame, radial threads from a central hub out to that frame, then a
+//! temporary auxiliary spiral winding outward in widening, roughly
+//! logarithmic turns, and finally the sticky capture spiral laid from the
+//! rim inward at an even, arithmetic spacing — stopping short of the hub to
+//! leave an open "free zone". Every spiral turn is measured as a fraction of
+//! each radial's length, so an irregular frame or an off-center hub gives
+//! the lopsided webs seen in the wild: the hub usually sits above center and
+//! the lower half is larger, because the spider runs downhill faster.
+//!
+//! Silk between radials sags under its own weight and the morning dew, which
+//! beads into drops along the sticky threads.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_range, Result};
+use crate::geometry::{Generator, Geometry, Layer, Style};
+use crate::render::theme::Theme;
+use super::fractals::{Point, SimpleRng};
+
+/// Parameters for an orb web.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WebParams {
+    /// Number of radial threads
+    pub radials: usize,
+    /// Anchor points in the frame
+    pub anchors: usize,
+    /// Frame radius
+    pub radius: f64,
+    /// Turns of the capture spiral
+    pub capture_turns: f64,
+    /// Turns of the auxiliary spiral
+    pub auxiliary_turns: f64,
+    /// Hub displacement upward as a fraction of the radius (0 = centered)
+    pub asymmetry: f64,
+    /// Droop of each thread between radials, as a fraction of its length
+    pub sag: f64,
+    /// Fraction of capture threads torn away
+    pub damage: f64,
+    /// Irregularity of radial angles and anchor placement (0 = perfect)
+    pub jitter: f64,
+}
+
+impl Default for WebParams {
+    fn default() -> Self {
+        Preset::Garden.params()
+    }
+}
+
+impl WebParams {
+    /// Require 3–360 radials, 3–12 anchors, positive turns, and fractions in range.
+    pub fn validate(&self) -> Result<()> {
+        check_range("radials", self.radials as f64, 3.0..=360.0, "3 to 360 radials")?;
+        check_range("anchors", self.anchors as f64, 3.0..=12.0, "3 to 12 anchors")?;
+        check_range("radius", self.radius, f64::MIN_POSITIVE..=1e6, "a positive radius")?;
+        check_range("capture_turns", self.capture_turns, 0.5..=200.0, "0.5 to 200 turns")?;
+        check_range("auxiliary_turns", self.auxiliary_turns, 0.0..=50.0, "0 to 50 turns")?;
+        check_range("asymmetry", self.asymmetry, 0.0..=0.6, "0 to 0.6")?;
+        check_range("sag", self.sag, 0.0..=0.5, "0 to 0.5")?;
+        check_range("damage", self.damage, 0.0..=1.0, "0 to 1")?;
+        check_range("jitter", self.jitter, 0.0..=1.0, "0 to 1")
+    }
+}
+
+/// Typical webs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// A fresh, nearly symmetric garden-spider web
+    Garden,
+    /// Hub high in the frame with a deep lower half, as most orb weavers build
+    Lopsided,
+    /// An old web, sagging and torn
+    Tattered,
+}
+
+impl Preset {
+    pub fn params(self) -> WebParams {
+        match self {
+            Preset::Garden => WebParams {
+                radials: 32,
+                anchors: 5,
+                radius: 350.0,
+                capture_turns: 30.0,
+                auxiliary_turns: 6.0,
+                asymmetry: 0.05,
+                sag: 0.03,
+                damage: 0.0,
+                jitter: 0.15,
+            },
+            Preset::Lopsided => WebParams {
+                radials: 28,
+                anchors: 4,
+                radius: 350.0,
+                capture_turns: 26.0,
+                auxiliary_turns: 5.0,
+                asymmetry: 0.3,
+                sag: 0.05,
+                damage: 0.02,
+                jitter: 0.3,
+            },
+            Preset::Tattered => WebParams {
+                radials: 24,
+                anchors: 6,
+                radius: 350.0,
+                capture_turns: 22.0,
+                auxiliary_turns: 0.0,
+                asymmetry: 0.15,
+                sag: 0.12,
+                damage: 0.25,
+                jitter: 0.5,
+            },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Garden => "Garden Orb Web",
+            Preset::Lopsided => "Lopsided Orb Web",
+            Preset::Tattered => "Tattered Web",
+        }
+    }
+}
+
+/// A built web. Coordinates are centered on the frame, y down.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrbWeb {
+    /// Anchor points, joined in order into a closed frame
+    pub frame: Vec<Point>,
+    pub hub: Point,
+    /// Outer end of each radial, on the frame, in angular order
+    pub radials: Vec<Point>,
+    /// The auxiliary spiral, hub outward
+    pub auxiliary: Vec<Point>,
+    /// The capture spiral, rim inward, split where threads are torn
+    pub capture: Vec<Vec<Point>>,
+}
+
+/// Distance along the ray `origin + t·dir` to the frame, or `None` if it misses.
+fn ray_to_frame(origin: Point, dir: (f64, f64), frame: &[Point]) -> Option<f64> {
+    let n = frame.len();
+    (0..n)
+        .filter_map(|i| {
+            let (a, b) = (frame[i], frame[(i + 1) % n]);
+            let (ex, ey) = (b.x - a.x, b.y - a.y);
+            let denom = dir.0 * ey - dir.1 * ex;
+            if denom.abs() < 1e-12 {
+                return None;
+            }
+            let (wx, wy) = (a.x - origin.x, a.y - origin.y);
+            let t = (wx * ey - wy * ex) / denom;
+            let u = (wx * dir.1 - wy * dir.0) / denom;
+            (t > 0.0 && (-1e-9..=1.0 + 1e-9).contains(&u)).then_some(t)
+        })
+        .fold(None, |best: Option<f64>, t| Some(best.map_or(t, |b| b.min(t))))
+}
+
+/// The thread between two spiral crossings, drooping by `sag` of its length.
+fn sagging(a: Point, b: Point, sag: f64, out: &mut Vec<Point>) {
+    const STEPS: usize = 4;
+    let droop = sag * (b.x - a.x).hypot(b.y - a.y);
+    for s in 1..=STEPS {
+        let t = s as f64 / STEPS as f64;
+        out.push(Point { x: a.x + (b.x - a.x) * t, y: a.y + (b.y - a.y) * t + 4.0 * droop * t * (1.0 - t) });
+    }
+}
+
+/// Build a web.
+pub fn build(params: &WebParams, seed: u64) -> OrbWeb {
+    let mut rng = SimpleRng::new(seed);
+    let jitter = params.jitter.clamp(0.0, 1.0);
+    let anchors = params.anchors.max(3);
+    let frame: Vec<Point> = (0..anchors)
+        .map(|i| {
+            let a = 2.0 * PI * (i as f64 + 0.5 * jitter * (rng.next_f64() - 0.5)) / anchors as f64 - PI / 2.0;
+            let r = params.radius * (1.0 - 0.25 * jitter * rng.next_f64());
+            Point { x: r * a.cos(), y: r * a.sin() }
+        })
+        .collect();
+    // The frame polygon contains a disc of at least its inradius around the center
+    let hub = Point { x: 0.0, y: -params.asymmetry * params.radius * (PI / anchors as f64).cos() };
+
+    let n = params.radials.max(3);
+    let spokes: Vec<((f64, f64), f64)> = (0..n)
+        .map(|i| {
+            let a = 2.0 * PI * (i as f64 + 0.4 * jitter * (rng.next_f64() - 0.5)) / n as f64;
+            let dir = (a.cos(), a.sin());
+            (dir, ray_to_frame(hub, dir, &frame).unwrap_or(params.radius))
+        })
+        .collect();
+    let along = |k: usize, fraction: f64| {
+        let ((dx, dy), len) = spokes[k % n];
+        Point { x: hub.x + fraction * len * dx, y: hub.y + fraction * len * dy }
+    };
+
+    // Auxiliary spiral: geometric spacing from the hub outward
+    let (aux_inner, aux_outer): (f64, f64) = (0.12, 0.6);
+    let aux_steps = (params.auxiliary_turns * n as f64).round() as usize;
+    let auxiliary = (0..=aux_steps)
+        .filter(|_| aux_steps > 0)
+        .map(|k| along(k, aux_inner * (aux_outer / aux_inner).powf(k as f64 / aux_steps.max(1) as f64)))
+        .collect();
+
+    // Capture spiral: even spacing from the rim in to the free zone
+    let (capture_outer, capture_inner) = (0.95, 0.25);
+    let steps = (params.capture_turns * n as f64).round().max(1.0) as usize;
+    let fraction = |k: usize| capture_outer - (capture_outer - capture_inner) * k as f64 / steps as f64;
+    let mut capture = Vec::new();
+    let mut thread = vec![along(0, fraction(0))];
+    for k in 1..=steps {
+        let (a, b) = (along(k - 1, fraction(k - 1)), along(k, fraction(k)));
+        if rng.next_f64() < params.damage {
+            if thread.len() > 1 {
+                capture.push(core::mem::take(&mut thread));
+            }
+            thread = vec![b];
+        } else {
+            sagging(a, b, params.sag, &mut thread);
+        }
+    }
+    if thread.len() > 1 {
+        capture.push(thread);
+    }
+
+    OrbWeb { frame, hub, radials: (0..n).map(|k| along(k, 1.0)).collect(), auxiliary, capture }
+}
+
+/// Dew drops beaded along the capture threads as (center, radius) pairs,
+/// about `density` drops per 100 units of thread.
+pub fn dew_drops(web: &OrbWeb, density: f64, seed: u64) -> Vec<(Point, f64)> {
+    let mut rng = SimpleRng::new(seed ^ 0x0064_6577);
+    let mut drops = Vec::new();
+    for thread in &web.capture {
+        for w in thread.windows(2) {
+            let len = (w[1].x - w[0].x).hypot(w[1].y - w[0].y);
+            if rng.next_f64() < density * len / 100.0 {
+                let t = rng.next_f64();
+                let at = Point { x: w[0].x + (w[1].x - w[0].x) * t, y: w[0].y + (w[1].y - w[0].y) * t };
+                // Mostly small beads, the occasional large drop
+                drops.push((at, 0.8 + 2.2 * rng.next_f64().powi(3)));
+            }
+        }
+    }
+    drops
+}
+
+impl Generator for WebParams {
+    /// Frame, radials, auxiliary and capture threads as layers styled like
+    /// [`to_svg`], with seed 42.
+    fn generate(&self) -> Geometry {
+        let web = build(self, 42);
+        let frame = vec![web.frame.iter().chain(web.frame.first()).copied().collect::<Vec<_>>()];
+        let radials = web.radials.iter().map(|&end| vec![web.hub, end]).collect();
+        let auxiliary = if web.auxiliary.is_empty() { Vec::new() } else { vec![web.auxiliary] };
+        let layer = |color: &str, width: f64, lines: Vec<Vec<Point>>| Layer {
+            style: Style::stroke(color, width),
+            geometry: Geometry::Polylines(lines),
+        };
+        Geometry::Layers(vec![
+            layer("#9aa5b1", 1.4, frame),
+            layer("#c9d1d9", 0.9, radials),
+            layer("#8b949e", 0.5, auxiliary),
+            Layer { style: Style::stroke("#e6edf3", 0.7).with_opacity(0.9), geometry: Geometry::Polylines(web.capture) },
+        ])
+    }
+}
+
+/// Render a web as silver threads on a dark background, with dew drops.
+pub fn to_svg(web: &OrbWeb, drops: &[(Point, f64)]) -> String {
+    to_svg_with_theme(web, drops, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, threads run from its first stop
+/// for the auxiliary spiral up to its last for the capture spiral.
+pub fn to_svg_with_theme(web: &OrbWeb, drops: &[(Point, f64)], theme: &Theme) -> String {
+    let size = 800.0;
+    let extent = web.frame.iter().map(|p| p.x.abs().max(p.y.abs())).fold(1e-9, f64::max);
+    let scale = (size / 2.0 - 20.0) / extent;
+    let c = size / 2.0;
+    let coords = |pts: &mut dyn Iterator<Item = &Point>| {
+        pts.map(|p| format!("{:.1},{:.1}", c + p.x * scale, c + p.y * scale)).collect::<Vec<_>>().join(" ")
+    };
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}<polygon points="{}" fill="none" stroke="{}" stroke-width="{}"/>
+"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        coords(&mut web.frame.iter()),
+        theme.color(0.3, "#9aa5b1"),
+        theme.stroke_width(1.4)
+    );
+    // Anchor lines out to the edge of the picture
+    for p in &web.frame {
+        let k = (size / 2.0) / p.x.abs().max(p.y.abs()).max(1e-9) / scale;
+        svg.push_str(&format!(
+            r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="{}"/>
+"##,
+            c + p.x * scale,
+            c + p.y * scale,
+            c + p.x * k * scale,
+            c + p.y * k * scale,
+            theme.color(0.3, "#9aa5b1"),
+            theme.stroke_width(1.2)
+        ));
+    }
+    for end in &web.radials {
+        svg.push_str(&format!(
+            r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="{}"/>
+"##,
+            c + web.hub.x * scale,
+            c + web.hub.y * scale,
+            c + end.x * scale,
+            c + end.y * scale,
+            theme.color(0.6, "#c9d1d9"),
+            theme.stroke_width(0.9)
+        ));
+    }
+    if !web.auxiliary.is_empty() {
+        svg.push_str(&format!(
+            r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-dasharray="2,3"/>
+"##,
+            coords(&mut web.auxiliary.iter()),
+            theme.color(0.0, "#8b949e"),
+            theme.stroke_width(0.5)
+        ));
+    }
+    for thread in &web.capture {
+        svg.push_str(&format!(
+            r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round" opacity="0.9"/>
+"##,
+            coords(&mut thread.iter()),
+            theme.color(1.0, "#e6edf3"),
+            theme.stroke_width(0.7)
+        ));
+    }
+    for (p, r) in drops {
+        let (x, y, r) = (c + p.x * scale, c + p.y * scale, r * scale.sqrt().min(1.5));
+        svg.push_str(&format!(
+            r##"<circle cx="{x:.1}" cy="{y:.1}" r="{r:.1}" fill="{}" opacity="0.55"/>
+<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}" opacity="0.8"/>
+"##,
+            theme.color(0.8, "#a5d6ff"),
+            x - r * 0.3,
+            y - r * 0.3,
+            r * 0.3,
+            theme.color(1.0, "#ffffff")
+        ));
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance_to_frame(p: &Point, frame: &[Point]) -> f64 {
+        (0..frame.len())
+            .map(|i| {
+                let (a, b) = (frame[i], frame[(i + 1) % frame.len()]);
+                let (ex, ey) = (b.x - a.x, b.y - a.y);
+                let t = (((p.x - a.x) * ex + (p.y - a.y) * ey) / (ex * ex + ey * ey)).clamp(0.0, 1.0);
+                (p.x - a.x - t * ex).hypot(p.y - a.y - t * ey)
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn test_radials_reach_the_frame() {
+        for preset in [Preset::Garden, Preset::Lopsided, Preset::Tattered] {
+            let params = preset.params();
+            let web = build(&params, 7);
+            assert_eq!(web.radials.len(), params.radials);
+            assert_eq!(web.frame.len(), params.anchors);
+            for end in &web.radials {
+                assert!(distance_to_frame(end, &web.frame) < 1e-6, "{} radial misses the frame", preset.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_capture_spiral_is_evenly_spaced() {
+        let params = WebParams { damage: 0.0, sag: 0.0, jitter: 0.0, ..Default::default() };
+        let web = build(&params, 1);
+        assert_eq!(web.capture.len(), 1);
+        // Successive crossings of the first radial, every n-th vertex group
+        let thread = &web.capture[0];
+        let per_turn = params.radials * 4;
+        let dist = |p: &Point| (p.x - web.hub.x).hypot(p.y - web.hub.y);
+        let gaps: Vec<f64> = (0..5).map(|t| dist(&thread[t * per_turn]) - dist(&thread[(t + 1) * per_turn])).collect();
+        assert!(gaps.iter().all(|g| (g - gaps[0]).abs() < 1e-9 && *g > 0.0), "{:?}", gaps);
+    }
+
+    #[test]
+    fn test_auxiliary_spiral_is_logarithmic() {
+        let params = WebParams { jitter: 0.0, ..Default::default() };
+        let web = build(&params, 1);
+        let n = params.radials;
+        let dist = |p: &Point| (p.x - web.hub.x).hypot(p.y - web.hub.y);
+        let ratios: Vec<f64> = (0..4).map(|t| dist(&web.auxiliary[(t + 1) * n]) / dist(&web.auxiliary[t * n])).collect();
+        assert!(ratios.iter().all(|r| (r - ratios[0]).abs() < 1e-9 && *r > 1.0));
+    }
+
+    #[test]
+    fn test_sag_droops_downward() {
+        let mut out = Vec::new();
+        sagging(Point { x: 0.0, y: 0.0 }, Point { x: 10.0, y: 0.0 }, 0.1, &mut out);
+        assert_eq!(out.len(), 4);
+        assert!((out[1].y - 1.0).abs() < 1e-12);
+        assert_eq!(out[3], Point { x: 10.0, y: 0.0 });
+    }
+
+    #[test]
+    fn test_damage_tears_threads() {
+        let intact = build(&WebParams { damage: 0.0, ..Default::default() }, 3);
+        let torn = build(&WebParams { damage: 0.3, ..Default::default() }, 3);
+        let vertices = |w: &OrbWeb| w.capture.iter().map(Vec::len).sum::<usize>();
+        assert!(torn.capture.len() > 10);
+        assert!(vertices(&torn) < vertices(&intact) * 8 / 10);
+    }
+
+    #[test]
+    fn test_asymmetry_raises_hub() {
+        let web = build(&Preset::Lopsided.params(), 5);
+        assert!(web.hub.y < 0.0);
+        // The lower radials are longer than the upper ones
+        let len = |p: &Point| (p.x - web.hub.x).hypot(p.y - web.hub.y);
+        let (below, above): (Vec<&Point>, Vec<&Point>) = web.radials.iter().partition(|p| p.y > web.hub.y);
+        let mean = |v: &[&Point]| v.iter().map(|p| len(p)).sum::<f64>() / v.len() as f64;
+        assert!(mean(&below) > mean(&above));
+    }
+
+    #[test]
+    fn test_dew_and_svg() {
+        let web = build(&Preset::Tattered.params(), 9);
+        let drops = dew_drops(&web, 2.0, 9);
+        assert!(!drops.is_empty());
+        assert_eq!(drops, dew_drops(&web, 2.0, 9));
+        let svg = to_svg(&web, &drops);
+        assert_eq!(svg.matches("<circle").count(), 2 * drops.len());
+        assert!(Preset::Garden.params().validate().is_ok());
+        assert!(WebParams { damage: 1.5, ..Default::default() }.validate().is_err());
+        assert!(!WebParams::default().generate().is_empty());
+    }
+}
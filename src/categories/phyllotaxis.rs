@@ -6,11 +6,24 @@
 //!
 //! When α = golden angle ≈ 137.508°, we get the optimal packing seen in sunflowers.
 
-use std::f64::consts::PI;
-use crate::constants::{GOLDEN_ANGLE_DEG, FIBONACCI};
+use core::f64::consts::PI;
+use crate::prelude::*;
+use crate::constants::{FIBONACCI, GOLDEN_ANGLE_DEG, GOLDEN_ANGLE_RAD};
+use crate::error::{check_count, check_positive, check_range, Result};
+use crate::float::{cast, Float};
+use crate::geometry::{Circle, Generator, Geometry};
+use crate::parallel::map_range;
+use crate::render::colormap::Colormap;
+use crate::render::mesh::Mesh;
+use crate::render::Projection;
+use crate::render::theme::Theme;
+use super::chaos::Point3D;
+use super::fractals::Point;
+use super::tessellations::{clip_convex, delaunay, voronoi};
 
 /// A single element in a phyllotactic arrangement.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Element {
     pub index: usize,
     pub angle: f64,
@@ -21,6 +34,7 @@ pub struct Element {
 
 /// Parameters for phyllotaxis generation.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Params {
     pub count: usize,
     pub divergence_angle: f64,
@@ -37,8 +51,21 @@ impl Default for Params {
     }
 }
 
+/// Largest arrangement [`Params::validate`] accepts.
+pub const MAX_ELEMENTS: usize = 1_000_000;
+
+impl Params {
+    /// Reject empty or oversized arrangements and non-positive scales.
+    pub fn validate(&self) -> Result<()> {
+        check_count("count", self.count, MAX_ELEMENTS)?;
+        check_range("divergence_angle", self.divergence_angle, 0.0..=360.0, "0 to 360 degrees")?;
+        check_positive("scale", self.scale)
+    }
+}
+
 /// Pattern type for different plant structures.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pattern {
     /// Flat sunflower head — classic Vogel model
     Sunflower,
@@ -50,19 +77,70 @@ pub enum Pattern {
 
 /// Generate a Vogel spiral pattern.
 pub fn vogel_spiral(params: &Params) -> Vec<Element> {
-    let angle_rad = params.divergence_angle.to_radians();
+    vogel_spiral_iter(params).collect()
+}
+
+/// Lazily generate the Vogel spiral, one element at a time.
+pub fn vogel_spiral_iter(params: &Params) -> VogelIter {
+    VogelIter { angle: params.divergence_angle.to_radians(), scale: params.scale, indices: 0..params.count }
+}
+
+/// The elements of [`vogel_spiral`], computed one at a time as iterated.
+///
+/// Knows its exact length and can also run from the rim inwards.
+#[derive(Debug, Clone)]
+pub struct VogelIter {
+    /// Divergence angle in radians
+    angle: f64,
+    scale: f64,
+    indices: core::ops::Range<usize>,
+}
+
+impl VogelIter {
+    fn element(&self, n: usize) -> Element {
+        let nf = n as f64;
+        let theta = nf * self.angle;
+        let r = self.scale * nf.sqrt();
+        Element {
+            index: n,
+            angle: theta,
+            radius: r,
+            x: r * theta.cos(),
+            y: r * theta.sin(),
+        }
+    }
+}
+
+impl Iterator for VogelIter {
+    type Item = Element;
+
+    fn next(&mut self) -> Option<Element> {
+        self.indices.next().map(|n| self.element(n))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for VogelIter {
+    fn next_back(&mut self) -> Option<Element> {
+        self.indices.next_back().map(|n| self.element(n))
+    }
+}
+
+impl ExactSizeIterator for VogelIter {}
+
+/// Vogel spiral positions computed at any float precision.
+pub fn vogel_spiral_as<F: Float>(params: &Params) -> Vec<[F; 2]> {
+    let angle_rad: F = cast(params.divergence_angle.to_radians());
+    let scale: F = cast(params.scale);
     (0..params.count)
         .map(|n| {
-            let nf = n as f64;
+            let nf: F = cast(n as f64);
             let theta = nf * angle_rad;
-            let r = params.scale * nf.sqrt();
-            Element {
-                index: n,
-                angle: theta,
-                radius: r,
-                x: r * theta.cos(),
-                y: r * theta.sin(),
-            }
+            let r = scale * nf.sqrt();
+            [r * theta.cos(), r * theta.sin()]
         })
         .collect()
 }
@@ -103,26 +181,467 @@ pub fn pinecone(params: &Params) -> Vec<Element> {
         .collect()
 }
 
+/// A surface to lift a flat arrangement onto, rising from z = 0 at the rim
+/// to `height` × the rim radius at the center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Surface {
+    /// A rounded dome, like a sunflower head
+    Paraboloid { height: f64 },
+    /// A straight-sided cone, like a pinecone
+    Cone { height: f64 },
+}
+
+impl Surface {
+    /// Height and slope dz/dr at radius `r` on a head of rim radius `rim`.
+    fn profile(&self, r: f64, rim: f64) -> (f64, f64) {
+        let t = (r / rim).min(1.0);
+        match *self {
+            Surface::Paraboloid { height } => (height * rim * (1.0 - t * t), -2.0 * height * t),
+            Surface::Cone { height } => (height * rim * (1.0 - t), -height),
+        }
+    }
+}
+
+/// Element positions lifted onto `surface`, keeping their x and y.
+pub fn lift(elements: &[Element], surface: Surface) -> Vec<Point3D> {
+    let rim = elements.iter().map(|e| e.radius).fold(1e-9, f64::max);
+    elements.iter().map(|e| Point3D { x: e.x, y: e.y, z: surface.profile(e.radius, rim).0 }).collect()
+}
+
+/// A printable mesh of a seed head: the surface through the element
+/// positions, thickened into a shell, with a dome for every floret standing
+/// along the surface normal. Every part is a closed solid.
+pub fn to_mesh(elements: &[Element], surface: Surface) -> Mesh {
+    let rim = elements.iter().map(|e| e.radius).fold(1e-9, f64::max);
+    let lifted = lift(elements, surface);
+    let flat: Vec<Point> = elements.iter().map(|e| Point { x: e.x, y: e.y }).collect();
+    let triangles = delaunay(&flat)
+        .into_iter()
+        .map(|[a, b, c]| {
+            let turn = (flat[b].x - flat[a].x) * (flat[c].y - flat[a].y) - (flat[b].y - flat[a].y) * (flat[c].x - flat[a].x);
+            if turn > 0.0 { [a, b, c] } else { [a, c, b] }
+        })
+        .collect();
+    let mut mesh = Mesh { vertices: lifted.clone(), triangles }.solidify(0.08 * rim);
+    // Vogel's r = c√n, so each element owns an area of π c²
+    let floret = 0.5 * rim / ((elements.len().max(2) - 1) as f64).sqrt();
+    for (e, &p) in elements.iter().zip(&lifted) {
+        let slope = surface.profile(e.radius, rim).1;
+        let (cos, sin) = if e.radius > 0.0 { (e.x / e.radius, e.y / e.radius) } else { (0.0, 0.0) };
+        let axis = Point3D { x: -slope * cos, y: -slope * sin, z: 1.0 };
+        mesh.append(&Mesh::dome(p, floret, axis, 12));
+    }
+    mesh
+}
+
+/// Points spread evenly over the unit sphere by the golden-angle lattice.
+///
+/// Element i sits at height z = 1 − (2i + 1)/n, so every element owns a band
+/// of equal area (Archimedes' hat-box theorem), and turns by the golden angle
+/// from the one before. Globular seed heads such as allium and buttonbush
+/// are packed this way; the lattice is also a near-uniform sample of
+/// directions.
+pub fn fibonacci_sphere(count: usize) -> Vec<Point3D> {
+    let n = count as f64;
+    (0..count)
+        .map(|i| {
+            let z = 1.0 - (2.0 * i as f64 + 1.0) / n;
+            let r = (1.0 - z * z).sqrt();
+            let theta = i as f64 * GOLDEN_ANGLE_RAD;
+            Point3D { x: r * theta.cos(), y: r * theta.sin(), z }
+        })
+        .collect()
+}
+
+/// The parastichy number seen around the equator of an n-point Fibonacci
+/// sphere: neighbors there are F indices apart with 2F/n ≈ √(4π/n).
+pub fn sphere_parastichy(count: usize) -> usize {
+    let target = (PI * count as f64).sqrt();
+    FIBONACCI[2..]
+        .iter()
+        .min_by(|&&a, &&b| (a as f64 - target).abs().total_cmp(&(b as f64 - target).abs()))
+        .map_or(1, |&f| f as usize)
+}
+
+/// Render a Fibonacci sphere through `projection`, near side bright and far
+/// side dimmed. Each spiral of the equatorial parastichy family takes one
+/// hue, in order around the sphere.
+pub fn sphere_to_svg(points: &[Point3D], projection: Projection) -> String {
+    sphere_to_svg_with_theme(points, projection, &Theme::dark())
+}
+
+/// [`sphere_to_svg`] in `theme`; with a palette, the spirals take it in
+/// order around the sphere.
+pub fn sphere_to_svg_with_theme(points: &[Point3D], projection: Projection, theme: &Theme) -> String {
+    let size = 800.0;
+    let c = size / 2.0;
+    let scale = 340.0;
+    let family = sphere_parastichy(points.len());
+    let dot = (0.45 * scale * (4.0 * PI / points.len().max(1) as f64).sqrt()).max(0.8);
+    let projected = projection.project(points);
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    // Painter's order: farthest first
+    order.sort_by(|&a, &b| projected[b][2].total_cmp(&projected[a][2]));
+
+    let mut content = format!(
+        r##"<circle cx="{c}" cy="{c}" r="{:.1}" fill="{rim}" fill-opacity="0.05" stroke="{rim}" stroke-opacity="0.12" stroke-width="{}"/>
+"##,
+        scale + dot,
+        theme.stroke_width(1.0),
+        rim = theme.color(0.0, "#ffffff")
+    );
+    for i in order {
+        let [x, y, depth] = projected[i];
+        // On a unit sphere the depth is minus the cosine toward the viewer
+        let facing = (-depth).clamp(-1.0, 1.0);
+        // Spiral i mod F starts at that element's angle, so hues sweep around
+        let hue = family_hue(i, family);
+        let (light, opacity) = if facing >= 0.0 { (35.0 + 30.0 * facing, 0.95) } else { (30.0, 0.25) };
+        content.push_str(&format!(
+            r##"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}" opacity="{opacity}"/>
+"##,
+            c + x * scale,
+            c + y * scale,
+            dot * (0.6 + 0.4 * facing.abs()),
+            theme.color(hue / 360.0, crate::render::hsl(hue, 70.0, light))
+        ));
+    }
+    crate::render::svg_document_with_theme(size as u32, size as u32, &content, theme)
+}
+
+/// Parameters for phyllotaxis on a cylinder — leaves up a stem, areoles on
+/// a cactus.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CylinderParams {
+    pub count: usize,
+    pub divergence_angle: f64,
+    /// Height gained from one element to the next
+    pub rise: f64,
+    /// Girth of the stem
+    pub circumference: f64,
+}
+
+impl Default for CylinderParams {
+    fn default() -> Self {
+        Self { count: 300, divergence_angle: GOLDEN_ANGLE_DEG, rise: 1.0, circumference: 40.0 }
+    }
+}
+
+impl CylinderParams {
+    /// Reject empty or oversized arrangements and non-positive dimensions.
+    pub fn validate(&self) -> Result<()> {
+        check_count("count", self.count, MAX_ELEMENTS)?;
+        check_range("divergence_angle", self.divergence_angle, 0.0..=360.0, "0 to 360 degrees")?;
+        check_positive("rise", self.rise)?;
+        check_positive("circumference", self.circumference)
+    }
+
+    /// Radius of the stem.
+    pub fn radius(&self) -> f64 {
+        self.circumference / (2.0 * PI)
+    }
+}
+
+/// Elements on a cylinder, unrolled: element n turns n × α around the stem
+/// and sits n × rise up it. `x` is the arc length around the stem in
+/// [0, circumference), `y` the height, and `radius` the stem radius.
+pub fn cylinder(params: &CylinderParams) -> Vec<Element> {
+    let angle_rad = params.divergence_angle.to_radians();
+    let radius = params.radius();
+    (0..params.count)
+        .map(|n| {
+            let theta = (n as f64 * angle_rad).rem_euclid(2.0 * PI);
+            Element { index: n, angle: theta, radius, x: theta * radius, y: n as f64 * params.rise }
+        })
+        .collect()
+}
+
+/// The (smaller, larger) pair of parastichy numbers on an unrolled cylinder:
+/// the two most common index steps from an element to its nearest
+/// neighbors, measured around the stem periodically.
+pub fn cylinder_parastichies(elements: &[Element], circumference: f64) -> Option<(usize, usize)> {
+    let mut steps: Map<usize, usize> = Map::new();
+    for a in elements {
+        let mut near: Vec<(f64, usize)> = elements
+            .iter()
+            .filter(|b| b.index != a.index)
+            .map(|b| {
+                let dx = (a.x - b.x).abs() % circumference;
+                (dx.min(circumference - dx).hypot(a.y - b.y), a.index.abs_diff(b.index))
+            })
+            .collect();
+        near.sort_by(|p, q| p.0.total_cmp(&q.0));
+        for &(_, step) in near.iter().take(2) {
+            *steps.entry(step).or_insert(0) += 1;
+        }
+    }
+    let mut ranked: Vec<(usize, usize)> = steps.into_iter().collect();
+    ranked.sort_by(|p, q| q.1.cmp(&p.1).then(p.0.cmp(&q.0)));
+    match ranked[..] {
+        [(a, _), (b, _), ..] => Some((a.min(b), a.max(b))),
+        _ => None,
+    }
+}
+
+/// Hue of element `index` when the spirals `family` apart are colored in
+/// order around the stem or sphere.
+fn family_hue(index: usize, family: usize) -> f64 {
+    ((index % family.max(1)) as f64 * GOLDEN_ANGLE_DEG).rem_euclid(360.0)
+}
+
+/// The color of element `index`'s spiral at `light`ness, or with a palette
+/// its hue's place along it.
+fn family_color(index: usize, family: usize, light: f64, theme: &Theme) -> String {
+    let hue = family_hue(index, family);
+    theme.color(hue / 360.0, crate::render::hsl(hue, 70.0, light))
+}
+
+/// Render the unrolled cylinder as a flat lattice, base at the bottom, with
+/// the steeper parastichy family picked out in hue.
+pub fn cylinder_to_svg(elements: &[Element], params: &CylinderParams) -> String {
+    cylinder_to_svg_with_theme(elements, params, &Theme::dark())
+}
+
+/// [`cylinder_to_svg`] in `theme`; with a palette, the spirals take it in
+/// order around the stem.
+pub fn cylinder_to_svg_with_theme(elements: &[Element], params: &CylinderParams, theme: &Theme) -> String {
+    let height = (params.count.max(1) - 1) as f64 * params.rise;
+    let margin = 30.0;
+    let scale = ((800.0 - 2.0 * margin) / height.max(params.circumference)).min(4.0 * 800.0 / params.circumference);
+    let (w, h) = (params.circumference * scale + 2.0 * margin, height * scale + 2.0 * margin);
+    let family = cylinder_parastichies(elements, params.circumference).map_or(1, |(_, b)| b);
+    let dot = 0.35 * (params.circumference * params.rise).sqrt() * scale;
+    let mut content = format!(
+        r##"<rect x="{margin}" y="{margin}" width="{:.1}" height="{:.1}" fill="{}" fill-opacity="0.05"/>
+"##,
+        params.circumference * scale,
+        height * scale,
+        theme.color(0.0, "#ffffff")
+    );
+    for e in elements {
+        content.push_str(&format!(
+            r##"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}"/>
+"##,
+            margin + e.x * scale,
+            h - margin - e.y * scale,
+            dot,
+            family_color(e.index, family, 55.0, theme)
+        ));
+    }
+    crate::render::svg_document_with_theme(w.ceil() as u32, h.ceil() as u32, &content, theme)
+}
+
+/// Render the elements wrapped round a stem and seen slightly from above
+/// through an orthographic projection, shaded by how squarely each faces
+/// the viewer; the far side shows faintly through.
+pub fn cylinder_3d_svg(elements: &[Element], params: &CylinderParams) -> String {
+    cylinder_3d_svg_with_theme(elements, params, &Theme::dark())
+}
+
+/// [`cylinder_3d_svg`] in `theme`, colored as [`cylinder_to_svg_with_theme`].
+pub fn cylinder_3d_svg_with_theme(elements: &[Element], params: &CylinderParams, theme: &Theme) -> String {
+    let size = 800.0;
+    let radius = params.radius();
+    let points: Vec<Point3D> = elements
+        .iter()
+        .map(|e| Point3D { x: radius * e.angle.cos(), y: radius * e.angle.sin(), z: e.y })
+        .collect();
+    let projected = Projection::Orthographic { azimuth: 0.0, elevation: 12.0 }.project(&points);
+    let (w, h) = projected.iter().fold((1e-9, 1e-9), |(w, h), p| (f64::max(w, p[0].abs()), f64::max(h, p[1].abs())));
+    let scale = (size / 2.0 - 30.0) / w.max(h);
+    let c = size / 2.0;
+    let family = cylinder_parastichies(elements, params.circumference).map_or(1, |(_, b)| b);
+    let dot = 0.35 * (params.circumference * params.rise).sqrt() * scale;
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| projected[b][2].total_cmp(&projected[a][2]));
+
+    let mut content = String::new();
+    for i in order {
+        let [x, y, _] = projected[i];
+        // The viewer looks along −x, so the surface normal's x is the facing
+        let facing = elements[i].angle.cos();
+        let (light, opacity) = if facing >= 0.0 { (30.0 + 35.0 * facing, 0.95) } else { (30.0, 0.2) };
+        content.push_str(&format!(
+            r##"<ellipse cx="{:.1}" cy="{:.1}" rx="{:.1}" ry="{:.1}" fill="{}" opacity="{opacity}"/>
+"##,
+            c + x * scale,
+            c + y * scale,
+            dot * (0.3 + 0.7 * facing.abs()),
+            dot,
+            family_color(i, family, light, theme)
+        ));
+    }
+    crate::render::svg_document_with_theme(size as u32, size as u32, &content, theme)
+}
+
+/// The Voronoi cell of every element, clipped to the disc of the head —
+/// the region of the receptacle each floret occupies. Cells are in element
+/// coordinates and input order; duplicate elements get an empty cell.
+pub fn voronoi_cells(elements: &[Element]) -> Vec<Vec<Point>> {
+    let rim = elements.iter().map(|e| e.radius).fold(0.0, f64::max);
+    // Half a floret spacing beyond the outermost centers
+    let disc = rim * (1.0 + 0.5 / (elements.len().max(2) as f64).sqrt()) + 1e-9;
+    let shift = disc + 1.0;
+    let points: Vec<Point> = elements.iter().map(|e| Point { x: e.x + shift, y: e.y + shift }).collect();
+    let window: Vec<Point> = (0..96)
+        .map(|i| {
+            let a = 2.0 * PI * i as f64 / 96.0;
+            Point { x: shift + disc * a.cos(), y: shift + disc * a.sin() }
+        })
+        .collect();
+    voronoi(&points, 2.0 * shift, 2.0 * shift)
+        .into_iter()
+        .map(|cell| clip_convex(&cell, &window).into_iter().map(|p| Point { x: p.x - shift, y: p.y - shift }).collect())
+        .collect()
+}
+
+/// Render a seed head as tessellated cells, one polygon per floret. Each
+/// spiral arm of the outermost counter-clockwise parastichy family has its
+/// own hue, and neighboring arms alternate light and dark.
+pub fn voronoi_to_svg(elements: &[Element]) -> String {
+    voronoi_to_svg_with_theme(elements, &Theme::dark())
+}
+
+/// [`voronoi_to_svg`] in `theme`; with a palette, the arms take it in order
+/// around the head.
+pub fn voronoi_to_svg_with_theme(elements: &[Element], theme: &Theme) -> String {
+    let cells = voronoi_cells(elements);
+    let extent = cells.iter().flatten().map(|p| p.x.abs().max(p.y.abs())).fold(1e-9, f64::max);
+    let size = (2.0 * extent + 40.0).max(200.0);
+    let c = size / 2.0;
+    let family = count_parastichies(elements).last().map_or(1, |&(_, ccw)| ccw);
+    // Arms in order around the head, so neighbors can alternate in shade
+    let mut arms: Vec<usize> = (0..family).collect();
+    arms.sort_by(|&a, &b| family_hue(a, family).total_cmp(&family_hue(b, family)));
+    let mut rank = vec![0; family];
+    for (k, &arm) in arms.iter().enumerate() {
+        rank[arm] = k;
+    }
+    let mut content = String::new();
+    let edge = theme.background_css();
+    for (e, cell) in elements.iter().zip(&cells).filter(|(_, cell)| cell.len() >= 3) {
+        let points: Vec<String> = cell.iter().map(|p| format!("{:.1},{:.1}", c + p.x, c + p.y)).collect();
+        let light = if rank[e.index % family].is_multiple_of(2) { 55.0 } else { 32.0 };
+        content.push_str(&format!(
+            r##"<polygon points="{}" fill="{}" stroke="{edge}" stroke-width="{}" stroke-linejoin="round"/>
+"##,
+            points.join(" "),
+            theme.color(family_hue(e.index, family) / 360.0, crate::render::hsl(family_hue(e.index, family), 65.0, light)),
+            theme.stroke_width(0.8)
+        ));
+    }
+    crate::render::svg_document_with_theme(size as u32, size as u32, &content, theme)
+}
+
 /// Count visible spirals (parastichies) in a pattern.
 ///
 /// In a sunflower, you can count spirals going clockwise and counter-clockwise.
-/// These counts are always consecutive Fibonacci numbers (e.g., 21 and 34).
+/// For the golden angle these counts are consecutive Fibonacci numbers
+/// (e.g., 21 and 34); other divergence angles give other sequences, such as
+/// the Lucas numbers 4, 7, 11, 18 for α ≈ 99.5°.
+///
+/// Each element is joined to its nearest earlier neighbor on either side;
+/// the index difference d of such a contact says it lies on one of d
+/// parallel spirals. The most common (clockwise, counter-clockwise) pair is
+/// taken in rings of doubling element counts, from the center outward, and
+/// each change of pair is reported once — the parastichy numbers rise as the
+/// head grows. Directions are in the elements' own coordinates, angles
+/// increasing counter-clockwise; elements must be centered on the origin.
 pub fn count_parastichies(elements: &[Element]) -> Vec<(usize, usize)> {
+    const MIN_ZONE: usize = 8;
     if elements.len() < 10 {
         return vec![];
     }
-    // The parastichy numbers are the Fibonacci numbers closest to
-    // the number of elements that evenly divide the angular range.
-    let mut result = Vec::new();
-    for window in FIBONACCI.windows(2) {
-        let (a, b) = (window[0] as usize, window[1] as usize);
-        if a > 0 && b > 0 && b < elements.len() {
-            result.push((a, b));
+    let contacts = nearest_contacts(elements);
+    let mut by_radius: Vec<usize> = (0..elements.len()).collect();
+    by_radius.sort_by(|&a, &b| elements[a].x.hypot(elements[a].y).total_cmp(&elements[b].x.hypot(elements[b].y)));
+
+    // Ring boundaries at n/2, n/4, ... listed inside out
+    let mut bounds = vec![elements.len()];
+    while let Some(&last) = bounds.last().filter(|&&b| b / 2 >= MIN_ZONE) {
+        bounds.push(last / 2);
+    }
+    bounds.reverse();
+    let mut result: Vec<(usize, usize)> = Vec::new();
+    for ring in bounds.windows(2) {
+        let mut tally: Map<(usize, usize), usize> = Map::new();
+        for &k in &by_radius[ring[0]..ring[1]] {
+            if let Some(pair) = contacts[k] {
+                *tally.entry(pair).or_default() += 1;
+            }
+        }
+        let mode = tally.into_iter().max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)));
+        if let Some((pair, _)) = mode {
+            if result.last() != Some(&pair) {
+                result.push(pair);
+            }
         }
     }
     result
 }
 
+/// For every element, the index differences to its nearest earlier
+/// neighbor turning clockwise and counter-clockwise, if both exist.
+fn nearest_contacts(elements: &[Element]) -> Vec<Option<(usize, usize)>> {
+    const CANDIDATES: usize = 6;
+    let (mut lo, mut hi) = (Point { x: f64::INFINITY, y: f64::INFINITY }, Point { x: f64::NEG_INFINITY, y: f64::NEG_INFINITY });
+    for e in elements {
+        (lo.x, lo.y, hi.x, hi.y) = (lo.x.min(e.x), lo.y.min(e.y), hi.x.max(e.x), hi.y.max(e.y));
+    }
+    // Buckets of about one element each on a square layout. Sized from the
+    // longer side, not the area, so a collinear layout gets a row of them
+    // instead of billions
+    let n = elements.len();
+    let cell = ((hi.x - lo.x).max(hi.y - lo.y) / (n as f64).sqrt()).max(1e-9);
+    let cols = (((hi.x - lo.x) / cell) as usize + 1).min(n);
+    let rows = (((hi.y - lo.y) / cell) as usize + 1).min(n);
+    let bucket = |e: &Element| ((((e.x - lo.x) / cell) as usize).min(cols - 1), (((e.y - lo.y) / cell) as usize).min(rows - 1));
+    let mut grid = vec![Vec::new(); cols * rows];
+    for (k, e) in elements.iter().enumerate() {
+        let (cx, cy) = bucket(e);
+        grid[cy * cols + cx].push(k);
+    }
+
+    elements
+        .iter()
+        .map(|e| {
+            let (cx, cy) = bucket(e);
+            let mut near: Vec<(f64, usize)> = Vec::new();
+            // Widen the search ring until the candidates are certainly the nearest
+            for ring in 0..cols.max(rows) {
+                for y in cy.saturating_sub(ring)..=(cy + ring).min(rows - 1) {
+                    for x in cx.saturating_sub(ring)..=(cx + ring).min(cols - 1) {
+                        if x.abs_diff(cx) != ring && y.abs_diff(cy) != ring {
+                            continue;
+                        }
+                        for &k in &grid[y * cols + x] {
+                            let other = &elements[k];
+                            if other.index < e.index {
+                                near.push(((other.x - e.x).hypot(other.y - e.y), k));
+                            }
+                        }
+                    }
+                }
+                near.sort_by(|a, b| a.0.total_cmp(&b.0));
+                near.truncate(CANDIDATES);
+                if near.len() == CANDIDATES && near[CANDIDATES - 1].0 <= ring as f64 * cell {
+                    break;
+                }
+            }
+            let turn = |k: usize| {
+                let d = e.y.atan2(e.x) - elements[k].y.atan2(elements[k].x);
+                (d + PI).rem_euclid(2.0 * PI) - PI
+            };
+            let step = |k: usize| e.index - elements[k].index;
+            let clockwise = near.iter().find(|&&(_, k)| turn(k) < 0.0).map(|&(_, k)| step(k));
+            let counter = near.iter().find(|&&(_, k)| turn(k) > 0.0).map(|&(_, k)| step(k));
+            clockwise.zip(counter)
+        })
+        .collect()
+}
+
 /// Measure packing efficiency compared to golden angle.
 ///
 /// Returns a value between 0.0 and 1.0, where 1.0 is perfectly uniform.
@@ -130,52 +649,206 @@ pub fn packing_efficiency(elements: &[Element]) -> f64 {
     if elements.len() < 3 {
         return 0.0;
     }
-    // Compute average nearest-neighbor distance
-    let mut total_min_dist = 0.0;
-    let mut count = 0;
-    for (i, e1) in elements.iter().enumerate().skip(1) {
-        let mut min_dist = f64::INFINITY;
-        for (j, e2) in elements.iter().enumerate().skip(1) {
-            if i != j {
-                let d = ((e1.x - e2.x).powi(2) + (e1.y - e2.y).powi(2)).sqrt();
-                if d < min_dist {
-                    min_dist = d;
-                }
-            }
-        }
-        if min_dist.is_finite() {
-            total_min_dist += min_dist;
-            count += 1;
-        }
-    }
+    // Nearest-neighbor distance of every element but the center one
+    let nearest: Vec<f64> = map_range(elements.len() - 1, |k| {
+        let e1 = &elements[k + 1];
+        elements
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|&(j, _)| j != k + 1)
+            .map(|(_, e2)| ((e1.x - e2.x).powi(2) + (e1.y - e2.y).powi(2)).sqrt())
+            .fold(f64::INFINITY, f64::min)
+    });
+    let finite: Vec<f64> = nearest.into_iter().filter(|d| d.is_finite()).collect();
+    let count = finite.len();
     if count == 0 {
         return 0.0;
     }
-    let avg = total_min_dist / count as f64;
+    let avg = finite.iter().sum::<f64>() / count as f64;
     // Compute standard deviation of nearest-neighbor distances
-    let mut variance = 0.0;
-    for (i, e1) in elements.iter().enumerate().skip(1) {
-        let mut min_dist = f64::INFINITY;
-        for (j, e2) in elements.iter().enumerate().skip(1) {
-            if i != j {
-                let d = ((e1.x - e2.x).powi(2) + (e1.y - e2.y).powi(2)).sqrt();
-                if d < min_dist {
-                    min_dist = d;
-                }
+    let variance: f64 = finite.iter().map(|d| (d - avg).powi(2)).sum();
+    let stddev = (variance / count as f64).sqrt();
+    // Coefficient of variation → invert for efficiency score
+    let cv = stddev / avg;
+    (1.0 - cv).clamp(0.0, 1.0)
+}
+
+/// Estimate the divergence angle in degrees from element centers, such as
+/// seed positions picked out of a photographed sunflower head.
+///
+/// Elements are numbered outward by distance from the one nearest their
+/// centroid, as in Vogel's model, and the angle α is the one that best explains their
+/// polar angles as θₙ = n·α + φ: the peak of |Σ exp(i(θₙ − nα))| over
+/// 90°–270°, folded into (0°, 180°] so either handedness gives the same
+/// answer. Returns `None` for fewer than three points.
+pub fn fit_divergence_angle(points: &[Point]) -> Option<f64> {
+    if points.len() < 3 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean = Point { x: points.iter().map(|p| p.x).sum::<f64>() / n, y: points.iter().map(|p| p.y).sum::<f64>() / n };
+    // The first element sits at the exact center; the centroid is only close,
+    // and near the rim even small offsets reorder neighboring radii
+    let dist = |p: &Point| (p.x - mean.x).hypot(p.y - mean.y);
+    let Point { x: cx, y: cy } = *points.iter().min_by(|a, b| dist(a).total_cmp(&dist(b)))?;
+    let mut polar: Vec<(f64, f64)> = points.iter().map(|p| ((p.x - cx).hypot(p.y - cy), (p.y - cy).atan2(p.x - cx))).collect();
+    polar.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let coherence = |deg: f64| {
+        let alpha = deg.to_radians();
+        let (s, c) = polar.iter().enumerate().fold((0.0, 0.0), |(s, c), (k, &(_, theta))| {
+            let (ps, pc) = (theta - k as f64 * alpha).sin_cos();
+            (s + ps, c + pc)
+        });
+        s.hypot(c)
+    };
+    // Coarse scan, then ternary search around the best sample
+    let step = 0.05;
+    let coarse = (0..=3600).map(|i| 90.0 + i as f64 * step).map(|a| (a, coherence(a)));
+    let (best, _) = coarse.fold((90.0, f64::NEG_INFINITY), |acc, (a, c)| if c > acc.1 { (a, c) } else { acc });
+    let (mut lo, mut hi) = (best - step, best + step);
+    for _ in 0..40 {
+        let (m1, m2) = (lo + (hi - lo) / 3.0, hi - (hi - lo) / 3.0);
+        if coherence(m1) < coherence(m2) {
+            lo = m1;
+        } else {
+            hi = m2;
+        }
+    }
+    let angle = (lo + hi) / 2.0;
+    Some(if angle > 180.0 { 360.0 - angle } else { angle })
+}
+
+impl Generator for Params {
+    /// Elements as discs a little under half the spacing across, so
+    /// neighbors just miss touching.
+    fn generate(&self) -> Geometry {
+        let radius = 0.4 * self.scale;
+        Geometry::Circles(vogel_spiral(self).iter().map(|e| Circle { center: Point { x: e.x, y: e.y }, radius }).collect())
+    }
+}
+
+/// How each element is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FloretShape {
+    Circle,
+    /// Elongated along the radius, like a sunflower seed
+    Ellipse,
+    /// A teardrop with its tip pointing outward, like a succulent leaf
+    Petal,
+    /// A square with a corner pointing outward, like a pinecone scale
+    Square,
+}
+
+impl FloretShape {
+    pub const NAMES: [&'static str; 4] = ["circle", "ellipse", "petal", "square"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "circle" => Some(FloretShape::Circle),
+            "ellipse" => Some(FloretShape::Ellipse),
+            "petal" => Some(FloretShape::Petal),
+            "square" => Some(FloretShape::Square),
+            _ => None,
+        }
+    }
+
+    /// SVG for one floret of size `r` at (x, y), its outward direction
+    /// `angle` radians clockwise from +x in screen coordinates.
+    fn svg(self, x: f64, y: f64, r: f64, angle: f64, fill: &str) -> String {
+        let turned = |extra: f64| {
+            format!(r#"transform="translate({:.1} {:.1}) rotate({:.1})""#, x, y, (angle.to_degrees() + extra).rem_euclid(360.0))
+        };
+        let turn = turned(0.0);
+        match self {
+            FloretShape::Circle => format!(
+                r##"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{fill}" opacity="0.9"/>
+"##,
+                x, y, r
+            ),
+            FloretShape::Ellipse => format!(
+                r##"<ellipse rx="{:.1}" ry="{:.1}" {turn} fill="{fill}" opacity="0.9"/>
+"##,
+                1.4 * r,
+                0.7 * r
+            ),
+            FloretShape::Petal => format!(
+                r##"<path d="M{:.1},0C{:.1},{:.1} {:.1},{:.1} {:.1},0C{:.1},{:.1} {:.1},{:.1} {:.1},0Z" {turn} fill="{fill}" opacity="0.9"/>
+"##,
+                -r,
+                -r,
+                -0.9 * r,
+                0.4 * r,
+                -0.7 * r,
+                1.6 * r,
+                0.4 * r,
+                0.7 * r,
+                -r,
+                0.9 * r,
+                -r
+            ),
+            FloretShape::Square => {
+                let side = r * core::f64::consts::SQRT_2;
+                format!(
+                    r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" {} fill="{fill}" opacity="0.9"/>
+"##,
+                    -side / 2.0,
+                    -side / 2.0,
+                    side,
+                    side,
+                    turned(45.0)
+                )
             }
         }
-        if min_dist.is_finite() {
-            variance += (min_dist - avg).powi(2);
+    }
+}
+
+impl Pattern {
+    /// The floret shape that suits the plant: seeds, leaves, or scales.
+    pub fn floret(self) -> FloretShape {
+        match self {
+            Pattern::Sunflower => FloretShape::Ellipse,
+            Pattern::Rosette => FloretShape::Petal,
+            Pattern::Pinecone => FloretShape::Square,
         }
     }
-    let stddev = (variance / count as f64).sqrt();
-    // Coefficient of variation → invert for efficiency score
-    let cv = stddev / avg;
-    (1.0 - cv).max(0.0).min(1.0)
 }
 
 /// Generate SVG of a phyllotaxis pattern.
 pub fn to_svg(elements: &[Element], pattern: Pattern) -> String {
+    to_svg_with_shape(elements, pattern, FloretShape::Circle)
+}
+
+/// Generate SVG of a phyllotaxis pattern with every element drawn as `shape`,
+/// oriented along its radius.
+pub fn to_svg_with_shape(elements: &[Element], pattern: Pattern, shape: FloretShape) -> String {
+    to_svg_with_theme(elements, pattern, shape, &Theme::dark())
+}
+
+/// [`to_svg_with_shape`] in `theme`, colored along its palette as by
+/// [`to_svg_with_colormap`] if it has one.
+pub fn to_svg_with_theme(elements: &[Element], pattern: Pattern, shape: FloretShape, theme: &Theme) -> String {
+    if let Some(palette) = &theme.palette {
+        return draw(elements, pattern, shape, |_, t| palette.css(t), theme);
+    }
+    let fill = |e: &Element, t: f64| {
+        // Color based on spiral arm (using golden angle)
+        let hue = (e.angle * 180.0 / PI * 0.3) % 360.0;
+        let sat = 70.0 + t * 20.0;
+        let light = 45.0 + t * 15.0;
+        format!("hsl({:.0},{:.0}%,{:.0}%)", hue, sat, light)
+    };
+    draw(elements, pattern, shape, fill, theme)
+}
+
+/// [`to_svg_with_shape`] colored along `colormap` from the first element,
+/// at the center, to the last at the rim.
+pub fn to_svg_with_colormap(elements: &[Element], pattern: Pattern, shape: FloretShape, colormap: &dyn Colormap) -> String {
+    draw(elements, pattern, shape, |_, t| colormap.css(t), &Theme::dark())
+}
+
+fn draw(elements: &[Element], pattern: Pattern, shape: FloretShape, fill: impl Fn(&Element, f64) -> String, theme: &Theme) -> String {
     if elements.is_empty() {
         return String::from(r##"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="800"></svg>"##);
     }
@@ -187,8 +860,10 @@ pub fn to_svg(elements: &[Element], pattern: Pattern) -> String {
 
     let mut svg = format!(
         r##"<svg xmlns="http://www.w3.org/2000/svg" width="{s}" height="{s}" viewBox="0 0 {s} {s}">
-<rect width="{s}" height="{s}" fill="#1a1a2e"/>
-"##,
+<rect width="{s}" height="{s}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start(),
         s = size as u32
     );
 
@@ -201,17 +876,10 @@ pub fn to_svg(elements: &[Element], pattern: Pattern) -> String {
             Pattern::Rosette => 3.0 + t * 10.0,
             Pattern::Pinecone => 2.0 + t * 3.0,
         };
-        // Color based on spiral arm (using golden angle)
-        let hue = (e.angle * 180.0 / PI * 0.3) % 360.0;
-        let sat = 70.0 + t * 20.0;
-        let light = 45.0 + t * 15.0;
-        svg.push_str(&format!(
-            r##"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="hsl({:.0},{:.0}%,{:.0}%)" opacity="0.9"/>
-"##,
-            x, y, base_r, hue, sat, light
-        ));
+        svg.push_str(&shape.svg(x, y, base_r, e.y.atan2(e.x), &fill(e, t)));
     }
 
+    svg.push_str(theme.foreground_end());
     svg.push_str("</svg>");
     svg
 }
@@ -284,6 +952,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parastichies_grow_outward() {
+        let elements = vogel_spiral(&Params { count: 1000, ..Default::default() });
+        let pairs = count_parastichies(&elements);
+        assert!(pairs.len() >= 3, "{:?}", pairs);
+        let (a, b) = *pairs.last().unwrap();
+        let fib = |n: usize| crate::constants::FIBONACCI.iter().position(|&f| f == n as u64 && n > 1);
+        let (ia, ib) = (fib(a).unwrap(), fib(b).unwrap());
+        assert_eq!(ia.abs_diff(ib), 1, "{:?}", pairs);
+        assert!(a.max(b) >= 34, "{:?}", pairs);
+        assert!(pairs.windows(2).all(|w| w[0].0.max(w[0].1) <= w[1].0.max(w[1].1)));
+    }
+
+    #[test]
+    fn test_parastichies_non_fibonacci() {
+        // The Lucas angle 360° / (1 + φ²) ≈ 99.5° gives Lucas-number spirals
+        let lucas = 360.0 / (1.0 + crate::constants::PHI * crate::constants::PHI);
+        let elements = vogel_spiral(&Params { count: 1000, divergence_angle: lucas, scale: 8.0 });
+        let pairs = count_parastichies(&elements);
+        let (a, b) = *pairs.last().unwrap();
+        let lucas_numbers = [4, 7, 11, 18, 29, 47, 76, 123];
+        assert!(lucas_numbers.contains(&a) && lucas_numbers.contains(&b), "{:?}", pairs);
+        // The mirrored spiral swaps the two directions
+        let mirrored = vogel_spiral(&Params { count: 1000, divergence_angle: 360.0 - lucas, scale: 8.0 });
+        assert_eq!(*count_parastichies(&mirrored).last().unwrap(), (b, a));
+    }
+
+    #[test]
+    fn test_parastichies_collinear() {
+        // Every element on one or two rays, so the bounding box has no area;
+        // this used to allocate a bucket grid of astronomical size
+        for angle in [0.0, 180.0, 360.0] {
+            let elements = vogel_spiral(&Params { count: 5000, divergence_angle: angle, scale: 8.0 });
+            let pairs = count_parastichies(&elements);
+            assert!(pairs.len() < 10, "{angle}: {pairs:?}");
+        }
+    }
+
     #[test]
     fn test_packing_efficiency_golden_angle() {
         let p = Params { count: 30, ..Default::default() };
@@ -307,4 +1013,207 @@ mod tests {
         let svg = to_svg(&[], Pattern::Sunflower);
         assert!(svg.contains("<svg"));
     }
+
+    #[test]
+    fn test_validate() {
+        assert!(Params::default().validate().is_ok());
+        assert!(Params { count: 0, ..Default::default() }.validate().is_err());
+        assert!(Params { scale: -1.0, ..Default::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_vogel_iter_is_lazy() {
+        let params = Params { count: 10_000_000, ..Default::default() };
+        let first: Vec<Element> = vogel_spiral_iter(&params).take(100).collect();
+        assert_eq!(first, vogel_spiral(&Params { count: 100, ..params.clone() }));
+        // Early exit: r = 8√n first exceeds 800 at n = 10001
+        let e = vogel_spiral_iter(&params).find(|e| e.radius > 800.0).unwrap();
+        assert_eq!(e.index, 10_001);
+    }
+
+    #[test]
+    fn test_vogel_iter_exact_size_and_reverse() {
+        let params = Params { count: 200, ..Default::default() };
+        let mut stream: VogelIter = vogel_spiral_iter(&params);
+        assert_eq!(stream.len(), 200);
+        stream.next();
+        assert_eq!(stream.len(), 199);
+        let rim_first: Vec<Element> = vogel_spiral_iter(&params).rev().collect();
+        let mut expected = vogel_spiral(&params);
+        expected.reverse();
+        assert_eq!(rim_first, expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let params = Params { count: 321, scale: 2.5, ..Default::default() };
+        let json = serde_json::to_string(&params).unwrap();
+        let back: Params = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.count, 321);
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+    }
+
+    #[test]
+    fn test_fit_divergence_angle() {
+        let centers = |angle: f64| -> Vec<Point> {
+            let params = Params { count: 400, divergence_angle: angle, scale: 5.0 };
+            vogel_spiral(&params).iter().map(|e| Point { x: e.x + 300.0, y: e.y + 200.0 }).collect()
+        };
+        let golden = fit_divergence_angle(&centers(GOLDEN_ANGLE_DEG)).unwrap();
+        assert!((golden - GOLDEN_ANGLE_DEG).abs() < 0.01, "fitted {}", golden);
+        // Mirrored spiral, shuffled order, and a little jitter as from a photo
+        let mut rng = super::super::fractals::SimpleRng::new(3);
+        let mut points: Vec<Point> = centers(360.0 - 99.5)
+            .into_iter()
+            .map(|p| Point { x: p.x + 0.2 * (rng.next_f64() - 0.5), y: p.y + 0.2 * (rng.next_f64() - 0.5) })
+            .collect();
+        points.reverse();
+        let fitted = fit_divergence_angle(&points).unwrap();
+        assert!((fitted - 99.5).abs() < 0.05, "fitted {}", fitted);
+        assert_eq!(fit_divergence_angle(&points[..2]), None);
+    }
+
+    #[test]
+    fn test_fibonacci_sphere_even() {
+        let n = 1000;
+        let points = fibonacci_sphere(n);
+        assert_eq!(points.len(), n);
+        assert!(points.iter().all(|p| (p.x * p.x + p.y * p.y + p.z * p.z - 1.0).abs() < 1e-12));
+        // Equal-area bands: each hemisphere holds half the points
+        assert_eq!(points.iter().filter(|p| p.z > 0.0).count(), n / 2);
+        // No clumping: every point keeps a healthy share of the mean spacing
+        let spacing = (4.0 * PI / n as f64).sqrt();
+        for (i, a) in points.iter().enumerate() {
+            let nearest = points
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, b)| (a.x - b.x).hypot(a.y - b.y).hypot(a.z - b.z))
+                .fold(f64::INFINITY, f64::min);
+            assert!(nearest > 0.6 * spacing, "point {} is {} from its neighbor", i, nearest);
+        }
+    }
+
+    #[test]
+    fn test_sphere_parastichy_neighbors() {
+        let n = 1000;
+        assert_eq!(sphere_parastichy(n), 55);
+        // Near the equator, i and i + 55 are close neighbors
+        let points = fibonacci_sphere(n);
+        let (a, b) = (points[500], points[555]);
+        let d = (a.x - b.x).hypot(a.y - b.y).hypot(a.z - b.z);
+        assert!(d < 2.0 * (4.0 * PI / n as f64).sqrt(), "{}", d);
+    }
+
+    #[test]
+    fn test_sphere_svg() {
+        let points = fibonacci_sphere(200);
+        let svg = sphere_to_svg(&points, Projection::Orthographic { azimuth: 30.0, elevation: 20.0 });
+        assert_eq!(svg.matches("<circle").count(), 201);
+        assert!(svg.contains(r#"opacity="0.25""#) && svg.contains(r#"opacity="0.95""#));
+    }
+
+    #[test]
+    fn test_cylinder_lattice() {
+        let params = CylinderParams { count: 100, ..Default::default() };
+        assert!(params.validate().is_ok());
+        let elements = cylinder(&params);
+        assert_eq!(elements.len(), 100);
+        assert!(elements.iter().all(|e| (0.0..params.circumference).contains(&e.x)));
+        assert_eq!(elements[10].y, 10.0);
+        assert!(CylinderParams { rise: 0.0, ..Default::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_cylinder_parastichies() {
+        // Girth 40 and rise 1: nearest neighbors are 3 and 5 elements away
+        let params = CylinderParams { count: 200, ..Default::default() };
+        assert_eq!(cylinder_parastichies(&cylinder(&params), params.circumference), Some((3, 5)));
+        // A thicker stem packs more spirals around it
+        let thick = CylinderParams { count: 400, circumference: 200.0, rise: 0.5, ..Default::default() };
+        let (a, b) = cylinder_parastichies(&cylinder(&thick), thick.circumference).unwrap();
+        assert!(crate::constants::FIBONACCI.contains(&(a as u64)) && crate::constants::FIBONACCI.contains(&(b as u64)));
+        assert!(b > 5, "{} {}", a, b);
+        // Distichous leaves, as on grasses: alternate sides, one rank each
+        let grass = CylinderParams { count: 20, divergence_angle: 180.0, rise: 10.0, ..Default::default() };
+        assert_eq!(cylinder_parastichies(&cylinder(&grass), grass.circumference).map(|p| p.0), Some(1));
+    }
+
+    #[test]
+    fn test_cylinder_svgs() {
+        let params = CylinderParams { count: 60, ..Default::default() };
+        let elements = cylinder(&params);
+        assert_eq!(cylinder_to_svg(&elements, &params).matches("<circle").count(), 60);
+        let solid = cylinder_3d_svg(&elements, &params);
+        assert_eq!(solid.matches("<ellipse").count(), 60);
+        assert!(solid.contains(r#"opacity="0.2""#));
+    }
+
+    #[test]
+    fn test_lift_surfaces() {
+        let elements = vogel_spiral(&Params { count: 50, ..Default::default() });
+        let rim = elements[49].radius;
+        let dome = lift(&elements, Surface::Paraboloid { height: 0.3 });
+        assert!((dome[0].z - 0.3 * rim).abs() < 1e-9);
+        assert!(dome[49].z.abs() < 1e-9);
+        assert_eq!((dome[7].x, dome[7].y), (elements[7].x, elements[7].y));
+        // Halfway out, the cone has dropped halfway and the paraboloid a quarter
+        let cone = lift(&elements, Surface::Cone { height: 0.3 });
+        let half = elements.iter().position(|e| e.radius >= rim / 2.0).unwrap();
+        let t = elements[half].radius / rim;
+        assert!((cone[half].z - 0.3 * rim * (1.0 - t)).abs() < 1e-9);
+        assert!(dome[half].z > cone[half].z);
+    }
+
+    #[test]
+    fn test_seed_head_mesh() {
+        let elements = vogel_spiral(&Params { count: 60, ..Default::default() });
+        let mesh = to_mesh(&elements, Surface::Paraboloid { height: 0.3 });
+        assert!(mesh.boundary_edges().is_empty());
+        // Shell: two copies of the surface; plus one 12-slice dome per floret
+        let dome_vertices = 2 + 12 * 3;
+        assert_eq!(mesh.vertices.len(), 2 * 60 + 60 * dome_vertices);
+        // The top of the shell faces up
+        assert!(mesh.normal(0).z > 0.0);
+    }
+
+    #[test]
+    fn test_voronoi_cells_tile_the_disc() {
+        let elements = vogel_spiral(&Params { count: 300, ..Default::default() });
+        let cells = voronoi_cells(&elements);
+        assert_eq!(cells.len(), 300);
+        let area = |cell: &[Point]| {
+            (0..cell.len())
+                .map(|i| cell[i].x * cell[(i + 1) % cell.len()].y - cell[(i + 1) % cell.len()].x * cell[i].y)
+                .sum::<f64>()
+                .abs()
+                / 2.0
+        };
+        // The cells cover the clipping disc, give or take the 96-gon's corners
+        let rim = elements[299].radius * (1.0 + 0.5 / 300f64.sqrt());
+        let total: f64 = cells.iter().map(|c| area(c)).sum();
+        assert!((total / (PI * rim * rim) - 1.0).abs() < 0.01, "{}", total / (PI * rim * rim));
+        // Golden-angle florets get near-equal shares: Vogel's π c² each
+        let inner: Vec<f64> = cells[20..200].iter().map(|c| area(c) / (PI * 64.0)).collect();
+        assert!(inner.iter().all(|&a| (a - 1.0).abs() < 0.05), "{:?}", inner);
+        assert!(voronoi_to_svg(&elements).matches("<polygon").count() == 300);
+    }
+
+    #[test]
+    fn test_floret_shapes() {
+        let elements = vogel_spiral(&Params { count: 12, ..Default::default() });
+        assert_eq!(to_svg(&elements, Pattern::Sunflower), to_svg_with_shape(&elements, Pattern::Sunflower, FloretShape::Circle));
+        let seeds = to_svg_with_shape(&elements, Pattern::Sunflower, Pattern::Sunflower.floret());
+        assert_eq!(seeds.matches("<ellipse").count(), 12);
+        let leaves = to_svg_with_shape(&elements, Pattern::Rosette, FloretShape::Petal);
+        assert_eq!(leaves.matches("<path").count(), 12);
+        // Element 1 sits at the golden angle; its petal points straight out
+        assert!(leaves.contains(&format!("rotate({:.1})", GOLDEN_ANGLE_DEG)), "{}", leaves);
+        let scales = to_svg_with_shape(&elements, Pattern::Pinecone, FloretShape::Square);
+        assert!(scales.contains(&format!("rotate({:.1})", GOLDEN_ANGLE_DEG + 45.0)));
+        for name in FloretShape::NAMES {
+            assert!(FloretShape::from_name(name).is_some());
+        }
+    }
 }
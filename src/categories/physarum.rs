@@ -0,0 +1,303 @@
+//! Physarum — a brainless slime mold that builds efficient transport networks.
+//!
+//! Jeff Jones' agent model (2010): thousands of particles each sense a shared
+//! chemical trail with three forward sensors, turn toward the strongest
+//! signal, step forward, and deposit more trail. The trail diffuses and
+//! decays. Positive feedback condenses the swarm into glowing filaments much
+//! like *Physarum polycephalum* reconnecting food sources.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::colormap::Colormap;
+use crate::render::theme::Theme;
+use super::fractals::SimpleRng;
+
+/// Parameters for the Physarum agent model.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhysarumParams {
+    /// Number of agents
+    pub agents: usize,
+    /// Angle between the front sensor and the side sensors (radians)
+    pub sensor_angle: f64,
+    /// How far ahead the sensors sample the trail (cells)
+    pub sensor_distance: f64,
+    /// How far an agent turns per step (radians)
+    pub rotation_angle: f64,
+    /// Distance moved per step (cells)
+    pub step_size: f64,
+    /// Trail deposited per agent per step
+    pub deposit: f64,
+    /// Fraction of trail remaining after each step
+    pub decay: f64,
+}
+
+impl Default for PhysarumParams {
+    fn default() -> Self {
+        Self {
+            agents: 20_000,
+            sensor_angle: PI / 4.0,
+            sensor_distance: 9.0,
+            rotation_angle: PI / 4.0,
+            step_size: 1.0,
+            deposit: 5.0,
+            decay: 0.9,
+        }
+    }
+}
+
+/// Largest agent population [`PhysarumParams::validate`] accepts.
+pub const MAX_AGENTS: usize = 1_000_000;
+
+impl PhysarumParams {
+    /// Reject empty or oversized populations, non-positive sensor and step
+    /// distances, and a decay outside 0–1.
+    pub fn validate(&self) -> Result<()> {
+        check_count("agents", self.agents, MAX_AGENTS)?;
+        check_positive("sensor_distance", self.sensor_distance)?;
+        check_positive("step_size", self.step_size)?;
+        check_range("decay", self.decay, 0.0..=1.0, "0 to 1")
+    }
+}
+
+/// A single particle of the slime mold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Agent {
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+}
+
+/// The trail map and the agents moving over it (periodic boundaries).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Physarum {
+    pub width: usize,
+    pub height: usize,
+    pub trail: Vec<f64>,
+    pub agents: Vec<Agent>,
+    rng: SimpleRng,
+}
+
+impl Physarum {
+    /// Scatter agents in a disc at the center with random headings.
+    pub fn new(width: usize, height: usize, params: &PhysarumParams, seed: u64) -> Self {
+        let mut rng = SimpleRng::new(seed);
+        let radius = width.min(height) as f64 * 0.35;
+        let agents = (0..params.agents)
+            .map(|_| {
+                let r = radius * rng.next_f64().sqrt();
+                let a = rng.next_f64() * 2.0 * PI;
+                Agent {
+                    x: width as f64 / 2.0 + r * a.cos(),
+                    y: height as f64 / 2.0 + r * a.sin(),
+                    heading: rng.next_f64() * 2.0 * PI,
+                }
+            })
+            .collect();
+        Physarum { width, height, trail: vec![0.0; width * height], agents, rng }
+    }
+
+    fn index(&self, x: f64, y: f64) -> usize {
+        let xi = (x.floor() as isize).rem_euclid(self.width as isize) as usize;
+        let yi = (y.floor() as isize).rem_euclid(self.height as isize) as usize;
+        yi * self.width + xi
+    }
+
+    /// Trail value at a (wrapped) position.
+    pub fn sense(&self, x: f64, y: f64) -> f64 {
+        self.trail[self.index(x, y)]
+    }
+
+    /// Advance agents and the trail map by one step.
+    pub fn step(&mut self, params: &PhysarumParams) {
+        let (w, h) = (self.width as f64, self.height as f64);
+        for i in 0..self.agents.len() {
+            let a = self.agents[i];
+            let probe = |angle: f64| {
+                self.sense(a.x + params.sensor_distance * angle.cos(), a.y + params.sensor_distance * angle.sin())
+            };
+            let front = probe(a.heading);
+            let left = probe(a.heading - params.sensor_angle);
+            let right = probe(a.heading + params.sensor_angle);
+
+            let mut heading = a.heading;
+            if front >= left && front >= right {
+                // Keep going straight
+            } else if front < left && front < right {
+                // Both sides stronger: pick one at random
+                heading += if self.rng.next_f64() < 0.5 { -1.0 } else { 1.0 } * params.rotation_angle;
+            } else if left > right {
+                heading -= params.rotation_angle;
+            } else {
+                heading += params.rotation_angle;
+            }
+
+            let x = (a.x + params.step_size * heading.cos()).rem_euclid(w);
+            let y = (a.y + params.step_size * heading.sin()).rem_euclid(h);
+            self.agents[i] = Agent { x, y, heading };
+            let idx = self.index(x, y);
+            self.trail[idx] += params.deposit;
+        }
+        self.diffuse(params.decay);
+    }
+
+    /// 3×3 mean filter followed by multiplicative decay.
+    fn diffuse(&mut self, decay: f64) {
+        let (w, h) = (self.width as isize, self.height as isize);
+        let mut next = vec![0.0; self.trail.len()];
+        for y in 0..h {
+            for x in 0..w {
+                let mut sum = 0.0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let nx = (x + dx).rem_euclid(w);
+                        let ny = (y + dy).rem_euclid(h);
+                        sum += self.trail[(ny * w + nx) as usize];
+                    }
+                }
+                next[(y * w + x) as usize] = sum / 9.0 * decay;
+            }
+        }
+        self.trail = next;
+    }
+
+    /// Run the simulation for n steps.
+    pub fn simulate(&mut self, params: &PhysarumParams, steps: usize) {
+        for _ in 0..steps {
+            self.step(params);
+        }
+    }
+
+    /// Fraction of cells whose trail exceeds `threshold` × the mean — low values
+    /// mean the swarm has condensed into thin filaments.
+    pub fn network_coverage(&self, threshold: f64) -> f64 {
+        let mean = self.trail.iter().sum::<f64>() / self.trail.len().max(1) as f64;
+        if mean <= 0.0 {
+            return 0.0;
+        }
+        self.trail.iter().filter(|&&v| v > threshold * mean).count() as f64 / self.trail.len() as f64
+    }
+}
+
+impl Generator for Physarum {
+    fn generate(&self) -> Geometry {
+        Geometry::Grid { cols: self.width, rows: self.height, values: self.trail.clone() }
+    }
+}
+
+/// Render the trail map as a glowing filament network.
+pub fn to_svg(sim: &Physarum, cell: f64) -> String {
+    to_svg_with_theme(sim, cell, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`, trail strength running along its palette if it
+/// has one.
+pub fn to_svg_with_theme(sim: &Physarum, cell: f64, theme: &Theme) -> String {
+    match &theme.palette {
+        Some(palette) => render(sim, cell, |t| palette.css(t), theme),
+        None => render(sim, cell, |t| crate::render::hsl(45.0 + 15.0 * t, 100.0, 8.0 + 70.0 * t), theme),
+    }
+}
+
+/// [`to_svg`] with trail strength running along `colormap`.
+pub fn to_svg_with_colormap(sim: &Physarum, cell: f64, colormap: &dyn Colormap) -> String {
+    render(sim, cell, |t| colormap.css(t), &Theme::dark())
+}
+
+fn render(sim: &Physarum, cell: f64, color: impl Fn(f64) -> String, theme: &Theme) -> String {
+    let max = sim.trail.iter().cloned().fold(0.0, f64::max).max(1e-12);
+    // Square-root tone mapping brings out faint filaments next to bright veins
+    let values: Vec<f64> = sim.trail.iter().map(|v| (v / max).sqrt()).collect();
+    let background = theme.background_css();
+    let color = |t| if t < 0.05 { background.clone() } else { color(t) };
+    crate::render::raster_svg_with_theme(sim.width, sim.height, cell, &values, color, theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small() -> PhysarumParams {
+        PhysarumParams { agents: 2000, ..Default::default() }
+    }
+
+    #[test]
+    fn test_agents_start_in_disc() {
+        let sim = Physarum::new(100, 100, &small(), 42);
+        assert_eq!(sim.agents.len(), 2000);
+        for a in &sim.agents {
+            assert!((a.x - 50.0).hypot(a.y - 50.0) <= 35.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_agents_stay_in_bounds() {
+        let params = small();
+        let mut sim = Physarum::new(64, 48, &params, 1);
+        sim.simulate(&params, 50);
+        for a in &sim.agents {
+            assert!(a.x >= 0.0 && a.x < 64.0 && a.y >= 0.0 && a.y < 48.0);
+        }
+    }
+
+    #[test]
+    fn test_trail_deposited() {
+        let params = small();
+        let mut sim = Physarum::new(64, 64, &params, 2);
+        sim.step(&params);
+        assert!(sim.trail.iter().sum::<f64>() > 0.0);
+    }
+
+    #[test]
+    fn test_trail_decays_without_agents() {
+        let params = PhysarumParams { agents: 0, ..Default::default() };
+        let mut sim = Physarum::new(20, 20, &params, 3);
+        sim.trail[210] = 100.0;
+        sim.simulate(&params, 10);
+        let total: f64 = sim.trail.iter().sum();
+        assert!((total - 100.0 * params.decay.powi(10)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_network_condenses() {
+        let params = small();
+        let mut sim = Physarum::new(80, 80, &params, 4);
+        sim.simulate(&params, 5);
+        let early = sim.network_coverage(1.0);
+        sim.simulate(&params, 200);
+        let late = sim.network_coverage(1.0);
+        assert!(late < early, "Trail should condense into filaments: {} → {}", early, late);
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let params = small();
+        let mut a = Physarum::new(50, 50, &params, 9);
+        let mut b = Physarum::new(50, 50, &params, 9);
+        a.simulate(&params, 20);
+        b.simulate(&params, 20);
+        assert_eq!(a.agents, b.agents);
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let params = small();
+        let mut sim = Physarum::new(40, 40, &params, 5);
+        sim.simulate(&params, 10);
+        let svg = to_svg(&sim, 4.0);
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("<rect"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(PhysarumParams::default().validate().is_ok());
+        assert!(PhysarumParams { agents: 0, ..Default::default() }.validate().is_err());
+        assert!(PhysarumParams { decay: 1.5, ..Default::default() }.validate().is_err());
+    }
+}
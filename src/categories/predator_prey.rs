@@ -0,0 +1,345 @@
+//! Predator–prey cycles — Lotka–Volterra and Rosenzweig–MacArthur.
+//!
+//! The Hudson's Bay Company's lynx and hare pelts rise and fall in a ten-year
+//! rhythm, predators lagging prey. Lotka (1925) and Volterra (1926) modeled it:
+//!   dx/dt = αx − βxy        (prey)
+//!   dy/dt = δxy − γy        (predators)
+//! Every orbit is a closed loop around (γ/δ, α/β), nested like tree rings,
+//! because V = δx − γ ln x + βy − α ln y is conserved.
+//!
+//! Rosenzweig & MacArthur (1963) added logistic prey growth and predator
+//! satiation (Holling type II):
+//!   dx/dt = rx(1 − x/K) − axy / (1 + ahx)
+//!   dy/dt = eaxy / (1 + ahx) − my
+//! Now orbits spiral onto a single limit cycle — or, for a small carrying
+//! capacity K, into a steady state: enriching the prey destabilizes the system
+//! (the paradox of enrichment).
+
+use crate::prelude::*;
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::{self, Theme};
+use super::fractals::Point;
+
+/// A two-species predator–prey model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Model {
+    /// Classic Lotka–Volterra with neutral cycles
+    LotkaVolterra { alpha: f64, beta: f64, delta: f64, gamma: f64 },
+    /// Logistic prey and saturating predation
+    RosenzweigMacArthur { r: f64, k: f64, a: f64, h: f64, e: f64, m: f64 },
+}
+
+impl Default for Model {
+    fn default() -> Self {
+        Model::LotkaVolterra { alpha: 1.1, beta: 0.4, delta: 0.1, gamma: 0.4 }
+    }
+}
+
+impl Model {
+    /// Rosenzweig–MacArthur with unit rates; carrying capacities above 7/3 cycle.
+    pub fn rosenzweig_macarthur(k: f64) -> Self {
+        Model::RosenzweigMacArthur { r: 1.0, k, a: 1.0, h: 1.0, e: 1.0, m: 0.4 }
+    }
+
+    /// Population growth rates (dx/dt, dy/dt).
+    pub fn derivative(&self, prey: f64, predator: f64) -> (f64, f64) {
+        match *self {
+            Model::LotkaVolterra { alpha, beta, delta, gamma } => (
+                alpha * prey - beta * prey * predator,
+                delta * prey * predator - gamma * predator,
+            ),
+            Model::RosenzweigMacArthur { r, k, a, h, e, m } => {
+                let eaten = a * prey * predator / (1.0 + a * h * prey);
+                (r * prey * (1.0 - prey / k) - eaten, e * eaten - m * predator)
+            }
+        }
+    }
+
+    /// Coexistence equilibrium (x*, y*).
+    pub fn equilibrium(&self) -> (f64, f64) {
+        match *self {
+            Model::LotkaVolterra { alpha, beta, delta, gamma } => (gamma / delta, alpha / beta),
+            Model::RosenzweigMacArthur { r, k, a, h, e, m } => {
+                let x = m / (a * (e - m * h));
+                (x, r / a * (1.0 - x / k) * (1.0 + a * h * x))
+            }
+        }
+    }
+
+    /// Whether the coexistence equilibrium is stable. Lotka–Volterra is
+    /// neutrally stable (closed orbits) and reported as not attracting.
+    pub fn equilibrium_stable(&self) -> bool {
+        match *self {
+            Model::LotkaVolterra { .. } => false,
+            // Hopf bifurcation where the prey isocline peaks: x* = (K − 1/ah) / 2
+            Model::RosenzweigMacArthur { k, a, h, .. } => self.equilibrium().0 > (k - 1.0 / (a * h)) / 2.0,
+        }
+    }
+
+    /// Lotka–Volterra first integral, constant along every orbit.
+    pub fn conserved_quantity(&self, prey: f64, predator: f64) -> Option<f64> {
+        match *self {
+            Model::LotkaVolterra { alpha, beta, delta, gamma } => {
+                Some(delta * prey - gamma * prey.ln() + beta * predator - alpha * predator.ln())
+            }
+            Model::RosenzweigMacArthur { .. } => None,
+        }
+    }
+}
+
+/// Population sizes at a moment in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct State {
+    pub t: f64,
+    pub prey: f64,
+    pub predator: f64,
+}
+
+/// Integrate with fourth-order Runge–Kutta from the initial populations.
+pub fn integrate(model: &Model, prey: f64, predator: f64, dt: f64, steps: usize) -> Vec<State> {
+    let mut s = State { t: 0.0, prey, predator };
+    let mut out = Vec::with_capacity(steps + 1);
+    out.push(s);
+    for _ in 0..steps {
+        let f = |x: f64, y: f64| model.derivative(x, y);
+        let k1 = f(s.prey, s.predator);
+        let k2 = f(s.prey + 0.5 * dt * k1.0, s.predator + 0.5 * dt * k1.1);
+        let k3 = f(s.prey + 0.5 * dt * k2.0, s.predator + 0.5 * dt * k2.1);
+        let k4 = f(s.prey + dt * k3.0, s.predator + dt * k3.1);
+        s = State {
+            t: s.t + dt,
+            prey: (s.prey + dt / 6.0 * (k1.0 + 2.0 * k2.0 + 2.0 * k3.0 + k4.0)).max(0.0),
+            predator: (s.predator + dt / 6.0 * (k1.1 + 2.0 * k2.1 + 2.0 * k3.1 + k4.1)).max(0.0),
+        };
+        out.push(s);
+    }
+    out
+}
+
+/// Orbits from `count` initial conditions spaced between the equilibrium and
+/// `spread` × its prey level, all with the equilibrium predator level.
+pub fn nested_orbits(model: &Model, count: usize, spread: f64, dt: f64, steps: usize) -> Vec<Vec<State>> {
+    let (x, y) = model.equilibrium();
+    (1..=count)
+        .map(|i| {
+            let prey = x * (1.0 + (spread - 1.0) * i as f64 / count as f64);
+            integrate(model, prey, y, dt, steps)
+        })
+        .collect()
+}
+
+/// Peaks of a population series: times at which it reaches a local maximum.
+pub fn peak_times(series: &[State], prey: bool) -> Vec<f64> {
+    let v = |s: &State| if prey { s.prey } else { s.predator };
+    series
+        .windows(3)
+        .filter(|w| v(&w[1]) > v(&w[0]) && v(&w[1]) >= v(&w[2]))
+        .map(|w| w[1].t)
+        .collect()
+}
+
+impl Generator for Model {
+    /// Six nested orbits in the phase plane, prey across and predators up.
+    fn generate(&self) -> Geometry {
+        let orbits = nested_orbits(self, 6, 3.0, 0.01, 6000);
+        Geometry::Polylines(
+            orbits
+                .iter()
+                .map(|orbit| orbit.iter().map(|s| Point { x: s.prey, y: -s.predator }).collect())
+                .collect(),
+        )
+    }
+}
+
+/// Render orbits in the (prey, predator) plane, inner orbits warm, outer cool.
+pub fn phase_portrait_to_svg(model: &Model, orbits: &[Vec<State>]) -> String {
+    phase_portrait_to_svg_with_theme(model, orbits, &Theme::dark())
+}
+
+/// [`phase_portrait_to_svg`] in `theme`; with a palette, orbits take it in
+/// turn and the axes its first stop.
+pub fn phase_portrait_to_svg_with_theme(model: &Model, orbits: &[Vec<State>], theme: &Theme) -> String {
+    let size = 800.0;
+    let m = 50.0;
+    let max_x = orbits.iter().flatten().map(|s| s.prey).fold(1e-9, f64::max);
+    let max_y = orbits.iter().flatten().map(|s| s.predator).fold(1e-9, f64::max);
+    let map = |x: f64, y: f64| (m + x / max_x * (size - 2.0 * m), size - m - y / max_y * (size - 2.0 * m));
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}<line x1="{m}" y1="{b}" x2="{r}" y2="{b}" stroke="{axis}"/>
+<line x1="{m}" y1="{m}" x2="{m}" y2="{b}" stroke="{axis}"/>
+<text x="{r}" y="{lx}" fill="{label}" font-family="sans-serif" font-size="14" text-anchor="end">prey</text>
+<text x="{m}" y="{ly}" fill="{label}" font-family="sans-serif" font-size="14">predators</text>
+"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        axis = theme.color(0.0, "#555"),
+        label = theme.color(0.0, "#888"),
+        b = size - m,
+        r = size - m,
+        lx = size - m + 25.0,
+        ly = m - 15.0,
+    );
+    let palette = crate::render::golden_hues(orbits.len());
+    for (i, (orbit, color)) in orbits.iter().zip(&palette).enumerate() {
+        let coords: Vec<String> = orbit
+            .iter()
+            .map(|s| {
+                let (x, y) = map(s.prey, s.predator);
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+        svg.push_str(&format!(
+            r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" opacity="0.9"/>
+"##,
+            coords.join(" "),
+            theme.color(theme::place(i, orbits.len()), color.as_str()),
+            theme.stroke_width(1.5)
+        ));
+    }
+    let (ex, ey) = model.equilibrium();
+    let (cx, cy) = map(ex, ey);
+    svg.push_str(&format!(
+        r##"<circle cx="{cx:.1}" cy="{cy:.1}" r="4" fill="{}"/>
+{}</svg>"##,
+        theme.color(1.0, "#ffffff"),
+        theme.foreground_end()
+    ));
+    svg
+}
+
+/// Render prey (green) and predator (red) populations over time.
+pub fn time_series_to_svg(series: &[State]) -> String {
+    time_series_to_svg_with_theme(series, &Theme::dark())
+}
+
+/// [`time_series_to_svg`] in `theme`; with a palette, prey take its middle
+/// and predators its last stop.
+pub fn time_series_to_svg_with_theme(series: &[State], theme: &Theme) -> String {
+    let (w, h, m) = (800.0, 400.0, 40.0);
+    let t_max = series.last().map_or(1.0, |s| s.t).max(1e-9);
+    let v_max = series.iter().map(|s| s.prey.max(s.predator)).fold(1e-9, f64::max);
+    let line = |f: &dyn Fn(&State) -> f64| {
+        series
+            .iter()
+            .map(|s| format!("{:.1},{:.1}", m + s.t / t_max * (w - 2.0 * m), h - m - f(s) / v_max * (h - 2.0 * m)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+<rect width="{w}" height="{h}" fill="{}"/>
+{}<line x1="{m}" y1="{b}" x2="{r}" y2="{b}" stroke="{}"/>
+<polyline points="{}" fill="none" stroke="{}" stroke-width="{width}"/>
+<polyline points="{}" fill="none" stroke="{}" stroke-width="{width}"/>
+{}</svg>"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        theme.color(0.0, "#555"),
+        line(&|s| s.prey),
+        theme.color(0.5, "#66bb6a"),
+        line(&|s| s.predator),
+        theme.color(1.0, "#ef5350"),
+        theme.foreground_end(),
+        width = theme.stroke_width(2.0),
+        b = h - m,
+        r = w - m,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equilibrium_is_fixed_point() {
+        for model in [Model::default(), Model::rosenzweig_macarthur(3.0)] {
+            let (x, y) = model.equilibrium();
+            let (dx, dy) = model.derivative(x, y);
+            assert!(dx.abs() < 1e-12 && dy.abs() < 1e-12, "{:?}", model);
+        }
+    }
+
+    #[test]
+    fn test_lotka_volterra_conserves_quantity() {
+        let model = Model::default();
+        let orbit = integrate(&model, 10.0, 5.0, 0.01, 5000);
+        let v0 = model.conserved_quantity(10.0, 5.0).unwrap();
+        for s in &orbit {
+            let v = model.conserved_quantity(s.prey, s.predator).unwrap();
+            assert!((v - v0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_lotka_volterra_orbits_nested() {
+        let model = Model::default();
+        let orbits = nested_orbits(&model, 4, 3.0, 0.01, 3000);
+        let width = |o: &Vec<State>| {
+            let lo = o.iter().map(|s| s.prey).fold(f64::INFINITY, f64::min);
+            let hi = o.iter().map(|s| s.prey).fold(0.0, f64::max);
+            hi - lo
+        };
+        for w in orbits.windows(2) {
+            assert!(width(&w[1]) > width(&w[0]), "Outer orbits should enclose inner ones");
+        }
+    }
+
+    #[test]
+    fn test_predators_lag_prey() {
+        let model = Model::default();
+        let series = integrate(&model, 10.0, 5.0, 0.01, 4000);
+        let prey_peak = peak_times(&series, true)[0];
+        let next_predator = peak_times(&series, false).into_iter().find(|&t| t > prey_peak).unwrap();
+        let next_prey = peak_times(&series, true)[1];
+        assert!(next_predator < next_prey, "Predator peak should fall between prey peaks");
+    }
+
+    #[test]
+    fn test_paradox_of_enrichment() {
+        let poor = Model::rosenzweig_macarthur(2.0);
+        let rich = Model::rosenzweig_macarthur(4.0);
+        assert!(poor.equilibrium_stable());
+        assert!(!rich.equilibrium_stable());
+
+        let amplitude = |model: &Model| {
+            let (x, y) = model.equilibrium();
+            let series = integrate(model, x * 1.1, y, 0.01, 20000);
+            let tail = &series[15000..];
+            let lo = tail.iter().map(|s| s.prey).fold(f64::INFINITY, f64::min);
+            tail.iter().map(|s| s.prey).fold(0.0, f64::max) - lo
+        };
+        assert!(amplitude(&poor) < 1e-3, "Poor system should settle");
+        assert!(amplitude(&rich) > 0.5, "Rich system should cycle");
+    }
+
+    #[test]
+    fn test_limit_cycle_attracts_both_sides() {
+        let model = Model::rosenzweig_macarthur(4.0);
+        let (x, y) = model.equilibrium();
+        let tail_max = |prey: f64| {
+            let series = integrate(&model, prey, y, 0.01, 30000);
+            series[25000..].iter().map(|s| s.prey).fold(0.0, f64::max)
+        };
+        assert!((tail_max(x * 1.05) - tail_max(3.5)).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_populations_non_negative() {
+        let model = Model::rosenzweig_macarthur(6.0);
+        for s in integrate(&model, 0.5, 3.0, 0.05, 5000) {
+            assert!(s.prey >= 0.0 && s.predator >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_svg_outputs() {
+        let model = Model::default();
+        let orbits = nested_orbits(&model, 3, 2.5, 0.02, 1000);
+        assert_eq!(phase_portrait_to_svg(&model, &orbits).matches("<polyline").count(), 3);
+        assert_eq!(time_series_to_svg(&orbits[0]).matches("<polyline").count(), 2);
+    }
+}
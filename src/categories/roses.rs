@@ -0,0 +1,273 @@
+//! Rose curves (rhodonea) — petals from a single cosine.
+//!
+//!   r = a × cos(kθ),  k = n/d in lowest terms
+//!
+//! If n and d are both odd the curve closes after θ = πd and has n petals;
+//! otherwise it closes after θ = 2πd and has 2n petals. Fractional k gives
+//! overlapping multi-loop roses reminiscent of layered flower heads.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::{self, Theme};
+use super::fractals::Point;
+use super::spirals::SpiralPoint;
+
+/// Parameters for a rose curve with rational k = n/d.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoseParams {
+    /// Petal length a
+    pub amplitude: f64,
+    /// Numerator of k
+    pub n: u32,
+    /// Denominator of k
+    pub d: u32,
+}
+
+impl Default for RoseParams {
+    fn default() -> Self {
+        Self { amplitude: 300.0, n: 5, d: 1 }
+    }
+}
+
+/// How to draw a rose.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoseStyle {
+    /// A single stroked curve
+    Outline,
+    /// Each petal as a translucent filled loop, colored by petal index
+    Filled,
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+impl RoseParams {
+    /// k reduced to lowest terms. Returns (0, 1) for n = 0.
+    pub fn reduced(&self) -> (u32, u32) {
+        let d = self.d.max(1);
+        let g = gcd(self.n, d).max(1);
+        (self.n / g, d / g)
+    }
+
+    /// The angular frequency k = n/d.
+    pub fn k(&self) -> f64 {
+        let (n, d) = self.reduced();
+        n as f64 / d as f64
+    }
+
+    /// Number of petals traced over one full period.
+    pub fn petal_count(&self) -> usize {
+        let (n, d) = self.reduced();
+        if n % 2 == 1 && d % 2 == 1 { n as usize } else { 2 * n as usize }
+    }
+
+    /// θ range needed for the curve to close.
+    pub fn period(&self) -> f64 {
+        let (n, d) = self.reduced();
+        if n % 2 == 1 && d % 2 == 1 { PI * d as f64 } else { 2.0 * PI * d as f64 }
+    }
+
+    /// Parse k from "n/d", an integer, or a decimal (approximated with d ≤ 100).
+    pub fn parse_k(s: &str) -> Option<(u32, u32)> {
+        if let Some((n, d)) = s.split_once('/') {
+            let n = n.trim().parse().ok()?;
+            let d: u32 = d.trim().parse().ok()?;
+            return (d > 0).then_some((n, d));
+        }
+        let k: f64 = s.trim().parse().ok()?;
+        if !k.is_finite() || k < 0.0 {
+            return None;
+        }
+        // Best rational approximation by brute force over small denominators
+        (1..=100u32)
+            .map(|d| ((k * d as f64).round() as u32, d))
+            .min_by(|a, b| {
+                let ea = (a.0 as f64 / a.1 as f64 - k).abs();
+                let eb = (b.0 as f64 / b.1 as f64 - k).abs();
+                ea.total_cmp(&eb)
+            })
+    }
+}
+
+fn rose_point(params: &RoseParams, theta: f64) -> SpiralPoint {
+    let r = params.amplitude * (params.k() * theta).cos();
+    SpiralPoint { x: r * theta.cos(), y: r * theta.sin(), theta, r }
+}
+
+/// Sample the full closed rose curve.
+pub fn rose_curve(params: &RoseParams, num_points: usize) -> Vec<SpiralPoint> {
+    let period = params.period();
+    (0..=num_points)
+        .map(|i| rose_point(params, period * i as f64 / num_points.max(1) as f64))
+        .collect()
+}
+
+/// Split the rose into individual petal loops, each running origin → tip → origin.
+///
+/// Petal i is centered on θ = iπ/k and spans one half-period of cos(kθ).
+pub fn petals(params: &RoseParams, points_per_petal: usize) -> Vec<Vec<SpiralPoint>> {
+    let k = params.k();
+    if k == 0.0 {
+        return vec![];
+    }
+    let width = PI / k;
+    (0..params.petal_count())
+        .map(|i| {
+            let start = i as f64 * width - width / 2.0;
+            (0..=points_per_petal)
+                .map(|j| rose_point(params, start + width * j as f64 / points_per_petal.max(1) as f64))
+                .collect()
+        })
+        .collect()
+}
+
+impl Generator for RoseParams {
+    fn generate(&self) -> Geometry {
+        Geometry::Polylines(vec![rose_curve(self, 2000).iter().map(|p| Point { x: p.x, y: p.y }).collect()])
+    }
+}
+
+/// Render a rose as SVG.
+pub fn to_svg(params: &RoseParams, style: RoseStyle) -> String {
+    to_svg_with_theme(params, style, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, petals take it in turn.
+pub fn to_svg_with_theme(params: &RoseParams, style: RoseStyle, theme: &Theme) -> String {
+    let size = 800.0;
+    let scale = (size / 2.0 - 30.0) / params.amplitude.abs().max(1e-9);
+    let c = size / 2.0;
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
+    );
+    let coords = |pts: &[SpiralPoint]| {
+        pts.iter()
+            .map(|p| format!("{:.1},{:.1}", c + p.x * scale, c - p.y * scale))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    match style {
+        RoseStyle::Outline => {
+            let pts = rose_curve(params, 2000 * params.reduced().1.max(1) as usize);
+            // A palette is traced along the curve in 64 pieces, each sharing its
+            // last point with the next
+            let pieces = if theme.palette.is_some() { 64 } else { 1 };
+            let chunk = pts.len().div_ceil(pieces).max(1);
+            for (k, start) in (0..pts.len()).step_by(chunk).enumerate() {
+                svg.push_str(&format!(
+                    r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>
+"##,
+                    coords(&pts[start..(start + chunk + 1).min(pts.len())]),
+                    theme.color(theme::place(k, pts.len().div_ceil(chunk)), "#f06292"),
+                    theme.stroke_width(1.5)
+                ));
+            }
+        }
+        RoseStyle::Filled => {
+            let loops = petals(params, 200);
+            let count = loops.len().max(1) as f64;
+            for (i, petal) in loops.iter().enumerate() {
+                let hue = 320.0 + 60.0 * i as f64 / count;
+                let t = theme::place(i, loops.len());
+                svg.push_str(&format!(
+                    r##"<polygon points="{}" fill="{}" fill-opacity="0.45" stroke="{}" stroke-width="{}"/>
+"##,
+                    coords(petal),
+                    theme.color(t, crate::render::hsl(hue, 75.0, 60.0)),
+                    theme.color(t, "#fce4ec"),
+                    theme.stroke_width(0.8)
+                ));
+            }
+        }
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rose(n: u32, d: u32) -> RoseParams {
+        RoseParams { amplitude: 1.0, n, d }
+    }
+
+    #[test]
+    fn test_petal_counts() {
+        assert_eq!(rose(3, 1).petal_count(), 3);
+        assert_eq!(rose(4, 1).petal_count(), 8);
+        assert_eq!(rose(5, 1).petal_count(), 5);
+        assert_eq!(rose(2, 1).petal_count(), 4);
+        assert_eq!(rose(1, 2).petal_count(), 2);
+        assert_eq!(rose(5, 3).petal_count(), 5);
+    }
+
+    #[test]
+    fn test_reduction() {
+        assert_eq!(rose(6, 4).reduced(), (3, 2));
+        assert_eq!(rose(6, 4).petal_count(), rose(3, 2).petal_count());
+    }
+
+    #[test]
+    fn test_curve_closes() {
+        for (n, d) in [(3, 1), (4, 1), (5, 3), (7, 2)] {
+            let pts = rose_curve(&rose(n, d), 1000);
+            let first = pts.first().unwrap();
+            let last = pts.last().unwrap();
+            assert!((first.x - last.x).abs() < 1e-9 && (first.y - last.y).abs() < 1e-9, "k={n}/{d} not closed");
+        }
+    }
+
+    #[test]
+    fn test_bounded_by_amplitude() {
+        let p = RoseParams { amplitude: 7.0, n: 7, d: 3 };
+        for pt in rose_curve(&p, 500) {
+            assert!((pt.x * pt.x + pt.y * pt.y).sqrt() <= 7.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_petals_start_and_end_at_origin() {
+        let loops = petals(&rose(5, 1), 50);
+        assert_eq!(loops.len(), 5);
+        for petal in &loops {
+            assert!(petal[0].r.abs() < 1e-9);
+            assert!(petal.last().unwrap().r.abs() < 1e-9);
+            // Tip of the petal reaches the full amplitude
+            assert!((petal[25].r.abs() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_parse_k() {
+        assert_eq!(RoseParams::parse_k("5/3"), Some((5, 3)));
+        assert_eq!(RoseParams::parse_k("4"), Some((4, 1)));
+        assert_eq!(RoseParams::parse_k("0.75"), Some((3, 4)));
+        assert_eq!(RoseParams::parse_k("1/0"), None);
+        assert_eq!(RoseParams::parse_k("petals"), None);
+    }
+
+    #[test]
+    fn test_svg_styles() {
+        let p = RoseParams::default();
+        let outline = to_svg(&p, RoseStyle::Outline);
+        assert!(outline.contains("<polyline"));
+        let filled = to_svg(&p, RoseStyle::Filled);
+        assert_eq!(filled.matches("<polygon").count(), 5);
+        // A palette runs along the one outline from its first stop to its last
+        let traced = to_svg_with_theme(&p, RoseStyle::Outline, &Theme::light());
+        assert!(traced.matches("<polyline").count() > 1);
+        assert!(traced.contains(&Theme::light().color(0.0, "")) && traced.contains(&Theme::light().color(1.0, "")));
+    }
+}
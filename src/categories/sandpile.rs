@@ -0,0 +1,282 @@
+//! Abelian sandpile — self-organized criticality in a pile of sand.
+//!
+//! Bak, Tang & Wiesenfeld (1987): each cell holds some grains; any cell with
+//! four or more topples, passing one grain to each neighbor (grains falling
+//! off the edge are lost). Dhar showed the final stable state does not depend
+//! on toppling order. Dropping a tower of grains on one cell relaxes into an
+//! intricate fractal mandala, and avalanches of every size appear — the same
+//! statistics seen in landslides, forest fires, and earthquakes.
+
+use alloc::collections::VecDeque;
+
+use crate::prelude::*;
+use crate::geometry::{Generator, Geometry};
+use crate::render::colormap::Colormap;
+use crate::render::theme::Theme;
+
+/// Grains at which a cell topples.
+pub const THRESHOLD: u32 = 4;
+
+/// A sandpile on a rectangular grid with sinks all around the border.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sandpile {
+    pub width: usize,
+    pub height: usize,
+    pub grains: Vec<u32>,
+}
+
+impl Sandpile {
+    /// An empty pile.
+    pub fn new(width: usize, height: usize) -> Self {
+        Sandpile { width, height, grains: vec![0; width * height] }
+    }
+
+    /// A pile with every cell at `value` grains.
+    pub fn filled(width: usize, height: usize, value: u32) -> Self {
+        Sandpile { width, height, grains: vec![value; width * height] }
+    }
+
+    /// Smallest odd square grid that comfortably holds `grains` dropped at the center.
+    ///
+    /// The stable mandala is nearly a disc with about 2.1 grains per cell.
+    pub fn fitting(grains: u64) -> usize {
+        let radius = (grains as f64 / (2.0 * core::f64::consts::PI)).sqrt();
+        2 * (radius.ceil() as usize + 2) + 1
+    }
+
+    /// Add grains at a cell without toppling.
+    pub fn add(&mut self, x: usize, y: usize, grains: u32) {
+        self.grains[y * self.width + x] += grains;
+    }
+
+    /// Whether no cell is at or above the toppling threshold.
+    pub fn is_stable(&self) -> bool {
+        self.grains.iter().all(|&g| g < THRESHOLD)
+    }
+
+    /// Topple until stable, returning the total number of topplings.
+    ///
+    /// Unstable cells are processed from a work queue and topple as many times
+    /// at once as their grain count allows; by the abelian property the result
+    /// is the same as toppling one grain at a time.
+    pub fn stabilize(&mut self) -> u64 {
+        let (w, h) = (self.width, self.height);
+        let mut queue: VecDeque<usize> = (0..self.grains.len()).filter(|&i| self.grains[i] >= THRESHOLD).collect();
+        let mut topplings = 0;
+        while let Some(i) = queue.pop_front() {
+            let times = self.grains[i] / THRESHOLD;
+            if times == 0 {
+                continue;
+            }
+            self.grains[i] -= times * THRESHOLD;
+            topplings += times as u64;
+            let (x, y) = (i % w, i / w);
+            let neighbors = [
+                (x > 0).then(|| i - 1),
+                (x + 1 < w).then(|| i + 1),
+                (y > 0).then(|| i - w),
+                (y + 1 < h).then(|| i + w),
+            ];
+            for n in neighbors.into_iter().flatten() {
+                let before = self.grains[n];
+                self.grains[n] += times;
+                if before < THRESHOLD && self.grains[n] >= THRESHOLD {
+                    queue.push_back(n);
+                }
+            }
+        }
+        topplings
+    }
+
+    /// Drop `grains` on the center cell and stabilize.
+    pub fn drop_center(&mut self, grains: u32) -> u64 {
+        self.add(self.width / 2, self.height / 2, grains);
+        self.stabilize()
+    }
+
+    /// Sandpile sum: add cell by cell, then stabilize.
+    pub fn combine(&self, other: &Sandpile) -> Sandpile {
+        let mut sum = self.clone();
+        for (a, b) in sum.grains.iter_mut().zip(other.grains.iter()) {
+            *a += b;
+        }
+        sum.stabilize();
+        sum
+    }
+
+    /// Total grains on the grid.
+    pub fn total(&self) -> u64 {
+        self.grains.iter().map(|&g| g as u64).sum()
+    }
+}
+
+/// Identity element of the sandpile group on a `width × height` grid.
+///
+/// With `m` the all-threes pile, the identity is `(2m − (2m)°)°` where `°`
+/// denotes stabilization — adding it to any recurrent pile changes nothing.
+pub fn identity(width: usize, height: usize) -> Sandpile {
+    let mut doubled = Sandpile::filled(width, height, 2 * (THRESHOLD - 1));
+    doubled.stabilize();
+    let mut id = Sandpile::filled(width, height, 2 * (THRESHOLD - 1));
+    for (a, b) in id.grains.iter_mut().zip(doubled.grains.iter()) {
+        *a -= b;
+    }
+    id.stabilize();
+    id
+}
+
+/// Stable piles after dropping `grains` at the center in `frames` equal
+/// installments — by the abelian property the last one equals a single drop.
+pub fn snapshots(size: usize, grains: u32, frames: usize) -> Vec<Sandpile> {
+    let frames = frames.max(1);
+    let mut pile = Sandpile::new(size, size);
+    let mut dropped = 0;
+    (1..=frames)
+        .map(|i| {
+            let target = (grains as u64 * i as u64 / frames as u64) as u32;
+            pile.drop_center(target - dropped);
+            dropped = target;
+            pile.clone()
+        })
+        .collect()
+}
+
+impl Generator for Sandpile {
+    fn generate(&self) -> Geometry {
+        Geometry::Grid { cols: self.width, rows: self.height, values: self.grains.iter().map(|&g| g as f64).collect() }
+    }
+}
+
+/// Render with 0 to 3 grains spread evenly along `colormap`.
+pub fn to_svg_with_colormap(pile: &Sandpile, cell: f64, colormap: &dyn Colormap) -> String {
+    spread(pile, cell, colormap, &Theme::dark())
+}
+
+fn spread(pile: &Sandpile, cell: f64, colormap: &dyn Colormap, theme: &Theme) -> String {
+    let values: Vec<f64> = pile.grains.iter().map(|&g| f64::from(g.min(3)) / 3.0).collect();
+    crate::render::raster_svg_with_theme(pile.width, pile.height, cell, &values, |t| colormap.css(t), theme)
+}
+
+/// Render with the classic four-color palette (0–3 grains).
+pub fn to_svg(pile: &Sandpile, cell: f64) -> String {
+    to_svg_with_theme(pile, cell, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`: along its palette if it has one, else the classic
+/// colors on its background.
+pub fn to_svg_with_theme(pile: &Sandpile, cell: f64, theme: &Theme) -> String {
+    if let Some(palette) = &theme.palette {
+        return spread(pile, cell, palette, theme);
+    }
+    let values: Vec<f64> = pile.grains.iter().map(|&g| g as f64).collect();
+    let background = theme.background_css();
+    crate::render::raster_svg_with_theme(
+        pile.width,
+        pile.height,
+        cell,
+        &values,
+        |g| match g as u32 {
+            0 => background.clone(),
+            1 => String::from("#1e88e5"),
+            2 => String::from("#fdd835"),
+            _ => String::from("#d81b60"),
+        },
+        theme,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_topple() {
+        let mut pile = Sandpile::new(3, 3);
+        pile.add(1, 1, 4);
+        assert_eq!(pile.stabilize(), 1);
+        assert_eq!(pile.grains, vec![0, 1, 0, 1, 0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_stabilize_is_stable() {
+        let mut pile = Sandpile::new(41, 41);
+        pile.drop_center(5000);
+        assert!(pile.is_stable());
+    }
+
+    #[test]
+    fn test_grains_conserved_away_from_edge() {
+        let size = Sandpile::fitting(3000);
+        let mut pile = Sandpile::new(size, size);
+        pile.drop_center(3000);
+        assert_eq!(pile.total(), 3000);
+        assert!(pile.grains[..size].iter().all(|&g| g == 0), "Grains reached the edge");
+    }
+
+    #[test]
+    fn test_grains_lost_at_edge() {
+        let mut pile = Sandpile::new(5, 5);
+        pile.drop_center(1000);
+        assert!(pile.total() < 1000);
+        assert!(pile.is_stable());
+    }
+
+    #[test]
+    fn test_center_drop_symmetric() {
+        let mut pile = Sandpile::new(61, 61);
+        pile.drop_center(4000);
+        let at = |x: usize, y: usize| pile.grains[y * 61 + x];
+        for y in 0..61 {
+            for x in 0..61 {
+                assert_eq!(at(x, y), at(60 - x, y));
+                assert_eq!(at(x, y), at(y, x));
+            }
+        }
+    }
+
+    #[test]
+    fn test_abelian() {
+        let mut a = Sandpile::new(21, 21);
+        a.add(5, 5, 50);
+        a.add(12, 9, 70);
+        a.stabilize();
+        let mut b = Sandpile::new(21, 21);
+        b.add(12, 9, 70);
+        b.stabilize();
+        b.add(5, 5, 50);
+        b.stabilize();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_identity_neutral() {
+        let id = identity(15, 11);
+        assert!(id.is_stable());
+        assert_eq!(id.combine(&id), id);
+        let max = Sandpile::filled(15, 11, 3);
+        assert_eq!(max.combine(&id), max);
+    }
+
+    #[test]
+    fn test_snapshots_match_single_drop() {
+        let frames = snapshots(41, 2000, 7);
+        assert_eq!(frames.len(), 7);
+        let mut single = Sandpile::new(41, 41);
+        single.drop_center(2000);
+        assert_eq!(frames.last(), Some(&single));
+        assert!(frames[0].total() < frames[6].total());
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let mut pile = Sandpile::new(31, 31);
+        pile.drop_center(1000);
+        let svg = to_svg(&pile, 4.0);
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("#fdd835"));
+        let gray = crate::render::gradient::Gradient::parse("#000000,#ffffff", crate::render::gradient::Interpolation::Srgb).unwrap();
+        let svg = to_svg_with_colormap(&pile, 4.0, &gray);
+        assert!(svg.contains("rgb(255,255,255)") && !svg.contains("#fdd835"));
+    }
+}
@@ -0,0 +1,511 @@
+//! Snowflakes — six-fold crystals grown from water vapour.
+//!
+//! Implements Clifford Reiter's hexagonal cellular automaton (2005). Each cell
+//! holds a water level s; a cell is *frozen* once s ≥ 1 and *receptive* if it
+//! is frozen or touches a frozen cell. Each step:
+//!
+//!   receptive cells gain vapour:  v = s + γ
+//!   other cells diffuse:          u' = u + (α/2)(ū - u)
+//!   new level:                    s = u' + v
+//!
+//! with the far boundary held at the background level β. Small changes to
+//! (α, β, γ) move the crystal between plates, stellar dendrites, and ferns.
+
+use crate::prelude::*;
+use crate::error::{check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::Theme;
+use super::fractals::Point;
+
+/// Axial neighbor offsets in counter-clockwise order (0°, 60°, …, 300°).
+const DIRECTIONS: [(isize, isize); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// Parameters for Reiter's snowflake model.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReiterParams {
+    /// Diffusion constant of unfrozen water
+    pub alpha: f64,
+    /// Background vapour level
+    pub beta: f64,
+    /// Vapour added to receptive cells each step
+    pub gamma: f64,
+}
+
+impl Default for ReiterParams {
+    fn default() -> Self {
+        Preset::Stellar.params()
+    }
+}
+
+impl ReiterParams {
+    /// Require non-negative diffusion and vapour levels no higher than
+    /// saturation (β, γ ≤ 1).
+    pub fn validate(&self) -> Result<()> {
+        check_range("alpha", self.alpha, 0.0..=f64::MAX, "a non-negative number")?;
+        check_range("beta", self.beta, 0.0..=1.0, "0 to 1")?;
+        check_range("gamma", self.gamma, 0.0..=1.0, "0 to 1")
+    }
+}
+
+/// Preset parameter sets for recognizable crystal habits.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Six-armed stellar dendrite with side branches
+    Stellar,
+    /// Broad hexagonal plate
+    Plate,
+    /// Thin, fern-like dendrite
+    Fernlike,
+}
+
+impl Preset {
+    pub fn params(self) -> ReiterParams {
+        match self {
+            Preset::Stellar => ReiterParams { alpha: 1.0, beta: 0.4, gamma: 0.001 },
+            Preset::Plate => ReiterParams { alpha: 1.0, beta: 0.3, gamma: 0.01 },
+            Preset::Fernlike => ReiterParams { alpha: 1.0, beta: 0.35, gamma: 0.0001 },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Stellar => "Stellar Dendrite",
+            Preset::Plate => "Hexagonal Plate",
+            Preset::Fernlike => "Fern-like Dendrite",
+        }
+    }
+}
+
+/// A hexagonal grid of water levels in axial coordinates.
+///
+/// The grid is a hexagon of the given radius around the seed cell; cells on
+/// the outer ring act as the vapour reservoir at level β.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snowflake {
+    pub radius: usize,
+    pub state: Vec<f64>,
+    /// Step at which each cell froze (`None` while still liquid/vapour)
+    pub frozen_at: Vec<Option<usize>>,
+    pub steps: usize,
+}
+
+impl Snowflake {
+    /// Create a grid at background level β with a single frozen seed in the center.
+    pub fn new(radius: usize, params: &ReiterParams) -> Self {
+        let radius = radius.max(2);
+        let size = 2 * radius + 1;
+        let mut state = vec![params.beta; size * size];
+        let mut frozen_at = vec![None; size * size];
+        let center = radius * size + radius;
+        state[center] = 1.0;
+        frozen_at[center] = Some(0);
+        Snowflake { radius, state, frozen_at, steps: 0 }
+    }
+
+    fn size(&self) -> usize {
+        2 * self.radius + 1
+    }
+
+    /// Hex distance of axial cell (q, r) from the center.
+    fn distance(&self, q: usize, r: usize) -> usize {
+        let dq = q as isize - self.radius as isize;
+        let dr = r as isize - self.radius as isize;
+        ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as usize
+    }
+
+    fn neighbor(&self, q: usize, r: usize, d: usize) -> usize {
+        let (dq, dr) = DIRECTIONS[d];
+        let nq = (q as isize + dq) as usize;
+        let nr = (r as isize + dr) as usize;
+        nr * self.size() + nq
+    }
+
+    /// Whether the cell at axial (q, r) has frozen.
+    pub fn is_frozen(&self, q: usize, r: usize) -> bool {
+        self.state[r * self.size() + q] >= 1.0
+    }
+
+    /// Advance the automaton by one step.
+    pub fn step(&mut self, params: &ReiterParams) {
+        let size = self.size();
+        let n = size * size;
+        let mut u = vec![0.0; n];
+        let mut v = vec![0.0; n];
+
+        for r in 0..size {
+            for q in 0..size {
+                let i = r * size + q;
+                let inside = self.distance(q, r) < self.radius;
+                let rec = inside
+                    && (self.state[i] >= 1.0
+                        || (0..6).any(|d| self.state[self.neighbor(q, r, d)] >= 1.0));
+                if rec {
+                    v[i] = self.state[i] + params.gamma;
+                } else {
+                    u[i] = self.state[i];
+                }
+            }
+        }
+
+        self.steps += 1;
+        for r in 0..size {
+            for q in 0..size {
+                let i = r * size + q;
+                let dist = self.distance(q, r);
+                if dist > self.radius {
+                    continue;
+                }
+                if dist == self.radius {
+                    self.state[i] = params.beta;
+                    continue;
+                }
+                let avg = (0..6).map(|d| u[self.neighbor(q, r, d)]).sum::<f64>() / 6.0;
+                let u_new = u[i] + params.alpha / 2.0 * (avg - u[i]);
+                self.state[i] = u_new + v[i];
+                if self.state[i] >= 1.0 && self.frozen_at[i].is_none() {
+                    self.frozen_at[i] = Some(self.steps);
+                }
+            }
+        }
+    }
+
+    /// Run the automaton for n steps, stopping early if the crystal reaches the boundary.
+    pub fn simulate(&mut self, params: &ReiterParams, steps: usize) {
+        for _ in 0..steps {
+            if self.touches_boundary() {
+                break;
+            }
+            self.step(params);
+        }
+    }
+
+    /// Whether any frozen cell lies next to the vapour reservoir ring.
+    pub fn touches_boundary(&self) -> bool {
+        let size = self.size();
+        (0..size * size).any(|i| {
+            let (q, r) = (i % size, i / size);
+            self.frozen_at[i].is_some() && self.distance(q, r) + 1 >= self.radius
+        })
+    }
+
+    /// Number of frozen cells.
+    pub fn frozen_count(&self) -> usize {
+        self.frozen_at.iter().filter(|f| f.is_some()).count()
+    }
+
+    /// Cartesian center of axial cell (q, r), with hexagons of circumradius 1.
+    pub fn cell_center(&self, q: usize, r: usize) -> Point {
+        let dq = q as f64 - self.radius as f64;
+        let dr = r as f64 - self.radius as f64;
+        Point { x: 3.0_f64.sqrt() * (dq + dr / 2.0), y: 1.5 * dr }
+    }
+
+    /// Extract the crystal outline as closed polygons.
+    ///
+    /// Every hexagon edge between a frozen and an unfrozen cell is collected
+    /// with the frozen cell on its left, then the edges are chained into loops.
+    /// Outer boundaries wind counter-clockwise and holes clockwise.
+    pub fn outline(&self) -> Vec<Vec<Point>> {
+        let size = self.size();
+        let key = |p: Point| ((p.x * 1000.0).round() as i64, (p.y * 1000.0).round() as i64);
+        let mut edges: Vec<(Point, Point)> = Vec::new();
+        for r in 0..size {
+            for q in 0..size {
+                let i = r * size + q;
+                if self.frozen_at[i].is_none() || self.distance(q, r) >= self.radius {
+                    continue;
+                }
+                let c = self.cell_center(q, r);
+                for d in 0..6 {
+                    if self.frozen_at[self.neighbor(q, r, d)].is_some() {
+                        continue;
+                    }
+                    let a0 = (60.0 * d as f64 - 30.0).to_radians();
+                    let a1 = (60.0 * d as f64 + 30.0).to_radians();
+                    edges.push((
+                        Point { x: c.x + a0.cos(), y: c.y + a0.sin() },
+                        Point { x: c.x + a1.cos(), y: c.y + a1.sin() },
+                    ));
+                }
+            }
+        }
+
+        let mut by_start: Map<(i64, i64), Vec<usize>> = Map::new();
+        for (i, e) in edges.iter().enumerate() {
+            by_start.entry(key(e.0)).or_default().push(i);
+        }
+        let mut used = vec![false; edges.len()];
+        let mut loops = Vec::new();
+        for start in 0..edges.len() {
+            if used[start] {
+                continue;
+            }
+            let mut poly = vec![edges[start].0];
+            let mut current = start;
+            loop {
+                used[current] = true;
+                let end = edges[current].1;
+                let next = by_start
+                    .get(&key(end))
+                    .and_then(|c| c.iter().copied().find(|&j| !used[j]));
+                match next {
+                    Some(j) => {
+                        poly.push(end);
+                        current = j;
+                    }
+                    None => break,
+                }
+            }
+            loops.push(poly);
+        }
+        loops
+    }
+}
+
+fn hexagon_points(c: Point, size: f64) -> String {
+    (0..6)
+        .map(|k| {
+            let a = (60.0 * k as f64 - 30.0).to_radians();
+            format!("{:.1},{:.1}", c.x + size * a.cos(), c.y + size * a.sin())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl Generator for Snowflake {
+    fn generate(&self) -> Geometry {
+        Geometry::Polygons(self.outline())
+    }
+}
+
+/// Render the crystal as it looked after `until_step` steps, colored by growth stage.
+pub fn stage_to_svg(flake: &Snowflake, until_step: usize) -> String {
+    stage_to_svg_with_theme(flake, until_step, &Theme::dark())
+}
+
+/// [`stage_to_svg`] in `theme`; with a palette, growth stages run along it.
+pub fn stage_to_svg_with_theme(flake: &Snowflake, until_step: usize, theme: &Theme) -> String {
+    let size = flake.size();
+    let w = 800.0;
+    let scale = w / (2.0 * 3.0_f64.sqrt() * flake.radius as f64 + 2.0);
+    let last = flake.steps.max(1) as f64;
+
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{w}" viewBox="0 0 {w} {w}">
+<rect width="{w}" height="{w}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
+    );
+    for r in 0..size {
+        for q in 0..size {
+            let Some(t) = flake.frozen_at[r * size + q] else { continue };
+            if t > until_step {
+                continue;
+            }
+            let p = flake.cell_center(q, r);
+            let c = Point { x: w / 2.0 + p.x * scale, y: w / 2.0 - p.y * scale };
+            // Older ice is deeper blue, fresh growth is nearly white
+            let light = 55.0 + 40.0 * (t as f64 / last);
+            svg.push_str(&format!(
+                r##"<polygon points="{}" fill="{}"/>
+"##,
+                hexagon_points(c, scale * 1.02),
+                theme.color(t as f64 / last, format!("hsl(200,80%,{light:.0}%)"))
+            ));
+        }
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Render the fully grown crystal.
+pub fn to_svg(flake: &Snowflake) -> String {
+    to_svg_with_theme(flake, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`.
+pub fn to_svg_with_theme(flake: &Snowflake, theme: &Theme) -> String {
+    stage_to_svg_with_theme(flake, flake.steps, theme)
+}
+
+/// Render `frames` growth stages evenly spaced over the simulation.
+pub fn stage_frames(flake: &Snowflake, frames: usize) -> Vec<String> {
+    stage_frames_with_theme(flake, frames, &Theme::dark())
+}
+
+/// [`stage_frames`] in `theme`.
+pub fn stage_frames_with_theme(flake: &Snowflake, frames: usize, theme: &Theme) -> Vec<String> {
+    let frames = frames.max(1);
+    (1..=frames)
+        .map(|i| stage_to_svg_with_theme(flake, flake.steps * i / frames, theme))
+        .collect()
+}
+
+/// Render outline polygons as a single even-odd filled path.
+pub fn outline_to_svg(loops: &[Vec<Point>]) -> String {
+    outline_to_svg_with_theme(loops, &Theme::dark())
+}
+
+/// [`outline_to_svg`] in `theme`.
+pub fn outline_to_svg_with_theme(loops: &[Vec<Point>], theme: &Theme) -> String {
+    let w = 800.0;
+    let extent = loops
+        .iter()
+        .flatten()
+        .map(|p| p.x.abs().max(p.y.abs()))
+        .fold(1.0_f64, f64::max);
+    let scale = (w / 2.0 - 20.0) / extent;
+
+    let mut d = String::new();
+    for poly in loops {
+        for (i, p) in poly.iter().enumerate() {
+            let cmd = if i == 0 { 'M' } else { 'L' };
+            d.push_str(&format!("{cmd}{:.1},{:.1} ", w / 2.0 + p.x * scale, w / 2.0 - p.y * scale));
+        }
+        d.push_str("Z ");
+    }
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{w}" viewBox="0 0 {w} {w}">
+<rect width="{w}" height="{w}" fill="{}"/>
+{}<path d="{d}" fill="{}" fill-rule="evenodd" stroke="{}" stroke-width="{}"/>
+{}</svg>"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        theme.color(0.0, "#e3f2fd"),
+        theme.color(1.0, "#4fc3f7"),
+        theme.stroke_width(1.0),
+        theme.foreground_end()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grown(preset: Preset, steps: usize) -> Snowflake {
+        let params = preset.params();
+        let mut flake = Snowflake::new(40, &params);
+        flake.simulate(&params, steps);
+        flake
+    }
+
+    #[test]
+    fn test_seed_is_frozen() {
+        let flake = Snowflake::new(10, &ReiterParams::default());
+        assert!(flake.is_frozen(10, 10));
+        assert_eq!(flake.frozen_count(), 1);
+    }
+
+    #[test]
+    fn test_crystal_grows() {
+        let flake = grown(Preset::Stellar, 200);
+        assert!(flake.frozen_count() > 1, "Crystal should grow");
+    }
+
+    #[test]
+    fn test_sixfold_symmetry() {
+        let flake = grown(Preset::Stellar, 300);
+        let size = 2 * flake.radius + 1;
+        let c = flake.radius as isize;
+        // Rotating by 60° in axial coordinates maps (q, r) → (-r, q + r)
+        for r in 0..size {
+            for q in 0..size {
+                let (dq, dr) = (q as isize - c, r as isize - c);
+                let (rq, rr) = (-dr + c, dq + dr + c);
+                if rq < 0 || rr < 0 || rq >= size as isize || rr >= size as isize {
+                    continue;
+                }
+                assert_eq!(
+                    flake.is_frozen(q, r),
+                    flake.is_frozen(rq as usize, rr as usize),
+                    "Asymmetric at ({q}, {r})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_frozen_cells_stay_frozen() {
+        let params = Preset::Stellar.params();
+        let mut flake = Snowflake::new(30, &params);
+        flake.simulate(&params, 100);
+        let before: Vec<bool> = flake.frozen_at.iter().map(|f| f.is_some()).collect();
+        flake.simulate(&params, 50);
+        for (i, was) in before.iter().enumerate() {
+            if *was {
+                assert!(flake.state[i] >= 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stops_at_boundary() {
+        let params = Preset::Plate.params();
+        let mut flake = Snowflake::new(8, &params);
+        flake.simulate(&params, 100_000);
+        assert!(flake.touches_boundary());
+        assert!(flake.steps < 100_000);
+    }
+
+    #[test]
+    fn test_outline_of_seed_is_hexagon() {
+        let flake = Snowflake::new(5, &ReiterParams::default());
+        let loops = flake.outline();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 6);
+    }
+
+    #[test]
+    fn test_outline_closed() {
+        let flake = grown(Preset::Stellar, 200);
+        let loops = flake.outline();
+        assert!(!loops.is_empty());
+        for poly in &loops {
+            assert!(poly.len() >= 6);
+        }
+    }
+
+    #[test]
+    fn test_stage_frames() {
+        let flake = grown(Preset::Stellar, 100);
+        let frames = stage_frames(&flake, 4);
+        assert_eq!(frames.len(), 4);
+        // Later frames contain at least as many cells
+        let counts: Vec<usize> = frames.iter().map(|f| f.matches("<polygon").count()).collect();
+        for w in counts.windows(2) {
+            assert!(w[1] >= w[0]);
+        }
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let flake = grown(Preset::Stellar, 50);
+        let svg = to_svg(&flake);
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("<polygon"));
+        let outline = outline_to_svg(&flake.outline());
+        assert!(outline.contains("<path"));
+    }
+
+    #[test]
+    fn test_presets() {
+        for preset in [Preset::Stellar, Preset::Plate, Preset::Fernlike] {
+            let p = preset.params();
+            assert!(p.alpha > 0.0 && p.beta > 0.0 && p.beta < 1.0);
+            assert!(!preset.name().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        for preset in [Preset::Stellar, Preset::Plate, Preset::Fernlike] {
+            assert!(preset.params().validate().is_ok());
+        }
+        assert!(ReiterParams { beta: 1.5, ..Default::default() }.validate().is_err());
+    }
+}
@@ -3,10 +3,17 @@
 //! From nautilus shells to galaxies, hurricanes to DNA helices,
 //! spirals appear wherever growth meets rotation.
 
-use std::f64::consts::PI;
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::{self, Theme};
+use super::fractals::Point;
 
 /// A point on a spiral curve.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpiralPoint {
     pub x: f64,
     pub y: f64,
@@ -16,6 +23,7 @@ pub struct SpiralPoint {
 
 /// Type of spiral.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpiralType {
     /// r = a × e^(b×θ) — nautilus, galaxies, hurricanes
     Logarithmic { a: f64, b: f64 },
@@ -29,8 +37,16 @@ pub enum SpiralType {
     Helix { radius: f64, pitch: f64 },
 }
 
+/// Most points [`checked_generate_spiral`] accepts.
+pub const MAX_POINTS: usize = 1_000_000;
+
 /// Generate points along a spiral.
+#[deprecated(note = "accepts any point count and sweep; use `checked_generate_spiral`")]
 pub fn generate_spiral(spiral_type: SpiralType, num_points: usize, max_theta: f64) -> Vec<SpiralPoint> {
+    spiral_points(spiral_type, num_points, max_theta)
+}
+
+fn spiral_points(spiral_type: SpiralType, num_points: usize, max_theta: f64) -> Vec<SpiralPoint> {
     let phi = crate::constants::PHI;
     (0..num_points)
         .map(|i| {
@@ -54,6 +70,14 @@ pub fn generate_spiral(spiral_type: SpiralType, num_points: usize, max_theta: f6
         .collect()
 }
 
+/// Generate points along a spiral, rejecting an empty or oversized point
+/// count and a sweep that is not a positive, finite angle.
+pub fn checked_generate_spiral(spiral_type: SpiralType, num_points: usize, max_theta: f64) -> Result<Vec<SpiralPoint>> {
+    check_count("points", num_points, MAX_POINTS)?;
+    check_positive("max_theta", max_theta)?;
+    Ok(spiral_points(spiral_type, num_points, max_theta))
+}
+
 /// Measure how closely a spiral matches the golden spiral.
 pub fn golden_spiral_fitness(points: &[SpiralPoint]) -> f64 {
     if points.len() < 2 {
@@ -110,8 +134,31 @@ pub fn curvature(points: &[SpiralPoint]) -> Vec<f64> {
     }).collect()
 }
 
+/// A spiral and its sampling, ready to generate.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spiral {
+    pub kind: SpiralType,
+    pub points: usize,
+    /// Number of full turns
+    pub turns: f64,
+}
+
+impl Generator for Spiral {
+    fn generate(&self) -> Geometry {
+        let points = spiral_points(self.kind, self.points, self.turns * 2.0 * PI);
+        Geometry::Polylines(vec![points.iter().map(|p| Point { x: p.x, y: p.y }).collect()])
+    }
+}
+
 /// Generate SVG for a spiral.
 pub fn to_svg(points: &[SpiralPoint], color: &str) -> String {
+    to_svg_with_theme(points, color, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, the curve runs along it from the
+/// first point to the last in place of `color`.
+pub fn to_svg_with_theme(points: &[SpiralPoint], color: &str, theme: &Theme) -> String {
     if points.is_empty() {
         return String::from(r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="800"></svg>"#);
     }
@@ -121,23 +168,30 @@ pub fn to_svg(points: &[SpiralPoint], color: &str) -> String {
     let _cy = size / 2.0;
 
     let hs = size / 2.0;
-    let sw = size / 400.0;
+    let sw = theme.stroke_width(size / 400.0);
     let mut svg = format!(
         "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"800\" height=\"800\" viewBox=\"{} {} {} {}\">\
-         <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#0a0a1a\"/>\
-         <polyline points=\"",
+         <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>{}",
         -hs, -hs, size, size,
         -hs, -hs, size, size,
+        theme.background_css(),
+        theme.foreground_start(),
     );
 
-    for p in points {
-        svg.push_str(&format!("{:.2},{:.2} ", p.x, p.y));
+    // A palette is followed in 64 steps, each piece sharing its last point
+    // with the next so the curve stays joined
+    let pieces = if theme.palette.is_some() { 64 } else { 1 };
+    let chunk = points.len().div_ceil(pieces).max(1);
+    for (k, start) in (0..points.len()).step_by(chunk).enumerate() {
+        let end = (start + chunk + 1).min(points.len());
+        let coords: String = points[start..end].iter().map(|p| format!("{:.2},{:.2} ", p.x, p.y)).collect();
+        svg.push_str(&format!(
+            "<polyline points=\"{coords}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{sw}\" stroke-linecap=\"round\" opacity=\"0.9\"/>",
+            theme.color(theme::place(k, points.len().div_ceil(chunk)), color)
+        ));
     }
-    svg.push_str(&format!(
-        "\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\" opacity=\"0.9\"/>\
-         </svg>",
-        color, sw
-    ));
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
     svg
 }
 
@@ -147,19 +201,19 @@ mod tests {
 
     #[test]
     fn test_logarithmic_spiral_grows() {
-        let points = generate_spiral(
+        let points = checked_generate_spiral(
             SpiralType::Logarithmic { a: 1.0, b: 0.15 },
             100, 6.0 * PI,
-        );
+        ).unwrap();
         assert!(points.last().unwrap().r > points[1].r);
     }
 
     #[test]
     fn test_archimedean_linear_growth() {
-        let points = generate_spiral(
+        let points = checked_generate_spiral(
             SpiralType::Archimedean { a: 0.0, b: 1.0 },
             100, 4.0 * PI,
-        );
+        ).unwrap();
         // Radius should grow linearly with theta
         let mid = &points[50];
         let end = &points[99];
@@ -170,10 +224,10 @@ mod tests {
 
     #[test]
     fn test_fermat_sqrt_growth() {
-        let points = generate_spiral(
+        let points = checked_generate_spiral(
             SpiralType::Fermat { a: 1.0 },
             100, 16.0 * PI,
-        );
+        ).unwrap();
         // r should be proportional to √θ
         let p = &points[50];
         assert!((p.r - p.theta.sqrt()).abs() < 0.1);
@@ -181,52 +235,71 @@ mod tests {
 
     #[test]
     fn test_golden_spiral_ratio() {
-        let points = generate_spiral(
+        let points = checked_generate_spiral(
             SpiralType::Golden { a: 1.0 },
             1000, 4.0 * PI,
-        );
+        ).unwrap();
         let fitness = golden_spiral_fitness(&points);
         assert!(fitness > 0.95, "Golden spiral should match itself: {}", fitness);
     }
 
     #[test]
     fn test_helix_constant_radius() {
-        let points = generate_spiral(
+        let points = checked_generate_spiral(
             SpiralType::Helix { radius: 5.0, pitch: 1.0 },
             100, 4.0 * PI,
-        );
+        ).unwrap();
         for p in &points {
             assert!((p.r - 5.0).abs() < 1e-10);
         }
     }
 
+    #[test]
+    fn test_checked_spiral_rejects_bad_input() {
+        let golden = SpiralType::Golden { a: 1.0 };
+        assert_eq!(checked_generate_spiral(golden, 50, 2.0 * PI).unwrap().len(), 50);
+        assert!(matches!(checked_generate_spiral(golden, 0, 2.0 * PI), Err(crate::MathaturaError::Empty { name: "points" })));
+        assert!(checked_generate_spiral(golden, 50, f64::NAN).is_err());
+        assert!(checked_generate_spiral(golden, MAX_POINTS + 1, 2.0 * PI).is_err());
+    }
+
     #[test]
     fn test_arc_length_positive() {
-        let points = generate_spiral(
+        let points = checked_generate_spiral(
             SpiralType::Logarithmic { a: 1.0, b: 0.1 },
             100, 4.0 * PI,
-        );
+        ).unwrap();
         assert!(arc_length(&points) > 0.0);
     }
 
     #[test]
     fn test_curvature_length() {
-        let points = generate_spiral(
+        let points = checked_generate_spiral(
             SpiralType::Logarithmic { a: 1.0, b: 0.1 },
             100, 4.0 * PI,
-        );
+        ).unwrap();
         let k = curvature(&points);
         assert_eq!(k.len(), 98); // n-2 points
     }
 
     #[test]
     fn test_spiral_svg() {
-        let points = generate_spiral(
+        let points = checked_generate_spiral(
             SpiralType::Golden { a: 1.0 },
             50, 4.0 * PI,
-        );
+        ).unwrap();
         let svg = to_svg(&points, "#ffd700");
         assert!(svg.contains("<svg"));
         assert!(svg.contains("polyline"));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let kind = SpiralType::Logarithmic { a: 1.0, b: 0.2 };
+        let json = serde_json::to_string(&kind).unwrap();
+        assert_eq!(json, r#"{"Logarithmic":{"a":1.0,"b":0.2}}"#);
+        let back: SpiralType = serde_json::from_str(&json).unwrap();
+        assert!(matches!(back, SpiralType::Logarithmic { a, b } if a == 1.0 && b == 0.2));
+    }
 }
@@ -0,0 +1,251 @@
+//! Spirographs — wheels rolling inside and outside rings.
+//!
+//! A pen at distance d from the center of a wheel of radius r, rolling
+//! around a fixed ring of radius R, traces
+//!
+//!   hypotrochoid (inside):  x = (R-r)cos t + d cos((R-r)t/r)
+//!                           y = (R-r)sin t - d sin((R-r)t/r)
+//!   epitrochoid (outside):  x = (R+r)cos t - d cos((R+r)t/r)
+//!                           y = (R+r)sin t - d sin((R+r)t/r)
+//!
+//! With integer radii the curve closes after the wheel has turned
+//! r / gcd(R, r) times around the ring, drawing R / gcd(R, r) lobes.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::{self, Theme};
+use super::fractals::Point;
+
+/// Whether the wheel rolls inside or outside the ring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Kind {
+    Hypotrochoid,
+    Epitrochoid,
+}
+
+/// Spirograph gear setup.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spirograph {
+    pub kind: Kind,
+    /// Fixed ring radius R (teeth)
+    pub ring: u32,
+    /// Rolling wheel radius r (teeth)
+    pub wheel: u32,
+    /// Pen distance d from the wheel center
+    pub pen: f64,
+}
+
+impl Default for Spirograph {
+    fn default() -> Self {
+        Self { kind: Kind::Hypotrochoid, ring: 96, wheel: 36, pen: 24.0 }
+    }
+}
+
+/// Samples per trip around the ring used by [`Generator::generate`].
+pub const SAMPLES_PER_REVOLUTION: usize = 720;
+
+/// Most points a closed trace may take before [`Spirograph::validate`] rejects the gears.
+pub const MAX_SAMPLES: usize = 2_000_000;
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+impl Spirograph {
+    /// Reject empty gears and setups that only close after an enormous trace.
+    pub fn validate(&self) -> Result<()> {
+        check_positive("ring", self.ring as f64)?;
+        check_positive("wheel", self.wheel as f64)?;
+        let samples = SAMPLES_PER_REVOLUTION.saturating_mul(self.revolutions() as usize);
+        check_count("samples", samples, MAX_SAMPLES)
+    }
+
+    /// Number of trips around the ring before the pattern closes.
+    pub fn revolutions(&self) -> u32 {
+        let g = gcd(self.ring, self.wheel).max(1);
+        self.wheel.max(1) / g
+    }
+
+    /// Number of lobes (cusps or loops) in the closed figure.
+    pub fn lobes(&self) -> u32 {
+        let g = gcd(self.ring, self.wheel).max(1);
+        self.ring / g
+    }
+
+    /// Parameter range needed to close the curve: 2π × lcm(R, r) / R.
+    pub fn period(&self) -> f64 {
+        2.0 * PI * self.revolutions() as f64
+    }
+
+    /// Pen position at parameter t.
+    pub fn position(&self, t: f64) -> Point {
+        let big = self.ring as f64;
+        let small = self.wheel.max(1) as f64;
+        match self.kind {
+            Kind::Hypotrochoid => {
+                let k = (big - small) / small;
+                Point {
+                    x: (big - small) * t.cos() + self.pen * (k * t).cos(),
+                    y: (big - small) * t.sin() - self.pen * (k * t).sin(),
+                }
+            }
+            Kind::Epitrochoid => {
+                let k = (big + small) / small;
+                Point {
+                    x: (big + small) * t.cos() - self.pen * (k * t).cos(),
+                    y: (big + small) * t.sin() - self.pen * (k * t).sin(),
+                }
+            }
+        }
+    }
+
+    /// Trace the closed figure with `points_per_revolution` samples per trip around the ring.
+    pub fn trace(&self, points_per_revolution: usize) -> Vec<Point> {
+        let n = points_per_revolution.max(3) * self.revolutions() as usize;
+        let period = self.period();
+        (0..=n).map(|i| self.position(period * i as f64 / n as f64)).collect()
+    }
+
+    /// Trace the same gears with several pen holes, as when layering inks.
+    pub fn layers(&self, pens: &[f64], points_per_revolution: usize) -> Vec<Vec<Point>> {
+        pens.iter()
+            .map(|&pen| Spirograph { pen, ..*self }.trace(points_per_revolution))
+            .collect()
+    }
+}
+
+impl Generator for Spirograph {
+    /// Nothing for gears that fail [`Spirograph::validate`]; see
+    /// [`try_generate`](Generator::try_generate).
+    fn generate(&self) -> Geometry {
+        self.try_generate().unwrap_or(Geometry::Polylines(Vec::new()))
+    }
+
+    fn try_generate(&self) -> Result<Geometry> {
+        self.validate()?;
+        Ok(Geometry::Polylines(vec![self.trace(SAMPLES_PER_REVOLUTION)]))
+    }
+}
+
+/// Render traces as layered, multi-colored strokes.
+pub fn to_svg(layers: &[Vec<Point>]) -> String {
+    to_svg_with_theme(layers, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, layers take it in turn.
+pub fn to_svg_with_theme(layers: &[Vec<Point>], theme: &Theme) -> String {
+    let size = 800.0;
+    let extent = layers
+        .iter()
+        .flatten()
+        .map(|p| p.x.abs().max(p.y.abs()))
+        .fold(1e-9, f64::max);
+    let scale = (size / 2.0 - 20.0) / extent;
+    let c = size / 2.0;
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
+    );
+    let palette = crate::render::golden_hues_cycling(layers.len(), 200.0, &[80.0], &[60.0]);
+    for (i, (layer, color)) in layers.iter().zip(&palette).enumerate() {
+        let coords: Vec<String> = layer
+            .iter()
+            .map(|p| format!("{:.1},{:.1}", c + p.x * scale, c - p.y * scale))
+            .collect();
+        svg.push_str(&format!(
+            r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" opacity="0.85"/>
+"##,
+            coords.join(" "),
+            theme.color(theme::place(i, layers.len()), color.as_str()),
+            theme.stroke_width(1.0)
+        ));
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closure_detection() {
+        let s = Spirograph { ring: 96, wheel: 36, ..Default::default() };
+        // gcd(96, 36) = 12 → 3 revolutions, 8 lobes
+        assert_eq!(s.revolutions(), 3);
+        assert_eq!(s.lobes(), 8);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Spirograph::default().validate().is_ok());
+        assert!(Spirograph { ring: 0, ..Default::default() }.validate().is_err());
+        assert!(Spirograph { wheel: 0, ..Default::default() }.validate().is_err());
+        // Coprime radii close only after 99,991 revolutions
+        let slow = Spirograph { ring: 100_000, wheel: 99_991, ..Default::default() };
+        assert!(slow.validate().is_err());
+        assert!(matches!(slow.generate(), Geometry::Polylines(ref lines) if lines.is_empty()));
+        assert!(slow.try_generate().is_err());
+        assert!(Spirograph::default().try_generate().is_ok());
+    }
+
+    #[test]
+    fn test_trace_closes() {
+        for kind in [Kind::Hypotrochoid, Kind::Epitrochoid] {
+            let s = Spirograph { kind, ring: 105, wheel: 30, pen: 20.0 };
+            let pts = s.trace(200);
+            let (a, b) = (pts[0], pts[pts.len() - 1]);
+            assert!((a.x - b.x).abs() < 1e-6 && (a.y - b.y).abs() < 1e-6, "{:?} not closed", kind);
+        }
+    }
+
+    #[test]
+    fn test_hypocycloid_cusps_on_ring() {
+        // Pen on the rim (d = r) gives a hypocycloid whose cusps touch the ring
+        let s = Spirograph { kind: Kind::Hypotrochoid, ring: 60, wheel: 20, pen: 20.0 };
+        let max_r = s.trace(500).iter().map(|p| p.x.hypot(p.y)).fold(0.0, f64::max);
+        assert!((max_r - 60.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_hypotrochoid_bounds() {
+        let s = Spirograph::default();
+        let limit = (s.ring - s.wheel) as f64 + s.pen;
+        for p in s.trace(300) {
+            assert!(p.x.hypot(p.y) <= limit + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_epitrochoid_outside_ring() {
+        let s = Spirograph { kind: Kind::Epitrochoid, ring: 50, wheel: 10, pen: 5.0 };
+        let min_r = s.trace(300).iter().map(|p| p.x.hypot(p.y)).fold(f64::INFINITY, f64::min);
+        assert!(min_r >= 55.0 - 1e-9);
+    }
+
+    #[test]
+    fn test_lobe_symmetry() {
+        use crate::analysis::rotational_order;
+        let s = Spirograph { ring: 70, wheel: 21, pen: 15.0, ..Default::default() };
+        let pts = s.trace(400);
+        assert_eq!(rotational_order(&pts[..pts.len() - 1], 12).0, s.lobes() as usize);
+    }
+
+    #[test]
+    fn test_layers_svg() {
+        let s = Spirograph::default();
+        let layers = s.layers(&[10.0, 20.0, 30.0], 200);
+        assert_eq!(layers.len(), 3);
+        let svg = to_svg(&layers);
+        assert_eq!(svg.matches("<polyline").count(), 3);
+    }
+}
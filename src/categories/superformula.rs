@@ -0,0 +1,231 @@
+//! Gielis superformula — one equation for starfish, flowers, diatoms, and leaves.
+//!
+//!   r(φ) = ( |cos(mφ/4) / a|^n₂ + |sin(mφ/4) / b|^n₃ )^(-1/n₁)
+//!
+//! Johan Gielis (2003) showed that this generalization of the superellipse
+//! describes a remarkable range of natural outlines: m sets the rotational
+//! symmetry, while n₁, n₂, n₃ bend the sides between pinched and bloated.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::Theme;
+use super::fractals::Point;
+
+/// Parameters of the superformula.
+///
+/// The shape is m-fold symmetric when n₂ = n₃ and a = b; otherwise the
+/// cosine and sine terms alternate and the symmetry halves to m/2.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Superformula {
+    /// Rotational symmetry (number of lobes for integer m)
+    pub m: f64,
+    pub n1: f64,
+    pub n2: f64,
+    pub n3: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl Default for Superformula {
+    /// The unit circle (m = 0).
+    fn default() -> Self {
+        Self { m: 0.0, n1: 1.0, n2: 1.0, n3: 1.0, a: 1.0, b: 1.0 }
+    }
+}
+
+/// Natural shapes reproduced by the superformula.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Five sharp arms
+    Starfish,
+    /// Six rounded petals
+    Flower,
+    /// Rounded-square diatom frustule
+    Diatom,
+    /// Teardrop leaf blade
+    Leaf,
+}
+
+impl Preset {
+    pub fn params(self) -> Superformula {
+        match self {
+            Preset::Starfish => Superformula { m: 5.0, n1: 0.3, n2: 0.3, n3: 0.3, a: 1.0, b: 1.0 },
+            Preset::Flower => Superformula { m: 6.0, n1: 1.0, n2: 7.0, n3: 7.0, a: 1.0, b: 1.0 },
+            Preset::Diatom => Superformula { m: 4.0, n1: 12.0, n2: 15.0, n3: 15.0, a: 1.0, b: 1.0 },
+            Preset::Leaf => Superformula { m: 1.0, n1: 0.5, n2: 0.5, n3: 0.5, a: 1.0, b: 1.0 },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Starfish => "Starfish",
+            Preset::Flower => "Flower",
+            Preset::Diatom => "Diatom",
+            Preset::Leaf => "Leaf",
+        }
+    }
+}
+
+impl Superformula {
+    /// Radius at angle φ. Returns 0 where the formula is undefined.
+    pub fn radius(&self, phi: f64) -> f64 {
+        let t = self.m * phi / 4.0;
+        let c = (t.cos() / self.a).abs().powf(self.n2);
+        let s = (t.sin() / self.b).abs().powf(self.n3);
+        let r = (c + s).powf(-1.0 / self.n1);
+        if r.is_finite() { r } else { 0.0 }
+    }
+
+    /// Linear interpolation between two parameter sets (t = 0 → self, t = 1 → other).
+    pub fn lerp(&self, other: &Superformula, t: f64) -> Superformula {
+        let mix = |x: f64, y: f64| x + (y - x) * t;
+        Superformula {
+            m: mix(self.m, other.m),
+            n1: mix(self.n1, other.n1),
+            n2: mix(self.n2, other.n2),
+            n3: mix(self.n3, other.n3),
+            a: mix(self.a, other.a),
+            b: mix(self.b, other.b),
+        }
+    }
+}
+
+/// Sample the closed outline over φ ∈ [0, 2π).
+pub fn outline(shape: &Superformula, num_points: usize) -> Vec<Point> {
+    (0..num_points)
+        .map(|i| {
+            let phi = 2.0 * PI * i as f64 / num_points as f64;
+            let r = shape.radius(phi);
+            Point { x: r * phi.cos(), y: r * phi.sin() }
+        })
+        .collect()
+}
+
+/// Outlines morphing from one shape to another over `frames` steps (inclusive of both ends).
+///
+/// Non-integer intermediate m values do not close exactly over 2π, so
+/// in-between frames can show a small seam at φ = 0.
+pub fn morph(from: &Superformula, to: &Superformula, frames: usize, num_points: usize) -> Vec<Vec<Point>> {
+    let frames = frames.max(2);
+    (0..frames)
+        .map(|i| {
+            let t = i as f64 / (frames - 1) as f64;
+            outline(&from.lerp(to, t), num_points)
+        })
+        .collect()
+}
+
+impl Generator for Superformula {
+    fn generate(&self) -> Geometry {
+        Geometry::Polygons(vec![outline(self, 720)])
+    }
+}
+
+/// Render an outline as a filled SVG shape, auto-scaled to fit.
+pub fn to_svg(points: &[Point], hue: f64) -> String {
+    to_svg_with_theme(points, hue, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, the outline takes its last stop
+/// and the fill a stop short of it in place of `hue`.
+pub fn to_svg_with_theme(points: &[Point], hue: f64, theme: &Theme) -> String {
+    let size = 800.0;
+    let extent = points.iter().map(|p| p.x.abs().max(p.y.abs())).fold(1e-9, f64::max);
+    let scale = (size / 2.0 - 40.0) / extent;
+    let c = size / 2.0;
+    let coords: Vec<String> = points
+        .iter()
+        .map(|p| format!("{:.1},{:.1}", c + p.x * scale, c - p.y * scale))
+        .collect();
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}<polygon points="{}" fill="{}" fill-opacity="0.8" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>
+{}</svg>"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        coords.join(" "),
+        theme.color(0.6, crate::render::hsl(hue, 70.0, 50.0)),
+        theme.color(1.0, crate::render::hsl(hue, 80.0, 75.0)),
+        theme.stroke_width(2.0),
+        theme.foreground_end()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_unit_circle() {
+        let shape = Superformula::default();
+        for p in outline(&shape, 64) {
+            assert!(((p.x * p.x + p.y * p.y).sqrt() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_square_superellipse() {
+        // m=4, n1=n2=n3=2 is the unit circle; large n gives a near-square
+        let circle = Superformula { m: 4.0, n1: 2.0, n2: 2.0, n3: 2.0, a: 1.0, b: 1.0 };
+        assert!((circle.radius(0.3) - 1.0).abs() < 1e-12);
+        let square = Superformula { m: 4.0, n1: 100.0, n2: 100.0, n3: 100.0, a: 1.0, b: 1.0 };
+        // At 45° a square of half-width 1 reaches radius √2
+        assert!((square.radius(PI / 4.0) - 2.0_f64.sqrt()).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_starfish_five_lobes() {
+        let shape = Preset::Starfish.params();
+        let pts = outline(&shape, 1000);
+        let radii: Vec<f64> = pts.iter().map(|p| (p.x * p.x + p.y * p.y).sqrt()).collect();
+        let n = radii.len();
+        let peaks = (0..n)
+            .filter(|&i| radii[i] > radii[(i + n - 1) % n] && radii[i] >= radii[(i + 1) % n])
+            .count();
+        assert_eq!(peaks, 5);
+    }
+
+    #[test]
+    fn test_rotational_symmetry_matches_m() {
+        use crate::analysis::rotational_order;
+        let pts = outline(&Preset::Flower.params(), 360);
+        assert_eq!(rotational_order(&pts, 12).0, 6);
+    }
+
+    #[test]
+    fn test_lerp_endpoints() {
+        let a = Preset::Starfish.params();
+        let b = Preset::Flower.params();
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        let mid = a.lerp(&b, 0.5);
+        assert!((mid.m - 5.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_morph_frames() {
+        let frames = morph(&Preset::Leaf.params(), &Preset::Diatom.params(), 10, 100);
+        assert_eq!(frames.len(), 10);
+        assert!(frames.iter().all(|f| f.len() == 100));
+    }
+
+    #[test]
+    fn test_all_presets_finite() {
+        for preset in [Preset::Starfish, Preset::Flower, Preset::Diatom, Preset::Leaf] {
+            let pts = outline(&preset.params(), 500);
+            assert!(pts.iter().all(|p| p.x.is_finite() && p.y.is_finite()), "{}", preset.name());
+        }
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let svg = to_svg(&outline(&Preset::Flower.params(), 200), 330.0);
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("<polygon"));
+    }
+}
@@ -0,0 +1,298 @@
+//! Symmetry — rosettes, mandalas and the symmetry groups of living forms.
+//!
+//! A single motif drawn inside one wedge, copied n times around a center
+//! by rotations of 2π/n, has cyclic symmetry Cₙ — a pinwheel or a spiral
+//! flower. Reflecting the motif across the wedge's axis as well doubles the
+//! copies and gives dihedral symmetry Dₙ: the six mirror planes of a snow
+//! crystal, the five of a starfish. With n = 1 and a mirror the group is D₁,
+//! the bilateral symmetry of a leaf or a butterfly.
+//!
+//! Motifs are lists of polylines in a unit disc with the mirror axis along
+//! +x; [`motif_from_fn`] samples any parametric curve into one.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::Theme;
+use super::fractals::Point;
+
+/// The symmetry group applied to a motif.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SymmetryParams {
+    /// Number of rotated copies (1 = no rotation)
+    pub order: usize,
+    /// Also reflect each copy across its axis (dihedral rather than cyclic)
+    pub mirror: bool,
+}
+
+impl SymmetryParams {
+    /// Require an order from 1 to 360.
+    pub fn validate(&self) -> Result<()> {
+        check_range("order", self.order as f64, 1.0..=360.0, "1 to 360")
+    }
+
+    /// Number of copies of the motif: n, or 2n with mirrors.
+    pub fn copies(&self) -> usize {
+        self.order * if self.mirror { 2 } else { 1 }
+    }
+
+    /// Group name in Schoenflies-style notation, e.g. "D6" or "C5".
+    pub fn name(&self) -> String {
+        format!("{}{}", if self.mirror { "D" } else { "C" }, self.order)
+    }
+}
+
+/// Sample the curve `f(t)`, t ∈ [0, 1], at `samples` points into a motif polyline.
+pub fn motif_from_fn(f: impl Fn(f64) -> Point, samples: usize) -> Vec<Point> {
+    let n = samples.max(2);
+    (0..n).map(|i| f(i as f64 / (n - 1) as f64)).collect()
+}
+
+/// Copy a motif around the origin under the given symmetry.
+///
+/// The output lists the rotated copies in order, each followed by its
+/// mirror image when `mirror` is set.
+pub fn apply(motif: &[Vec<Point>], symmetry: &SymmetryParams) -> Vec<Vec<Point>> {
+    let order = symmetry.order.max(1);
+    let mut out = Vec::with_capacity(motif.len() * symmetry.copies());
+    for k in 0..order {
+        let (s, c) = (2.0 * PI * k as f64 / order as f64).sin_cos();
+        let rotate = |p: &Point, flip: f64| Point { x: p.x * c - flip * p.y * s, y: p.x * s + flip * p.y * c };
+        out.extend(motif.iter().map(|line| line.iter().map(|p| rotate(p, 1.0)).collect()));
+        if symmetry.mirror {
+            // Reflect across the x axis, then rotate
+            out.extend(motif.iter().map(|line| line.iter().map(|p| rotate(p, -1.0)).collect()));
+        }
+    }
+    out
+}
+
+/// Symmetric forms from nature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Dendritic snow crystal, D6
+    Snowflake,
+    /// Five-armed sea star, D5
+    Starfish,
+    /// Eight-petalled flower with a ring of sepals, D8
+    Flower,
+    /// Bilateral leaf with pinnate veins, D1
+    Leaf,
+}
+
+impl Preset {
+    pub fn symmetry(self) -> SymmetryParams {
+        match self {
+            Preset::Snowflake => SymmetryParams { order: 6, mirror: true },
+            Preset::Starfish => SymmetryParams { order: 5, mirror: true },
+            Preset::Flower => SymmetryParams { order: 8, mirror: true },
+            Preset::Leaf => SymmetryParams { order: 1, mirror: true },
+        }
+    }
+
+    /// The motif for one wedge, upper half only: the mirror supplies the rest.
+    pub fn motif(self) -> Vec<Vec<Point>> {
+        let p = |x: f64, y: f64| Point { x, y };
+        match self {
+            Preset::Snowflake => {
+                // Main arm with side branches at 60°, shrinking towards the tip
+                let mut lines = vec![vec![p(0.0, 0.0), p(1.0, 0.0)]];
+                let (s, c) = (PI / 3.0).sin_cos();
+                for (at, len) in [(0.25, 0.3), (0.45, 0.26), (0.65, 0.18), (0.82, 0.1)] {
+                    lines.push(vec![p(at, 0.0), p(at + len * c, len * s)]);
+                    // Secondary twigs on the longer branches
+                    if len > 0.2 {
+                        let (x, y) = (at + 0.5 * len * c, 0.5 * len * s);
+                        lines.push(vec![p(x, y), p(x + 0.1, y)]);
+                    }
+                }
+                // A hexagonal plate at the center
+                lines.push(vec![p(0.12, 0.0), p(0.06, 0.12 * s)]);
+                lines
+            }
+            Preset::Starfish => {
+                // Arm outline tapering to the tip, and the ambulacral groove
+                let outline = motif_from_fn(|t| {
+                    let r = 0.12 + 0.88 * t;
+                    let half_width = 0.2 * (1.0 - t).powf(0.8) + 0.01;
+                    p(r, half_width.min(r * (PI / 5.0).tan()))
+                }, 40);
+                let feet = (1..8).map(|i| {
+                    let r = 0.15 + 0.1 * i as f64;
+                    vec![p(r, 0.03), p(r, 0.06 * (1.0 - r) + 0.03)]
+                });
+                let mut lines = vec![outline, vec![p(0.0, 0.0), p(0.96, 0.0)]];
+                lines.extend(feet);
+                lines
+            }
+            Preset::Flower => {
+                let petal = motif_from_fn(|t| p(0.15 + 0.85 * t, 0.28 * (PI * t).sin() * (1.0 - 0.3 * t)), 40);
+                let sepal = motif_from_fn(|t| {
+                    let (s, c) = (PI / 8.0).sin_cos();
+                    let (x, y) = (0.1 + 0.45 * t, 0.12 * (PI * t).sin());
+                    p(x * c - y * s, x * s + y * c)
+                }, 20);
+                let center = motif_from_fn(|t| p(0.12 * (t * PI / 8.0).cos(), 0.12 * (t * PI / 8.0).sin()), 6);
+                vec![petal, sepal, center, vec![p(0.15, 0.0), p(0.8, 0.0)]]
+            }
+            Preset::Leaf => {
+                let blade = motif_from_fn(|t| p(2.0 * t - 1.0, 0.42 * (PI * t).sin() * (1.0 - 0.35 * t)), 50);
+                let mut lines = vec![blade, vec![p(-1.2, 0.0), p(1.0, 0.0)]];
+                for i in 1..8 {
+                    let t = i as f64 / 8.0;
+                    let x = 2.0 * t - 1.0;
+                    let reach = 0.38 * (PI * (t + 0.06)).sin() * (1.0 - 0.35 * t);
+                    lines.push(vec![p(x, 0.0), p(x + 0.6 * reach, 0.85 * reach)]);
+                }
+                lines
+            }
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Snowflake => "Snowflake",
+            Preset::Starfish => "Starfish",
+            Preset::Flower => "Flower",
+            Preset::Leaf => "Leaf",
+        }
+    }
+
+    /// Base hue for rendering.
+    fn hue(self) -> f64 {
+        match self {
+            Preset::Snowflake => 195.0,
+            Preset::Starfish => 15.0,
+            Preset::Flower => 320.0,
+            Preset::Leaf => 110.0,
+        }
+    }
+}
+
+/// A motif and the symmetry that copies it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mandala {
+    pub motif: Vec<Vec<Point>>,
+    pub symmetry: SymmetryParams,
+}
+
+impl From<Preset> for Mandala {
+    fn from(preset: Preset) -> Self {
+        Mandala { motif: preset.motif(), symmetry: preset.symmetry() }
+    }
+}
+
+impl Generator for Mandala {
+    fn generate(&self) -> Geometry {
+        Geometry::Polylines(apply(&self.motif, &self.symmetry))
+    }
+}
+
+/// Render symmetric lines in a disc, the first motif pointing up. Mirror
+/// images are drawn in a lighter shade so the group's structure shows.
+pub fn to_svg(lines: &[Vec<Point>], symmetry: &SymmetryParams, hue: f64) -> String {
+    to_svg_with_theme(lines, symmetry, hue, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`; with a palette, motifs take its last stop and
+/// mirror images its middle.
+pub fn to_svg_with_theme(lines: &[Vec<Point>], symmetry: &SymmetryParams, hue: f64, theme: &Theme) -> String {
+    let size = 800.0;
+    let extent = lines.iter().flatten().map(|p| p.x.hypot(p.y)).fold(1e-9, f64::max);
+    let scale = (size / 2.0 - 30.0) / extent;
+    let c = size / 2.0;
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">
+<rect width="{size}" height="{size}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
+    );
+    let per_copy = lines.len() / symmetry.copies().max(1);
+    for (i, line) in lines.iter().enumerate() {
+        let mirrored = symmetry.mirror && (i / per_copy.max(1)) % 2 == 1;
+        // Rotate a quarter turn so +x points up the page
+        let points: Vec<String> = line.iter().map(|p| format!("{:.1},{:.1}", c + p.y * scale, c - p.x * scale)).collect();
+        svg.push_str(&format!(
+            r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"/>
+"##,
+            points.join(" "),
+            theme.color(if mirrored { 0.5 } else { 1.0 }, crate::render::hsl(hue, 70.0, if mirrored { 75.0 } else { 58.0 })),
+            theme.stroke_width(1.6)
+        ));
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Render a preset mandala, optionally with a different symmetry.
+pub fn preset_to_svg(preset: Preset, symmetry: &SymmetryParams) -> String {
+    preset_to_svg_with_theme(preset, symmetry, &Theme::dark())
+}
+
+/// [`preset_to_svg`] in `theme`.
+pub fn preset_to_svg_with_theme(preset: Preset, symmetry: &SymmetryParams, theme: &Theme) -> String {
+    to_svg_with_theme(&apply(&preset.motif(), symmetry), symmetry, preset.hue(), theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis;
+
+    fn points(lines: &[Vec<Point>]) -> Vec<Point> {
+        lines.iter().flatten().copied().collect()
+    }
+
+    #[test]
+    fn test_copies() {
+        let motif = vec![vec![Point { x: 0.5, y: 0.1 }, Point { x: 1.0, y: 0.2 }]];
+        let cyclic = apply(&motif, &SymmetryParams { order: 4, mirror: false });
+        assert_eq!(cyclic.len(), 4);
+        // A quarter turn maps (1, 0.2) to (−0.2, 1)
+        assert!((cyclic[1][1].x + 0.2).abs() < 1e-12 && (cyclic[1][1].y - 1.0).abs() < 1e-12);
+        let dihedral = apply(&motif, &SymmetryParams { order: 4, mirror: true });
+        assert_eq!(dihedral.len(), 8);
+        assert_eq!(dihedral[1][0], Point { x: 0.5, y: -0.1 });
+    }
+
+    #[test]
+    fn test_presets_have_their_symmetry() {
+        for preset in [Preset::Snowflake, Preset::Starfish, Preset::Flower] {
+            let symmetry = preset.symmetry();
+            let shape = points(&apply(&preset.motif(), &symmetry));
+            let (order, score) = analysis::rotational_order(&shape, 12);
+            assert_eq!(order, symmetry.order, "{} scored {}", preset.name(), score);
+            assert!(analysis::mirror_scores(&shape, 1)[0].1 > 0.9, "{}", preset.name());
+        }
+    }
+
+    #[test]
+    fn test_bilateral_leaf() {
+        let shape = points(&apply(&Preset::Leaf.motif(), &Preset::Leaf.symmetry()));
+        assert!(!Mandala::from(Preset::Leaf).generate().is_empty());
+        assert_eq!(analysis::rotational_order(&shape, 8).0, 1);
+        // Mirrored across the midrib, not across the perpendicular
+        let scores = analysis::mirror_scores(&shape, 2);
+        assert!(scores[0].1 > 0.9 && scores[1].1 < 0.5, "{:?}", scores);
+    }
+
+    #[test]
+    fn test_closure_motif_and_svg() {
+        let spoke = motif_from_fn(|t| Point { x: t, y: 0.2 * t * t }, 10);
+        assert_eq!(spoke.len(), 10);
+        assert_eq!(spoke[9], Point { x: 1.0, y: 0.2 });
+        let symmetry = SymmetryParams { order: 7, mirror: false };
+        assert_eq!(symmetry.name(), "C7");
+        let svg = to_svg(&apply(&[spoke], &symmetry), &symmetry, 40.0);
+        assert_eq!(svg.matches("<polyline").count(), 7);
+        assert!(SymmetryParams { order: 0, mirror: true }.validate().is_err());
+        assert!(preset_to_svg(Preset::Snowflake, &Preset::Snowflake.symmetry()).contains("hsl(195"));
+    }
+}
@@ -0,0 +1,414 @@
+//! Terrain — fractional Brownian motion mountains and coastlines.
+//!
+//! Real relief is statistically self-similar: zoom into a ridge and smaller
+//! ridges appear. Fractional Brownian motion imitates this by summing octaves
+//! of smooth gradient noise, each at a higher frequency and lower amplitude,
+//!   h(x, y) = Σₖ pᵏ n(λᵏ x, λᵏ y)
+//! where λ is the lacunarity (frequency step) and p the persistence (gain).
+//! With p = λ^(−H) the surface has Hurst exponent H; rougher coasts come from
+//! larger p. Flooding the surface to a sea level carves coastlines whose
+//! fractal dimension echoes Richardson's measurements of Britain.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, check_range, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::colormap::Colormap;
+use crate::render::theme::Theme;
+use crate::parallel::map_range;
+use super::fractals::SimpleRng;
+
+/// Parameters for fBm terrain.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TerrainParams {
+    /// Number of noise layers summed
+    pub octaves: u32,
+    /// Frequency multiplier between octaves
+    pub lacunarity: f64,
+    /// Amplitude multiplier between octaves
+    pub persistence: f64,
+    /// Noise features across the map at the base octave
+    pub scale: f64,
+    /// Fold each octave into sharp ridges (1 − |n|)
+    pub ridged: bool,
+    /// How strongly the map edges are pushed below the sea (0 = not at all)
+    pub falloff: f64,
+    /// Water line as a fraction of the height range
+    pub sea_level: f64,
+}
+
+impl Default for TerrainParams {
+    fn default() -> Self {
+        Preset::Mountains.params()
+    }
+}
+
+impl TerrainParams {
+    /// Require 1–16 octaves, a lacunarity above 1, a persistence in (0, 1],
+    /// and a sea level inside the normalized height range.
+    pub fn validate(&self) -> Result<()> {
+        check_count("octaves", self.octaves as usize, 16)?;
+        check_range("lacunarity", self.lacunarity, 1.0 + f64::EPSILON..=16.0, "1 < lacunarity ≤ 16")?;
+        check_range("persistence", self.persistence, f64::MIN_POSITIVE..=1.0, "0 < persistence ≤ 1")?;
+        check_positive("scale", self.scale)?;
+        check_range("falloff", self.falloff, 0.0..=f64::MAX, "a non-negative number")?;
+        check_range("sea_level", self.sea_level, 0.0..=1.0, "0 to 1")
+    }
+}
+
+/// Presets for typical landscapes.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Ridged alpine relief with snowy peaks
+    Mountains,
+    /// Islands and ragged coastlines fading into open sea
+    Archipelago,
+    /// Gentle rolling lowland
+    Hills,
+}
+
+impl Preset {
+    pub fn params(self) -> TerrainParams {
+        match self {
+            Preset::Mountains => TerrainParams {
+                octaves: 7,
+                lacunarity: 2.0,
+                persistence: 0.5,
+                scale: 3.0,
+                ridged: true,
+                falloff: 0.0,
+                sea_level: 0.15,
+            },
+            Preset::Archipelago => TerrainParams {
+                octaves: 7,
+                lacunarity: 2.0,
+                persistence: 0.55,
+                scale: 5.0,
+                ridged: false,
+                falloff: 0.4,
+                sea_level: 0.6,
+            },
+            Preset::Hills => TerrainParams {
+                octaves: 4,
+                lacunarity: 2.0,
+                persistence: 0.35,
+                scale: 2.5,
+                ridged: false,
+                falloff: 0.0,
+                sea_level: 0.05,
+            },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Mountains => "Mountains",
+            Preset::Archipelago => "Archipelago",
+            Preset::Hills => "Rolling Hills",
+        }
+    }
+}
+
+/// Seeded 2D gradient (Perlin) noise.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Noise {
+    perm: Vec<usize>,
+}
+
+impl Noise {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = SimpleRng::new(seed);
+        let mut perm: Vec<usize> = (0..256).collect();
+        for i in (1..256).rev() {
+            perm.swap(i, rng.next_usize(i + 1));
+        }
+        perm.extend_from_within(..);
+        Noise { perm }
+    }
+
+    fn gradient(&self, ix: i64, iy: i64) -> (f64, f64) {
+        let h = self.perm[self.perm[(ix & 255) as usize] + (iy & 255) as usize];
+        let angle = h as f64 * 2.0 * PI / 256.0;
+        (angle.cos(), angle.sin())
+    }
+
+    /// Noise value at (x, y), roughly in [−1, 1] and zero on integer points.
+    pub fn get(&self, x: f64, y: f64) -> f64 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (ix, iy) = (x0 as i64, y0 as i64);
+        let dot = |dx: i64, dy: i64| {
+            let (gx, gy) = self.gradient(ix + dx, iy + dy);
+            gx * (fx - dx as f64) + gy * (fy - dy as f64)
+        };
+        // Quintic fade keeps the second derivative continuous
+        let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let (u, v) = (fade(fx), fade(fy));
+        let top = dot(0, 0) + u * (dot(1, 0) - dot(0, 0));
+        let bottom = dot(0, 1) + u * (dot(1, 1) - dot(0, 1));
+        core::f64::consts::SQRT_2 * (top + v * (bottom - top))
+    }
+
+    /// Fractional Brownian motion: octaves of noise, normalized by total amplitude.
+    pub fn fbm(&self, x: f64, y: f64, params: &TerrainParams) -> f64 {
+        let (mut sum, mut norm) = (0.0, 0.0);
+        let (mut freq, mut amp) = (1.0, 1.0);
+        for octave in 0..params.octaves.max(1) {
+            // Offset each octave so their zero lattices don't line up
+            let offset = octave as f64 * 17.31;
+            let n = self.get(x * freq + offset, y * freq - offset);
+            sum += amp * if params.ridged { 1.0 - 2.0 * n.abs() } else { n };
+            norm += amp;
+            freq *= params.lacunarity;
+            amp *= params.persistence;
+        }
+        sum / norm
+    }
+}
+
+/// A heightmap normalized to [0, 1].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Terrain {
+    pub width: usize,
+    pub height: usize,
+    pub heights: Vec<f64>,
+}
+
+impl Terrain {
+    /// Sample fBm over a `width × height` grid.
+    pub fn generate(width: usize, height: usize, params: &TerrainParams, seed: u64) -> Self {
+        let noise = Noise::new(seed);
+        let span = width.max(height).max(1) as f64;
+        let mut heights = map_range(width * height, |k| {
+            let (x, y) = ((k % width) as f64, (k / width) as f64);
+            let mut h = noise.fbm(x / span * params.scale, y / span * params.scale, params);
+            if params.falloff > 0.0 {
+                // Squared distance from the center, 1 at the middle of each edge
+                let dx = 2.0 * x / (width.max(2) - 1) as f64 - 1.0;
+                let dy = 2.0 * y / (height.max(2) - 1) as f64 - 1.0;
+                h -= params.falloff * (dx * dx + dy * dy);
+            }
+            h
+        });
+        let lo = heights.iter().cloned().fold(f64::INFINITY, f64::min);
+        let hi = heights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let range = (hi - lo).max(1e-12);
+        for h in &mut heights {
+            *h = (*h - lo) / range;
+        }
+        Terrain { width, height, heights }
+    }
+
+    /// Fraction of cells above the water line.
+    pub fn land_fraction(&self, sea_level: f64) -> f64 {
+        self.heights.iter().filter(|&&h| h > sea_level).count() as f64 / self.heights.len().max(1) as f64
+    }
+
+    /// Mean absolute height difference between horizontal neighbors.
+    pub fn roughness(&self) -> f64 {
+        let mut sum = 0.0;
+        for y in 0..self.height {
+            for x in 1..self.width {
+                sum += (self.heights[y * self.width + x] - self.heights[y * self.width + x - 1]).abs();
+            }
+        }
+        sum / (self.height * self.width.saturating_sub(1)).max(1) as f64
+    }
+}
+
+impl Generator for Terrain {
+    fn generate(&self) -> Geometry {
+        Geometry::Grid { cols: self.width, rows: self.height, values: self.heights.clone() }
+    }
+}
+
+/// Hypsometric tint: sea blues below the water line, then green lowland,
+/// tan upland, brown rock and snow.
+fn tint(h: f64, sea_level: f64) -> (f64, f64, f64) {
+    const SEA: [(f64, (f64, f64, f64)); 2] = [(0.0, (12.0, 38.0, 92.0)), (1.0, (70.0, 140.0, 190.0))];
+    const LAND: [(f64, (f64, f64, f64)); 6] = [
+        (0.0, (200.0, 190.0, 140.0)),
+        (0.05, (80.0, 140.0, 70.0)),
+        (0.35, (160.0, 170.0, 100.0)),
+        (0.6, (150.0, 115.0, 75.0)),
+        (0.85, (120.0, 100.0, 90.0)),
+        (0.95, (245.0, 245.0, 250.0)),
+    ];
+    let (stops, t): (&[_], f64) = if h <= sea_level {
+        (&SEA, h / sea_level.max(1e-12))
+    } else {
+        (&LAND, (h - sea_level) / (1.0 - sea_level).max(1e-12))
+    };
+    let k = stops.iter().rposition(|&(s, _)| s <= t).unwrap_or(0);
+    let (s0, c0) = stops[k];
+    let Some(&(s1, c1)) = stops.get(k + 1) else { return c0 };
+    let f = ((t - s0) / (s1 - s0)).clamp(0.0, 1.0);
+    (c0.0 + f * (c1.0 - c0.0), c0.1 + f * (c1.1 - c0.1), c0.2 + f * (c1.2 - c0.2))
+}
+
+/// Render the terrain as a shaded relief map with `contours` evenly spaced
+/// contour lines above the sea and a coastline.
+pub fn to_svg(terrain: &Terrain, sea_level: f64, contours: usize, cell: f64) -> String {
+    to_svg_with_theme(terrain, sea_level, contours, cell, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`, its palette standing in for the colormap of
+/// [`to_svg_with_colormap`] if it has one.
+pub fn to_svg_with_theme(terrain: &Terrain, sea_level: f64, contours: usize, cell: f64, theme: &Theme) -> String {
+    match &theme.palette {
+        Some(palette) => relief(terrain, sea_level, contours, cell, palette, theme),
+        None => render(terrain, sea_level, contours, cell, |band| tint(band, sea_level), theme),
+    }
+}
+
+/// [`to_svg`] with elevation, sea floor to summit, running along `colormap`.
+pub fn to_svg_with_colormap(terrain: &Terrain, sea_level: f64, contours: usize, cell: f64, colormap: &dyn Colormap) -> String {
+    relief(terrain, sea_level, contours, cell, colormap, &Theme::dark())
+}
+
+fn relief(terrain: &Terrain, sea_level: f64, contours: usize, cell: f64, colormap: &dyn Colormap, theme: &Theme) -> String {
+    let tint = |band| {
+        let [r, g, b] = colormap.rgb(band);
+        (f64::from(r), f64::from(g), f64::from(b))
+    };
+    render(terrain, sea_level, contours, cell, tint, theme)
+}
+
+fn render(terrain: &Terrain, sea_level: f64, contours: usize, cell: f64, tint: impl Fn(f64) -> (f64, f64, f64), theme: &Theme) -> String {
+    let (w, h) = (terrain.width, terrain.height);
+    // Light from the northwest, the cartographic convention
+    let shade = crate::render::hillshade(&terrain.heights, w, h, 40.0, -PI / 4.0, PI / 4.0);
+    let values: Vec<f64> = terrain
+        .heights
+        .iter()
+        .zip(&shade)
+        // Pack 32 height bands and 8 shade levels so runs can merge
+        .map(|(&z, &s)| {
+            let s = if z <= sea_level { 5.0 } else { (s * 7.0).round() };
+            (z * 31.0).round() * 8.0 + s
+        })
+        .collect();
+    let color = |v: f64| {
+        let (band, light) = ((v / 8.0).floor() / 31.0, (v % 8.0) / 7.0);
+        let (r, g, b) = tint(band);
+        let f = 0.35 + 0.75 * light;
+        format!("rgb({},{},{})", (r * f).min(255.0) as u8, (g * f).min(255.0) as u8, (b * f).min(255.0) as u8)
+    };
+    let raster = crate::render::raster_svg_with_theme(w, h, cell, &values, color, theme);
+
+    let mut overlay = String::new();
+    let levels: Vec<f64> =
+        (1..=contours).map(|i| sea_level + (1.0 - sea_level) * i as f64 / (contours + 1) as f64).collect();
+    for (_, lines) in crate::contour::contour_levels(&terrain.heights, w, h, &levels) {
+        overlay.push_str(&format!(
+            "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"0.45\" stroke-width=\"{}\"/>\n",
+            crate::contour::path_data(&lines, cell),
+            theme.color(0.0, "#3b2a1a"),
+            theme.stroke_width(0.6)
+        ));
+    }
+    let coast = crate::contour::marching_squares(&terrain.heights, w, h, sea_level);
+    overlay.push_str(&format!(
+        "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>\n",
+        crate::contour::path_data(&coast, cell),
+        theme.color(1.0, "#0d2340"),
+        theme.stroke_width(1.2)
+    ));
+    // Inside the foreground group, so the lines fade with the relief
+    let end = format!("{}</svg>", theme.foreground_end());
+    raster.replace(&end, &format!("{overlay}{end}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_zero_on_lattice() {
+        let noise = Noise::new(1);
+        for (x, y) in [(0.0, 0.0), (3.0, -2.0), (17.0, 5.0)] {
+            assert!(noise.get(x, y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_noise_bounded_and_continuous() {
+        let noise = Noise::new(2);
+        for i in 0..2000 {
+            let (x, y) = (i as f64 * 0.137, i as f64 * 0.071);
+            let n = noise.get(x, y);
+            assert!((-1.5..=1.5).contains(&n), "Noise out of range: {}", n);
+            assert!((noise.get(x + 1e-4, y) - n).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_deterministic_seed() {
+        let params = TerrainParams::default();
+        let a = Terrain::generate(32, 24, &params, 7);
+        let b = Terrain::generate(32, 24, &params, 7);
+        let c = Terrain::generate(32, 24, &params, 8);
+        assert_eq!(a.heights, b.heights);
+        assert_ne!(a.heights, c.heights);
+    }
+
+    #[test]
+    fn test_heights_normalized() {
+        let terrain = Terrain::generate(40, 30, &Preset::Hills.params(), 3);
+        assert_eq!(terrain.heights.len(), 1200);
+        let lo = terrain.heights.iter().cloned().fold(f64::INFINITY, f64::min);
+        let hi = terrain.heights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        assert!(lo.abs() < 1e-12 && (hi - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_persistence_adds_roughness() {
+        let smooth = TerrainParams { persistence: 0.3, ridged: false, ..Default::default() };
+        let rough = TerrainParams { persistence: 0.7, ..smooth };
+        let a = Terrain::generate(64, 64, &smooth, 4);
+        let b = Terrain::generate(64, 64, &rough, 4);
+        assert!(b.roughness() > a.roughness(), "{} vs {}", b.roughness(), a.roughness());
+    }
+
+    #[test]
+    fn test_falloff_floods_edges() {
+        let params = Preset::Archipelago.params();
+        let terrain = Terrain::generate(64, 64, &params, 5);
+        let land = terrain.land_fraction(params.sea_level);
+        assert!(land > 0.05 && land < 0.6, "Land fraction {}", land);
+        let corners = [0, 63, 64 * 63, 64 * 64 - 1];
+        assert!(corners.iter().all(|&k| terrain.heights[k] < params.sea_level));
+    }
+
+    #[test]
+    fn test_tint_ramps() {
+        let sea = tint(0.1, 0.3);
+        let snow = tint(1.0, 0.3);
+        assert!(sea.2 > sea.0, "Water should be blue");
+        assert!(snow.0 > 240.0 && snow.1 > 240.0);
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let params = Preset::Archipelago.params();
+        let terrain = Terrain::generate(48, 48, &params, 6);
+        let svg = to_svg(&terrain, params.sea_level, 5, 4.0);
+        assert!(svg.contains("<rect"));
+        assert_eq!(svg.matches("<path").count(), 6);
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_validate() {
+        for preset in [Preset::Mountains, Preset::Archipelago, Preset::Hills] {
+            assert!(preset.params().validate().is_ok());
+        }
+        assert!(TerrainParams { lacunarity: 1.0, ..Default::default() }.validate().is_err());
+        assert!(TerrainParams { octaves: 0, ..Default::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,738 @@
+//! Tessellations — honeycombs, Voronoi diagrams and the tilings of nature.
+//!
+//! Bees build hexagonal cells because, of all tilings of the plane by equal
+//! cells, the regular hexagon encloses a given area with the least wall
+//! (Hales' honeycomb theorem, 1999). Cells start out as soft wax cylinders
+//! and are drawn by surface tension into hexagons where three walls meet at
+//! 120°. Real combs are never perfect: here each wall junction is displaced
+//! by a `jitter` fraction of the cell size, so neighbouring cells still share
+//! walls exactly, and the walls themselves have a finite thickness.
+//!
+//! Cells are pointy-topped, laid out in rows offset by half a cell:
+//!   center(q, r) = s · (√3 (q + ½ (r mod 2)), 3r/2)
+//!
+//! When cells grow outward from scattered seeds at equal speed, they meet
+//! along the perpendicular bisectors between seeds: a Voronoi diagram. The
+//! patches of a giraffe's coat, the veins of a dragonfly's wing and the
+//! cracks of drying mud all approximate one. Here the diagram is built as
+//! the dual of a Bowyer–Watson Delaunay triangulation — each cell is its
+//! seed's bounding box cut by the bisectors with its Delaunay neighbours —
+//! from random, phyllotactic or Poisson-disc seeds.
+
+use crate::prelude::*;
+use crate::error::{check_count, check_positive, check_range, MathaturaError, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::theme::Theme;
+use super::fractals::{Point, SimpleRng};
+
+/// Parameters for a honeycomb.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HoneycombParams {
+    /// Distance from a cell's center to its corners
+    pub cell_size: f64,
+    /// Random displacement of wall junctions, as a fraction of the cell size
+    pub jitter: f64,
+    /// Wall thickness
+    pub wall: f64,
+    /// Width of the comb
+    pub width: f64,
+    /// Height of the comb
+    pub height: f64,
+}
+
+impl Default for HoneycombParams {
+    fn default() -> Self {
+        Preset::Apis.params()
+    }
+}
+
+/// Largest number of cells [`HoneycombParams::validate`] accepts.
+pub const MAX_CELLS: usize = 1_000_000;
+
+impl HoneycombParams {
+    /// Require positive sizes, a jitter below a third of a cell (so cells
+    /// can't fold over), walls thinner than a cell, and a bounded cell count.
+    pub fn validate(&self) -> Result<()> {
+        check_positive("cell_size", self.cell_size)?;
+        check_range("jitter", self.jitter, 0.0..=0.3, "0 to 0.3")?;
+        check_range("wall", self.wall, 0.0..=self.cell_size, "between 0 and the cell size")?;
+        check_positive("width", self.width)?;
+        check_positive("height", self.height)?;
+        let (cols, rows) = self.dimensions();
+        check_count("cells", cols * rows, MAX_CELLS)
+    }
+
+    /// Columns and rows of cells needed to cover the comb.
+    pub fn dimensions(&self) -> (usize, usize) {
+        let cols = (self.width / (self.cell_size * 3f64.sqrt())).ceil() as usize + 1;
+        let rows = (self.height / (self.cell_size * 1.5)).ceil() as usize + 1;
+        (cols, rows)
+    }
+}
+
+/// Honeycomb presets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Honey-bee worker comb: regular cells, thin walls
+    Apis,
+    /// Wild comb built freehand in a hollow tree
+    Wild,
+    /// Paper-wasp nest: small, thick-walled cells
+    Wasp,
+}
+
+impl Preset {
+    pub fn params(self) -> HoneycombParams {
+        match self {
+            Preset::Apis => HoneycombParams { cell_size: 24.0, jitter: 0.02, wall: 3.0, width: 800.0, height: 600.0 },
+            Preset::Wild => HoneycombParams { cell_size: 28.0, jitter: 0.18, wall: 4.0, width: 800.0, height: 600.0 },
+            Preset::Wasp => HoneycombParams { cell_size: 16.0, jitter: 0.08, wall: 5.0, width: 800.0, height: 600.0 },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Apis => "Honey-bee Comb",
+            Preset::Wild => "Wild Comb",
+            Preset::Wasp => "Paper-wasp Nest",
+        }
+    }
+}
+
+/// Deterministic displacement of the wall junction with lattice key `key`.
+fn junction_offset(key: (i64, i64), seed: u64, amount: f64) -> (f64, f64) {
+    if amount == 0.0 {
+        return (0.0, 0.0);
+    }
+    let hash = (key.0 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ (key.1 as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f) ^ seed;
+    let mut rng = SimpleRng::new(hash);
+    rng.next_u64();
+    ((rng.next_f64() * 2.0 - 1.0) * amount, (rng.next_f64() * 2.0 - 1.0) * amount)
+}
+
+/// The cells of a honeycomb, as hexagons listed row by row.
+///
+/// Neighbouring cells share their corner points exactly, jitter included.
+pub fn honeycomb(params: &HoneycombParams, seed: u64) -> Vec<Vec<Point>> {
+    let s = params.cell_size;
+    let w = s * 3f64.sqrt();
+    let (cols, rows) = params.dimensions();
+    let amount = params.jitter * s;
+    let mut cells = Vec::with_capacity(cols * rows);
+    for r in 0..rows {
+        for q in 0..cols {
+            let cx = w * (q as f64 + 0.5 * (r % 2) as f64);
+            let cy = 1.5 * s * r as f64;
+            let cell = (0..6)
+                .map(|k| {
+                    let a = (30.0 + 60.0 * k as f64).to_radians();
+                    // Corners sit on a lattice of half-widths and half-sizes; snapping
+                    // to it makes neighbours' shared corners bit-for-bit equal
+                    let key = (((cx + s * a.cos()) / (w / 2.0)).round() as i64, ((cy + s * a.sin()) / (s / 2.0)).round() as i64);
+                    let (dx, dy) = junction_offset(key, seed, amount);
+                    Point { x: key.0 as f64 * w / 2.0 + dx, y: key.1 as f64 * s / 2.0 + dy }
+                })
+                .collect();
+            cells.push(cell);
+        }
+    }
+    cells
+}
+
+/// Centroid of a polygon's vertices.
+pub fn centroid(cell: &[Point]) -> Point {
+    let n = cell.len().max(1) as f64;
+    Point { x: cell.iter().map(|p| p.x).sum::<f64>() / n, y: cell.iter().map(|p| p.y).sum::<f64>() / n }
+}
+
+/// Shrink a convex cell towards its centroid so its edges move in by about
+/// `distance` — half a wall on each side of a shared boundary.
+pub fn inset(cell: &[Point], distance: f64) -> Vec<Point> {
+    let c = centroid(cell);
+    // Mean distance from the centroid to the edge midpoints
+    let apothem = (0..cell.len())
+        .map(|i| {
+            let (a, b) = (cell[i], cell[(i + 1) % cell.len()]);
+            ((a.x + b.x) / 2.0 - c.x).hypot((a.y + b.y) / 2.0 - c.y)
+        })
+        .sum::<f64>()
+        / cell.len().max(1) as f64;
+    let k = (1.0 - distance / apothem.max(1e-12)).max(0.0);
+    cell.iter().map(|p| Point { x: c.x + (p.x - c.x) * k, y: c.y + (p.y - c.y) * k }).collect()
+}
+
+impl Generator for HoneycombParams {
+    /// The cell interiors inside their walls, with seed 42.
+    fn generate(&self) -> Geometry {
+        Geometry::Polygons(honeycomb(self, 42).iter().map(|cell| inset(cell, self.wall / 2.0)).collect())
+    }
+}
+
+/// Render a honeycomb: wax walls around cells of honey, some capped.
+pub fn honeycomb_to_svg(params: &HoneycombParams, seed: u64) -> String {
+    honeycomb_to_svg_with_theme(params, seed, &Theme::dark())
+}
+
+/// [`honeycomb_to_svg`] in `theme`; with a palette, the wax takes its first
+/// stop, capped cells a lighter step and honey the upper half.
+pub fn honeycomb_to_svg_with_theme(params: &HoneycombParams, seed: u64, theme: &Theme) -> String {
+    let (w, h) = (params.width, params.height);
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+<rect width="{w}" height="{h}" fill="{}"/>
+{}<rect width="{w}" height="{h}" fill="{}"/>
+"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        theme.color(0.0, "#e8c77a")
+    );
+    let mut rng = SimpleRng::new(seed);
+    for cell in honeycomb(params, seed) {
+        let points: Vec<String> = inset(&cell, params.wall / 2.0).iter().map(|p| format!("{:.1},{:.1}", p.x, p.y)).collect();
+        // A third of the cells capped with wax, the rest holding honey
+        let fill = if rng.next_f64() < 0.33 {
+            theme.color(0.35, crate::render::hsl(42.0, 70.0, 72.0 + 6.0 * rng.next_f64()))
+        } else {
+            let (hue, light) = (rng.next_f64(), rng.next_f64());
+            theme.color(0.6 + 0.4 * light, crate::render::hsl(32.0 + 8.0 * hue, 95.0, 38.0 + 14.0 * light))
+        };
+        svg.push_str(&format!(
+            r##"<polygon points="{}" fill="{fill}" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>
+"##,
+            points.join(" "),
+            theme.color(0.2, "#b8862b"),
+            theme.stroke_width(0.6)
+        ));
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Where Voronoi seeds come from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Seeding {
+    /// Uniformly random points
+    Random,
+    /// A golden-angle Vogel spiral filling the frame
+    Phyllotaxis,
+    /// Random points no closer than a minimum distance (Bridson's algorithm)
+    PoissonDisc,
+}
+
+/// How a Voronoi diagram is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VoronoiStyle {
+    /// Brown patches separated by pale channels
+    Giraffe,
+    /// Iridescent cells between dark veins
+    Dragonfly,
+    /// Curled mud plates between dark cracks
+    Mud,
+}
+
+/// Parameters for a Voronoi tiling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VoronoiParams {
+    /// Number of seeds (a target for Poisson-disc seeding)
+    pub count: usize,
+    pub seeding: Seeding,
+    /// Gap between neighbouring cells
+    pub gap: f64,
+    pub width: f64,
+    pub height: f64,
+    pub style: VoronoiStyle,
+}
+
+impl Default for VoronoiParams {
+    fn default() -> Self {
+        VoronoiPreset::Giraffe.params()
+    }
+}
+
+/// Largest seed count [`VoronoiParams::validate`] accepts; the Delaunay
+/// construction is quadratic.
+pub const MAX_SEEDS: usize = 20_000;
+
+impl VoronoiParams {
+    /// Require 1 to [`MAX_SEEDS`] seeds, a positive frame and a non-negative gap.
+    pub fn validate(&self) -> Result<()> {
+        check_count("count", self.count, MAX_SEEDS)?;
+        check_range("gap", self.gap, 0.0..=f64::MAX, "a non-negative gap")?;
+        check_positive("width", self.width)?;
+        check_positive("height", self.height)
+    }
+
+    /// The seed points for this tiling.
+    pub fn seeds(&self, seed: u64) -> Vec<Point> {
+        match self.seeding {
+            Seeding::Random => random_points(self.width, self.height, self.count, seed),
+            Seeding::Phyllotaxis => phyllotaxis_points(self.width, self.height, self.count),
+            Seeding::PoissonDisc => {
+                // Bridson fills a region to about 0.7 / r² points per unit area;
+                // valid parameters keep r positive and the grid near 3 cells per seed
+                let r = (0.7 * self.width * self.height / self.count as f64).sqrt();
+                poisson_disc(self.width, self.height, r, seed).unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Natural Voronoi patterns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VoronoiPreset {
+    /// Reticulated giraffe coat: evenly spaced patches
+    Giraffe,
+    /// Dragonfly wing: cells shrinking towards the veins' origin
+    Dragonfly,
+    /// Cracked mud drying in the sun
+    Mud,
+}
+
+impl VoronoiPreset {
+    pub fn params(self) -> VoronoiParams {
+        let (width, height) = (800.0, 600.0);
+        match self {
+            VoronoiPreset::Giraffe => {
+                VoronoiParams { count: 90, seeding: Seeding::PoissonDisc, gap: 9.0, width, height, style: VoronoiStyle::Giraffe }
+            }
+            VoronoiPreset::Dragonfly => {
+                VoronoiParams { count: 600, seeding: Seeding::Phyllotaxis, gap: 0.0, width, height, style: VoronoiStyle::Dragonfly }
+            }
+            VoronoiPreset::Mud => VoronoiParams { count: 160, seeding: Seeding::Random, gap: 3.0, width, height, style: VoronoiStyle::Mud },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            VoronoiPreset::Giraffe => "Giraffe Coat",
+            VoronoiPreset::Dragonfly => "Dragonfly Wing",
+            VoronoiPreset::Mud => "Cracked Mud",
+        }
+    }
+}
+
+/// `count` uniformly random points in `[0, width] × [0, height]`.
+pub fn random_points(width: f64, height: f64, count: usize, seed: u64) -> Vec<Point> {
+    let mut rng = SimpleRng::new(seed);
+    (0..count).map(|_| Point { x: rng.next_f64() * width, y: rng.next_f64() * height }).collect()
+}
+
+/// `count` points of a golden-angle spiral centered in the frame and
+/// scaled so the outermost reaches its corners; points outside are dropped.
+pub fn phyllotaxis_points(width: f64, height: f64, count: usize) -> Vec<Point> {
+    let params = super::phyllotaxis::Params { count, scale: 1.0, ..Default::default() };
+    let reach = (width / 2.0).hypot(height / 2.0) / (count.max(2) as f64 - 1.0).sqrt();
+    super::phyllotaxis::vogel_spiral_iter(&params)
+        .map(|e| Point { x: width / 2.0 + e.x * reach, y: height / 2.0 + e.y * reach })
+        .filter(|p| (0.0..=width).contains(&p.x) && (0.0..=height).contains(&p.y))
+        .collect()
+}
+
+/// Most background-grid cells [`poisson_disc`] allocates, about the
+/// frame area over min_distance² / 2.
+pub const MAX_DISC_CELLS: usize = 4_000_000;
+
+/// Poisson-disc samples of `[0, width] × [0, height]`: random points no two
+/// closer than `min_distance`, filling the frame (Bridson, 2007). Fails for
+/// a non-positive size or distance, or a distance so small against the
+/// frame that the grid would exceed [`MAX_DISC_CELLS`].
+pub fn poisson_disc(width: f64, height: f64, min_distance: f64, seed: u64) -> Result<Vec<Point>> {
+    const ATTEMPTS: usize = 30;
+    check_positive("width", width)?;
+    check_positive("height", height)?;
+    check_positive("min_distance", min_distance)?;
+    let cell = min_distance / 2f64.sqrt();
+    let cells = (width / cell + 1.0) * (height / cell + 1.0);
+    if cells > MAX_DISC_CELLS as f64 {
+        return Err(MathaturaError::TooLarge { name: "Poisson-disc cells", requested: cells as usize, limit: MAX_DISC_CELLS });
+    }
+    let mut rng = SimpleRng::new(seed);
+    let (cols, rows) = ((width / cell) as usize + 1, (height / cell) as usize + 1);
+    // Background grid: at most one sample per cell
+    let mut grid: Vec<Option<usize>> = vec![None; cols * rows];
+    let index = |p: &Point| ((p.y / cell) as usize).min(rows - 1) * cols + ((p.x / cell) as usize).min(cols - 1);
+    let mut points = vec![Point { x: rng.next_f64() * width, y: rng.next_f64() * height }];
+    grid[index(&points[0])] = Some(0);
+    let mut active = vec![0];
+    while !active.is_empty() {
+        let slot = rng.next_usize(active.len());
+        let origin = points[active[slot]];
+        let found = (0..ATTEMPTS).find_map(|_| {
+            // Uniform in the annulus [r, 2r]
+            let radius = min_distance * (1.0 + 3.0 * rng.next_f64()).sqrt();
+            let angle = rng.next_f64() * 2.0 * core::f64::consts::PI;
+            let p = Point { x: origin.x + radius * angle.cos(), y: origin.y + radius * angle.sin() };
+            if !(0.0..width).contains(&p.x) || !(0.0..height).contains(&p.y) {
+                return None;
+            }
+            let (gx, gy) = ((p.x / cell) as usize, (p.y / cell) as usize);
+            let clear = (gy.saturating_sub(2)..=(gy + 2).min(rows - 1)).all(|y| {
+                (gx.saturating_sub(2)..=(gx + 2).min(cols - 1)).all(|x| {
+                    grid[y * cols + x].is_none_or(|k| (points[k].x - p.x).hypot(points[k].y - p.y) >= min_distance)
+                })
+            });
+            clear.then_some(p)
+        });
+        match found {
+            Some(p) => {
+                grid[index(&p)] = Some(points.len());
+                active.push(points.len());
+                points.push(p);
+            }
+            None => {
+                active.swap_remove(slot);
+            }
+        }
+    }
+    Ok(points)
+}
+
+/// Circumcircle center and squared radius of a triangle, or `None` if degenerate.
+fn circumcircle(a: Point, b: Point, c: Point) -> Option<(Point, f64)> {
+    let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+    if d.abs() < 1e-12 {
+        return None;
+    }
+    let (a2, b2, c2) = (a.x * a.x + a.y * a.y, b.x * b.x + b.y * b.y, c.x * c.x + c.y * c.y);
+    let center = Point {
+        x: (a2 * (b.y - c.y) + b2 * (c.y - a.y) + c2 * (a.y - b.y)) / d,
+        y: (a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x)) / d,
+    };
+    Some((center, (a.x - center.x).powi(2) + (a.y - center.y).powi(2)))
+}
+
+/// Delaunay triangulation by Bowyer–Watson insertion, as triples of point
+/// indices. Duplicate points are skipped; collinear input has no triangles.
+pub fn delaunay(points: &[Point]) -> Vec<[usize; 3]> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    let (mut lo, mut hi) = (points[0], points[0]);
+    for p in points {
+        (lo.x, lo.y, hi.x, hi.y) = (lo.x.min(p.x), lo.y.min(p.y), hi.x.max(p.x), hi.y.max(p.y));
+    }
+    let span = (hi.x - lo.x).max(hi.y - lo.y).max(1e-9);
+    let mid = Point { x: (lo.x + hi.x) / 2.0, y: (lo.y + hi.y) / 2.0 };
+    // A super-triangle enclosing everything, its corners past the real points
+    let n = points.len();
+    let mut all = points.to_vec();
+    all.extend([
+        Point { x: mid.x - 20.0 * span, y: mid.y - span },
+        Point { x: mid.x, y: mid.y + 20.0 * span },
+        Point { x: mid.x + 20.0 * span, y: mid.y - span },
+    ]);
+    let mut triangles: Vec<([usize; 3], Point, f64)> = Vec::new();
+    if let Some((c, r2)) = circumcircle(all[n], all[n + 1], all[n + 2]) {
+        triangles.push(([n, n + 1, n + 2], c, r2));
+    }
+    for i in 0..n {
+        let p = all[i];
+        let mut edges: Vec<[usize; 2]> = Vec::new();
+        let mut duplicate = false;
+        triangles.retain(|&(t, c, r2)| {
+            if (p.x - c.x).powi(2) + (p.y - c.y).powi(2) >= r2 {
+                return true;
+            }
+            duplicate |= t.iter().any(|&k| all[k] == p);
+            for e in [[t[0], t[1]], [t[1], t[2]], [t[2], t[0]]] {
+                edges.push(e);
+            }
+            false
+        });
+        if duplicate {
+            // Undo by re-inserting without the point; rare, so done simply
+            let kept: Vec<Point> = points[..i].iter().chain(&points[i + 1..]).copied().collect();
+            let shift = |k: usize| if k >= i { k + 1 } else { k };
+            return delaunay(&kept).into_iter().map(|t| t.map(shift)).collect();
+        }
+        // The cavity's boundary: edges belonging to exactly one removed triangle
+        for (k, e) in edges.iter().enumerate() {
+            let shared = edges.iter().enumerate().any(|(j, f)| j != k && (f == e || (f[0] == e[1] && f[1] == e[0])));
+            if !shared {
+                if let Some((c, r2)) = circumcircle(all[e[0]], all[e[1]], p) {
+                    triangles.push(([e[0], e[1], i], c, r2));
+                }
+            }
+        }
+    }
+    triangles.into_iter().map(|(t, _, _)| t).filter(|t| t.iter().all(|&k| k < n)).collect()
+}
+
+/// Cut a convex polygon by the half-plane of points nearer `a` than `b`.
+fn clip_nearer(polygon: &[Point], a: Point, b: Point) -> Vec<Point> {
+    let (nx, ny) = (b.x - a.x, b.y - a.y);
+    let mid = Point { x: (a.x + b.x) / 2.0, y: (a.y + b.y) / 2.0 };
+    clip_side(polygon, |p| (p.x - mid.x) * nx + (p.y - mid.y) * ny)
+}
+
+/// Clip a convex polygon to a convex, counter-clockwise `window`
+/// (Sutherland–Hodgman).
+pub fn clip_convex(polygon: &[Point], window: &[Point]) -> Vec<Point> {
+    let mut out = polygon.to_vec();
+    for i in 0..window.len() {
+        let (a, b) = (window[i], window[(i + 1) % window.len()]);
+        // Points right of a → b are outside
+        out = clip_side(&out, |p| (b.x - a.x) * (a.y - p.y) - (b.y - a.y) * (a.x - p.x));
+    }
+    out
+}
+
+/// Keep the part of a convex polygon where `side` ≤ 0, cutting the edges
+/// that cross zero.
+fn clip_side(polygon: &[Point], side: impl Fn(&Point) -> f64) -> Vec<Point> {
+    let mut out = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let (p, q) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        let (sp, sq) = (side(&p), side(&q));
+        if sp <= 0.0 {
+            out.push(p);
+        }
+        if (sp < 0.0) != (sq < 0.0) && sp != sq {
+            let t = sp / (sp - sq);
+            out.push(Point { x: p.x + (q.x - p.x) * t, y: p.y + (q.y - p.y) * t });
+        }
+    }
+    out
+}
+
+/// Voronoi cells of `points` clipped to `[0, width] × [0, height]`, one per
+/// point in input order (empty for duplicates and points outside the frame).
+pub fn voronoi(points: &[Point], width: f64, height: f64) -> Vec<Vec<Point>> {
+    let triangles = delaunay(points);
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+    for t in &triangles {
+        for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+            neighbors[a].push(b);
+            neighbors[b].push(a);
+        }
+    }
+    let frame = vec![Point { x: 0.0, y: 0.0 }, Point { x: width, y: 0.0 }, Point { x: width, y: height }, Point { x: 0.0, y: height }];
+    (0..points.len())
+        .map(|i| {
+            let p = points[i];
+            if !(0.0..=width).contains(&p.x) || !(0.0..=height).contains(&p.y) || points[..i].contains(&p) {
+                return Vec::new();
+            }
+            // Without a triangulation (fewer than 3 or collinear points) every
+            // other point is a potential neighbour
+            let others: Vec<usize> = if triangles.is_empty() { (0..points.len()).filter(|&j| j != i).collect() } else { core::mem::take(&mut neighbors[i]) };
+            let mut cell = frame.clone();
+            for j in others.into_iter().filter(|&j| points[j] != p) {
+                cell = clip_nearer(&cell, p, points[j]);
+            }
+            cell
+        })
+        .collect()
+}
+
+impl Generator for VoronoiParams {
+    /// The Voronoi cells, inset by half the gap, with seed 42.
+    fn generate(&self) -> Geometry {
+        let cells = voronoi(&self.seeds(42), self.width, self.height);
+        Geometry::Polygons(cells.iter().filter(|c| c.len() >= 3).map(|c| inset(c, self.gap / 2.0)).collect())
+    }
+}
+
+/// Render Voronoi cells in one of the natural styles.
+pub fn voronoi_to_svg(cells: &[Vec<Point>], params: &VoronoiParams, seed: u64) -> String {
+    voronoi_to_svg_with_theme(cells, params, seed, &Theme::dark())
+}
+
+/// [`voronoi_to_svg`] in `theme`; with a palette, the skin or mud between
+/// cells takes its first stop and the cells the upper part.
+pub fn voronoi_to_svg_with_theme(cells: &[Vec<Point>], params: &VoronoiParams, seed: u64, theme: &Theme) -> String {
+    let (w, h) = (params.width, params.height);
+    let ground = match params.style {
+        VoronoiStyle::Giraffe => Some("#f1e2c2"),
+        VoronoiStyle::Dragonfly => None,
+        VoronoiStyle::Mud => Some("#3b2a1c"),
+    };
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+<rect width="{w}" height="{h}" fill="{}"/>
+{}"##,
+        theme.background_css(),
+        theme.foreground_start()
+    );
+    if let Some(ground) = ground {
+        svg.push_str(&format!("<rect width=\"{w}\" height=\"{h}\" fill=\"{}\"/>\n", theme.color(0.0, ground)));
+    }
+    let mut rng = SimpleRng::new(seed);
+    for cell in cells.iter().filter(|c| c.len() >= 3) {
+        let points: Vec<String> = inset(cell, params.gap / 2.0).iter().map(|p| format!("{:.1},{:.1}", p.x, p.y)).collect();
+        let (fill, stroke) = match params.style {
+            VoronoiStyle::Giraffe => {
+                let (hue, light) = (rng.next_f64(), rng.next_f64());
+                (theme.color(0.5 + 0.5 * light, crate::render::hsl(22.0 + 8.0 * hue, 65.0, 28.0 + 10.0 * light)), String::from("none"))
+            }
+            VoronoiStyle::Dragonfly => {
+                let c = centroid(cell);
+                let hue = 170.0 + 80.0 * (c.x / w) + 20.0 * rng.next_f64();
+                (theme.color(c.x / w, crate::render::hsl(hue, 60.0, 55.0)), theme.color(0.0, "#1b2430"))
+            }
+            VoronoiStyle::Mud => {
+                let (saturation, light) = (rng.next_f64(), rng.next_f64());
+                let fill = crate::render::hsl(28.0, 30.0 + 10.0 * saturation, 48.0 + 10.0 * light);
+                (theme.color(0.5 + 0.5 * light, fill), theme.color(0.0, "#2a1d12"))
+            }
+        };
+        let extra = match params.style {
+            VoronoiStyle::Giraffe => String::from(r##" stroke-linejoin="round""##),
+            VoronoiStyle::Dragonfly => format!(r##" fill-opacity="0.35" stroke-width="{}""##, theme.stroke_width(1.2)),
+            VoronoiStyle::Mud => format!(r##" stroke-width="{}""##, theme.stroke_width(0.8)),
+        };
+        svg.push_str(&format!(
+            r##"<polygon points="{}" fill="{fill}" stroke="{stroke}"{extra}/>
+"##,
+            points.join(" ")
+        ));
+    }
+    svg.push_str(theme.foreground_end());
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(cell: &[Point]) -> f64 {
+        (0..cell.len())
+            .map(|i| {
+                let (a, b) = (cell[i], cell[(i + 1) % cell.len()]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f64>()
+            .abs()
+            / 2.0
+    }
+
+    #[test]
+    fn test_regular_hexagons() {
+        let params = HoneycombParams { jitter: 0.0, ..Default::default() };
+        let cells = honeycomb(&params, 1);
+        let (cols, rows) = params.dimensions();
+        assert_eq!(cells.len(), cols * rows);
+        let s = params.cell_size;
+        for cell in &cells {
+            assert_eq!(cell.len(), 6);
+            // Regular hexagon area 3√3/2 s²
+            assert!((area(cell) - 1.5 * 3f64.sqrt() * s * s).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_jittered_cells_share_walls() {
+        let params = HoneycombParams { jitter: 0.25, ..Default::default() };
+        let cells = honeycomb(&params, 7);
+        let regular = honeycomb(&HoneycombParams { jitter: 0.0, ..params }, 7);
+        assert_ne!(cells, regular);
+        // Every corner is shared with a neighbouring cell, exactly
+        let (cols, _) = params.dimensions();
+        let (a, b) = (&cells[cols + 1], &cells[cols + 2]);
+        assert_eq!(a.iter().filter(|p| b.contains(p)).count(), 2);
+        // Areas vary, but the comb is still tiled: the total is unchanged
+        let total = |cs: &[Vec<Point>]| cs.iter().map(|c| area(c)).sum::<f64>();
+        let inner = |cs: &[Vec<Point>]| cs[cols + 1..2 * cols - 1].to_vec();
+        assert!((total(&inner(&cells)) - total(&inner(&regular))).abs() / total(&inner(&regular)) < 0.05);
+    }
+
+    #[test]
+    fn test_walls_shrink_cells() {
+        let cell = &honeycomb(&HoneycombParams { jitter: 0.0, ..Default::default() }, 1)[0];
+        let apothem = 24.0 * 3f64.sqrt() / 2.0;
+        let shrunk = inset(cell, 2.0);
+        let ratio = area(&shrunk) / area(cell);
+        assert!((ratio - ((apothem - 2.0) / apothem).powi(2)).abs() < 1e-9);
+        assert_eq!(centroid(&shrunk), centroid(cell));
+    }
+
+    #[test]
+    fn test_delaunay_counts() {
+        // Square with a center point: four triangles around the center
+        let square = [Point { x: 0.0, y: 0.0 }, Point { x: 2.0, y: 0.0 }, Point { x: 2.0, y: 2.0 }, Point { x: 0.0, y: 2.0 }, Point { x: 1.0, y: 1.0 }];
+        let triangles = delaunay(&square);
+        assert_eq!(triangles.len(), 4);
+        assert!(triangles.iter().all(|t| t.contains(&4)));
+        // Euler: 2n − 2 − h triangles for n points with h on the hull
+        let mut points = random_points(100.0, 100.0, 200, 3);
+        points.extend_from_slice(&square[..4].iter().map(|p| Point { x: p.x * 60.0 - 10.0, y: p.y * 60.0 - 10.0 }).collect::<Vec<_>>());
+        assert_eq!(delaunay(&points).len(), 2 * points.len() - 2 - 4);
+        assert!(delaunay(&[Point { x: 0.0, y: 0.0 }, Point { x: 1.0, y: 1.0 }, Point { x: 2.0, y: 2.0 }]).is_empty());
+    }
+
+    #[test]
+    fn test_voronoi_cells_are_nearest_regions() {
+        let points = random_points(200.0, 100.0, 60, 11);
+        let cells = voronoi(&points, 200.0, 100.0);
+        assert_eq!(cells.len(), 60);
+        // The cells tile the frame
+        let total: f64 = cells.iter().map(|c| area(c)).sum();
+        assert!((total - 20_000.0).abs() < 1e-6, "{}", total);
+        // Each cell's centroid is nearer its own seed than any other
+        for (i, cell) in cells.iter().enumerate() {
+            let c = centroid(cell);
+            let d = |p: &Point| (p.x - c.x).hypot(p.y - c.y);
+            assert!(points.iter().all(|p| d(p) >= d(&points[i]) - 1e-9));
+        }
+        // Duplicates get one cell between them
+        let twice = voronoi(&[points[0], points[1], points[0], points[2]], 200.0, 100.0);
+        assert!(twice[2].is_empty() && !twice[0].is_empty());
+    }
+
+    #[test]
+    fn test_seedings() {
+        let disc = poisson_disc(300.0, 200.0, 20.0, 5).unwrap();
+        for (i, a) in disc.iter().enumerate() {
+            for b in &disc[i + 1..] {
+                assert!((a.x - b.x).hypot(a.y - b.y) >= 20.0);
+            }
+        }
+        // Bridson fills the frame: roughly 0.7 points per r²
+        assert!(disc.len() > 80 && disc.len() < 140, "{}", disc.len());
+        assert!(poisson_disc(300.0, 200.0, 0.0, 5).is_err());
+        assert!(poisson_disc(300.0, 200.0, f64::NAN, 5).is_err());
+        assert!(matches!(poisson_disc(300.0, 200.0, 1e-6, 5), Err(MathaturaError::TooLarge { .. })));
+        let spiral = phyllotaxis_points(300.0, 200.0, 500);
+        assert!(spiral.len() > 250 && spiral.len() < 500);
+        for preset in [VoronoiPreset::Giraffe, VoronoiPreset::Dragonfly, VoronoiPreset::Mud] {
+            let params = preset.params();
+            assert!(params.validate().is_ok(), "{}", preset.name());
+            let cells = voronoi(&params.seeds(1), params.width, params.height);
+            let svg = voronoi_to_svg(&cells, &params, 1);
+            assert_eq!(svg.matches("<polygon").count(), cells.len());
+        }
+        assert!(VoronoiParams { count: 0, ..Default::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_and_svg() {
+        for preset in [Preset::Apis, Preset::Wild, Preset::Wasp] {
+            assert!(preset.params().validate().is_ok(), "{}", preset.name());
+        }
+        assert!(HoneycombParams { jitter: 0.5, ..Default::default() }.validate().is_err());
+        assert!(HoneycombParams { cell_size: 0.01, ..Default::default() }.validate().is_err());
+        let params = HoneycombParams { width: 100.0, height: 80.0, ..Default::default() };
+        let svg = honeycomb_to_svg(&params, 3);
+        let (cols, rows) = params.dimensions();
+        assert_eq!(svg.matches("<polygon").count(), cols * rows);
+        assert_eq!(params.generate().len(), cols * rows * 6);
+    }
+
+    #[test]
+    fn test_clip_convex() {
+        let square = [Point { x: 0.0, y: 0.0 }, Point { x: 2.0, y: 0.0 }, Point { x: 2.0, y: 2.0 }, Point { x: 0.0, y: 2.0 }];
+        let shifted: Vec<Point> = square.iter().map(|p| Point { x: p.x + 1.0, y: p.y + 1.0 }).collect();
+        let overlap = clip_convex(&square, &shifted);
+        assert!((area(&overlap) - 1.0).abs() < 1e-12);
+        let apart: Vec<Point> = square.iter().map(|p| Point { x: p.x + 5.0, y: p.y }).collect();
+        assert!(clip_convex(&square, &apart).is_empty());
+    }
+}
@@ -3,16 +3,37 @@
 //! Alan Turing's 1952 paper "The Chemical Basis of Morphogenesis" showed how
 //! two interacting chemicals (morphogens) can create stable patterns:
 //! spots (leopard), stripes (zebra), and labyrinths (brain coral).
+//!
+//! The grid is independent of the chemistry: any [`ReactionModel`] can drive
+//! it. Gray–Scott is the default; Gierer–Meinhardt's activator–inhibitor
+//! kinetics and the FitzHugh–Nagumo excitable medium reproduce the classic
+//! figures of the Turing-pattern literature.
+
+use crate::prelude::*;
+use crate::categories::fractals::Point;
+use crate::error::{check_positive, check_range, MathaturaError, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::parallel::for_each_row;
+use crate::progress::{checkpoint, Progress};
+use crate::render::colormap::{Colormap, INFERNO};
+use crate::render::theme::Theme;
 
 /// Grid cell containing two chemical concentrations.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cell {
     pub a: f64, // Activator concentration
     pub b: f64, // Inhibitor concentration
 }
 
-/// Parameters for the Gray-Scott reaction-diffusion model.
-#[derive(Debug, Clone, Copy)]
+/// Gray–Scott diffusion rates follow Karl Sims' convention, whose 3×3
+/// Laplacian kernel (−1 center, 0.2 edges, 0.05 corners) is 0.3 × ∇².
+const SIMS_SCALE: f64 = 0.3;
+
+/// Parameters for the Gray-Scott reaction-diffusion model, with diffusion
+/// rates in Karl Sims' units (so the presets run stably at dt = 1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GrayScottParams {
     /// Diffusion rate of chemical A
     pub da: f64,
@@ -24,10 +45,460 @@ pub struct GrayScottParams {
     pub kill: f64,
     /// Time step
     pub dt: f64,
+    /// Directional diffusion; isotropic by default
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub anisotropy: Anisotropy,
+    /// Edge behaviour; periodic by default
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub boundary: Boundary,
+}
+
+impl GrayScottParams {
+    /// Check the diffusion rates and time step, and that feed and kill lie in
+    /// the 0–0.1 region of Pearson's map where patterns form.
+    pub fn validate(&self) -> Result<()> {
+        check_positive("da", self.da)?;
+        check_positive("db", self.db)?;
+        check_range("feed", self.feed, 0.0..=0.1, "0 to 0.1")?;
+        check_range("kill", self.kill, 0.0..=0.1, "0 to 0.1")?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=1.0, "0 < dt ≤ 1")?;
+        self.anisotropy.validate()
+    }
+
+    /// The same kinetics with different edges.
+    pub fn with_boundary(self, boundary: Boundary) -> Self {
+        GrayScottParams { boundary, ..self }
+    }
+
+    /// The same kinetics with diffusion `ratio` times faster along `angle`
+    /// than across it.
+    pub fn with_anisotropy(self, angle: f64, ratio: f64) -> Self {
+        GrayScottParams { anisotropy: Anisotropy { angle, ratio }, ..self }
+    }
+}
+
+/// What lies beyond the grid's edges.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Boundary {
+    /// Opposite edges join, as on a torus: patterns tile seamlessly
+    #[default]
+    Periodic,
+    /// The pattern reflects about the edge cells, as if the domain were
+    /// one half of a symmetric one
+    Mirror,
+    /// The edges are held at fixed concentrations, such as the steady state
+    Fixed(Cell),
+    /// Zero flux: nothing diffuses in or out, so spots and coral meet the
+    /// edge head-on like a real, closed domain
+    NoFlux,
+}
+
+impl Boundary {
+    pub const NAMES: [&'static str; 4] = ["periodic", "mirror", "fixed", "no-flux"];
+
+    /// Index into a row or column of `n` cells for any `i`, or `None`
+    /// outside a fixed boundary.
+    pub fn index(self, i: isize, n: usize) -> Option<usize> {
+        let n = n as isize;
+        let i = match self {
+            Boundary::Periodic => i.rem_euclid(n),
+            Boundary::NoFlux => i.clamp(0, n - 1),
+            Boundary::Mirror if n == 1 => 0,
+            Boundary::Mirror => {
+                let period = 2 * (n - 1);
+                let m = i.rem_euclid(period);
+                if m < n { m } else { period - m }
+            }
+            Boundary::Fixed(_) if !(0..n).contains(&i) => return None,
+            Boundary::Fixed(_) => i,
+        };
+        Some(i as usize)
+    }
+}
+
+/// Directional diffusion: both chemicals spread `ratio` times faster along
+/// the direction `angle` (radians from the +x axis toward +y, i.e. clockwise
+/// on screen) than across it. Stripes line up with the fast direction:
+/// angle 0 gives horizontal stripes, a right angle vertical ones. The faster
+/// of the two rates is the model's own, so the time step stays stable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Anisotropy {
+    pub angle: f64,
+    pub ratio: f64,
+}
+
+impl Default for Anisotropy {
+    fn default() -> Self {
+        Anisotropy::ISOTROPIC
+    }
+}
+
+impl Anisotropy {
+    pub const ISOTROPIC: Anisotropy = Anisotropy { angle: 0.0, ratio: 1.0 };
+
+    pub fn is_isotropic(&self) -> bool {
+        self.ratio == 1.0
+    }
+
+    /// Require a ratio in 0.1–10, where the explicit stencil stays stable.
+    pub fn validate(&self) -> Result<()> {
+        check_range("anisotropy ratio", self.ratio, 0.1..=10.0, "0.1 to 10")?;
+        check_range("anisotropy angle", self.angle, -1e6..=1e6, "a finite angle")
+    }
+
+    /// Diffusion across the axis, and the extra along it, relative to the
+    /// faster of the two.
+    fn split(&self) -> (f64, f64) {
+        let (along, across) = if self.ratio >= 1.0 { (1.0, 1.0 / self.ratio) } else { (self.ratio, 1.0) };
+        (across, along - across)
+    }
+}
+
+/// Local kinetics of two diffusing chemicals, a and b.
+pub trait ReactionModel {
+    /// Diffusion rates of a and b.
+    fn diffusion(&self) -> (f64, f64);
+
+    /// Reaction rates (∂a/∂t, ∂b/∂t) at a cell, without diffusion.
+    fn reaction(&self, cell: Cell) -> (f64, f64);
+
+    /// Time step.
+    fn dt(&self) -> f64;
+
+    /// The uniform steady state that patterns grow out of.
+    fn steady_state(&self) -> Cell;
+
+    /// Keep concentrations physical after a step.
+    fn clamp(&self, cell: Cell) -> Cell {
+        cell
+    }
+
+    /// The quantity that shows the pattern when rendered.
+    fn pattern_value(&self, cell: Cell) -> f64 {
+        cell.b
+    }
+
+    /// Directional diffusion, scaling both rates.
+    fn anisotropy(&self) -> Anisotropy {
+        Anisotropy::ISOTROPIC
+    }
+
+    /// Edge behaviour.
+    fn boundary(&self) -> Boundary {
+        Boundary::Periodic
+    }
+
+    /// Reaction rates at grid cell (x, y), for kinetics that vary across
+    /// the domain.
+    fn reaction_at(&self, _x: usize, _y: usize, cell: Cell) -> (f64, f64) {
+        self.reaction(cell)
+    }
+}
+
+impl ReactionModel for GrayScottParams {
+    fn diffusion(&self) -> (f64, f64) {
+        (SIMS_SCALE * self.da, SIMS_SCALE * self.db)
+    }
+
+    fn reaction(&self, cell: Cell) -> (f64, f64) {
+        let ab2 = cell.a * cell.b * cell.b;
+        (self.feed * (1.0 - cell.a) - ab2, ab2 - (self.kill + self.feed) * cell.b)
+    }
+
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    fn steady_state(&self) -> Cell {
+        Cell { a: 1.0, b: 0.0 }
+    }
+
+    fn clamp(&self, cell: Cell) -> Cell {
+        Cell { a: cell.a.clamp(0.0, 1.0), b: cell.b.clamp(0.0, 1.0) }
+    }
+
+    fn anisotropy(&self) -> Anisotropy {
+        self.anisotropy
+    }
+}
+
+/// Parameters for Gierer–Meinhardt activator–inhibitor kinetics, with a the
+/// activator and b = h the inhibitor.
+///
+/// ∂a/∂t = Dₐ∇²a + ρa²/(h(1 + κa²)) − μₐa + σ
+/// ∂h/∂t = Dₕ∇²h + ρa² − μₕh
+///
+/// The activator catalyzes itself and its own inhibitor, which spreads
+/// faster: local activation with long-range inhibition. Without saturation
+/// (κ = 0) the peaks stay round; saturated autocatalysis merges them into
+/// stripes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GiererMeinhardtParams {
+    pub da: f64,
+    pub dh: f64,
+    /// Production rate ρ
+    pub rho: f64,
+    /// Activator decay μₐ
+    pub mu_a: f64,
+    /// Inhibitor decay μₕ
+    pub mu_h: f64,
+    /// Basal activator production σ
+    pub sigma: f64,
+    /// Saturation κ of autocatalysis
+    pub kappa: f64,
+    pub dt: f64,
+    /// Edge behaviour; periodic by default
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub boundary: Boundary,
+}
+
+impl GiererMeinhardtParams {
+    /// Isolated activator peaks (Meinhardt's spots).
+    pub fn spots() -> Self {
+        GiererMeinhardtParams { da: 1.0, dh: 30.0, rho: 1.0, mu_a: 1.0, mu_h: 1.2, sigma: 0.01, kappa: 0.0, dt: 0.008, boundary: Boundary::Periodic }
+    }
+
+    /// Saturated autocatalysis: peaks fuse into stripes.
+    pub fn stripes() -> Self {
+        GiererMeinhardtParams { kappa: 0.1, ..Self::spots() }
+    }
+
+    /// Require positive rates, a faster-spreading inhibitor, and a time step
+    /// within the explicit scheme's stability limit.
+    pub fn validate(&self) -> Result<()> {
+        check_positive("da", self.da)?;
+        check_range("dh", self.dh, self.da..=f64::MAX, "at least da")?;
+        check_positive("rho", self.rho)?;
+        check_positive("mu_a", self.mu_a)?;
+        check_positive("mu_h", self.mu_h)?;
+        check_range("sigma", self.sigma, 0.0..=f64::MAX, "0 or more")?;
+        check_range("kappa", self.kappa, 0.0..=f64::MAX, "0 or more")?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.25 / self.dh.max(self.mu_h), "at most 0.25 / dh")
+    }
+}
+
+impl ReactionModel for GiererMeinhardtParams {
+    fn diffusion(&self) -> (f64, f64) {
+        (self.da, self.dh)
+    }
+
+    fn reaction(&self, cell: Cell) -> (f64, f64) {
+        let a2 = cell.a * cell.a;
+        (self.rho * a2 / (cell.b * (1.0 + self.kappa * a2)) - self.mu_a * cell.a + self.sigma, self.rho * a2 - self.mu_h * cell.b)
+    }
+
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// With h = ρa²/μₕ, the activator balance μₕ/(1 + κa²) − μₐa + σ = 0
+    /// has a single root, found by bisection.
+    fn steady_state(&self) -> Cell {
+        let balance = |a: f64| self.mu_h / (1.0 + self.kappa * a * a) - self.mu_a * a + self.sigma;
+        let (mut lo, mut hi) = (0.0, (self.mu_h + self.sigma) / self.mu_a);
+        for _ in 0..60 {
+            let mid = (lo + hi) / 2.0;
+            if balance(mid) > 0.0 {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let a = (lo + hi) / 2.0;
+        Cell { a, b: self.rho * a * a / self.mu_h }
+    }
+
+    fn clamp(&self, cell: Cell) -> Cell {
+        // The inhibitor divides the activator's production
+        Cell { a: cell.a.max(0.0), b: cell.b.max(1e-6) }
+    }
+
+    fn pattern_value(&self, cell: Cell) -> f64 {
+        cell.a
+    }
+}
+
+/// Parameters for FitzHugh–Nagumo kinetics, with a = u the fast excitable
+/// variable and b = v the slow recovery variable.
+///
+/// ∂u/∂t = Dᵤ∇²u + u − u³ − v + k
+/// τ ∂v/∂t = Dᵥ∇²v + u − v
+///
+/// A simplified nerve membrane; with a fast-diffusing recovery variable it
+/// forms Turing spots and labyrinths. The offset k tips the balance between
+/// excited and resting regions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FitzHughNagumoParams {
+    pub du: f64,
+    pub dv: f64,
+    /// Time scale τ of the recovery variable
+    pub tau: f64,
+    pub k: f64,
+    pub dt: f64,
+    /// Edge behaviour; periodic by default
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub boundary: Boundary,
+}
+
+impl FitzHughNagumoParams {
+    /// Excited spots in a resting medium; they separate out over about
+    /// 15000 steps.
+    pub fn spots() -> Self {
+        FitzHughNagumoParams { du: 0.3, dv: 5.0, tau: 0.1, k: -0.05, dt: 0.004, boundary: Boundary::Periodic }
+    }
+
+    /// Balanced excitation: a maze of fronts.
+    pub fn labyrinth() -> Self {
+        FitzHughNagumoParams { k: 0.0, ..Self::spots() }
+    }
+
+    /// Require positive rates, τ, and a time step within the explicit
+    /// scheme's stability limit.
+    pub fn validate(&self) -> Result<()> {
+        check_positive("du", self.du)?;
+        check_positive("dv", self.dv)?;
+        check_positive("tau", self.tau)?;
+        check_range("k", self.k, -1.0..=1.0, "-1 to 1")?;
+        check_range("dt", self.dt, f64::MIN_POSITIVE..=0.25 * self.tau / self.dv.max(1.0), "at most 0.25 tau / dv")
+    }
+}
+
+impl ReactionModel for FitzHughNagumoParams {
+    fn diffusion(&self) -> (f64, f64) {
+        (self.du, self.dv / self.tau)
+    }
+
+    fn reaction(&self, cell: Cell) -> (f64, f64) {
+        let u = cell.a;
+        (u - u * u * u - cell.b + self.k, (u - cell.b) / self.tau)
+    }
+
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// v = u and u³ = k.
+    fn steady_state(&self) -> Cell {
+        let u = self.k.cbrt();
+        Cell { a: u, b: u }
+    }
+
+    fn pattern_value(&self, cell: Cell) -> f64 {
+        cell.a
+    }
+}
+
+/// Gray-Scott kinetics with feed and kill rates that vary cell by cell, so
+/// one simulation can pass from spots to stripes across the domain — the
+/// leopard's spotted body running into its ringed tail.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeedKillMap {
+    /// Diffusion, time step and the rates used outside the maps
+    pub params: GrayScottParams,
+    pub width: usize,
+    pub height: usize,
+    /// Row-major feed rate per cell
+    pub feed: Vec<f64>,
+    /// Row-major kill rate per cell
+    pub kill: Vec<f64>,
+}
+
+impl FeedKillMap {
+    /// Rates from a function of the cell's relative position (u, v) in
+    /// [0, 1]², returning (feed, kill).
+    pub fn from_fn(params: GrayScottParams, width: usize, height: usize, rates: impl Fn(f64, f64) -> (f64, f64)) -> Self {
+        let relative = |i: usize, n: usize| if n > 1 { i as f64 / (n - 1) as f64 } else { 0.5 };
+        let (feed, kill) = (0..width * height)
+            .map(|k| rates(relative(k % width, width), relative(k / width, height)))
+            .unzip();
+        FeedKillMap { params, width, height, feed, kill }
+    }
+
+    /// Rates from row-major arrays, one value per cell.
+    pub fn from_arrays(params: GrayScottParams, width: usize, height: usize, feed: Vec<f64>, kill: Vec<f64>) -> Result<Self> {
+        for (name, values) in [("feed", &feed), ("kill", &kill)] {
+            if values.len() != width * height {
+                return Err(MathaturaError::InvalidParameter { name, value: values.len() as f64, expected: "one value per grid cell" });
+            }
+        }
+        Ok(FeedKillMap { params, width, height, feed, kill })
+    }
+
+    /// A left-to-right blend from one preset's rates to another's.
+    pub fn gradient(width: usize, height: usize, from: Preset, to: Preset) -> Self {
+        let (a, b) = (from.params(), to.params());
+        FeedKillMap::from_fn(a, width, height, |u, _| (a.feed + (b.feed - a.feed) * u, a.kill + (b.kill - a.kill) * u))
+    }
+
+    /// Check the base parameters and keep every cell's rates in 0–0.1.
+    pub fn validate(&self) -> Result<()> {
+        self.params.validate()?;
+        for (&feed, &kill) in self.feed.iter().zip(&self.kill) {
+            check_range("feed", feed, 0.0..=0.1, "0 to 0.1")?;
+            check_range("kill", kill, 0.0..=0.1, "0 to 0.1")?;
+        }
+        Ok(())
+    }
+}
+
+impl ReactionModel for FeedKillMap {
+    fn diffusion(&self) -> (f64, f64) {
+        self.params.diffusion()
+    }
+
+    fn reaction(&self, cell: Cell) -> (f64, f64) {
+        self.params.reaction(cell)
+    }
+
+    fn dt(&self) -> f64 {
+        self.params.dt
+    }
+
+    fn boundary(&self) -> Boundary {
+        self.params.boundary
+    }
+
+    fn steady_state(&self) -> Cell {
+        self.params.steady_state()
+    }
+
+    fn clamp(&self, cell: Cell) -> Cell {
+        self.params.clamp(cell)
+    }
+
+    fn anisotropy(&self) -> Anisotropy {
+        self.params.anisotropy
+    }
+
+    fn reaction_at(&self, x: usize, y: usize, cell: Cell) -> (f64, f64) {
+        let k = y * self.width + x;
+        match (self.feed.get(k), self.kill.get(k)) {
+            (Some(&feed), Some(&kill)) if x < self.width => GrayScottParams { feed, kill, ..self.params }.reaction(cell),
+            _ => self.params.reaction(cell),
+        }
+    }
 }
 
 /// Preset patterns for Gray-Scott model.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Preset {
     /// Spots like a leopard
     Spots,
@@ -42,13 +513,20 @@ pub enum Preset {
 }
 
 impl Preset {
+    pub const ALL: [Preset; 5] = [Preset::Spots, Preset::Stripes, Preset::Coral, Preset::Mitosis, Preset::Worms];
+
+    /// Look a preset up by its lowercase CLI name.
+    pub fn from_name(name: &str) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|p| format!("{:?}", p).eq_ignore_ascii_case(name))
+    }
+
     pub fn params(self) -> GrayScottParams {
         match self {
-            Preset::Spots => GrayScottParams { da: 1.0, db: 0.5, feed: 0.035, kill: 0.065, dt: 1.0 },
-            Preset::Stripes => GrayScottParams { da: 1.0, db: 0.5, feed: 0.04, kill: 0.06, dt: 1.0 },
-            Preset::Coral => GrayScottParams { da: 1.0, db: 0.5, feed: 0.06, kill: 0.062, dt: 1.0 },
-            Preset::Mitosis => GrayScottParams { da: 1.0, db: 0.5, feed: 0.028, kill: 0.062, dt: 1.0 },
-            Preset::Worms => GrayScottParams { da: 1.0, db: 0.5, feed: 0.058, kill: 0.065, dt: 1.0 },
+            Preset::Spots => GrayScottParams { da: 1.0, db: 0.5, feed: 0.035, kill: 0.065, dt: 1.0, anisotropy: Anisotropy::ISOTROPIC, boundary: Boundary::Periodic },
+            Preset::Stripes => GrayScottParams { da: 1.0, db: 0.5, feed: 0.04, kill: 0.06, dt: 1.0, anisotropy: Anisotropy::ISOTROPIC, boundary: Boundary::Periodic },
+            Preset::Coral => GrayScottParams { da: 1.0, db: 0.5, feed: 0.06, kill: 0.062, dt: 1.0, anisotropy: Anisotropy::ISOTROPIC, boundary: Boundary::Periodic },
+            Preset::Mitosis => GrayScottParams { da: 1.0, db: 0.5, feed: 0.028, kill: 0.062, dt: 1.0, anisotropy: Anisotropy::ISOTROPIC, boundary: Boundary::Periodic },
+            Preset::Worms => GrayScottParams { da: 1.0, db: 0.5, feed: 0.058, kill: 0.065, dt: 1.0, anisotropy: Anisotropy::ISOTROPIC, boundary: Boundary::Periodic },
         }
     }
 
@@ -65,10 +543,15 @@ impl Preset {
 
 /// A 2D grid for reaction-diffusion simulation.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Grid {
     pub width: usize,
     pub height: usize,
     pub cells: Vec<Cell>,
+    /// Per-cell direction of fast diffusion (radians), overriding the
+    /// model's anisotropy angle
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub orientation: Option<Vec<f64>>,
 }
 
 impl Grid {
@@ -89,7 +572,7 @@ impl Grid {
                 }
             }
         }
-        Grid { width, height, cells }
+        Grid { width, height, cells, orientation: None }
     }
 
     /// Create with random seed points for more interesting patterns.
@@ -112,57 +595,147 @@ impl Grid {
         grid
     }
 
+    /// The model's steady state everywhere, with each chemical perturbed by
+    /// up to ±`noise` (relative) — the random fluctuations that a Turing
+    /// instability amplifies into a pattern.
+    pub fn perturbed<M: ReactionModel + ?Sized>(width: usize, height: usize, model: &M, noise: f64, seed: u64) -> Self {
+        let rest = model.steady_state();
+        let mut rng = super::fractals::SimpleRng::new(seed);
+        let mut jitter = |v: f64| v + (rng.next_f64() * 2.0 - 1.0) * noise * v.abs().max(0.1);
+        let cells = (0..width * height).map(|_| Cell { a: jitter(rest.a), b: jitter(rest.b) }).collect();
+        Grid { width, height, cells, orientation: None }
+    }
+
+    /// Seed from a row-major field in [0, 1], such as a grayscale image:
+    /// each cell takes B from the field and A = 1 − B.
+    pub fn from_field(width: usize, height: usize, values: &[f64]) -> Self {
+        let cells = (0..width * height)
+            .map(|k| {
+                let b = values.get(k).copied().unwrap_or(0.0).clamp(0.0, 1.0);
+                Cell { a: 1.0 - b, b }
+            })
+            .collect();
+        Grid { width, height, cells, orientation: None }
+    }
+
+    /// Align anisotropic diffusion with a vector field: `field(x, y)` gives
+    /// the fast direction at each cell, so stripes can follow a flow, a
+    /// limb or a fin ray. Has no effect with an isotropic model.
+    pub fn with_orientation(mut self, field: impl Fn(usize, usize) -> (f64, f64)) -> Self {
+        let angles = (0..self.width * self.height)
+            .map(|k| {
+                let (vx, vy) = field(k % self.width, k / self.width);
+                vy.atan2(vx)
+            })
+            .collect();
+        self.orientation = Some(angles);
+        self
+    }
+
     /// Get cell at (x, y) with wrapping boundary conditions.
     pub fn get(&self, x: isize, y: isize) -> Cell {
-        let wx = ((x % self.width as isize) + self.width as isize) as usize % self.width;
-        let wy = ((y % self.height as isize) + self.height as isize) as usize % self.height;
-        self.cells[wy * self.width + wx]
+        self.sample(x, y, Boundary::Periodic)
     }
 
-    /// Compute Laplacian of chemical concentrations at (x, y).
-    fn laplacian(&self, x: usize, y: usize) -> (f64, f64) {
+    /// Get cell at (x, y), which may lie beyond the edges, under `boundary`.
+    pub fn sample(&self, x: isize, y: isize, boundary: Boundary) -> Cell {
+        match (boundary.index(x, self.width), boundary.index(y, self.height), boundary) {
+            (Some(x), Some(y), _) => self.cells[y * self.width + x],
+            (_, _, Boundary::Fixed(outside)) => outside,
+            _ => unreachable!("only fixed boundaries leave the grid"),
+        }
+    }
+
+    /// Compute Laplacian of chemical concentrations at (x, y). With
+    /// `directional` = (across, extra, angle) it is the anisotropic
+    /// ∇·(D∇) for D = across·I + extra·ddᵀ, d the unit vector at `angle`.
+    fn laplacian(&self, x: usize, y: usize, directional: Option<(f64, f64, f64)>, boundary: Boundary) -> (f64, f64) {
         let xi = x as isize;
         let yi = y as isize;
-        let center = self.get(xi, yi);
-        // 5-point stencil
-        let neighbors = [
-            self.get(xi - 1, yi),
-            self.get(xi + 1, yi),
-            self.get(xi, yi - 1),
-            self.get(xi, yi + 1),
-        ];
-        let la = neighbors.iter().map(|c| c.a).sum::<f64>() - 4.0 * center.a;
-        let lb = neighbors.iter().map(|c| c.b).sum::<f64>() - 4.0 * center.b;
-        (la, lb)
-    }
-
-    /// Advance simulation by one time step using Gray-Scott model.
+        let center = self.cells[y * self.width + x];
+        let at = |(dx, dy): (isize, isize)| self.sample(xi + dx, yi + dy, boundary);
+        let [left, right, up, down] = [(-1, 0), (1, 0), (0, -1), (0, 1)].map(at);
+        let [se, nw, ne, sw] = [(1, 1), (-1, -1), (1, -1), (-1, 1)].map(at);
+        let apply = |v: fn(&Cell) -> f64| {
+            // Isotropic 9-point stencil
+            let edges = v(&left) + v(&right) + v(&up) + v(&down);
+            let corners = v(&se) + v(&nw) + v(&ne) + v(&sw);
+            let laplacian = (4.0 * edges + corners - 20.0 * v(&center)) / 6.0;
+            let Some((across, extra, angle)) = directional else {
+                return laplacian;
+            };
+            // Second derivative along d
+            let uxx = v(&left) + v(&right) - 2.0 * v(&center);
+            let uyy = v(&up) + v(&down) - 2.0 * v(&center);
+            let uxy = (v(&se) + v(&nw) - v(&ne) - v(&sw)) / 4.0;
+            let (sin, cos) = angle.sin_cos();
+            across * laplacian + extra * (cos * cos * uxx + 2.0 * cos * sin * uxy + sin * sin * uyy)
+        };
+        (apply(|c| c.a), apply(|c| c.b))
+    }
+
+    /// Advance simulation by one explicit time step of `model`, by default
+    /// the Gray-Scott model:
     ///
     /// ∂A/∂t = Dₐ∇²A - AB² + f(1-A)
     /// ∂B/∂t = D_b∇²B + AB² - (k+f)B
-    pub fn step(&mut self, params: &GrayScottParams) {
+    pub fn step<M: ReactionModel + Sync + ?Sized>(&mut self, model: &M) {
+        let (da, db) = model.diffusion();
+        let dt = model.dt();
+        let anisotropy = model.anisotropy();
+        let (across, extra) = anisotropy.split();
+        let boundary = model.boundary();
         let mut new_cells = self.cells.clone();
-        for y in 0..self.height {
-            for x in 0..self.width {
+        for_each_row(&mut new_cells, self.width, |y, row| {
+            for (x, out) in row.iter_mut().enumerate() {
                 let cell = self.cells[y * self.width + x];
-                let (la, lb) = self.laplacian(x, y);
-                let ab2 = cell.a * cell.b * cell.b;
-                let new_a = cell.a + params.dt * (params.da * la - ab2 + params.feed * (1.0 - cell.a));
-                let new_b = cell.b + params.dt * (params.db * lb + ab2 - (params.kill + params.feed) * cell.b);
-                new_cells[y * self.width + x] = Cell {
-                    a: new_a.clamp(0.0, 1.0),
-                    b: new_b.clamp(0.0, 1.0),
+                let directional = match &self.orientation {
+                    _ if anisotropy.is_isotropic() => None,
+                    Some(angles) => Some((across, extra, angles[y * self.width + x])),
+                    None => Some((across, extra, anisotropy.angle)),
                 };
+                let (la, lb) = self.laplacian(x, y, directional, boundary);
+                let (ra, rb) = model.reaction_at(x, y, cell);
+                *out = model.clamp(Cell { a: cell.a + dt * (da * la + ra), b: cell.b + dt * (db * lb + rb) });
             }
-        }
+        });
         self.cells = new_cells;
     }
 
     /// Run simulation for n steps.
-    pub fn simulate(&mut self, params: &GrayScottParams, steps: usize) {
+    pub fn simulate<M: ReactionModel + Sync + ?Sized>(&mut self, model: &M, steps: usize) {
         for _ in 0..steps {
-            self.step(params);
+            self.step(model);
+        }
+    }
+
+    /// Run simulation for n steps, reporting after each one. On cancellation
+    /// the grid keeps the state reached so far.
+    pub fn simulate_with_progress<M: ReactionModel + Sync + ?Sized>(
+        &mut self,
+        model: &M,
+        steps: usize,
+        progress: &mut impl Progress,
+    ) -> Result<()> {
+        for i in 0..steps {
+            self.step(model);
+            checkpoint(progress, i + 1, steps)?;
         }
+        Ok(())
+    }
+
+    /// Run `steps` steps, keeping a copy of the grid after every `every`
+    /// steps — the frames of the pattern's formation.
+    pub fn simulate_with_snapshots<M: ReactionModel + Sync + ?Sized>(&mut self, model: &M, steps: usize, every: usize) -> Vec<Grid> {
+        let every = every.max(1);
+        let mut snapshots = Vec::with_capacity(steps / every);
+        for i in 1..=steps {
+            self.step(model);
+            if i % every == 0 {
+                snapshots.push(self.clone());
+            }
+        }
+        snapshots
     }
 
     /// Calculate average concentrations.
@@ -181,22 +754,155 @@ impl Grid {
     }
 }
 
+impl Generator for Grid {
+    /// Concentration of B, where the pattern shows.
+    fn generate(&self) -> Geometry {
+        Geometry::Grid { cols: self.width, rows: self.height, values: self.cells.iter().map(|c| c.b).collect() }
+    }
+}
+
+/// Side of one grid cell in rendered images, in pixels.
+pub const CELL_PIXELS: usize = 4;
+
 /// Generate a simple SVG heatmap of the grid's B chemical.
 pub fn grid_to_svg(grid: &Grid) -> String {
-    let scale = 4;
-    let w = grid.width * scale;
-    let h = grid.height * scale;
+    grid_to_svg_with_theme(grid, &Theme::dark())
+}
+
+/// [`grid_to_svg`] in `theme`, along its palette if it has one.
+pub fn grid_to_svg_with_theme(grid: &Grid, theme: &Theme) -> String {
+    heatmap_to_svg_with_theme(grid.width, grid.height, &absolute_values(grid), field_colormap(theme), theme)
+}
+
+/// Heatmap of `model`'s pattern variable, stretched over the grid's own
+/// range so models with unbounded concentrations render alike.
+pub fn model_to_svg<M: ReactionModel + ?Sized>(grid: &Grid, model: &M) -> String {
+    model_to_svg_with_theme(grid, model, &Theme::dark())
+}
+
+/// [`model_to_svg`] in `theme`, along its palette if it has one.
+pub fn model_to_svg_with_theme<M: ReactionModel + ?Sized>(grid: &Grid, model: &M, theme: &Theme) -> String {
+    heatmap_to_svg_with_theme(grid.width, grid.height, &stretched_values(grid, model), field_colormap(theme), theme)
+}
+
+/// The theme's palette, or inferno without one.
+fn field_colormap(theme: &Theme) -> &dyn Colormap {
+    match &theme.palette {
+        Some(palette) => palette,
+        None => &INFERNO,
+    }
+}
+
+/// The [`grid_to_svg`] heatmap as row-major RGB bytes, [`CELL_PIXELS`]
+/// pixels per cell, for PNG frames.
+pub fn grid_to_rgb(grid: &Grid) -> Vec<u8> {
+    heatmap_to_rgb(grid.width, grid.height, &absolute_values(grid), &INFERNO)
+}
+
+/// The [`model_to_svg`] heatmap as row-major RGB bytes.
+pub fn model_to_rgb<M: ReactionModel + ?Sized>(grid: &Grid, model: &M) -> Vec<u8> {
+    heatmap_to_rgb(grid.width, grid.height, &stretched_values(grid, model), &INFERNO)
+}
+
+/// The regions where B exceeds `level` as filled, interpolated outlines —
+/// a few paths instead of one `<rect>` per cell, so large grids stay
+/// compact and scale without pixelation.
+pub fn grid_to_contour_svg(grid: &Grid, level: f64) -> String {
+    grid_to_contour_svg_with_theme(grid, level, &Theme::dark())
+}
+
+/// [`grid_to_contour_svg`] in `theme`, along its palette if it has one.
+pub fn grid_to_contour_svg_with_theme(grid: &Grid, level: f64, theme: &Theme) -> String {
+    contour_to_svg_with_theme(grid.width, grid.height, &absolute_values(grid), level, field_colormap(theme), theme)
+}
+
+/// [`grid_to_contour_svg`] for `model`'s pattern variable, with `level` a
+/// fraction of its range on the grid.
+pub fn model_to_contour_svg<M: ReactionModel + ?Sized>(grid: &Grid, model: &M, level: f64) -> String {
+    model_to_contour_svg_with_theme(grid, model, level, &Theme::dark())
+}
+
+/// [`model_to_contour_svg`] in `theme`, along its palette if it has one.
+pub fn model_to_contour_svg_with_theme<M: ReactionModel + ?Sized>(grid: &Grid, model: &M, level: f64, theme: &Theme) -> String {
+    contour_to_svg_with_theme(grid.width, grid.height, &stretched_values(grid, model), level, field_colormap(theme), theme)
+}
+
+/// The values the heatmaps above color: B as it is, or with a `model`,
+/// its pattern variable stretched to [0, 1].
+pub fn heatmap_values<M: ReactionModel + ?Sized>(grid: &Grid, model: Option<&M>) -> Vec<f64> {
+    match model {
+        Some(model) => stretched_values(grid, model),
+        None => absolute_values(grid),
+    }
+}
+
+/// Contours of row-major `values` at `level`, filled with the top of
+/// `colormap` over its bottom.
+pub fn contour_to_svg(width: usize, height: usize, values: &[f64], level: f64, colormap: &dyn Colormap) -> String {
+    contour_to_svg_with_theme(width, height, values, level, colormap, &Theme::dark())
+}
+
+/// [`contour_to_svg`] on `theme`'s background.
+pub fn contour_to_svg_with_theme(width: usize, height: usize, values: &[f64], level: f64, colormap: &dyn Colormap, theme: &Theme) -> String {
+    let (w, h) = (width * CELL_PIXELS, height * CELL_PIXELS);
+    // Sample (i, j) is the center of cell (i, j)
+    let regions: Vec<Vec<Point>> = crate::contour::filled_regions(values, width, height, level)
+        .into_iter()
+        .map(|line| line.into_iter().map(|p| Point { x: p.x + 0.5, y: p.y + 0.5 }).collect())
+        .collect();
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+<rect width="{w}" height="{h}" fill="{}"/>
+{}<rect width="{w}" height="{h}" fill="{}"/>
+<path d="{}" fill="{}" fill-rule="evenodd"/>
+{}</svg>"#,
+        theme.background_css(),
+        theme.foreground_start(),
+        colormap.css(0.0),
+        crate::contour::path_data(&regions, CELL_PIXELS as f64),
+        colormap.css(1.0),
+        theme.foreground_end()
+    )
+}
+
+fn absolute_values(grid: &Grid) -> Vec<f64> {
+    grid.cells.iter().map(|c| c.b).collect()
+}
+
+fn stretched_values<M: ReactionModel + ?Sized>(grid: &Grid, model: &M) -> Vec<f64> {
+    let values: Vec<f64> = grid.cells.iter().map(|&c| model.pattern_value(c)).collect();
+    let (lo, hi) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let range = (hi - lo).max(1e-12);
+    values.iter().map(|v| (v - lo) / range).collect()
+}
+
+/// Row-major values in [0, 1] as `colormap`-colored RGB bytes, [`CELL_PIXELS`]
+/// pixels per cell.
+pub fn heatmap_to_rgb(width: usize, height: usize, values: &[f64], colormap: &dyn Colormap) -> Vec<u8> {
+    let (w, h) = (width * CELL_PIXELS, height * CELL_PIXELS);
+    (0..w * h).flat_map(|k| colormap.rgb(values[(k / w / CELL_PIXELS) * width + (k % w) / CELL_PIXELS])).collect()
+}
+
+/// Row-major values in [0, 1] as 4-pixel `colormap`-colored cells.
+pub fn heatmap_to_svg(width: usize, height: usize, values: &[f64], colormap: &dyn Colormap) -> String {
+    heatmap_to_svg_with_theme(width, height, values, colormap, &Theme::dark())
+}
+
+/// [`heatmap_to_svg`] on `theme`'s background.
+pub fn heatmap_to_svg_with_theme(width: usize, height: usize, values: &[f64], colormap: &dyn Colormap, theme: &Theme) -> String {
+    let scale = CELL_PIXELS;
+    let w = width * scale;
+    let h = height * scale;
     let mut svg = format!(
         r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
-"#
+<rect width="{w}" height="{h}" fill="{}"/>
+{}"#,
+        theme.background_css(),
+        theme.foreground_start()
     );
-    for y in 0..grid.height {
-        for x in 0..grid.width {
-            let cell = &grid.cells[y * grid.width + x];
-            let v = (cell.b * 255.0).clamp(0.0, 255.0) as u8;
-            let r = v;
-            let g = (v as f64 * 0.6) as u8;
-            let b_col = 50 + v / 2;
+    for y in 0..height {
+        for x in 0..width {
+            let [r, g, b_col] = colormap.rgb(values[y * width + x]);
             svg.push_str(&format!(
                 r#"<rect x="{}" y="{}" width="{scale}" height="{scale}" fill="rgb({r},{g},{b_col})"/>
 "#,
@@ -204,6 +910,7 @@ pub fn grid_to_svg(grid: &Grid) -> String {
             ));
         }
     }
+    svg.push_str(theme.foreground_end());
     svg.push_str("</svg>");
     svg
 }
@@ -298,4 +1005,300 @@ mod tests {
         assert!(svg.contains("<svg"));
         assert!(svg.contains("<rect"));
     }
+
+    #[test]
+    fn test_simulate_with_progress() {
+        let params = Preset::Spots.params();
+        let mut reported = Vec::new();
+        let mut grid = Grid::new(20, 20);
+        grid.simulate_with_progress(&params, 5, &mut |done, total| {
+            reported.push((done, total));
+            true
+        })
+        .unwrap();
+        assert_eq!(reported, vec![(1, 5), (2, 5), (3, 5), (4, 5), (5, 5)]);
+
+        // Cancelling after three steps leaves the grid three steps in
+        let mut cancelled = Grid::new(20, 20);
+        let result = cancelled.simulate_with_progress(&params, 100, &mut |done, _| done < 3);
+        assert!(matches!(result, Err(crate::error::MathaturaError::Cancelled)));
+        let mut expected = Grid::new(20, 20);
+        expected.simulate(&params, 3);
+        assert_eq!(cancelled.averages(), expected.averages());
+    }
+
+    #[test]
+    fn test_validate() {
+        for preset in [Preset::Spots, Preset::Stripes, Preset::Coral, Preset::Mitosis, Preset::Worms] {
+            assert!(preset.params().validate().is_ok());
+        }
+        let params = Preset::Spots.params();
+        assert!(GrayScottParams { feed: 0.5, ..params }.validate().is_err());
+        assert!(GrayScottParams { da: 0.0, ..params }.validate().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let params = Preset::Coral.params();
+        let json = serde_json::to_string(&params).unwrap();
+        let back: GrayScottParams = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.feed, params.feed);
+        assert_eq!(back.kill, params.kill);
+        let preset: Preset = serde_json::from_str("\"Coral\"").unwrap();
+        assert_eq!(preset.params().feed, params.feed);
+    }
+
+    #[test]
+    fn test_from_field() {
+        let grid = Grid::from_field(2, 2, &[0.0, 0.25, 1.0, 3.0]);
+        assert_eq!(grid.cells[1].b, 0.25);
+        assert_eq!(grid.cells[1].a, 0.75);
+        assert_eq!(grid.cells[3].b, 1.0);
+        // Short fields leave the rest at the steady state
+        let grid = Grid::from_field(3, 1, &[0.5]);
+        assert_eq!((grid.cells[2].a, grid.cells[2].b), (1.0, 0.0));
+    }
+
+    /// Spread of the model's pattern variable over the grid.
+    fn spread<M: ReactionModel>(grid: &Grid, model: &M) -> f64 {
+        let values = grid.cells.iter().map(|&c| model.pattern_value(c));
+        values.clone().fold(f64::NEG_INFINITY, f64::max) - values.fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn test_steady_states() {
+        let gm = [GiererMeinhardtParams::spots(), GiererMeinhardtParams::stripes()];
+        let fhn = [FitzHughNagumoParams::spots(), FitzHughNagumoParams::labyrinth()];
+        let gray_scott = Preset::Spots.params();
+        let models: Vec<&dyn ReactionModel> = vec![&gm[0], &gm[1], &fhn[0], &fhn[1], &gray_scott];
+        for model in models {
+            let (ra, rb) = model.reaction(model.steady_state());
+            assert!(ra.abs() < 1e-9 && rb.abs() < 1e-9, "{:?}", (ra, rb));
+        }
+    }
+
+    #[test]
+    fn test_gierer_meinhardt_spots() {
+        let model = GiererMeinhardtParams::spots();
+        let mut grid = Grid::perturbed(40, 40, &model, 0.05, 3);
+        let before = spread(&grid, &model);
+        grid.simulate(&model, 3000);
+        // Small fluctuations grow into activator peaks on an inhibited floor
+        assert!(spread(&grid, &model) > 20.0 * before, "{} -> {}", before, spread(&grid, &model));
+        assert!(grid.cells.iter().all(|c| c.a.is_finite() && c.b > 0.0));
+    }
+
+    #[test]
+    fn test_fitzhugh_nagumo_labyrinth() {
+        let model = FitzHughNagumoParams::labyrinth();
+        let mut grid = Grid::perturbed(40, 40, &model, 0.05, 3);
+        grid.simulate(&model, 3000);
+        // Excited (u > 0) and resting (u < 0) regions both take hold
+        let excited = grid.cells.iter().filter(|c| c.a > 0.5).count();
+        let resting = grid.cells.iter().filter(|c| c.a < -0.5).count();
+        assert!(excited > 200 && resting > 200, "{} excited, {} resting", excited, resting);
+    }
+
+    #[test]
+    fn test_model_validate() {
+        assert!(GiererMeinhardtParams::spots().validate().is_ok());
+        assert!(GiererMeinhardtParams::stripes().validate().is_ok());
+        assert!(FitzHughNagumoParams::spots().validate().is_ok());
+        assert!(FitzHughNagumoParams::labyrinth().validate().is_ok());
+        // The inhibitor must outrun the activator, and dt must respect diffusion
+        assert!(GiererMeinhardtParams { dh: 0.5, ..GiererMeinhardtParams::spots() }.validate().is_err());
+        assert!(GiererMeinhardtParams { dt: 0.1, ..GiererMeinhardtParams::spots() }.validate().is_err());
+        assert!(FitzHughNagumoParams { tau: 0.0, ..FitzHughNagumoParams::spots() }.validate().is_err());
+        assert!(FitzHughNagumoParams { dt: 0.01, ..FitzHughNagumoParams::spots() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_model_svg() {
+        let model = FitzHughNagumoParams::spots();
+        let grid = Grid::perturbed(5, 4, &model, 0.1, 1);
+        let svg = model_to_svg(&grid, &model);
+        // One per cell on the background
+        assert_eq!(svg.matches("<rect").count(), 21);
+        // Stretched to the full range whatever the model's units
+        assert!(svg.contains("rgb(252,255,164)") && svg.contains("rgb(0,0,4)"));
+    }
+
+    /// Squared differences of B between horizontal and vertical neighbours.
+    fn gradient_energy(grid: &Grid) -> (f64, f64) {
+        let (mut ex, mut ey) = (0.0, 0.0);
+        for y in 0..grid.height as isize {
+            for x in 0..grid.width as isize {
+                let b = grid.get(x, y).b;
+                ex += (grid.get(x + 1, y).b - b).powi(2);
+                ey += (grid.get(x, y + 1).b - b).powi(2);
+            }
+        }
+        (ex, ey)
+    }
+
+    #[test]
+    fn test_presets_stay_smooth() {
+        // The time step is stable: no cell-scale checkerboard
+        let mut grid = Grid::new_random(40, 40, 42);
+        grid.simulate(&Preset::Stripes.params(), 1000);
+        let (ex, ey) = gradient_energy(&grid);
+        assert!((ex + ey) / 1600.0 < 0.02, "{}", (ex + ey) / 1600.0);
+    }
+
+    #[test]
+    fn test_anisotropic_stripes() {
+        let params = Preset::Stripes.params();
+        // Horizontal stripes vary vertically, and the other way round
+        let mut horizontal = Grid::new_random(48, 48, 42);
+        horizontal.simulate(&params.with_anisotropy(0.0, 3.0), 2000);
+        let (ex, ey) = gradient_energy(&horizontal);
+        assert!(ey > 2.0 * ex, "{} {}", ex, ey);
+        let mut vertical = Grid::new_random(48, 48, 42);
+        vertical.simulate(&params.with_anisotropy(core::f64::consts::FRAC_PI_2, 3.0), 2000);
+        let (ex, ey) = gradient_energy(&vertical);
+        assert!(ex > 2.0 * ey, "{} {}", ex, ey);
+    }
+
+    #[test]
+    fn test_orientation_field() {
+        let params = Preset::Stripes.params().with_anisotropy(0.0, 3.0);
+        let plain = Grid::new_random(20, 20, 1);
+        // A uniform field along x matches the fixed angle
+        let mut along = plain.clone().with_orientation(|_, _| (2.0, 0.0));
+        let mut fixed = plain.clone();
+        along.simulate(&params, 50);
+        fixed.simulate(&params, 50);
+        assert_eq!(along.averages(), fixed.averages());
+        // An isotropic model ignores the field
+        let mut ignored = plain.clone().with_orientation(|x, y| (x as f64, y as f64));
+        let mut iso = plain;
+        ignored.simulate(&Preset::Stripes.params(), 50);
+        iso.simulate(&Preset::Stripes.params(), 50);
+        assert_eq!(ignored.averages(), iso.averages());
+        assert!(params.validate().is_ok());
+        assert!(Preset::Spots.params().with_anisotropy(0.0, 50.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_uniform_map_matches_params() {
+        let params = Preset::Coral.params();
+        let map = FeedKillMap::from_fn(params, 20, 20, |_, _| (params.feed, params.kill));
+        let mut mapped = Grid::new_random(20, 20, 5);
+        let mut plain = mapped.clone();
+        mapped.simulate(&map, 100);
+        plain.simulate(&params, 100);
+        assert_eq!(mapped.averages(), plain.averages());
+    }
+
+    #[test]
+    fn test_feed_kill_gradient() {
+        let map = FeedKillMap::gradient(11, 3, Preset::Spots, Preset::Stripes);
+        assert!(map.validate().is_ok());
+        assert_eq!((map.feed[0], map.kill[0]), (0.035, 0.065));
+        assert!((map.feed[10] - 0.04).abs() < 1e-12 && (map.kill[32] - 0.06).abs() < 1e-12);
+        // Only the varying rates change the outcome
+        let mut varying = Grid::new_random(11, 3, 5);
+        let mut plain = varying.clone();
+        varying.simulate(&map, 50);
+        plain.simulate(&map.params, 50);
+        assert_ne!(varying.averages(), plain.averages());
+    }
+
+    #[test]
+    fn test_feed_kill_arrays() {
+        let params = Preset::Spots.params();
+        assert!(FeedKillMap::from_arrays(params, 2, 2, vec![0.03; 4], vec![0.06; 4]).is_ok());
+        assert!(FeedKillMap::from_arrays(params, 2, 2, vec![0.03; 3], vec![0.06; 4]).is_err());
+        let bad = FeedKillMap::from_arrays(params, 1, 2, vec![0.03, 0.5], vec![0.06; 2]).unwrap();
+        assert!(bad.validate().is_err());
+        assert!(matches!(Preset::from_name("coral"), Some(Preset::Coral)));
+        assert!(Preset::from_name("plaid").is_none());
+    }
+
+    #[test]
+    fn test_boundary_index() {
+        let indices = |b: Boundary| (-2..6).map(|i| b.index(i, 4)).collect::<Vec<_>>();
+        let some = |v: [usize; 8]| v.map(Some).to_vec();
+        assert_eq!(indices(Boundary::Periodic), some([2, 3, 0, 1, 2, 3, 0, 1]));
+        assert_eq!(indices(Boundary::NoFlux), some([0, 0, 0, 1, 2, 3, 3, 3]));
+        assert_eq!(indices(Boundary::Mirror), some([2, 1, 0, 1, 2, 3, 2, 1]));
+        let fixed = indices(Boundary::Fixed(Cell { a: 1.0, b: 0.0 }));
+        assert_eq!(fixed[0..2], [None, None]);
+        assert_eq!(fixed[2..6], [Some(0), Some(1), Some(2), Some(3)]);
+        assert_eq!(Boundary::Mirror.index(5, 1), Some(0));
+    }
+
+    #[test]
+    fn test_no_flux_conserves() {
+        // Zero flux across the edges: diffusion alone only moves material
+        let grid = Grid::new_random(12, 9, 3);
+        for boundary in [Boundary::Periodic, Boundary::NoFlux] {
+            let total: f64 = (0..9).flat_map(|y| (0..12).map(move |x| (x, y))).map(|(x, y)| grid.laplacian(x, y, None, boundary).1).sum();
+            assert!(total.abs() < 1e-9, "{:?}: {}", boundary, total);
+        }
+        let outside = Cell { a: 1.0, b: 0.0 };
+        assert_eq!(grid.sample(-1, 4, Boundary::Fixed(outside)), outside);
+        assert_eq!(grid.sample(-1, 4, Boundary::Mirror), grid.cells[4 * 12 + 1]);
+    }
+
+    #[test]
+    fn test_edges_do_not_wrap() {
+        // A seed on the left edge reaches the right edge only on a torus
+        let mut seeded = Grid::from_field(30, 10, &[0.0; 300]);
+        for y in 0..10 {
+            seeded.cells[y * 30] = Cell { a: 0.0, b: 1.0 };
+        }
+        let params = Preset::Coral.params();
+        let right_edge = |grid: &Grid| (0..10).map(|y| grid.cells[y * 30 + 29].b).sum::<f64>();
+        let mut periodic = seeded.clone();
+        periodic.simulate(&params, 10);
+        assert!(right_edge(&periodic) > 0.01);
+        for boundary in [Boundary::NoFlux, Boundary::Mirror, Boundary::Fixed(params.steady_state())] {
+            let mut closed = seeded.clone();
+            closed.simulate(&params.with_boundary(boundary), 10);
+            assert_eq!(right_edge(&closed), 0.0, "{:?}", boundary);
+        }
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let params = Preset::Worms.params();
+        let mut grid = Grid::new_random(16, 16, 2);
+        let snapshots = grid.simulate_with_snapshots(&params, 10, 3);
+        assert_eq!(snapshots.len(), 3);
+        let mut expected = Grid::new_random(16, 16, 2);
+        expected.simulate(&params, 6);
+        assert_eq!(snapshots[1].cells, expected.cells);
+        // The grid itself runs all the steps
+        expected.simulate(&params, 4);
+        assert_eq!(grid.cells, expected.cells);
+    }
+
+    #[test]
+    fn test_heatmap_rgb() {
+        let grid = Grid::from_field(2, 1, &[0.0, 1.0]);
+        let rgb = grid_to_rgb(&grid);
+        assert_eq!(rgb.len(), 2 * CELL_PIXELS * CELL_PIXELS * 3);
+        assert_eq!(&rgb[..3], &[0, 0, 4]);
+        assert_eq!(&rgb[CELL_PIXELS * 3..CELL_PIXELS * 3 + 3], &[252, 255, 164]);
+        let model = FitzHughNagumoParams::spots();
+        assert_eq!(model_to_rgb(&grid, &model).len(), rgb.len());
+    }
+
+    #[test]
+    fn test_contour_svg() {
+        let mut grid = Grid::new_random(60, 60, 42);
+        grid.simulate(&Preset::Spots.params(), 1500);
+        let contours = grid_to_contour_svg(&grid, 0.2);
+        assert_eq!(contours.matches("<path").count(), 1);
+        assert!(contours.contains("fill-rule=\"evenodd\""));
+        // Far smaller than a rect per cell
+        assert!(contours.len() * 5 < grid_to_svg(&grid).len(), "{} bytes", contours.len());
+        // Nothing above the level: just the background
+        let empty = grid_to_contour_svg(&Grid::from_field(4, 4, &[]), 0.5);
+        assert!(empty.contains(r#"d="""#));
+        let model = FitzHughNagumoParams::labyrinth();
+        assert!(model_to_contour_svg(&Grid::perturbed(8, 8, &model, 0.1, 1), &model, 0.5).contains("M"));
+    }
 }
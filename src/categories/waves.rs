@@ -0,0 +1,281 @@
+//! Wave interference — ripples on a pond.
+//!
+//! Raindrops or a pair of tapping fingers send out circular waves. The
+//! surface height is the sum of every source's ripple,
+//!   h(x, y) = Σ Aᵢ e^(−κᵢ dᵢ) cos(2π dᵢ / λᵢ − φᵢ),   dᵢ = |(x, y) − sᵢ|
+//! Where crests meet crests the water heaps up; where crests meet troughs it
+//! stays flat, tracing hyperbolic nodal lines — Young's double-slit pattern.
+//! Many sources with close wavelengths beat against each other in moiré
+//! figures.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::error::{check_positive, Result};
+use crate::geometry::{Generator, Geometry};
+use crate::render::colormap::Colormap;
+use crate::render::theme::Theme;
+use crate::parallel::map_range;
+use super::fractals::SimpleRng;
+
+/// A point source of circular waves, positioned in the unit square.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Source {
+    pub x: f64,
+    pub y: f64,
+    pub wavelength: f64,
+    /// Phase offset in radians
+    pub phase: f64,
+    pub amplitude: f64,
+    /// Exponential decay rate with distance
+    pub decay: f64,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self { x: 0.5, y: 0.5, wavelength: 0.05, phase: 0.0, amplitude: 1.0, decay: 0.0 }
+    }
+}
+
+impl Source {
+    /// This source's contribution at (x, y).
+    pub fn height(&self, x: f64, y: f64) -> f64 {
+        let d = (x - self.x).hypot(y - self.y);
+        self.amplitude * (-self.decay * d).exp() * (2.0 * PI * d / self.wavelength - self.phase).cos()
+    }
+}
+
+/// Superposition of circular waves.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WaveField {
+    pub sources: Vec<Source>,
+}
+
+/// Source arrangements with characteristic interference figures.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// Two coherent sources: hyperbolic nodal lines
+    TwoSource,
+    /// Sources on a ring: a star of fringes around a calm center
+    Ring,
+    /// Random raindrops with slightly different wavelengths and decay
+    Rain,
+    /// Two slightly different wavelengths from nearby points: moiré beats
+    Moire,
+}
+
+impl Preset {
+    pub fn field(self, seed: u64) -> WaveField {
+        let src = Source::default();
+        let sources = match self {
+            Preset::TwoSource => vec![Source { x: 0.4, ..src }, Source { x: 0.6, ..src }],
+            Preset::Ring => (0..6)
+                .map(|i| {
+                    let a = 2.0 * PI * i as f64 / 6.0;
+                    Source { x: 0.5 + 0.25 * a.cos(), y: 0.5 + 0.25 * a.sin(), ..src }
+                })
+                .collect(),
+            Preset::Rain => {
+                let mut rng = SimpleRng::new(seed);
+                (0..12)
+                    .map(|_| Source {
+                        x: rng.next_f64(),
+                        y: rng.next_f64(),
+                        wavelength: 0.03 + 0.03 * rng.next_f64(),
+                        phase: rng.next_f64() * 2.0 * PI,
+                        amplitude: 0.5 + rng.next_f64(),
+                        decay: 4.0 + 4.0 * rng.next_f64(),
+                    })
+                    .collect()
+            }
+            Preset::Moire => vec![
+                Source { x: 0.45, wavelength: 0.02, ..src },
+                Source { x: 0.55, wavelength: 0.021, ..src },
+            ],
+        };
+        WaveField { sources }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::TwoSource => "Two Sources",
+            Preset::Ring => "Ring",
+            Preset::Rain => "Rain",
+            Preset::Moire => "Moiré",
+        }
+    }
+}
+
+impl WaveField {
+    /// Every source needs a positive wavelength; zero would divide the phase by zero.
+    pub fn validate(&self) -> Result<()> {
+        for source in &self.sources {
+            check_positive("wavelength", source.wavelength)?;
+        }
+        Ok(())
+    }
+
+    /// Surface height at (x, y).
+    pub fn height(&self, x: f64, y: f64) -> f64 {
+        self.sources.iter().map(|s| s.height(x, y)).sum()
+    }
+
+    /// Sample the unit square on a `resolution × resolution` grid (row-major).
+    pub fn sample(&self, resolution: usize) -> Vec<f64> {
+        let n = resolution.max(2);
+        let step = 1.0 / (n - 1) as f64;
+        map_range(n * n, |k| self.height((k % n) as f64 * step, (k / n) as f64 * step))
+    }
+}
+
+impl Generator for WaveField {
+    /// The surface sampled on a 200 × 200 grid.
+    fn generate(&self) -> Geometry {
+        Geometry::Grid { cols: 200, rows: 200, values: self.sample(200) }
+    }
+}
+
+/// How to draw the sampled surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Render {
+    /// Blue-white water colormap
+    Colormap,
+    /// Nodal line and crest contours from marching squares
+    Contours,
+}
+
+/// Render a sampled field of `resolution × resolution` heights.
+pub fn to_svg(values: &[f64], resolution: usize, render: Render) -> String {
+    to_svg_with_theme(values, resolution, render, &Theme::dark())
+}
+
+/// [`to_svg`] in `theme`, its palette standing in for the colormap of
+/// [`to_svg_with_colormap`] if it has one.
+pub fn to_svg_with_theme(values: &[f64], resolution: usize, render: Render, theme: &Theme) -> String {
+    match &theme.palette {
+        Some(palette) => shade(values, resolution, render, palette, theme),
+        None => {
+            let strokes = ["#4fc3f7", "#e1f5fe", "#1565c0"].map(String::from);
+            draw(values, resolution, render, |t| crate::render::hsl(205.0 - 15.0 * t, 70.0, 8.0 + 70.0 * t * t), strokes, theme)
+        }
+    }
+}
+
+/// [`to_svg`] along `colormap`, troughs at its start and crests at its end;
+/// contours take its ends and middle.
+pub fn to_svg_with_colormap(values: &[f64], resolution: usize, render: Render, colormap: &dyn Colormap) -> String {
+    shade(values, resolution, render, colormap, &Theme::dark())
+}
+
+fn shade(values: &[f64], resolution: usize, render: Render, colormap: &dyn Colormap, theme: &Theme) -> String {
+    let strokes = [0.5, 1.0, 0.0].map(|t| colormap.css(t));
+    draw(values, resolution, render, |t| colormap.css(t), strokes, theme)
+}
+
+/// `strokes` color the nodal lines, crests and troughs.
+fn draw(values: &[f64], resolution: usize, render: Render, color: impl Fn(f64) -> String, strokes: [String; 3], theme: &Theme) -> String {
+    let size = 800.0;
+    let cell = size / resolution.max(1) as f64;
+    let max = values.iter().map(|v| v.abs()).fold(1e-12, f64::max);
+    match render {
+        Render::Colormap => {
+            // 32 bands keep the rect count manageable after run merging
+            let values: Vec<f64> = values.iter().map(|v| ((v / max + 1.0) * 16.0).floor() / 32.0).collect();
+            crate::render::raster_svg_with_theme(resolution, resolution, cell, &values, color, theme)
+        }
+        Render::Contours => {
+            let scale = size / (resolution.max(2) - 1) as f64;
+            let mut content = String::new();
+            for ((level, width), color) in [(0.0, 1.2), (0.5 * max, 0.8), (-0.5 * max, 0.8)].into_iter().zip(strokes) {
+                let lines = crate::contour::marching_squares(values, resolution, resolution, level);
+                content.push_str(&format!(
+                    "<path d=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"{}\"/>\n",
+                    crate::contour::path_data(&lines, scale),
+                    theme.stroke_width(width)
+                ));
+            }
+            crate::render::svg_document_with_theme(size as u32, size as u32, &content, theme)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_source_crest_at_center() {
+        let s = Source::default();
+        assert!((s.height(0.5, 0.5) - 1.0).abs() < 1e-12);
+        // Half a wavelength away is a trough
+        assert!((s.height(0.5 + s.wavelength / 2.0, 0.5) + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_decay() {
+        let s = Source { decay: 5.0, ..Default::default() };
+        // Whole wavelengths away: crest heights shrink
+        assert!(s.height(0.5 + 0.2, 0.5) < s.height(0.5 + 0.1, 0.5));
+    }
+
+    #[test]
+    fn test_superposition_is_linear() {
+        let field = Preset::Ring.field(0);
+        let (x, y) = (0.31, 0.77);
+        let sum: f64 = field.sources.iter().map(|s| s.height(x, y)).sum();
+        assert!((field.height(x, y) - sum).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_two_source_interference() {
+        let field = Preset::TwoSource.field(0);
+        // Equidistant points interfere constructively
+        assert!(field.height(0.5, 0.5).abs() > 1.9);
+        // A path difference of half a wavelength cancels
+        let (s0, s1) = (field.sources[0], field.sources[1]);
+        let x = 0.5 + s0.wavelength / 4.0;
+        let d = (x - s0.x).abs() - (x - s1.x).abs();
+        assert!((d.abs() - s0.wavelength / 2.0).abs() < 1e-12);
+        assert!(field.height(x, 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Preset::Rain.field(3).validate().is_ok());
+        let mut field = Preset::TwoSource.field(0);
+        field.sources[1].wavelength = 0.0;
+        assert!(field.validate().is_err());
+    }
+
+    #[test]
+    fn test_rain_deterministic() {
+        assert_eq!(Preset::Rain.field(5), Preset::Rain.field(5));
+        assert_ne!(Preset::Rain.field(5), Preset::Rain.field(6));
+    }
+
+    #[test]
+    fn test_sample_grid() {
+        let values = Preset::TwoSource.field(0).sample(50);
+        assert_eq!(values.len(), 2500);
+    }
+
+    #[test]
+    fn test_nodal_contours() {
+        let values = Preset::TwoSource.field(0).sample(120);
+        let nodal = crate::contour::marching_squares(&values, 120, 120, 0.0);
+        assert!(nodal.len() > 5, "Expected many nodal lines, got {}", nodal.len());
+    }
+
+    #[test]
+    fn test_svg_outputs() {
+        let values = Preset::Moire.field(0).sample(60);
+        assert!(to_svg(&values, 60, Render::Colormap).contains("<rect"));
+        assert_eq!(to_svg(&values, 60, Render::Contours).matches("<path").count(), 3);
+        let contours = to_svg_with_colormap(&values, 60, Render::Contours, &crate::render::colormap::VIRIDIS);
+        assert!(contours.contains(&crate::render::viridis(1.0)) && !contours.contains("#4fc3f7"));
+    }
+}
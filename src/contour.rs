@@ -0,0 +1,240 @@
+//! Contour extraction — marching squares over scalar grids.
+//!
+//! Each 2×2 block of samples is classified by which corners lie above the
+//! contour level; the 16 cases map to zero, one, or two line segments whose
+//! endpoints are linearly interpolated along the cell edges. Segments that
+//! share an edge crossing are then chained into polylines, so a level set
+//! comes back as a handful of smooth curves rather than thousands of pieces.
+//! Ambiguous saddle cells are resolved with the cell-center average.
+//!
+//! [`filled_regions`] closes every contour against the grid's border, so the
+//! area above a level can be drawn as filled polygons.
+
+use crate::prelude::*;
+use crate::categories::fractals::Point;
+
+/// A grid edge crossed by a contour: (x, y, vertical) of its first sample.
+type EdgeKey = (usize, usize, bool);
+
+/// Extract the `level` contour of a row-major `cols × rows` grid.
+///
+/// Coordinates are in sample units: sample (i, j) sits at (i, j). Closed
+/// loops repeat their first point at the end.
+pub fn marching_squares(values: &[f64], cols: usize, rows: usize, level: f64) -> Vec<Vec<Point>> {
+    if cols < 2 || rows < 2 || values.len() < cols * rows {
+        return vec![];
+    }
+    let v = |i: usize, j: usize| values[j * cols + i];
+    let crossing = |key: EdgeKey| {
+        let (i, j, vertical) = key;
+        let (a, b) = if vertical { (v(i, j), v(i, j + 1)) } else { (v(i, j), v(i + 1, j)) };
+        let t = if (b - a).abs() > f64::EPSILON { ((level - a) / (b - a)).clamp(0.0, 1.0) } else { 0.5 };
+        if vertical {
+            Point { x: i as f64, y: j as f64 + t }
+        } else {
+            Point { x: i as f64 + t, y: j as f64 }
+        }
+    };
+
+    let mut segments: Vec<(EdgeKey, EdgeKey)> = Vec::new();
+    for j in 0..rows - 1 {
+        for i in 0..cols - 1 {
+            let (tl, tr, br, bl) = (v(i, j), v(i + 1, j), v(i + 1, j + 1), v(i, j + 1));
+            let case = ((tl > level) as u8) << 3 | ((tr > level) as u8) << 2 | ((br > level) as u8) << 1 | (bl > level) as u8;
+            let top = (i, j, false);
+            let right = (i + 1, j, true);
+            let bottom = (i, j + 1, false);
+            let left = (i, j, true);
+            let center_above = (tl + tr + br + bl) / 4.0 > level;
+            match case {
+                1 | 14 => segments.push((left, bottom)),
+                2 | 13 => segments.push((bottom, right)),
+                3 | 12 => segments.push((left, right)),
+                4 | 11 => segments.push((top, right)),
+                6 | 9 => segments.push((top, bottom)),
+                7 | 8 => segments.push((left, top)),
+                5 if center_above => segments.extend([(left, top), (bottom, right)]),
+                5 => segments.extend([(top, right), (left, bottom)]),
+                10 if center_above => segments.extend([(top, right), (left, bottom)]),
+                10 => segments.extend([(left, top), (bottom, right)]),
+                _ => {}
+            }
+        }
+    }
+    chain(&segments).into_iter().map(|keys| keys.into_iter().map(crossing).collect()).collect()
+}
+
+/// Join segments that share an edge crossing into polylines of edge keys.
+fn chain(segments: &[(EdgeKey, EdgeKey)]) -> Vec<Vec<EdgeKey>> {
+    let mut at: Map<EdgeKey, Vec<usize>> = Map::new();
+    for (s, &(a, b)) in segments.iter().enumerate() {
+        at.entry(a).or_default().push(s);
+        at.entry(b).or_default().push(s);
+    }
+    let mut used = vec![false; segments.len()];
+    let mut lines = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut line = vec![segments[start].0, segments[start].1];
+        // Grow forward from the tail, then backward from the head
+        for forward in [true, false] {
+            loop {
+                let end = if forward { line[line.len() - 1] } else { line[0] };
+                let next = at[&end].iter().copied().find(|&s| !used[s]);
+                let Some(s) = next else { break };
+                used[s] = true;
+                let (a, b) = segments[s];
+                let other = if a == end { b } else { a };
+                if forward {
+                    line.push(other);
+                } else {
+                    line.insert(0, other);
+                }
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Closed outlines of the regions above `level`, cut off at the grid's
+/// border: sample (i, j) is the center of a unit cell, so outlines span
+/// [−0.5, cols − 0.5] × [−0.5, rows − 0.5]. Holes come back as separate
+/// loops; fill with the even-odd rule.
+pub fn filled_regions(values: &[f64], cols: usize, rows: usize, level: f64) -> Vec<Vec<Point>> {
+    if cols == 0 || rows == 0 || values.len() < cols * rows {
+        return vec![];
+    }
+    // A ring of samples below the level closes every contour
+    let (pc, pr) = (cols + 2, rows + 2);
+    let below = values[..cols * rows].iter().cloned().fold(level, f64::min) - 1.0;
+    let padded: Vec<f64> = (0..pc * pr)
+        .map(|k| {
+            let (i, j) = (k % pc, k / pc);
+            if i == 0 || j == 0 || i > cols || j > rows { below } else { values[(j - 1) * cols + i - 1] }
+        })
+        .collect();
+    // Crossings into the ring snap to the border half a cell out
+    let snap = |v: f64, n: usize| if v < 0.0 { -0.5 } else if v > (n - 1) as f64 { n as f64 - 0.5 } else { v };
+    marching_squares(&padded, pc, pr, level)
+        .into_iter()
+        .map(|line| line.into_iter().map(|p| Point { x: snap(p.x - 1.0, cols), y: snap(p.y - 1.0, rows) }).collect())
+        .collect()
+}
+
+/// Contours at each of the given levels.
+pub fn contour_levels(values: &[f64], cols: usize, rows: usize, levels: &[f64]) -> Vec<(f64, Vec<Vec<Point>>)> {
+    levels.iter().map(|&l| (l, marching_squares(values, cols, rows, l))).collect()
+}
+
+/// `count` levels evenly spaced strictly between the grid's minimum and maximum.
+pub fn even_levels(values: &[f64], count: usize) -> Vec<f64> {
+    let lo = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let hi = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    (1..=count).map(|i| lo + (hi - lo) * i as f64 / (count + 1) as f64).collect()
+}
+
+/// SVG path data for polylines, scaling sample units by `scale`.
+pub fn path_data(lines: &[Vec<Point>], scale: f64) -> String {
+    let mut d = String::new();
+    for line in lines.iter().filter(|l| l.len() >= 2) {
+        for (k, p) in line.iter().enumerate() {
+            d.push_str(&format!("{}{:.1},{:.1}", if k == 0 { "M" } else { "L" }, p.x * scale, p.y * scale));
+        }
+    }
+    d
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radial(n: usize) -> Vec<f64> {
+        let c = (n - 1) as f64 / 2.0;
+        (0..n * n)
+            .map(|k| ((k % n) as f64 - c).hypot((k / n) as f64 - c))
+            .collect()
+    }
+
+    #[test]
+    fn test_circle_single_closed_loop() {
+        let grid = radial(41);
+        let lines = marching_squares(&grid, 41, 41, 12.0);
+        assert_eq!(lines.len(), 1);
+        let loop_ = &lines[0];
+        assert_eq!(loop_.first(), loop_.last(), "Loop should close");
+        for p in loop_ {
+            let r = (p.x - 20.0).hypot(p.y - 20.0);
+            assert!((r - 12.0).abs() < 0.1, "Radius {}", r);
+        }
+    }
+
+    #[test]
+    fn test_level_outside_range_is_empty() {
+        let grid = radial(11);
+        assert!(marching_squares(&grid, 11, 11, 100.0).is_empty());
+        assert!(marching_squares(&grid, 11, 11, -1.0).is_empty());
+    }
+
+    #[test]
+    fn test_open_line_across_ramp() {
+        // f = x crosses 2.5 along a vertical line from top to bottom
+        let grid: Vec<f64> = (0..6 * 5).map(|k| (k % 6) as f64).collect();
+        let lines = marching_squares(&grid, 6, 5, 2.5);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].len(), 5);
+        assert!(lines[0].iter().all(|p| (p.x - 2.5).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_two_blobs_two_loops() {
+        let n = 40;
+        let grid: Vec<f64> = (0..n * n)
+            .map(|k| {
+                let (x, y) = ((k % n) as f64, (k / n) as f64);
+                let d1 = (x - 10.0).hypot(y - 20.0);
+                let d2 = (x - 30.0).hypot(y - 20.0);
+                (-d1 * d1 / 20.0).exp() + (-d2 * d2 / 20.0).exp()
+            })
+            .collect();
+        assert_eq!(marching_squares(&grid, n, n, 0.5).len(), 2);
+    }
+
+    #[test]
+    fn test_saddle_resolved() {
+        // Checkerboard 2×2 with high center → above-corners connected
+        let grid = [1.0, 0.0, 0.0, 1.0];
+        let lines = marching_squares(&grid, 2, 2, 0.4);
+        assert_eq!(lines.len(), 2);
+    }
+
+    #[test]
+    fn test_levels_and_path() {
+        let grid = radial(21);
+        let levels = even_levels(&grid, 3);
+        assert_eq!(levels.len(), 3);
+        let contours = contour_levels(&grid, 21, 21, &levels);
+        assert!(contours.iter().all(|(_, lines)| !lines.is_empty()));
+        let d = path_data(&contours[0].1, 2.0);
+        assert!(d.starts_with('M') && d.contains('L'));
+    }
+
+    #[test]
+    fn test_filled_regions_close_at_border() {
+        // Everything left of x = 2.5 is above the level: one loop, cut at the border
+        let grid: Vec<f64> = (0..6 * 4).map(|k| 5.0 - (k % 6) as f64).collect();
+        let regions = filled_regions(&grid, 6, 4, 2.5);
+        assert_eq!(regions.len(), 1);
+        let region = &regions[0];
+        assert_eq!(region.first(), region.last());
+        assert!(region.iter().all(|p| p.x >= -0.5 && p.x <= 2.5 && p.y >= -0.5 && p.y <= 3.5));
+        assert!(region.iter().any(|p| p.x == -0.5) && region.iter().any(|p| (p.x - 2.5).abs() < 1e-12));
+        // An annulus: outer boundary and hole
+        let ring: Vec<f64> = radial(31).iter().map(|r| (8.0..12.0).contains(r) as u8 as f64).collect();
+        assert_eq!(filled_regions(&ring, 31, 31, 0.5).len(), 2);
+        assert!(filled_regions(&[], 0, 0, 0.5).is_empty());
+    }
+}
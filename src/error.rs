@@ -0,0 +1,135 @@
+//! Errors — invalid parameters, oversized requests, malformed definitions,
+//! cancellation, and I/O failures.
+//!
+//! Models are only meaningful (or numerically stable) over a range of
+//! parameters, and a few grow exponentially with their iteration count.
+//! Parameter structs expose `validate()` so servers and GUIs can reject bad
+//! input up front instead of hanging or producing a blank image.
+
+use core::ops::RangeInclusive;
+
+use crate::prelude::*;
+
+
+/// Everything that can go wrong in the library or the CLI.
+///
+/// Messages are written for the person who typed the parameter, so the CLI
+/// prints them as they are.
+#[derive(Debug, thiserror::Error)]
+pub enum MathaturaError {
+    /// A parameter lies outside the range where the model is defined or stable
+    #[error("invalid {name} = {value}: expected {expected}")]
+    InvalidParameter { name: &'static str, value: f64, expected: &'static str },
+    /// An option the chosen figure has no use for, such as a data export
+    /// of a figure that is only a picture
+    #[error("invalid {name} = {value} for this figure: expected {expected}")]
+    Unsupported { name: &'static str, value: String, expected: &'static str },
+    /// A count or size that must be positive was zero
+    #[error("{name} must be greater than zero")]
+    Empty { name: &'static str },
+    /// A request would take unreasonable time or memory
+    #[error("{name} = {requested} exceeds the limit of {limit}")]
+    TooLarge { name: &'static str, requested: usize, limit: usize },
+    /// A text definition (such as an L-system file) is malformed at `line`,
+    /// counting from 1, or 0 where no single line is to blame
+    #[error("{}{message}", if *line > 0 { format!("line {line}: ") } else { String::new() })]
+    Parse { line: usize, message: String },
+    /// A progress hook asked to stop
+    #[error("cancelled")]
+    Cancelled,
+    /// Reading or writing a file failed
+    #[cfg(feature = "std")]
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The crate's error type under its conventional name.
+pub type Error = MathaturaError;
+
+/// Result alias used throughout the crate.
+pub type Result<T> = core::result::Result<T, MathaturaError>;
+
+/// Require a finite value inside `range`.
+pub fn check_range(name: &'static str, value: f64, range: RangeInclusive<f64>, expected: &'static str) -> Result<()> {
+    if value.is_finite() && range.contains(&value) {
+        Ok(())
+    } else {
+        Err(MathaturaError::InvalidParameter { name, value, expected })
+    }
+}
+
+/// Require a finite, strictly positive value.
+pub fn check_positive(name: &'static str, value: f64) -> Result<()> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(MathaturaError::InvalidParameter { name, value, expected: "a positive number" })
+    }
+}
+
+/// Require a count between 1 and `limit`.
+pub fn check_count(name: &'static str, count: usize, limit: usize) -> Result<()> {
+    if count == 0 {
+        Err(MathaturaError::Empty { name })
+    } else if count > limit {
+        Err(MathaturaError::TooLarge { name, requested: count, limit })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_range() {
+        assert!(check_range("feed", 0.05, 0.0..=0.1, "0 to 0.1").is_ok());
+        assert!(check_range("feed", 0.2, 0.0..=0.1, "0 to 0.1").is_err());
+        assert!(check_range("feed", f64::NAN, 0.0..=0.1, "0 to 0.1").is_err());
+    }
+
+    #[test]
+    fn test_check_positive() {
+        assert!(check_positive("scale", 1.0).is_ok());
+        assert!(check_positive("scale", 0.0).is_err());
+        assert!(check_positive("scale", -3.0).is_err());
+        assert!(check_positive("scale", f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_check_count() {
+        assert!(check_count("count", 10, 100).is_ok());
+        assert!(matches!(check_count("count", 0, 100), Err(MathaturaError::Empty { name: "count" })));
+        assert!(matches!(check_count("count", 101, 100), Err(MathaturaError::TooLarge { limit: 100, .. })));
+    }
+
+    #[test]
+    fn test_display_messages() {
+        let e = MathaturaError::InvalidParameter { name: "scale", value: -1.0, expected: "a positive number" };
+        assert_eq!(e.to_string(), "invalid scale = -1: expected a positive number");
+        let e = MathaturaError::Unsupported { name: "format", value: "csv".to_string(), expected: "svg" };
+        assert_eq!(e.to_string(), "invalid format = csv for this figure: expected svg");
+        let e = MathaturaError::TooLarge { name: "iterations", requested: 12, limit: 8 };
+        assert_eq!(e.to_string(), "iterations = 12 exceeds the limit of 8");
+        assert_eq!(MathaturaError::Cancelled.to_string(), "cancelled");
+        let e = MathaturaError::Parse { line: 3, message: "missing '->'".to_string() };
+        assert_eq!(e.to_string(), "line 3: missing '->'");
+        let e = MathaturaError::Parse { line: 0, message: "no transforms".to_string() };
+        assert_eq!(e.to_string(), "no transforms");
+    }
+
+    #[test]
+    fn test_error_is_thread_safe() {
+        fn assert_send_sync<T: Send + Sync + core::error::Error + 'static>() {}
+        assert_send_sync::<Error>();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_io_source() {
+        let e: MathaturaError = std::io::Error::new(std::io::ErrorKind::NotFound, "missing").into();
+        assert!(std::error::Error::source(&e).is_some());
+        assert!(e.to_string().contains("missing"));
+    }
+}
@@ -0,0 +1,270 @@
+//! Export — raw generated data as JSON or CSV, for post-processing elsewhere.
+//!
+//! Every exportable type is a [`Table`]: a fixed list of numeric columns and
+//! one row per item. CSV output has a header row with the column names; JSON
+//! output is an array of objects keyed by column name, which
+//! `pandas.read_json` and R's `jsonlite::fromJSON` load as a data frame.
+//! Non-finite values become empty CSV fields and JSON `null`.
+//!
+//! ## Schema
+//!
+//! | Data | Columns |
+//! |------|---------|
+//! | `[fractals::Point]` | `x, y` |
+//! | `[phyllotaxis::Element]` | `index, angle, radius, x, y` (angle in radians) |
+//! | `[spirals::SpiralPoint]` | `x, y, theta, r` |
+//! | `[chaos::Point3D]` | `x, y, z`, one row per integration step |
+//! | `[lsystems::Segment]` | `x1, y1, x2, y2, depth` |
+//! | `turing::Grid` | `x, y, a, b`, row-major |
+//! | `Geometry::Points` | `x, y` |
+//! | `Geometry::Polylines` / `Polygons` | `shape, x, y`, `shape` numbering each curve from 0 |
+//! | `Geometry::Circles` | `x, y, radius` |
+//! | `Geometry::Grid` | `x, y, value`, row-major |
+//! | `Geometry::Layers` | `layer, shape, x, y`, one row per vertex, point or circle center |
+
+use core::fmt::Write;
+
+use crate::prelude::*;
+use crate::categories::chaos::Point3D;
+use crate::categories::fractals::Point;
+use crate::categories::lsystems::Segment;
+use crate::categories::phyllotaxis::Element;
+use crate::categories::spirals::SpiralPoint;
+use crate::categories::turing;
+use crate::geometry::{Geometry, Shape};
+
+/// Data with named numeric columns and one row per item.
+pub trait Table {
+    /// Column names, in row order.
+    fn columns(&self) -> &'static [&'static str];
+
+    /// Call `f` with each row's values.
+    fn for_each_row(&self, f: &mut dyn FnMut(&[f64]));
+}
+
+/// One item of a homogeneous list, such as a point or a segment.
+pub trait Row {
+    const COLUMNS: &'static [&'static str];
+
+    fn values(&self) -> Vec<f64>;
+}
+
+impl<R: Row> Table for [R] {
+    fn columns(&self) -> &'static [&'static str] {
+        R::COLUMNS
+    }
+
+    fn for_each_row(&self, f: &mut dyn FnMut(&[f64])) {
+        for item in self {
+            f(&item.values());
+        }
+    }
+}
+
+impl<R: Row> Table for Vec<R> {
+    fn columns(&self) -> &'static [&'static str] {
+        R::COLUMNS
+    }
+
+    fn for_each_row(&self, f: &mut dyn FnMut(&[f64])) {
+        self.as_slice().for_each_row(f)
+    }
+}
+
+impl Row for Point {
+    const COLUMNS: &'static [&'static str] = &["x", "y"];
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.x, self.y]
+    }
+}
+
+impl Row for Element {
+    const COLUMNS: &'static [&'static str] = &["index", "angle", "radius", "x", "y"];
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.index as f64, self.angle, self.radius, self.x, self.y]
+    }
+}
+
+impl Row for SpiralPoint {
+    const COLUMNS: &'static [&'static str] = &["x", "y", "theta", "r"];
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.x, self.y, self.theta, self.r]
+    }
+}
+
+impl Row for Point3D {
+    const COLUMNS: &'static [&'static str] = &["x", "y", "z"];
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.x, self.y, self.z]
+    }
+}
+
+impl Row for Segment {
+    const COLUMNS: &'static [&'static str] = &["x1", "y1", "x2", "y2", "depth"];
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.x1, self.y1, self.x2, self.y2, self.depth as f64]
+    }
+}
+
+impl Table for turing::Grid {
+    fn columns(&self) -> &'static [&'static str] {
+        &["x", "y", "a", "b"]
+    }
+
+    fn for_each_row(&self, f: &mut dyn FnMut(&[f64])) {
+        for (k, cell) in self.cells.iter().enumerate() {
+            f(&[(k % self.width) as f64, (k / self.width) as f64, cell.a, cell.b]);
+        }
+    }
+}
+
+impl Table for Geometry {
+    fn columns(&self) -> &'static [&'static str] {
+        match self {
+            Geometry::Points(_) => &["x", "y"],
+            Geometry::Polylines(_) | Geometry::Polygons(_) => &["shape", "x", "y"],
+            Geometry::Circles(_) => &["x", "y", "radius"],
+            Geometry::Grid { .. } => &["x", "y", "value"],
+            Geometry::Layers(_) => &["layer", "shape", "x", "y"],
+        }
+    }
+
+    fn for_each_row(&self, f: &mut dyn FnMut(&[f64])) {
+        match self {
+            Geometry::Points(points) => points.for_each_row(f),
+            Geometry::Polylines(lines) | Geometry::Polygons(lines) => {
+                for (i, line) in lines.iter().enumerate() {
+                    for p in line {
+                        f(&[i as f64, p.x, p.y]);
+                    }
+                }
+            }
+            Geometry::Grid { cols, values, .. } => {
+                let cols = (*cols).max(1);
+                for (k, &v) in values.iter().enumerate() {
+                    f(&[(k % cols) as f64, (k / cols) as f64, v]);
+                }
+            }
+            Geometry::Circles(circles) => {
+                for c in circles {
+                    f(&[c.center.x, c.center.y, c.radius]);
+                }
+            }
+            Geometry::Layers(layers) => {
+                for (i, layer) in layers.iter().enumerate() {
+                    let mut shape = 0;
+                    layer.geometry.for_each_shape(&mut |s| {
+                        let points = match s {
+                            Shape::Point(p) => vec![p],
+                            Shape::Circle(c) => vec![c.center],
+                            Shape::Polyline(line) | Shape::Polygon(line) => line.to_vec(),
+                        };
+                        for p in points {
+                            f(&[i as f64, shape as f64, p.x, p.y]);
+                        }
+                        shape += 1;
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Shortest round-tripping decimal, or `None` for NaN and infinities.
+fn number(v: f64) -> Option<String> {
+    v.is_finite().then(|| format!("{v}"))
+}
+
+/// CSV with a header row.
+pub fn to_csv<T: Table + ?Sized>(table: &T) -> String {
+    let mut out = table.columns().join(",");
+    out.push('\n');
+    table.for_each_row(&mut |row| {
+        let fields: Vec<String> = row.iter().map(|&v| number(v).unwrap_or_default()).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    });
+    out
+}
+
+/// JSON array with one object per row, keyed by column name.
+pub fn to_json<T: Table + ?Sized>(table: &T) -> String {
+    let columns = table.columns();
+    let mut out = String::from("[");
+    let mut first = true;
+    table.for_each_row(&mut |row| {
+        out.push_str(if first { "\n  {" } else { ",\n  {" });
+        first = false;
+        for (i, (name, &v)) in columns.iter().zip(row).enumerate() {
+            let sep = if i == 0 { "" } else { ", " };
+            let _ = write!(out, "{sep}\"{name}\": {}", number(v).as_deref().unwrap_or("null"));
+        }
+        out.push('}');
+    });
+    out.push_str(if first { "]\n" } else { "\n]\n" });
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::{chaos, lsystems, phyllotaxis};
+    use crate::geometry::{Circle, Layer, Style};
+
+    #[test]
+    fn test_points_csv() {
+        let points = vec![Point { x: 0.5, y: -1.0 }, Point { x: 2.0, y: 3.25 }];
+        assert_eq!(to_csv(&points), "x,y\n0.5,-1\n2,3.25\n");
+        assert_eq!(to_csv(&points[..1]), "x,y\n0.5,-1\n");
+    }
+
+    #[test]
+    fn test_json_objects() {
+        let segments = vec![lsystems::Segment { x1: 0.0, y1: 0.0, x2: 1.0, y2: 0.5, depth: 2 }];
+        assert_eq!(to_json(&segments), "[\n  {\"x1\": 0, \"y1\": 0, \"x2\": 1, \"y2\": 0.5, \"depth\": 2}\n]\n");
+        assert_eq!(to_json(&Vec::<Point>::new()), "[]\n");
+        let bad = [Point { x: f64::NAN, y: 1.0 }];
+        assert!(to_json(&bad[..]).contains("\"x\": null"));
+        assert_eq!(to_csv(&bad[..]), "x,y\n,1\n");
+    }
+
+    #[test]
+    fn test_values_round_trip() {
+        let elements = phyllotaxis::vogel_spiral(&phyllotaxis::Params::default());
+        let csv = to_csv(&elements);
+        assert_eq!(csv.lines().count(), elements.len() + 1);
+        let last: Vec<f64> = csv.lines().last().unwrap().split(',').map(|v| v.parse().unwrap()).collect();
+        let e = elements.last().unwrap();
+        assert_eq!(last, vec![e.index as f64, e.angle, e.radius, e.x, e.y]);
+    }
+
+    #[test]
+    fn test_turing_grid_rows() {
+        let grid = turing::Grid::new(3, 2);
+        let csv = to_csv(&grid);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "x,y,a,b");
+        assert_eq!(lines.len(), 7);
+        assert!(lines[6].starts_with("2,1,"));
+    }
+
+    #[test]
+    fn test_geometry_tables() {
+        let orbit = chaos::lorenz_attractor(&chaos::LorenzParams::default(), 10, chaos::Point3D { x: 1.0, y: 1.0, z: 1.0 });
+        assert_eq!(to_csv(&orbit).lines().count(), 11);
+        let lines = Geometry::Polylines(vec![vec![Point { x: 0.0, y: 0.0 }], vec![Point { x: 1.0, y: 1.0 }]]);
+        assert_eq!(to_csv(&lines), "shape,x,y\n0,0,0\n1,1,1\n");
+        let grid = Geometry::Grid { cols: 2, rows: 1, values: vec![0.25, 0.75] };
+        assert_eq!(to_csv(&grid), "x,y,value\n0,0,0.25\n1,0,0.75\n");
+        let layers = Geometry::Layers(vec![
+            Layer { style: Style::default(), geometry: lines },
+            Layer { style: Style::fill("red"), geometry: Geometry::Circles(vec![Circle { center: Point { x: 2.0, y: 3.0 }, radius: 0.5 }]) },
+        ]);
+        assert_eq!(to_csv(&layers), "layer,shape,x,y\n0,0,0,0\n0,1,1,1\n1,0,2,3\n");
+    }
+}
@@ -0,0 +1,171 @@
+//! Fixed — arbitrary-precision fixed-point numbers.
+//!
+//! `f64` runs out of digits around a 1e-14 pixel spacing, which is where
+//! Mandelbrot zooms stop. [`Fixed`] keeps a big-integer mantissa over a
+//! binary point `bits` places in, so a view center can carry hundreds of
+//! digits. It only needs to be fast enough for one reference orbit per
+//! frame; everything per pixel stays in `f64` (see
+//! [`fractals::mandelbrot_deep_grid`](crate::categories::fractals::mandelbrot_deep_grid)).
+
+use core::ops::{Add, Mul, Sub};
+
+use num_bigint::BigInt;
+use num_traits::{Pow, ToPrimitive, Zero};
+
+use crate::prelude::*;
+use crate::error::{MathaturaError, Result};
+
+/// Largest decimal exponent [`Fixed::parse`] accepts.
+const MAX_EXPONENT: i64 = 10_000;
+
+/// A signed number `mantissa / 2^bits`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixed {
+    mantissa: BigInt,
+    bits: u32,
+}
+
+impl Fixed {
+    /// Zero with `bits` fractional bits.
+    pub fn zero(bits: u32) -> Fixed {
+        Fixed { mantissa: BigInt::zero(), bits }
+    }
+
+    /// The exact value of `x` (non-finite values become 0), truncated to
+    /// `bits` fractional bits.
+    pub fn from_f64(x: f64, bits: u32) -> Fixed {
+        if !x.is_finite() {
+            return Fixed::zero(bits);
+        }
+        let (mantissa, exponent, sign) = num_traits::Float::integer_decode(x);
+        let mantissa = BigInt::from(mantissa) * i64::from(sign);
+        let shift = i64::from(exponent) + i64::from(bits);
+        let mantissa = if shift >= 0 { mantissa << shift as u64 } else { mantissa >> shift.unsigned_abs() };
+        Fixed { mantissa, bits }
+    }
+
+    /// Parse a decimal such as `-0.743643887037158704752191506114774` or
+    /// `2.5e-3`, rounded to `bits` fractional bits. Unlike `f64` parsing,
+    /// every digit counts.
+    pub fn parse(text: &str, bits: u32) -> Result<Fixed> {
+        let invalid = || MathaturaError::Parse { line: 0, message: format!("not a decimal number: {text:?}") };
+        let text = text.trim();
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (number, exponent) = match text.split_once(['e', 'E']) {
+            Some((number, exponent)) => (number, exponent.parse::<i64>().map_err(|_| invalid())?),
+            None => (text, 0),
+        };
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let digits = format!("{whole}{fraction}");
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) || exponent.abs() > MAX_EXPONENT {
+            return Err(invalid());
+        }
+        let value = BigInt::parse_bytes(digits.as_bytes(), 10).ok_or_else(invalid)?;
+        // value · 10^scale · 2^bits, rounded to the nearest integer
+        let scale = exponent - fraction.len() as i64;
+        let ten = BigInt::from(10);
+        let mut mantissa = if scale >= 0 {
+            (value * Pow::pow(&ten, scale as u64)) << bits
+        } else {
+            let divisor: BigInt = Pow::pow(&ten, scale.unsigned_abs());
+            ((value << bits) + (&divisor >> 1u32)) / divisor
+        };
+        if negative {
+            mantissa = -mantissa;
+        }
+        Ok(Fixed { mantissa, bits })
+    }
+
+    /// Number of fractional bits.
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// The same number with `bits` fractional bits, truncating if fewer.
+    pub fn with_bits(&self, bits: u32) -> Fixed {
+        let mantissa = if bits >= self.bits {
+            &self.mantissa << (bits - self.bits)
+        } else {
+            &self.mantissa >> (self.bits - bits)
+        };
+        Fixed { mantissa, bits }
+    }
+
+    /// The nearest `f64`, give or take the last bit.
+    pub fn to_f64(&self) -> f64 {
+        // Keep the top 64 bits so the integer conversion can't overflow
+        let shift = self.mantissa.bits().saturating_sub(64);
+        let top = (&self.mantissa >> shift).to_f64().unwrap_or(0.0);
+        top * 2f64.powi((shift as i64 - i64::from(self.bits)).clamp(-2000, 2000) as i32)
+    }
+}
+
+impl Add for &Fixed {
+    type Output = Fixed;
+
+    fn add(self, other: &Fixed) -> Fixed {
+        debug_assert_eq!(self.bits, other.bits);
+        Fixed { mantissa: &self.mantissa + &other.mantissa, bits: self.bits }
+    }
+}
+
+impl Sub for &Fixed {
+    type Output = Fixed;
+
+    fn sub(self, other: &Fixed) -> Fixed {
+        debug_assert_eq!(self.bits, other.bits);
+        Fixed { mantissa: &self.mantissa - &other.mantissa, bits: self.bits }
+    }
+}
+
+impl Mul for &Fixed {
+    type Output = Fixed;
+
+    fn mul(self, other: &Fixed) -> Fixed {
+        debug_assert_eq!(self.bits, other.bits);
+        Fixed { mantissa: (&self.mantissa * &other.mantissa) >> self.bits, bits: self.bits }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_convert() {
+        assert_eq!(Fixed::parse("1.5", 8).unwrap(), Fixed::from_f64(1.5, 8));
+        assert_eq!(Fixed::parse("-2.5e-1", 16).unwrap().to_f64(), -0.25);
+        assert_eq!(Fixed::parse("+3E2", 4).unwrap().to_f64(), 300.0);
+        assert_eq!(Fixed::parse(".5", 4).unwrap().to_f64(), 0.5);
+        for bad in ["", "-", "1.2.3", "abc", "1e", "1e99999", "0x10"] {
+            assert!(Fixed::parse(bad, 32).is_err(), "{bad:?}");
+        }
+        for &x in &[0.0, 1.0, -0.743643887037158, 1e-30, 123456.789] {
+            assert_eq!(Fixed::from_f64(x, 200).to_f64(), x);
+            assert_eq!(Fixed::parse(&format!("{x:e}"), 200).unwrap().to_f64(), x);
+        }
+    }
+
+    #[test]
+    fn test_digits_beyond_f64() {
+        // Two centers 1e-30 apart are the same f64 but differ as Fixed
+        let a = Fixed::parse("-0.7436438870371587047521915061147", 128).unwrap();
+        let b = Fixed::parse("-0.7436438870371587047521915061157", 128).unwrap();
+        assert_eq!(a.to_f64(), b.to_f64());
+        assert!(((&b - &a).to_f64() + 1e-30).abs() < 1e-37);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (Fixed::from_f64(1.25, 64), Fixed::from_f64(-0.5, 64));
+        assert_eq!((&a + &b).to_f64(), 0.75);
+        assert_eq!((&a - &b).to_f64(), 1.75);
+        assert_eq!((&a * &b).to_f64(), -0.625);
+        assert_eq!((&b * &b).to_f64(), 0.25);
+        assert_eq!(a.with_bits(200).with_bits(64), a);
+        assert_eq!(a.with_bits(200).bits(), 200);
+    }
+}
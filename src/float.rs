@@ -0,0 +1,68 @@
+//! Float — precision-generic generators.
+//!
+//! The point-cloud generators have `_as::<F>` variants generic over
+//! num-traits' [`Float`], yielding plain `[F; 2]` / `[F; 3]` arrays. With
+//! `F = f32` a million-point fern takes half the memory and is ready to
+//! upload as a GPU vertex buffer; with `F = f64` the results match the
+//! regular generators exactly. Analysis functions stay in `f64`.
+
+pub use num_traits::Float;
+
+/// Convert an `f64` constant to `F`.
+pub fn cast<F: Float>(x: f64) -> F {
+    F::from(x).expect("f64 constant representable in F")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::categories::{chaos, fractals, phyllotaxis};
+
+    #[test]
+    fn test_cast() {
+        assert_eq!(cast::<f32>(0.5), 0.5f32);
+        assert_eq!(cast::<f64>(0.1), 0.1);
+    }
+
+    #[test]
+    fn test_f64_variants_match_exactly() {
+        let fern: Vec<[f64; 2]> = fractals::barnsley_fern_iter_as(4).take(2000).collect();
+        let reference = fractals::barnsley_fern(2000, 4);
+        assert!(fern.iter().zip(&reference).all(|(a, p)| a[0] == p.x && a[1] == p.y));
+
+        let params = phyllotaxis::Params::default();
+        let vogel = phyllotaxis::vogel_spiral_as::<f64>(&params);
+        // Testing a no_std build links std, whose f64 trig then differs from libm's by an ulp
+        let same = |a: f64, b: f64| if cfg!(feature = "std") { a == b } else { (a - b).abs() <= 1e-15 * b.abs().max(1.0) };
+        assert!(vogel.iter().zip(phyllotaxis::vogel_spiral(&params)).all(|(a, e)| same(a[0], e.x) && same(a[1], e.y)));
+
+        let lorenz = chaos::LorenzParams::default();
+        let start = chaos::Point3D { x: 1.0, y: 1.0, z: 1.0 };
+        let orbit: Vec<[f64; 3]> = chaos::lorenz_iter_as(&lorenz, start).take(300).collect();
+        assert!(orbit.iter().zip(chaos::lorenz_attractor(&lorenz, 300, start)).all(|(a, p)| *a == [p.x, p.y, p.z]));
+    }
+
+    #[test]
+    fn test_f32_close_to_f64() {
+        // Chaos amplifies rounding, so compare the early, still-correlated steps
+        let lorenz = chaos::LorenzParams::default();
+        let start = chaos::Point3D { x: 1.0, y: 1.0, z: 1.0 };
+        let single: Vec<[f32; 3]> = chaos::lorenz_iter_as(&lorenz, start).take(200).collect();
+        let double = chaos::lorenz_attractor(&lorenz, 200, start);
+        for (a, p) in single.iter().zip(&double) {
+            assert!((a[0] as f64 - p.x).abs() < 1e-2 && (a[2] as f64 - p.z).abs() < 1e-2);
+        }
+        let vogel = phyllotaxis::vogel_spiral_as::<f32>(&phyllotaxis::Params::default());
+        let reference = phyllotaxis::vogel_spiral(&phyllotaxis::Params::default());
+        // Angles reach hundreds of radians, so single-precision error grows with n
+        assert!(vogel.iter().zip(&reference).all(|(a, e)| (a[0] as f64 - e.x).abs() < 1e-3 * (1.0 + e.radius)));
+    }
+
+    #[test]
+    fn test_f32_fern_stays_in_bounds() {
+        let fern: Vec<[f32; 2]> = fractals::barnsley_fern_iter_as(1).take(100_000).collect();
+        assert_eq!(core::mem::size_of_val(fern.as_slice()), 100_000 * 8);
+        assert!(fern.iter().all(|p| p[0].abs() < 3.0 && p[1] >= 0.0 && p[1] < 10.1));
+    }
+}
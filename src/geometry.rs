@@ -0,0 +1,488 @@
+//! Geometry — a shared intermediate representation between models and renderers.
+//!
+//! Every category boils down to a handful of shapes: scattered points (seeds,
+//! boids, chaos-game orbits), polylines (spirals, trajectories, branches),
+//! closed polygons (snowflake outlines, superformula shapes), circles (florets,
+//! dew drops), or a scalar field on a grid (reaction–diffusion, sandpiles,
+//! terrain). Shapes that need their own look are wrapped in styled
+//! [`Layer`]s. A [`Generator`] produces one of these, and any [`Backend`]
+//! that understands [`Geometry`] can draw every category without knowing the
+//! math behind it; the blanket [`Pattern`] trait joins the two, so
+//! `pattern.render(&Svg::default())` works for every model.
+//!
+//! Coordinates follow SVG conventions: x to the right, y down. Models that
+//! are naturally y-up (the fern, the Lorenz butterfly) flip y when generating.
+
+use crate::prelude::*;
+use crate::categories::fractals::Point;
+use crate::error::Result;
+use crate::float::{cast, Float};
+use crate::render::theme::{self, Theme};
+
+/// Shapes produced by a generator.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Geometry {
+    /// Unconnected points
+    Points(Vec<Point>),
+    /// Open curves
+    Polylines(Vec<Vec<Point>>),
+    /// Closed outlines (the last point joins the first)
+    Polygons(Vec<Vec<Point>>),
+    /// Filled discs
+    Circles(Vec<Circle>),
+    /// A row-major scalar field of `cols × rows` samples
+    Grid { cols: usize, rows: usize, values: Vec<f64> },
+    /// Styled groups of vector shapes, drawn in order; grids inside layers
+    /// are skipped
+    Layers(Vec<Layer>),
+}
+
+/// A disc.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Circle {
+    pub center: Point,
+    pub radius: f64,
+}
+
+/// How a layer is painted. Colors are any SVG color string; `None` leaves
+/// the backend's default for the shape kind.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Style {
+    pub stroke: Option<String>,
+    pub fill: Option<String>,
+    /// Stroke width in output units
+    pub width: f64,
+    pub opacity: f64,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style { stroke: None, fill: None, width: 1.0, opacity: 1.0 }
+    }
+}
+
+impl Style {
+    /// Lines in `color`, `width` wide.
+    pub fn stroke(color: impl Into<String>, width: f64) -> Self {
+        Style { stroke: Some(color.into()), width, ..Default::default() }
+    }
+
+    /// Shapes filled with `color`, unstroked.
+    pub fn fill(color: impl Into<String>) -> Self {
+        Style { fill: Some(color.into()), ..Default::default() }
+    }
+
+    pub fn with_opacity(self, opacity: f64) -> Self {
+        Style { opacity, ..self }
+    }
+}
+
+/// Shapes sharing one style.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layer {
+    pub style: Style,
+    pub geometry: Geometry,
+}
+
+/// One vector shape, as visited by [`Geometry::for_each_shape`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape<'a> {
+    Point(Point),
+    Polyline(&'a [Point]),
+    Polygon(&'a [Point]),
+    Circle(Circle),
+}
+
+/// A model that can be turned into geometry: parameters in, shapes out.
+pub trait Generator {
+    fn generate(&self) -> Geometry;
+
+    /// Like [`generate`](Generator::generate), but reports invalid parameters
+    /// instead of drawing nothing. Models with a `validate` method override it.
+    fn try_generate(&self) -> Result<Geometry> {
+        Ok(self.generate())
+    }
+}
+
+/// Something that draws geometry: an SVG writer, a rasterizer, a terminal.
+pub trait Backend {
+    type Output;
+
+    fn render(&self, geometry: &Geometry) -> Self::Output;
+}
+
+/// A generator together with rendering through any backend. Implemented for
+/// every [`Generator`], so a new backend works with all categories at once.
+pub trait Pattern: Generator {
+    fn render<B: Backend>(&self, backend: &B) -> B::Output {
+        backend.render(&self.generate())
+    }
+}
+
+impl<T: Generator + ?Sized> Pattern for T {}
+
+impl Geometry {
+    /// Call `f` with every vector shape, descending into layers. Grids have
+    /// no vector shapes.
+    pub fn for_each_shape<'a>(&'a self, f: &mut dyn FnMut(Shape<'a>)) {
+        match self {
+            Geometry::Points(points) => points.iter().for_each(|&p| f(Shape::Point(p))),
+            Geometry::Polylines(lines) => lines.iter().for_each(|l| f(Shape::Polyline(l))),
+            Geometry::Polygons(lines) => lines.iter().for_each(|l| f(Shape::Polygon(l))),
+            Geometry::Circles(circles) => circles.iter().for_each(|&c| f(Shape::Circle(c))),
+            Geometry::Grid { .. } => {}
+            Geometry::Layers(layers) => layers.iter().for_each(|layer| layer.geometry.for_each_shape(f)),
+        }
+    }
+
+    /// Number of points, circles, or grid samples.
+    pub fn len(&self) -> usize {
+        if let Geometry::Grid { values, .. } = self {
+            return values.len();
+        }
+        let mut n = 0;
+        self.for_each_shape(&mut |shape| {
+            n += match shape {
+                Shape::Polyline(line) | Shape::Polygon(line) => line.len(),
+                Shape::Point(_) | Shape::Circle(_) => 1,
+            }
+        });
+        n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bounding box as (min, max) corners, circles included whole. A grid
+    /// spans one unit per sample.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        if let Geometry::Grid { cols, rows, .. } = self {
+            return (*cols > 0 && *rows > 0).then_some((Point { x: 0.0, y: 0.0 }, Point { x: *cols as f64, y: *rows as f64 }));
+        }
+        let mut acc: Option<(Point, Point)> = None;
+        let mut include = |lo: Point, hi: Point| {
+            let (a, b) = acc.unwrap_or((lo, hi));
+            acc = Some((Point { x: a.x.min(lo.x), y: a.y.min(lo.y) }, Point { x: b.x.max(hi.x), y: b.y.max(hi.y) }));
+        };
+        self.for_each_shape(&mut |shape| match shape {
+            Shape::Point(p) => include(p, p),
+            Shape::Polyline(line) | Shape::Polygon(line) => line.iter().for_each(|&p| include(p, p)),
+            Shape::Circle(c) => {
+                let r = c.radius.abs();
+                include(Point { x: c.center.x - r, y: c.center.y - r }, Point { x: c.center.x + r, y: c.center.y + r })
+            }
+        });
+        acc
+    }
+
+    /// Every vertex, point and circle center, in order.
+    pub fn vertices(&self) -> Vec<Point> {
+        let mut out = Vec::new();
+        self.for_each_shape(&mut |shape| match shape {
+            Shape::Point(p) => out.push(p),
+            Shape::Polyline(line) | Shape::Polygon(line) => out.extend_from_slice(line),
+            Shape::Circle(c) => out.push(c.center),
+        });
+        out
+    }
+
+    /// Flatten into a vertex buffer at precision `F`: interleaved x, y for
+    /// points and shapes (line breaks are dropped), x, y, radius for circles,
+    /// the samples for a grid.
+    pub fn to_buffer<F: Float>(&self) -> Vec<F> {
+        match self {
+            Geometry::Grid { values, .. } => values.iter().map(|&v| cast(v)).collect(),
+            Geometry::Circles(circles) => circles.iter().flat_map(|c| [cast(c.center.x), cast(c.center.y), cast(c.radius)]).collect(),
+            _ => self.vertices().iter().flat_map(|p| [cast(p.x), cast(p.y)]).collect(),
+        }
+    }
+}
+
+/// SVG documents on a square canvas, shapes scaled to fit with a margin.
+///
+/// Points become round dots, polylines thin strokes, polygons translucent
+/// fills, circles solid discs, and grids a viridis raster normalized to the
+/// field's range. Layers override these defaults with their own style.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Svg {
+    pub size: f64,
+    pub margin: f64,
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Svg { size: 800.0, margin: 40.0 }
+    }
+}
+
+/// The look of unstyled shapes of each kind.
+pub(crate) fn default_style(geometry: &Geometry) -> Style {
+    match geometry {
+        Geometry::Points(_) => Style { stroke: Some("#7fdbca".into()), width: 2.0, opacity: 0.8, ..Default::default() },
+        Geometry::Polylines(_) => Style::stroke("#ffd166", 1.0).with_opacity(0.9),
+        Geometry::Polygons(_) => Style { stroke: Some("#ef476f".into()), fill: Some("#ef476f".into()), ..Default::default() },
+        _ => Style::fill("#f4a261").with_opacity(0.85),
+    }
+}
+
+/// Uniform scale and placement that fit the box `lo`–`hi` into a
+/// `width`×`height` canvas inside `margin`, centered.
+pub(crate) fn fit(lo: Point, hi: Point, width: f64, height: f64, margin: f64) -> (f64, impl Fn(Point) -> Point) {
+    let scale = ((width - 2.0 * margin) / (hi.x - lo.x).max(1e-12)).min((height - 2.0 * margin) / (hi.y - lo.y).max(1e-12));
+    let (ox, oy) = (
+        (width - (hi.x - lo.x) * scale) / 2.0 - lo.x * scale,
+        (height - (hi.y - lo.y) * scale) / 2.0 - lo.y * scale,
+    );
+    (scale, move |p: Point| Point { x: ox + p.x * scale, y: oy + p.y * scale })
+}
+
+impl Svg {
+    /// SVG elements for one group of shapes painted with `style`.
+    fn shapes(&self, geometry: &Geometry, style: &Style, to_canvas: &dyn Fn(Point) -> Point, scale: f64) -> String {
+        let mut d = String::new();
+        let mut discs = String::new();
+        geometry.for_each_shape(&mut |shape| match shape {
+            // Zero-length segments with round caps draw as dots in one path
+            Shape::Point(p) => {
+                let p = to_canvas(p);
+                d.push_str(&format!("M{:.1},{:.1}h0", p.x, p.y));
+            }
+            Shape::Polyline(line) | Shape::Polygon(line) => {
+                for (k, &p) in line.iter().enumerate() {
+                    let p = to_canvas(p);
+                    d.push_str(&format!("{}{:.1},{:.1}", if k == 0 { "M" } else { "L" }, p.x, p.y));
+                }
+                if matches!(shape, Shape::Polygon(l) if !l.is_empty()) {
+                    d.push('Z');
+                }
+            }
+            Shape::Circle(c) => {
+                let p = to_canvas(c.center);
+                discs.push_str(&format!(r##"<circle cx="{:.1}" cy="{:.1}" r="{:.2}"/>"##, p.x, p.y, c.radius * scale));
+            }
+        });
+        let paint = format!(
+            r##"fill="{}" stroke="{}" stroke-width="{}""##,
+            style.fill.as_deref().unwrap_or("none"),
+            style.stroke.as_deref().unwrap_or("none"),
+            style.width
+        );
+        let opacity = if style.opacity < 1.0 { format!(r##" opacity="{}""##, style.opacity) } else { String::new() };
+        let mut out = String::new();
+        if !d.is_empty() {
+            let extra = match geometry {
+                Geometry::Points(_) => r##" stroke-linecap="round""##,
+                Geometry::Polygons(_) => r##" fill-opacity="0.35" fill-rule="evenodd""##,
+                _ => r##" stroke-linejoin="round""##,
+            };
+            out.push_str(&format!(r##"<path d="{d}" {paint}{extra}{opacity}/>"##));
+        }
+        if !discs.is_empty() {
+            out.push_str(&format!("<g {paint}{opacity}>{discs}</g>"));
+        }
+        out
+    }
+}
+
+impl Svg {
+    /// [`Backend::render`] in `theme`: with a palette, each layer takes it
+    /// at its index. Grids stay viridis.
+    pub fn render_with_theme(&self, geometry: &Geometry, theme: &Theme) -> String {
+        let size = self.size;
+        if let Geometry::Grid { cols, rows, values } = geometry {
+            let (lo, hi) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+            let range = (hi - lo).max(1e-12);
+            // 64 levels keep the rect count manageable after run merging
+            let levels: Vec<f64> = values.iter().map(|v| ((v - lo) / range * 63.0).round() / 63.0).collect();
+            let cell = size / (*cols).max(*rows).max(1) as f64;
+            return crate::render::raster_svg_with_theme(*cols, *rows, cell, &levels, crate::render::viridis, theme);
+        }
+        let Some((lo, hi)) = geometry.bounds() else {
+            return crate::render::svg_document_with_theme(size as u32, size as u32, "", theme);
+        };
+        let (scale, to_canvas) = fit(lo, hi, size, size, self.margin);
+        let groups: Vec<(&Geometry, Style)> = match geometry {
+            // Layers nested in layers are flattened, each keeping its own style
+            Geometry::Layers(layers) => layers
+                .iter()
+                .flat_map(|layer| match &layer.geometry {
+                    Geometry::Layers(inner) => inner.as_slice(),
+                    _ => core::slice::from_ref(layer),
+                })
+                .map(|l| (&l.geometry, l.style.clone()))
+                .collect(),
+            other => vec![(other, default_style(other))],
+        };
+        let n = groups.len();
+        let mut content = String::new();
+        for (i, (geometry, style)) in groups.into_iter().enumerate() {
+            let style = theme.style(&style, theme::place(i, n));
+            content.push_str(&self.shapes(geometry, &style, &to_canvas, scale));
+        }
+        crate::render::svg_document_with_theme(size as u32, size as u32, &content, theme)
+    }
+}
+
+impl Backend for Svg {
+    type Output = String;
+
+    fn render(&self, geometry: &Geometry) -> String {
+        self.render_with_theme(geometry, &Theme::dark())
+    }
+}
+
+/// Render any geometry onto an 800×800 canvas with [`Svg`]'s defaults.
+pub fn to_svg(geometry: &Geometry) -> String {
+    Svg::default().render(geometry)
+}
+
+/// [`to_svg`] in `theme`.
+pub fn to_svg_with_theme(geometry: &Geometry, theme: &Theme) -> String {
+    Svg::default().render_with_theme(geometry, theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::*;
+    use alloc::boxed::Box;
+
+    #[test]
+    fn test_bounds_and_len() {
+        let g = Geometry::Polylines(vec![
+            vec![Point { x: -1.0, y: 2.0 }, Point { x: 3.0, y: 0.5 }],
+            vec![Point { x: 0.0, y: -4.0 }],
+        ]);
+        assert_eq!(g.len(), 3);
+        let (lo, hi) = g.bounds().unwrap();
+        assert_eq!((lo.x, lo.y, hi.x, hi.y), (-1.0, -4.0, 3.0, 2.0));
+        assert!(Geometry::Points(vec![]).bounds().is_none());
+        assert!(Geometry::Points(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_grid_bounds() {
+        let g = Geometry::Grid { cols: 4, rows: 3, values: vec![0.0; 12] };
+        let (lo, hi) = g.bounds().unwrap();
+        assert_eq!((lo.x, lo.y, hi.x, hi.y), (0.0, 0.0, 4.0, 3.0));
+    }
+
+    #[test]
+    fn test_to_buffer() {
+        let g = Geometry::Polylines(vec![vec![Point { x: 1.0, y: 2.0 }], vec![Point { x: 3.0, y: 4.5 }]]);
+        assert_eq!(g.to_buffer::<f32>(), vec![1.0f32, 2.0, 3.0, 4.5]);
+        let grid = Geometry::Grid { cols: 2, rows: 1, values: vec![0.25, 0.75] };
+        assert_eq!(grid.to_buffer::<f64>(), vec![0.25, 0.75]);
+    }
+
+    #[test]
+    fn test_svg_fits_canvas() {
+        let g = Geometry::Points(vec![Point { x: 1000.0, y: 1000.0 }, Point { x: 3000.0, y: 2000.0 }]);
+        let svg = to_svg(&g);
+        // Wider extent spans the canvas minus margins, shorter one is centered
+        assert!(svg.contains("M40.0,220.0h0"), "{}", svg);
+        assert!(svg.contains("M760.0,580.0h0"), "{}", svg);
+    }
+
+    #[test]
+    fn test_svg_each_kind() {
+        let square = vec![
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 1.0, y: 0.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: 0.0, y: 1.0 },
+        ];
+        assert!(to_svg(&Geometry::Polylines(vec![square.clone()])).contains("fill=\"none\""));
+        assert!(to_svg(&Geometry::Polygons(vec![square])).contains("Z\""));
+        let grid = Geometry::Grid { cols: 2, rows: 2, values: vec![0.0, 1.0, 2.0, 3.0] };
+        assert_eq!(to_svg(&grid).matches("<rect").count(), 1 + 4);
+        assert!(to_svg(&Geometry::Polygons(vec![])).contains("<svg"));
+    }
+
+    #[test]
+    fn test_circles_and_layers() {
+        let disc = Circle { center: Point { x: 1.0, y: 1.0 }, radius: 2.0 };
+        let layers = Geometry::Layers(vec![
+            Layer { style: Style::fill("#123456"), geometry: Geometry::Circles(vec![disc]) },
+            Layer { style: Style::stroke("#abcdef", 3.0), geometry: Geometry::Polylines(vec![vec![disc.center; 2]]) },
+        ]);
+        assert_eq!(layers.len(), 3);
+        let (lo, hi) = layers.bounds().unwrap();
+        assert_eq!((lo.x, lo.y, hi.x, hi.y), (-1.0, -1.0, 3.0, 3.0));
+        assert_eq!(Geometry::Circles(vec![disc]).to_buffer::<f64>(), vec![1.0, 1.0, 2.0]);
+        let svg = to_svg(&layers);
+        assert_eq!(svg.matches("<circle").count(), 1);
+        assert!(svg.contains(r##"fill="#123456" stroke="none""##), "{}", svg);
+        assert!(svg.contains(r##"fill="none" stroke="#abcdef" stroke-width="3""##), "{}", svg);
+    }
+
+    /// Counts shapes instead of drawing them.
+    struct Census;
+
+    impl Backend for Census {
+        type Output = usize;
+
+        fn render(&self, geometry: &Geometry) -> usize {
+            let mut n = 0;
+            geometry.for_each_shape(&mut |_| n += 1);
+            n
+        }
+    }
+
+    #[test]
+    fn test_pattern_render() {
+        let rose = roses::RoseParams::default();
+        assert_eq!(rose.render(&Census), 1);
+        assert_eq!(phyllotaxis::Params { count: 30, ..Default::default() }.render(&Census), 30);
+        let small = Svg { size: 200.0, margin: 10.0 };
+        assert!(rose.render(&small).contains(r##"width="200""##));
+        assert_eq!(rose.render(&Svg::default()), to_svg(&rose.generate()));
+    }
+
+    #[test]
+    fn test_every_category_generates() {
+        let mut turing_grid = turing::Grid::new(20, 20);
+        turing_grid.step(&turing::Preset::Spots.params());
+        let mut flake = snowflake::Snowflake::new(12, &snowflake::ReiterParams::default());
+        flake.simulate(&snowflake::ReiterParams::default(), 30);
+        let generators: Vec<Box<dyn Generator>> = vec![
+            Box::new(phyllotaxis::Params { count: 50, ..Default::default() }),
+            Box::new(fractals::Fractal::Fern { points: 500, seed: 1 }),
+            Box::new(fractals::Fractal::Koch { iterations: 2 }),
+            Box::new(spirals::Spiral { kind: spirals::SpiralType::Golden { a: 0.5 }, points: 100, turns: 3.0 }),
+            Box::new(chaos::LorenzOrbit { steps: 500, ..Default::default() }),
+            Box::new(lsystems::Derivation { system: lsystems::plant(), iterations: 2 }),
+            Box::new(turing_grid),
+            Box::new(flake),
+            Box::new(roses::RoseParams::default()),
+            Box::new(superformula::Preset::Starfish.params()),
+            Box::new(harmonograph::LissajousParams::default()),
+            Box::new(epicycles::fourier_series(&epicycles::leaf_outline(64))),
+            Box::new(spirograph::Spirograph::default()),
+            Box::new(flocking::Flock::new(&flocking::FlockParams { count: 20, ..Default::default() }, 1)),
+            Box::new(physarum::Physarum::new(16, 16, &physarum::PhysarumParams { agents: 50, ..Default::default() }, 1)),
+            Box::new(dla::grow(41, 41, &dla::DlaParams { particles: 30, ..Default::default() }, 1)),
+            Box::new(sandpile::Sandpile::filled(8, 8, 3)),
+            Box::new(automata::Diagram { rule: automata::Rule::Elementary(30), width: 21, generations: 10, init: automata::InitialState::SingleCell }),
+            Box::new(lenia::Lenia::from_preset(lenia::Preset::Orbium, 32, 32, 1)),
+            Box::new(fireflies::Swarm::new(&fireflies::KuramotoParams { count: 20, ..Default::default() }, 1)),
+            Box::new(predator_prey::Model::default()),
+            Box::new(waves::Preset::TwoSource.field(0)),
+            Box::new(chladni::Chladni::square(2, 3)),
+            Box::new(dunes::DuneField::new(16, 16, &dunes::DuneParams::default(), 1)),
+            Box::new(terrain::Terrain::generate(16, 16, &terrain::TerrainParams::default(), 1)),
+            Box::new(orbweb::Preset::Garden.params()),
+            Box::new(tessellations::Preset::Apis.params()),
+            Box::new(symmetry::Mandala::from(symmetry::Preset::Snowflake)),
+        ];
+        for (i, generator) in generators.iter().enumerate() {
+            assert!(!generator.generate().is_empty(), "Generator {} produced nothing", i);
+            assert!(generator.render(&Svg::default()).contains("</svg>"));
+        }
+    }
+}
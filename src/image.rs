@@ -0,0 +1,220 @@
+//! Image import — photographs and silhouettes as model input.
+//!
+//! Enabled by the `image` feature. A PNG or JPEG is loaded as a grayscale
+//! grid of luminance values in [0, 1] (0 black, 1 white), which can then
+//! seed a Turing simulation, become a boolean domain mask, or be reduced to
+//! points — every dark pixel, or the centroid of every dark blob, such as
+//! the seeds picked out of a sunflower photograph for
+//! [`phyllotaxis::fit_divergence_angle`](crate::categories::phyllotaxis::fit_divergence_angle).
+//!
+//! Foreground is dark on a light background throughout; use
+//! [`Grayscale::inverted`] for light-on-dark images.
+//!
+//! In the other direction, [`save_rgb_png`] writes rendered rasters such as
+//! escape-time fractals, which are too dense to be practical as SVG.
+
+use std::path::Path;
+
+use crate::prelude::*;
+use crate::categories::fractals::Point;
+use crate::categories::turing;
+use crate::error::{MathaturaError, Result};
+use crate::geometry::Geometry;
+
+/// A row-major grid of luminance values in [0, 1].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grayscale {
+    pub width: usize,
+    pub height: usize,
+    pub values: Vec<f64>,
+}
+
+fn image_error(e: ::image::ImageError) -> MathaturaError {
+    match e {
+        ::image::ImageError::IoError(e) => MathaturaError::Io(e),
+        other => MathaturaError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, other)),
+    }
+}
+
+impl Grayscale {
+    /// Load a PNG or JPEG file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::from_image(::image::open(path).map_err(image_error)?))
+    }
+
+    /// Decode a PNG or JPEG held in memory.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(Self::from_image(::image::load_from_memory(bytes).map_err(image_error)?))
+    }
+
+    fn from_image(image: ::image::DynamicImage) -> Self {
+        let luma = image.into_luma8();
+        let (width, height) = (luma.width() as usize, luma.height() as usize);
+        let values = luma.into_raw().into_iter().map(|v| v as f64 / 255.0).collect();
+        Grayscale { width, height, values }
+    }
+
+    /// Luminance at (x, y), clamped to the edges.
+    pub fn get(&self, x: usize, y: usize) -> f64 {
+        self.values[y.min(self.height - 1) * self.width + x.min(self.width - 1)]
+    }
+
+    /// Bilinearly resampled to `width × height`.
+    pub fn resize(&self, width: usize, height: usize) -> Grayscale {
+        let sx = self.width as f64 / width.max(1) as f64;
+        let sy = self.height as f64 / height.max(1) as f64;
+        let values = (0..width * height)
+            .map(|k| {
+                // Sample at pixel centers
+                let fx = ((k % width) as f64 + 0.5) * sx - 0.5;
+                let fy = ((k / width) as f64 + 0.5) * sy - 0.5;
+                let (x0, y0) = (fx.max(0.0) as usize, fy.max(0.0) as usize);
+                let (tx, ty) = ((fx - x0 as f64).clamp(0.0, 1.0), (fy - y0 as f64).clamp(0.0, 1.0));
+                let top = self.get(x0, y0) * (1.0 - tx) + self.get(x0 + 1, y0) * tx;
+                let bottom = self.get(x0, y0 + 1) * (1.0 - tx) + self.get(x0 + 1, y0 + 1) * tx;
+                top * (1.0 - ty) + bottom * ty
+            })
+            .collect();
+        Grayscale { width, height, values }
+    }
+
+    /// Light and dark swapped.
+    pub fn inverted(&self) -> Grayscale {
+        Grayscale { width: self.width, height: self.height, values: self.values.iter().map(|v| 1.0 - v).collect() }
+    }
+
+    /// True for pixels darker than `threshold`.
+    pub fn mask(&self, threshold: f64) -> Vec<bool> {
+        self.values.iter().map(|&v| v < threshold).collect()
+    }
+
+    /// Centers of all pixels darker than `threshold`, in pixel units, y down.
+    pub fn points(&self, threshold: f64) -> Vec<Point> {
+        self.mask(threshold)
+            .iter()
+            .enumerate()
+            .filter(|(_, &dark)| dark)
+            .map(|(k, _)| Point { x: (k % self.width) as f64 + 0.5, y: (k / self.width) as f64 + 0.5 })
+            .collect()
+    }
+
+    /// Centroids of the 4-connected dark regions with at least `min_pixels`
+    /// pixels — one point per seed, floret or dot.
+    pub fn blobs(&self, threshold: f64, min_pixels: usize) -> Vec<Point> {
+        let mask = self.mask(threshold);
+        let mut seen = vec![false; mask.len()];
+        let mut centroids = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..mask.len() {
+            if !mask[start] || seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            stack.push(start);
+            let (mut count, mut sx, mut sy) = (0usize, 0.0, 0.0);
+            while let Some(k) = stack.pop() {
+                let (x, y) = (k % self.width, k / self.width);
+                count += 1;
+                sx += x as f64 + 0.5;
+                sy += y as f64 + 0.5;
+                let neighbors = [
+                    (x > 0).then(|| k - 1),
+                    (x + 1 < self.width).then(|| k + 1),
+                    (y > 0).then(|| k - self.width),
+                    (y + 1 < self.height).then(|| k + self.width),
+                ];
+                for n in neighbors.into_iter().flatten() {
+                    if mask[n] && !seen[n] {
+                        seen[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+            if count >= min_pixels.max(1) {
+                centroids.push(Point { x: sx / count as f64, y: sy / count as f64 });
+            }
+        }
+        centroids
+    }
+
+    /// A Gray–Scott grid seeded with chemical B wherever the image is dark.
+    pub fn to_turing(&self) -> turing::Grid {
+        turing::Grid::from_field(self.width, self.height, &self.inverted().values)
+    }
+
+    /// The luminance field as geometry.
+    pub fn to_geometry(&self) -> Geometry {
+        Geometry::Grid { cols: self.width, rows: self.height, values: self.values.clone() }
+    }
+}
+
+/// Write row-major RGB bytes (three per pixel) as a PNG file.
+pub fn save_rgb_png(path: impl AsRef<Path>, width: usize, height: usize, rgb: &[u8]) -> Result<()> {
+    let image = ::image::RgbImage::from_raw(width as u32, height as u32, rgb.to_vec()).ok_or_else(|| {
+        MathaturaError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "pixel buffer does not match the image size"))
+    })?;
+    image.save_with_format(path, ::image::ImageFormat::Png).map_err(image_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Two dark squares on white, encoded as a PNG.
+    fn two_squares_png() -> Vec<u8> {
+        let image = ::image::GrayImage::from_fn(20, 10, |x, y| {
+            let dark = ((2..5).contains(&x) && (2..5).contains(&y)) || ((12..16).contains(&x) && (4..8).contains(&y));
+            ::image::Luma([if dark { 0 } else { 255 }])
+        });
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), ::image::ImageFormat::Png).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_decode_png() {
+        let gray = Grayscale::from_bytes(&two_squares_png()).unwrap();
+        assert_eq!((gray.width, gray.height), (20, 10));
+        assert_eq!(gray.get(0, 0), 1.0);
+        assert_eq!(gray.get(3, 3), 0.0);
+        assert_eq!(gray.mask(0.5).iter().filter(|&&m| m).count(), 9 + 16);
+        assert_eq!(gray.points(0.5).len(), 25);
+    }
+
+    #[test]
+    fn test_blob_centroids() {
+        let gray = Grayscale::from_bytes(&two_squares_png()).unwrap();
+        let blobs = gray.blobs(0.5, 1);
+        assert_eq!(blobs, vec![Point { x: 3.5, y: 3.5 }, Point { x: 14.0, y: 6.0 }]);
+        assert_eq!(gray.blobs(0.5, 10).len(), 1);
+    }
+
+    #[test]
+    fn test_resize_and_seed() {
+        let gray = Grayscale { width: 2, height: 1, values: vec![0.0, 1.0] };
+        let wide = gray.resize(4, 2);
+        assert_eq!((wide.width, wide.height), (4, 2));
+        assert_eq!(wide.values[0], 0.0);
+        assert_eq!(wide.values[3], 1.0);
+        assert!(wide.values[1] > 0.0 && wide.values[1] < wide.values[2]);
+        let grid = gray.to_turing();
+        assert_eq!((grid.cells[0].b, grid.cells[1].b), (1.0, 0.0));
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let path = std::env::temp_dir().join("mathatura-image-test.png");
+        save_rgb_png(&path, 2, 1, &[0, 0, 0, 255, 255, 255]).unwrap();
+        let gray = Grayscale::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(gray.values, vec![0.0, 1.0]);
+        assert!(save_rgb_png(&path, 3, 3, &[0; 6]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_bytes() {
+        assert!(matches!(Grayscale::from_bytes(b"not an image"), Err(MathaturaError::Io(_))));
+    }
+}
@@ -10,19 +10,139 @@
 //! ## Categories
 //!
 //! - **Phyllotaxis**: Leaf spirals, sunflowers, pinecones (golden angle, Vogel's model)
-//! - **Fractals**: Barnsley fern, Koch snowflake, Mandelbrot set, Sierpinski triangle
+//! - **Fractals**: Barnsley fern, Koch snowflake and its variants, Fibonacci word fractal, Mandelbrot, Julia, Burning Ship and Multibrot sets, Newton fractals, Sierpinski triangle and carpet, Menger sponge
+//! - **IFS**: Iterated function systems from text or JSON, drawn by the chaos game
 //! - **Spirals**: Logarithmic, Archimedean, Fermat spirals found in shells and galaxies
 //! - **Chaos**: Lorenz attractor, logistic map, strange attractors
+//! - **Attractors**: de Jong, Clifford and Tinkerbell maps rendered as density images
 //! - **L-Systems**: Lindenmayer systems for trees, ferns, branching structures
 //! - **Turing Patterns**: Reaction-diffusion systems creating animal markings
+//! - **Snowflakes**: Reiter's hexagonal automaton growing six-fold ice crystals
+//! - **Roses**: Rhodonea curves r = a·cos(kθ) with rational k
+//! - **Superformula**: Gielis shapes for starfish, flowers, diatoms, and leaves
+//! - **Harmonographs**: Lissajous figures and damped pendulum drawings
+//! - **Epicycles**: Fourier series tracing closed outlines with rotating circles
+//! - **Spirographs**: Hypotrochoids and epitrochoids from rolling gears
+//! - **Flocking**: Reynolds' boids and starling murmurations
+//! - **Physarum**: Agent-based slime-mold transport networks
+//! - **DLA**: Diffusion-limited aggregation of coral, frost, and mineral dendrites
+//! - **Space colonization**: Leaf venation with closed loops and open tree crowns
+//! - **Sandpile**: Abelian sandpile toppling and self-organized criticality
+//! - **Automata**: Elementary and totalistic 1D cellular automata (Rule 30 shells)
+//! - **Lenia**: Continuous cellular automata with self-organizing creatures
+//! - **Fireflies**: Kuramoto oscillators and spontaneous synchronization
+//! - **Predator–prey**: Lotka–Volterra and Rosenzweig–MacArthur population cycles
+//! - **Waves**: Interference of circular ripples, nodal lines, and moiré
+//! - **Chladni**: Nodal-line figures of vibrating square and circular plates
+//! - **Dunes**: Werner slab model of wind ripples, transverse dunes and barchans
+//! - **Terrain**: Fractional Brownian motion relief maps with hillshade and contours
+//! - **Orb webs**: Spider webs with radials, auxiliary and capture spirals, sag and dew
 //! - **Symmetry**: Bilateral, radial, and rotational symmetry in nature
 //! - **Tessellations**: Honeycombs, Voronoi diagrams, natural tilings
+//!
+//! ## Shared geometry
+//!
+//! Most models also implement [`geometry::Generator`], reducing them to a
+//! [`geometry::Geometry`] — points, polylines, polygons, circles, styled
+//! layers, or a scalar grid — that any renderer can draw without knowing the
+//! math behind it. Every generator is also a [`geometry::Pattern`], rendered
+//! through any [`geometry::Backend`] such as [`geometry::Svg`]. Below that,
+//! [`render::canvas::Canvas`] is the handful of drawing primitives a new
+//! output format has to provide for [`render::canvas::draw`] to paint any
+//! geometry onto it.
+//! Every `to_svg` has a `_with_theme` sibling drawing in a
+//! [`render::theme::Theme`], as [`render::canvas::draw_with_theme`] does.
+//!
+//! ## Features
+//!
+//! - `serde`: `Serialize`/`Deserialize` for parameter structs, presets, and
+//!   model state
+//! - `parallel`: multithreaded grid updates, escape-time rows, field sampling
+//!   and analysis via rayon, with output identical to the sequential build
+//! - `image`: PNG/JPEG loading into grayscale grids, masks and point sets,
+//!   for image-seeded simulations and fitting models to photographs
+//! - `wasm`: wasm-bindgen classes that step Turing, Lorenz and L-system
+//!   models frame by frame and return typed arrays
+//! - `std` (default): the CLI and file I/O. Without it the crate is
+//!   `no_std` + `alloc`, using libm for floating-point math, so the models
+//!   and SVG generation run on embedded displays and bare WASM
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+pub mod analysis;
 pub mod categories;
+pub mod contour;
+pub mod error;
+pub mod export;
+pub mod fixed;
+pub mod float;
+pub mod geometry;
+#[cfg(feature = "image")]
+pub mod image;
+pub mod metrics;
+pub mod parallel;
+pub mod progress;
 pub mod render;
+pub mod rng;
+pub mod sonify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::{Error, MathaturaError, Result};
+
+/// What the standard prelude would provide, for `no_std` builds.
+pub(crate) mod prelude {
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec::Vec;
+    pub use alloc::{format, vec};
+
+    /// Hash map with `std`; ordered map without, which needs no hasher.
+    /// Lookups behave the same, but iteration order differs.
+    #[cfg(feature = "std")]
+    pub type Map<K, V> = std::collections::HashMap<K, V>;
+    #[cfg(not(feature = "std"))]
+    pub type Map<K, V> = alloc::collections::BTreeMap<K, V>;
+
+    /// Hash set with `std`; ordered set without, as for [`Map`].
+    #[cfg(feature = "std")]
+    pub type Set<T> = std::collections::HashSet<T>;
+    #[cfg(not(feature = "std"))]
+    pub type Set<T> = alloc::collections::BTreeSet<T>;
+
+    // Float methods (sqrt, sin, ...) from libm
+    #[cfg(not(feature = "std"))]
+    pub use num_traits::Float as _;
+    #[cfg(not(feature = "std"))]
+    pub use self::euclid::RemEuclid as _;
+
+    #[cfg(not(feature = "std"))]
+    mod euclid {
+        /// `f64::rem_euclid`, which core lacks.
+        pub trait RemEuclid {
+            fn rem_euclid(self, rhs: Self) -> Self;
+        }
+
+        impl RemEuclid for f64 {
+            fn rem_euclid(self, rhs: f64) -> f64 {
+                let r = self % rhs;
+                if r < 0.0 {
+                    r + rhs.abs()
+                } else {
+                    r
+                }
+            }
+        }
+    }
+}
 
 /// Mathematical constants used throughout the library.
 pub mod constants {
+    pub use num_bigint::BigUint;
+
+    use crate::prelude::*;
+
     /// The golden ratio φ = (1 + √5) / 2
     pub const PHI: f64 = 1.618_033_988_749_895;
 
@@ -32,6 +152,18 @@ pub mod constants {
     /// The golden angle in radians
     pub const GOLDEN_ANGLE_RAD: f64 = 2.399_963_229_728_653;
 
+    /// The silver ratio δ = 1 + √2, the second metallic mean
+    pub const SILVER_RATIO: f64 = 2.414_213_562_373_095;
+
+    /// The bronze ratio (3 + √13) / 2, the third metallic mean
+    pub const BRONZE_RATIO: f64 = 3.302_775_637_731_995;
+
+    /// The silver angle in degrees ≈ 105.44°
+    pub const SILVER_ANGLE_DEG: f64 = 105.441_558_772_842_9;
+
+    /// The bronze angle in degrees ≈ 83.67°
+    pub const BRONZE_ANGLE_DEG: f64 = 83.666_923_472_160_63;
+
     /// Fibonacci numbers up to F(20)
     pub const FIBONACCI: [u64; 21] = [
         0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144, 233, 377, 610, 987, 1597, 2584, 4181,
@@ -41,31 +173,64 @@ pub mod constants {
     /// Common Fibonacci numbers found in flower petals
     pub const PETAL_COUNTS: [u64; 8] = [1, 2, 3, 5, 8, 13, 21, 34];
 
-    /// Generate Fibonacci sequence of n terms
+    /// Generate Fibonacci sequence of n terms. Only 94 fit in u64, so it
+    /// stops there rather than overflow; use [`fibonacci_sequence_big`]
+    /// beyond that.
     pub fn fibonacci_sequence(n: usize) -> Vec<u64> {
-        if n == 0 {
-            return vec![];
+        generalized_fibonacci(0, 1, 1, n)
+    }
+
+    /// Check if a number is a Fibonacci number (exact for every u64)
+    pub fn is_fibonacci(n: u64) -> bool {
+        // Only 94 Fibonacci numbers fit in u64, so walking them is cheap
+        let (mut a, mut b) = (0u64, 1u64);
+        while a < n {
+            let Some(next) = a.checked_add(b) else { return b == n };
+            (a, b) = (b, next);
         }
-        let mut seq = Vec::with_capacity(n);
-        seq.push(0);
-        if n >= 2 {
-            seq.push(1);
+        a == n
+    }
+
+    /// Check if an arbitrarily large number is a Fibonacci number:
+    /// exactly when 5n² + 4 or 5n² − 4 is a perfect square.
+    pub fn is_fibonacci_big(n: &BigUint) -> bool {
+        let is_square = |x: &BigUint| {
+            let root = x.sqrt();
+            &root * &root == *x
+        };
+        let five_n2 = n * n * 5u32;
+        let four = BigUint::from(4u32);
+        is_square(&(&five_n2 + &four)) || (five_n2 >= four && is_square(&(&five_n2 - &four)))
+    }
+
+    /// F(n), or `None` past F(186), the largest that fits in u128.
+    pub fn fibonacci_u128(n: u32) -> Option<u128> {
+        if n > 186 {
+            return None;
         }
-        for i in 2..n {
-            seq.push(seq[i - 1] + seq[i - 2]);
+        u128::try_from(fibonacci_big(n as u64)).ok()
+    }
+
+    /// F(n) of any size, by fast doubling: F(2k) = F(k)·(2F(k+1) − F(k))
+    /// and F(2k+1) = F(k)² + F(k+1)², one step per bit of n.
+    pub fn fibonacci_big(n: u64) -> BigUint {
+        let (mut a, mut b) = (BigUint::from(0u32), BigUint::from(1u32));
+        for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+            let c = &a * (&b * 2u32 - &a);
+            let d = &a * &a + &b * &b;
+            (a, b) = if n >> bit & 1 == 0 { (c, d) } else { (d.clone(), c + d) };
         }
-        seq
+        a
     }
 
-    /// Check if a number is a Fibonacci number
-    pub fn is_fibonacci(n: u64) -> bool {
-        // A number is Fibonacci if 5n² + 4 or 5n² - 4 is a perfect square
-        let check = |x: u64| -> bool {
-            let s = (x as f64).sqrt() as u64;
-            s * s == x
-        };
-        let n2 = n.saturating_mul(n).saturating_mul(5);
-        check(n2 + 4) || check(n2.saturating_sub(4))
+    /// Generate Fibonacci sequence of n terms without overflow
+    pub fn fibonacci_sequence_big(n: usize) -> Vec<BigUint> {
+        let mut seq: Vec<BigUint> = [0u32, 1].into_iter().map(BigUint::from).take(n).collect();
+        while seq.len() < n {
+            let next = &seq[seq.len() - 1] + &seq[seq.len() - 2];
+            seq.push(next);
+        }
+        seq
     }
 
     /// Fibonacci ratios converging to φ
@@ -76,4 +241,155 @@ pub mod constants {
             .map(|w| w[1] as f64 / w[0] as f64)
             .collect()
     }
+
+    /// The n-th metallic mean (n + √(n² + 4)) / 2: golden for 1, silver
+    /// for 2, bronze for 3. Its continued fraction is [n; n, n, n, ...].
+    pub fn metallic_ratio(n: u32) -> f64 {
+        let n = n as f64;
+        (n + (n * n + 4.0).sqrt()) / 2.0
+    }
+
+    /// Divergence angle in degrees dividing the circle in the ratio δ : 1,
+    /// as the golden angle does for φ.
+    pub fn divergence_angle_deg(ratio: f64) -> f64 {
+        360.0 / (1.0 + ratio)
+    }
+
+    /// First n terms of a(k) = multiplier · a(k−1) + a(k−2). Successive
+    /// ratios converge to `metallic_ratio(multiplier)`. Stops early rather
+    /// than overflow.
+    pub fn generalized_fibonacci(first: u64, second: u64, multiplier: u64, n: usize) -> Vec<u64> {
+        let mut seq: Vec<u64> = [first, second].into_iter().take(n).collect();
+        while seq.len() < n {
+            let k = seq.len();
+            match seq[k - 1].checked_mul(multiplier).and_then(|x| x.checked_add(seq[k - 2])) {
+                Some(next) => seq.push(next),
+                None => break,
+            }
+        }
+        seq
+    }
+
+    /// Lucas numbers 2, 1, 3, 4, 7, 11, ... — Fibonacci's companion sequence,
+    /// also seen in the parastichy counts of some sunflowers.
+    pub fn lucas_sequence(n: usize) -> Vec<u64> {
+        generalized_fibonacci(2, 1, 1, n)
+    }
+
+    /// Continued-fraction expansion [a₀; a₁, a₂, ...] of a non-negative x,
+    /// up to `max_terms` terms or until the remainder vanishes.
+    ///
+    /// This is why noble angles pack well: the golden angle as a fraction of
+    /// a turn is [0; 2, 1, 1, 1, ...], all ones after the start, so it is the
+    /// number worst approximated by rationals. No small number of turns ever
+    /// brings a new primordium back over an old one.
+    pub fn continued_fraction(x: f64, max_terms: usize) -> Vec<u64> {
+        let mut terms = Vec::new();
+        let mut x = x.abs();
+        while terms.len() < max_terms {
+            let a = x.floor();
+            terms.push(a as u64);
+            let rest = x - a;
+            if rest < 1e-9 {
+                break;
+            }
+            x = 1.0 / rest;
+        }
+        terms
+    }
+
+    /// Convergents p/q of a continued fraction, as (p, q) pairs. For the
+    /// golden angle these are the Fibonacci parastichy ratios 1/2, 1/3,
+    /// 2/5, 3/8, 5/13, ...
+    pub fn convergents(terms: &[u64]) -> Vec<(u64, u64)> {
+        let (mut p, mut p_prev) = (1u64, 0u64);
+        let (mut q, mut q_prev) = (0u64, 1u64);
+        let mut result = Vec::with_capacity(terms.len());
+        for &a in terms {
+            let next = a
+                .checked_mul(p)
+                .and_then(|x| x.checked_add(p_prev))
+                .zip(a.checked_mul(q).and_then(|x| x.checked_add(q_prev)));
+            let Some((p_next, q_next)) = next else { break };
+            (p_prev, p) = (p, p_next);
+            (q_prev, q) = (q, q_next);
+            result.push((p, q));
+        }
+        result
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_is_fibonacci_exact() {
+            assert!(is_fibonacci(0) && is_fibonacci(1) && is_fibonacci(144));
+            assert!(!is_fibonacci(4) && !is_fibonacci(145));
+            // F(93) is the largest u64 Fibonacci number; f64 sqrt misjudged neighbours
+            let f93 = 12_200_160_415_121_876_738;
+            assert!(is_fibonacci(f93));
+            assert!(!is_fibonacci(f93 - 1) && !is_fibonacci(f93 + 1) && !is_fibonacci(u64::MAX));
+        }
+
+        #[test]
+        fn test_fast_doubling() {
+            let seq = fibonacci_sequence(94);
+            for (n, &f) in seq.iter().enumerate() {
+                assert_eq!(fibonacci_u128(n as u32), Some(f as u128));
+                assert_eq!(fibonacci_big(n as u64), BigUint::from(f));
+            }
+            let f186 = fibonacci_u128(186).unwrap();
+            assert_eq!(fibonacci_big(186), BigUint::from(f186));
+            assert_eq!(fibonacci_u128(187), None);
+            assert_eq!(fibonacci_u128(u32::MAX), None);
+            assert_eq!(fibonacci_sequence(95), seq);
+            assert_eq!(fibonacci_big(300).to_string(), "222232244629420445529739893461909967206666939096499764990979600");
+        }
+
+        #[test]
+        fn test_big_sequence() {
+            let seq = fibonacci_sequence_big(301);
+            assert_eq!(seq.len(), 301);
+            assert_eq!(seq[300], fibonacci_big(300));
+            assert!(seq.iter().skip(90).all(is_fibonacci_big));
+            assert!(!is_fibonacci_big(&(&seq[300] + 1u32)));
+            assert!(fibonacci_sequence_big(0).is_empty());
+            assert_eq!(fibonacci_sequence_big(1), vec![BigUint::from(0u32)]);
+        }
+
+        #[test]
+        fn test_metallic_ratios() {
+            assert!((metallic_ratio(1) - PHI).abs() < 1e-12);
+            assert!((metallic_ratio(2) - SILVER_RATIO).abs() < 1e-12);
+            assert!((metallic_ratio(3) - BRONZE_RATIO).abs() < 1e-12);
+            assert!((divergence_angle_deg(PHI) - GOLDEN_ANGLE_DEG).abs() < 1e-9);
+            assert!((divergence_angle_deg(SILVER_RATIO) - SILVER_ANGLE_DEG).abs() < 1e-9);
+            assert!((divergence_angle_deg(BRONZE_RATIO) - BRONZE_ANGLE_DEG).abs() < 1e-9);
+        }
+
+        #[test]
+        fn test_lucas_and_generalized() {
+            assert_eq!(lucas_sequence(8), vec![2, 1, 3, 4, 7, 11, 18, 29]);
+            assert_eq!(generalized_fibonacci(0, 1, 1, 21), FIBONACCI.to_vec());
+            // Pell numbers, whose ratios approach the silver ratio
+            let pell = generalized_fibonacci(0, 1, 2, 20);
+            assert_eq!(&pell[..6], &[0, 1, 2, 5, 12, 29]);
+            assert!((pell[19] as f64 / pell[18] as f64 - SILVER_RATIO).abs() < 1e-12);
+            assert_eq!(generalized_fibonacci(0, 1, 1, 1), vec![0]);
+            // Overflow ends the sequence instead of panicking
+            assert_eq!(generalized_fibonacci(0, 1, 1, 200).len(), 94);
+        }
+
+        #[test]
+        fn test_continued_fractions() {
+            assert_eq!(continued_fraction(PHI, 10), vec![1; 10]);
+            assert_eq!(continued_fraction(SILVER_RATIO, 8), vec![2; 8]);
+            assert_eq!(continued_fraction(0.75, 10), vec![0, 1, 3]);
+            let turn = continued_fraction(GOLDEN_ANGLE_DEG / 360.0, 8);
+            assert_eq!(turn, vec![0, 2, 1, 1, 1, 1, 1, 1]);
+            let ratios = convergents(&turn);
+            assert_eq!(ratios[1..].to_vec(), vec![(1, 2), (1, 3), (2, 5), (3, 8), (5, 13), (8, 21), (13, 34)]);
+        }
+    }
 }
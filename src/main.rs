@@ -1,8 +1,33 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-use mathatura::categories::{phyllotaxis, fractals, spirals, chaos, lsystems, turing};
+use mathatura::categories::{phyllotaxis, fractals, ifs, spirals, chaos, attractors, lsystems, turing, snowflake, roses, superformula, harmonograph, epicycles, spirograph, flocking, physarum, dla, colonization, sandpile, automata, lenia, fireflies, predator_prey, waves, chladni, dunes, terrain, orbweb, tessellations, symmetry};
+use mathatura::categories::chaos::Attractor3D;
+use mathatura::categories::turing::ReactionModel;
+use mathatura::geometry::{Backend, Generator, Geometry};
+use mathatura::render::canvas::Dots;
+use mathatura::render::colormap::{self, Colormap};
+use mathatura::render::gradient::{Gradient, Interpolation};
+use mathatura::render::terminal::Terminal;
+use mathatura::render::theme::Theme;
+use mathatura::error::{check_count, check_positive, check_range};
+use mathatura::export;
+use mathatura::fixed::Fixed;
+use mathatura::sonify;
+use mathatura::{MathaturaError, Result};
+
+/// Largest grid side accepted for simulations and maps.
+const MAX_GRID_SIZE: usize = 4096;
+
+/// Patterns the `analyze` subcommand knows the statistics of.
+const ANALYZE_PATTERNS: [&str; 16] = [
+    "fern", "sierpinski", "koch", "carpet", "mandelbrot", "lsystem", "logistic", "phyllotaxis", "turing", "dla",
+    "lorenz", "rossler", "aizawa", "halvorsen", "thomas", "chen",
+];
 
 #[derive(Parser)]
 #[command(name = "mathatura")]
@@ -15,6 +40,43 @@ struct Cli {
     /// Output file path
     #[arg(short, long, default_value = "output.svg")]
     output: PathBuf,
+
+    /// Scene file (TOML, or JSON by extension) supplying any flags; flags on
+    /// the command line override it
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Random seed for the stochastic generators: change it to explore
+    /// variations, keep it to reproduce a favorite
+    #[arg(long, global = true, default_value_t = 42)]
+    seed: u64,
+
+    /// Write the figure (svg) or the numbers behind it (json, csv) for
+    /// phyllotaxis, fractals, spirals, chaos, lsystem and turing
+    #[arg(long, global = true, default_value = "svg", value_parser = ["svg", "json", "csv"])]
+    format: String,
+
+    /// Your own palette, as comma-separated stops such as
+    /// "#012a4a,#2a6f97,#a9d6e5" or "hsl(200,80%,20%),#fff": rasters run
+    /// along it, curves and dots take it by their place in the figure;
+    /// overrides --colormap, --palette and the theme's palette
+    #[arg(long, global = true, value_name = "STOPS")]
+    colors: Option<String>,
+
+    /// Blend --colors stops in oklab (perceptually even) or srgb
+    #[arg(long, global = true, default_value = "oklab", value_parser = Interpolation::NAMES)]
+    color_space: String,
+
+    /// Look of the SVG output: dark, light, paper, neon, or a TOML theme
+    /// file with background, palette, stroke_scale and opacity
+    #[arg(long, global = true, default_value = "dark", value_name = "THEME")]
+    theme: String,
+
+    /// Write point clouds (ferns, IFS and Sierpinski chaos games, map
+    /// orbits, attractor points, Poincaré sections) as one path per color
+    /// instead of a circle per dot, for files about a tenth the size
+    #[arg(long, global = true)]
+    compact: bool,
 }
 
 #[derive(Subcommand)]
@@ -30,18 +92,117 @@ enum Commands {
         /// Scaling factor
         #[arg(short, long, default_value_t = 8.0)]
         scale: f64,
-        /// Pattern: sunflower, rosette, pinecone
+        /// Pattern: sunflower, rosette, pinecone, sphere, cylinder, stem (unrolled cylinder)
         #[arg(short, long, default_value = "sunflower")]
         pattern: String,
+        /// Cylinder: height gained per element
+        #[arg(long, default_value_t = 1.0)]
+        rise: f64,
+        /// Cylinder: girth of the stem
+        #[arg(long, default_value_t = 40.0)]
+        circumference: f64,
+        /// Mesh export (-o *.obj or *.stl): head height as a fraction of its radius
+        #[arg(long, default_value_t = 0.3)]
+        height: f64,
+        /// Sunflower rendering: dots, cells (Voronoi tessellation of the head)
+        #[arg(short, long, default_value = "dots")]
+        render: String,
+        /// Element shape: circle, ellipse, petal, square, natural (suits the pattern)
+        #[arg(short, long, default_value = "circle")]
+        floret: String,
     },
     /// Generate fractal visualizations
     Fractals {
-        /// Type: fern, koch, sierpinski, mandelbrot
+        /// Type: fern, koch, fibword, sierpinski, carpet, menger, mandelbrot, julia, burningship, multibrot, newton, ifs
         #[arg(short = 't', long, default_value = "fern")]
         fractal_type: String,
-        /// Iterations / detail level
+        /// IFS: built-in system, fern, maple, carpet, tree, spiral or cantor
+        #[arg(short, long, default_value = "fern")]
+        preset: String,
+        /// IFS: definition file, one map "a b c d e f [p]" per line (or .json), instead of a preset
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Iterations / detail level (subdivision depth for koch, fibword, carpet and menger)
         #[arg(short, long, default_value_t = 50000)]
         iterations: usize,
+        /// Koch: snowflake, anti (inward bumps), cesaro or quadratic (Minkowski island)
+        #[arg(long, default_value = "snowflake", value_parser = fractals::KochVariant::NAMES)]
+        variant: String,
+        /// Koch cesaro: apex angle of the tents in degrees
+        #[arg(long, default_value_t = 85.0)]
+        angle: f64,
+        /// Escape-time fractals: iteration limit
+        #[arg(long, default_value_t = 256)]
+        max_iter: u32,
+        /// Real part of the view center, to as many digits as the zoom needs [default: -0.75 for Mandelbrot, -0.5 for Burning Ship, otherwise 0]
+        #[arg(long, allow_hyphen_values = true, value_parser = decimal)]
+        re: Option<String>,
+        /// Imaginary part of the view center [default: -0.5 for Burning Ship, otherwise 0]
+        #[arg(long, allow_hyphen_values = true, value_parser = decimal)]
+        im: Option<String>,
+        /// Julia: real part of the constant c
+        #[arg(long, default_value_t = -0.8, allow_hyphen_values = true)]
+        c_re: f64,
+        /// Julia: imaginary part of the constant c
+        #[arg(long, default_value_t = 0.156, allow_hyphen_values = true)]
+        c_im: f64,
+        /// Multibrot: exponent d of z → zᵈ + c
+        #[arg(long, default_value_t = 3.0)]
+        power: f64,
+        /// Newton: solve zⁿ − 1 for this degree n
+        #[arg(long, default_value_t = 3)]
+        degree: usize,
+        /// Newton: polynomial roots as "re,im re,im ...", instead of --degree
+        #[arg(long, allow_hyphen_values = true)]
+        roots: Option<String>,
+        /// Escape-time and Newton fractals: magnification of the full-set view; Mandelbrot zooms past 1e10 use perturbation, up to 1e280
+        #[arg(long, default_value_t = 1.0)]
+        zoom: f64,
+        /// Escape-time and Newton fractals: image width in pixels (a .png output is written as PNG, except for Newton)
+        #[arg(long, default_value_t = 700)]
+        width: usize,
+        /// Escape-time fractals: colormap, magma, viridis, inferno, plasma, turbo or cividis
+        #[arg(long, default_value = "magma", value_parser = colormap::NAMES)]
+        colormap: String,
+        /// Mandelbrot/Julia: shade by the orbit's closest approach to a trap instead of escape time
+        #[arg(long, value_parser = fractals::OrbitTrap::NAMES)]
+        trap: Option<String>,
+    },
+    /// Render numbered frames zooming exponentially into a Mandelbrot or Julia point
+    AnimateZoom {
+        /// Set: mandelbrot or julia
+        #[arg(short = 't', long, default_value = "mandelbrot")]
+        fractal_type: String,
+        /// Real part of the target, to as many digits as the final zoom needs
+        #[arg(long, default_value = "-0.743643887037158704752191506114774", allow_hyphen_values = true, value_parser = decimal)]
+        re: String,
+        /// Imaginary part of the target
+        #[arg(long, default_value = "0.131825904205311970493132056385139", allow_hyphen_values = true, value_parser = decimal)]
+        im: String,
+        /// Julia: real part of the constant c
+        #[arg(long, default_value_t = -0.8, allow_hyphen_values = true)]
+        c_re: f64,
+        /// Julia: imaginary part of the constant c
+        #[arg(long, default_value_t = 0.156, allow_hyphen_values = true)]
+        c_im: f64,
+        /// Magnification of the first frame
+        #[arg(long, default_value_t = 1.0)]
+        start_zoom: f64,
+        /// Magnification of the last frame (Mandelbrot up to 1e280, Julia up to 1e10)
+        #[arg(long, default_value_t = 1e6)]
+        end_zoom: f64,
+        /// Number of frames
+        #[arg(long, default_value_t = 60)]
+        frames: usize,
+        /// Frame width in pixels
+        #[arg(long, default_value_t = 320)]
+        width: usize,
+        /// Escape-time iteration limit
+        #[arg(long, default_value_t = 1000)]
+        max_iter: u32,
+        /// Colormap: magma, viridis, inferno, plasma, turbo or cividis
+        #[arg(long, default_value = "magma", value_parser = colormap::NAMES)]
+        colormap: String,
     },
     /// Generate spiral curves
     Spirals {
@@ -57,25 +218,106 @@ enum Commands {
     },
     /// Generate chaos theory visualizations
     Chaos {
-        /// Type: lorenz, logistic, bifurcation
+        /// Type: lorenz, rossler, aizawa, halvorsen, thomas, chen, butterfly (two Lorenz paths
+        /// 1e-10 apart), logistic, cobweb, bifurcation, henon, ikeda, or three bodies: figure-eight, lagrange, pythagorean
         #[arg(short = 't', long, default_value = "lorenz")]
         chaos_type: String,
-        /// Number of steps
-        #[arg(short = 'n', long, default_value_t = 20000)]
-        steps: usize,
+        /// Number of steps (default 20000 for attractors and maps, 200 iterates
+        /// for logistic, 60 for cobweb, 5000 for butterfly, 800 r values for bifurcation, enough for the three
+        /// bodies to show their dance), or the side of a basin image in pixels
+        /// (default 600)
+        #[arg(short = 'n', long)]
+        steps: Option<usize>,
+        /// Growth rate r for the logistic map and its cobweb
+        #[arg(short = 'r', long, default_value_t = 3.9)]
+        rate: f64,
+        /// Smallest r in the bifurcation diagram
+        #[arg(long, default_value_t = 2.5)]
+        r_min: f64,
+        /// Largest r in the bifurcation diagram
+        #[arg(long, default_value_t = 4.0)]
+        r_max: f64,
+        /// Integrator for the 3D attractors: euler, rk4, rk45 (adaptive)
+        #[arg(long, default_value = "rk4")]
+        integrator: String,
+        /// How to draw attractors: line, points colored by time, time or speed
+        /// (a line colored by either), or recurrence (a recurrence plot, also
+        /// available for logistic); Hénon and Ikeda:
+        /// points, or basin (where starting points end up)
+        #[arg(long, default_value = "line")]
+        render: String,
+        /// Draw a Poincaré section of a 3D attractor through the plane
+        /// AXIS=VALUE (e.g. z=27 for Lorenz, x=0 for Rössler) instead of
+        /// its trajectory
+        #[arg(long)]
+        section: Option<String>,
+        /// How to look at 3D attractors: xy, xz, yz, isometric, or
+        /// orthographic from --azimuth and --elevation (default: each
+        /// attractor's classic plane)
+        #[arg(long, value_parser = chaos::View::NAMES)]
+        view: Option<String>,
+        /// Orthographic camera direction, degrees around the z axis
+        #[arg(long, default_value_t = -60.0, allow_hyphen_values = true)]
+        azimuth: f64,
+        /// Orthographic camera height, degrees above the xy-plane
+        #[arg(long, default_value_t = 20.0, allow_hyphen_values = true)]
+        elevation: f64,
+    },
+    /// Generate strange attractors of 2D maps (de Jong, Clifford, Tinkerbell) as density images
+    Attractors {
+        /// Map: dejong, clifford, tinkerbell
+        #[arg(short = 't', long, default_value = "dejong", value_parser = attractors::StrangeMap::NAMES)]
+        map_type: String,
+        /// Coefficients a,b,c,d instead of the map's classic set
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        coefficients: Option<Vec<f64>>,
+        /// Draw random coefficients (from --seed) until the orbit is chaotic
+        #[arg(long)]
+        explore: bool,
+        /// Number of iterates accumulated
+        #[arg(short = 'n', long, default_value_t = 2_000_000)]
+        points: usize,
+        /// Longer side of the density raster in pixels (a .png output is written as PNG)
+        #[arg(long, default_value_t = 600)]
+        size: usize,
+        /// Colormap: magma, viridis, inferno, plasma, turbo or cividis
+        #[arg(long, default_value = "magma", value_parser = colormap::NAMES)]
+        colormap: String,
     },
     /// Generate L-system patterns
     Lsystem {
-        /// Type: tree, koch, sierpinski, dragon, plant
+        /// Type: tree, koch, sierpinski, dragon, plant, tree3d, coral
         #[arg(short = 't', long, default_value = "plant")]
         system_type: String,
+        /// Read a custom L-system definition instead (axiom, angle, step, rules)
+        #[arg(short = 'f', long)]
+        file: Option<PathBuf>,
         /// Number of iterations (careful: grows exponentially!)
         #[arg(short, long, default_value_t = 5)]
         iterations: usize,
+        /// Animate the plant growing from the root (SMIL)
+        #[arg(long)]
+        animate: bool,
+        /// Growth animation length in seconds
+        #[arg(long, default_value_t = 6.0)]
+        duration: f64,
+        /// View for 3D systems: isometric, perspective
+        #[arg(long, default_value = "isometric")]
+        projection: String,
+        /// Perspective camera direction, degrees around the vertical axis
+        #[arg(long, default_value_t = 30.0)]
+        azimuth: f64,
+        /// Perspective camera height, degrees above the horizon
+        #[arg(long, default_value_t = 15.0)]
+        elevation: f64,
     },
     /// Generate Turing reaction-diffusion patterns
     Turing {
-        /// Preset: spots, stripes, coral, mitosis, worms
+        /// Kinetics: gray-scott, gierer-meinhardt, fitzhugh-nagumo
+        #[arg(short, long, default_value = "gray-scott")]
+        model: String,
+        /// Preset: spots, stripes, coral, mitosis, worms (Gray-Scott);
+        /// spots, stripes (Gierer-Meinhardt); spots, labyrinth (FitzHugh-Nagumo)
         #[arg(short, long, default_value = "spots")]
         preset: String,
         /// Grid size
@@ -84,6 +326,486 @@ enum Commands {
         /// Simulation steps
         #[arg(short = 'n', long, default_value_t = 5000)]
         steps: usize,
+        /// Edges: periodic, mirror, fixed (held at the steady state), no-flux
+        #[arg(short, long, default_value = "periodic")]
+        boundary: String,
+        /// Render: heatmap (a rect per cell), contours (filled outlines)
+        #[arg(short, long, default_value = "heatmap")]
+        render: String,
+        /// Contour level: B for Gray-Scott (default 0.2), else a fraction of the range (default 0.5)
+        #[arg(long)]
+        level: Option<f64>,
+        /// Print the pattern to the terminal instead of writing a file
+        #[arg(long)]
+        preview: bool,
+        /// Also write this many snapshot frames next to the output file (PNG for a .png output)
+        #[arg(long, default_value_t = 0)]
+        frames: usize,
+        /// Gray-Scott: blend feed and kill from --preset on the left to this preset on the right
+        #[arg(long)]
+        to: Option<String>,
+        /// Gray-Scott: diffuse this many times faster along --angle (1 = isotropic)
+        #[arg(long, default_value_t = 1.0)]
+        anisotropy: f64,
+        /// Gray-Scott: direction of fast diffusion in degrees; stripes line up with it
+        #[arg(long, default_value_t = 0.0)]
+        angle: f64,
+        /// Seed chemical B from the dark areas of a PNG/JPEG image
+        #[cfg(feature = "image")]
+        #[arg(long)]
+        seed_image: Option<PathBuf>,
+    },
+    /// Grow a Reiter hexagonal snowflake
+    Snowflake {
+        /// Preset: stellar, plate, fernlike
+        #[arg(short, long, default_value = "stellar")]
+        preset: String,
+        /// Diffusion constant α (overrides preset)
+        #[arg(long)]
+        alpha: Option<f64>,
+        /// Background vapour level β (overrides preset)
+        #[arg(long)]
+        beta: Option<f64>,
+        /// Vapour addition γ (overrides preset)
+        #[arg(long)]
+        gamma: Option<f64>,
+        /// Hex grid radius in cells
+        #[arg(short = 's', long, default_value_t = 100)]
+        size: usize,
+        /// Maximum growth steps
+        #[arg(short = 'n', long, default_value_t = 5000)]
+        steps: usize,
+        /// Render the vector outline instead of hex cells
+        #[arg(long)]
+        outline: bool,
+        /// Also write this many growth-stage frames next to the output file
+        #[arg(long, default_value_t = 0)]
+        frames: usize,
+    },
+    /// Generate rose curves r = a·cos(kθ)
+    Rose {
+        /// Frequency k as "n/d", an integer, or a decimal
+        #[arg(short, long, default_value = "5")]
+        k: String,
+        /// Draw each petal as a filled loop
+        #[arg(long)]
+        filled: bool,
+    },
+    /// Generate Gielis superformula shapes
+    Superformula {
+        /// Preset: starfish, flower, diatom, leaf
+        #[arg(short, long, default_value = "starfish")]
+        preset: String,
+        /// Morph toward this preset, writing frames next to the output file
+        #[arg(long)]
+        morph_to: Option<String>,
+        /// Number of morph frames
+        #[arg(long, default_value_t = 30)]
+        frames: usize,
+    },
+    /// Generate Lissajous figures and damped harmonograph traces
+    Harmonograph {
+        /// Type: lissajous, two (pendulums), three (with rotary pendulum)
+        #[arg(short = 't', long, default_value = "two")]
+        harmonograph_type: String,
+        /// X frequency (rounded to an integer for Lissajous)
+        #[arg(long, default_value_t = 3.0)]
+        fx: f64,
+        /// Y frequency (rounded to an integer for Lissajous)
+        #[arg(long, default_value_t = 2.01)]
+        fy: f64,
+        /// Rotary pendulum frequency (three-pendulum only)
+        #[arg(long, default_value_t = 1.0)]
+        fr: f64,
+        /// Phase offset in degrees
+        #[arg(long, default_value_t = 90.0)]
+        phase: f64,
+        /// Exponential damping rate
+        #[arg(short, long, default_value_t = 0.004)]
+        damping: f64,
+        /// Drawing duration in time units
+        #[arg(long, default_value_t = 600.0)]
+        duration: f64,
+    },
+    /// Draw a closed outline with Fourier epicycles
+    Epicycles {
+        /// Point list or simple SVG path file (defaults to a leaf outline)
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+        /// Number of Fourier terms (circles) to keep
+        #[arg(short, long, default_value_t = 40)]
+        terms: usize,
+        /// Also write this many animation frames next to the output file
+        #[arg(long, default_value_t = 0)]
+        frames: usize,
+        /// Write a self-playing animated SVG instead of a still frame
+        #[arg(short, long)]
+        animate: bool,
+        /// Seconds per drawing cycle of the animated SVG
+        #[arg(long, default_value_t = 12.0)]
+        duration: f64,
+    },
+    /// Generate spirograph (hypotrochoid / epitrochoid) patterns
+    Spirograph {
+        /// Type: hypo (wheel inside ring), epi (wheel outside ring)
+        #[arg(short = 't', long, default_value = "hypo")]
+        spirograph_type: String,
+        /// Fixed ring radius R
+        #[arg(short = 'R', long, default_value_t = 96)]
+        ring: u32,
+        /// Rolling wheel radius r
+        #[arg(short = 'r', long, default_value_t = 36)]
+        wheel: u32,
+        /// Pen offsets from the wheel center, one layer each (comma-separated)
+        #[arg(short, long, value_delimiter = ',', default_value = "12,20,28")]
+        pens: Vec<f64>,
+    },
+    /// Simulate boids flocking (starling murmurations)
+    Flocking {
+        /// Number of boids
+        #[arg(short = 'n', long, default_value_t = 300)]
+        count: usize,
+        /// Simulation steps
+        #[arg(short, long, default_value_t = 400)]
+        steps: usize,
+        /// Render: snapshot, trails, density
+        #[arg(short, long, default_value = "trails")]
+        render: String,
+        /// Perception radius for alignment and cohesion
+        #[arg(short, long, default_value_t = 40.0)]
+        perception: f64,
+        /// Add a predator chasing the flock
+        #[arg(long)]
+        predator: bool,
+        /// Also write this many snapshot frames next to the output file
+        #[arg(long, default_value_t = 0)]
+        frames: usize,
+    },
+    /// Simulate a Physarum slime-mold transport network
+    Physarum {
+        /// Number of agents
+        #[arg(short = 'n', long, default_value_t = 40000)]
+        agents: usize,
+        /// Grid size
+        #[arg(short = 's', long, default_value_t = 200)]
+        size: usize,
+        /// Simulation steps
+        #[arg(long, default_value_t = 500)]
+        steps: usize,
+        /// Sensor angle in degrees
+        #[arg(long, default_value_t = 45.0)]
+        sensor_angle: f64,
+        /// Sensor distance in cells
+        #[arg(long, default_value_t = 9.0)]
+        sensor_distance: f64,
+    },
+    /// Grow a diffusion-limited aggregation cluster
+    Dla {
+        /// Preset: coral, frost, mineral
+        #[arg(short, long, default_value = "coral")]
+        preset: String,
+        /// Particles to attach (overrides preset)
+        #[arg(short = 'n', long)]
+        particles: Option<usize>,
+        /// Sticking probability per contact (overrides preset)
+        #[arg(long)]
+        stickiness: Option<f64>,
+        /// Seed shape: point, line, circle (overrides preset)
+        #[arg(long)]
+        seed_shape: Option<String>,
+        /// Grid size
+        #[arg(short = 's', long, default_value_t = 400)]
+        size: usize,
+    },
+    /// Grow leaf veins or a tree crown by space colonization
+    Colonization {
+        /// Preset: leaf, tree
+        #[arg(short, long, default_value = "leaf")]
+        preset: String,
+        /// Attraction points to scatter (overrides preset)
+        #[arg(short = 'n', long)]
+        attractors: Option<usize>,
+        /// Venation: open, closed (overrides preset)
+        #[arg(long)]
+        venation: Option<String>,
+        /// Stroke width of the thickest vein
+        #[arg(short, long, default_value_t = 12.0)]
+        width: f64,
+    },
+    /// Topple an Abelian sandpile into its fractal mandala
+    Sandpile {
+        /// Grains dropped on the center cell
+        #[arg(short = 'n', long, default_value_t = 50000)]
+        grains: u32,
+        /// Grid size (defaults to just large enough for the grains)
+        #[arg(short = 's', long)]
+        size: Option<usize>,
+        /// Render the sandpile-group identity of the grid instead
+        #[arg(long)]
+        identity: bool,
+        /// Also write this many incremental snapshots next to the output file
+        #[arg(long, default_value_t = 0)]
+        frames: usize,
+    },
+    /// Draw a 1D cellular automaton space-time diagram
+    #[command(visible_alias = "automata")]
+    Automaton {
+        /// Rule number (0–255, or a totalistic code with --states)
+        #[arg(short, long, default_value_t = 30)]
+        rule: u64,
+        /// Use a k-state totalistic rule instead of an elementary one
+        #[arg(short = 'k', long)]
+        states: Option<u8>,
+        /// Start from random cells instead of a single live cell
+        #[arg(long)]
+        random: bool,
+        /// Row width in cells
+        #[arg(short, long, default_value_t = 401)]
+        width: usize,
+        /// Number of generations
+        #[arg(short = 'g', long, visible_alias = "steps", default_value_t = 200)]
+        generations: usize,
+        /// Palette: shell, mono
+        #[arg(short, long, default_value = "shell")]
+        palette: String,
+    },
+    /// Run a Lenia continuous cellular automaton
+    Lenia {
+        /// Preset: orbium, hydrogeminium
+        #[arg(short, long, default_value = "orbium")]
+        preset: String,
+        /// World size (rounded up to a power of two)
+        #[arg(short = 's', long, default_value_t = 128)]
+        size: usize,
+        /// Simulation steps
+        #[arg(short = 'n', long, default_value_t = 200)]
+        steps: usize,
+        /// Also write this many animation frames next to the output file
+        #[arg(long, default_value_t = 0)]
+        frames: usize,
+    },
+    /// Synchronize a field of fireflies (Kuramoto oscillators)
+    Fireflies {
+        /// Number of fireflies
+        #[arg(short = 'n', long, default_value_t = 300)]
+        count: usize,
+        /// Coupling strength K
+        #[arg(short = 'k', long, default_value_t = 1.5)]
+        coupling: f64,
+        /// Half-width of the natural-frequency spread
+        #[arg(long, default_value_t = 0.5)]
+        spread: f64,
+        /// Visibility radius (fraction of the field; ≥ 1.5 couples everyone)
+        #[arg(long, default_value_t = 0.3)]
+        radius: f64,
+        /// Simulation steps
+        #[arg(short, long, default_value_t = 1500)]
+        steps: usize,
+        /// Render: field, timeline, order
+        #[arg(short, long, default_value = "field")]
+        render: String,
+        /// Also write this many field frames next to the output file
+        #[arg(long, default_value_t = 0)]
+        frames: usize,
+    },
+    /// Plot predator–prey population cycles
+    PredatorPrey {
+        /// Model: lotka-volterra, rosenzweig
+        #[arg(short = 't', long = "type", default_value = "lotka-volterra")]
+        model: String,
+        /// Prey carrying capacity K (Rosenzweig–MacArthur)
+        #[arg(short = 'k', long, default_value_t = 4.0)]
+        capacity: f64,
+        /// Number of nested orbits in the phase portrait
+        #[arg(short = 'n', long, default_value_t = 6)]
+        orbits: usize,
+        /// Simulated time
+        #[arg(short, long, default_value_t = 60.0)]
+        duration: f64,
+        /// Render: phase, series
+        #[arg(short, long, default_value = "phase")]
+        render: String,
+    },
+    /// Draw interference of circular waves from point sources
+    Waves {
+        /// Preset: two, ring, rain, moire
+        #[arg(short, long, default_value = "two")]
+        preset: String,
+        /// Wavelength for every source, as a fraction of the image (overrides preset)
+        #[arg(short, long)]
+        wavelength: Option<f64>,
+        /// Decay rate with distance for every source (overrides preset)
+        #[arg(short, long)]
+        decay: Option<f64>,
+        /// Samples per side
+        #[arg(short = 's', long, default_value_t = 200)]
+        resolution: usize,
+        /// Render: colormap, contours
+        #[arg(short, long, default_value = "colormap")]
+        render: String,
+    },
+    /// Draw Chladni nodal-line figures on a vibrating plate
+    Chladni {
+        /// Plate: square, circle
+        #[arg(short, long, default_value = "square")]
+        plate: String,
+        /// Mode number m (radial zero on the circular plate)
+        #[arg(short, long, default_value_t = 3)]
+        m: u32,
+        /// Mode number n (nodal diameters on the circular plate)
+        #[arg(short, long, default_value_t = 5)]
+        n: u32,
+        /// Extra modes to superpose, as m,n or m,n,weight (repeatable)
+        #[arg(long)]
+        mix: Vec<String>,
+        /// Samples per side
+        #[arg(short = 's', long, default_value_t = 300)]
+        resolution: usize,
+    },
+    /// Grow wind ripples and dunes with Werner's slab model
+    Dunes {
+        /// Preset: ripples, transverse, barchans
+        #[arg(short, long, default_value = "transverse")]
+        preset: String,
+        /// Field width in cells (the wind blows along it)
+        #[arg(short, long, default_value_t = 160)]
+        width: usize,
+        /// Field height in cells
+        #[arg(short = 'H', long, default_value_t = 100)]
+        height: usize,
+        /// Sweeps of one event per cell
+        #[arg(short, long, default_value_t = 400)]
+        cycles: usize,
+        /// Also write this many time-lapse frames next to the output file
+        #[arg(long, default_value_t = 0)]
+        frames: usize,
+    },
+    /// Render fractal terrain as a shaded relief map
+    Terrain {
+        /// Preset: mountains, archipelago, hills
+        #[arg(short, long, default_value = "mountains")]
+        preset: String,
+        /// Noise octaves (overrides preset)
+        #[arg(long)]
+        octaves: Option<u32>,
+        /// Frequency multiplier between octaves (overrides preset)
+        #[arg(long)]
+        lacunarity: Option<f64>,
+        /// Amplitude multiplier between octaves (overrides preset)
+        #[arg(long)]
+        persistence: Option<f64>,
+        /// Water line as a fraction of the height range (overrides preset)
+        #[arg(long)]
+        sea_level: Option<f64>,
+        /// Number of contour lines above the sea
+        #[arg(short, long, default_value_t = 8)]
+        contours: usize,
+        /// Map size in cells
+        #[arg(short = 's', long, default_value_t = 160)]
+        size: usize,
+    },
+    /// Spin a spider orb web with dew drops
+    Orbweb {
+        /// Preset: garden, lopsided, tattered
+        #[arg(short, long, default_value = "garden")]
+        preset: String,
+        /// Number of radial threads (overrides preset)
+        #[arg(short, long)]
+        radials: Option<usize>,
+        /// Turns of the capture spiral (overrides preset)
+        #[arg(short, long)]
+        turns: Option<f64>,
+        /// Hub offset above center as a fraction of the radius (overrides preset)
+        #[arg(long)]
+        asymmetry: Option<f64>,
+        /// Thread droop between radials (overrides preset)
+        #[arg(long)]
+        sag: Option<f64>,
+        /// Fraction of capture threads torn away (overrides preset)
+        #[arg(long)]
+        damage: Option<f64>,
+        /// Dew drops per 100 units of capture thread
+        #[arg(long, default_value_t = 0.0)]
+        dew: f64,
+    },
+    /// Generate natural tilings: honeycombs and Voronoi patterns
+    Tessellations {
+        /// Tiling: honeycomb, voronoi
+        #[arg(short = 't', long, default_value = "honeycomb")]
+        tiling: String,
+        /// Preset: apis, wild, wasp (honeycomb); giraffe, dragonfly, mud (voronoi)
+        #[arg(short, long)]
+        preset: Option<String>,
+        /// Voronoi: number of seeds (overrides preset)
+        #[arg(short = 'n', long)]
+        count: Option<usize>,
+        /// Voronoi: seeding, random, phyllotaxis or poisson (overrides preset)
+        #[arg(long)]
+        seeding: Option<String>,
+        /// Cell size, center to corner (overrides preset)
+        #[arg(short, long)]
+        cell: Option<f64>,
+        /// Wall-junction displacement as a fraction of the cell size (overrides preset)
+        #[arg(long)]
+        jitter: Option<f64>,
+        /// Wall thickness, or the gap between Voronoi cells (overrides preset)
+        #[arg(long)]
+        wall: Option<f64>,
+    },
+    /// Draw symmetric forms: snowflakes, starfish, flowers, leaves
+    Symmetry {
+        /// Preset: snowflake, starfish, flower, leaf
+        #[arg(short, long, default_value = "snowflake")]
+        preset: String,
+        /// Rotational order (overrides preset)
+        #[arg(short = 'n', long)]
+        order: Option<usize>,
+        /// Drop the mirror images, leaving cyclic symmetry
+        #[arg(long)]
+        no_mirror: bool,
+    },
+    /// Turn a pattern into music, written as a MIDI file (use -o out.mid)
+    Sonify {
+        /// Source: logistic, phyllotaxis, lsystem
+        #[arg(short, long, default_value = "logistic")]
+        source: String,
+        /// Number of notes (logistic iterates or phyllotaxis elements)
+        #[arg(short = 'n', long, default_value_t = 64)]
+        count: usize,
+        /// Logistic growth rate r
+        #[arg(short, long, default_value_t = 3.9)]
+        rate: f64,
+        /// Divergence angle in degrees for the phyllotaxis rhythm
+        #[arg(short, long, default_value_t = 137.508)]
+        angle: f64,
+        /// L-system: tree, koch, sierpinski, dragon, plant
+        #[arg(long, default_value = "plant")]
+        system: String,
+        /// L-system iterations
+        #[arg(short, long, default_value_t = 3)]
+        iterations: usize,
+        /// Scale: major, minor, pentatonic, chromatic
+        #[arg(long, default_value = "pentatonic")]
+        scale: String,
+        /// Tempo in beats per minute
+        #[arg(long, default_value_t = 120.0)]
+        bpm: f64,
+    },
+    /// Generate a pattern and print statistics about it instead of drawing it
+    /// (text, or --format json / csv)
+    Analyze {
+        /// Pattern: fern, sierpinski, koch, carpet, mandelbrot, lsystem, logistic, phyllotaxis, turing, dla, or a chaos attractor
+        #[arg(short = 't', long, default_value = "fern", value_parser = ANALYZE_PATTERNS)]
+        pattern_type: String,
+        /// Size: points, iterations, depth, pixels across, elements, steps or particles, by pattern
+        #[arg(short = 'n', long)]
+        count: Option<usize>,
+        /// L-system: tree, koch, sierpinski, dragon, plant
+        #[arg(long, default_value = "plant")]
+        system: String,
+        /// Logistic growth rate r
+        #[arg(short, long, default_value_t = 3.9)]
+        rate: f64,
     },
     /// Generate the interactive web gallery
     Web {
@@ -93,46 +815,484 @@ enum Commands {
     },
 }
 
-fn main() {
-    let cli = Cli::parse();
+fn main() -> ExitCode {
+    let args = match with_config(std::env::args_os().collect()) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let cli = cli_command()
+        .try_get_matches_from(args)
+        .and_then(|matches| Cli::from_arg_matches(&matches))
+        .unwrap_or_else(|e| e.exit());
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The command line parser, with every flag allowed to repeat so the last
+/// occurrence — typed rather than read from a scene file — wins.
+fn cli_command() -> clap::Command {
+    let mut command = Cli::command().args_override_self(true);
+    let names: Vec<String> = command.get_subcommands().map(|s| s.get_name().to_string()).collect();
+    for name in names {
+        command = command.mut_subcommand(name, |s| s.args_override_self(true));
+    }
+    command
+}
+
+/// Splice a `--config` scene file into the arguments.
+///
+/// Top-level keys are global flags (`output = "fern.svg"`), `command` names
+/// the subcommand to run when the command line gives none, and the table
+/// named after the subcommand holds its flags (`[turing]` with
+/// `steps = 8000`). Keys are long flag names, with `_` or `-`; `true` sets a
+/// switch and arrays become comma-separated lists. The file's flags go in
+/// front of the typed ones, so anything typed takes precedence.
+fn with_config(args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let Some(path) = config_path(&args) else {
+        return Ok(args);
+    };
+    let text = fs::read_to_string(&path)?;
+    let scene = parse_scene(&text, &path)?;
+    let command = Cli::command();
+    let position = subcommand_position(&command, &args);
+    let name = match position {
+        Some(i) => args[i].to_string_lossy().into_owned(),
+        None => match scene.get("command") {
+            Some(serde_json::Value::String(name)) => name.clone(),
+            _ => return Ok(args),
+        },
+    };
+    let Some(sub) = command.find_subcommand(&name) else {
+        return Err(MathaturaError::Parse { line: 0, message: format!("unknown command '{name}' in {}", path.display()) });
+    };
+    let table = std::iter::once(sub.get_name())
+        .chain(sub.get_all_aliases())
+        .find_map(|key| scene.get(key).and_then(|v| v.as_object()));
+
+    let split = position.unwrap_or(args.len());
+    let mut spliced = vec![args[0].clone()];
+    spliced.extend(scene_flags(&scene, None)?);
+    spliced.extend(args[1..split].iter().cloned());
+    spliced.push(OsString::from(sub.get_name()));
+    if let Some(table) = table {
+        spliced.extend(scene_flags(table, Some(sub.get_name()))?);
+    }
+    spliced.extend(args.iter().skip(split + 1).cloned());
+    Ok(spliced)
+}
+
+/// The value of `--config` anywhere on the command line.
+fn config_path(args: &[OsString]) -> Option<PathBuf> {
+    let mut tokens = args.iter().skip(1).map(|a| a.to_string_lossy());
+    while let Some(token) = tokens.next() {
+        if token == "--" {
+            break;
+        } else if token == "--config" {
+            return tokens.next().map(|p| PathBuf::from(p.as_ref()));
+        } else if let Some(path) = token.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Parse a scene file as JSON or TOML by its extension.
+fn parse_scene(text: &str, path: &Path) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let line_of = |offset: usize| text[..offset.min(text.len())].lines().count().max(1);
+    if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json")) {
+        serde_json::from_str(text).map_err(|e| MathaturaError::Parse { line: e.line(), message: e.to_string() })
+    } else {
+        let table: toml::Table = text.parse().map_err(|e: toml::de::Error| MathaturaError::Parse {
+            line: e.span().map_or(0, |span| line_of(span.start)),
+            message: e.message().to_string(),
+        })?;
+        serde_json::to_value(table)
+            .ok()
+            .and_then(|v| v.as_object().cloned())
+            .ok_or_else(|| MathaturaError::Parse { line: 0, message: "scene is not a table".to_string() })
+    }
+}
+
+/// Index of the subcommand name, stepping over global flags and their values.
+fn subcommand_position(command: &clap::Command, args: &[OsString]) -> Option<usize> {
+    let takes_value = |pred: &dyn Fn(&clap::Arg) -> bool| {
+        command.get_arguments().find(|a| pred(a)).is_some_and(|a| a.get_action().takes_values())
+    };
+    let mut i = 1;
+    while i < args.len() {
+        let token = args[i].to_string_lossy();
+        if token == "--" {
+            return None;
+        } else if let Some(long) = token.strip_prefix("--") {
+            if !long.contains('=') && takes_value(&|a| a.get_long() == Some(long)) {
+                i += 1;
+            }
+        } else if let Some(short) = token.strip_prefix('-').filter(|s| s.chars().count() == 1) {
+            if takes_value(&|a| a.get_short() == short.chars().next()) {
+                i += 1;
+            }
+        } else if command.find_subcommand(token.as_ref()).is_some() {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Flags for one table of a scene file. At the top level (`section` None),
+/// `command` and the per-command tables are skipped.
+fn scene_flags(table: &serde_json::Map<String, serde_json::Value>, section: Option<&str>) -> Result<Vec<OsString>> {
+    use serde_json::Value;
+    let scalar = |v: &Value| match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    };
+    let mut flags = Vec::new();
+    for (key, value) in table {
+        if section.is_none() && (key == "command" || value.is_object()) {
+            continue;
+        }
+        let flag = format!("--{}", key.replace('_', "-"));
+        let joined = match value {
+            Value::Bool(true) => Some(flag),
+            Value::Bool(false) | Value::Null => None,
+            Value::Array(items) => {
+                let items: Option<Vec<String>> = items.iter().map(scalar).collect();
+                items.map(|items| format!("{flag}={}", items.join(",")))
+            }
+            Value::Object(_) => None,
+            other => scalar(other).map(|v| format!("{flag}={v}")),
+        };
+        match (joined, value) {
+            (Some(token), _) => flags.push(OsString::from(token)),
+            (None, Value::Object(_) | Value::Array(_)) => {
+                return Err(MathaturaError::Parse {
+                    line: 0,
+                    message: format!("[{}] {key}: nested tables are not flags", section.unwrap_or("")),
+                })
+            }
+            (None, _) => {}
+        }
+    }
+    Ok(flags)
+}
 
+/// Generate the requested figure and write it, and any frames, to disk.
+fn run(cli: &Cli) -> Result<()> {
+    let data_export = matches!(
+        cli.command,
+        Commands::Phyllotaxis { .. } | Commands::Fractals { .. } | Commands::Spirals { .. } | Commands::Chaos { .. } | Commands::Lsystem { .. } | Commands::Turing { .. } | Commands::Analyze { .. }
+    );
+    if cli.format != "svg" && !data_export {
+        return Err(no_data_export(cli));
+    }
+    let mut theme = match Theme::from_name(&cli.theme) {
+        Some(theme) => theme,
+        None => {
+            let text = fs::read_to_string(&cli.theme).map_err(|e| {
+                std::io::Error::new(e.kind(), format!("{}: not dark, light, paper, neon or a readable theme file ({e})", cli.theme))
+            })?;
+            Theme::from_toml(&text)?
+        }
+    };
+    // --colors takes the place of the theme's own palette
+    if let Some(gradient) = gradient(cli)? {
+        theme.palette = Some(gradient);
+    }
+    let dots = if cli.compact { Dots::Batched } else { Dots::Circles };
     let svg = match cli.command {
-        Commands::Phyllotaxis { count, angle, scale, ref pattern } => {
+        Commands::Phyllotaxis { count, angle, scale, ref pattern, rise, circumference, height, ref render, ref floret } => {
             let params = phyllotaxis::Params { count, divergence_angle: angle, scale };
+            params.validate()?;
+            let shape = |p: phyllotaxis::Pattern| match floret.as_str() {
+                "natural" => p.floret(),
+                name => phyllotaxis::FloretShape::from_name(name).unwrap_or(phyllotaxis::FloretShape::Circle),
+            };
+            let mesh_format = cli.output.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+            let florets = |elements: &[phyllotaxis::Element], p: phyllotaxis::Pattern| phyllotaxis::to_svg_with_theme(elements, p, shape(p), &theme);
+            if let Some(format @ ("obj" | "stl")) = mesh_format.as_deref() {
+                check_range("height", height, 0.0..=10.0, "0 to 10 rim radii")?;
+                let surface = match pattern.as_str() {
+                    "pinecone" => phyllotaxis::Surface::Cone { height },
+                    _ => phyllotaxis::Surface::Paraboloid { height },
+                };
+                let mesh = phyllotaxis::to_mesh(&phyllotaxis::vogel_spiral(&params), surface);
+                match format {
+                    "stl" => fs::write(&cli.output, mesh.to_stl())?,
+                    _ => fs::write(&cli.output, mesh.to_obj())?,
+                }
+                println!("✨ Generated {} ({} triangles)", cli.output.display(), mesh.triangles.len());
+                return Ok(());
+            }
             match pattern.as_str() {
                 "rosette" => {
                     let elements: Vec<_> = phyllotaxis::rosette(&params).into_iter().map(|(e, _)| e).collect();
-                    phyllotaxis::to_svg(&elements, phyllotaxis::Pattern::Rosette)
+                    if write_table(cli, &elements)? {
+                        return Ok(());
+                    }
+                    florets(&elements, phyllotaxis::Pattern::Rosette)
                 }
                 "pinecone" => {
                     let elements = phyllotaxis::pinecone(&params);
-                    phyllotaxis::to_svg(&elements, phyllotaxis::Pattern::Pinecone)
+                    if write_table(cli, &elements)? {
+                        return Ok(());
+                    }
+                    florets(&elements, phyllotaxis::Pattern::Pinecone)
+                }
+                "sphere" => {
+                    let points = phyllotaxis::fibonacci_sphere(count);
+                    if write_table(cli, &points)? {
+                        return Ok(());
+                    }
+                    println!("Fibonacci sphere: {} spirals around the equator", phyllotaxis::sphere_parastichy(count));
+                    phyllotaxis::sphere_to_svg_with_theme(&points, mathatura::render::Projection::Orthographic { azimuth: 30.0, elevation: 20.0 }, &theme)
+                }
+                "cylinder" | "stem" => {
+                    let params = phyllotaxis::CylinderParams { count, divergence_angle: angle, rise, circumference };
+                    params.validate()?;
+                    let elements = phyllotaxis::cylinder(&params);
+                    if write_table(cli, &elements)? {
+                        return Ok(());
+                    }
+                    if let Some((a, b)) = phyllotaxis::cylinder_parastichies(&elements, circumference) {
+                        println!("Parastichies on the stem: {} and {}", a, b);
+                    }
+                    if pattern == "stem" {
+                        phyllotaxis::cylinder_to_svg_with_theme(&elements, &params, &theme)
+                    } else {
+                        phyllotaxis::cylinder_3d_svg_with_theme(&elements, &params, &theme)
+                    }
                 }
                 _ => {
                     let elements = phyllotaxis::vogel_spiral(&params);
-                    phyllotaxis::to_svg(&elements, phyllotaxis::Pattern::Sunflower)
+                    if write_table(cli, &elements)? {
+                        return Ok(());
+                    }
+                    if let Some((cw, ccw)) = phyllotaxis::count_parastichies(&elements).last() {
+                        println!("Parastichies at the rim: {} clockwise, {} counter-clockwise", cw, ccw);
+                    }
+                    match render.as_str() {
+                        "cells" => phyllotaxis::voronoi_to_svg_with_theme(&elements, &theme),
+                        _ => florets(&elements, phyllotaxis::Pattern::Sunflower),
+                    }
                 }
             }
         }
-        Commands::Fractals { ref fractal_type, iterations } => {
+        Commands::Fractals { ref fractal_type, ref preset, ref file, iterations, ref variant, angle, max_iter, ref re, ref im, c_re, c_im, power, degree, ref roots, zoom, width, ref colormap, ref trap } => {
+            // The parser has already checked these are decimals
+            let center = |text: &Option<String>, default: f64| text.as_deref().and_then(|t| t.parse().ok()).unwrap_or(default);
             match fractal_type.as_str() {
+                kind @ ("mandelbrot" | "julia" | "burningship" | "multibrot") => {
+                    check_count("width", width, MAX_GRID_SIZE)?;
+                    check_range("max_iter", max_iter as f64, 1.0..=1e6, "1 to 1000000 iterations")?;
+                    if kind == "mandelbrot" && trap.is_none() {
+                        check_range("zoom", zoom, f64::MIN_POSITIVE..=fractals::MAX_DEEP_ZOOM, "a positive zoom below 1e280")?;
+                    } else {
+                        check_range("zoom", zoom, f64::MIN_POSITIVE..=1e13, "a positive zoom below 1e13")?;
+                    }
+                    let height = width * 5 / 7;
+                    let aspect = height as f64 / width as f64;
+                    let view = |re_default: f64, im_default: f64, span: f64| {
+                        fractals::Region::centered(center(re, re_default), center(im, im_default), span / zoom, aspect)
+                    };
+                    if kind == "julia" {
+                        check_range("c_re", c_re, -2.0..=2.0, "-2 to 2")?;
+                        check_range("c_im", c_im, -2.0..=2.0, "-2 to 2")?;
+                    }
+                    let lut = palette(&theme, colormap);
+                    let color = |t| lut.rgb(t);
+                    let css = |t| lut.css(t);
+                    if let Some((name, trap)) = trap.as_deref().and_then(|name| Some((name, fractals::OrbitTrap::from_name(name)?))) {
+                        let grid = match kind {
+                            "julia" => fractals::julia_trap_grid(&view(0.0, 0.0, 3.5), width, height, (c_re, c_im), max_iter, &trap),
+                            "mandelbrot" => fractals::mandelbrot_trap_grid(&view(-0.75, 0.0, 3.5), width, height, max_iter, &trap),
+                            _ => return Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "orbit traps apply to mandelbrot and julia").into()),
+                        };
+                        println!("{kind} with a {} trap: {}×{} pixels", name, width, height);
+                        if write_png(&cli.output, width, height, || grid.to_rgb(color))? {
+                            println!("✨ Generated {}", cli.output.display());
+                            return Ok(());
+                        }
+                        fractals::trap_to_svg_with_theme(&grid, 1.0, css, &theme)
+                    } else {
+                        let grid = match kind {
+                            "julia" => {
+                                let grid = fractals::julia_grid(&view(0.0, 0.0, 3.5), width, height, (c_re, c_im), max_iter);
+                                println!("Julia set of c = {c_re} {:+}i: {}×{} pixels, {:.1}% filled", c_im, width, height, 100.0 * grid.inside_fraction());
+                                grid
+                            }
+                            "burningship" => {
+                                let grid = fractals::burning_ship_grid(&view(-0.5, -0.5, 4.2), width, height, max_iter);
+                                println!("Burning Ship: {}×{} pixels, {:.1}% in the set", width, height, 100.0 * grid.inside_fraction());
+                                grid
+                            }
+                            "multibrot" => {
+                                check_range("power", power, 1.1..=64.0, "an exponent from 1.1 to 64")?;
+                                let grid = fractals::multibrot_grid(&view(0.0, 0.0, 3.5), width, height, power, max_iter);
+                                println!("Multibrot z^{power} + c: {}×{} pixels, {:.1}% in the set", width, height, 100.0 * grid.inside_fraction());
+                                grid
+                            }
+                            _ if zoom > fractals::DEEP_ZOOM => {
+                                let span = 3.5 / zoom;
+                                let bits = fractals::deep_zoom_bits(span, width);
+                                let exact = |text: &Option<String>, default: f64| match text {
+                                    Some(text) => Fixed::parse(text, bits),
+                                    None => Ok(Fixed::from_f64(default, bits)),
+                                };
+                                let grid = fractals::mandelbrot_deep_grid(&exact(re, -0.75)?, &exact(im, 0.0)?, span, width, height, max_iter);
+                                println!("Mandelbrot at zoom {zoom:e} ({bits}-bit reference orbit): {}×{} pixels, {:.1}% in the set", width, height, 100.0 * grid.inside_fraction());
+                                grid
+                            }
+                            _ => {
+                                let grid = fractals::mandelbrot_grid(&view(-0.75, 0.0, 3.5), width, height, max_iter);
+                                println!("Mandelbrot: {}×{} pixels, {:.1}% in the set", width, height, 100.0 * grid.inside_fraction());
+                                grid
+                            }
+                        };
+                        if write_png(&cli.output, width, height, || grid.to_rgb(color))? {
+                            println!("✨ Generated {}", cli.output.display());
+                            return Ok(());
+                        }
+                        fractals::escape_to_svg_with_theme(&grid, 1.0, css, &theme)
+                    }
+                }
+                "newton" => {
+                    check_count("width", width, MAX_GRID_SIZE)?;
+                    check_range("zoom", zoom, f64::MIN_POSITIVE..=1e13, "a positive zoom below 1e13")?;
+                    let roots = match roots {
+                        Some(text) => epicycles::parse_points(text).into_iter().map(|p| (p.x, p.y)).collect(),
+                        None => fractals::roots_of_unity(degree),
+                    };
+                    if roots.is_empty() {
+                        return Err(MathaturaError::Empty { name: "roots" });
+                    }
+                    check_count("roots", roots.len(), fractals::MAX_NEWTON_ROOTS)?;
+                    let height = width * 5 / 7;
+                    let region = fractals::Region::centered(center(re, 0.0), center(im, 0.0), 3.5 / zoom, height as f64 / width as f64);
+                    let grid = fractals::newton_fractal(&roots, &region, width, height);
+                    let sizes: Vec<String> = grid.basin_sizes().iter().map(|n| format!("{:.1}%", 100.0 * *n as f64 / grid.basins.len().max(1) as f64)).collect();
+                    println!("Newton: {} roots, {:.1}% converged, basins {}", roots.len(), 100.0 * grid.converged_fraction(), sizes.join(" "));
+                    fractals::newton_to_svg_with_theme(&grid, 1.0, &theme)
+                }
                 "koch" => {
-                    let points = fractals::koch_snowflake(iterations.min(6));
-                    fractals::koch_to_svg(&points)
+                    let variant = fractals::KochVariant::from_name(variant, angle).unwrap_or(fractals::KochVariant::Snowflake);
+                    let points = fractals::checked_koch_variant(&variant, iterations)?;
+                    if write_table(cli, &points)? {
+                        return Ok(());
+                    }
+                    fractals::koch_to_svg_with_theme(&points, &theme)
+                }
+                "carpet" => {
+                    let squares = fractals::sierpinski_carpet(iterations)?;
+                    if write_table(cli, &squares)? {
+                        return Ok(());
+                    }
+                    println!("Sierpinski carpet depth {}: {} squares", iterations, squares.len());
+                    fractals::carpet_to_svg_with_theme(iterations, &theme)
+                }
+                "menger" => {
+                    let cubes = fractals::menger_sponge(iterations)?;
+                    let side = 3f64.powi(iterations as i32);
+                    let corners: Vec<chaos::Point3D> = cubes.iter().map(|c| chaos::Point3D { x: c[0] as f64 / side, y: c[1] as f64 / side, z: c[2] as f64 / side }).collect();
+                    if write_table(cli, &corners)? {
+                        return Ok(());
+                    }
+                    let mesh_format = cli.output.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+                    if let Some(format @ ("obj" | "stl")) = mesh_format.as_deref() {
+                        let mesh = fractals::menger_mesh(iterations, 100.0)?;
+                        match format {
+                            "stl" => fs::write(&cli.output, mesh.to_stl())?,
+                            _ => fs::write(&cli.output, mesh.to_obj())?,
+                        }
+                        println!("✨ Generated {} ({} cubes, {} triangles)", cli.output.display(), cubes.len(), mesh.triangles.len());
+                        return Ok(());
+                    }
+                    println!("Menger sponge depth {}: {} cubes", iterations, cubes.len());
+                    fractals::menger_to_svg_with_theme(iterations, &mathatura::render::Projection::Isometric, &theme)?
+                }
+                "fibword" => {
+                    let points = fractals::checked_fibonacci_word_fractal(iterations)?;
+                    if write_table(cli, &points)? {
+                        return Ok(());
+                    }
+                    println!("Fibonacci word {}: {} segments", iterations, points.len() - 1);
+                    fractals::fibonacci_word_to_svg_with_theme(&points, &theme)
+                }
+                "ifs" => {
+                    let preset = ifs::Preset::from_name(preset).unwrap_or(ifs::Preset::Fern);
+                    let system = match file {
+                        Some(path) if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json")) => ifs::from_json(&fs::read_to_string(path)?)?,
+                        Some(path) => ifs::parse(&fs::read_to_string(path)?)?,
+                        None => preset.system(),
+                    };
+                    system.validate()?;
+                    check_count("iterations", iterations, fractals::MAX_CHAOS_GAME_POINTS)?;
+                    let points = ifs::chaos_game(&system, iterations, cli.seed);
+                    if write_table(cli, &points)? {
+                        return Ok(());
+                    }
+                    println!("{}: {} maps", system.name, system.transforms.len());
+                    match file {
+                        Some(_) => ifs::to_svg_with_theme(&points, 120.0, dots, &theme),
+                        None => ifs::to_svg_with_theme(&points, preset.hue(), dots, &theme),
+                    }
                 }
                 "sierpinski" => {
-                    let points = fractals::sierpinski_triangle(iterations, 42);
-                    // Reuse fern SVG with different scaling
-                    let fern_pts: Vec<_> = points.iter().map(|p| fractals::Point { x: p.x / 100.0, y: (p.y + 200.0) / 60.0 }).collect();
-                    fractals::fern_to_svg(&fern_pts)
+                    check_count("iterations", iterations, fractals::MAX_CHAOS_GAME_POINTS)?;
+                    let points = fractals::sierpinski_triangle(iterations, cli.seed);
+                    if write_table(cli, &points)? {
+                        return Ok(());
+                    }
+                    fractals::sierpinski_to_svg_with_theme(&points, dots, &theme)
                 }
                 _ => {
-                    let points = fractals::barnsley_fern(iterations, 42);
-                    fractals::fern_to_svg(&points)
+                    check_count("iterations", iterations, fractals::MAX_CHAOS_GAME_POINTS)?;
+                    let points = fractals::barnsley_fern_with_progress(iterations, cli.seed, &mut progress_bar("Iterating"))?;
+                    if write_table(cli, &points)? {
+                        return Ok(());
+                    }
+                    fractals::fern_to_svg_with_theme(&points, dots, &theme)
                 }
             }
         }
+        Commands::AnimateZoom { ref fractal_type, ref re, ref im, c_re, c_im, start_zoom, end_zoom, frames, width, max_iter, ref colormap } => {
+            check_count("width", width, MAX_GRID_SIZE)?;
+            let target = match fractal_type.as_str() {
+                "julia" => {
+                    check_range("c_re", c_re, -2.0..=2.0, "-2 to 2")?;
+                    check_range("c_im", c_im, -2.0..=2.0, "-2 to 2")?;
+                    fractals::ZoomTarget::Julia { c: (c_re, c_im) }
+                }
+                _ => fractals::ZoomTarget::Mandelbrot,
+            };
+            let height = width * 5 / 7;
+            let bits = fractals::deep_zoom_bits(3.5 / end_zoom.max(1.0), width);
+            let params = fractals::ZoomParams { target, re: Fixed::parse(re, bits)?, im: Fixed::parse(im, bits)?, start_zoom, end_zoom, frames, width, height, max_iter };
+            params.validate()?;
+            let lut = palette(&theme, colormap);
+            let mut progress = progress_bar("Rendering");
+            for (i, frame) in fractals::zoom_animation(&params).enumerate() {
+                let path = frame_path(&cli.output, i + 1);
+                if !write_png(&path, width, height, || frame.grid.to_rgb_in(frame.range, |t| lut.rgb(t)))? {
+                    fs::write(&path, fractals::escape_to_svg_in_with_theme(&frame.grid, frame.range, 1.0, |t| lut.css(t), &theme))?;
+                }
+                progress(i + 1, frames);
+            }
+            println!("🎞  Wrote {} zoom frames, {:e}× to {:e}×, next to {}", frames, start_zoom, end_zoom, cli.output.display());
+            return Ok(());
+        }
         Commands::Spirals { ref spiral_type, points, turns } => {
+            check_positive("turns", turns)?;
             let max_theta = turns * 2.0 * std::f64::consts::PI;
             let (spiral, color) = match spiral_type.as_str() {
                 "logarithmic" => (spirals::SpiralType::Logarithmic { a: 0.5, b: 0.12 }, "#e91e63"),
@@ -141,54 +1301,1112 @@ fn main() {
                 "helix" => (spirals::SpiralType::Helix { radius: 50.0, pitch: 20.0 }, "#9c27b0"),
                 _ => (spirals::SpiralType::Golden { a: 0.5 }, "#ffd700"),
             };
-            let pts = spirals::generate_spiral(spiral, points, max_theta);
-            spirals::to_svg(&pts, color)
+            let pts = spirals::checked_generate_spiral(spiral, points, max_theta)?;
+            if write_table(cli, &pts)? {
+                return Ok(());
+            }
+            spirals::to_svg_with_theme(&pts, color, &theme)
         }
-        Commands::Chaos { ref chaos_type, steps } => {
+        Commands::Chaos { ref chaos_type, steps, rate, r_min, r_max, ref integrator, ref render, ref section, ref view, azimuth, elevation } => {
+            let recurrence_svg = |plot: &chaos::RecurrencePlot| {
+                let cell = (800 / plot.size).max(1) as f64;
+                chaos::recurrence_to_svg_with_theme(plot, cell, &theme)
+            };
             match chaos_type.as_str() {
-                _ => {
+                "logistic" => {
+                    let steps = steps.unwrap_or(200);
+                    check_count("steps", steps, 100_000)?;
+                    check_range("rate", rate, 0.0..=4.0, "0 to 4")?;
+                    let values = chaos::logistic_map(rate, 0.5, steps);
+                    if render == "recurrence" {
+                        // Three delays rebuild the map's curve; 0.05 is 5% of the unit interval
+                        let states = chaos::delay_embedding(&values, 3, 1);
+                        let plot = chaos::recurrence_plot(&states, 0.05, |a, b| chaos::embedded_distance(a, b))?;
+                        print_recurrence(&plot);
+                        recurrence_svg(&plot)
+                    } else {
+                        chaos::logistic_to_svg_with_theme(&values, &theme)
+                    }
+                }
+                "cobweb" => {
+                    let steps = steps.unwrap_or(60);
+                    check_count("steps", steps, 100_000)?;
+                    check_range("rate", rate, 0.0..=4.0, "0 to 4")?;
+                    chaos::cobweb_to_svg_with_theme(rate, 0.2, steps, &theme)
+                }
+                "bifurcation" => {
+                    let steps = steps.unwrap_or(800);
+                    check_count("steps", steps, 4000)?;
+                    check_range("r_min", r_min, 0.0..=4.0, "0 to 4")?;
+                    check_range("r_max", r_max, r_min..=4.0, "r_min to 4")?;
+                    let data = chaos::bifurcation_diagram(r_min, r_max, steps, 500, 300);
+                    chaos::bifurcation_to_svg_with_theme(&data, steps, steps * 3 / 4, &theme)
+                }
+                "butterfly" => {
+                    let steps = steps.unwrap_or(5000);
+                    check_count("steps", steps, 10_000_000)?;
+                    check_range("elevation", elevation, -90.0..=90.0, "-90 to 90 degrees")?;
                     let params = chaos::LorenzParams::default();
-                    let points = chaos::lorenz_attractor(&params, steps, chaos::Point3D { x: 1.0, y: 1.0, z: 1.0 });
-                    chaos::lorenz_to_svg(&points)
+                    let (path1, path2) = chaos::butterfly_effect(&params, steps, 1e-10);
+                    println!("Paths 1e-10 apart end {:.3} apart", chaos::distance_3d(&path1[steps - 1], &path2[steps - 1]));
+                    let view = view.as_deref().and_then(|name| chaos::View::from_name(name, azimuth, elevation)).unwrap_or(chaos::View::Plane(chaos::Plane::XZ));
+                    chaos::butterfly_to_svg_with_theme(&path1, &path2, params.dt, view, &theme)
+                }
+                "figure-eight" | "lagrange" | "pythagorean" => {
+                    let preset = chaos::ThreeBody::from_name(chaos_type).unwrap_or(chaos::ThreeBody::FigureEight);
+                    let system = preset.system();
+                    let steps = steps.unwrap_or((preset.duration() / system.dt).round() as usize);
+                    check_count("steps", steps, 10_000_000)?;
+                    let trails = chaos::nbody_trails(&system, steps, (steps / 3000).max(1));
+                    let masses: Vec<f64> = system.bodies.iter().map(|b| b.mass).collect();
+                    chaos::nbody_to_svg_with_theme(&trails, &masses, &theme)
+                }
+                "henon" | "ikeda" => {
+                    let map = chaos::DiscreteMap::from_name(chaos_type).unwrap_or(chaos::DiscreteMap::Henon { a: 1.4, b: 0.3 });
+                    if render == "basin" {
+                        let size = steps.unwrap_or(600);
+                        check_count("steps", size, MAX_GRID_SIZE)?;
+                        let grid = chaos::basin_grid(&map, &map.region(), size, size, 500);
+                        println!("{:.1}% of starting points reach the strange attractor", 100.0 * grid.captured_fraction());
+                        chaos::basin_to_svg_with_theme(&grid, 1.0, &theme)
+                    } else {
+                        let steps = steps.unwrap_or(20000);
+                        check_count("steps", steps, 10_000_000)?;
+                        let points = chaos::map_orbit(&map, steps);
+                        if write_table(cli, &points)? {
+                            return Ok(());
+                        }
+                        chaos::map_orbit_to_svg_with_theme(&points, dots, &theme)
+                    }
+                }
+                name => {
+                    let steps = steps.unwrap_or(20000);
+                    check_count("steps", steps, 10_000_000)?;
+                    let integrator = match integrator.as_str() {
+                        "euler" => chaos::Integrator::Euler,
+                        "rk45" => chaos::Integrator::Rk45 { tolerance: 1e-9 },
+                        _ => chaos::Integrator::Rk4,
+                    };
+                    let attractor = chaos::Attractor::from_name(name).unwrap_or(chaos::Attractor::Lorenz(Default::default()));
+                    let points = chaos::trajectory(&attractor, steps, integrator);
+                    if let Some(section) = section {
+                        let plane = chaos::SectionPlane::parse(section).ok_or_else(|| MathaturaError::Parse {
+                            line: 0,
+                            message: format!("section plane should look like z=27, got {section:?}"),
+                        })?;
+                        let crossings = chaos::poincare_section(&points, &plane);
+                        if write_table(cli, &crossings)? {
+                            return Ok(());
+                        }
+                        println!("{} crossings of {section}", crossings.len());
+                        chaos::poincare_to_svg_with_theme(&crossings, &plane, dots, &theme)
+                    } else {
+                        if write_table(cli, &points)? {
+                            return Ok(());
+                        }
+                        match render.as_str() {
+                            "recurrence" => {
+                                // About 1000 states, close when within a tenth of the attractor's extent
+                                let states: Vec<_> = points.iter().step_by((points.len() / 1000).max(1)).copied().collect();
+                                let extent = states.iter().map(|p| chaos::distance_3d(p, &states[0])).fold(0.0, f64::max);
+                                let plot = chaos::recurrence_plot(&states, 0.1 * extent, chaos::distance_3d)?;
+                                print_recurrence(&plot);
+                                recurrence_svg(&plot)
+                            }
+                            render => {
+                                check_range("elevation", elevation, -90.0..=90.0, "-90 to 90 degrees")?;
+                                let view = view
+                                    .as_deref()
+                                    .and_then(|name| chaos::View::from_name(name, azimuth, elevation))
+                                    .unwrap_or(chaos::View::Plane(attractor.plane()));
+                                match render {
+                                    "points" => chaos::cloud_to_svg_with_theme(&points, view, dots, &theme),
+                                    "time" | "speed" => {
+                                        let color = chaos::TrajectoryColor::from_name(render).unwrap_or(chaos::TrajectoryColor::Time);
+                                        chaos::colored_trajectory_to_svg_with_theme(&points, view, color, &theme)
+                                    }
+                                    _ => chaos::trajectory_to_svg_with_theme(&points, view, &theme),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Commands::Attractors { ref map_type, ref coefficients, explore, points, size, ref colormap } => {
+            check_count("size", size, MAX_GRID_SIZE)?;
+            let lut = palette(&theme, colormap);
+            let classic = attractors::StrangeMap::from_name(map_type).unwrap_or(attractors::StrangeMap::from_name("dejong").unwrap());
+            let map = match coefficients.as_deref() {
+                Some(&[a, b, c, d]) => classic.with_coefficients([a, b, c, d]),
+                Some(k) => {
+                    return Err(MathaturaError::InvalidParameter { name: "coefficients", value: k.len() as f64, expected: "four values a,b,c,d" });
                 }
+                None => classic,
+            };
+            let map = if explore {
+                let (map, lambda) = attractors::explore(&map, cli.seed, 10_000).ok_or(MathaturaError::InvalidParameter {
+                    name: "seed",
+                    value: cli.seed as f64,
+                    expected: "a seed that leads to a chaotic draw",
+                })?;
+                println!("Found chaos with Lyapunov exponent {lambda:.3}");
+                map
+            } else {
+                map
+            };
+            let [a, b, c, d] = map.coefficients();
+            println!("{} map, a = {a:.4}, b = {b:.4}, c = {c:.4}, d = {d:.4}", map.name());
+            let density = attractors::density(&map, points, size)?;
+            if write_png(&cli.output, density.width, density.height, || density.to_rgb(|t| lut.rgb(t)))? {
+                println!("✨ Generated {}", cli.output.display());
+                return Ok(());
             }
+            attractors::density_to_svg_with_theme(&density, 1.0, |t| lut.css(t), &theme)
         }
-        Commands::Lsystem { ref system_type, iterations } => {
-            let system = match system_type.as_str() {
-                "tree" => lsystems::tree(),
-                "koch" => lsystems::koch_curve(),
-                "sierpinski" => lsystems::sierpinski_arrowhead(),
-                "dragon" => lsystems::dragon_curve(),
-                _ => lsystems::plant(),
+        Commands::Lsystem { ref system_type, ref file, iterations, animate, duration, ref projection, azimuth, elevation } => {
+            let system = match file {
+                Some(path) => lsystems::parse(&fs::read_to_string(path)?)?,
+                None => match system_type.as_str() {
+                    "tree" => lsystems::tree(),
+                    "koch" => lsystems::koch_curve(),
+                    "sierpinski" => lsystems::sierpinski_arrowhead(),
+                    "dragon" => lsystems::dragon_curve(),
+                    "tree3d" => lsystems::tree_3d(),
+                    "coral" => lsystems::coral(),
+                    _ => lsystems::plant(),
+                },
             };
-            let s = lsystems::generate(&system, iterations.min(8));
-            let segments = lsystems::interpret(&system, &s);
+            let s = lsystems::checked_generate(&system, iterations)?;
+            let projection = match projection.as_str() {
+                "perspective" => {
+                    check_range("elevation", elevation, -90.0..=90.0, "-90 to 90 degrees")?;
+                    mathatura::render::Projection::Perspective { azimuth, elevation, distance: 4.0 }
+                }
+                _ => mathatura::render::Projection::Isometric,
+            };
+            let (segments, segments_3d) = if lsystems::is_3d(&system) {
+                let segments_3d = lsystems::interpret_3d(&system, &s);
+                (lsystems::project_segments(&segments_3d, &projection), Some(segments_3d))
+            } else {
+                (lsystems::interpret(&system, &s), None)
+            };
+            if write_table(cli, &segments)? {
+                return Ok(());
+            }
             let md = lsystems::max_depth(&segments);
-            lsystems::to_svg(&segments, md)
+            if animate {
+                check_range("duration", duration, 0.1..=600.0, "0.1 to 600 seconds")?;
+                lsystems::to_animated_svg_with_theme(&segments, md, duration, &theme)
+            } else if let Some(segments_3d) = segments_3d {
+                lsystems::to_svg_3d_with_theme(&segments_3d, md, &projection, &theme)
+            } else {
+                lsystems::to_svg_with_theme(&segments, md, &theme)
+            }
         }
-        Commands::Turing { ref preset, size, steps } => {
+        Commands::Turing {
+            ref model,
+            ref preset,
+            size,
+            steps,
+            ref boundary,
+            ref render,
+            level,
+            preview,
+            frames,
+            ref to,
+            anisotropy,
+            angle,
+            #[cfg(feature = "image")]
+            ref seed_image,
+        } => {
+            check_count("size", size, MAX_GRID_SIZE)?;
+            let edges = |rest: turing::Cell| match boundary.as_str() {
+                "mirror" => turing::Boundary::Mirror,
+                "fixed" => turing::Boundary::Fixed(rest),
+                "no-flux" => turing::Boundary::NoFlux,
+                _ => turing::Boundary::Periodic,
+            };
+            // Gray-Scott renders B on its own 0–1 scale; other models stretch to their range
+            let gray_scott = !matches!(model.as_str(), "gierer-meinhardt" | "fitzhugh-nagumo");
+            let (model, mut grid): (Box<dyn turing::ReactionModel + Sync>, _) = match model.as_str() {
+                "gierer-meinhardt" => {
+                    let params = if preset == "stripes" { turing::GiererMeinhardtParams::stripes() } else { turing::GiererMeinhardtParams::spots() };
+                    let params = turing::GiererMeinhardtParams { boundary: edges(params.steady_state()), ..params };
+                    params.validate()?;
+                    (Box::new(params), turing::Grid::perturbed(size, size, &params, 0.05, cli.seed))
+                }
+                "fitzhugh-nagumo" => {
+                    let params = if preset == "labyrinth" { turing::FitzHughNagumoParams::labyrinth() } else { turing::FitzHughNagumoParams::spots() };
+                    let params = turing::FitzHughNagumoParams { boundary: edges(params.steady_state()), ..params };
+                    params.validate()?;
+                    (Box::new(params), turing::Grid::perturbed(size, size, &params, 0.05, cli.seed))
+                }
+                _ => {
+                    let p = turing::Preset::from_name(preset).unwrap_or(turing::Preset::Spots);
+                    let params = p.params().with_anisotropy(angle.to_radians(), anisotropy);
+                    let params = params.with_boundary(edges(params.steady_state()));
+                    params.validate()?;
+                    let grid = turing::Grid::new_random(size, size, cli.seed);
+                    #[cfg(feature = "image")]
+                    let grid = match seed_image {
+                        Some(path) => mathatura::image::Grayscale::open(path)?.resize(size, size).to_turing(),
+                        None => grid,
+                    };
+                    match to {
+                        Some(to) => {
+                            let to = turing::Preset::from_name(to).unwrap_or(turing::Preset::Stripes);
+                            let map = turing::FeedKillMap { params, ..turing::FeedKillMap::gradient(size, size, p, to) };
+                            map.validate()?;
+                            (Box::new(map), grid)
+                        }
+                        None => (Box::new(params), grid),
+                    }
+                }
+            };
+            let level = level.unwrap_or(if gray_scott { 0.2 } else { 0.5 });
+            let contours = render == "contours";
+            let lut = palette(&theme, "inferno");
+            let values = |grid: &turing::Grid| turing::heatmap_values(grid, (!gray_scott).then_some(&*model));
+            let render = |grid: &turing::Grid| {
+                if contours {
+                    turing::contour_to_svg_with_theme(grid.width, grid.height, &values(grid), level, &*lut, &theme)
+                } else {
+                    turing::heatmap_to_svg_with_theme(grid.width, grid.height, &values(grid), &*lut, &theme)
+                }
+            };
+            let png = cli.output.extension().is_some_and(|e| e.eq_ignore_ascii_case("png"));
+            #[cfg(not(feature = "image"))]
+            if png {
+                return Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "PNG output needs the `image` feature").into());
+            }
+            let write = |path: &Path, grid: &turing::Grid| -> Result<()> {
+                #[cfg(feature = "image")]
+                if png {
+                    let rgb = turing::heatmap_to_rgb(grid.width, grid.height, &values(grid), &*lut);
+                    let (width, height) = (grid.width * turing::CELL_PIXELS, grid.height * turing::CELL_PIXELS);
+                    return mathatura::image::save_rgb_png(path, width, height, &rgb);
+                }
+                fs::write(path, render(grid))?;
+                Ok(())
+            };
+            if frames > 0 {
+                let every = steps.checked_div(frames).map_or(1, |n| n.max(1));
+                let snapshots = grid.simulate_with_snapshots(&*model, steps, every);
+                for (i, snapshot) in snapshots.iter().enumerate() {
+                    write(&frame_path(&cli.output, i + 1), snapshot)?;
+                }
+                println!("🎞  Wrote {} pattern frames", snapshots.len());
+            } else {
+                grid.simulate_with_progress(&*model, steps, &mut progress_bar("Simulating"))?;
+            }
+            if write_table(cli, &grid)? {
+                return Ok(());
+            }
+            if preview {
+                let values = grid.cells.iter().map(|&c| model.pattern_value(c)).collect();
+                print!("{}", Terminal::default().render(&Geometry::Grid { cols: grid.width, rows: grid.height, values }));
+                return Ok(());
+            }
+            if png {
+                write(&cli.output, &grid)?;
+                println!("✨ Generated {}", cli.output.display());
+                return Ok(());
+            }
+            render(&grid)
+        }
+        Commands::Snowflake { ref preset, alpha, beta, gamma, size, steps, outline, frames } => {
             let p = match preset.as_str() {
-                "stripes" => turing::Preset::Stripes,
-                "coral" => turing::Preset::Coral,
-                "mitosis" => turing::Preset::Mitosis,
-                "worms" => turing::Preset::Worms,
-                _ => turing::Preset::Spots,
+                "plate" => snowflake::Preset::Plate,
+                "fernlike" => snowflake::Preset::Fernlike,
+                _ => snowflake::Preset::Stellar,
+            };
+            let mut params = p.params();
+            params.alpha = alpha.unwrap_or(params.alpha);
+            params.beta = beta.unwrap_or(params.beta);
+            params.gamma = gamma.unwrap_or(params.gamma);
+            params.validate()?;
+            check_count("size", size, MAX_GRID_SIZE)?;
+            let mut flake = snowflake::Snowflake::new(size, &params);
+            flake.simulate(&params, steps);
+            if frames > 0 {
+                for (i, frame) in snowflake::stage_frames_with_theme(&flake, frames, &theme).iter().enumerate() {
+                    let path = frame_path(&cli.output, i + 1);
+                    fs::write(&path, frame)?;
+                }
+                println!("🎞  Wrote {} growth frames", frames);
+            }
+            if outline {
+                snowflake::outline_to_svg_with_theme(&flake.outline(), &theme)
+            } else {
+                snowflake::to_svg_with_theme(&flake, &theme)
+            }
+        }
+        Commands::Rose { ref k, filled } => {
+            let (n, d) = roses::RoseParams::parse_k(k).ok_or_else(|| MathaturaError::InvalidParameter {
+                name: "k",
+                value: k.trim().parse().unwrap_or(f64::NAN),
+                expected: "a non-negative integer, decimal or n/d with d > 0",
+            })?;
+            let params = roses::RoseParams { n, d, ..Default::default() };
+            let style = if filled { roses::RoseStyle::Filled } else { roses::RoseStyle::Outline };
+            roses::to_svg_with_theme(&params, style, &theme)
+        }
+        Commands::Superformula { ref preset, ref morph_to, frames } => {
+            let parse = |name: &str| match name {
+                "flower" => superformula::Preset::Flower,
+                "diatom" => superformula::Preset::Diatom,
+                "leaf" => superformula::Preset::Leaf,
+                _ => superformula::Preset::Starfish,
+            };
+            let shape = parse(preset).params();
+            if let Some(target) = morph_to {
+                let outlines = superformula::morph(&shape, &parse(target).params(), frames, 720);
+                for (i, pts) in outlines.iter().enumerate() {
+                    let hue = 330.0 - 150.0 * i as f64 / outlines.len() as f64;
+                    fs::write(frame_path(&cli.output, i + 1), superformula::to_svg_with_theme(pts, hue, &theme))?;
+                }
+                println!("🎞  Wrote {} morph frames", outlines.len());
+            }
+            superformula::to_svg_with_theme(&superformula::outline(&shape, 720), 330.0, &theme)
+        }
+        Commands::Harmonograph { ref harmonograph_type, fx, fy, fr, phase, damping, duration } => {
+            let points = match harmonograph_type.as_str() {
+                "lissajous" => {
+                    let params = harmonograph::LissajousParams {
+                        a: fx.round().max(1.0) as u32,
+                        b: fy.round().max(1.0) as u32,
+                        delta: phase.to_radians(),
+                        ..Default::default()
+                    };
+                    harmonograph::lissajous(&params, 4000)
+                }
+                "three" => harmonograph::Harmonograph::three_pendulum(fx, fy, fr, damping).trace(duration, 40000),
+                _ => harmonograph::Harmonograph::two_pendulum(fx, fy, phase.to_radians(), damping).trace(duration, 40000),
+            };
+            harmonograph::to_svg_with_theme(&points, 180.0, 320.0, &theme)
+        }
+        Commands::Epicycles { ref file, terms, frames, animate, duration } => {
+            let outline = match file {
+                Some(path) => {
+                    let text = fs::read_to_string(path)?;
+                    epicycles::parse_points(&text)
+                }
+                None => epicycles::leaf_outline(400),
+            };
+            if outline.len() < 3 {
+                return Err(mathatura::MathaturaError::Empty { name: "outline points" });
+            }
+            let target = epicycles::resample_closed(&outline, 256);
+            let series = epicycles::fourier_series(&target);
+            let coeffs = &series[..terms.min(series.len())];
+            if frames > 0 {
+                for (i, frame) in epicycles::animation_frames_with_theme(&target, coeffs, frames, &theme).iter().enumerate() {
+                    fs::write(frame_path(&cli.output, i + 1), frame)?;
+                }
+                println!("🎞  Wrote {} epicycle frames", frames);
+            }
+            if animate {
+                check_range("duration", duration, 0.1..=3600.0, "0.1 ≤ duration ≤ 3600 s")?;
+                epicycles::animated_svg_with_theme(&target, coeffs, duration, 240, &theme)
+            } else {
+                epicycles::frame_to_svg_with_theme(&target, coeffs, 1.0, &theme)
+            }
+        }
+        Commands::Spirograph { ref spirograph_type, ring, wheel, ref pens } => {
+            let kind = match spirograph_type.as_str() {
+                "epi" => spirograph::Kind::Epitrochoid,
+                _ => spirograph::Kind::Hypotrochoid,
+            };
+            let gears = spirograph::Spirograph { kind, ring, wheel, pen: 0.0 };
+            gears.validate()?;
+            spirograph::to_svg_with_theme(&gears.layers(pens, spirograph::SAMPLES_PER_REVOLUTION), &theme)
+        }
+        Commands::Flocking { count, steps, ref render, perception, predator, frames } => {
+            let params = flocking::FlockParams { count, perception_radius: perception, predator, ..Default::default() };
+            params.validate()?;
+            let mut flock = flocking::Flock::new(&params, cli.seed);
+            let mut trails: Vec<Vec<fractals::Point>> = vec![Vec::new(); count];
+            let every = steps.checked_div(frames).map_or(usize::MAX, |n| n.max(1));
+            let mut written = 0;
+            for step in 1..=steps {
+                flock.step(&params);
+                for (trail, b) in trails.iter_mut().zip(flock.boids.iter()) {
+                    trail.push(fractals::Point { x: b.x, y: b.y });
+                }
+                if step % every == 0 && written < frames {
+                    written += 1;
+                    fs::write(frame_path(&cli.output, written), flocking::snapshot_to_svg_with_theme(&flock, &params, &theme))?;
+                }
+            }
+            if written > 0 {
+                println!("🎞  Wrote {} flock frames", written);
+            }
+            let (nn_mean, nn_std) = flock.nearest_neighbor_stats();
+            println!("Polarization {:.3}, nearest neighbor {:.1} ± {:.1}", flock.polarization(), nn_mean, nn_std);
+            match render.as_str() {
+                "snapshot" => flocking::snapshot_to_svg_with_theme(&flock, &params, &theme),
+                "density" => flocking::density_to_svg_with_theme(&trails.concat(), &params, 200, 150, &theme),
+                _ => flocking::trails_to_svg_with_theme(&trails, &params, &theme),
+            }
+        }
+        Commands::Physarum { agents, size, steps, sensor_angle, sensor_distance } => {
+            let params = physarum::PhysarumParams {
+                agents,
+                sensor_angle: sensor_angle.to_radians(),
+                sensor_distance,
+                ..Default::default()
             };
-            let mut grid = turing::Grid::new_random(size, size, 42);
-            grid.simulate(&p.params(), steps);
-            turing::grid_to_svg(&grid)
+            params.validate()?;
+            check_count("size", size, MAX_GRID_SIZE)?;
+            let mut sim = physarum::Physarum::new(size, size, &params, cli.seed);
+            sim.simulate(&params, steps);
+            physarum::to_svg_with_theme(&sim, 4.0, &theme)
+        }
+        Commands::Dla { ref preset, particles, stickiness, ref seed_shape, size } => {
+            let p = match preset.as_str() {
+                "frost" => dla::Preset::Frost,
+                "mineral" => dla::Preset::Mineral,
+                _ => dla::Preset::Coral,
+            };
+            let mut params = p.params();
+            params.particles = particles.unwrap_or(params.particles);
+            params.stickiness = stickiness.unwrap_or(params.stickiness);
+            params.seed = match seed_shape.as_deref() {
+                Some("point") => dla::Seed::Point,
+                Some("line") => dla::Seed::Line,
+                Some("circle") => dla::Seed::Circle,
+                _ => params.seed,
+            };
+            params.validate()?;
+            check_count("size", size, MAX_GRID_SIZE)?;
+            let cluster = dla::grow(size, size, &params, cli.seed);
+            println!(
+                "{}: {} particles, fractal dimension ≈ {:.3}",
+                p.name(),
+                cluster.particles.len(),
+                cluster.fractal_dimension()
+            );
+            dla::to_svg_with_theme(&cluster, 2.0, &theme)
+        }
+        Commands::Colonization { ref preset, attractors, ref venation, width } => {
+            let p = match preset.as_str() {
+                "tree" => colonization::Preset::Tree,
+                _ => colonization::Preset::Leaf,
+            };
+            let mut params = p.params();
+            params.attractors = attractors.unwrap_or(params.attractors);
+            params.venation = match venation.as_deref() {
+                Some("open") => colonization::Venation::Open,
+                Some("closed") => colonization::Venation::Closed,
+                _ => params.venation,
+            };
+            params.validate()?;
+            check_range("width", width, 0.5..=100.0, "0.5 ≤ width ≤ 100")?;
+            let network = colonization::grow(&params, cli.seed);
+            println!(
+                "{}: {} nodes, {} vein endings, {} loops after {} steps",
+                p.name(),
+                network.nodes.len(),
+                network.tips().len(),
+                network.anastomoses.len(),
+                network.iterations
+            );
+            colonization::to_svg_with_theme(&network, width, &theme)
+        }
+        Commands::Sandpile { grains, size, identity, frames } => {
+            let size = size.unwrap_or_else(|| sandpile::Sandpile::fitting(grains as u64));
+            check_count("size", size, MAX_GRID_SIZE)?;
+            let draw = |pile: &sandpile::Sandpile| sandpile::to_svg_with_theme(pile, 3.0, &theme);
+            if identity {
+                draw(&sandpile::identity(size, size))
+            } else {
+                if frames > 0 {
+                    for (i, pile) in sandpile::snapshots(size, grains, frames).iter().enumerate() {
+                        let path = frame_path(&cli.output, i + 1);
+                        fs::write(&path, draw(pile))?;
+                    }
+                    println!("🎞  Wrote {} sandpile frames", frames);
+                }
+                let mut pile = sandpile::Sandpile::new(size, size);
+                let topplings = pile.drop_center(grains);
+                println!("{} topplings, {} grains left on the grid", topplings, pile.total());
+                draw(&pile)
+            }
+        }
+        Commands::Automaton { rule, states, random, width, generations, ref palette } => {
+            let rule = match states {
+                Some(states) => {
+                    check_range("states", f64::from(states), 2.0..=f64::from(automata::MAX_COLORS), "2 to 6 states")?;
+                    let max = automata::Rule::max_totalistic_code(states) as f64;
+                    check_range("rule", rule as f64, 0.0..=max, "a totalistic code from 0 to k^(3k-2) - 1")?;
+                    automata::Rule::totalistic(states, rule)?
+                }
+                None => {
+                    check_range("rule", rule as f64, 0.0..=255.0, "an elementary rule from 0 to 255")?;
+                    automata::Rule::Elementary(rule as u8)
+                }
+            };
+            let init = if random { automata::InitialState::Random(cli.seed) } else { automata::InitialState::SingleCell };
+            let palette = match palette.as_str() {
+                "mono" => automata::Palette::Mono,
+                _ => automata::Palette::Shell,
+            };
+            let rows = automata::evolve(&rule, width, generations, init);
+            automata::to_svg_with_theme(&rows, rule.colors(), palette, 2.0, &theme)
+        }
+        Commands::Lenia { ref preset, size, steps, frames } => {
+            let p = match preset.as_str() {
+                "hydrogeminium" => lenia::Preset::Hydrogeminium,
+                _ => lenia::Preset::Orbium,
+            };
+            check_count("size", size, MAX_GRID_SIZE)?;
+            let mut world = lenia::Lenia::from_preset(p, size, size, cli.seed);
+            let draw = |world: &lenia::Lenia| lenia::to_svg_with_theme(world, 4.0, &theme);
+            if frames > 0 {
+                let per_frame = steps.checked_div(frames).map_or(1, |n| n.max(1));
+                for i in 0..frames {
+                    world.simulate(per_frame);
+                    fs::write(frame_path(&cli.output, i + 1), draw(&world))?;
+                }
+                println!("🎞  Wrote {} Lenia frames", frames);
+            } else {
+                world.simulate(steps);
+            }
+            println!("{}: mass {:.1}", p.name(), world.mass());
+            draw(&world)
+        }
+        Commands::Fireflies { count, coupling, spread, radius, steps, ref render, frames } => {
+            let params = fireflies::KuramotoParams {
+                count,
+                coupling,
+                frequency_spread: spread,
+                coupling_radius: radius,
+                ..Default::default()
+            };
+            params.validate()?;
+            let mut swarm = fireflies::Swarm::new(&params, cli.seed);
+            let every = steps.checked_div(120).map_or(1, |n| n.max(1));
+            let mut order = Vec::with_capacity(steps);
+            let mut history = vec![swarm.phases.clone()];
+            let mut written = 0;
+            for i in 1..=steps {
+                swarm.step(&params);
+                order.push(swarm.order_parameter().0);
+                if i % every == 0 {
+                    history.push(swarm.phases.clone());
+                }
+                if frames > 0 && i % (steps / frames).max(1) == 0 && written < frames {
+                    written += 1;
+                    let path = frame_path(&cli.output, written);
+                    fs::write(&path, fireflies::field_to_svg_with_theme(&swarm, &theme))?;
+                }
+            }
+            if written > 0 {
+                println!("🎞  Wrote {} firefly frames", written);
+            }
+            println!(
+                "Order parameter r = {:.3} (critical coupling ≈ {:.2} for all-to-all)",
+                order.last().copied().unwrap_or(0.0),
+                params.critical_coupling()
+            );
+            match render.as_str() {
+                "timeline" => fireflies::timeline_to_svg_with_theme(&swarm, &history, &theme),
+                "order" => fireflies::order_plot_to_svg_with_theme(&order, params.dt, &theme),
+                _ => fireflies::field_to_svg_with_theme(&swarm, &theme),
+            }
+        }
+        Commands::PredatorPrey { ref model, capacity, orbits, duration, ref render } => {
+            let model = match model.as_str() {
+                "rosenzweig" => predator_prey::Model::rosenzweig_macarthur(capacity),
+                _ => predator_prey::Model::default(),
+            };
+            let dt = 0.01;
+            let steps = (duration / dt) as usize;
+            let orbits = predator_prey::nested_orbits(&model, orbits.max(1), 3.0, dt, steps);
+            match render.as_str() {
+                "series" => predator_prey::time_series_to_svg_with_theme(&orbits[orbits.len() - 1], &theme),
+                _ => predator_prey::phase_portrait_to_svg_with_theme(&model, &orbits, &theme),
+            }
+        }
+        Commands::Waves { ref preset, wavelength, decay, resolution, ref render } => {
+            let p = match preset.as_str() {
+                "ring" => waves::Preset::Ring,
+                "rain" => waves::Preset::Rain,
+                "moire" => waves::Preset::Moire,
+                _ => waves::Preset::TwoSource,
+            };
+            let mut field = p.field(cli.seed);
+            for source in &mut field.sources {
+                source.wavelength = wavelength.unwrap_or(source.wavelength);
+                source.decay = decay.unwrap_or(source.decay);
+            }
+            field.validate()?;
+            let render = match render.as_str() {
+                "contours" => waves::Render::Contours,
+                _ => waves::Render::Colormap,
+            };
+            waves::to_svg_with_theme(&field.sample(resolution), resolution, render, &theme)
+        }
+        Commands::Chladni { ref plate, m, n, ref mix, resolution } => {
+            let mut pattern = match plate.as_str() {
+                "circle" => chladni::Chladni::circular(n, m),
+                _ => chladni::Chladni::square(m, n),
+            };
+            for spec in mix {
+                let parts: Vec<f64> = spec.split(',').filter_map(|v| v.trim().parse().ok()).collect();
+                if let [m, n, ref rest @ ..] = parts[..] {
+                    pattern = pattern.with_mode(m as u32, n as u32, rest.first().copied().unwrap_or(1.0));
+                }
+            }
+            pattern.validate()?;
+            chladni::to_svg_with_theme(&pattern, &pattern.nodal_lines(resolution), &theme)
+        }
+        Commands::Dunes { ref preset, width, height, cycles, frames } => {
+            let p = match preset.as_str() {
+                "ripples" => dunes::Preset::Ripples,
+                "barchans" => dunes::Preset::Barchans,
+                _ => dunes::Preset::Transverse,
+            };
+            let params = p.params();
+            check_count("width", width, MAX_GRID_SIZE)?;
+            check_count("height", height, MAX_GRID_SIZE)?;
+            let mut field = dunes::DuneField::new(width, height, &params, cli.seed);
+            let draw = |slabs: &[u32]| dunes::to_svg_with_theme(slabs, width, height, 5.0, &theme);
+            if frames > 0 {
+                let per_frame = cycles.checked_div(frames).map_or(1, |n| n.max(1));
+                for (i, slabs) in field.time_lapse(&params, frames, per_frame).iter().enumerate() {
+                    let path = frame_path(&cli.output, i + 1);
+                    fs::write(&path, draw(slabs))?;
+                }
+                println!("🎞  Wrote {} dune frames", frames);
+            } else {
+                field.simulate(&params, cycles);
+            }
+            let (downwind, crosswind) = field.correlation(4);
+            println!("{}: height correlation at 4 cells {:.2} downwind, {:.2} crosswind", p.name(), downwind, crosswind);
+            draw(&field.slabs)
+        }
+        Commands::Terrain { ref preset, octaves, lacunarity, persistence, sea_level, contours, size } => {
+            let p = match preset.as_str() {
+                "archipelago" => terrain::Preset::Archipelago,
+                "hills" => terrain::Preset::Hills,
+                _ => terrain::Preset::Mountains,
+            };
+            let mut params = p.params();
+            params.octaves = octaves.unwrap_or(params.octaves);
+            params.lacunarity = lacunarity.unwrap_or(params.lacunarity);
+            params.persistence = persistence.unwrap_or(params.persistence);
+            params.sea_level = sea_level.unwrap_or(params.sea_level);
+            params.validate()?;
+            check_count("size", size, MAX_GRID_SIZE)?;
+            let map = terrain::Terrain::generate(size, size, &params, cli.seed);
+            println!("{}: {:.0}% land", p.name(), 100.0 * map.land_fraction(params.sea_level));
+            terrain::to_svg_with_theme(&map, params.sea_level, contours, 5.0, &theme)
+        }
+        Commands::Orbweb { ref preset, radials, turns, asymmetry, sag, damage, dew } => {
+            let p = match preset.as_str() {
+                "lopsided" => orbweb::Preset::Lopsided,
+                "tattered" => orbweb::Preset::Tattered,
+                _ => orbweb::Preset::Garden,
+            };
+            let mut params = p.params();
+            params.radials = radials.unwrap_or(params.radials);
+            params.capture_turns = turns.unwrap_or(params.capture_turns);
+            params.asymmetry = asymmetry.unwrap_or(params.asymmetry);
+            params.sag = sag.unwrap_or(params.sag);
+            params.damage = damage.unwrap_or(params.damage);
+            params.validate()?;
+            check_range("dew", dew, 0.0..=100.0, "0 to 100 drops per 100 units")?;
+            let web = orbweb::build(&params, cli.seed);
+            let drops = orbweb::dew_drops(&web, dew, cli.seed);
+            println!("{}: {} radials, {} capture threads, {} dew drops", p.name(), web.radials.len(), web.capture.len(), drops.len());
+            orbweb::to_svg_with_theme(&web, &drops, &theme)
+        }
+        Commands::Tessellations { ref tiling, ref preset, count, ref seeding, cell, jitter, wall } => match tiling.as_str() {
+            "voronoi" => {
+                let p = match preset.as_deref() {
+                    Some("dragonfly") => tessellations::VoronoiPreset::Dragonfly,
+                    Some("mud") => tessellations::VoronoiPreset::Mud,
+                    _ => tessellations::VoronoiPreset::Giraffe,
+                };
+                let mut params = p.params();
+                params.count = count.unwrap_or(params.count);
+                params.gap = wall.unwrap_or(params.gap);
+                params.seeding = match seeding.as_deref() {
+                    Some("random") => tessellations::Seeding::Random,
+                    Some("phyllotaxis") => tessellations::Seeding::Phyllotaxis,
+                    Some("poisson") => tessellations::Seeding::PoissonDisc,
+                    _ => params.seeding,
+                };
+                params.validate()?;
+                let cells = tessellations::voronoi(&params.seeds(cli.seed), params.width, params.height);
+                println!("{}: {} cells", p.name(), cells.iter().filter(|c| !c.is_empty()).count());
+                tessellations::voronoi_to_svg_with_theme(&cells, &params, cli.seed, &theme)
+            }
+            _ => {
+                let p = match preset.as_deref() {
+                    Some("wild") => tessellations::Preset::Wild,
+                    Some("wasp") => tessellations::Preset::Wasp,
+                    _ => tessellations::Preset::Apis,
+                };
+                let mut params = p.params();
+                params.cell_size = cell.unwrap_or(params.cell_size);
+                params.jitter = jitter.unwrap_or(params.jitter);
+                params.wall = wall.unwrap_or(params.wall);
+                params.validate()?;
+                let (cols, rows) = params.dimensions();
+                println!("{}: {} cells", p.name(), cols * rows);
+                tessellations::honeycomb_to_svg_with_theme(&params, cli.seed, &theme)
+            }
+        },
+        Commands::Symmetry { ref preset, order, no_mirror } => {
+            let p = match preset.as_str() {
+                "starfish" => symmetry::Preset::Starfish,
+                "flower" => symmetry::Preset::Flower,
+                "leaf" => symmetry::Preset::Leaf,
+                _ => symmetry::Preset::Snowflake,
+            };
+            let mut group = p.symmetry();
+            group.order = order.unwrap_or(group.order);
+            group.mirror &= !no_mirror;
+            group.validate()?;
+            println!("{}: symmetry group {}", p.name(), group.name());
+            symmetry::preset_to_svg_with_theme(p, &group, &theme)
+        }
+        Commands::Sonify { ref source, count, rate, angle, ref system, iterations, ref scale, bpm } => {
+            let scale = match scale.as_str() {
+                "major" => sonify::Scale::Major,
+                "minor" => sonify::Scale::Minor,
+                "chromatic" => sonify::Scale::Chromatic,
+                _ => sonify::Scale::Pentatonic,
+            };
+            check_count("count", count, 100_000)?;
+            let notes = match source.as_str() {
+                "phyllotaxis" => {
+                    let params = phyllotaxis::Params { count, divergence_angle: angle, scale: 1.0 };
+                    params.validate()?;
+                    // One full turn of the rhythm lasts four bars
+                    let cycle = 16 * sonify::TICKS_PER_QUARTER as u32;
+                    sonify::phyllotaxis_rhythm(&phyllotaxis::vogel_spiral(&params), scale, 60, cycle)
+                }
+                "lsystem" => {
+                    let sys = match system.as_str() {
+                        "tree" => lsystems::tree(),
+                        "koch" => lsystems::koch_curve(),
+                        "sierpinski" => lsystems::sierpinski_arrowhead(),
+                        "dragon" => lsystems::dragon_curve(),
+                        _ => lsystems::plant(),
+                    };
+                    sonify::lsystem_melody(&lsystems::checked_generate(&sys, iterations)?, scale, 60)
+                }
+                _ => {
+                    mathatura::error::check_range("rate", rate, 0.0..=4.0, "a growth rate in [0, 4]")?;
+                    sonify::logistic_melody(&chaos::logistic_map(rate, 0.5, count), scale, 48, 3)
+                }
+            };
+            let midi = sonify::to_midi(&notes, bpm, 0);
+            fs::write(&cli.output, &midi)?;
+            println!("🎵 Generated {} ({} notes, {} bytes)", cli.output.display(), notes.len(), midi.len());
+            return Ok(());
+        }
+        Commands::Analyze { ref pattern_type, count, ref system, rate } => {
+            let mut stats: Vec<(&str, f64)> = Vec::new();
+            let (title, geometry) = match pattern_type.as_str() {
+                "fern" | "sierpinski" => {
+                    let n = count.unwrap_or(100_000);
+                    check_count("count", n, fractals::MAX_CHAOS_GAME_POINTS)?;
+                    let (title, points, fractal) = if pattern_type == "fern" {
+                        ("Barnsley fern", fractals::barnsley_fern(n, cli.seed), fractals::Fractal::Fern { points: n, seed: cli.seed })
+                    } else {
+                        ("Sierpinski triangle", fractals::sierpinski_triangle(n, cli.seed), fractals::Fractal::Sierpinski { points: n, seed: cli.seed })
+                    };
+                    stats.push(("points", n as f64));
+                    stats.push(("box-counting dimension", point_dimension(&points)));
+                    (title, fractal.generate())
+                }
+                "koch" => {
+                    let n = count.unwrap_or(5);
+                    let outline = fractals::checked_koch_snowflake(n)?;
+                    stats.push(("iterations", n as f64));
+                    stats.push(("segments", outline.len().saturating_sub(1) as f64));
+                    stats.push(("arc length", outline.windows(2).map(|w| (w[1].x - w[0].x).hypot(w[1].y - w[0].y)).sum()));
+                    stats.push(("similarity dimension", 4f64.ln() / 3f64.ln()));
+                    ("Koch snowflake", fractals::Fractal::Koch { iterations: n }.generate())
+                }
+                "carpet" => {
+                    let n = count.unwrap_or(5);
+                    let squares = fractals::sierpinski_carpet(n)?;
+                    let side = 3usize.pow(n as u32);
+                    let mask: Vec<bool> = (0..side * side).map(|k| fractals::in_sierpinski_carpet(k % side, k / side)).collect();
+                    let sizes: Vec<usize> = (0..n.saturating_sub(1)).map(|k| 3usize.pow(k as u32)).collect();
+                    stats.push(("depth", n as f64));
+                    stats.push(("squares", squares.len() as f64));
+                    stats.push(("area", squares.len() as f64 / (side * side) as f64));
+                    stats.push(("box-counting dimension", fractals::estimate_dimension(&fractals::box_counting_dimension_raster(&mask, side, &sizes))));
+                    let values = mask.iter().map(|&m| if m { 1.0 } else { 0.0 }).collect();
+                    ("Sierpinski carpet", Geometry::Grid { cols: side, rows: side, values })
+                }
+                "mandelbrot" => {
+                    let width = count.unwrap_or(400);
+                    check_count("count", width, MAX_GRID_SIZE)?;
+                    let region = fractals::Region::MANDELBROT;
+                    let height = width * 5 / 7;
+                    let grid = fractals::mandelbrot_grid(&region, width, height.max(1), 500);
+                    let shades = grid.shades();
+                    let inside: Vec<bool> = shades.iter().map(Option::is_none).collect();
+                    let fraction = inside.iter().filter(|&&i| i).count() as f64 / inside.len() as f64;
+                    stats.push(("pixels", inside.len() as f64));
+                    stats.push(("area", fraction * (region.re_max - region.re_min) * (region.im_max - region.im_min)));
+                    let sizes = fractals::raster_box_sizes(width, grid.height);
+                    stats.push(("box-counting dimension", fractals::estimate_dimension(&fractals::box_counting_dimension_raster(&inside, width, &sizes))));
+                    let values = shades.into_iter().map(|t| t.unwrap_or(0.0)).collect();
+                    ("Mandelbrot set", Geometry::Grid { cols: width, rows: grid.height, values })
+                }
+                "lsystem" => {
+                    let n = count.unwrap_or(4);
+                    let system = match system.as_str() {
+                        "tree" => lsystems::tree(),
+                        "koch" => lsystems::koch_curve(),
+                        "sierpinski" => lsystems::sierpinski_arrowhead(),
+                        "dragon" => lsystems::dragon_curve(),
+                        _ => lsystems::plant(),
+                    };
+                    let segments = lsystems::interpret(&system, &lsystems::checked_generate(&system, n)?);
+                    stats.push(("iterations", n as f64));
+                    stats.push(("segments", segments.len() as f64));
+                    stats.push(("arc length", lsystems::total_length(&segments)));
+                    stats.push(("branches", lsystems::count_branches(&segments) as f64));
+                    stats.push(("max depth", lsystems::max_depth(&segments) as f64));
+                    ("L-system", lsystems::Derivation { system, iterations: n }.generate())
+                }
+                "logistic" => {
+                    let n = count.unwrap_or(10_000);
+                    check_count("count", n, 10_000_000)?;
+                    check_range("rate", rate, 0.0..=4.0, "0 to 4")?;
+                    let lambda = chaos::lyapunov_exponent(rate, n);
+                    stats.push(("rate", rate));
+                    stats.push(("iterations", n as f64));
+                    stats.push(("lyapunov exponent", lambda));
+                    print_stats(cli, "Logistic map", &stats)?;
+                    return Ok(());
+                }
+                "phyllotaxis" => {
+                    let params = phyllotaxis::Params { count: count.unwrap_or(500), ..Default::default() };
+                    params.validate()?;
+                    let elements = phyllotaxis::vogel_spiral(&params);
+                    stats.push(("elements", elements.len() as f64));
+                    stats.push(("packing efficiency", phyllotaxis::packing_efficiency(&elements)));
+                    if let Some(&(cw, ccw)) = phyllotaxis::count_parastichies(&elements).last() {
+                        stats.push(("clockwise parastichies", cw as f64));
+                        stats.push(("counterclockwise parastichies", ccw as f64));
+                    }
+                    ("Phyllotaxis", params.generate())
+                }
+                "turing" => {
+                    let steps = count.unwrap_or(5000);
+                    check_count("count", steps, 1_000_000)?;
+                    let params = turing::Preset::Spots.params();
+                    let mut grid = turing::Grid::new_random(128, 128, cli.seed);
+                    grid.simulate_with_progress(&params, steps, &mut progress_bar("Simulating"))?;
+                    let (a, b) = grid.averages();
+                    stats.push(("steps", steps as f64));
+                    stats.push(("turing contrast", grid.contrast()));
+                    stats.push(("mean a", a));
+                    stats.push(("mean b", b));
+                    ("Turing pattern", grid.generate())
+                }
+                "dla" => {
+                    let mut params = dla::Preset::Coral.params();
+                    params.particles = count.unwrap_or(params.particles);
+                    params.validate()?;
+                    let cluster = dla::grow(300, 300, &params, cli.seed);
+                    stats.push(("particles", cluster.particles.len() as f64));
+                    stats.push(("box-counting dimension", cluster.fractal_dimension()));
+                    ("DLA cluster", cluster.generate())
+                }
+                name => {
+                    let steps = count.unwrap_or(50_000);
+                    check_count("count", steps, 10_000_000)?;
+                    let attractor = chaos::Attractor::from_name(name).unwrap_or(chaos::Attractor::Lorenz(Default::default()));
+                    let points = chaos::trajectory(&attractor, steps, chaos::Integrator::Rk4);
+                    stats.push(("steps", steps as f64));
+                    stats.push(("arc length", points.windows(2).map(|w| chaos::distance_3d(&w[0], &w[1])).sum()));
+                    stats.push(("largest lyapunov exponent", chaos::largest_lyapunov(&attractor, steps, chaos::Integrator::Rk4)));
+                    let title = match name {
+                        "rossler" => "Rössler attractor",
+                        "aizawa" => "Aizawa attractor",
+                        "halvorsen" => "Halvorsen attractor",
+                        "thomas" => "Thomas attractor",
+                        "chen" => "Chen attractor",
+                        _ => "Lorenz attractor",
+                    };
+                    (title, chaos::Orbit { attractor, steps }.generate())
+                }
+            };
+            let report = mathatura::metrics::measure(&geometry);
+            stats.push(("raster dimension", report.fractal_dimension));
+            stats.push(("entropy", report.entropy));
+            stats.push(("symmetry order", report.symmetry_order as f64));
+            stats.push(("symmetry score", report.symmetry_score));
+            stats.push(("coverage", report.coverage));
+            stats.push(("aesthetic score", report.score()));
+            print_stats(cli, title, &stats)?;
+            return Ok(());
         }
         Commands::Web { ref dir } => {
             println!("Web gallery files are in the '{}' directory.", dir.display());
             println!("Open web/index.html in a browser to explore!");
-            return;
+            return Ok(());
         }
     };
 
     if let Commands::Web { .. } = cli.command {
-        return;
+        return Ok(());
+    }
+    if cli.format != "svg" {
+        return Err(no_data_export(cli));
     }
 
-    fs::write(&cli.output, &svg).expect("Failed to write output file");
+    fs::write(&cli.output, &svg)?;
     println!("✨ Generated {} ({} bytes)", cli.output.display(), svg.len());
+    Ok(())
+}
+
+/// Write the numbers behind a figure when `--format` asks for data instead
+/// of SVG, returning whether it did. A default `.svg` output path takes the
+/// format's extension.
+fn write_table<T: export::Table + ?Sized>(cli: &Cli, table: &T) -> Result<bool> {
+    let text = match cli.format.as_str() {
+        "json" => export::to_json(table),
+        "csv" => export::to_csv(table),
+        _ => return Ok(false),
+    };
+    let path = if cli.output.extension().is_some_and(|e| e.eq_ignore_ascii_case("svg")) {
+        cli.output.with_extension(&cli.format)
+    } else {
+        cli.output.clone()
+    };
+    fs::write(&path, &text)?;
+    println!("✨ Exported {} ({} bytes)", path.display(), text.len());
+    Ok(true)
+}
+
+/// Write the pixels from `rgb` as a PNG when `path` ends in `.png`,
+/// returning whether it did.
+fn write_png(path: &Path, width: usize, height: usize, rgb: impl FnOnce() -> Vec<u8>) -> Result<bool> {
+    if !path.extension().is_some_and(|e| e.eq_ignore_ascii_case("png")) {
+        return Ok(false);
+    }
+    #[cfg(feature = "image")]
+    {
+        mathatura::image::save_rgb_png(path, width, height, &rgb())?;
+        Ok(true)
+    }
+    #[cfg(not(feature = "image"))]
+    {
+        let _ = (path, width, height, rgb);
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "PNG output needs the `image` feature").into())
+    }
+}
+
+/// The `--colors` gradient, if given.
+fn gradient(cli: &Cli) -> Result<Option<Gradient>> {
+    let interpolation = Interpolation::from_name(&cli.color_space).unwrap_or(Interpolation::Oklab);
+    cli.colors.as_deref().map(|stops| Gradient::parse(stops, interpolation)).transpose()
+}
+
+/// The theme's palette, which `--colors` replaces, when it has one,
+/// otherwise the named colormap.
+fn palette(theme: &Theme, colormap: &str) -> Box<dyn Colormap> {
+    match &theme.palette {
+        Some(gradient) => Box::new(gradient.clone()),
+        None => Box::new(colormap::by_name(colormap).unwrap_or(colormap::MAGMA)),
+    }
+}
+
+/// Argument parser accepting plain decimals such as `-0.7436438870371587047521915`,
+/// kept as text so no digits are lost before a deep zoom reads them.
+fn decimal(text: &str) -> std::result::Result<String, String> {
+    Fixed::parse(text, 0).map(|_| text.to_string()).map_err(|e| e.to_string())
+}
+
+/// Print `analyze` statistics as aligned text, or as one JSON object or
+/// `statistic,value` CSV rows when `--format` asks for data.
+fn print_stats(cli: &Cli, title: &str, stats: &[(&str, f64)]) -> Result<()> {
+    let whole = |v: f64| v.fract() == 0.0 && v.abs() < 1e15;
+    match cli.format.as_str() {
+        "json" => {
+            let mut object = serde_json::Map::new();
+            object.insert("pattern".into(), title.into());
+            for &(name, value) in stats {
+                let value = if whole(value) { serde_json::Value::from(value as i64) } else { serde_json::Value::from(value) };
+                object.insert(name.replace([' ', '-'], "_"), value);
+            }
+            println!("{:#}", serde_json::Value::Object(object));
+        }
+        "csv" => {
+            println!("statistic,value");
+            for &(name, value) in stats {
+                println!("{},{value}", name.replace([' ', '-'], "_"));
+            }
+        }
+        _ => {
+            println!("{title}");
+            let width = stats.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+            for &(name, value) in stats {
+                let value = if whole(value) { format!("{}", value as i64) } else { format!("{value:.4}") };
+                println!("  {name:<width$}  {value}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Report a recurrence plot's headline measures.
+fn print_recurrence(plot: &chaos::RecurrencePlot) {
+    println!("Recurrence rate {:.1}%, determinism {:.1}%", 100.0 * plot.recurrence_rate(), 100.0 * plot.determinism(2));
+}
+
+/// Box-counting dimension of a point set, over boxes from half its extent
+/// down to 1/256 of it.
+fn point_dimension(points: &[fractals::Point]) -> f64 {
+    let (min_x, max_x) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.x), hi.max(p.x)));
+    let (min_y, max_y) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+    let extent = (max_x - min_x).max(max_y - min_y);
+    if !extent.is_finite() || extent <= 0.0 {
+        return 0.0;
+    }
+    let sizes: Vec<f64> = (1..=8).map(|k| extent / 2f64.powi(k)).collect();
+    fractals::estimate_dimension(&fractals::box_counting_dimension(points, &sizes))
+}
+
+/// Error for a `--format` the chosen figure has no data for.
+fn no_data_export(cli: &Cli) -> MathaturaError {
+    MathaturaError::Unsupported { name: "format", value: cli.format.clone(), expected: "svg, as it has no data to export" }
+}
+
+/// Progress hook drawing a bar on stderr, if stderr is a terminal.
+fn progress_bar(label: &'static str) -> impl FnMut(usize, usize) -> bool {
+    let tty = std::io::stderr().is_terminal();
+    let mut shown = usize::MAX;
+    move |done, total| {
+        let percent = done * 100 / total.max(1);
+        if tty && percent != shown {
+            shown = percent;
+            let filled = percent * 30 / 100;
+            eprint!("\r{label} [{}{}] {percent:3}%", "█".repeat(filled), "░".repeat(30 - filled));
+            if done >= total {
+                eprintln!();
+            }
+        }
+        true
+    }
+}
+
+/// Numbered sibling of the output path, e.g. `out.svg` → `out_0003.svg`.
+fn frame_path(output: &Path, index: usize) -> PathBuf {
+    let stem = output.file_stem().and_then(|s| s.to_str()).unwrap_or("frame");
+    let ext = output.extension().and_then(|s| s.to_str()).unwrap_or("svg");
+    output.with_file_name(format!("{stem}_{index:04}.{ext}"))
 }
@@ -0,0 +1,300 @@
+//! Metrics — scoring generated output on what makes it pleasing to look at.
+//!
+//! Any [`Geometry`] is rasterized onto a density field — points, curve
+//! samples and circle outlines accumulate on a square raster, grids are taken as they are — and
+//! measured: box-counting fractal dimension of the covered cells, Shannon
+//! entropy of the rendered tones, rotational and mirror symmetry, tonal
+//! variance, and how evenly the mass sits around the center.
+//! [`MetricsReport::score`] folds these into one number for ranking random
+//! parameter draws against each other.
+
+use core::f64::consts::TAU;
+
+use crate::prelude::*;
+use crate::analysis;
+use crate::categories::fractals::{box_counting_dimension_raster, estimate_dimension, raster_box_sizes, Point};
+use crate::geometry::{Geometry, Shape};
+
+/// Side of the square raster that points and curves are binned into.
+pub const RESOLUTION: usize = 128;
+
+/// Sides of the polygon that stands in for a circle's outline.
+const CIRCLE_SIDES: usize = 24;
+
+/// Histogram bins for the entropy of the rendered field.
+const ENTROPY_BINS: usize = 16;
+
+/// Rotation score a symmetry order must reach. Generated geometry is
+/// exact, so a true symmetry matches almost perfectly; the looser
+/// [`analysis::SYMMETRY_THRESHOLD`], meant for noisy point sets, passes a
+/// sunflower's 55 parastichies off as 11-fold symmetry.
+const ORDER_THRESHOLD: f64 = 0.98;
+
+/// Structured scores for one generated output.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MetricsReport {
+    /// Box-counting dimension of the covered cells, 0–2
+    pub fractal_dimension: f64,
+    /// Shannon entropy of the tone histogram, normalized to [0, 1]
+    pub entropy: f64,
+    /// Highest n-fold rotational symmetry (1 = none)
+    pub symmetry_order: usize,
+    /// Combined rotation and mirror symmetry score in [0, 1]
+    pub symmetry_score: f64,
+    /// Variance of the rendered tones (0–1), at most 0.25
+    pub color_variance: f64,
+    /// 1 when the mass centroid is at the center, 0 at a corner
+    pub density_balance: f64,
+    /// Fraction of cells covered
+    pub coverage: f64,
+}
+
+impl MetricsReport {
+    /// One aesthetic score in [0, 1]: the mean of how close the dimension
+    /// is to 1.5 (between a curve and a fill, where natural forms tend to
+    /// sit), entropy, symmetry, and balance.
+    pub fn score(&self) -> f64 {
+        let dimension = 1.0 - ((self.fractal_dimension - 1.5).abs() / 1.5).min(1.0);
+        (dimension + self.entropy + self.symmetry_score + self.density_balance) / 4.0
+    }
+}
+
+/// A rendered field: tones in [0, 1] and which cells count as covered.
+struct Field {
+    cols: usize,
+    rows: usize,
+    tones: Vec<f64>,
+    covered: Vec<bool>,
+}
+
+fn rasterize(geometry: &Geometry) -> Field {
+    if let Geometry::Grid { cols, rows, values } = geometry {
+        let (lo, hi) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        let range = (hi - lo).max(1e-12);
+        let tones: Vec<f64> = values.iter().map(|v| (v - lo) / range).collect();
+        let covered = tones.iter().map(|&t| t > 0.5).collect();
+        return Field { cols: *cols, rows: *rows, tones, covered };
+    }
+    let n = RESOLUTION;
+    let mut counts = vec![0.0f64; n * n];
+    if let Some((lo, hi)) = geometry.bounds() {
+        let extent = (hi.x - lo.x).max(hi.y - lo.y).max(1e-12);
+        // Center the shape on the raster
+        let (ox, oy) = ((extent - (hi.x - lo.x)) / 2.0 - lo.x, (extent - (hi.y - lo.y)) / 2.0 - lo.y);
+        let to_cell = |p: &Point| ((p.x + ox) / extent * (n - 1) as f64, (p.y + oy) / extent * (n - 1) as f64);
+        let mut plot = |x: f64, y: f64| counts[y.round() as usize * n + x.round() as usize] += 1.0;
+        let mut trace = |line: &[Point], close: bool| {
+            let closing = if close && line.len() > 2 { line.first() } else { None };
+            for (a, b) in line.iter().zip(line.iter().skip(1).chain(closing)) {
+                let ((x0, y0), (x1, y1)) = (to_cell(a), to_cell(b));
+                // Two samples per cell crossed
+                let steps = ((x1 - x0).hypot(y1 - y0) * 2.0).ceil().max(1.0) as usize;
+                for s in 0..steps {
+                    let t = s as f64 / steps as f64;
+                    plot(x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+                }
+            }
+            if let Some(p) = line.last().filter(|_| closing.is_none()) {
+                let (x, y) = to_cell(p);
+                plot(x, y);
+            }
+        };
+        geometry.for_each_shape(&mut |shape| match shape {
+            Shape::Point(p) => trace(&[p], false),
+            Shape::Polyline(line) => trace(line, false),
+            Shape::Polygon(line) => trace(line, true),
+            Shape::Circle(c) => {
+                let outline: Vec<Point> = (0..CIRCLE_SIDES)
+                    .map(|k| {
+                        let t = k as f64 * TAU / CIRCLE_SIDES as f64;
+                        Point { x: c.center.x + c.radius * t.cos(), y: c.center.y + c.radius * t.sin() }
+                    })
+                    .collect();
+                trace(&outline, true)
+            }
+        });
+    }
+    // Log scale keeps dense chaos-game cores from washing out the rest
+    let max = counts.iter().copied().fold(0.0, f64::max);
+    let tones = counts.iter().map(|&c| if max > 0.0 { (1.0 + c).ln() / (1.0 + max).ln() } else { 0.0 }).collect();
+    let covered = counts.iter().map(|&c| c > 0.0).collect();
+    Field { cols: n, rows: n, tones, covered }
+}
+
+/// Box-counting dimension of the covered cells over power-of-two box sizes.
+fn raster_dimension(field: &Field) -> f64 {
+    let sizes = raster_box_sizes(field.cols, field.rows);
+    estimate_dimension(&box_counting_dimension_raster(&field.covered, field.cols, &sizes)).clamp(0.0, 2.0)
+}
+
+/// Shannon entropy of the tone histogram, divided by its maximum.
+fn entropy(tones: &[f64]) -> f64 {
+    if tones.is_empty() {
+        return 0.0;
+    }
+    let mut bins = [0usize; ENTROPY_BINS];
+    for &t in tones {
+        bins[((t * ENTROPY_BINS as f64) as usize).min(ENTROPY_BINS - 1)] += 1;
+    }
+    let n = tones.len() as f64;
+    let h: f64 = bins.iter().filter(|&&c| c > 0).map(|&c| -(c as f64 / n) * (c as f64 / n).log2()).sum();
+    h / (ENTROPY_BINS as f64).log2()
+}
+
+/// Centroid of the tone mass relative to the center, as 1 − offset.
+fn balance(field: &Field) -> f64 {
+    let (mut mass, mut mx, mut my) = (0.0, 0.0, 0.0);
+    for (k, &t) in field.tones.iter().enumerate() {
+        mass += t;
+        mx += t * (k % field.cols) as f64;
+        my += t * (k / field.cols) as f64;
+    }
+    if mass <= 0.0 {
+        return 0.0;
+    }
+    let (cx, cy) = ((field.cols - 1) as f64 / 2.0, (field.rows - 1) as f64 / 2.0);
+    let reach = cx.hypot(cy).max(1e-12);
+    1.0 - ((mx / mass - cx).hypot(my / mass - cy) / reach).min(1.0)
+}
+
+/// Points to test for symmetry: the geometry's own, or covered grid cells.
+///
+/// All of them: a strided subset keeps a point but not its rotated image,
+/// and [`analysis`] already caps how many it queries. Repeats, such as a
+/// closed outline's last vertex, are dropped so they don't pull the
+/// centroid off the center of rotation.
+fn symmetry_points(geometry: &Geometry, field: &Field) -> Vec<Point> {
+    let mut points: Vec<Point> = match geometry {
+        Geometry::Grid { .. } => (0..field.tones.len())
+            .filter(|&k| field.covered[k])
+            .map(|k| Point { x: (k % field.cols) as f64, y: (k / field.cols) as f64 })
+            .collect(),
+        other => other.vertices(),
+    };
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    points
+}
+
+/// Rotational order and combined symmetry score, as in
+/// [`analysis::analyze_symmetry`] but without refining mirror axes, which
+/// dominates the cost on asymmetric sets and barely moves the score.
+fn symmetry(points: &[Point]) -> (usize, f64) {
+    if points.len() < 3 {
+        return (1, 0.0);
+    }
+    let rotations = analysis::rotational_scores(points, 12);
+    let order = rotations.iter().rfind(|&&(_, s)| s >= ORDER_THRESHOLD).map_or(1, |&(n, _)| n);
+    let rotation = rotations.iter().map(|&(_, s)| s).fold(0.0, f64::max);
+    let mirror = analysis::mirror_scores(points, 180).iter().map(|&(_, s)| s).fold(0.0, f64::max);
+    (order, ((rotation + mirror) / 2.0).clamp(0.0, 1.0))
+}
+
+/// Measure any generated output.
+pub fn measure(geometry: &Geometry) -> MetricsReport {
+    let field = rasterize(geometry);
+    let points = symmetry_points(geometry, &field);
+    let (symmetry_order, symmetry_score) = symmetry(&points);
+    let n = field.tones.len().max(1) as f64;
+    let mean = field.tones.iter().sum::<f64>() / n;
+    MetricsReport {
+        fractal_dimension: raster_dimension(&field),
+        entropy: entropy(&field.tones),
+        symmetry_order,
+        symmetry_score,
+        color_variance: field.tones.iter().map(|t| (t - mean) * (t - mean)).sum::<f64>() / n,
+        density_balance: balance(&field),
+        coverage: field.covered.iter().filter(|&&c| c).count() as f64 / n,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f64::consts::PI;
+
+    fn circle(n: usize, r: f64) -> Vec<Point> {
+        (0..n).map(|i| 2.0 * PI * i as f64 / n as f64).map(|a| Point { x: r * a.cos(), y: r * a.sin() }).collect()
+    }
+
+    #[test]
+    fn test_line_and_fill_dimensions() {
+        let line = Geometry::Polylines(vec![vec![Point { x: 0.0, y: 0.0 }, Point { x: 10.0, y: 10.0 }]]);
+        let d = measure(&line).fractal_dimension;
+        assert!((d - 1.0).abs() < 0.1, "line dimension {}", d);
+        let square = |k: usize| (16..48).contains(&(k % 64)) && (16..48).contains(&(k / 64));
+        let filled = Geometry::Grid { cols: 64, rows: 64, values: (0..4096).map(|k| square(k) as u8 as f64).collect() };
+        let d = measure(&filled).fractal_dimension;
+        assert!(d > 1.8, "fill dimension {}", d);
+    }
+
+    #[test]
+    fn test_entropy_and_variance() {
+        let flat = Geometry::Grid { cols: 8, rows: 8, values: vec![1.0; 64] };
+        let report = measure(&flat);
+        assert_eq!(report.entropy, 0.0);
+        assert_eq!(report.color_variance, 0.0);
+        let ramp = Geometry::Grid { cols: 16, rows: 16, values: (0..256).map(|k| k as f64).collect() };
+        let report = measure(&ramp);
+        assert!((report.entropy - 1.0).abs() < 1e-9);
+        assert!((report.color_variance - 1.0 / 12.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_symmetric_and_balanced() {
+        let hexagon = Geometry::Polygons(vec![circle(6, 10.0)]);
+        let report = measure(&hexagon);
+        assert_eq!(report.symmetry_order, 6);
+        assert!(report.symmetry_score > 0.9);
+        assert!(report.density_balance > 0.95);
+    }
+
+    #[test]
+    fn test_symmetry_order_of_generated_figures() {
+        // 3072 vertices: every one must stay, with its five rotated images
+        let koch = Geometry::Polygons(vec![crate::categories::fractals::checked_koch_snowflake(5).unwrap()]);
+        assert_eq!(measure(&koch).symmetry_order, 6);
+        // Golden-angle spirals nearly match themselves a 55th of a turn on,
+        // but have no rotational symmetry
+        let sunflower = crate::geometry::Generator::generate(&crate::categories::phyllotaxis::Params::default());
+        assert_eq!(measure(&sunflower).symmetry_order, 1);
+    }
+
+    #[test]
+    fn test_off_center_mass_is_unbalanced() {
+        // A dense cluster in one corner plus a lone point opposite
+        let mut points: Vec<Point> = (0..200).map(|i| Point { x: (i % 5) as f64 * 0.1, y: (i / 5 % 5) as f64 * 0.1 }).collect();
+        points.push(Point { x: 10.0, y: 10.0 });
+        let report = measure(&Geometry::Points(points));
+        assert!(report.density_balance < 0.6, "balance {}", report.density_balance);
+        assert!(report.coverage < 0.01);
+    }
+
+    #[test]
+    fn test_score_ranks_structure_above_noise() {
+        let fern = crate::geometry::Generator::generate(&crate::categories::fractals::Fractal::Fern { points: 20_000, seed: 1 });
+        let empty = Geometry::Points(Vec::new());
+        let report = measure(&fern);
+        assert!(report.fractal_dimension > 1.2 && report.fractal_dimension < 2.0);
+        assert!(report.score() > measure(&empty).score());
+        assert!((0.0..=1.0).contains(&report.score()));
+    }
+}
+#[cfg(test)]
+mod scratch {
+    use super::*;
+    use crate::geometry::Generator;
+    #[test]
+    fn scratch_scores() {
+        let phy = crate::categories::phyllotaxis::Params { count: 500, ..Default::default() }.generate();
+        let koch = crate::geometry::Geometry::Polygons(vec![crate::categories::fractals::checked_koch_snowflake(5).unwrap()]);
+        for (name, g) in [("phy", phy), ("koch", koch)] {
+            let pts = g.vertices();
+            let field = rasterize(&g);
+            let cells: Vec<Point> = (0..field.tones.len()).filter(|&k| field.covered[k]).map(|k| Point { x: (k % field.cols) as f64, y: (k / field.cols) as f64 }).collect();
+            eprintln!("{name} {} verts: {:?}", pts.len(), analysis::rotational_scores(&pts, 12).iter().map(|s| (s.0, (s.1*100.0).round())).collect::<Vec<_>>());
+            eprintln!("{name} {} cells: {:?}", cells.len(), analysis::rotational_scores(&cells, 12).iter().map(|s| (s.0, (s.1*100.0).round())).collect::<Vec<_>>());
+        }
+    }
+}
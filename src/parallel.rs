@@ -0,0 +1,75 @@
+//! Parallel — optional multithreading for embarrassingly parallel loops.
+//!
+//! With the `parallel` feature these helpers spread independent work (grid
+//! cells, pixels, per-point searches) across threads with rayon; without it
+//! they run sequentially. Results always come back in index order and any
+//! reductions happen afterwards on a single thread, so the output is
+//! bit-for-bit identical either way.
+
+use crate::prelude::*;
+
+/// Evaluate `f(i)` for every `i` in `0..n`, returning results in index order.
+pub fn map_range<T, F>(n: usize, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        (0..n).into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        (0..n).map(f).collect()
+    }
+}
+
+/// Fill a row-major buffer one row at a time: `f(y, row)` writes row `y`.
+pub fn for_each_row<T, F>(buffer: &mut [T], width: usize, f: F)
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Sync + Send,
+{
+    let width = width.max(1);
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        buffer.par_chunks_mut(width).enumerate().for_each(|(y, row)| f(y, row));
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        buffer.chunks_mut(width).enumerate().for_each(|(y, row)| f(y, row));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_range_keeps_order() {
+        let squares = map_range(10_000, |i| i * i);
+        assert_eq!(squares.len(), 10_000);
+        assert!(squares.iter().enumerate().all(|(i, &s)| s == i * i));
+        assert!(map_range(0, |i| i).is_empty());
+    }
+
+    #[test]
+    fn test_for_each_row() {
+        let mut buffer = vec![0usize; 12];
+        for_each_row(&mut buffer, 4, |y, row| {
+            for (x, v) in row.iter_mut().enumerate() {
+                *v = 10 * y + x;
+            }
+        });
+        assert_eq!(buffer, vec![0, 1, 2, 3, 10, 11, 12, 13, 20, 21, 22, 23]);
+    }
+
+    #[test]
+    fn test_float_results_deterministic() {
+        // Per-index work is independent, so repeated runs agree exactly
+        let f = |i: usize| (i as f64 * 0.37).sin().powi(3);
+        assert_eq!(map_range(5000, f), (0..5000).map(f).collect::<Vec<_>>());
+    }
+}
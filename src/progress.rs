@@ -0,0 +1,97 @@
+//! Progress — reporting and cancelling long computations.
+//!
+//! Simulations with thousands of steps or chaos games with millions of points
+//! can run for a long time. Operations that accept a [`Progress`] call it at
+//! regular checkpoints with the work done so far; returning `false` stops the
+//! computation cleanly with [`MathaturaError::Cancelled`]. A closure
+//! `|done, total| -> bool` works as a progress hook.
+
+use crate::prelude::*;
+use crate::error::{MathaturaError, Result};
+
+/// Receives progress updates and decides whether to continue.
+pub trait Progress {
+    /// `done` of `total` units of work are complete. Return `false` to cancel.
+    fn update(&mut self, done: usize, total: usize) -> bool;
+}
+
+impl<F: FnMut(usize, usize) -> bool> Progress for F {
+    fn update(&mut self, done: usize, total: usize) -> bool {
+        self(done, total)
+    }
+}
+
+/// Ignores updates and never cancels.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Silent;
+
+impl Progress for Silent {
+    fn update(&mut self, _done: usize, _total: usize) -> bool {
+        true
+    }
+}
+
+/// Report `done` of `total`, turning a cancel into an error.
+pub fn checkpoint(progress: &mut impl Progress, done: usize, total: usize) -> Result<()> {
+    if progress.update(done, total) {
+        Ok(())
+    } else {
+        Err(MathaturaError::Cancelled)
+    }
+}
+
+/// Collect the first `total` items of an iterator, reporting about every 1%.
+pub fn collect_with_progress<I: Iterator>(iter: I, total: usize, progress: &mut impl Progress) -> Result<Vec<I::Item>> {
+    let chunk = (total / 100).max(1);
+    let mut items = Vec::with_capacity(total);
+    for item in iter.take(total) {
+        items.push(item);
+        if items.len() % chunk == 0 {
+            checkpoint(progress, items.len(), total)?;
+        }
+    }
+    checkpoint(progress, items.len(), total)?;
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closure_receives_updates() {
+        let mut seen = Vec::new();
+        let items = collect_with_progress(0..1000, 1000, &mut |done, total| {
+            seen.push((done, total));
+            true
+        })
+        .unwrap();
+        assert_eq!(items.len(), 1000);
+        assert_eq!(seen.first(), Some(&(10, 1000)));
+        assert_eq!(seen.last(), Some(&(1000, 1000)));
+        assert!(seen.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
+    #[test]
+    fn test_cancel_stops_early() {
+        let mut pulled = 0;
+        let iter = (0..).inspect(|_| pulled += 1);
+        let result = collect_with_progress(iter, 1_000_000, &mut |done, _| done < 50_000);
+        assert!(matches!(result, Err(MathaturaError::Cancelled)));
+        assert_eq!(pulled, 50_000);
+    }
+
+    #[test]
+    fn test_short_input() {
+        // Fewer items than requested still ends with a final report
+        let mut last = None;
+        let items = collect_with_progress(0..5, 100, &mut |done, total| {
+            last = Some((done, total));
+            true
+        })
+        .unwrap();
+        assert_eq!(items, vec![0, 1, 2, 3, 4]);
+        assert_eq!(last, Some((5, 100)));
+        assert!(collect_with_progress(0..5, 5, &mut Silent).is_ok());
+    }
+}
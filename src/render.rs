@@ -1,13 +1,39 @@
-//! Shared SVG rendering utilities.
+//! Shared SVG rendering utilities, the [`canvas`] primitives they draw
+//! with, and a [`terminal`] backend for previews.
+
+use crate::prelude::*;
+use crate::categories::chaos::Point3D;
+use crate::constants::GOLDEN_ANGLE_DEG;
+
+pub mod canvas;
+pub mod colormap;
+pub mod gradient;
+pub mod mesh;
+pub mod terminal;
+pub mod theme;
+
+use canvas::{Canvas, Dots, SvgCanvas};
+use colormap::Colormap;
+use theme::Theme;
+use crate::categories::fractals::Point;
+use crate::geometry::Style;
 
 /// Wrap content in an SVG document.
 pub fn svg_document(width: u32, height: u32, content: &str) -> String {
+    svg_document_with_theme(width, height, content, &Theme::dark())
+}
+
+/// [`svg_document`] on `theme`'s background, `content` at its opacity.
+pub fn svg_document_with_theme(width: u32, height: u32, content: &str, theme: &Theme) -> String {
     format!(
         r##"<?xml version="1.0" encoding="UTF-8"?>
 <svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">
-<rect width="{width}" height="{height}" fill="#0a0a1a"/>
-{content}
-</svg>"##
+<rect width="{width}" height="{height}" fill="{}"/>
+{}{content}
+{}</svg>"##,
+        theme.background_css(),
+        theme.foreground_start(),
+        theme.foreground_end()
     )
 }
 
@@ -16,22 +42,186 @@ pub fn hsl(h: f64, s: f64, l: f64) -> String {
     format!("hsl({:.0},{:.0}%,{:.0}%)", h % 360.0, s.clamp(0.0, 100.0), l.clamp(0.0, 100.0))
 }
 
-/// Map a value 0..1 to a viridis-like color.
+/// Hue in degrees of the `i`-th color of a golden-angle sequence from `start`.
+///
+/// Stepping by the golden angle puts each new hue in the largest remaining
+/// gap, so any prefix of the sequence is close to evenly spread — the same
+/// reason sunflower seeds pack without gaps.
+pub fn golden_hue(start: f64, i: usize) -> f64 {
+    (start + i as f64 * GOLDEN_ANGLE_DEG).rem_euclid(360.0)
+}
+
+/// `n` maximally distinct colors: the default categorical palette for
+/// renders with several families of curves.
+pub fn golden_hues(n: usize) -> Vec<String> {
+    golden_hues_cycling(n, 30.0, &[75.0], &[60.0])
+}
+
+/// Golden-angle hues with saturation and lightness cycling through the
+/// given levels, so long palettes stay distinguishable. Empty level lists
+/// fall back to 75% saturation and 60% lightness.
+pub fn golden_hues_cycling(n: usize, start: f64, saturations: &[f64], lightnesses: &[f64]) -> Vec<String> {
+    let level = |levels: &[f64], i: usize, default: f64| if levels.is_empty() { default } else { levels[i % levels.len()] };
+    (0..n)
+        .map(|i| hsl(golden_hue(start, i), level(saturations, i, 75.0), level(lightnesses, i, 60.0)))
+        .collect()
+}
+
+/// Map a value 0..1 to a [`colormap::VIRIDIS`] color string.
 pub fn viridis(t: f64) -> String {
-    let t = t.clamp(0.0, 1.0);
-    let r = (68.0 + t * 187.0).min(255.0) as u8;
-    let g = (1.0 + t * 180.0 + (1.0 - t) * 40.0).min(255.0) as u8;
-    let b = (84.0 + (1.0 - t) * 140.0 + t * 20.0).min(255.0) as u8;
-    format!("rgb({r},{g},{b})")
+    colormap::VIRIDIS.css(t)
 }
 
-/// Map a value 0..1 to a magma-like color.
+/// [`viridis`] as RGB bytes, for raster output.
+pub fn viridis_rgb(t: f64) -> [u8; 3] {
+    colormap::VIRIDIS.rgb(t)
+}
+
+/// Map a value 0..1 to a [`colormap::MAGMA`] color string.
 pub fn magma(t: f64) -> String {
-    let t = t.clamp(0.0, 1.0);
-    let r = (t * 255.0).min(255.0) as u8;
-    let g = (t * t * 180.0).min(255.0) as u8;
-    let b = (80.0 + t * 100.0).min(255.0) as u8;
-    format!("rgb({r},{g},{b})")
+    colormap::MAGMA.css(t)
+}
+
+/// [`magma`] as RGB bytes, for raster output.
+pub fn magma_rgb(t: f64) -> [u8; 3] {
+    colormap::MAGMA.rgb(t)
+}
+
+/// Render a row-major grid of values as square pixels of size `cell`.
+///
+/// Horizontal runs that map to the same color are merged into a single
+/// `<rect>`, which keeps smooth or sparse fields compact.
+pub fn raster_svg(cols: usize, rows: usize, cell: f64, values: &[f64], color: impl Fn(f64) -> String) -> String {
+    raster_svg_with_theme(cols, rows, cell, values, color, &Theme::dark())
+}
+
+/// [`raster_svg`] on `theme`'s background.
+pub fn raster_svg_with_theme(cols: usize, rows: usize, cell: f64, values: &[f64], color: impl Fn(f64) -> String, theme: &Theme) -> String {
+    let mut canvas = SvgCanvas::new((cols as f64 * cell).ceil(), (rows as f64 * cell).ceil()).with_theme(theme);
+    canvas::fill_raster(&mut canvas, cols, rows, cell, values, color);
+    canvas.finish()
+}
+
+/// Plot a 2D point cloud as dots, scaled uniformly so its bounding box fills
+/// a frame whose longer side is `size` pixels, 20 of them margin; the
+/// shorter side shrinks to fit. `y` points up.
+///
+/// `color(i, u, v)` colors point `i` from its place in the box, `u` running
+/// 0 → 1 left to right and `v` bottom to top. Non-finite points are skipped.
+pub fn point_cloud_svg(points: &[(f64, f64)], size: f64, radius: f64, color: impl Fn(usize, f64, f64) -> String) -> String {
+    point_cloud_svg_with_dots(points, size, radius, Dots::Circles, color)
+}
+
+/// [`point_cloud_svg`], its dots written as `dots` says.
+pub fn point_cloud_svg_with_dots(points: &[(f64, f64)], size: f64, radius: f64, dots: Dots, color: impl Fn(usize, f64, f64) -> String) -> String {
+    point_cloud_svg_with_theme(points, size, radius, dots, color, &Theme::dark())
+}
+
+/// [`point_cloud_svg_with_dots`] on `theme`'s background. With a palette,
+/// point `i` takes it at its index in place of `color`.
+pub fn point_cloud_svg_with_theme(
+    points: &[(f64, f64)],
+    size: f64,
+    radius: f64,
+    dots: Dots,
+    color: impl Fn(usize, f64, f64) -> String,
+    theme: &Theme,
+) -> String {
+    let finite = || points.iter().enumerate().filter(|(_, p)| p.0.is_finite() && p.1.is_finite());
+    let (mut x0, mut x1, mut y0, mut y1) = (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+    for (_, &(x, y)) in finite() {
+        (x0, x1, y0, y1) = (x0.min(x), x1.max(x), y0.min(y), y1.max(y));
+    }
+    if x0 > x1 {
+        return svg_document_with_theme(size as u32, size as u32, "", theme);
+    }
+    let margin = 20.0;
+    let (span_x, span_y) = ((x1 - x0).max(1e-9), (y1 - y0).max(1e-9));
+    let scale = (size - 2.0 * margin) / span_x.max(span_y);
+    let (width, height) = (span_x * scale + 2.0 * margin, span_y * scale + 2.0 * margin);
+    let mut canvas = SvgCanvas::new(width, height).with_dots(dots).with_theme(theme);
+    for (i, &(x, y)) in finite() {
+        let (u, v) = ((x - x0) / span_x, (y - y0) / span_y);
+        let center = Point { x: margin + (x - x0) * scale, y: height - margin - (y - y0) * scale };
+        canvas.circle(center, radius, &Style::fill(theme.color(theme::place(i, points.len()), color(i, u, v))).with_opacity(0.7));
+    }
+    canvas.finish()
+}
+
+/// Lambertian hillshade of a row-major height grid, in [0, 1].
+///
+/// Light comes from `azimuth` (radians, clockwise from north, i.e. up the
+/// grid) at `altitude` above the horizon; `z_scale` converts heights to cell
+/// units. Edge cells use one-sided differences.
+pub fn hillshade(heights: &[f64], cols: usize, rows: usize, z_scale: f64, azimuth: f64, altitude: f64) -> Vec<f64> {
+    let h = |x: usize, y: usize| heights[y * cols + x] * z_scale;
+    let (lx, ly, lz) = (azimuth.sin() * altitude.cos(), -azimuth.cos() * altitude.cos(), altitude.sin());
+    (0..cols * rows)
+        .map(|k| {
+            let (x, y) = (k % cols, k / cols);
+            let (x0, x1) = (x.saturating_sub(1), (x + 1).min(cols - 1));
+            let (y0, y1) = (y.saturating_sub(1), (y + 1).min(rows - 1));
+            let dzdx = (h(x1, y) - h(x0, y)) / (x1 - x0).max(1) as f64;
+            let dzdy = (h(x, y1) - h(x, y0)) / (y1 - y0).max(1) as f64;
+            // Surface normal (−∂z/∂x, −∂z/∂y, 1), normalized
+            let norm = (dzdx * dzdx + dzdy * dzdy + 1.0).sqrt();
+            ((-dzdx * lx - dzdy * ly + lz) / norm).max(0.0)
+        })
+        .collect()
+}
+
+/// How 3D scenes are flattened onto the page. World z is up.
+///
+/// Views are given by the camera's azimuth (degrees from +x towards +y) and
+/// elevation (degrees above the xy-plane), looking at the scene's center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Projection {
+    /// Parallel projection with the three axes equally foreshortened
+    Isometric,
+    /// Parallel projection from any direction
+    Orthographic { azimuth: f64, elevation: f64 },
+    /// Pinhole camera `distance` scene radii from the center; nearer parts
+    /// appear larger
+    Perspective { azimuth: f64, elevation: f64, distance: f64 },
+}
+
+impl Projection {
+    /// Project a scene to (x right, y down, depth away from the viewer),
+    /// centered on the origin in scene units.
+    pub fn project(&self, points: &[Point3D]) -> Vec<[f64; 3]> {
+        let (azimuth, elevation, distance) = match *self {
+            // Elevation atan(1/√2) looks down the cube diagonal
+            Projection::Isometric => (45.0, 35.264_389_682_754_654, None),
+            Projection::Orthographic { azimuth, elevation } => (azimuth, elevation, None),
+            Projection::Perspective { azimuth, elevation, distance } => (azimuth, elevation, Some(distance.max(1.01))),
+        };
+        let (sa, ca) = azimuth.to_radians().sin_cos();
+        let (se, ce) = elevation.to_radians().sin_cos();
+        // Toward the camera, and the screen's right and up directions
+        let eye = [ce * ca, ce * sa, se];
+        let right = [-sa, ca, 0.0];
+        let up = [-se * ca, -se * sa, ce];
+        let n = points.len().max(1) as f64;
+        let center = points.iter().fold([0.0; 3], |c, p| [c[0] + p.x / n, c[1] + p.y / n, c[2] + p.z / n]);
+        let radius = points.iter().map(|p| (p.x - center[0]).hypot(p.y - center[1]).hypot(p.z - center[2])).fold(1e-9, f64::max);
+        let dot = |v: [f64; 3], p: &Point3D| v[0] * (p.x - center[0]) + v[1] * (p.y - center[1]) + v[2] * (p.z - center[2]);
+        points
+            .iter()
+            .map(|p| {
+                let (x, y, toward) = (dot(right, p), dot(up, p), dot(eye, p));
+                match distance {
+                    Some(d) => {
+                        // Perspective divide, normalized so the center keeps its scale
+                        let depth = (d * radius - toward).max(1e-9 * radius);
+                        let f = d * radius / depth;
+                        [x * f, -y * f, depth]
+                    }
+                    None => [x, -y, -toward],
+                }
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -51,6 +241,31 @@ mod tests {
         assert_eq!(hsl(120.0, 50.0, 50.0), "hsl(120,50%,50%)");
     }
 
+    #[test]
+    fn test_golden_hues_spread() {
+        let hues: Vec<f64> = (0..8).map(|i| golden_hue(0.0, i)).collect();
+        assert!((hues[1] - GOLDEN_ANGLE_DEG).abs() < 1e-9);
+        // Any prefix is well spread: no two of the first 8 hues within 20°
+        for (i, a) in hues.iter().enumerate() {
+            for b in &hues[i + 1..] {
+                let d = (a - b).abs();
+                assert!(d.min(360.0 - d) > 20.0, "{} vs {}", a, b);
+            }
+        }
+        let palette = golden_hues(5);
+        assert_eq!(palette.len(), 5);
+        assert_eq!(palette[0], "hsl(30,75%,60%)");
+    }
+
+    #[test]
+    fn test_golden_hues_cycling() {
+        let palette = golden_hues_cycling(4, 0.0, &[90.0, 50.0], &[]);
+        assert!(palette[0].ends_with(",90%,60%)"));
+        assert!(palette[1].ends_with(",50%,60%)"));
+        assert!(palette[2].ends_with(",90%,60%)"));
+        assert!(golden_hues(0).is_empty());
+    }
+
     #[test]
     fn test_viridis_bounds() {
         let c0 = viridis(0.0);
@@ -63,6 +278,9 @@ mod tests {
     fn test_magma_bounds() {
         let c = magma(0.5);
         assert!(c.starts_with("rgb("));
+        // matplotlib's endpoints: near-black to pale yellow
+        assert_eq!(magma_rgb(1.0), [252, 253, 191]);
+        assert_eq!(magma(0.0), "rgb(0,0,4)");
     }
 
     #[test]
@@ -71,4 +289,67 @@ mod tests {
         let _ = viridis(2.0);
         // Should not panic
     }
+
+    #[test]
+    fn test_raster_merges_runs() {
+        let values = [0.0, 0.0, 0.0, 1.0, 0.5, 0.5, 0.5, 0.5];
+        let svg = raster_svg(4, 2, 2.0, &values, viridis);
+        // Row 0: two runs, row 1: one run
+        assert_eq!(svg.matches("<rect").count(), 1 + 3);
+        assert!(svg.contains("width=\"6.0\""));
+    }
+
+    #[test]
+    fn test_projections() {
+        let axes = [
+            Point3D { x: 1.0, y: 0.0, z: 0.0 },
+            Point3D { x: 0.0, y: 1.0, z: 0.0 },
+            Point3D { x: 0.0, y: 0.0, z: 1.0 },
+            Point3D { x: 0.0, y: 0.0, z: 0.0 },
+        ];
+        // Seen from the front (looking along +y), x is to the right and z up
+        let front = Projection::Orthographic { azimuth: -90.0, elevation: 0.0 }.project(&axes);
+        assert!((front[0][0] - front[3][0] - 1.0).abs() < 1e-12);
+        assert!((front[2][1] - front[3][1] + 1.0).abs() < 1e-12);
+        assert!(front[1][2] > front[3][2], "+y is farther away");
+        // Isometric foreshortens the three axes equally
+        let iso = Projection::Isometric.project(&axes);
+        let lengths: Vec<f64> = iso[..3].iter().map(|p| (p[0] - iso[3][0]).hypot(p[1] - iso[3][1])).collect();
+        assert!(lengths.iter().all(|l| (l - lengths[0]).abs() < 1e-9), "{:?}", lengths);
+        // Under perspective the nearer of two equal offsets looks larger
+        let pair = [
+            Point3D { x: 1.0, y: 0.0, z: 1.0 },
+            Point3D { x: 1.0, y: 0.0, z: -1.0 },
+            Point3D { x: -1.0, y: 0.0, z: 1.0 },
+            Point3D { x: -1.0, y: 0.0, z: -1.0 },
+        ];
+        let seen = Projection::Perspective { azimuth: 0.0, elevation: 0.0, distance: 3.0 }.project(&pair);
+        assert!(seen[0][1].abs() > seen[2][1].abs() && seen[0][2] < seen[2][2]);
+    }
+
+    #[test]
+    fn test_point_cloud_fits_box() {
+        let points = [(-1.0, 0.0), (3.0, 2.0), (f64::NAN, 1.0), (1.0, 1.0)];
+        let svg = point_cloud_svg(&points, 420.0, 1.0, |i, u, v| format!("c{i}-{u}-{v}"));
+        // 4 × 2 box: the width fills 380 pixels plus margins, the height half that
+        assert!(svg.contains("width=\"420\" height=\"230\""));
+        assert!(svg.contains("cx=\"20.0\" cy=\"210.0\" r=\"1\" fill=\"c0-0-0\""));
+        assert!(svg.contains("cx=\"400.0\" cy=\"20.0\" r=\"1\" fill=\"c1-1-1\""));
+        assert!(svg.contains("fill=\"c3-0.5-0.5\""));
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert!(!point_cloud_svg(&[], 100.0, 1.0, |_, _, _| String::new()).contains("<circle"));
+    }
+
+    #[test]
+    fn test_hillshade_slopes() {
+        // A ramp rising to the east faces west: bright in western light, dark in eastern
+        let ramp: Vec<f64> = (0..25).map(|k| (k % 5) as f64).collect();
+        let west = -core::f64::consts::FRAC_PI_2;
+        let shade = hillshade(&ramp, 5, 5, 1.0, west, 0.7);
+        let flat = hillshade(&[0.0; 25], 5, 5, 1.0, west, 0.7);
+        assert!((flat[12] - 0.7f64.sin()).abs() < 1e-12);
+        assert!(shade[12] > flat[12]);
+        let east = hillshade(&ramp, 5, 5, 1.0, -west, 0.7);
+        assert!(east[12] < flat[12]);
+    }
 }
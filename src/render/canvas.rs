@@ -0,0 +1,382 @@
+//! Canvas — drawing primitives that every output format can provide.
+//!
+//! [`Backend`](crate::geometry::Backend) works a level up, on whole
+//! [`Geometry`] values. A [`Canvas`] is what such a backend draws on: six
+//! primitives (circle, line, open path, polygon, filled rectangle, text)
+//! in canvas units, x to the right and y down. [`SvgCanvas`] writes SVG
+//! elements; a PNG rasterizer, a terminal or a pen plotter only has to
+//! implement the same six calls, and [`draw`] then paints any generator's
+//! geometry onto it without the category knowing.
+//!
+//! Colors are SVG color strings, as in [`Style`].
+
+use crate::prelude::*;
+use crate::categories::fractals::Point;
+use crate::geometry::{default_style, fit, Geometry, Shape, Style};
+use crate::render::theme::{self, Theme};
+
+/// A surface that draws primitives.
+pub trait Canvas {
+    /// Width and height in canvas units.
+    fn size(&self) -> (f64, f64);
+
+    /// A disc, filled and stroked per `style`.
+    fn circle(&mut self, center: Point, radius: f64, style: &Style);
+
+    /// A straight segment in `style`'s stroke.
+    fn line(&mut self, from: Point, to: Point, style: &Style) {
+        self.path(&[from, to], style);
+    }
+
+    /// An open curve through `points`.
+    fn path(&mut self, points: &[Point], style: &Style);
+
+    /// A closed outline, the last point joining the first.
+    fn polygon(&mut self, points: &[Point], style: &Style);
+
+    /// An axis-aligned rectangle filled with `color`, unstroked.
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: &str);
+
+    /// A label with its baseline starting at `at`, `size` units tall.
+    fn text(&mut self, at: Point, text: &str, size: f64, color: &str);
+}
+
+/// How [`SvgCanvas`] writes filled, unstroked discs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dots {
+    /// A `<circle>` each
+    #[default]
+    Circles,
+    /// One `<path>` per look for each run of consecutive dots, every dot a
+    /// zero-length, round-capped segment `2r` wide — the trick
+    /// [`Svg`](crate::geometry::Svg) uses for points. Moves are relative, in
+    /// tenths of a unit, and run row by row, so a 50 000-point fern shrinks
+    /// about eightfold. Overlapping translucent dots of one path no longer
+    /// darken each other, and a run's dots are regrouped by look, so
+    /// stacking between colors can change.
+    Batched,
+}
+
+/// Radius, fill and opacity shared by a batch of dots.
+type Look = (f64, String, f64);
+
+/// A canvas that collects SVG elements, one per line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgCanvas {
+    width: f64,
+    height: f64,
+    content: String,
+    dots: Dots,
+    /// The current run's dots by look, in order of first appearance, centers
+    /// in tenths
+    run: Vec<(Look, Vec<(i64, i64)>)>,
+    theme: Theme,
+}
+
+impl SvgCanvas {
+    pub fn new(width: f64, height: f64) -> Self {
+        SvgCanvas { width, height, content: String::new(), dots: Dots::Circles, run: Vec::new(), theme: Theme::dark() }
+    }
+
+    /// Write discs as `dots` says; [`Dots::Circles`] by default.
+    pub fn with_dots(mut self, dots: Dots) -> Self {
+        self.dots = dots;
+        self
+    }
+
+    /// Draw on `theme`'s background with its stroke widths and opacity.
+    /// Colors are the caller's: pick them with [`Theme::color`].
+    pub fn with_theme(mut self, theme: &Theme) -> Self {
+        self.theme = theme.clone();
+        self
+    }
+
+    /// The finished document, on the theme's background.
+    pub fn finish(mut self) -> String {
+        self.flush_dots();
+        super::svg_document_with_theme(self.width.round() as u32, self.height.round() as u32, &self.content, &self.theme)
+    }
+
+    /// `style` with the theme's stroke width.
+    fn scaled(&self, style: &Style) -> Style {
+        Style { width: self.theme.stroke_width(style.width), ..style.clone() }
+    }
+
+    /// Write the current run of batched dots, one path per look.
+    fn flush_dots(&mut self) {
+        let tenths = |n: i64| {
+            let sign = if n < 0 { "-" } else { "" };
+            match (n.abs() / 10, n.abs() % 10) {
+                (whole, 0) => format!("{sign}{whole}"),
+                (0, tenth) => format!("{sign}.{tenth}"),
+                (whole, tenth) => format!("{sign}{whole}.{tenth}"),
+            }
+        };
+        for ((radius, fill, opacity), mut centers) in core::mem::take(&mut self.run) {
+            centers.sort_unstable_by_key(|&(x, y)| (y, x));
+            centers.dedup();
+            // A lone dot is shorter as a circle
+            if let [(x, y)] = centers[..] {
+                let (x, y) = (x as f64 / 10.0, y as f64 / 10.0);
+                let style = Style::fill(fill).with_opacity(opacity);
+                self.content.push_str(&format!("<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"{radius}\" {}/>\n", paint(&style)));
+                continue;
+            }
+            let mut d = String::new();
+            let mut at = (0, 0);
+            for (k, &(x, y)) in centers.iter().enumerate() {
+                let (dx, dy) = if k == 0 { (x, y) } else { (x - at.0, y - at.1) };
+                let dy = tenths(dy);
+                let comma = if dy.starts_with('-') { "" } else { "," };
+                d.push_str(&format!("{}{}{comma}{dy}h0", if k == 0 { 'M' } else { 'm' }, tenths(dx)));
+                at = (x, y);
+            }
+            let style = Style::stroke(fill, 2.0 * radius).with_opacity(opacity);
+            self.content.push_str(&format!("<path d=\"{d}\" {} stroke-linecap=\"round\"/>\n", paint(&style)));
+        }
+    }
+}
+
+/// `fill`, `stroke` and `opacity` attributes for `style`; shapes without
+/// a fill are left unfilled.
+fn paint(style: &Style) -> String {
+    let mut out = format!(r##"fill="{}""##, style.fill.as_deref().unwrap_or("none"));
+    if let Some(stroke) = &style.stroke {
+        out.push_str(&format!(r##" stroke="{stroke}" stroke-width="{}""##, style.width));
+    }
+    if style.opacity < 1.0 {
+        out.push_str(&format!(r##" opacity="{}""##, style.opacity));
+    }
+    out
+}
+
+fn points_attribute(points: &[Point]) -> String {
+    points.iter().map(|p| format!("{:.1},{:.1}", p.x, p.y)).collect::<Vec<_>>().join(" ")
+}
+
+impl Canvas for SvgCanvas {
+    fn size(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+
+    fn circle(&mut self, center: Point, radius: f64, style: &Style) {
+        let tenths = |v: f64| v.is_finite().then(|| (v * 10.0).round() as i64);
+        if let (Dots::Batched, Some(fill), None, Some(x), Some(y)) = (self.dots, &style.fill, &style.stroke, tenths(center.x), tenths(center.y)) {
+            if radius > 0.0 {
+                let look = (radius, fill.clone(), style.opacity);
+                match self.run.iter_mut().find(|(l, _)| *l == look) {
+                    Some((_, centers)) => centers.push((x, y)),
+                    None => self.run.push((look, vec![(x, y)])),
+                }
+                return;
+            }
+        }
+        self.flush_dots();
+        self.content.push_str(&format!("<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{radius}\" {}/>\n", center.x, center.y, paint(&self.scaled(style))));
+    }
+
+    fn path(&mut self, points: &[Point], style: &Style) {
+        self.flush_dots();
+        self.content.push_str(&format!("<polyline points=\"{}\" {}/>\n", points_attribute(points), paint(&self.scaled(style))));
+    }
+
+    fn polygon(&mut self, points: &[Point], style: &Style) {
+        self.flush_dots();
+        self.content.push_str(&format!("<polygon points=\"{}\" {}/>\n", points_attribute(points), paint(&self.scaled(style))));
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: &str) {
+        self.flush_dots();
+        self.content.push_str(&format!("<rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{width:.1}\" height=\"{height:.1}\" fill=\"{color}\"/>\n"));
+    }
+
+    fn text(&mut self, at: Point, text: &str, size: f64, color: &str) {
+        self.flush_dots();
+        let escaped = text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        self.content.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" fill=\"{color}\" font-family=\"sans-serif\" font-size=\"{size}\">{escaped}</text>\n",
+            at.x, at.y
+        ));
+    }
+}
+
+/// Paint a row-major grid as square cells of side `cell`. Horizontal runs
+/// that map to the same color become a single rectangle.
+pub fn fill_raster(canvas: &mut impl Canvas, cols: usize, rows: usize, cell: f64, values: &[f64], color: impl Fn(f64) -> String) {
+    for y in 0..rows {
+        let mut x = 0;
+        while x < cols {
+            let c = color(values[y * cols + x]);
+            let mut run = 1;
+            while x + run < cols && color(values[y * cols + x + run]) == c {
+                run += 1;
+            }
+            canvas.fill_rect(x as f64 * cell, y as f64 * cell, run as f64 * cell, cell, &c);
+            x += run;
+        }
+    }
+}
+
+/// Paint `geometry` onto `canvas`, scaled uniformly to fit inside `margin`
+/// and centered. Unstyled shapes get the same look as with
+/// [`Svg`](crate::geometry::Svg): points as dots the stroke width across,
+/// grids as a viridis raster normalized to the field's range.
+pub fn draw(canvas: &mut impl Canvas, geometry: &Geometry, margin: f64) {
+    draw_with_theme(canvas, geometry, margin, &Theme::dark());
+}
+
+/// [`draw`] in `theme`'s colors: with a palette, each layer takes it at
+/// its index. Grids stay viridis. Stroke widths are left to the canvas, as
+/// an [`SvgCanvas::with_theme`] scales them.
+pub fn draw_with_theme(canvas: &mut impl Canvas, geometry: &Geometry, margin: f64, theme: &Theme) {
+    let (width, height) = canvas.size();
+    if let Geometry::Grid { cols, rows, values } = geometry {
+        let (lo, hi) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        let range = (hi - lo).max(1e-12);
+        let cell = (width / (*cols).max(1) as f64).min(height / (*rows).max(1) as f64);
+        fill_raster(canvas, *cols, *rows, cell, values, |v| super::viridis(((v - lo) / range * 63.0).round() / 63.0));
+        return;
+    }
+    let Some((lo, hi)) = geometry.bounds() else {
+        return;
+    };
+    let (scale, to_canvas) = fit(lo, hi, width, height, margin);
+    let layers: Vec<_> = match geometry {
+        Geometry::Layers(layers) => layers.iter().map(|l| (&l.geometry, l.style.clone())).collect(),
+        other => vec![(other, default_style(other))],
+    };
+    let n = layers.len();
+    for (i, (geometry, style)) in layers.into_iter().enumerate() {
+        let style = Style { width: style.width, ..theme.style(&style, theme::place(i, n)) };
+        geometry.for_each_shape(&mut |shape| match shape {
+            Shape::Point(p) => {
+                let color = style.stroke.clone().or_else(|| style.fill.clone()).unwrap_or_default();
+                canvas.circle(to_canvas(p), style.width / 2.0, &Style { fill: Some(color), stroke: None, ..style.clone() });
+            }
+            Shape::Polyline(line) => canvas.path(&line.iter().map(|&p| to_canvas(p)).collect::<Vec<_>>(), &style),
+            Shape::Polygon(line) => canvas.polygon(&line.iter().map(|&p| to_canvas(p)).collect::<Vec<_>>(), &style),
+            Shape::Circle(c) => canvas.circle(to_canvas(c.center), c.radius * scale, &style),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records calls instead of drawing, the way a plotter driver might.
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    impl Canvas for Log {
+        fn size(&self) -> (f64, f64) {
+            (100.0, 50.0)
+        }
+
+        fn circle(&mut self, center: Point, radius: f64, _: &Style) {
+            self.0.push(format!("circle {},{} {radius}", center.x, center.y));
+        }
+
+        fn path(&mut self, points: &[Point], _: &Style) {
+            self.0.push(format!("path {}", points.len()));
+        }
+
+        fn polygon(&mut self, points: &[Point], _: &Style) {
+            self.0.push(format!("polygon {}", points.len()));
+        }
+
+        fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: &str) {
+            self.0.push(format!("rect {x},{y} {width}x{height} {color}"));
+        }
+
+        fn text(&mut self, _: Point, text: &str, _: f64, _: &str) {
+            self.0.push(format!("text {text}"));
+        }
+    }
+
+    #[test]
+    fn test_svg_canvas() {
+        let mut canvas = SvgCanvas::new(200.0, 100.0);
+        let origin = Point { x: 0.0, y: 0.0 };
+        canvas.circle(Point { x: 10.0, y: 20.0 }, 3.0, &Style::fill("red").with_opacity(0.5));
+        canvas.line(origin, Point { x: 5.0, y: 5.0 }, &Style::stroke("#fff", 2.0));
+        canvas.polygon(&[origin, Point { x: 1.0, y: 0.0 }, Point { x: 0.0, y: 1.0 }], &Style::fill("blue"));
+        canvas.fill_rect(1.0, 2.0, 3.0, 4.0, "green");
+        canvas.text(origin, "a < b & c", 12.0, "#888");
+        assert_eq!(canvas.size(), (200.0, 100.0));
+        let svg = canvas.finish();
+        assert!(svg.contains(r##"width="200" height="100""##));
+        assert!(svg.contains(r##"<circle cx="10.0" cy="20.0" r="3" fill="red" opacity="0.5"/>"##), "{svg}");
+        assert!(svg.contains(r##"<polyline points="0.0,0.0 5.0,5.0" fill="none" stroke="#fff" stroke-width="2"/>"##), "{svg}");
+        assert!(svg.contains(r##"<polygon points="0.0,0.0 1.0,0.0 0.0,1.0" fill="blue"/>"##), "{svg}");
+        assert!(svg.contains(r##"<rect x="1.0" y="2.0" width="3.0" height="4.0" fill="green"/>"##), "{svg}");
+        assert!(svg.contains(">a &lt; b &amp; c</text>"), "{svg}");
+    }
+
+    #[test]
+    fn test_batched_dots() {
+        let dot = |x, y| Point { x, y };
+        let red = Style::fill("red").with_opacity(0.7);
+        let mut canvas = SvgCanvas::new(100.0, 100.0).with_dots(Dots::Batched);
+        canvas.circle(dot(10.0, 20.0), 0.5, &red);
+        canvas.circle(dot(5.5, 20.0), 0.5, &Style::fill("blue").with_opacity(0.7));
+        canvas.circle(dot(4.0, 8.5), 0.5, &red);
+        canvas.circle(dot(4.0, 8.5), 0.5, &red);
+        canvas.line(dot(0.0, 0.0), dot(1.0, 1.0), &Style::stroke("#fff", 1.0));
+        canvas.circle(dot(1.0, 2.0), 3.0, &Style::stroke("#fff", 1.0));
+        canvas.circle(dot(1.0, 2.0), 3.0, &Style::fill("green"));
+        canvas.circle(dot(-1.0, -2.0), 3.0, &Style::fill("green"));
+        let svg = canvas.finish();
+        // Reds sorted by row, the duplicate dropped; the lone blue stays a circle
+        assert!(svg.contains(r##"<path d="M4,8.5h0m6,11.5h0" fill="none" stroke="red" stroke-width="1" opacity="0.7" stroke-linecap="round"/>
+<circle cx="5.5" cy="20.0" r="0.5" fill="blue" opacity="0.7"/>
+<polyline points="0.0,0.0 1.0,1.0""##), "{svg}");
+        // Outlines stay circles and end the run; negative moves need no comma
+        assert!(svg.contains(r##"<circle cx="1.0" cy="2.0" r="3" fill="none" stroke="#fff" stroke-width="1"/>
+<path d="M-1-2h0m2,4h0" fill="none" stroke="green" stroke-width="6" stroke-linecap="round"/>"##), "{svg}");
+        assert_eq!(SvgCanvas::new(10.0, 10.0).with_dots(Dots::Batched).finish(), SvgCanvas::new(10.0, 10.0).finish());
+    }
+
+    #[test]
+    fn test_draw_on_any_canvas() {
+        // A 2:1 box fills the 100×50 canvas inside a 10-unit margin
+        let shapes = Geometry::Layers(vec![
+            crate::geometry::Layer {
+                style: Style::stroke("#fff", 1.0),
+                geometry: Geometry::Polylines(vec![vec![Point { x: 0.0, y: 0.0 }, Point { x: 4.0, y: 2.0 }]]),
+            },
+            crate::geometry::Layer { style: Style::default(), geometry: Geometry::Points(vec![Point { x: 4.0, y: 0.0 }]) },
+        ]);
+        let mut log = Log::default();
+        draw(&mut log, &shapes, 10.0);
+        assert_eq!(log.0, vec!["path 2", "circle 80,10 0.5"]);
+
+        let mut log = Log::default();
+        draw(&mut log, &Geometry::Grid { cols: 2, rows: 1, values: vec![1.0, 1.0] }, 0.0);
+        assert_eq!(log.0, vec![format!("rect 0,0 100x50 {}", crate::render::viridis(0.0))]);
+
+        let mut log = Log::default();
+        draw(&mut log, &Geometry::Points(vec![]), 0.0);
+        assert!(log.0.is_empty());
+    }
+
+    #[test]
+    fn test_draw_with_theme() {
+        let line = |x| Geometry::Polylines(vec![vec![Point { x, y: 0.0 }, Point { x, y: 1.0 }]]);
+        let shapes = Geometry::Layers(vec![
+            crate::geometry::Layer { style: Style::stroke("#fff", 2.0), geometry: line(0.0) },
+            crate::geometry::Layer { style: Style::stroke("#fff", 2.0), geometry: line(1.0) },
+        ]);
+        let theme = Theme::neon();
+        let mut canvas = SvgCanvas::new(100.0, 100.0).with_theme(&theme);
+        draw_with_theme(&mut canvas, &shapes, 10.0, &theme);
+        let svg = canvas.finish();
+        assert!(svg.contains(r##"fill="#05010f""##), "{svg}");
+        // Each layer at its place along the palette, its width scaled once
+        for t in [0.0, 1.0] {
+            let stroke = theme.color(t, "#fff");
+            assert!(svg.contains(&format!(r#"stroke="{stroke}" stroke-width="3""#)), "{svg}");
+        }
+    }
+}
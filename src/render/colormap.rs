@@ -0,0 +1,178 @@
+//! Colormaps — perceptually uniform color scales as lookup tables.
+//!
+//! Each map is 33 sRGB entries, evenly spaced over [0, 1] and linearly
+//! interpolated between. Viridis, magma, inferno and plasma are sampled
+//! from Matt Zucker's degree-six polynomial fits to the matplotlib tables,
+//! turbo and cividis from the fits in d3-scale-chromatic; the end entries
+//! are the published endpoints exactly. Away from the ends the tables sit
+//! within a few levels of the references (turbo's dark-red tail is the
+//! loosest), which is well below what the eye can tell apart.
+//!
+//! Viridis, cividis, magma, inferno and plasma rise steadily in lightness,
+//! so they print legibly in grayscale; cividis also reads the same to
+//! red–green colorblind eyes. Turbo trades that for contrast: a rainbow
+//! without the banding of hue-cycling maps.
+
+use crate::prelude::*;
+
+/// A color scale over [0, 1].
+pub trait Colormap {
+    /// Color at `t` as sRGB bytes; `t` is clamped, NaN counts as 0.
+    fn rgb(&self, t: f64) -> [u8; 3];
+
+    /// Color at `t` as an SVG color string.
+    fn css(&self, t: f64) -> String {
+        let [r, g, b] = self.rgb(t);
+        format!("rgb({r},{g},{b})")
+    }
+}
+
+/// A colormap given by evenly spaced entries, interpolated linearly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lut {
+    pub name: &'static str,
+    table: &'static [[u8; 3]],
+}
+
+impl Lut {
+    /// A map through `table`, first entry at 0 and last at 1.
+    pub const fn new(name: &'static str, table: &'static [[u8; 3]]) -> Lut {
+        Lut { name, table }
+    }
+}
+
+impl Colormap for Lut {
+    fn rgb(&self, t: f64) -> [u8; 3] {
+        let n = self.table.len();
+        if n < 2 {
+            return self.table.first().copied().unwrap_or([0; 3]);
+        }
+        let x = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) * (n - 1) as f64 };
+        let i = (x as usize).min(n - 2);
+        let f = x - i as f64;
+        let (a, b) = (self.table[i], self.table[i + 1]);
+        core::array::from_fn(|c| (f64::from(a[c]) + (f64::from(b[c]) - f64::from(a[c])) * f).round() as u8)
+    }
+}
+
+pub const VIRIDIS: Lut = Lut::new("viridis", &VIRIDIS_TABLE);
+pub const MAGMA: Lut = Lut::new("magma", &MAGMA_TABLE);
+pub const INFERNO: Lut = Lut::new("inferno", &INFERNO_TABLE);
+pub const PLASMA: Lut = Lut::new("plasma", &PLASMA_TABLE);
+pub const TURBO: Lut = Lut::new("turbo", &TURBO_TABLE);
+pub const CIVIDIS: Lut = Lut::new("cividis", &CIVIDIS_TABLE);
+
+/// Every built-in map, in [`NAMES`] order.
+pub const ALL: [Lut; 6] = [MAGMA, VIRIDIS, INFERNO, PLASMA, TURBO, CIVIDIS];
+
+pub const NAMES: [&str; 6] = ["magma", "viridis", "inferno", "plasma", "turbo", "cividis"];
+
+/// Look up a built-in map by name.
+pub fn by_name(name: &str) -> Option<Lut> {
+    ALL.into_iter().find(|lut| lut.name == name)
+}
+
+const VIRIDIS_TABLE: [[u8; 3]; 33] = [
+    [68, 1, 84], [72, 13, 96], [72, 24, 106], [72, 34, 115], [71, 45, 123], [69, 55, 129],
+    [67, 64, 134], [64, 74, 137], [61, 82, 140], [57, 91, 141], [52, 99, 142], [48, 107, 142],
+    [43, 114, 142], [39, 122, 142], [35, 129, 141], [33, 137, 140], [31, 144, 139], [31, 152, 138],
+    [33, 159, 135], [36, 166, 132], [42, 174, 128], [50, 181, 123], [61, 188, 116], [74, 194, 107],
+    [90, 200, 97], [108, 206, 86], [128, 211, 73], [149, 216, 60], [172, 220, 48], [194, 223, 37],
+    [216, 226, 29], [236, 229, 27], [253, 231, 37],
+];
+const MAGMA_TABLE: [[u8; 3]; 33] = [
+    [0, 0, 4], [3, 4, 18], [10, 8, 38], [19, 10, 56], [30, 13, 73], [41, 15, 89],
+    [54, 17, 102], [66, 19, 113], [79, 22, 122], [92, 24, 128], [105, 27, 132], [118, 30, 134],
+    [131, 34, 134], [145, 38, 132], [158, 42, 129], [171, 48, 125], [183, 53, 119], [196, 59, 114],
+    [207, 67, 108], [218, 75, 103], [228, 84, 99], [237, 94, 96], [244, 106, 95], [250, 118, 96],
+    [254, 132, 99], [255, 147, 104], [255, 163, 111], [255, 180, 121], [254, 197, 132], [252, 213, 145],
+    [250, 228, 159], [251, 240, 173], [252, 253, 191],
+];
+const INFERNO_TABLE: [[u8; 3]; 33] = [
+    [0, 0, 4], [3, 4, 23], [11, 6, 44], [21, 8, 61], [33, 9, 74], [46, 11, 85],
+    [59, 12, 93], [73, 14, 99], [86, 17, 104], [99, 19, 107], [112, 23, 108], [125, 27, 107],
+    [138, 31, 105], [150, 36, 102], [162, 41, 96], [174, 47, 90], [186, 54, 82], [197, 61, 72],
+    [207, 69, 62], [217, 78, 52], [226, 88, 42], [234, 99, 32], [241, 111, 24], [246, 124, 18],
+    [249, 138, 15], [250, 153, 15], [250, 169, 19], [249, 186, 29], [247, 203, 44], [245, 219, 65],
+    [243, 234, 93], [244, 248, 127], [252, 255, 164],
+];
+const PLASMA_TABLE: [[u8; 3]; 33] = [
+    [13, 8, 135], [32, 6, 145], [47, 5, 152], [62, 2, 159], [76, 0, 164], [89, 0, 168],
+    [102, 0, 171], [115, 2, 171], [126, 5, 170], [138, 10, 168], [149, 17, 164], [159, 24, 159],
+    [169, 33, 153], [179, 42, 146], [188, 51, 139], [197, 61, 132], [205, 71, 124], [212, 81, 117],
+    [219, 90, 109], [226, 100, 102], [232, 109, 95], [237, 119, 88], [242, 128, 81], [246, 138, 74],
+    [249, 148, 67], [252, 160, 60], [254, 171, 53], [255, 184, 47], [255, 197, 41], [253, 210, 37],
+    [250, 224, 34], [245, 236, 34], [240, 249, 33],
+];
+const TURBO_TABLE: [[u8; 3]; 33] = [
+    [48, 18, 59], [62, 42, 113], [73, 62, 174], [74, 83, 215], [69, 105, 238], [60, 127, 248],
+    [50, 149, 247], [43, 169, 239], [38, 188, 225], [37, 205, 207], [41, 220, 188], [50, 233, 167],
+    [63, 243, 147], [80, 249, 128], [101, 253, 110], [124, 253, 94], [149, 251, 81], [173, 245, 69],
+    [197, 236, 60], [218, 224, 52], [236, 209, 46], [250, 192, 41], [255, 173, 36], [255, 152, 33],
+    [255, 130, 29], [253, 108, 26], [240, 86, 22], [223, 65, 17], [203, 47, 13], [182, 31, 7],
+    [163, 19, 2], [149, 12, 0], [122, 4, 3],
+];
+const CIVIDIS_TABLE: [[u8; 3]; 33] = [
+    [0, 34, 78], [0, 38, 93], [1, 43, 101], [8, 49, 105], [17, 54, 108], [28, 60, 110],
+    [38, 66, 110], [49, 71, 110], [60, 77, 110], [71, 83, 109], [81, 88, 110], [90, 94, 110],
+    [98, 100, 111], [106, 106, 112], [114, 112, 113], [121, 118, 115], [127, 124, 117], [134, 130, 118],
+    [140, 136, 119], [147, 142, 120], [154, 148, 120], [161, 155, 120], [169, 161, 119], [178, 168, 116],
+    [187, 175, 113], [197, 182, 109], [206, 189, 104], [216, 196, 98], [226, 203, 92], [235, 211, 85],
+    [243, 218, 79], [250, 226, 73], [254, 232, 56],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Published values at 0.25, 0.5 and 0.75.
+    const REFERENCES: [(Lut, [[u8; 3]; 3]); 5] = [
+        (VIRIDIS, [[59, 82, 139], [33, 145, 140], [94, 201, 98]]),
+        (MAGMA, [[81, 18, 124], [183, 55, 121], [252, 137, 97]]),
+        (INFERNO, [[87, 16, 110], [188, 55, 84], [249, 142, 9]]),
+        (PLASMA, [[126, 3, 168], [204, 71, 120], [248, 149, 64]]),
+        (CIVIDIS, [[65, 77, 107], [124, 123, 120], [188, 175, 111]]),
+    ];
+
+    #[test]
+    fn test_close_to_references() {
+        for (lut, stops) in REFERENCES {
+            for (stop, expected) in stops.into_iter().enumerate() {
+                let got = lut.rgb(0.25 * (stop + 1) as f64);
+                let error = (0..3).map(|c| got[c].abs_diff(expected[c])).max().unwrap();
+                assert!(error <= 6, "{} at {}: {got:?} vs {expected:?}", lut.name, 0.25 * (stop + 1) as f64);
+            }
+        }
+        assert_eq!(VIRIDIS.rgb(0.0), [68, 1, 84]);
+        assert_eq!(VIRIDIS.rgb(1.0), [253, 231, 37]);
+        assert_eq!(TURBO.rgb(0.0), [48, 18, 59]);
+        assert_eq!(TURBO.rgb(1.0), [122, 4, 3]);
+    }
+
+    #[test]
+    fn test_lookup_and_interpolation() {
+        // Clamped outside [0, 1], NaN at the bottom
+        assert_eq!(MAGMA.rgb(-1.0), MAGMA.rgb(0.0));
+        assert_eq!(MAGMA.rgb(2.0), MAGMA.rgb(1.0));
+        assert_eq!(MAGMA.rgb(f64::NAN), MAGMA.rgb(0.0));
+        const RAMP: Lut = Lut::new("ramp", &[[0, 0, 0], [200, 100, 50]]);
+        assert_eq!(RAMP.rgb(0.5), [100, 50, 25]);
+        assert_eq!(RAMP.css(1.0), "rgb(200,100,50)");
+        assert_eq!(Lut::new("empty", &[]).rgb(0.5), [0, 0, 0]);
+        for name in NAMES {
+            assert_eq!(by_name(name).map(|lut| lut.name), Some(name));
+        }
+        assert!(by_name("jet").is_none());
+    }
+
+    #[test]
+    fn test_sequential_maps_brighten() {
+        let luma = |[r, g, b]: [u8; 3]| 0.2126 * f64::from(r) + 0.7152 * f64::from(g) + 0.0722 * f64::from(b);
+        for lut in [VIRIDIS, MAGMA, INFERNO, PLASMA, CIVIDIS] {
+            for k in 0..32 {
+                let (a, b) = (luma(lut.rgb(k as f64 / 32.0)), luma(lut.rgb((k + 1) as f64 / 32.0)));
+                assert!(b > a, "{} darkens at {k}/32", lut.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Gradients — user palettes built from color stops.
+//!
+//! A [`Gradient`] spreads its stops evenly over [0, 1] and blends between
+//! neighbours either directly in sRGB, as most drawing programs do, or in
+//! Oklab, where equal steps look equally different: halfway from black to
+//! white is the perceived middle gray (99, 99, 99), where sRGB's (128, 128,
+//! 128) already looks closer to white.
+//! Stops are written as `#rgb`, `#rrggbb`, `rgb(r,g,b)` or `hsl(h,s%,l%)`,
+//! the same strings the renderers emit.
+
+use crate::prelude::*;
+use crate::error::{MathaturaError, Result};
+use crate::render::colormap::Colormap;
+
+/// The space in which neighbouring stops are blended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    Srgb,
+    Oklab,
+}
+
+impl Interpolation {
+    pub const NAMES: [&'static str; 2] = ["oklab", "srgb"];
+
+    pub fn from_name(name: &str) -> Option<Interpolation> {
+        match name {
+            "oklab" => Some(Interpolation::Oklab),
+            "srgb" => Some(Interpolation::Srgb),
+            _ => None,
+        }
+    }
+}
+
+/// A colormap through evenly spaced color stops.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gradient {
+    stops: Vec<[u8; 3]>,
+    interpolation: Interpolation,
+}
+
+impl Gradient {
+    /// A gradient through `stops`, first at 0 and last at 1.
+    pub fn new(stops: Vec<[u8; 3]>, interpolation: Interpolation) -> Result<Gradient> {
+        if stops.is_empty() {
+            return Err(MathaturaError::Empty { name: "colors" });
+        }
+        Ok(Gradient { stops, interpolation })
+    }
+
+    /// Parse comma-separated stops such as `#012a4a,#2a6f97,#a9d6e5` or
+    /// `hsl(200,80%,20%), hsl(40,90%,70%)`.
+    pub fn parse(spec: &str, interpolation: Interpolation) -> Result<Gradient> {
+        let mut stops = Vec::new();
+        let (mut depth, mut start) = (0, 0);
+        // Split on commas outside parentheses
+        for (i, c) in spec.char_indices().chain(core::iter::once((spec.len(), ','))) {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    stops.push(parse_color(&spec[start..i])?);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        Gradient::new(stops, interpolation)
+    }
+
+    pub fn stops(&self) -> &[[u8; 3]] {
+        &self.stops
+    }
+}
+
+impl Colormap for Gradient {
+    fn rgb(&self, t: f64) -> [u8; 3] {
+        let n = self.stops.len();
+        if n == 1 {
+            return self.stops[0];
+        }
+        let x = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) * (n - 1) as f64 };
+        let i = (x as usize).min(n - 2);
+        let f = x - i as f64;
+        let (a, b) = (self.stops[i], self.stops[i + 1]);
+        match self.interpolation {
+            Interpolation::Srgb => core::array::from_fn(|c| (f64::from(a[c]) + (f64::from(b[c]) - f64::from(a[c])) * f).round() as u8),
+            Interpolation::Oklab => {
+                let (a, b) = (oklab(a), oklab(b));
+                from_oklab(core::array::from_fn(|c| a[c] + (b[c] - a[c]) * f))
+            }
+        }
+    }
+}
+
+/// Parse one color: `#rgb`, `#rrggbb`, `rgb(r,g,b)` or `hsl(h,s%,l%)`.
+pub fn parse_color(text: &str) -> Result<[u8; 3]> {
+    let invalid = || MathaturaError::Parse { line: 0, message: format!("not a color: {:?} (expected #rrggbb, rgb(…) or hsl(…))", text.trim()) };
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#') {
+        let digit = |i: usize| hex.get(i..i + 1).and_then(|d| u8::from_str_radix(d, 16).ok()).ok_or_else(invalid);
+        return match hex.len() {
+            3 => Ok([digit(0)? * 17, digit(1)? * 17, digit(2)? * 17]),
+            6 => Ok([digit(0)? * 16 + digit(1)?, digit(2)? * 16 + digit(3)?, digit(4)? * 16 + digit(5)?]),
+            _ => Err(invalid()),
+        };
+    }
+    let (function, arguments) = text.strip_suffix(')').and_then(|t| t.split_once('(')).ok_or_else(invalid)?;
+    let values: Vec<f64> = arguments
+        .split(',')
+        .map(|v| v.trim().trim_end_matches('%').parse::<f64>().ok().filter(|v| v.is_finite()))
+        .collect::<Option<_>>()
+        .ok_or_else(invalid)?;
+    let &[x, y, z] = values.as_slice() else {
+        return Err(invalid());
+    };
+    match function.trim() {
+        "rgb" => Ok([x, y, z].map(|v| v.clamp(0.0, 255.0).round() as u8)),
+        "hsl" => Ok(hsl_to_rgb(x, y / 100.0, z / 100.0)),
+        _ => Err(invalid()),
+    }
+}
+
+/// sRGB bytes of hue `h` degrees, saturation and lightness in [0, 1].
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> [u8; 3] {
+    let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let channel = |n: f64| {
+        let k = (n + h.rem_euclid(360.0) / 30.0) % 12.0;
+        let v = l - chroma / 2.0 * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0);
+        (v * 255.0).round() as u8
+    };
+    [channel(0.0), channel(8.0), channel(4.0)]
+}
+
+fn to_linear(c: u8) -> f64 {
+    let c = f64::from(c) / 255.0;
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn from_linear(c: f64) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.003_130_8 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+    (c * 255.0).round() as u8
+}
+
+/// Björn Ottosson's Oklab coordinates (L, a, b) of an sRGB color.
+fn oklab(rgb: [u8; 3]) -> [f64; 3] {
+    let [r, g, b] = rgb.map(to_linear);
+    let l = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+    let m = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+    let s = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+    [
+        0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s,
+        1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s,
+        0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s,
+    ]
+}
+
+fn from_oklab([lightness, a, b]: [f64; 3]) -> [u8; 3] {
+    let l = (lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+    let m = (lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+    let s = (lightness - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+    [
+        4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s,
+        -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s,
+        -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s,
+    ]
+    .map(from_linear)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_colors() {
+        assert_eq!(parse_color("#012a4a").unwrap(), [1, 42, 74]);
+        assert_eq!(parse_color(" #FfF ").unwrap(), [255, 255, 255]);
+        assert_eq!(parse_color("rgb(10, 20, 300)").unwrap(), [10, 20, 255]);
+        assert_eq!(parse_color("hsl(0,100%,50%)").unwrap(), [255, 0, 0]);
+        assert_eq!(parse_color("hsl(240, 100%, 25%)").unwrap(), [0, 0, 128]);
+        assert_eq!(parse_color("hsl(-240,100%,50%)").unwrap(), [0, 255, 0]);
+        for bad in ["", "012a4a", "#12345", "#ggg", "hsl(1,2)", "hsv(1,2,3)", "rgb(1,2,x)", "rgb(1,2,3"] {
+            assert!(parse_color(bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_gradient() {
+        let spec = "#000000, hsl(0,100%,50%),#fff";
+        let srgb = Gradient::parse(spec, Interpolation::Srgb).unwrap();
+        assert_eq!(srgb.stops(), &[[0, 0, 0], [255, 0, 0], [255, 255, 255]]);
+        assert_eq!(srgb.rgb(0.0), [0, 0, 0]);
+        assert_eq!(srgb.rgb(0.25), [128, 0, 0]);
+        assert_eq!(srgb.rgb(0.5), [255, 0, 0]);
+        assert_eq!(srgb.rgb(1.5), [255, 255, 255]);
+        assert_eq!(srgb.css(0.75), "rgb(255,128,128)");
+        // Oklab keeps the stops exact and blends by perceived lightness
+        let perceptual = Gradient::parse(spec, Interpolation::Oklab).unwrap();
+        for t in [0.0, 0.5, 1.0] {
+            assert_eq!(perceptual.rgb(t), srgb.rgb(t));
+        }
+        let ramp = Gradient::parse("#000,#fff", Interpolation::Oklab).unwrap();
+        assert_eq!(ramp.rgb(0.5), [99, 99, 99]);
+        for t in [0.1, 0.3, 0.7, 0.9] {
+            assert!((oklab(ramp.rgb(t))[0] - t).abs() < 0.005, "{t}");
+        }
+        assert_eq!(Gradient::parse("#123456", Interpolation::Oklab).unwrap().rgb(0.7), [0x12, 0x34, 0x56]);
+        assert!(Gradient::parse("#000,,#fff", Interpolation::Srgb).is_err());
+        assert!(Gradient::new(vec![], Interpolation::Srgb).is_err());
+        assert_eq!(Interpolation::from_name("oklab"), Some(Interpolation::Oklab));
+    }
+}
@@ -0,0 +1,301 @@
+//! Triangle meshes — 3D-printable and Blender-ready output.
+//!
+//! A [`Mesh`] is a vertex list and triangles of vertex indices, wound
+//! counter-clockwise seen from outside. It writes Wavefront OBJ, which keeps
+//! shared vertices, and binary STL, which slicers for 3D printers expect.
+//! [`Mesh::solidify`] turns an open sheet into a closed solid with a given
+//! wall thickness, so surfaces print without further repair.
+//!
+//! The [`Relief`] backend lifts flat geometry into a mesh: grids become
+//! heightfields on a solid base and circles become domes standing on it.
+
+use core::f64::consts::PI;
+
+use crate::prelude::*;
+use crate::categories::chaos::Point3D;
+use crate::geometry::{Backend, Geometry, Shape};
+
+/// An indexed triangle mesh.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mesh {
+    pub vertices: Vec<Point3D>,
+    /// Vertex indices, counter-clockwise seen from outside
+    pub triangles: Vec<[usize; 3]>,
+}
+
+fn sub(a: Point3D, b: Point3D) -> Point3D {
+    Point3D { x: a.x - b.x, y: a.y - b.y, z: a.z - b.z }
+}
+
+fn cross(a: Point3D, b: Point3D) -> Point3D {
+    Point3D { x: a.y * b.z - a.z * b.y, y: a.z * b.x - a.x * b.z, z: a.x * b.y - a.y * b.x }
+}
+
+fn normalize(v: Point3D) -> Point3D {
+    let n = v.x.hypot(v.y).hypot(v.z);
+    if n > 0.0 {
+        Point3D { x: v.x / n, y: v.y / n, z: v.z / n }
+    } else {
+        Point3D { x: 0.0, y: 0.0, z: 1.0 }
+    }
+}
+
+impl Mesh {
+    /// Add another mesh's vertices and triangles to this one.
+    pub fn append(&mut self, other: &Mesh) {
+        let offset = self.vertices.len();
+        self.vertices.extend_from_slice(&other.vertices);
+        self.triangles.extend(other.triangles.iter().map(|t| t.map(|k| k + offset)));
+    }
+
+    /// Unit normal of triangle `t`, by the right-hand rule.
+    pub fn normal(&self, t: usize) -> Point3D {
+        let [a, b, c] = self.triangles[t].map(|k| self.vertices[k]);
+        normalize(cross(sub(b, a), sub(c, a)))
+    }
+
+    /// Edges used by exactly one triangle. A closed, printable solid has none.
+    pub fn boundary_edges(&self) -> Vec<[usize; 2]> {
+        let mut count: Map<(usize, usize), usize> = Map::new();
+        for t in &self.triangles {
+            for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+                *count.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+        // Directed as they run in their triangle, so walls can follow them
+        let mut edges = Vec::new();
+        for t in &self.triangles {
+            for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+                if count[&(a.min(b), a.max(b))] == 1 {
+                    edges.push([a, b]);
+                }
+            }
+        }
+        edges
+    }
+
+    /// Close an open sheet into a solid: a copy of the sheet `thickness`
+    /// below it, facing down, joined to it by walls along the open edges.
+    pub fn solidify(&self, thickness: f64) -> Mesh {
+        let n = self.vertices.len();
+        let mut solid = self.clone();
+        solid.vertices.extend(self.vertices.iter().map(|v| Point3D { z: v.z - thickness, ..*v }));
+        solid.triangles.extend(self.triangles.iter().map(|t| [t[0] + n, t[2] + n, t[1] + n]));
+        for [a, b] in self.boundary_edges() {
+            solid.triangles.push([b, a, a + n]);
+            solid.triangles.push([b, a + n, b + n]);
+        }
+        solid
+    }
+
+    /// A closed dome: a hemisphere of `radius` standing on `center` along
+    /// `axis`, with a flat base, in `segments` slices around and a quarter as
+    /// many rings up.
+    pub fn dome(center: Point3D, radius: f64, axis: Point3D, segments: usize) -> Mesh {
+        let segments = segments.max(3);
+        let rings = (segments / 4).max(1);
+        let up = normalize(axis);
+        // Two directions spanning the base plane
+        let helper = if up.z.abs() < 0.9 { Point3D { x: 0.0, y: 0.0, z: 1.0 } } else { Point3D { x: 1.0, y: 0.0, z: 0.0 } };
+        let u = normalize(cross(helper, up));
+        let v = cross(up, u);
+        let at = |polar: f64, azimuth: f64| {
+            let (s, c) = (polar.sin() * radius, polar.cos() * radius);
+            let (sa, ca) = azimuth.sin_cos();
+            Point3D {
+                x: center.x + s * (ca * u.x + sa * v.x) + c * up.x,
+                y: center.y + s * (ca * u.y + sa * v.y) + c * up.y,
+                z: center.z + s * (ca * u.z + sa * v.z) + c * up.z,
+            }
+        };
+        let mut mesh = Mesh { vertices: vec![at(0.0, 0.0), center], triangles: Vec::new() };
+        // Ring r (1 = nearest the top, rings = the base rim) starts at vertex 2 + (r − 1)·segments
+        for r in 1..=rings {
+            let polar = PI / 2.0 * r as f64 / rings as f64;
+            for s in 0..segments {
+                mesh.vertices.push(at(polar, 2.0 * PI * s as f64 / segments as f64));
+            }
+        }
+        let ring = |r: usize, s: usize| 2 + (r - 1) * segments + s % segments;
+        for s in 0..segments {
+            mesh.triangles.push([0, ring(1, s), ring(1, s + 1)]);
+            for r in 1..rings {
+                mesh.triangles.push([ring(r, s), ring(r + 1, s), ring(r + 1, s + 1)]);
+                mesh.triangles.push([ring(r, s), ring(r + 1, s + 1), ring(r, s + 1)]);
+            }
+            mesh.triangles.push([1, ring(rings, s + 1), ring(rings, s)]);
+        }
+        mesh
+    }
+
+    /// A heightfield over a row-major `cols × rows` grid of `cell`-sized
+    /// squares, z = value × height, y growing with the row.
+    pub fn heightfield(cols: usize, rows: usize, values: &[f64], cell: f64, height: f64) -> Mesh {
+        let mut mesh = Mesh::default();
+        if cols < 2 || rows < 2 || values.len() < cols * rows {
+            return mesh;
+        }
+        mesh.vertices = (0..cols * rows)
+            .map(|k| Point3D { x: (k % cols) as f64 * cell, y: (k / cols) as f64 * cell, z: values[k] * height })
+            .collect();
+        for y in 0..rows - 1 {
+            for x in 0..cols - 1 {
+                let k = y * cols + x;
+                mesh.triangles.push([k, k + 1, k + cols + 1]);
+                mesh.triangles.push([k, k + cols + 1, k + cols]);
+            }
+        }
+        mesh
+    }
+
+    /// Wavefront OBJ text: `v` lines, then 1-based `f` lines.
+    pub fn to_obj(&self) -> String {
+        let mut out = String::with_capacity(self.vertices.len() * 32 + self.triangles.len() * 24);
+        out.push_str("# mathatura mesh\n");
+        for v in &self.vertices {
+            out.push_str(&format!("v {:.6} {:.6} {:.6}\n", v.x, v.y, v.z));
+        }
+        for t in &self.triangles {
+            out.push_str(&format!("f {} {} {}\n", t[0] + 1, t[1] + 1, t[2] + 1));
+        }
+        out
+    }
+
+    /// Binary STL: an 80-byte header, the triangle count, and per triangle
+    /// its normal and three corners as little-endian `f32`s.
+    pub fn to_stl(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(84 + 50 * self.triangles.len());
+        let mut header = [b' '; 80];
+        header[..15].copy_from_slice(b"mathatura mesh ");
+        out.extend(header);
+        out.extend((self.triangles.len() as u32).to_le_bytes());
+        for (i, t) in self.triangles.iter().enumerate() {
+            let n = self.normal(i);
+            for p in core::iter::once(n).chain(t.iter().map(|&k| self.vertices[k])) {
+                for c in [p.x, p.y, p.z] {
+                    out.extend((c as f32).to_le_bytes());
+                }
+            }
+            out.extend([0, 0]);
+        }
+        out
+    }
+}
+
+/// A backend lifting flat geometry into a printable solid on the xy plane.
+///
+/// Grids become heightfields, one unit per cell and `height` tall at value
+/// 1, closed underneath by a `base`-thick floor. Circles become domes and
+/// points become domes of radius `base`, each standing on z = 0. Lines and
+/// polygons have no volume and are skipped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Relief {
+    pub height: f64,
+    pub base: f64,
+    /// Slices around each dome
+    pub segments: usize,
+}
+
+impl Default for Relief {
+    fn default() -> Self {
+        Relief { height: 10.0, base: 1.0, segments: 16 }
+    }
+}
+
+impl Backend for Relief {
+    type Output = Mesh;
+
+    fn render(&self, geometry: &Geometry) -> Mesh {
+        let mut mesh = Mesh::default();
+        if let Geometry::Grid { cols, rows, values } = geometry {
+            let (lo, hi) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+            let span = if hi > lo { hi - lo } else { 1.0 };
+            let normalized: Vec<f64> = values.iter().map(|v| (v - lo) / span).collect();
+            mesh = Mesh::heightfield(*cols, *rows, &normalized, 1.0, self.height).solidify(self.base);
+        }
+        let up = Point3D { x: 0.0, y: 0.0, z: 1.0 };
+        geometry.for_each_shape(&mut |shape| {
+            let (center, radius) = match shape {
+                Shape::Circle(c) => (c.center, c.radius),
+                Shape::Point(p) => (p, self.base),
+                Shape::Polyline(_) | Shape::Polygon(_) => return,
+            };
+            mesh.append(&Mesh::dome(Point3D { x: center.x, y: center.y, z: 0.0 }, radius, up, self.segments));
+        });
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::fractals::Point;
+    use crate::geometry::Circle;
+
+    /// Signed volume by the divergence theorem; positive for outward winding.
+    fn volume(mesh: &Mesh) -> f64 {
+        mesh.triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.map(|k| mesh.vertices[k]);
+                let n = cross(b, c);
+                (a.x * n.x + a.y * n.y + a.z * n.z) / 6.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_dome_is_closed() {
+        let dome = Mesh::dome(Point3D { x: 1.0, y: 2.0, z: 3.0 }, 2.0, Point3D { x: 0.0, y: 0.0, z: 1.0 }, 32);
+        assert!(dome.boundary_edges().is_empty());
+        // Close to a hemisphere's 2πr³/3, a little under for the facets
+        let exact = 2.0 * PI * 8.0 / 3.0;
+        let v = volume(&dome);
+        assert!(v > 0.9 * exact && v < exact, "{} vs {}", v, exact);
+        assert!(dome.vertices.iter().all(|p| p.z >= 3.0 - 1e-12));
+        // Tipped on its side, the dome points along the new axis
+        let side = Mesh::dome(Point3D { x: 0.0, y: 0.0, z: 0.0 }, 1.0, Point3D { x: 1.0, y: 0.0, z: 0.0 }, 8);
+        assert!((side.vertices[0].x - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_solidify_heightfield() {
+        let values = [0.0, 0.5, 1.0, 0.5, 1.0, 0.5, 1.0, 0.5, 0.0];
+        let sheet = Mesh::heightfield(3, 3, &values, 1.0, 2.0);
+        assert_eq!(sheet.triangles.len(), 8);
+        assert_eq!(sheet.boundary_edges().len(), 8);
+        assert!((0..sheet.triangles.len()).all(|t| sheet.normal(t).z > 0.0));
+        let solid = sheet.solidify(1.0);
+        assert!(solid.boundary_edges().is_empty());
+        // A floor one unit thick under a 2×2 footprint
+        assert!((volume(&solid) - 4.0).abs() < 1e-9, "{}", volume(&solid));
+    }
+
+    #[test]
+    fn test_obj_and_stl() {
+        let mesh = Mesh::dome(Point3D { x: 0.0, y: 0.0, z: 0.0 }, 1.0, Point3D { x: 0.0, y: 0.0, z: 1.0 }, 8);
+        let obj = mesh.to_obj();
+        assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), mesh.vertices.len());
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), mesh.triangles.len());
+        assert!(obj.contains("f 1 3 4"));
+        let stl = mesh.to_stl();
+        assert_eq!(stl.len(), 84 + 50 * mesh.triangles.len());
+        assert_eq!(u32::from_le_bytes(stl[80..84].try_into().unwrap()) as usize, mesh.triangles.len());
+    }
+
+    #[test]
+    fn test_relief_backend() {
+        let grid = Geometry::Grid { cols: 4, rows: 3, values: vec![1.0; 12] };
+        let slab = Relief::default().render(&grid);
+        assert!(slab.boundary_edges().is_empty());
+        let discs = Geometry::Layers(vec![crate::geometry::Layer {
+            style: Default::default(),
+            geometry: Geometry::Circles(vec![Circle { center: Point { x: 0.0, y: 0.0 }, radius: 1.0 }; 2]),
+        }]);
+        let domes = Relief::default().render(&discs);
+        assert_eq!(domes.vertices.len(), 2 * (2 + 16 * 4));
+        assert!(Relief::default().render(&Geometry::Polylines(vec![vec![Point { x: 0.0, y: 0.0 }]])).triangles.is_empty());
+    }
+}
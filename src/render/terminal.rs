@@ -0,0 +1,208 @@
+//! Terminal previews — geometry drawn as ANSI 256-color text.
+//!
+//! Two encodings trade color for resolution:
+//!
+//! - **Half blocks.** Each character is two stacked pixels: `▀` with the
+//!   upper pixel as foreground color and the lower as background, so every
+//!   pixel keeps its own color. Best for grids.
+//! - **Braille.** Each character is a 2×4 dot matrix (U+2800–U+28FF): four
+//!   times the pixels of half blocks, in one color. Best for curves and
+//!   point clouds.
+//!
+//! Both keep the aspect ratio: a terminal cell is about twice as tall as it
+//! is wide, so half-block and braille pixels are close to square.
+
+use crate::prelude::*;
+use crate::categories::fractals::Point;
+use crate::geometry::{Backend, Geometry, Shape};
+use crate::render::magma_rgb;
+
+/// How pixels are packed into characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    HalfBlock,
+    Braille,
+}
+
+/// A terminal backend drawing into `cols × rows` characters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Terminal {
+    pub cols: usize,
+    pub rows: usize,
+    pub mode: Mode,
+}
+
+impl Default for Terminal {
+    fn default() -> Self {
+        Terminal { cols: 80, rows: 40, mode: Mode::HalfBlock }
+    }
+}
+
+impl Terminal {
+    pub fn new(cols: usize, rows: usize, mode: Mode) -> Self {
+        Terminal { cols, rows, mode }
+    }
+
+    /// Pixels across and down.
+    pub fn resolution(&self) -> (usize, usize) {
+        match self.mode {
+            Mode::HalfBlock => (self.cols, self.rows * 2),
+            Mode::Braille => (self.cols * 2, self.rows * 4),
+        }
+    }
+
+    /// Rasterize into row-major pixel intensities in [0, 1]; `None` is empty.
+    /// Grids are normalized to their range, shapes drawn at full intensity.
+    fn rasterize(&self, geometry: &Geometry) -> Vec<Option<f64>> {
+        let (w, h) = self.resolution();
+        let mut pixels = vec![None; w * h];
+        if let Geometry::Grid { cols, rows, values } = geometry {
+            let scale = (w as f64 / (*cols).max(1) as f64).min(h as f64 / (*rows).max(1) as f64);
+            let (ox, oy) = ((w as f64 - *cols as f64 * scale) / 2.0, (h as f64 - *rows as f64 * scale) / 2.0);
+            let (min, max) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| (a.min(v), b.max(v)));
+            let range = (max - min).max(1e-12);
+            for (k, pixel) in pixels.iter_mut().enumerate() {
+                // Sample the grid cell under each pixel center
+                let gx = ((k % w) as f64 + 0.5 - ox) / scale;
+                let gy = ((k / w) as f64 + 0.5 - oy) / scale;
+                if gx >= 0.0 && gy >= 0.0 && (gx as usize) < *cols && (gy as usize) < *rows {
+                    *pixel = values.get(gy as usize * cols + gx as usize).map(|v| (v - min) / range);
+                }
+            }
+            return pixels;
+        }
+        let Some((lo, hi)) = geometry.bounds() else {
+            return pixels;
+        };
+        // Fit the bounds, centered, so both extremes land on pixel centers
+        let scale = ((w.max(2) - 1) as f64 / (hi.x - lo.x).max(1e-12)).min((h.max(2) - 1) as f64 / (hi.y - lo.y).max(1e-12));
+        let (ox, oy) = (
+            (w as f64 - 1.0 - (hi.x - lo.x) * scale) / 2.0 - lo.x * scale,
+            (h as f64 - 1.0 - (hi.y - lo.y) * scale) / 2.0 - lo.y * scale,
+        );
+        let mut plot = |p: Point| {
+            let (x, y) = ((ox + p.x * scale).round(), (oy + p.y * scale).round());
+            if x >= 0.0 && y >= 0.0 && (x as usize) < w && (y as usize) < h {
+                pixels[y as usize * w + x as usize] = Some(1.0);
+            }
+        };
+        let mut line = |a: Point, b: Point| {
+            let steps = ((b.x - a.x).hypot(b.y - a.y) * scale * 2.0).ceil().max(1.0) as usize;
+            for i in 0..=steps {
+                let t = i as f64 / steps as f64;
+                plot(Point { x: a.x + (b.x - a.x) * t, y: a.y + (b.y - a.y) * t });
+            }
+        };
+        geometry.for_each_shape(&mut |shape| match shape {
+            Shape::Point(p) => line(p, p),
+            Shape::Polyline(points) => points.windows(2).for_each(|s| line(s[0], s[1])),
+            Shape::Polygon(points) => {
+                points.windows(2).for_each(|s| line(s[0], s[1]));
+                if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+                    line(last, first);
+                }
+            }
+            Shape::Circle(c) => line(c.center, c.center),
+        });
+        pixels
+    }
+}
+
+/// Nearest color in the 6×6×6 cube of the xterm 256-color palette.
+pub fn ansi256(rgb: [u8; 3]) -> u8 {
+    let level = |c: u8| (c as f64 / 255.0 * 5.0).round() as u8;
+    16 + 36 * level(rgb[0]) + 6 * level(rgb[1]) + level(rgb[2])
+}
+
+/// Braille dot bits for the 2×4 cell positions, by row then column.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+impl Backend for Terminal {
+    type Output = String;
+
+    /// Lines of text ending in newlines; half blocks carry ANSI color codes
+    /// and reset them at the end of each line.
+    fn render(&self, geometry: &Geometry) -> String {
+        let (w, _) = self.resolution();
+        let pixels = self.rasterize(geometry);
+        let mut out = String::new();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                match self.mode {
+                    Mode::HalfBlock => {
+                        let color = |v: f64| ansi256(magma_rgb(0.15 + 0.85 * v));
+                        let (top, bottom) = (pixels[2 * row * w + col], pixels[(2 * row + 1) * w + col]);
+                        out.push_str(&match (top, bottom) {
+                            (Some(t), Some(b)) => format!("\x1b[38;5;{}m\x1b[48;5;{}m▀", color(t), color(b)),
+                            (Some(t), None) => format!("\x1b[38;5;{}m\x1b[49m▀", color(t)),
+                            (None, Some(b)) => format!("\x1b[38;5;{}m\x1b[49m▄", color(b)),
+                            (None, None) => "\x1b[0m ".into(),
+                        });
+                    }
+                    Mode::Braille => {
+                        let mut bits = 0;
+                        for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+                            for (dx, bit) in dots.iter().enumerate() {
+                                if pixels[(4 * row + dy) * w + 2 * col + dx].is_some_and(|v| v >= 0.5) {
+                                    bits |= bit;
+                                }
+                            }
+                        }
+                        out.push(if bits == 0 { ' ' } else { char::from_u32(0x2800 + bits).unwrap_or(' ') });
+                    }
+                }
+            }
+            if self.mode == Mode::HalfBlock {
+                out.push_str("\x1b[0m");
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi256() {
+        assert_eq!(ansi256([0, 0, 0]), 16);
+        assert_eq!(ansi256([255, 255, 255]), 231);
+        assert_eq!(ansi256([255, 0, 0]), 196);
+    }
+
+    #[test]
+    fn test_half_block_grid() {
+        let grid = Geometry::Grid { cols: 2, rows: 2, values: vec![0.0, 1.0, 1.0, 0.0] };
+        let text = Terminal::new(4, 2, Mode::HalfBlock).render(&grid);
+        assert_eq!(text.lines().count(), 2);
+        assert_eq!(text.matches('▀').count(), 8);
+        // Low values dark, high values bright
+        let (dark, bright) = (ansi256(magma_rgb(0.15)), ansi256(magma_rgb(1.0)));
+        assert!(text.starts_with(&format!("\x1b[38;5;{dark}m\x1b[48;5;{dark}m▀")));
+        assert!(text.contains(&format!("\x1b[38;5;{bright}m")));
+        assert!(text.lines().all(|l| l.ends_with("\x1b[0m")));
+    }
+
+    #[test]
+    fn test_braille_line() {
+        // A horizontal line across the middle of a one-row canvas
+        let line = Geometry::Polylines(vec![vec![Point { x: 0.0, y: 0.0 }, Point { x: 1.0, y: 0.0 }, Point { x: 1.0, y: 0.0001 }]]);
+        let text = Terminal::new(3, 1, Mode::Braille).render(&line);
+        assert_eq!(text.chars().count(), 4);
+        assert!(text.chars().take(3).all(|c| ('\u{2801}'..='\u{28ff}').contains(&c)), "{}", text);
+    }
+
+    #[test]
+    fn test_braille_points_and_empty() {
+        let corners = Geometry::Points(vec![Point { x: 0.0, y: 0.0 }, Point { x: 1.0, y: 3.0 }]);
+        let text = Terminal::new(1, 1, Mode::Braille).render(&corners);
+        // Top-left and bottom-right dots
+        assert_eq!(text, "\u{2881}\n");
+        let empty = Terminal::new(3, 2, Mode::Braille).render(&Geometry::Points(vec![]));
+        assert_eq!(empty, "   \n   \n");
+    }
+}
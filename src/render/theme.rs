@@ -0,0 +1,263 @@
+//! Themes — one look across every renderer.
+//!
+//! Each renderer draws on the project's dark background ([`BACKGROUND`])
+//! with colors picked for it. Every `to_svg` has a `_with_theme` sibling
+//! that takes a [`Theme`] and draws in it: the background becomes the
+//! theme's, a palette replaces the renderer's own line and dot colors,
+//! stroke widths scale, and everything in front of the background can fade.
+//! Rasters with a look of their own take the palette as their colormap;
+//! those handed a colormap keep it. Besides the presets in
+//! [`Theme::NAMES`], themes load from TOML with [`Theme::from_toml`].
+
+use crate::prelude::*;
+use crate::error::{check_positive, check_range, Result};
+use crate::geometry::Style;
+use crate::render::colormap::Colormap;
+use crate::render::gradient::{Gradient, Interpolation};
+
+/// The background every renderer draws on, `#0a0a1a`.
+pub const BACKGROUND: [u8; 3] = [0x0a, 0x0a, 0x1a];
+
+/// [`BACKGROUND`] as written in SVG, for background rects and empty cells.
+pub const BACKGROUND_CSS: &str = "#0a0a1a";
+
+/// Where the `i`-th of `n` features sits along a palette: `i / (n - 1)`,
+/// and 1 for a lone one.
+pub fn place(i: usize, n: usize) -> f64 {
+    if n < 2 {
+        1.0
+    } else {
+        i as f64 / (n - 1) as f64
+    }
+}
+
+/// Background, foreground palette and line weight for SVG output.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Theme {
+    pub background: [u8; 3],
+    /// Colors for the foreground, sampled by each feature's place in the
+    /// figure (see [`Theme::color`]). `None` keeps each renderer's own
+    /// colors.
+    pub palette: Option<Gradient>,
+    /// Factor on every stroke width
+    pub stroke_scale: f64,
+    /// Opacity of everything in front of the background
+    pub opacity: f64,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub const NAMES: [&'static str; 4] = ["dark", "light", "paper", "neon"];
+
+    /// The renderers' own look.
+    pub fn dark() -> Theme {
+        Theme { background: BACKGROUND, palette: None, stroke_scale: 1.0, opacity: 1.0 }
+    }
+
+    /// Slate and blue ink on near-white.
+    pub fn light() -> Theme {
+        Theme::preset([0xf6, 0xf8, 0xfa], "#6e7781,#0969da,#0a3069", 1.0, 1.0)
+    }
+
+    /// Sepia ink on cream, a little translucent.
+    pub fn paper() -> Theme {
+        Theme::preset([0xf4, 0xec, 0xd8], "#a1887f,#6d4c41,#2e1f14", 1.0, 0.9)
+    }
+
+    /// Violet, magenta and cyan on black, with bolder strokes.
+    pub fn neon() -> Theme {
+        Theme::preset([0x05, 0x01, 0x0f], "#7209b7,#f72585,#4cc9f0,#e0fbfc", 1.5, 1.0)
+    }
+
+    fn preset(background: [u8; 3], stops: &str, stroke_scale: f64, opacity: f64) -> Theme {
+        let palette = Gradient::parse(stops, Interpolation::Oklab).ok();
+        Theme { background, palette, stroke_scale, opacity }
+    }
+
+    pub fn from_name(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "paper" => Some(Theme::paper()),
+            "neon" => Some(Theme::neon()),
+            _ => None,
+        }
+    }
+
+    /// Parse a theme such as
+    ///
+    /// ```toml
+    /// base = "dark"                 # preset to start from (default dark)
+    /// background = "#001219"
+    /// palette = ["#005f73", "#0a9396", "#e9d8a6"]   # or one "a,b,c" string
+    /// color_space = "oklab"         # or "srgb"
+    /// stroke_scale = 1.5
+    /// opacity = 0.9
+    /// ```
+    ///
+    /// Missing keys keep the base theme's values.
+    #[cfg(feature = "std")]
+    pub fn from_toml(source: &str) -> Result<Theme> {
+        use crate::error::MathaturaError;
+        use crate::render::gradient::parse_color;
+        let table: toml::Table = source.parse().map_err(|e: toml::de::Error| MathaturaError::Parse {
+            line: e.span().map_or(0, |span| source[..span.start.min(source.len())].lines().count().max(1)),
+            message: e.message().to_string(),
+        })?;
+        let fail = |key: &str, expected: &str| MathaturaError::Parse { line: 0, message: format!("{key}: expected {expected}") };
+        let text = |key: &str, value: &toml::Value| value.as_str().map(str::to_string).ok_or_else(|| fail(key, "a string"));
+        let number = |key: &str, value: &toml::Value| {
+            value.as_float().or_else(|| value.as_integer().map(|i| i as f64)).ok_or_else(|| fail(key, "a number"))
+        };
+        let mut theme = match table.get("base") {
+            Some(value) => Theme::from_name(&text("base", value)?).ok_or_else(|| fail("base", "dark, light, paper or neon"))?,
+            None => Theme::dark(),
+        };
+        let mut interpolation = Interpolation::Oklab;
+        let mut stops = theme.palette.as_ref().map(|p| p.stops().to_vec());
+        for (key, value) in &table {
+            match key.as_str() {
+                "base" => {}
+                "background" => theme.background = parse_color(&text(key, value)?)?,
+                "palette" => {
+                    let spec = match value {
+                        toml::Value::Array(items) => items.iter().map(|v| text(key, v)).collect::<Result<Vec<_>>>()?.join(","),
+                        other => text(key, other)?,
+                    };
+                    stops = Some(Gradient::parse(&spec, interpolation)?.stops().to_vec());
+                }
+                "color_space" => {
+                    interpolation = Interpolation::from_name(&text(key, value)?).ok_or_else(|| fail(key, "oklab or srgb"))?;
+                }
+                "stroke_scale" => theme.stroke_scale = number(key, value)?,
+                "opacity" => theme.opacity = number(key, value)?,
+                _ => return Err(fail(key, "one of base, background, palette, color_space, stroke_scale, opacity")),
+            }
+        }
+        theme.palette = stops.map(|stops| Gradient::new(stops, interpolation)).transpose()?;
+        theme.validate()?;
+        Ok(theme)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        check_positive("stroke_scale", self.stroke_scale)?;
+        check_range("opacity", self.opacity, 0.0..=1.0, "0 to 1")
+    }
+
+    /// The background as written in SVG.
+    pub fn background_css(&self) -> String {
+        let [r, g, b] = self.background;
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+
+    /// The color of a feature at `t` in [0, 1] along the palette, or `own`,
+    /// the renderer's choice, when there is none.
+    ///
+    /// Renderers pass a feature's place in the figure: the `i`-th of `n`
+    /// curves at [`place`]`(i, n)`, a point along a trace at its arc position.
+    /// A figure drawn in one color takes 1, the palette's last stop, and
+    /// guides behind it lower values.
+    pub fn color(&self, t: f64, own: impl Into<String>) -> String {
+        match &self.palette {
+            Some(palette) => palette.css(t),
+            None => own.into(),
+        }
+    }
+
+    /// `width` scaled by [`Theme::stroke_scale`], to a thousandth.
+    pub fn stroke_width(&self, width: f64) -> f64 {
+        if self.stroke_scale == 1.0 {
+            return width;
+        }
+        (width * self.stroke_scale * 1000.0).round() / 1000.0
+    }
+
+    /// `style` in this theme, its colors taken at `t` as by [`Theme::color`].
+    pub fn style(&self, style: &Style, t: f64) -> Style {
+        let recolor = |color: &Option<String>| color.as_ref().map(|c| self.color(t, c.as_str()));
+        Style { stroke: recolor(&style.stroke), fill: recolor(&style.fill), width: self.stroke_width(style.width), opacity: style.opacity }
+    }
+
+    /// Opens the group that holds everything in front of the background,
+    /// faded to [`Theme::opacity`]; empty when that is 1. Close it with
+    /// [`Theme::foreground_end`].
+    pub fn foreground_start(&self) -> String {
+        if self.opacity < 1.0 {
+            format!(r#"<g opacity="{}">"#, self.opacity)
+        } else {
+            String::new()
+        }
+    }
+
+    pub fn foreground_end(&self) -> &'static str {
+        if self.opacity < 1.0 {
+            "</g>"
+        } else {
+            ""
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        let dark = Theme::default();
+        assert_eq!(dark.background_css(), BACKGROUND_CSS);
+        assert_eq!(dark.color(0.3, "#ffd166"), "#ffd166");
+        assert_eq!((dark.stroke_width(1.5), dark.foreground_start(), dark.foreground_end()), (1.5, String::new(), ""));
+        for name in Theme::NAMES {
+            let theme = Theme::from_name(name).unwrap();
+            assert!(theme.validate().is_ok(), "{name}");
+            assert_eq!(name == "dark", theme.palette.is_none(), "{name}");
+        }
+        assert_eq!(Theme::from_name("sepia"), None);
+    }
+
+    #[test]
+    fn test_theme_colors() {
+        let theme = Theme {
+            background: [255, 255, 255],
+            palette: Some(Gradient::parse("#ccc,#000", Interpolation::Srgb).unwrap()),
+            stroke_scale: 1.5,
+            opacity: 0.5,
+        };
+        assert_eq!(theme.background_css(), "#ffffff");
+        assert_eq!(theme.color(1.0, "#ffd166"), "rgb(0,0,0)");
+        assert_eq!(theme.color(0.0, String::from("red")), "rgb(204,204,204)");
+        assert_eq!(theme.stroke_width(0.35), 0.525);
+        assert_eq!((theme.foreground_start(), theme.foreground_end()), (String::from(r#"<g opacity="0.5">"#), "</g>"));
+
+        let style = theme.style(&Style::stroke("#fff", 2.0).with_opacity(0.7), 1.0);
+        assert_eq!(style, Style { stroke: Some(String::from("rgb(0,0,0)")), fill: None, width: 3.0, opacity: 0.7 });
+        // Without a palette the renderer's colors survive
+        let plain = Theme { palette: None, ..theme };
+        assert_eq!(plain.style(&Style::fill("#fff"), 0.0).fill.as_deref(), Some("#fff"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_from_toml() {
+        let theme = Theme::from_toml("base = \"neon\"\nbackground = \"#001219\"\nopacity = 0.8\n").unwrap();
+        assert_eq!(theme.background, [0x00, 0x12, 0x19]);
+        assert_eq!((theme.stroke_scale, theme.opacity), (1.5, 0.8));
+        assert_eq!(theme.palette, Theme::neon().palette);
+
+        let theme = Theme::from_toml("palette = [\"#000\", \"hsl(0,100%,50%)\"]\ncolor_space = \"srgb\"\nstroke_scale = 2").unwrap();
+        assert_eq!(theme.palette, Some(Gradient::new(vec![[0, 0, 0], [255, 0, 0]], Interpolation::Srgb).unwrap()));
+        assert_eq!(theme.stroke_scale, 2.0);
+        assert_eq!(theme.background, BACKGROUND);
+
+        for bad in ["opacity = 2", "stroke_scale = 0", "base = \"sepia\"", "palette = [1]", "colour = \"red\"", "background = \"#12\"", "opacity ="] {
+            assert!(Theme::from_toml(bad).is_err(), "{bad:?}");
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Random numbers — seeded, reproducible, with independent streams.
+//!
+//! [`Pcg32`] is O'Neill's PCG-XSH-RR generator: a 64-bit linear congruential
+//! state permuted into 32-bit outputs. The LCG increment selects one of 2⁶³
+//! streams, so [`Pcg32::fork`] can hand every boid, branch, or star its own
+//! uncorrelated sequence derived from a single seed. The [`Rng`] trait layers
+//! the distributions the models need — Gaussian jitter, exponential waiting
+//! times, von Mises headings — over any source, including [`SimpleRng`].
+
+use core::f64::consts::PI;
+
+use crate::categories::fractals::SimpleRng;
+// Only the float methods are needed, and std provides those itself
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+
+/// A source of random bits with sampling helpers.
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+
+    /// Uniform in [0, 1), with 53 random bits.
+    fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Uniform in [lo, hi).
+    fn range(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (hi - lo) * self.uniform()
+    }
+
+    /// Uniform integer in 0..bound (bound > 0), without modulo bias.
+    fn below(&mut self, bound: usize) -> usize {
+        // Lemire's multiply-shift with rejection
+        let bound = bound.max(1) as u64;
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let wide = self.next_u64() as u128 * bound as u128;
+            if (wide as u64) >= threshold {
+                return (wide >> 64) as usize;
+            }
+        }
+    }
+
+    /// Normal distribution by the Box–Muller transform.
+    fn gaussian(&mut self, mean: f64, std_dev: f64) -> f64 {
+        let u1 = 1.0 - self.uniform(); // (0, 1], safe for ln
+        let u2 = self.uniform();
+        mean + std_dev * (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+
+    /// Exponential distribution with the given rate (mean 1/rate).
+    fn exponential(&mut self, rate: f64) -> f64 {
+        -(1.0 - self.uniform()).ln() / rate
+    }
+
+    /// Von Mises distribution — the circular normal — around `mean` with
+    /// concentration `kappa`, in (mean − π, mean + π]. Best & Fisher (1979).
+    fn von_mises(&mut self, mean: f64, kappa: f64) -> f64 {
+        if kappa < 1e-8 {
+            return mean + PI * (2.0 * self.uniform() - 1.0);
+        }
+        let tau = 1.0 + (1.0 + 4.0 * kappa * kappa).sqrt();
+        let rho = (tau - (2.0 * tau).sqrt()) / (2.0 * kappa);
+        let r = (1.0 + rho * rho) / (2.0 * rho);
+        loop {
+            let z = (PI * self.uniform()).cos();
+            let f = (1.0 + r * z) / (r + z);
+            let c = kappa * (r - f);
+            let u2 = self.uniform();
+            if c * (2.0 - c) > u2 || (c / u2).ln() + 1.0 >= c {
+                let sign = if self.uniform() < 0.5 { -1.0 } else { 1.0 };
+                return mean + sign * f.clamp(-1.0, 1.0).acos();
+            }
+        }
+    }
+}
+
+const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+/// SplitMix64 finalizer, used to derive child seeds and streams.
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// PCG-XSH-RR 64/32 generator with selectable streams.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// Generator on the default stream.
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, 0xda3e_39cb_94b9_5bdb)
+    }
+
+    /// Generator on one of 2⁶³ independent streams.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Pcg32 { state: 0, increment: (stream << 1) | 1 };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(MULTIPLIER).wrapping_add(self.increment);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    /// An independent child generator. The same parent state and `child_id`
+    /// always give the same child; the parent itself does not advance.
+    pub fn fork(&self, child_id: u64) -> Pcg32 {
+        let seed = mix(self.state ^ mix(child_id));
+        let stream = mix(self.increment.wrapping_add(mix(child_id.wrapping_add(1))));
+        Pcg32::with_stream(seed, stream)
+    }
+}
+
+impl Rng for Pcg32 {
+    fn next_u64(&mut self) -> u64 {
+        let hi = self.next_u32() as u64;
+        (hi << 32) | self.next_u32() as u64
+    }
+}
+
+impl Rng for SimpleRng {
+    fn next_u64(&mut self) -> u64 {
+        SimpleRng::next_u64(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean_and_variance(samples: &[f64]) -> (f64, f64) {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        (mean, samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n)
+    }
+
+    #[test]
+    fn test_pcg_reference_vector() {
+        // pcg32_srandom(42, 54) from the PCG reference implementation
+        let mut rng = Pcg32::with_stream(42, 54);
+        let expected = [0xa15c02b7u32, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e];
+        for e in expected {
+            assert_eq!(rng.next_u32(), e);
+        }
+    }
+
+    #[test]
+    fn test_streams_are_independent() {
+        let (mut a, mut b) = (Pcg32::with_stream(7, 1), Pcg32::with_stream(7, 2));
+        let same = (0..100).filter(|_| a.next_u32() == b.next_u32()).count();
+        assert!(same < 2);
+    }
+
+    #[test]
+    fn test_fork_is_deterministic() {
+        let parent = Pcg32::new(99);
+        assert_eq!(parent.fork(3), parent.fork(3));
+        assert_ne!(parent.fork(3), parent.fork(4));
+        let mut child = parent.fork(0);
+        let mut sibling = parent.fork(1);
+        let same = (0..100).filter(|_| child.next_u32() == sibling.next_u32()).count();
+        assert!(same < 2);
+    }
+
+    #[test]
+    fn test_uniform_and_below() {
+        let mut rng = Pcg32::new(1);
+        let samples: Vec<f64> = (0..20_000).map(|_| rng.uniform()).collect();
+        assert!(samples.iter().all(|&u| (0.0..1.0).contains(&u)));
+        let (mean, var) = mean_and_variance(&samples);
+        assert!((mean - 0.5).abs() < 0.01 && (var - 1.0 / 12.0).abs() < 0.005);
+        let mut counts = [0usize; 6];
+        for _ in 0..6000 {
+            counts[rng.below(6)] += 1;
+        }
+        assert!(counts.iter().all(|&c| (850..1150).contains(&c)), "{:?}", counts);
+    }
+
+    #[test]
+    fn test_gaussian_moments() {
+        let mut rng = Pcg32::new(2);
+        let samples: Vec<f64> = (0..20_000).map(|_| rng.gaussian(3.0, 2.0)).collect();
+        let (mean, var) = mean_and_variance(&samples);
+        assert!((mean - 3.0).abs() < 0.05, "mean {}", mean);
+        assert!((var - 4.0).abs() < 0.15, "variance {}", var);
+    }
+
+    #[test]
+    fn test_exponential_mean() {
+        let mut rng = SimpleRng::new(5);
+        let samples: Vec<f64> = (0..20_000).map(|_| rng.exponential(0.5)).collect();
+        assert!(samples.iter().all(|&x| x >= 0.0));
+        let (mean, _) = mean_and_variance(&samples);
+        assert!((mean - 2.0).abs() < 0.06, "mean {}", mean);
+    }
+
+    #[test]
+    fn test_von_mises_concentration() {
+        // Mean resultant length is I₁(κ)/I₀(κ) ≈ 0.6978 for κ = 2
+        let mut rng = Pcg32::new(3);
+        let angles: Vec<f64> = (0..20_000).map(|_| rng.von_mises(1.0, 2.0)).collect();
+        let (c, s) = angles.iter().fold((0.0, 0.0), |(c, s), a| (c + a.cos(), s + a.sin()));
+        let n = angles.len() as f64;
+        assert!(((c / n).hypot(s / n) - 0.6978).abs() < 0.01);
+        assert!((s.atan2(c) - 1.0).abs() < 0.02);
+        assert!(angles.iter().all(|a| (a - 1.0).abs() <= PI + 1e-12));
+    }
+}
@@ -0,0 +1,271 @@
+//! Sonification — hearing the patterns as Standard MIDI Files.
+//!
+//! Three mappings carry the structure of a model over into sound:
+//!
+//! - **Logistic-map orbits → melodies.** Each iterate picks a pitch from a
+//!   scale, so a period-4 cycle is a four-note riff and chaos never repeats.
+//! - **Phyllotaxis angles → golden rhythms.** Placing n elements around a
+//!   circle by the divergence angle and reading the gaps between them as
+//!   note lengths gives, by the three-distance theorem, at most three
+//!   durations — in golden ratio to each other for the golden angle.
+//! - **L-system strings → note grammars.** The turtle alphabet becomes a
+//!   score: drawing symbols play, `+`/`−` step up and down the scale, and
+//!   `[`/`]` branch and return like a voice leading back to its motif.
+//!
+//! [`to_midi`] writes any note list as a format-0 file.
+
+use core::f64::consts::TAU;
+
+use crate::prelude::*;
+use crate::categories::phyllotaxis::Element;
+
+/// Ticks per quarter note in generated files.
+pub const TICKS_PER_QUARTER: u16 = 480;
+
+/// A single note; times are in MIDI ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Note {
+    /// MIDI note number (60 = middle C)
+    pub pitch: u8,
+    pub velocity: u8,
+    pub start: u32,
+    pub duration: u32,
+}
+
+/// Scale used to turn values into pitches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Scale {
+    Major,
+    Minor,
+    Pentatonic,
+    Chromatic,
+}
+
+impl Scale {
+    /// Semitone offsets of the scale degrees within an octave.
+    pub fn steps(&self) -> &'static [u8] {
+        match self {
+            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Scale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Scale::Pentatonic => &[0, 2, 4, 7, 9],
+            Scale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        }
+    }
+
+    /// Pitch of scale degree `degree` above `root`, any sign, clamped to MIDI range.
+    pub fn pitch(&self, root: u8, degree: i32) -> u8 {
+        let steps = self.steps();
+        let n = steps.len() as i32;
+        let semitones = degree.div_euclid(n) * 12 + steps[degree.rem_euclid(n) as usize] as i32;
+        (root as i32 + semitones).clamp(0, 127) as u8
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scale::Major => "major",
+            Scale::Minor => "minor",
+            Scale::Pentatonic => "pentatonic",
+            Scale::Chromatic => "chromatic",
+        }
+    }
+}
+
+/// One note per logistic-map iterate: x ∈ [0, 1] spans `octaves` octaves
+/// of `scale` above `root`, each an eighth note long.
+pub fn logistic_melody(orbit: &[f64], scale: Scale, root: u8, octaves: u32) -> Vec<Note> {
+    let degrees = (scale.steps().len() as u32 * octaves.max(1)) as f64;
+    let eighth = TICKS_PER_QUARTER as u32 / 2;
+    orbit
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| Note {
+            pitch: scale.pitch(root, (x.clamp(0.0, 1.0) * (degrees - 1.0)).round() as i32),
+            velocity: 72 + (x.clamp(0.0, 1.0) * 40.0) as u8,
+            start: i as u32 * eighth,
+            duration: eighth,
+        })
+        .collect()
+}
+
+/// A rhythm from the angular gaps between phyllotaxis elements.
+///
+/// The elements' angles, sorted around the circle, become onsets within a
+/// cycle of `cycle` ticks; each note lasts until the next onset. Pitch rises
+/// with the order in which an element was placed, so the melody reveals the
+/// spiral's construction while the rhythm reveals its packing.
+pub fn phyllotaxis_rhythm(elements: &[Element], scale: Scale, root: u8, cycle: u32) -> Vec<Note> {
+    let mut onsets: Vec<(f64, usize)> = elements.iter().map(|e| (e.angle.rem_euclid(TAU) / TAU, e.index)).collect();
+    onsets.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let ticks: Vec<u32> = onsets.iter().map(|&(t, _)| (t * cycle as f64).round() as u32).collect();
+    let degrees = scale.steps().len() as i32 * 2;
+    onsets
+        .iter()
+        .enumerate()
+        .map(|(k, &(_, index))| {
+            let end = ticks.get(k + 1).copied().unwrap_or(cycle);
+            Note {
+                pitch: scale.pitch(root, index as i32 % degrees),
+                velocity: if k == 0 { 110 } else { 80 },
+                start: ticks[k],
+                duration: end.saturating_sub(ticks[k]).max(1),
+            }
+        })
+        .collect()
+}
+
+/// Play an L-system string as a note grammar.
+///
+/// `F`, `G`, `A` and `B` and digits play a sixteenth note at the current
+/// scale degree; `+` and `-` step up and down; `[` saves the degree and
+/// softens the voice, `]` restores both. Other symbols are silent.
+pub fn lsystem_melody(lstring: &str, scale: Scale, root: u8) -> Vec<Note> {
+    let sixteenth = TICKS_PER_QUARTER as u32 / 4;
+    let mut notes = Vec::new();
+    let (mut degree, mut depth) = (0i32, 0u8);
+    let mut stack = Vec::new();
+    let mut time = 0;
+    for c in lstring.chars() {
+        match c {
+            'F' | 'G' | 'A' | 'B' | '0'..='9' => {
+                notes.push(Note {
+                    pitch: scale.pitch(root, degree),
+                    velocity: 100u8.saturating_sub(depth.saturating_mul(12)).max(30),
+                    start: time,
+                    duration: sixteenth,
+                });
+                time += sixteenth;
+            }
+            '+' => degree += 1,
+            '-' => degree -= 1,
+            '[' => {
+                stack.push((degree, depth));
+                depth = depth.saturating_add(1);
+            }
+            ']' => {
+                if let Some((d, v)) = stack.pop() {
+                    (degree, depth) = (d, v);
+                }
+            }
+            _ => {}
+        }
+    }
+    notes
+}
+
+/// Append a MIDI variable-length quantity.
+fn push_vlq(out: &mut Vec<u8>, mut value: u32) {
+    let mut bytes = [0u8; 5];
+    let mut n = 0;
+    loop {
+        bytes[n] = (value & 0x7f) as u8 | if n > 0 { 0x80 } else { 0 };
+        n += 1;
+        value >>= 7;
+        if value == 0 {
+            break;
+        }
+    }
+    out.extend(bytes[..n].iter().rev());
+}
+
+/// Encode notes as a format-0 Standard MIDI File at `bpm` on `program`
+/// (General MIDI instrument, 0 = piano).
+pub fn to_midi(notes: &[Note], bpm: f64, program: u8) -> Vec<u8> {
+    // Note-offs sort before note-ons at the same tick so repeated pitches retrigger
+    let mut events: Vec<(u32, u8, u8, u8)> = Vec::with_capacity(notes.len() * 2);
+    for n in notes {
+        events.push((n.start, 1, n.pitch.min(127), n.velocity.clamp(1, 127)));
+        events.push((n.start.saturating_add(n.duration), 0, n.pitch.min(127), 0));
+    }
+    events.sort_by_key(|&(time, on, pitch, _)| (time, on, pitch));
+
+    let mut track = Vec::new();
+    let tempo = (60_000_000.0 / bpm.clamp(1.0, 1000.0)) as u32;
+    track.extend([0x00, 0xff, 0x51, 0x03]);
+    track.extend(&tempo.to_be_bytes()[1..]);
+    track.extend([0x00, 0xc0, program.min(127)]);
+    let mut last = 0;
+    for (time, on, pitch, velocity) in events {
+        push_vlq(&mut track, time - last);
+        last = time;
+        track.extend([if on == 1 { 0x90 } else { 0x80 }, pitch, velocity]);
+    }
+    track.extend([0x00, 0xff, 0x2f, 0x00]);
+
+    let mut out = Vec::with_capacity(track.len() + 22);
+    out.extend(b"MThd");
+    out.extend(6u32.to_be_bytes());
+    out.extend([0, 0, 0, 1]); // format 0, one track
+    out.extend(TICKS_PER_QUARTER.to_be_bytes());
+    out.extend(b"MTrk");
+    out.extend((track.len() as u32).to_be_bytes());
+    out.extend(track);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::{chaos, lsystems, phyllotaxis};
+    use crate::constants::PHI;
+
+    #[test]
+    fn test_scale_pitches() {
+        assert_eq!(Scale::Major.pitch(60, 0), 60);
+        assert_eq!(Scale::Major.pitch(60, 7), 72);
+        assert_eq!(Scale::Major.pitch(60, -1), 59);
+        assert_eq!(Scale::Pentatonic.pitch(60, 3), 67);
+        assert_eq!(Scale::Chromatic.pitch(120, 20), 127);
+    }
+
+    #[test]
+    fn test_periodic_orbit_repeats() {
+        // r = 3.2 settles into a period-2 cycle: a two-note trill
+        let orbit = chaos::logistic_map(3.2, 0.5, 200);
+        let notes = logistic_melody(&orbit[100..], Scale::Minor, 48, 2);
+        let pitches: Set<u8> = notes.iter().map(|n| n.pitch).collect();
+        assert_eq!(pitches.len(), 2);
+        assert_eq!(notes[1].start - notes[0].start, TICKS_PER_QUARTER as u32 / 2);
+    }
+
+    #[test]
+    fn test_golden_rhythm_three_gaps() {
+        let params = phyllotaxis::Params { count: 20, ..Default::default() };
+        let notes = phyllotaxis_rhythm(&phyllotaxis::vogel_spiral(&params), Scale::Major, 60, 100_000);
+        assert_eq!(notes.len(), 20);
+        let mut durations: Vec<u32> = notes.iter().map(|n| n.duration).collect();
+        durations.sort();
+        durations.dedup_by(|a, b| a.abs_diff(*b) <= 2);
+        // Three-distance theorem, and the gaps come in golden ratio
+        assert!(durations.len() <= 3, "{:?}", durations);
+        let ratio = durations[1] as f64 / durations[0] as f64;
+        assert!((ratio - PHI).abs() < 0.01, "{:?}", durations);
+        assert!(notes.windows(2).all(|w| w[0].start + w[0].duration == w[1].start));
+    }
+
+    #[test]
+    fn test_lsystem_grammar() {
+        let notes = lsystem_melody("F+F[+F]-F", Scale::Major, 60);
+        let pitches: Vec<u8> = notes.iter().map(|n| n.pitch).collect();
+        assert_eq!(pitches, vec![60, 62, 64, 60]);
+        assert!(notes[2].velocity < notes[1].velocity);
+        let plant = lsystems::checked_generate(&lsystems::plant(), 2).unwrap();
+        assert!(!lsystem_melody(&plant, Scale::Pentatonic, 60).is_empty());
+    }
+
+    #[test]
+    fn test_midi_encoding() {
+        let notes = [Note { pitch: 60, velocity: 100, start: 0, duration: 200 }];
+        let bytes = to_midi(&notes, 120.0, 0);
+        assert_eq!(&bytes[..4], b"MThd");
+        assert_eq!(&bytes[14..18], b"MTrk");
+        let len = u32::from_be_bytes(bytes[18..22].try_into().unwrap()) as usize;
+        assert_eq!(bytes.len(), 22 + len);
+        // 120 bpm = 500000 µs per quarter
+        assert_eq!(&bytes[22..29], &[0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20]);
+        // 200 ticks encodes as the two-byte quantity 0x81 0x48
+        assert!(bytes.windows(4).any(|w| w == [0x81, 0x48, 0x80, 60]));
+        assert_eq!(&bytes[bytes.len() - 4..], &[0x00, 0xff, 0x2f, 0x00]);
+    }
+}
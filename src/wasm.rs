@@ -0,0 +1,204 @@
+//! WASM — browser bindings with per-frame stepping.
+//!
+//! Enabled by the `wasm` feature. Simulations are exposed as stateful
+//! objects: the web gallery calls `step()` from `requestAnimationFrame` and
+//! reads the state back as a typed array (`Float32Array`) to draw on a
+//! canvas, so pages run the same Rust models as the CLI, live.
+//!
+//! ```js
+//! const sim = new TuringSim(200, 200, "coral", 42);
+//! function frame() {
+//!     sim.step(10);
+//!     draw(sim.concentrations()); // Float32Array, row-major
+//!     requestAnimationFrame(frame);
+//! }
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::categories::{chaos, fractals, lsystems, phyllotaxis, turing};
+use crate::error::check_count;
+use crate::prelude::*;
+
+/// Largest grid side accepted from JavaScript.
+const MAX_SIZE: usize = 2048;
+
+fn js_error(e: crate::MathaturaError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// A Gray–Scott reaction–diffusion simulation.
+#[wasm_bindgen]
+pub struct TuringSim {
+    grid: turing::Grid,
+    params: turing::GrayScottParams,
+}
+
+#[wasm_bindgen]
+impl TuringSim {
+    /// Preset: spots, stripes, coral, mitosis or worms.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize, preset: &str, seed: u32) -> Result<TuringSim, JsError> {
+        check_count("width", width, MAX_SIZE).map_err(js_error)?;
+        check_count("height", height, MAX_SIZE).map_err(js_error)?;
+        let preset = match preset {
+            "stripes" => turing::Preset::Stripes,
+            "coral" => turing::Preset::Coral,
+            "mitosis" => turing::Preset::Mitosis,
+            "worms" => turing::Preset::Worms,
+            _ => turing::Preset::Spots,
+        };
+        Ok(TuringSim { grid: turing::Grid::new_random(width, height, seed as u64), params: preset.params() })
+    }
+
+    /// Advance `steps` time steps.
+    pub fn step(&mut self, steps: usize) {
+        self.grid.simulate(&self.params, steps);
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height
+    }
+
+    /// Concentration of B for every cell, row-major.
+    pub fn concentrations(&self) -> Vec<f32> {
+        self.grid.cells.iter().map(|c| c.b as f32).collect()
+    }
+}
+
+/// A Lorenz trajectory integrated a few steps per frame.
+#[wasm_bindgen]
+pub struct LorenzSim {
+    params: chaos::LorenzParams,
+    point: chaos::Point3D,
+}
+
+#[wasm_bindgen]
+impl LorenzSim {
+    #[wasm_bindgen(constructor)]
+    pub fn new(sigma: f64, rho: f64, beta: f64) -> LorenzSim {
+        let params = chaos::LorenzParams { sigma, rho, beta, ..Default::default() };
+        LorenzSim { params, point: chaos::Point3D { x: 1.0, y: 1.0, z: 1.0 } }
+    }
+
+    /// Integrate `steps` steps and return the new points as x, y, z triples.
+    pub fn step(&mut self, steps: usize) -> Vec<f32> {
+        // Integrate in f64 so frame-by-frame stepping matches one long run
+        let points: Vec<chaos::Point3D> = chaos::lorenz_iter(&self.params, self.point).skip(1).take(steps).collect();
+        self.point = points.last().copied().unwrap_or(self.point);
+        points.iter().flat_map(|p| [p.x as f32, p.y as f32, p.z as f32]).collect()
+    }
+
+    /// Current position as x, y, z.
+    pub fn position(&self) -> Vec<f64> {
+        vec![self.point.x, self.point.y, self.point.z]
+    }
+}
+
+/// An L-system that grows one generation at a time.
+#[wasm_bindgen]
+pub struct LSystemSim {
+    system: lsystems::LSystem,
+    current: String,
+    generation: usize,
+}
+
+#[wasm_bindgen]
+impl LSystemSim {
+    /// System: tree, koch, sierpinski, dragon or plant.
+    #[wasm_bindgen(constructor)]
+    pub fn new(system: &str) -> LSystemSim {
+        let system = match system {
+            "koch" => lsystems::koch_curve(),
+            "sierpinski" => lsystems::sierpinski_arrowhead(),
+            "dragon" => lsystems::dragon_curve(),
+            "plant" => lsystems::plant(),
+            _ => lsystems::tree(),
+        };
+        let current = system.axiom.clone();
+        LSystemSim { system, current, generation: 0 }
+    }
+
+    /// Rewrite one more generation; fails once the string grows too long.
+    pub fn step(&mut self) -> Result<(), JsError> {
+        let next = lsystems::rewrite(&self.system, &self.current);
+        check_count("L-system length", next.len(), lsystems::MAX_SYMBOLS).map_err(js_error)?;
+        self.current = next;
+        self.generation += 1;
+        Ok(())
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Turtle segments of the current generation as x1, y1, x2, y2 quads.
+    pub fn segments(&self) -> Vec<f32> {
+        lsystems::interpret(&self.system, &self.current)
+            .iter()
+            .flat_map(|s| [s.x1 as f32, s.y1 as f32, s.x2 as f32, s.y2 as f32])
+            .collect()
+    }
+}
+
+/// Vogel spiral positions as x, y pairs.
+#[wasm_bindgen]
+pub fn phyllotaxis_points(count: usize, angle: f64, scale: f64) -> Vec<f32> {
+    let params = phyllotaxis::Params { count: count.min(1_000_000), divergence_angle: angle, scale };
+    phyllotaxis::vogel_spiral_as::<f32>(&params).into_iter().flatten().collect()
+}
+
+/// Barnsley fern points as x, y pairs, y up.
+#[wasm_bindgen]
+pub fn fern_points(count: usize, seed: u32) -> Vec<f32> {
+    fractals::barnsley_fern_iter_as::<f32>(seed as u64).take(count.min(10_000_000)).flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turing_sim_steps() {
+        let mut sim = TuringSim::new(24, 16, "coral", 7).ok().unwrap();
+        let before = sim.concentrations();
+        assert_eq!(before.len(), 24 * 16);
+        sim.step(5);
+        assert_ne!(sim.concentrations(), before);
+        assert_eq!((sim.width(), sim.height()), (24, 16));
+    }
+
+    #[test]
+    fn test_lorenz_sim_continues() {
+        let mut sim = LorenzSim::new(10.0, 28.0, 8.0 / 3.0);
+        let first = sim.step(100);
+        let second = sim.step(100);
+        assert_eq!(first.len(), 300);
+        // Stepping in frames follows the same trajectory as one long run
+        let params = chaos::LorenzParams::default();
+        let whole = chaos::lorenz_attractor(&params, 201, chaos::Point3D { x: 1.0, y: 1.0, z: 1.0 });
+        assert_eq!(second[297], whole[200].x as f32);
+        assert_eq!(sim.position()[2], whole[200].z);
+    }
+
+    #[test]
+    fn test_lsystem_sim_grows() {
+        let mut sim = LSystemSim::new("koch");
+        let base = sim.segments().len();
+        sim.step().ok().unwrap();
+        sim.step().ok().unwrap();
+        assert_eq!(sim.generation(), 2);
+        // F → F+F−F−F+F: five segments for each one
+        assert_eq!(sim.segments().len(), base * 25);
+    }
+
+    #[test]
+    fn test_point_arrays() {
+        assert_eq!(phyllotaxis_points(100, 137.5, 4.0).len(), 200);
+        assert_eq!(fern_points(1000, 1).len(), 2000);
+    }
+}